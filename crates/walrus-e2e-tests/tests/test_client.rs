@@ -63,6 +63,7 @@ use walrus_service::{
 use walrus_sui::{
     client::{
         BlobPersistence,
+        ContractClient as _,
         ExpirySelectionPolicy,
         PostStoreAction,
         ReadClient,
@@ -96,6 +97,42 @@ async fn test_store_and_read_blob_without_failures(blob_size: usize) {
     ))
 }
 
+/// Stores and reads a blob with storage nodes and a client that run against the in-memory fake of
+/// the Walrus contracts instead of a Sui test cluster.
+#[tokio::test]
+async fn test_store_and_read_blob_with_fake_sui() -> TestResult {
+    telemetry_subscribers::init_for_testing();
+
+    let (fake, _cluster, client) = test_cluster::fake_setup().await?;
+    let blob = walrus_test_utils::random_data(31415);
+
+    let results = client
+        .reserve_and_store_blobs_retry_committees(
+            &[blob.as_slice()],
+            DEFAULT_ENCODING,
+            1,
+            StoreWhen::Always,
+            BlobPersistence::Permanent,
+            PostStoreAction::Keep,
+        )
+        .await?;
+    let [BlobStoreResult::NewlyCreated { blob_object, .. }] = results.as_slice() else {
+        panic!("expected a newly created blob, got {results:?}");
+    };
+    assert_eq!(blob_object.certified_epoch, Some(1));
+
+    // The certified blob object is owned by the client on the simulated chain.
+    let owned_blobs = fake.owned_blobs(None, ExpirySelectionPolicy::Valid).await?;
+    assert_eq!(owned_blobs.len(), 1);
+    assert_eq!(owned_blobs[0].id, blob_object.id);
+    assert_eq!(owned_blobs[0].certified_epoch, Some(1));
+
+    let read_blob = client.read_blob::<Primary>(&blob_object.blob_id).await?;
+    assert_eq!(read_blob, blob);
+
+    Ok(())
+}
+
 /// Basic read and write test for the client.
 ///
 /// It generates random blobs and stores them.
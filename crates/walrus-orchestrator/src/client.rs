@@ -8,12 +8,14 @@ use std::{
 
 use serde::{Deserialize, Serialize};
 
+use self::local::LocalExecutor;
 use crate::error::CloudProviderResult;
 
 pub mod aws;
+pub mod local;
 pub mod vultr;
 
-#[derive(Debug, Deserialize, Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
 pub enum InstanceStatus {
     Active,
     Inactive,
@@ -31,7 +33,7 @@ impl From<&str> for InstanceStatus {
 }
 
 /// Represents a cloud provider instance.
-#[derive(Debug, Deserialize, Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
 pub struct Instance {
     /// The unique identifier of the instance.
     pub id: String,
@@ -112,6 +114,12 @@ pub trait ServerProviderClient: Display {
 
     /// Return provider-specific commands to setup the instance.
     async fn instance_setup_commands(&self) -> CloudProviderResult<Vec<String>>;
+
+    /// Return the executor running commands on the instances if they are local processes rather
+    /// than remote machines accessed through ssh.
+    fn local_executor(&self) -> Option<LocalExecutor> {
+        None
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,444 @@
+// Copyright (c) Walrus Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    fmt::Display,
+    fs,
+    net::Ipv4Addr,
+    path::{Path, PathBuf},
+    process::Output,
+};
+
+use serde::Serialize;
+use tokio::process::Command;
+use walrus_core::ensure;
+
+use super::{Instance, InstanceStatus, ServerProviderClient};
+use crate::{
+    display,
+    error::{CloudProviderError, CloudProviderResult, SshError, SshResult},
+    settings::Settings,
+};
+
+/// The name of the file holding the description of a local instance in its home directory.
+const INSTANCE_FILE: &str = "instance.yaml";
+/// The bridge connecting the network namespaces of the local instances.
+const BRIDGE: &str = "walrus-br0";
+/// The first three octets of the addresses of the instances running in network namespaces.
+const NAMESPACE_SUBNET: [u8; 3] = [10, 77, 0];
+/// The first three octets of the loopback addresses of the instances running as plain processes.
+const LOOPBACK_SUBNET: [u8; 3] = [127, 0, 0];
+/// The maximum number of local instances (limited by the size of the /24 subnets).
+const MAX_INSTANCES: usize = 253;
+
+/// Make an error signaling a failure to manage the local instances.
+impl From<std::io::Error> for CloudProviderError {
+    fn from(e: std::io::Error) -> Self {
+        Self::LocalError(e.to_string())
+    }
+}
+
+/// Make an error signaling a failure to (de)serialize the description of a local instance.
+impl From<serde_yaml::Error> for CloudProviderError {
+    fn from(e: serde_yaml::Error) -> Self {
+        Self::LocalError(e.to_string())
+    }
+}
+
+/// Runs commands on the local instances, as processes of the current user.
+///
+/// Each instance has its own home directory (holding its repository checkout, working directory,
+/// and log files) and its own tmux server. If network namespaces are enabled, the processes of
+/// each instance run in a dedicated network namespace and are reachable through a bridge on the
+/// host; otherwise, each instance is assigned its own loopback address.
+#[derive(Clone, Debug)]
+pub struct LocalExecutor {
+    /// The directory holding the home directories of all instances.
+    root_dir: PathBuf,
+    /// Whether each instance runs in its own network namespace.
+    network_namespaces: bool,
+}
+
+impl LocalExecutor {
+    /// Make a new executor for the instances located in the specified directory.
+    pub fn new(root_dir: PathBuf, network_namespaces: bool) -> Self {
+        Self {
+            root_dir,
+            network_namespaces,
+        }
+    }
+
+    /// Return the home directory of the instance.
+    pub fn home_dir(&self, instance: &Instance) -> PathBuf {
+        self.root_dir.join(&instance.id)
+    }
+
+    /// Return the name of the network namespace of the instance.
+    fn namespace(instance: &Instance) -> String {
+        format!("walrus-{}", instance.id)
+    }
+
+    /// Execute a shell command on the instance and return both stdout and stderr.
+    pub async fn execute(
+        &self,
+        instance: &Instance,
+        command: String,
+    ) -> SshResult<(String, String)> {
+        let home = self.home_dir(instance);
+        let mut process = if self.network_namespaces {
+            // Enter the namespace as root, then drop back to the current user.
+            let user = std::env::var("USER").unwrap_or_else(|_| "root".into());
+            let mut process = Command::new("sudo");
+            process
+                .args(["ip", "netns", "exec", &Self::namespace(instance)])
+                .args(["sudo", "-u", &user, "env"])
+                .arg(format!("HOME={}", home.display()))
+                .arg(format!("TMUX_TMPDIR={}", home.display()))
+                .args(["bash", "-c", &command]);
+            process
+        } else {
+            let mut process = Command::new("bash");
+            process
+                .args(["-c", &command])
+                .env("HOME", &home)
+                .env("TMUX_TMPDIR", &home);
+            process
+        };
+
+        let output = process.current_dir(&home).output().await.map_err(|error| {
+            SshError::LocalExecutionError {
+                instance: instance.id.clone(),
+                message: error.to_string(),
+            }
+        })?;
+        let Output {
+            status,
+            stdout,
+            stderr,
+        } = output;
+        let stdout = String::from_utf8_lossy(&stdout).into_owned();
+        let stderr = String::from_utf8_lossy(&stderr).into_owned();
+
+        ensure!(
+            status.success(),
+            SshError::LocalExecutionError {
+                instance: instance.id.clone(),
+                message: format!("exit status ({status}): {stderr}"),
+            }
+        );
+        Ok((stdout, stderr))
+    }
+
+    /// Read a file located in the home directory of the instance.
+    pub fn download<P: AsRef<Path>>(&self, instance: &Instance, path: P) -> SshResult<String> {
+        fs::read_to_string(self.home_dir(instance).join(path)).map_err(|error| {
+            SshError::LocalExecutionError {
+                instance: instance.id.clone(),
+                message: error.to_string(),
+            }
+        })
+    }
+}
+
+/// A client managing instances that run as local processes on the current machine.
+///
+/// This provider allows running benchmarks on a single (Linux) machine. Dependencies (including
+/// tmux, git, and the rust toolchain) are expected to be installed on the machine beforehand.
+pub struct LocalClient {
+    settings: Settings,
+    executor: LocalExecutor,
+}
+
+impl Display for LocalClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let isolation = if self.executor.network_namespaces {
+            "network namespaces"
+        } else {
+            "processes"
+        };
+        write!(
+            f,
+            "Local {isolation} ({})",
+            self.executor.root_dir.display()
+        )
+    }
+}
+
+impl LocalClient {
+    /// Make a new local client.
+    pub fn new(settings: Settings) -> CloudProviderResult<Self> {
+        let local = &settings.local;
+        fs::create_dir_all(&local.root_dir)?;
+        // The home directories must be absolute, as commands change their working directory.
+        let root_dir = local.root_dir.canonicalize()?;
+        if !local.network_namespaces && settings.dedicated_clients != 1 {
+            display::warn(
+                "Without network namespaces, all load generators bind the same metrics port; \
+                consider setting `dedicated_clients: 1`",
+            );
+        }
+        let executor = LocalExecutor::new(root_dir, local.network_namespaces);
+        Ok(Self { settings, executor })
+    }
+
+    /// Return the address of the instance with the specified index.
+    fn instance_ip(&self, index: usize) -> Ipv4Addr {
+        let [a, b, c] = if self.executor.network_namespaces {
+            NAMESPACE_SUBNET
+        } else {
+            LOOPBACK_SUBNET
+        };
+        // The first address of the subnet belongs to the host.
+        Ipv4Addr::new(a, b, c, (index + 2) as u8)
+    }
+
+    /// Persist the description of the instance in its home directory.
+    fn save_instance(&self, instance: &Instance) -> CloudProviderResult<()> {
+        let path = self.executor.home_dir(instance).join(INSTANCE_FILE);
+        fs::write(path, serde_yaml::to_string(instance)?)?;
+        Ok(())
+    }
+
+    /// Update the status of the specified instances.
+    fn set_status<'a, I>(&self, instances: I, status: InstanceStatus) -> CloudProviderResult<()>
+    where
+        I: Iterator<Item = &'a Instance>,
+    {
+        for instance in instances {
+            let mut instance = instance.clone();
+            instance.status = status.clone();
+            self.save_instance(&instance)?;
+        }
+        Ok(())
+    }
+
+    /// Run a shell command on the host and fail if it returns an error.
+    async fn run_host_command(&self, command: &str) -> CloudProviderResult<()> {
+        let output = Command::new("bash").args(["-c", command]).output().await?;
+        ensure!(
+            output.status.success(),
+            CloudProviderError::LocalError(format!(
+                "command '{command}' failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ))
+        );
+        Ok(())
+    }
+
+    /// Create the network namespace of the instance and connect it to the bridge.
+    async fn create_namespace(&self, instance: &Instance) -> CloudProviderResult<()> {
+        let namespace = LocalExecutor::namespace(instance);
+        let veth = format!("wv-{}", instance.id);
+        let [a, b, c] = NAMESPACE_SUBNET;
+        let commands = [
+            // Create the bridge and give namespaces access to the outside world (e.g., to reach
+            // the Sui full nodes), if this was not already done.
+            format!(
+                "(ip link show {BRIDGE} > /dev/null 2>&1 || \
+                (sudo ip link add {BRIDGE} type bridge && \
+                sudo ip addr add {a}.{b}.{c}.1/24 dev {BRIDGE} && \
+                sudo ip link set {BRIDGE} up))"
+            ),
+            "sudo sysctl -q -w net.ipv4.ip_forward=1".into(),
+            format!(
+                "(sudo iptables -t nat -C POSTROUTING -s {a}.{b}.{c}.0/24 -j MASQUERADE || \
+                sudo iptables -t nat -A POSTROUTING -s {a}.{b}.{c}.0/24 -j MASQUERADE)"
+            ),
+            // Create the namespace and connect it to the bridge.
+            format!("sudo ip netns add {namespace}"),
+            format!("sudo ip link add {veth} type veth peer name eth0 netns {namespace}"),
+            format!("sudo ip link set {veth} master {BRIDGE} up"),
+            format!(
+                "sudo ip netns exec {namespace} ip addr add {}/24 dev eth0",
+                instance.main_ip
+            ),
+            format!("sudo ip netns exec {namespace} ip link set eth0 up"),
+            format!("sudo ip netns exec {namespace} ip link set lo up"),
+            format!("sudo ip netns exec {namespace} ip route add default via {a}.{b}.{c}.1"),
+        ];
+        self.run_host_command(&commands.join(" && ")).await
+    }
+
+    /// Delete the network namespace of the instance (and its virtual interfaces).
+    async fn delete_namespace(&self, instance: &Instance) -> CloudProviderResult<()> {
+        let namespace = LocalExecutor::namespace(instance);
+        self.run_host_command(&format!(
+            "(sudo ip link delete wv-{} || true) && (sudo ip netns delete {namespace} || true)",
+            instance.id
+        ))
+        .await
+    }
+
+    /// Kill all processes of the instance.
+    async fn kill_processes(&self, instance: &Instance) -> CloudProviderResult<()> {
+        self.executor
+            .execute(instance, "(tmux kill-server || true)".into())
+            .await
+            .map_err(|e| CloudProviderError::LocalError(e.to_string()))?;
+        Ok(())
+    }
+}
+
+impl ServerProviderClient for LocalClient {
+    const USERNAME: &'static str = "local";
+
+    async fn list_instances(&self) -> CloudProviderResult<Vec<Instance>> {
+        let mut instances = Vec::new();
+        for entry in fs::read_dir(&self.executor.root_dir)? {
+            let path = entry?.path().join(INSTANCE_FILE);
+            if path.is_file() {
+                let instance: Instance = serde_yaml::from_str(&fs::read_to_string(path)?)?;
+                instances.push(instance);
+            }
+        }
+        instances.sort_by_key(|instance| instance.id.parse::<usize>().unwrap_or(usize::MAX));
+        Ok(instances)
+    }
+
+    async fn start_instances<'a, I>(&self, instances: I) -> CloudProviderResult<()>
+    where
+        I: Iterator<Item = &'a Instance> + Send,
+    {
+        self.set_status(instances, InstanceStatus::Active)
+    }
+
+    async fn stop_instances<'a, I>(&self, instances: I) -> CloudProviderResult<()>
+    where
+        I: Iterator<Item = &'a Instance> + Send,
+    {
+        let instances: Vec<_> = instances.collect();
+        for instance in &instances {
+            self.kill_processes(instance).await?;
+        }
+        self.set_status(instances.into_iter(), InstanceStatus::Inactive)
+    }
+
+    async fn create_instance<S>(&self, region: S) -> CloudProviderResult<Instance>
+    where
+        S: Into<String> + Serialize + Send,
+    {
+        // Pick the lowest free index.
+        let existing = self.list_instances().await?;
+        let index = (0..MAX_INSTANCES)
+            .find(|i| !existing.iter().any(|instance| instance.id == i.to_string()))
+            .ok_or_else(|| {
+                CloudProviderError::LocalError(format!(
+                    "at most {MAX_INSTANCES} local instances are supported"
+                ))
+            })?;
+
+        let instance = Instance {
+            id: index.to_string(),
+            region: region.into(),
+            main_ip: self.instance_ip(index),
+            tags: vec![self.settings.testbed_id.clone()],
+            specs: self.settings.specs.clone(),
+            status: InstanceStatus::Active,
+        };
+
+        // Create the home directory of the instance, sharing the rust toolchain of the user.
+        let home = self.executor.home_dir(&instance);
+        fs::create_dir_all(&home)?;
+        if let Some(user_home) = std::env::var_os("HOME").map(PathBuf::from) {
+            for toolchain_dir in [".cargo", ".rustup"] {
+                let target = user_home.join(toolchain_dir);
+                let link = home.join(toolchain_dir);
+                if target.exists() && !link.exists() {
+                    std::os::unix::fs::symlink(target, link)?;
+                }
+            }
+        }
+
+        if self.executor.network_namespaces {
+            self.create_namespace(&instance).await?;
+        }
+        self.save_instance(&instance)?;
+        Ok(instance)
+    }
+
+    async fn delete_instance(&self, instance: Instance) -> CloudProviderResult<()> {
+        self.kill_processes(&instance).await?;
+        if self.executor.network_namespaces {
+            self.delete_namespace(&instance).await?;
+        }
+        fs::remove_dir_all(self.executor.home_dir(&instance))?;
+        Ok(())
+    }
+
+    async fn register_ssh_public_key(&self, _public_key: String) -> CloudProviderResult<()> {
+        // Local instances are not accessed through ssh.
+        Ok(())
+    }
+
+    async fn instance_setup_commands(&self) -> CloudProviderResult<Vec<String>> {
+        Ok(Vec::new())
+    }
+
+    fn local_executor(&self) -> Option<LocalExecutor> {
+        Some(self.executor.clone())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::LocalClient;
+    use crate::{
+        client::{InstanceStatus, ServerProviderClient},
+        settings::Settings,
+    };
+
+    fn settings(root_dir: &std::path::Path) -> Settings {
+        let mut settings = Settings::new_for_test();
+        settings.local.root_dir = root_dir.to_path_buf();
+        settings.local.network_namespaces = false;
+        settings.dedicated_clients = 1;
+        settings
+    }
+
+    #[tokio::test]
+    async fn create_stop_and_delete_instances() {
+        let root_dir = tempfile::tempdir().unwrap();
+        let client = LocalClient::new(settings(root_dir.path())).unwrap();
+
+        let first = client.create_instance("local").await.unwrap();
+        let second = client.create_instance("local").await.unwrap();
+        assert_eq!(first.main_ip.to_string(), "127.0.0.2");
+        assert_eq!(second.main_ip.to_string(), "127.0.0.3");
+        assert_eq!(
+            client.list_instances().await.unwrap(),
+            vec![first.clone(), second]
+        );
+
+        client.stop_instances([first.clone()].iter()).await.unwrap();
+        let instances = client.list_instances().await.unwrap();
+        assert_eq!(instances[0].status, InstanceStatus::Inactive);
+        assert_eq!(instances[1].status, InstanceStatus::Active);
+
+        // The index of a deleted instance is reused.
+        client.delete_instance(first).await.unwrap();
+        assert_eq!(client.list_instances().await.unwrap().len(), 1);
+        let third = client.create_instance("local").await.unwrap();
+        assert_eq!(third.id, "0");
+    }
+
+    #[tokio::test]
+    async fn execute_in_instance_home() {
+        let root_dir = tempfile::tempdir().unwrap();
+        let client = LocalClient::new(settings(root_dir.path())).unwrap();
+        let instance = client.create_instance("local").await.unwrap();
+        let executor = client.local_executor().unwrap();
+
+        let (stdout, _) = executor
+            .execute(&instance, "echo hello > ~/test.log && pwd".into())
+            .await
+            .unwrap();
+
+        let home = executor.home_dir(&instance);
+        assert_eq!(
+            stdout.trim(),
+            home.canonicalize().unwrap().display().to_string()
+        );
+        assert_eq!(executor.download(&instance, "test.log").unwrap(), "hello\n");
+        assert!(executor.execute(&instance, "exit 1".into()).await.is_err());
+    }
+}
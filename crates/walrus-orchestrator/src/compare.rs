@@ -0,0 +1,464 @@
+// Copyright (c) Walrus Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{collections::HashMap, fmt::Display, fs, path::Path};
+
+use prettytable::{row, Table};
+
+use crate::{
+    benchmark::BenchmarkParameters,
+    display,
+    faults::{FaultsType, ScheduledFault},
+    measurements::{MeasurementsCollection, SampleStatistics},
+};
+
+/// The z-score above which a difference between two means is considered statistically
+/// significant (two-sided test at the 95% confidence level).
+const SIGNIFICANCE_Z_SCORE: f64 = 1.96;
+
+/// The maximum tolerated degradation of each metric before reporting a regression.
+#[derive(Clone, Copy, Debug)]
+pub struct RegressionThresholds {
+    /// The maximum tolerated increase of the average latency (in percent).
+    pub max_latency_increase: f64,
+    /// The maximum tolerated decrease of the average throughput (in percent).
+    pub max_throughput_decrease: f64,
+}
+
+/// The metric compared between two runs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Metric {
+    /// The average latency (in seconds); lower is better.
+    Latency,
+    /// The average throughput (in tx/s); higher is better.
+    Throughput,
+}
+
+impl Display for Metric {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Latency => write!(f, "Latency (avg)"),
+            Self::Throughput => write!(f, "TPS (avg)"),
+        }
+    }
+}
+
+/// The comparison of a metric of a workload between a baseline and a candidate run.
+#[derive(Clone, Debug)]
+pub struct MetricComparison {
+    /// The label of the workload.
+    pub workload: String,
+    /// The compared metric.
+    pub metric: Metric,
+    /// The statistics of the metric in the baseline run.
+    pub baseline: SampleStatistics,
+    /// The statistics of the metric in the candidate run.
+    pub candidate: SampleStatistics,
+    /// The relative change of the metric compared to the baseline (in percent).
+    pub change: f64,
+    /// The relative degradation of the candidate compared to the baseline (in percent). A
+    /// negative value indicates an improvement.
+    pub degradation: f64,
+    /// Whether the difference between the two runs is statistically significant.
+    pub significant: bool,
+    /// Whether the degradation is significant and exceeds the threshold.
+    pub regression: bool,
+}
+
+impl MetricComparison {
+    /// Compare the statistics of a metric between two runs.
+    fn new(
+        workload: String,
+        metric: Metric,
+        baseline: SampleStatistics,
+        candidate: SampleStatistics,
+        thresholds: &RegressionThresholds,
+    ) -> Self {
+        let change = if baseline.mean == 0.0 {
+            0.0
+        } else {
+            (candidate.mean - baseline.mean) / baseline.mean * 100.0
+        };
+        let (degradation, threshold) = match metric {
+            Metric::Latency => (change, thresholds.max_latency_increase),
+            Metric::Throughput => (-change, thresholds.max_throughput_decrease),
+        };
+        let significant = welch_z_score(&baseline, &candidate).abs() > SIGNIFICANCE_Z_SCORE;
+        Self {
+            workload,
+            metric,
+            baseline,
+            candidate,
+            change,
+            degradation,
+            significant,
+            regression: significant && degradation > threshold,
+        }
+    }
+
+    /// Format a value of the metric for display.
+    fn format_value(&self, value: f64) -> String {
+        match self.metric {
+            Metric::Latency => format!("{} ms", (value * 1000.0).round()),
+            Metric::Throughput => format!("{value:.1} tx/s"),
+        }
+    }
+}
+
+/// Compute Welch's t statistic of the difference between the means of two sets of samples. The
+/// statistic is compared against the normal distribution, which assumes enough samples.
+fn welch_z_score(a: &SampleStatistics, b: &SampleStatistics) -> f64 {
+    if a.count == 0 || b.count == 0 {
+        return 0.0;
+    }
+    let standard_error = (a.variance / a.count as f64 + b.variance / b.count as f64).sqrt();
+    let difference = b.mean - a.mean;
+    if standard_error == 0.0 {
+        // Without variance, any difference is significant.
+        return if difference == 0.0 {
+            0.0
+        } else {
+            f64::INFINITY.copysign(difference)
+        };
+    }
+    difference / standard_error
+}
+
+/// The result of the comparison of two benchmark runs.
+#[derive(Clone, Debug)]
+pub struct Comparison {
+    /// The comparison of each metric of the workloads present in both runs.
+    pub metrics: Vec<MetricComparison>,
+    /// The workloads present in only one of the runs.
+    pub unmatched_workloads: Vec<String>,
+}
+
+impl Comparison {
+    /// Compare the latency and throughput of all workloads of two benchmark runs.
+    pub fn new(
+        baseline: &MeasurementsCollection,
+        candidate: &MeasurementsCollection,
+        thresholds: &RegressionThresholds,
+    ) -> Self {
+        let mut labels: Vec<_> = baseline.labels().chain(candidate.labels()).collect();
+        labels.sort();
+        labels.dedup();
+
+        let mut metrics = Vec::new();
+        let mut unmatched_workloads = Vec::new();
+        for label in labels {
+            if !baseline.data.contains_key(label) || !candidate.data.contains_key(label) {
+                unmatched_workloads.push(label.clone());
+                continue;
+            }
+            metrics.push(MetricComparison::new(
+                label.clone(),
+                Metric::Latency,
+                baseline.latency_statistics(label),
+                candidate.latency_statistics(label),
+                thresholds,
+            ));
+            metrics.push(MetricComparison::new(
+                label.clone(),
+                Metric::Throughput,
+                SampleStatistics::from_samples(&baseline.throughput_series(label)),
+                SampleStatistics::from_samples(&candidate.throughput_series(label)),
+                thresholds,
+            ));
+        }
+
+        Self {
+            metrics,
+            unmatched_workloads,
+        }
+    }
+
+    /// The metrics that regressed.
+    pub fn regressions(&self) -> impl Iterator<Item = &MetricComparison> {
+        self.metrics.iter().filter(|x| x.regression)
+    }
+
+    /// Display the comparison.
+    pub fn display(&self) {
+        let mut table = Table::new();
+        table.set_format(display::default_table_format());
+
+        table.set_titles(row![bH6->"Benchmark Comparison"]);
+        table.add_row(row![
+            b->"Workload",
+            b->"Metric",
+            b->"Baseline",
+            b->"Candidate",
+            b->"Change",
+            b->"Status"
+        ]);
+        for comparison in &self.metrics {
+            let status = if comparison.regression {
+                "REGRESSION"
+            } else if !comparison.significant {
+                "not significant"
+            } else if comparison.degradation > 0.0 {
+                "within threshold"
+            } else {
+                "improvement"
+            };
+            table.add_row(row![
+                comparison.workload,
+                comparison.metric,
+                comparison.format_value(comparison.baseline.mean),
+                comparison.format_value(comparison.candidate.mean),
+                format!("{:+.1}%", comparison.change),
+                status
+            ]);
+        }
+
+        display::newline();
+        table.printstd();
+        display::newline();
+
+        for workload in &self.unmatched_workloads {
+            display::warn(format!(
+                "Workload '{workload}' is present in only one of the runs"
+            ));
+        }
+    }
+}
+
+/// The parameters identifying a benchmark across runs.
+///
+/// The node and client parameters are not part of the key, so that runs with different
+/// configurations of the same workload can be compared.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BenchmarkKey {
+    /// The committee size.
+    pub nodes: usize,
+    /// The total read and write loads (ops/minute) submitted to the system.
+    pub load: usize,
+    /// The node crashes of the benchmark.
+    pub faults: FaultsType,
+    /// The faults injected during the benchmark.
+    pub injected_faults: Vec<ScheduledFault>,
+}
+
+impl BenchmarkKey {
+    /// The key by which benchmarks are ordered for display.
+    fn sort_key(&self) -> (usize, usize, String) {
+        (self.nodes, self.load, self.to_string())
+    }
+}
+
+impl From<&BenchmarkParameters> for BenchmarkKey {
+    fn from(parameters: &BenchmarkParameters) -> Self {
+        Self {
+            nodes: parameters.nodes,
+            load: parameters.load,
+            faults: parameters.settings.faults.clone(),
+            injected_faults: parameters.settings.injected_faults.clone(),
+        }
+    }
+}
+
+impl Display for BenchmarkKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} nodes ({}) - {} ops/min",
+            self.nodes, self.faults, self.load
+        )?;
+        if !self.injected_faults.is_empty() {
+            write!(f, " - {} injected fault(s)", self.injected_faults.len())?;
+        }
+        Ok(())
+    }
+}
+
+/// Load a set of benchmark results, indexed by benchmark. The path is either a single
+/// measurements file or a directory containing the measurements files of multiple runs.
+pub fn load_result_set<P: AsRef<Path>>(
+    path: P,
+) -> Result<HashMap<BenchmarkKey, MeasurementsCollection>, std::io::Error> {
+    let path = path.as_ref();
+    let files = if path.is_dir() {
+        let mut files = Vec::new();
+        for entry in fs::read_dir(path)? {
+            let file = entry?.path();
+            let is_measurements_file = file.extension().is_some_and(|x| x == "json")
+                && file
+                    .file_name()
+                    .and_then(|x| x.to_str())
+                    .is_some_and(|x| x.starts_with("measurements-"));
+            if is_measurements_file {
+                files.push(file);
+            }
+        }
+        files
+    } else {
+        vec![path.to_path_buf()]
+    };
+
+    files
+        .into_iter()
+        .map(|file| {
+            let measurements = MeasurementsCollection::load(file)?;
+            Ok((BenchmarkKey::from(&measurements.parameters), measurements))
+        })
+        .collect()
+}
+
+/// Compare two sets of benchmark results and display the comparison of each benchmark present in
+/// both sets, warning about the benchmarks present in only one of them. Returns the number of
+/// regressions.
+pub fn compare_result_sets<P: AsRef<Path>>(
+    baseline: P,
+    candidate: P,
+    thresholds: &RegressionThresholds,
+) -> Result<usize, std::io::Error> {
+    let baseline = load_result_set(baseline)?;
+    let candidate = load_result_set(candidate)?;
+
+    // Compare single files regardless of their benchmark parameters.
+    let pairs: Vec<_> = if baseline.len() == 1 && candidate.len() == 1 {
+        baseline.iter().zip(candidate.values()).collect()
+    } else {
+        let sorted = |results: &HashMap<BenchmarkKey, MeasurementsCollection>| {
+            let mut keys: Vec<_> = results.keys().collect();
+            keys.sort_by_key(|key| key.sort_key());
+            keys
+        };
+        for key in sorted(&candidate) {
+            if !baseline.contains_key(key) {
+                display::warn(format!("Benchmark '{key}' is missing from the baseline"));
+            }
+        }
+        sorted(&baseline)
+            .into_iter()
+            .filter_map(|key| {
+                let other = candidate.get(key);
+                if other.is_none() {
+                    display::warn(format!("Benchmark '{key}' is missing from the candidate"));
+                }
+                other.map(|other| ((key, &baseline[key]), other))
+            })
+            .collect()
+    };
+
+    let mut regressions = 0;
+    for ((key, baseline), candidate) in pairs {
+        display::header(key);
+        let comparison = Comparison::new(baseline, candidate, thresholds);
+        comparison.display();
+        regressions += comparison.regressions().count();
+    }
+    Ok(regressions)
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::{
+        load_result_set,
+        welch_z_score,
+        BenchmarkKey,
+        Metric,
+        MetricComparison,
+        RegressionThresholds,
+    };
+    use crate::{
+        benchmark::BenchmarkParameters,
+        faults::FaultsType,
+        measurements::{MeasurementsCollection, SampleStatistics},
+    };
+
+    const THRESHOLDS: RegressionThresholds = RegressionThresholds {
+        max_latency_increase: 10.0,
+        max_throughput_decrease: 10.0,
+    };
+
+    fn statistics(mean: f64, variance: f64, count: usize) -> SampleStatistics {
+        SampleStatistics {
+            mean,
+            variance,
+            count,
+        }
+    }
+
+    #[test]
+    fn welch_z_score_symmetric() {
+        let a = statistics(1.0, 0.04, 100);
+        let b = statistics(1.1, 0.04, 100);
+        let z = welch_z_score(&a, &b);
+        assert!((z - 0.1 / (0.0008_f64).sqrt()).abs() < 1e-9);
+        assert_eq!(welch_z_score(&b, &a), -z);
+    }
+
+    #[test]
+    fn latency_regression() {
+        let comparison = MetricComparison::new(
+            "write".into(),
+            Metric::Latency,
+            statistics(1.0, 0.01, 1000),
+            statistics(1.2, 0.01, 1000),
+            &THRESHOLDS,
+        );
+        assert!(comparison.significant);
+        assert!(comparison.regression);
+        assert!((comparison.degradation - 20.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn throughput_improvement_is_not_a_regression() {
+        let comparison = MetricComparison::new(
+            "write".into(),
+            Metric::Throughput,
+            statistics(100.0, 1.0, 50),
+            statistics(150.0, 1.0, 50),
+            &THRESHOLDS,
+        );
+        assert!(comparison.significant);
+        assert!(!comparison.regression);
+    }
+
+    #[test]
+    fn noisy_degradation_is_not_significant() {
+        let comparison = MetricComparison::new(
+            "read".into(),
+            Metric::Throughput,
+            statistics(100.0, 10_000.0, 5),
+            statistics(50.0, 10_000.0, 5),
+            &THRESHOLDS,
+        );
+        assert!(!comparison.significant);
+        assert!(!comparison.regression);
+    }
+
+    #[test]
+    fn benchmark_key_ignores_unrelated_settings() {
+        let parameters = BenchmarkParameters::new_for_tests();
+        let mut other = parameters.clone();
+        other.settings.repository.commit = "other".into();
+        assert_eq!(BenchmarkKey::from(&parameters), BenchmarkKey::from(&other));
+
+        other.settings.faults = FaultsType::CrashRecovery {
+            max_faults: 1,
+            interval: Duration::from_secs(60),
+        };
+        assert_ne!(BenchmarkKey::from(&parameters), BenchmarkKey::from(&other));
+    }
+
+    #[test]
+    fn load_result_set_indexes_by_benchmark_key() {
+        let directory = tempfile::tempdir().unwrap();
+        for load in [100, 200] {
+            let mut parameters = BenchmarkParameters::new_for_tests();
+            parameters.load = load;
+            MeasurementsCollection::new(parameters).save(directory.path());
+        }
+
+        let results = load_result_set(directory.path()).unwrap();
+        assert_eq!(results.len(), 2);
+        let mut loads: Vec<_> = results.keys().map(|key| key.load).collect();
+        loads.sort();
+        assert_eq!(loads, vec![100, 200]);
+    }
+}
@@ -32,6 +32,9 @@ pub enum CloudProviderError {
 
     #[error("SSH key \"{0}\" not found")]
     SshKeyNotFound(String),
+
+    #[error("Failed to manage local instances: {0}")]
+    LocalError(String),
 }
 
 pub type SshResult<T> = Result<T, SshError>;
@@ -56,6 +59,9 @@ pub enum SshError {
         code: i32,
         message: String,
     },
+
+    #[error("Execution on local instance {instance} failed: {message}")]
+    LocalExecutionError { instance: String, message: String },
 }
 
 pub type MonitorResult<T> = Result<T, MonitorError>;
@@ -80,6 +86,9 @@ pub enum TestbedError {
     #[error("Not enough instances: missing {0} instances")]
     InsufficientCapacity(usize),
 
+    #[error("Invalid fault schedule: {0}")]
+    InvalidFaultSchedule(String),
+
     #[error(transparent)]
     MonitorError(#[from] MonitorError),
 }
@@ -3,10 +3,13 @@
 
 use std::{
     fmt::{Debug, Display},
+    net::Ipv4Addr,
+    path::Path,
     time::Duration,
 };
 
 use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DurationSeconds};
 
 use crate::client::Instance;
 
@@ -172,11 +175,456 @@ impl CrashRecoverySchedule {
     }
 }
 
+/// A fault injected into a subset of the nodes, in addition to node crashes.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum InjectedFault {
+    /// Partition the nodes from all other nodes of the committee.
+    Partition,
+    /// Delay and drop the packets sent by the nodes (through `tc netem`).
+    Netem {
+        /// The latency added to each packet (in milliseconds).
+        #[serde(default)]
+        latency_ms: u64,
+        /// The jitter of the added latency (in milliseconds).
+        #[serde(default)]
+        jitter_ms: u64,
+        /// The percentage of dropped packets.
+        #[serde(default)]
+        loss_percent: u8,
+    },
+    /// Fill the disk holding the working directory of the nodes, leaving only the specified
+    /// number of bytes available.
+    DiskFull {
+        /// The number of bytes left available on the disk.
+        #[serde(default)]
+        free_bytes: u64,
+    },
+    /// Throttle the disk bandwidth of the node processes (through the `io.max` controller of
+    /// cgroup v2).
+    SlowDisk {
+        /// The maximum read and write bandwidth of the node processes.
+        bytes_per_second: u64,
+    },
+    /// Make the Sui RPC endpoints unreachable from the nodes. The host names of the endpoints
+    /// are resolved when the fault is injected.
+    RpcOutage,
+}
+
+impl Display for InjectedFault {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Partition => write!(f, "network partition"),
+            Self::Netem {
+                latency_ms,
+                jitter_ms,
+                loss_percent,
+            } => write!(
+                f,
+                "{latency_ms}ms (+/- {jitter_ms}ms) latency, {loss_percent}% packet loss"
+            ),
+            Self::DiskFull { free_bytes } => write!(f, "disk full ({free_bytes} bytes free)"),
+            Self::SlowDisk { bytes_per_second } => {
+                write!(f, "slow disk ({bytes_per_second} bytes/s)")
+            }
+            Self::RpcOutage => write!(f, "Sui RPC outage"),
+        }
+    }
+}
+
+/// The information about the testbed needed to inject a fault.
+#[derive(Debug)]
+pub struct FaultContext<'a> {
+    /// The addresses of the nodes that are not affected by the fault.
+    pub other_nodes: Vec<Ipv4Addr>,
+    /// The hosts of the Sui RPC endpoints used by the nodes.
+    pub rpc_hosts: Vec<String>,
+    /// The working directory of the nodes on the instances.
+    pub working_dir: &'a Path,
+    /// A pattern (for `pgrep -f`) matching the node processes.
+    pub node_process_pattern: &'a str,
+}
+
+/// A fault injected at a given time of the benchmark into a subset of the nodes.
+#[serde_as]
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq, Hash)]
+pub struct ScheduledFault {
+    /// The fault to inject.
+    #[serde(flatten)]
+    pub fault: InjectedFault,
+    /// The indices of the affected nodes in the committee.
+    pub nodes: Vec<usize>,
+    /// When to inject the fault, relative to the start of the benchmark (in seconds).
+    #[serde_as(as = "DurationSeconds")]
+    pub start: Duration,
+    /// How long the fault lasts (in seconds). If not set, the fault lasts until the end of the
+    /// benchmark.
+    #[serde_as(as = "Option<DurationSeconds>")]
+    #[serde(default)]
+    pub duration: Option<Duration>,
+}
+
+impl ScheduledFault {
+    /// The time at which the fault is reverted, if it is reverted before the end of the benchmark.
+    fn end(&self) -> Option<Duration> {
+        self.duration.map(|duration| self.start + duration)
+    }
+
+    /// Returns whether the fault conflicts with the other fault, i.e., whether both faults
+    /// configure the same exclusive resource of a common node at the same time.
+    ///
+    /// `Netem` faults replace the root queueing discipline of the network interface, and
+    /// `SlowDisk` faults move the node processes into their own cgroup; such faults would
+    /// overwrite each other.
+    pub fn conflicts_with(&self, other: &Self) -> bool {
+        let exclusive = matches!(
+            self.fault,
+            InjectedFault::Netem { .. } | InjectedFault::SlowDisk { .. }
+        );
+        let same_kind = std::mem::discriminant(&self.fault) == std::mem::discriminant(&other.fault);
+        let common_node = self.nodes.iter().any(|node| other.nodes.contains(node));
+        let overlapping = self.end().is_none_or(|end| other.start < end)
+            && other.end().is_none_or(|end| self.start < end);
+        exclusive && same_kind && common_node && overlapping
+    }
+
+    /// The name of the iptables chain holding the rules of the fault with the specified id.
+    fn chain(id: usize) -> String {
+        format!("WALRUS_FAULT_{id}")
+    }
+
+    /// The cgroup holding the node processes throttled by the fault with the specified id.
+    fn cgroup(id: usize) -> String {
+        format!("/sys/fs/cgroup/walrus-fault-{id}")
+    }
+
+    /// The file filling the disk for the fault with the specified id.
+    fn fill_file(id: usize, working_dir: &Path) -> String {
+        format!("{}/walrus-fault-{id}.fill", working_dir.display())
+    }
+
+    /// Returns the command injecting the fault on the affected nodes.
+    pub fn inject_command(&self, id: usize, context: &FaultContext) -> String {
+        const DEFAULT_INTERFACE: &str = "IFACE=$(ip route show default | awk '{print $5; exit}')";
+        let chain = Self::chain(id);
+        // Hook a dedicated chain into the INPUT and OUTPUT chains, so it can be removed at once.
+        let with_chain = |rules: Vec<String>| {
+            [
+                vec![format!("sudo iptables -N {chain}")],
+                rules,
+                vec![
+                    format!("sudo iptables -I INPUT -j {chain}"),
+                    format!("sudo iptables -I OUTPUT -j {chain}"),
+                ],
+            ]
+            .concat()
+            .join(" && ")
+        };
+
+        match &self.fault {
+            InjectedFault::Partition => with_chain(
+                context
+                    .other_nodes
+                    .iter()
+                    .flat_map(|ip| {
+                        [
+                            format!("sudo iptables -A {chain} -s {ip} -j DROP"),
+                            format!("sudo iptables -A {chain} -d {ip} -j DROP"),
+                        ]
+                    })
+                    .collect(),
+            ),
+            InjectedFault::Netem {
+                latency_ms,
+                jitter_ms,
+                loss_percent,
+            } => format!(
+                "{DEFAULT_INTERFACE} && sudo tc qdisc replace dev $IFACE root netem \
+                delay {latency_ms}ms {jitter_ms}ms loss {loss_percent}%"
+            ),
+            InjectedFault::DiskFull { free_bytes } => {
+                let working_dir = context.working_dir.display();
+                let fill_file = Self::fill_file(id, context.working_dir);
+                format!(
+                    "SIZE=$(( $(df --output=avail -B1 {working_dir} | tail -1) - {free_bytes} )) \
+                    && if [ $SIZE -gt 0 ]; then fallocate -l $SIZE {fill_file}; fi"
+                )
+            }
+            InjectedFault::SlowDisk { bytes_per_second } => {
+                let cgroup = Self::cgroup(id);
+                let working_dir = context.working_dir.display();
+                let pattern = context.node_process_pattern;
+                [
+                    "echo +io | sudo tee /sys/fs/cgroup/cgroup.subtree_control > /dev/null".into(),
+                    format!("sudo mkdir -p {cgroup}"),
+                    format!("DEVICE=$(findmnt -n -o MAJ:MIN --target {working_dir} | tr -d ' ')"),
+                    format!(
+                        "echo \"$DEVICE rbps={bytes_per_second} wbps={bytes_per_second}\" \
+                        | sudo tee {cgroup}/io.max > /dev/null"
+                    ),
+                    format!(
+                        "for pid in $(pgrep -f '{pattern}'); do \
+                        echo $pid | sudo tee {cgroup}/cgroup.procs > /dev/null; done"
+                    ),
+                ]
+                .join(" && ")
+            }
+            InjectedFault::RpcOutage => with_chain(
+                context
+                    .rpc_hosts
+                    .iter()
+                    .map(|host| {
+                        format!(
+                            "for ip in $(getent ahostsv4 {host} | awk '{{print $1}}' | sort -u); \
+                            do sudo iptables -A {chain} -d $ip -j DROP; done"
+                        )
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Returns the command injecting the fault again on nodes that were rebooted while it was
+    /// injected, as the restarted node processes are not affected by some of the faults.
+    pub fn reapply_command(&self, id: usize, context: &FaultContext) -> String {
+        format!(
+            "{} ; {}",
+            self.revert_command(id, context.working_dir),
+            self.inject_command(id, context)
+        )
+    }
+
+    /// Returns the command reverting the fault on the affected nodes. The command succeeds even
+    /// if the fault is not currently injected.
+    pub fn revert_command(&self, id: usize, working_dir: &Path) -> String {
+        let chain = Self::chain(id);
+        let commands = match &self.fault {
+            InjectedFault::Partition | InjectedFault::RpcOutage => vec![
+                format!("sudo iptables -D INPUT -j {chain}"),
+                format!("sudo iptables -D OUTPUT -j {chain}"),
+                format!("sudo iptables -F {chain}"),
+                format!("sudo iptables -X {chain}"),
+            ],
+            InjectedFault::Netem { .. } => vec![
+                "IFACE=$(ip route show default | awk '{print $5; exit}') && \
+                sudo tc qdisc del dev $IFACE root"
+                    .into(),
+            ],
+            InjectedFault::DiskFull { .. } => {
+                vec![format!("rm -f {}", Self::fill_file(id, working_dir))]
+            }
+            InjectedFault::SlowDisk { .. } => {
+                let cgroup = Self::cgroup(id);
+                vec![
+                    format!(
+                        "for pid in $(cat {cgroup}/cgroup.procs); do \
+                        echo $pid | sudo tee /sys/fs/cgroup/cgroup.procs > /dev/null; done"
+                    ),
+                    format!("sudo rmdir {cgroup}"),
+                ]
+            }
+        };
+        commands
+            .into_iter()
+            .map(|command| format!("({command} || true)"))
+            .collect::<Vec<_>>()
+            .join(" ; ")
+    }
+}
+
+/// Whether a fault is injected or reverted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FaultInjectionKind {
+    /// Inject the fault.
+    Inject,
+    /// Revert the fault.
+    Revert,
+    /// Inject the fault again into nodes that were rebooted while it was injected.
+    Reapply,
+}
+
+/// The action to apply to the testbed to inject or revert a scheduled fault.
+#[derive(Clone, Debug)]
+pub struct FaultInjectionAction {
+    /// The id of the fault (its index in the schedule).
+    pub id: usize,
+    /// The fault to inject or revert.
+    pub fault: ScheduledFault,
+    /// Whether to inject or revert the fault.
+    pub kind: FaultInjectionKind,
+    /// The indices of the nodes to apply the action to, a subset of the nodes of the fault.
+    pub nodes: Vec<usize>,
+}
+
+impl Display for FaultInjectionAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let action = match self.kind {
+            FaultInjectionKind::Inject => "injected",
+            FaultInjectionKind::Revert => "reverted",
+            FaultInjectionKind::Reapply => "re-applied",
+        };
+        write!(
+            f,
+            "{} {action} on {} node(s)",
+            self.fault.fault,
+            self.nodes.len()
+        )
+    }
+}
+
+/// The state of a scheduled fault.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum FaultState {
+    Pending,
+    Active,
+    Reverted,
+}
+
+/// Schedules the injection and reversion of faults over the benchmark timeline.
+#[derive(Debug)]
+pub struct FaultInjectionSchedule {
+    /// The faults to inject.
+    faults: Vec<ScheduledFault>,
+    /// The current state of each fault.
+    states: Vec<FaultState>,
+}
+
+impl FaultInjectionSchedule {
+    /// Creates a new schedule for the specified faults.
+    pub fn new(faults: Vec<ScheduledFault>) -> Self {
+        let states = vec![FaultState::Pending; faults.len()];
+        Self { faults, states }
+    }
+
+    /// Returns whether there is no fault to inject.
+    pub fn is_empty(&self) -> bool {
+        self.faults.is_empty()
+    }
+
+    /// Checks that the faults only affect nodes with an index below `n_nodes`, and that no two
+    /// faults conflict with each other.
+    pub fn check(&self, n_nodes: usize) -> Result<(), String> {
+        if let Some(index) = self
+            .faults
+            .iter()
+            .flat_map(|fault| &fault.nodes)
+            .find(|index| **index >= n_nodes)
+        {
+            return Err(format!(
+                "cannot inject a fault into node {index}; \
+                faults can only be injected into nodes 0 to {}",
+                n_nodes.saturating_sub(1)
+            ));
+        }
+        for (id, fault) in self.faults.iter().enumerate() {
+            if let Some(other_id) = self.faults[id + 1..]
+                .iter()
+                .position(|other| fault.conflicts_with(other))
+            {
+                return Err(format!(
+                    "faults {id} and {} cannot be injected into the same node at the same time",
+                    id + 1 + other_id
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the faults to inject and to revert at the specified time since the start of the
+    /// benchmark. Reversions are returned before injections.
+    pub fn update(&mut self, elapsed: Duration) -> Vec<FaultInjectionAction> {
+        let mut reverted = Vec::new();
+        let mut injected = Vec::new();
+        for (id, (fault, state)) in self.faults.iter().zip(self.states.iter_mut()).enumerate() {
+            let end = fault.duration.map(|duration| fault.start + duration);
+            let (next_state, kind) = match state {
+                FaultState::Pending if end.is_some_and(|end| elapsed >= end) => {
+                    // The fault window passed entirely between two updates.
+                    (FaultState::Reverted, None)
+                }
+                FaultState::Pending if elapsed >= fault.start => {
+                    (FaultState::Active, Some(FaultInjectionKind::Inject))
+                }
+                FaultState::Active if end.is_some_and(|end| elapsed >= end) => {
+                    (FaultState::Reverted, Some(FaultInjectionKind::Revert))
+                }
+                _ => continue,
+            };
+            *state = next_state;
+            let action = kind.map(|kind| FaultInjectionAction {
+                id,
+                fault: fault.clone(),
+                kind,
+                nodes: fault.nodes.clone(),
+            });
+            match kind {
+                Some(FaultInjectionKind::Inject) => injected.extend(action),
+                Some(FaultInjectionKind::Revert) => reverted.extend(action),
+                None => (),
+            }
+        }
+        reverted.extend(injected);
+        reverted
+    }
+
+    /// Returns the actions reverting all faults that are currently injected.
+    pub fn revert_all(&mut self) -> Vec<FaultInjectionAction> {
+        self.faults
+            .iter()
+            .zip(self.states.iter_mut())
+            .enumerate()
+            .filter(|(_, (_, state))| **state == FaultState::Active)
+            .map(|(id, (fault, state))| {
+                *state = FaultState::Reverted;
+                FaultInjectionAction {
+                    id,
+                    fault: fault.clone(),
+                    kind: FaultInjectionKind::Revert,
+                    nodes: fault.nodes.clone(),
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the actions re-applying the currently injected faults to the specified nodes,
+    /// which were rebooted.
+    pub fn reapply(&self, rebooted: &[usize]) -> Vec<FaultInjectionAction> {
+        self.faults
+            .iter()
+            .zip(&self.states)
+            .enumerate()
+            .filter(|(_, (_, state))| **state == FaultState::Active)
+            .filter_map(|(id, (fault, _))| {
+                let nodes: Vec<_> = fault
+                    .nodes
+                    .iter()
+                    .filter(|node| rebooted.contains(node))
+                    .copied()
+                    .collect();
+                (!nodes.is_empty()).then(|| FaultInjectionAction {
+                    id,
+                    fault: fault.clone(),
+                    kind: FaultInjectionKind::Reapply,
+                    nodes,
+                })
+            })
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod faults_tests {
-    use std::time::Duration;
-
-    use super::{CrashRecoverySchedule, FaultsType};
+    use std::{path::Path, time::Duration};
+
+    use super::{
+        CrashRecoverySchedule,
+        FaultContext,
+        FaultInjectionKind,
+        FaultInjectionSchedule,
+        FaultsType,
+        InjectedFault,
+        ScheduledFault,
+    };
     use crate::client::Instance;
 
     #[test]
@@ -282,4 +730,241 @@ mod faults_tests {
             assert_eq!(action.kill.len(), min_faults);
         }
     }
+
+    fn scheduled(start: u64, duration: Option<u64>) -> ScheduledFault {
+        ScheduledFault {
+            fault: InjectedFault::Partition,
+            nodes: vec![0],
+            start: Duration::from_secs(start),
+            duration: duration.map(Duration::from_secs),
+        }
+    }
+
+    #[test]
+    fn injection_schedule() {
+        let mut schedule = FaultInjectionSchedule::new(vec![
+            scheduled(10, Some(20)),
+            scheduled(20, None),
+            scheduled(15, Some(1)),
+        ]);
+
+        assert!(schedule.update(Duration::from_secs(5)).is_empty());
+
+        let actions = schedule.update(Duration::from_secs(10));
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].id, 0);
+        assert_eq!(actions[0].kind, FaultInjectionKind::Inject);
+
+        // The third fault started and ended between two updates, so it is never injected.
+        let actions = schedule.update(Duration::from_secs(20));
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].id, 1);
+        assert_eq!(actions[0].kind, FaultInjectionKind::Inject);
+
+        let actions = schedule.update(Duration::from_secs(30));
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].id, 0);
+        assert_eq!(actions[0].kind, FaultInjectionKind::Revert);
+
+        assert!(schedule.update(Duration::from_secs(100)).is_empty());
+
+        let actions = schedule.revert_all();
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].id, 1);
+        assert_eq!(actions[0].kind, FaultInjectionKind::Revert);
+        assert!(schedule.revert_all().is_empty());
+    }
+
+    #[test]
+    fn reapply_active_faults_to_rebooted_nodes() {
+        let mut schedule = FaultInjectionSchedule::new(vec![
+            ScheduledFault {
+                nodes: vec![0, 1],
+                ..scheduled(0, None)
+            },
+            scheduled(100, None),
+        ]);
+        schedule.update(Duration::from_secs(10));
+
+        let actions = schedule.reapply(&[1, 2]);
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].id, 0);
+        assert_eq!(actions[0].kind, FaultInjectionKind::Reapply);
+        assert_eq!(actions[0].nodes, vec![1]);
+
+        assert!(schedule.reapply(&[2]).is_empty());
+        schedule.revert_all();
+        assert!(schedule.reapply(&[0, 1]).is_empty());
+    }
+
+    fn netem(nodes: Vec<usize>, start: u64, duration: Option<u64>) -> ScheduledFault {
+        ScheduledFault {
+            fault: InjectedFault::Netem {
+                latency_ms: 100,
+                jitter_ms: 10,
+                loss_percent: 5,
+            },
+            nodes,
+            ..scheduled(start, duration)
+        }
+    }
+
+    #[test]
+    fn exclusive_faults_conflict_on_common_nodes_and_overlapping_windows() {
+        assert!(netem(vec![0, 1], 0, None).conflicts_with(&netem(vec![1], 30, Some(10))));
+        assert!(netem(vec![0], 0, Some(20)).conflicts_with(&netem(vec![0], 10, None)));
+        // Disjoint nodes or time windows do not conflict.
+        assert!(!netem(vec![0], 0, None).conflicts_with(&netem(vec![1], 0, None)));
+        assert!(!netem(vec![0], 0, Some(10)).conflicts_with(&netem(vec![0], 10, None)));
+        // Partitions use one iptables chain per fault and can be combined.
+        assert!(!scheduled(0, None).conflicts_with(&scheduled(0, None)));
+        assert!(!netem(vec![0], 0, None).conflicts_with(&scheduled(0, None)));
+    }
+
+    #[test]
+    fn check_rejects_invalid_schedules() {
+        let schedule = FaultInjectionSchedule::new(vec![netem(vec![0], 0, None)]);
+        assert!(schedule.check(1).is_ok());
+        assert!(schedule.check(0).is_err());
+
+        let schedule = FaultInjectionSchedule::new(vec![
+            netem(vec![0], 0, None),
+            scheduled(0, None),
+            netem(vec![0], 60, Some(10)),
+        ]);
+        let error = schedule.check(1).unwrap_err();
+        assert!(error.contains("faults 0 and 2"), "{error}");
+    }
+
+    fn context(working_dir: &Path) -> FaultContext<'_> {
+        FaultContext {
+            other_nodes: vec!["10.0.0.2".parse().unwrap()],
+            rpc_hosts: vec!["rpc.example.com".into()],
+            working_dir,
+            node_process_pattern: "walrus-node run",
+        }
+    }
+
+    #[test]
+    fn partition_commands() {
+        let working_dir = Path::new("/opt/walrus");
+        let fault = scheduled(0, None);
+        assert_eq!(
+            fault.inject_command(3, &context(working_dir)),
+            "sudo iptables -N WALRUS_FAULT_3 \
+            && sudo iptables -A WALRUS_FAULT_3 -s 10.0.0.2 -j DROP \
+            && sudo iptables -A WALRUS_FAULT_3 -d 10.0.0.2 -j DROP \
+            && sudo iptables -I INPUT -j WALRUS_FAULT_3 \
+            && sudo iptables -I OUTPUT -j WALRUS_FAULT_3"
+        );
+        assert_eq!(
+            fault.revert_command(3, working_dir),
+            "(sudo iptables -D INPUT -j WALRUS_FAULT_3 || true) ; \
+            (sudo iptables -D OUTPUT -j WALRUS_FAULT_3 || true) ; \
+            (sudo iptables -F WALRUS_FAULT_3 || true) ; \
+            (sudo iptables -X WALRUS_FAULT_3 || true)"
+        );
+    }
+
+    #[test]
+    fn netem_commands() {
+        let working_dir = Path::new("/opt/walrus");
+        let fault = netem(vec![0], 0, None);
+        let set_interface = "IFACE=$(ip route show default | awk '{print $5; exit}')";
+        assert_eq!(
+            fault.inject_command(0, &context(working_dir)),
+            format!(
+                "{set_interface} && sudo tc qdisc replace dev $IFACE root netem \
+                delay 100ms 10ms loss 5%"
+            )
+        );
+        assert_eq!(
+            fault.revert_command(0, working_dir),
+            format!("({set_interface} && sudo tc qdisc del dev $IFACE root || true)")
+        );
+    }
+
+    #[test]
+    fn disk_commands() {
+        let working_dir = Path::new("/opt/walrus");
+        let disk_full = ScheduledFault {
+            fault: InjectedFault::DiskFull { free_bytes: 1024 },
+            ..scheduled(0, None)
+        };
+        assert_eq!(
+            disk_full.inject_command(1, &context(working_dir)),
+            "SIZE=$(( $(df --output=avail -B1 /opt/walrus | tail -1) - 1024 )) \
+            && if [ $SIZE -gt 0 ]; then fallocate -l $SIZE /opt/walrus/walrus-fault-1.fill; fi"
+        );
+        assert_eq!(
+            disk_full.revert_command(1, working_dir),
+            "(rm -f /opt/walrus/walrus-fault-1.fill || true)"
+        );
+
+        let slow_disk = ScheduledFault {
+            fault: InjectedFault::SlowDisk {
+                bytes_per_second: 4096,
+            },
+            ..scheduled(0, None)
+        };
+        let inject = slow_disk.inject_command(2, &context(working_dir));
+        assert!(inject.contains("sudo mkdir -p /sys/fs/cgroup/walrus-fault-2"));
+        assert!(inject.contains("--target /opt/walrus"));
+        assert!(inject.contains(
+            "echo \"$DEVICE rbps=4096 wbps=4096\" | sudo tee /sys/fs/cgroup/walrus-fault-2/io.max"
+        ));
+        assert!(inject.contains("$(pgrep -f 'walrus-node run')"));
+        assert!(slow_disk
+            .revert_command(2, working_dir)
+            .ends_with("(sudo rmdir /sys/fs/cgroup/walrus-fault-2 || true)"));
+    }
+
+    #[test]
+    fn rpc_outage_and_reapply_commands() {
+        let working_dir = Path::new("/opt/walrus");
+        let fault = ScheduledFault {
+            fault: InjectedFault::RpcOutage,
+            ..scheduled(0, None)
+        };
+        let inject = fault.inject_command(4, &context(working_dir));
+        assert!(inject.starts_with("sudo iptables -N WALRUS_FAULT_4 && "));
+        assert!(inject.contains(
+            "for ip in $(getent ahostsv4 rpc.example.com | awk '{print $1}' | sort -u); \
+            do sudo iptables -A WALRUS_FAULT_4 -d $ip -j DROP; done"
+        ));
+        assert!(inject.ends_with("sudo iptables -I OUTPUT -j WALRUS_FAULT_4"));
+
+        assert_eq!(
+            fault.reapply_command(4, &context(working_dir)),
+            format!("{} ; {inject}", fault.revert_command(4, working_dir))
+        );
+    }
+
+    #[test]
+    fn deserialize_scheduled_faults() {
+        let yaml = r#"
+- type: partition
+  nodes: [0, 1]
+  start: 30
+  duration: 60
+- type: netem
+  nodes: [2]
+  start: 10
+  latency_ms: 100
+  loss_percent: 5
+"#;
+        let faults: Vec<ScheduledFault> = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(faults.len(), 2);
+        assert_eq!(faults[0].fault, InjectedFault::Partition);
+        assert_eq!(faults[0].duration, Some(Duration::from_secs(60)));
+        assert_eq!(
+            faults[1].fault,
+            InjectedFault::Netem {
+                latency_ms: 100,
+                jitter_ms: 0,
+                loss_percent: 5
+            }
+        );
+        assert_eq!(faults[1].duration, None);
+    }
 }
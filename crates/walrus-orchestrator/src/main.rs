@@ -7,9 +7,10 @@ use std::path::PathBuf;
 
 use benchmark::BenchmarkParameters;
 use clap::Parser;
-use client::{aws::AwsClient, vultr::VultrClient, ServerProviderClient};
+use client::{aws::AwsClient, local::LocalClient, vultr::VultrClient, ServerProviderClient};
+use compare::RegressionThresholds;
 use eyre::Context;
-use measurements::MeasurementsCollection;
+use measurements::{ExportFormat, MeasurementsCollection};
 use orchestrator::Orchestrator;
 use protocol::{
     target::{ProtocolClientParameters, ProtocolNodeParameters, TargetProtocol},
@@ -21,6 +22,7 @@ use testbed::Testbed;
 
 mod benchmark;
 mod client;
+mod compare;
 mod display;
 mod error;
 mod faults;
@@ -98,6 +100,41 @@ pub enum Operation {
         #[clap(long, value_name = "FILE")]
         path: PathBuf,
     },
+    /// Export the latency histograms and throughput series of the specified measurements
+    /// collection.
+    Export {
+        /// The path to the measurements file.
+        #[clap(long, value_name = "FILE")]
+        path: PathBuf,
+
+        /// The format of the exported files.
+        #[clap(long, value_enum, default_value_t = ExportFormat::Json)]
+        format: ExportFormat,
+
+        /// The directory where to write the exported files.
+        #[clap(long, value_name = "DIR", default_value = ".")]
+        output_dir: PathBuf,
+    },
+    /// Compare two sets of benchmark results and exit with an error if the candidate regressed.
+    /// Each set is either a measurements file or a directory of measurements files; benchmarks
+    /// are matched by their parameters.
+    Compare {
+        /// The measurements of the baseline run.
+        #[clap(long, value_name = "PATH")]
+        baseline: PathBuf,
+
+        /// The measurements of the candidate run.
+        #[clap(long, value_name = "PATH")]
+        candidate: PathBuf,
+
+        /// The maximum tolerated increase of the average latency (in percent) of a workload.
+        #[clap(long, value_name = "PERCENT", default_value_t = 10.0)]
+        max_latency_increase: f64,
+
+        /// The maximum tolerated decrease of the average throughput (in percent) of a workload.
+        #[clap(long, value_name = "PERCENT", default_value_t = 10.0)]
+        max_throughput_decrease: f64,
+    },
 }
 
 /// The action to perform on the testbed.
@@ -139,6 +176,42 @@ async fn main() -> eyre::Result<()> {
     color_eyre::install()?;
     let opts: Opts = Opts::parse();
 
+    // Process the results of previous runs (without loading the testbed).
+    match &opts.operation {
+        Operation::Export {
+            path,
+            format,
+            output_dir,
+        } => {
+            let files = MeasurementsCollection::load(path)?
+                .export()
+                .write(output_dir, *format)
+                .wrap_err("Failed to export measurements")?;
+            for file in files {
+                display::config("Exported", file.display());
+            }
+            return Ok(());
+        }
+        Operation::Compare {
+            baseline,
+            candidate,
+            max_latency_increase,
+            max_throughput_decrease,
+        } => {
+            let thresholds = RegressionThresholds {
+                max_latency_increase: *max_latency_increase,
+                max_throughput_decrease: *max_throughput_decrease,
+            };
+            let regressions = compare::compare_result_sets(baseline, candidate, &thresholds)
+                .wrap_err("Failed to load benchmark results")?;
+            if regressions > 0 {
+                eyre::bail!("Found {regressions} performance regression(s)");
+            }
+            return Ok(());
+        }
+        _ => (),
+    }
+
     // Load the settings files.
     let settings = Settings::load(&opts.settings_path).wrap_err("Failed to load settings")?;
 
@@ -157,6 +230,14 @@ async fn main() -> eyre::Result<()> {
                 .wrap_err("Failed to load cloud provider's token")?;
             let client = VultrClient::new(token, settings.clone());
 
+            // Execute the command.
+            run(settings, client, opts).await
+        }
+        CloudProvider::Local => {
+            // Create the client managing the local instances.
+            let client =
+                LocalClient::new(settings.clone()).wrap_err("Failed to create local testbed")?;
+
             // Execute the command.
             run(settings, client, opts).await
         }
@@ -212,7 +293,8 @@ async fn run<C: ServerProviderClient>(
             let private_key_file = settings.ssh_private_key_file.clone();
             let ssh_manager = SshConnectionManager::new(username.into(), private_key_file)
                 .with_timeout(settings.ssh_timeout)
-                .with_retries(settings.ssh_retries);
+                .with_retries(settings.ssh_retries)
+                .with_local_executor(testbed.local_executor());
 
             let instances = testbed.instances();
 
@@ -259,6 +341,9 @@ async fn run<C: ServerProviderClient>(
 
         // Print a summary of the specified measurements collection.
         Operation::Summarize { path } => MeasurementsCollection::load(path)?.display_summary(),
+
+        // Processed before loading the testbed.
+        Operation::Export { .. } | Operation::Compare { .. } => (),
     }
     Ok(())
 }
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     fmt::Debug,
     fs,
     io::BufRead,
@@ -137,12 +137,204 @@ impl Measurement {
 /// The identifier of the scrapers collecting the prometheus metrics.
 type ScraperId = usize;
 
+/// Summary statistics of a set of samples.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SampleStatistics {
+    /// The mean of the samples.
+    pub mean: f64,
+    /// The (unbiased) variance of the samples.
+    pub variance: f64,
+    /// The number of samples.
+    pub count: usize,
+}
+
+impl SampleStatistics {
+    /// Compute the statistics of the specified samples.
+    pub fn from_samples(samples: &[f64]) -> Self {
+        let count = samples.len();
+        if count == 0 {
+            return Self::default();
+        }
+        let mean = samples.iter().sum::<f64>() / count as f64;
+        let variance = if count > 1 {
+            samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (count - 1) as f64
+        } else {
+            0.0
+        };
+        Self {
+            mean,
+            variance,
+            count,
+        }
+    }
+
+    /// Compute the statistics from the count, sum, and sum of squares of the samples.
+    pub fn from_sums(count: usize, sum: f64, squared_sum: f64) -> Self {
+        if count == 0 {
+            return Self::default();
+        }
+        let mean = sum / count as f64;
+        let variance = if count > 1 {
+            ((squared_sum - count as f64 * mean.powi(2)) / (count - 1) as f64).max(0.0)
+        } else {
+            0.0
+        };
+        Self {
+            mean,
+            variance,
+            count,
+        }
+    }
+}
+
+/// The format in which to export measurements.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportFormat {
+    /// A single json file.
+    Json,
+    /// One csv file for the latency histograms and one for the throughput series.
+    Csv,
+}
+
+/// A bucket of an exported latency histogram.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct HistogramBucket {
+    /// The upper bound of the bucket (in seconds).
+    pub le: String,
+    /// The number of transactions with a latency lower than the upper bound.
+    pub count: usize,
+}
+
+/// A data point of an exported throughput series.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct SeriesPoint {
+    /// The scraper that collected the data point.
+    pub scraper: ScraperId,
+    /// Duration since the beginning of the benchmark (in seconds).
+    pub timestamp_s: u64,
+    /// Total number of finalized transactions.
+    pub count: usize,
+    /// The throughput since the previous data point (in tx/s).
+    pub tps: f64,
+    /// The average latency of all finalized transactions (in milliseconds).
+    pub average_latency_ms: f64,
+    /// The standard deviation of the latency of all finalized transactions (in milliseconds).
+    pub stdev_latency_ms: f64,
+}
+
+/// The exported measurements of a workload.
+#[derive(Serialize, Debug, Clone)]
+pub struct WorkloadExport {
+    /// The label of the workload.
+    pub label: Label,
+    /// The latency histogram, aggregated over all scrapers.
+    pub histogram: Vec<HistogramBucket>,
+    /// The throughput and latency series of each scraper.
+    pub series: Vec<SeriesPoint>,
+}
+
+/// The exported measurements of a benchmark run.
+#[derive(Serialize, Debug, Clone)]
+pub struct MeasurementsExport {
+    /// The benchmark parameters of the run.
+    pub benchmark: String,
+    /// The exported measurements of each workload.
+    pub workloads: Vec<WorkloadExport>,
+}
+
+impl MeasurementsExport {
+    /// Write the export to the specified directory. Returns the paths of the written files.
+    pub fn write<P: AsRef<Path>>(
+        &self,
+        directory: P,
+        format: ExportFormat,
+    ) -> Result<Vec<PathBuf>, std::io::Error> {
+        let directory = directory.as_ref();
+        fs::create_dir_all(directory)?;
+        match format {
+            ExportFormat::Json => {
+                let path = directory.join(format!("export-{}.json", self.benchmark));
+                fs::write(&path, serde_json::to_string_pretty(self)?)?;
+                Ok(vec![path])
+            }
+            ExportFormat::Csv => {
+                let histograms = directory.join(format!("histograms-{}.csv", self.benchmark));
+                fs::write(&histograms, self.histograms_csv())?;
+                let series = directory.join(format!("series-{}.csv", self.benchmark));
+                fs::write(&series, self.series_csv())?;
+                Ok(vec![histograms, series])
+            }
+        }
+    }
+
+    /// The latency histograms of all workloads in csv format.
+    fn histograms_csv(&self) -> String {
+        let mut csv = String::from("workload,le,count\n");
+        for workload in &self.workloads {
+            for bucket in &workload.histogram {
+                csv.push_str(&format!(
+                    "{},{},{}\n",
+                    csv_field(&workload.label),
+                    bucket.le,
+                    bucket.count
+                ));
+            }
+        }
+        csv
+    }
+
+    /// The throughput series of all workloads in csv format.
+    fn series_csv(&self) -> String {
+        let mut csv = String::from(
+            "workload,scraper,timestamp_s,count,tps,average_latency_ms,stdev_latency_ms\n",
+        );
+        for workload in &self.workloads {
+            for point in &workload.series {
+                csv.push_str(&format!(
+                    "{},{},{},{},{:.3},{:.3},{:.3}\n",
+                    csv_field(&workload.label),
+                    point.scraper,
+                    point.timestamp_s,
+                    point.count,
+                    point.tps,
+                    point.average_latency_ms,
+                    point.stdev_latency_ms
+                ));
+            }
+        }
+        csv
+    }
+}
+
+/// Quote a csv field if needed.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// A fault injected into (or reverted from) the testbed during the benchmark.
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+pub struct FaultEvent {
+    /// Duration since the beginning of the benchmark.
+    pub timestamp: Duration,
+    /// The description of the event.
+    pub description: String,
+    /// The indices of the affected nodes in the committee.
+    pub nodes: Vec<usize>,
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct MeasurementsCollection {
     /// The benchmark parameters of the current run.
     pub parameters: BenchmarkParameters,
     /// The data collected by each scraper.
     pub data: HashMap<Label, HashMap<ScraperId, Vec<Measurement>>>,
+    /// The faults injected into the testbed during the benchmark.
+    #[serde(default)]
+    pub faults: Vec<FaultEvent>,
 }
 
 impl MeasurementsCollection {
@@ -154,6 +346,7 @@ impl MeasurementsCollection {
         Self {
             parameters,
             data: HashMap::new(),
+            faults: Vec::new(),
         }
     }
 
@@ -174,6 +367,15 @@ impl MeasurementsCollection {
             .push(measurement);
     }
 
+    /// Record a fault injected into (or reverted from) the testbed.
+    pub fn add_fault_event(&mut self, timestamp: Duration, description: String, nodes: Vec<usize>) {
+        self.faults.push(FaultEvent {
+            timestamp,
+            description,
+            nodes,
+        });
+    }
+
     /// Get all measurements associated with the specified label.
     pub fn all_measurements(&self, label: &Label) -> Vec<Vec<Measurement>> {
         self.data
@@ -233,6 +435,123 @@ impl MeasurementsCollection {
         self.max_result(label, |x| x.stdev_latency())
     }
 
+    /// Compute the latency statistics (in seconds) of all transactions of the specified
+    /// workload, aggregated over all scrapers.
+    pub fn latency_statistics(&self, label: &Label) -> SampleStatistics {
+        let last_data_points: Vec<_> = self
+            .all_measurements(label)
+            .into_iter()
+            .filter_map(|x| x.last().cloned())
+            .collect();
+        SampleStatistics::from_sums(
+            last_data_points.iter().map(|x| x.count).sum(),
+            last_data_points.iter().map(|x| x.sum.as_secs_f64()).sum(),
+            last_data_points.iter().map(|x| x.squared_sum).sum(),
+        )
+    }
+
+    /// Compute the throughput (in tx/s) of the specified workload over each scrape interval,
+    /// summed over all scrapers.
+    ///
+    /// The measurements of the scrapers are aligned by their timestamp rather than by their
+    /// position, as scrapers may start late or miss scrapes (e.g., while their node is crashed).
+    pub fn throughput_series(&self, label: &Label) -> Vec<f64> {
+        let interval = self.parameters.settings.scrape_interval.as_secs().max(1);
+        let mut series: BTreeMap<u64, f64> = BTreeMap::new();
+        for measurements in self.all_measurements(label) {
+            // The throughput of the scraper in each interval, and the number of measurements.
+            let mut scraper_series: BTreeMap<u64, (f64, usize)> = BTreeMap::new();
+            for window in measurements.windows(2) {
+                let elapsed = window[1].timestamp.saturating_sub(window[0].timestamp);
+                if elapsed.is_zero() {
+                    continue;
+                }
+                let tps =
+                    window[1].count.saturating_sub(window[0].count) as f64 / elapsed.as_secs_f64();
+                let entry = scraper_series
+                    .entry(window[1].timestamp.as_secs() / interval)
+                    .or_default();
+                entry.0 += tps;
+                entry.1 += 1;
+            }
+            for (slot, (tps, count)) in scraper_series {
+                *series.entry(slot).or_default() += tps / count as f64;
+            }
+        }
+        series.into_values().collect()
+    }
+
+    /// Export the full latency histograms and throughput series of all workloads.
+    pub fn export(&self) -> MeasurementsExport {
+        let mut labels: Vec<_> = self.labels().cloned().collect();
+        labels.sort();
+        let workloads = labels
+            .into_iter()
+            .map(|label| {
+                let mut scrapers: Vec<_> = self.data[&label].iter().collect();
+                scrapers.sort_by_key(|(scraper, _)| **scraper);
+
+                // Sum the latest (cumulative) histogram of each scraper.
+                let mut buckets: HashMap<BucketId, usize> = HashMap::new();
+                for (_, measurements) in &scrapers {
+                    for (bucket, count) in measurements
+                        .last()
+                        .map(|x| &x.buckets)
+                        .into_iter()
+                        .flatten()
+                    {
+                        *buckets.entry(bucket.clone()).or_default() += count;
+                    }
+                }
+                let mut histogram: Vec<_> = buckets
+                    .into_iter()
+                    .map(|(le, count)| HistogramBucket { le, count })
+                    .collect();
+                histogram.sort_by(|a, b| {
+                    let bound = |x: &HistogramBucket| x.le.parse::<f64>().unwrap_or(f64::INFINITY);
+                    bound(a).total_cmp(&bound(b))
+                });
+
+                let mut series = Vec::new();
+                for (scraper, measurements) in scrapers {
+                    let mut previous: Option<&Measurement> = None;
+                    for measurement in measurements {
+                        let tps = previous
+                            .map(|previous| {
+                                let elapsed =
+                                    measurement.timestamp.saturating_sub(previous.timestamp);
+                                measurement.count.saturating_sub(previous.count) as f64
+                                    / elapsed.as_secs_f64()
+                            })
+                            .filter(|tps| tps.is_finite())
+                            .unwrap_or_default();
+                        series.push(SeriesPoint {
+                            scraper: *scraper,
+                            timestamp_s: measurement.timestamp.as_secs(),
+                            count: measurement.count,
+                            tps,
+                            average_latency_ms: measurement.average_latency().as_secs_f64()
+                                * 1000.0,
+                            stdev_latency_ms: measurement.stdev_latency().as_secs_f64() * 1000.0,
+                        });
+                        previous = Some(measurement);
+                    }
+                }
+
+                WorkloadExport {
+                    label,
+                    histogram,
+                    series,
+                }
+            })
+            .collect();
+
+        MeasurementsExport {
+            benchmark: format!("{:?}", self.parameters),
+            workloads,
+        }
+    }
+
     /// Save the collection of measurements as a json file.
     pub fn save<P: AsRef<Path>>(&self, path: P) {
         let json = serde_json::to_string_pretty(self).expect("Cannot serialize metrics");
@@ -253,6 +572,7 @@ impl MeasurementsCollection {
         table.add_row(row![bH2->""]);
         table.add_row(row![b->"Nodes:", self.parameters.nodes]);
         table.add_row(row![b->"Faults:", self.parameters.settings.faults]);
+        table.add_row(row![b->"Fault events:", self.faults.len()]);
         table.add_row(row![b->"Load:", format!("{} ops/min", self.parameters.load)]);
         table.add_row(row![b->"Duration:", format!("{} s", duration.as_secs())]);
 
@@ -270,6 +590,22 @@ impl MeasurementsCollection {
             table.add_row(row![b->"Latency (stdev):", format!("{} ms", stdev_latency.as_millis())]);
         }
 
+        if !self.faults.is_empty() {
+            table.add_row(row![bH2->""]);
+            for event in &self.faults {
+                let nodes = event
+                    .nodes
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                table.add_row(row![
+                    b->format!("t = {} s:", event.timestamp.as_secs()),
+                    format!("{} (nodes: {nodes})", event.description)
+                ]);
+            }
+        }
+
         display::newline();
         table.printstd();
         display::newline();
@@ -280,7 +616,7 @@ impl MeasurementsCollection {
 mod test {
     use std::{collections::HashMap, time::Duration};
 
-    use super::{BenchmarkParameters, Measurement, MeasurementsCollection};
+    use super::{BenchmarkParameters, ExportFormat, Measurement, MeasurementsCollection};
     use crate::protocol::test_protocol_metrics::TestProtocolMetrics;
 
     #[test]
@@ -422,4 +758,103 @@ mod test {
             .unwrap();
         assert_eq!(shared_workload_data_points.len(), 1);
     }
+
+    fn measurement(timestamp: u64, count: usize, buckets: &[(&str, usize)]) -> Measurement {
+        Measurement {
+            timestamp: Duration::from_secs(timestamp),
+            buckets: buckets.iter().map(|(id, x)| (id.to_string(), *x)).collect(),
+            sum: Duration::from_secs(count as u64),
+            count,
+            squared_sum: count as f64,
+        }
+    }
+
+    #[test]
+    fn throughput_series_aligns_scrapers_by_timestamp() {
+        let mut aggregator = MeasurementsCollection::new(BenchmarkParameters::new_for_tests());
+        let interval = aggregator.parameters.settings.scrape_interval.as_secs();
+        for (i, count) in [0, 150, 300].into_iter().enumerate() {
+            aggregator.add(
+                0,
+                "write".into(),
+                measurement(i as u64 * interval, count, &[]),
+            );
+        }
+        // The second scraper misses the first scrape.
+        for (i, count) in [0, 300].into_iter().enumerate() {
+            aggregator.add(
+                1,
+                "write".into(),
+                measurement((i as u64 + 1) * interval, count, &[]),
+            );
+        }
+
+        let tps = 150.0 / interval as f64;
+        assert_eq!(
+            aggregator.throughput_series(&"write".into()),
+            vec![tps, 3.0 * tps]
+        );
+    }
+
+    #[test]
+    fn export_histograms_and_series() {
+        let mut aggregator = MeasurementsCollection::new(BenchmarkParameters::new_for_tests());
+        for scraper in 0..2 {
+            aggregator.add(scraper, "write".into(), measurement(10, 100, &[]));
+            aggregator.add(
+                scraper,
+                "write".into(),
+                measurement(20, 300, &[("0.5", 100), ("inf", 300), ("10", 250)]),
+            );
+        }
+
+        assert_eq!(aggregator.throughput_series(&"write".into()), vec![40.0]);
+        let statistics = aggregator.latency_statistics(&"write".into());
+        assert_eq!(statistics.count, 600);
+        assert_eq!(statistics.mean, 1.0);
+        assert_eq!(statistics.variance, 0.0);
+
+        let export = aggregator.export();
+        assert_eq!(export.workloads.len(), 1);
+        let workload = &export.workloads[0];
+        let bounds: Vec<_> = workload.histogram.iter().map(|x| x.le.as_str()).collect();
+        assert_eq!(bounds, vec!["0.5", "10", "inf"]);
+        assert_eq!(workload.histogram[2].count, 600);
+        assert_eq!(workload.series.len(), 4);
+        assert_eq!(workload.series[0].tps, 0.0);
+        assert_eq!(workload.series[1].tps, 20.0);
+
+        let directory = tempfile::tempdir().unwrap();
+        let files = export.write(directory.path(), ExportFormat::Csv).unwrap();
+        assert_eq!(files.len(), 2);
+        let series = std::fs::read_to_string(&files[1]).unwrap();
+        assert_eq!(series.lines().count(), 5);
+        assert!(series
+            .lines()
+            .nth(2)
+            .unwrap()
+            .starts_with("write,0,20,300,20.000,"));
+    }
+
+    #[test]
+    fn fault_events_roundtrip() {
+        let mut aggregator = MeasurementsCollection::new(BenchmarkParameters::new_for_tests());
+        aggregator.add_fault_event(
+            Duration::from_secs(30),
+            "network partition injected on 2 node(s)".into(),
+            vec![0, 1],
+        );
+
+        let json = serde_json::to_string(&aggregator).unwrap();
+        let loaded: MeasurementsCollection = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.faults.len(), 1);
+        assert_eq!(loaded.faults[0].timestamp, Duration::from_secs(30));
+        assert_eq!(loaded.faults[0].nodes, vec![0, 1]);
+
+        // Measurements saved before fault events were recorded can still be loaded.
+        let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        value.as_object_mut().unwrap().remove("faults");
+        let loaded: MeasurementsCollection = serde_json::from_value(value).unwrap();
+        assert!(loaded.faults.is_empty());
+    }
 }
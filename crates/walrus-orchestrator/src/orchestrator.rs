@@ -5,6 +5,7 @@ use std::{
     collections::{HashMap, VecDeque},
     fs,
     path::PathBuf,
+    time::Duration,
 };
 
 use tokio::time::{self, Instant};
@@ -15,7 +16,13 @@ use crate::{
     client::Instance,
     display,
     error::{TestbedError, TestbedResult},
-    faults::CrashRecoverySchedule,
+    faults::{
+        CrashRecoverySchedule,
+        FaultContext,
+        FaultInjectionAction,
+        FaultInjectionKind,
+        FaultInjectionSchedule,
+    },
     logs::LogsAnalyzer,
     measurements::{Measurement, MeasurementsCollection},
     monitor::Monitor,
@@ -166,6 +173,23 @@ impl<P: ProtocolCommands + ProtocolMetrics> Orchestrator<P> {
 
         let working_dir = self.settings.working_dir.display();
         let url = &self.settings.repository.url;
+
+        // Local instances share the system packages and the rust toolchain of the host, so only
+        // the working directory and the repository need to be set up.
+        if self.ssh_manager.is_local() {
+            let command = [
+                format!("mkdir -p {working_dir}"),
+                format!("(git clone {url} || true)"),
+            ]
+            .join(" && ");
+            let active = self.instances.iter().filter(|x| x.is_active()).cloned();
+            self.ssh_manager
+                .execute(active, command, CommandContext::default())
+                .await?;
+            display::done();
+            return Ok(());
+        }
+
         let basic_commands = [
             "sudo apt-get update",
             "sudo apt-get -y upgrade",
@@ -299,6 +323,10 @@ impl<P: ProtocolCommands + ProtocolMetrics> Orchestrator<P> {
         if delete_logs {
             command.push("(rm -rf ~/*log* || true)".into());
         }
+        // Revert all injected faults (in case the previous run was interrupted).
+        for (id, fault) in self.settings.injected_faults.iter().enumerate() {
+            command.push(fault.revert_command(id, &self.settings.working_dir));
+        }
         let command = command.join(" ; ");
 
         // Execute the deletion on all machines.
@@ -358,6 +386,59 @@ impl<P: ProtocolCommands + ProtocolMetrics> Orchestrator<P> {
         Ok(())
     }
 
+    /// Save the measurements collected so far in the results directory.
+    fn save_measurements(&self, aggregator: &MeasurementsCollection) {
+        let results_directory = &self.settings.results_dir;
+        let commit = &self.settings.repository.commit;
+        let path: PathBuf = results_directory.join(format!("results-{commit}"));
+        fs::create_dir_all(&path).expect("Failed to create log directory");
+        aggregator.save(path);
+    }
+
+    /// Inject or revert faults on the nodes and record them in the measurements.
+    async fn apply_injected_faults(
+        &self,
+        actions: Vec<FaultInjectionAction>,
+        nodes: &[Instance],
+        parameters: &BenchmarkParameters,
+        aggregator: &mut MeasurementsCollection,
+        elapsed: Duration,
+    ) -> TestbedResult<()> {
+        for action in actions {
+            let affected: Vec<_> = action
+                .nodes
+                .iter()
+                .map(|index| nodes[*index].clone())
+                .collect();
+            let context = || FaultContext {
+                other_nodes: nodes
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| !action.fault.nodes.contains(i))
+                    .map(|(_, instance)| instance.main_ip)
+                    .collect(),
+                rpc_hosts: self.protocol_commands.sui_rpc_hosts(parameters),
+                working_dir: &self.settings.working_dir,
+                node_process_pattern: self.protocol_commands.node_process_pattern(),
+            };
+            let command = match action.kind {
+                FaultInjectionKind::Inject => action.fault.inject_command(action.id, &context()),
+                FaultInjectionKind::Revert => action
+                    .fault
+                    .revert_command(action.id, &self.settings.working_dir),
+                FaultInjectionKind::Reapply => action.fault.reapply_command(action.id, &context()),
+            };
+            self.ssh_manager
+                .execute(affected, command, CommandContext::default())
+                .await?;
+
+            display::newline();
+            display::config("Testbed update", &action);
+            aggregator.add_fault_event(elapsed, action.to_string(), action.nodes);
+        }
+        Ok(())
+    }
+
     /// Deploy the nodes.
     pub async fn run_nodes(&self, parameters: &BenchmarkParameters) -> TestbedResult<()> {
         display::action("Deploying validators");
@@ -436,6 +517,13 @@ impl<P: ProtocolCommands + ProtocolMetrics> Orchestrator<P> {
         let mut faults_interval = time::interval(self.settings.faults.crash_interval());
         faults_interval.tick().await; // The first tick returns immediately.
 
+        let mut injection_schedule =
+            FaultInjectionSchedule::new(parameters.settings.injected_faults.clone());
+        injection_schedule
+            .check(nodes.len())
+            .map_err(TestbedError::InvalidFaultSchedule)?;
+        let mut injection_interval = time::interval(Duration::from_secs(1));
+
         let start = Instant::now();
         loop {
             tokio::select! {
@@ -457,11 +545,7 @@ impl<P: ProtocolCommands + ProtocolMetrics> Orchestrator<P> {
                         }
                     }
 
-                    let results_directory = &self.settings.results_dir;
-                    let commit = &self.settings.repository.commit;
-                    let path: PathBuf = results_directory.join(format!("results-{commit}"));
-                    fs::create_dir_all(&path).expect("Failed to create log directory");
-                    aggregator.save(path);
+                    self.save_measurements(&aggregator);
 
                     let benchmark_duration = parameters.settings.benchmark_duration.as_secs();
                     if elapsed > benchmark_duration {
@@ -482,13 +566,71 @@ impl<P: ProtocolCommands + ProtocolMetrics> Orchestrator<P> {
                         self.boot_nodes(action.boot.clone(), parameters).await?;
                     }
                     if !action.kill.is_empty() || !action.boot.is_empty() {
+                        let elapsed = start.elapsed();
+                        let indices = |instances: &[Instance]| {
+                            instances
+                                .iter()
+                                .filter_map(|instance| nodes.iter().position(|x| x == instance))
+                                .collect::<Vec<_>>()
+                        };
+                        if !action.kill.is_empty() {
+                            aggregator.add_fault_event(
+                                elapsed,
+                                "node crash".into(),
+                                indices(&action.kill),
+                            );
+                        }
+                        if !action.boot.is_empty() {
+                            aggregator.add_fault_event(
+                                elapsed,
+                                "node recovery".into(),
+                                indices(&action.boot),
+                            );
+                        }
                         display::newline();
                         display::config("Testbed update", action);
+                        // Some faults (e.g., slow disks) only affect the processes running when
+                        // they are injected, so re-apply the active faults to the rebooted nodes.
+                        let reapply = injection_schedule.reapply(&indices(&action.boot));
+                        self.apply_injected_faults(
+                            reapply,
+                            &nodes,
+                            parameters,
+                            &mut aggregator,
+                            elapsed,
+                        )
+                        .await?;
                     }
                 }
+
+                // Inject and revert faults according to the input schedule.
+                now = injection_interval.tick(), if !injection_schedule.is_empty() => {
+                    let elapsed = now.duration_since(start);
+                    let actions = injection_schedule.update(elapsed);
+                    self.apply_injected_faults(
+                        actions,
+                        &nodes,
+                        parameters,
+                        &mut aggregator,
+                        elapsed,
+                    )
+                    .await?;
+                }
             }
         }
 
+        // Revert the faults that are still injected.
+        let actions = injection_schedule.revert_all();
+        self.apply_injected_faults(
+            actions,
+            &nodes,
+            parameters,
+            &mut aggregator,
+            start.elapsed(),
+        )
+        .await?;
+        self.save_measurements(&aggregator);
+
         display::done();
         Ok(aggregator)
     }
@@ -520,8 +662,7 @@ impl<P: ProtocolCommands + ProtocolMetrics> Orchestrator<P> {
         for (i, instance) in clients.iter().enumerate() {
             display::status(format!("{}/{}", i + 1, clients.len()));
 
-            let connection = self.ssh_manager.connect(instance.ssh_address()).await?;
-            let client_log_content = connection.download("client.log")?;
+            let client_log_content = self.ssh_manager.download(instance, "client.log").await?;
 
             let client_log_file = [path.clone(), format!("client-{i}.log").into()]
                 .iter()
@@ -539,8 +680,7 @@ impl<P: ProtocolCommands + ProtocolMetrics> Orchestrator<P> {
         for (i, instance) in nodes.iter().enumerate() {
             display::status(format!("{}/{}", i + 1, nodes.len()));
 
-            let connection = self.ssh_manager.connect(instance.ssh_address()).await?;
-            let node_log_content = connection.download("node.log")?;
+            let node_log_content = self.ssh_manager.download(instance, "node.log").await?;
 
             let node_log_file = [path.clone(), format!("node-{i}.log").into()]
                 .iter()
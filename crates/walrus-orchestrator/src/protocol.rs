@@ -65,6 +65,13 @@ pub trait ProtocolCommands {
     ) -> Vec<(Instance, String)>
     where
         I: IntoIterator<Item = Instance>;
+
+    /// A pattern (for `pgrep -f`) matching the node processes. The pattern must not match its
+    /// own command line.
+    fn node_process_pattern(&self) -> &'static str;
+
+    /// The hosts of the Sui RPC endpoints used by the nodes (used to simulate RPC outages).
+    fn sui_rpc_hosts(&self, parameters: &BenchmarkParameters) -> Vec<String>;
 }
 
 /// The names of the minimum metrics exposed by the protocol that are required to
@@ -8,6 +8,7 @@ use std::{
     time::Duration,
 };
 
+use reqwest::Url;
 use serde::{Deserialize, Serialize};
 use walrus_core::ShardIndex;
 use walrus_service::{
@@ -290,6 +291,18 @@ impl ProtocolCommands for TargetProtocol {
             })
             .collect()
     }
+
+    fn node_process_pattern(&self) -> &'static str {
+        "[w]alrus-node run"
+    }
+
+    fn sui_rpc_hosts(&self, parameters: &BenchmarkParameters) -> Vec<String> {
+        Url::parse(&parameters.node_parameters.sui_network.env().rpc)
+            .ok()
+            .and_then(|url| url.host_str().map(ToString::to_string))
+            .into_iter()
+            .collect()
+    }
 }
 
 impl ProtocolMetrics for TargetProtocol {
@@ -16,7 +16,7 @@ use serde_with::{serde_as, DisplayFromStr, DurationSeconds};
 use crate::{
     client::Instance,
     error::{SettingsError, SettingsResult},
-    faults::FaultsType,
+    faults::{FaultsType, ScheduledFault},
 };
 
 /// The git repository holding the codebase.
@@ -62,6 +62,33 @@ pub enum CloudProvider {
     Aws,
     #[serde(alias = "vultr")]
     Vultr,
+    #[serde(alias = "local")]
+    Local,
+}
+
+/// The settings of the local testbed, used with the `local` cloud provider.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LocalSettings {
+    /// The directory (on the local machine) holding the home directories of the instances.
+    #[serde(
+        default = "defaults::default_local_root_dir",
+        deserialize_with = "walrus_utils::config::resolve_home_dir"
+    )]
+    pub root_dir: PathBuf,
+    /// Whether to run each instance in its own network namespace. This requires Linux and sudo
+    /// privileges. Otherwise, the instances run as plain processes bound to distinct loopback
+    /// addresses.
+    #[serde(default = "defaults::default_network_namespaces")]
+    pub network_namespaces: bool,
+}
+
+impl Default for LocalSettings {
+    fn default() -> Self {
+        Self {
+            root_dir: defaults::default_local_root_dir(),
+            network_namespaces: defaults::default_network_namespaces(),
+        }
+    }
 }
 
 /// The testbed settings. Those are typically specified in a file.
@@ -107,6 +134,10 @@ pub struct Settings {
     /// The default faults type to apply to the testbed's nodes.
     #[serde(default = "defaults::default_faults_type")]
     pub faults: FaultsType,
+    /// Additional faults (network partitions, latency, disk and RPC failures) injected into
+    /// subsets of the nodes at given times of the benchmark.
+    #[serde(default)]
+    pub injected_faults: Vec<ScheduledFault>,
     /// The working directory on the remote instance (containing all configuration files).
     #[serde(default = "defaults::default_working_dir")]
     pub working_dir: PathBuf,
@@ -140,6 +171,9 @@ pub struct Settings {
     /// The number of times the orchestrator should retry an ssh command.
     #[serde(default = "defaults::default_ssh_retries")]
     pub ssh_retries: usize,
+    /// The settings of the local testbed (only used by the `local` cloud provider).
+    #[serde(default)]
+    pub local: LocalSettings,
 }
 
 mod defaults {
@@ -194,6 +228,14 @@ mod defaults {
     pub fn default_ssh_retries() -> usize {
         3
     }
+
+    pub fn default_local_root_dir() -> PathBuf {
+        ["./", "local-testbed"].iter().collect()
+    }
+
+    pub fn default_network_namespaces() -> bool {
+        true
+    }
 }
 
 impl Settings {
@@ -14,7 +14,7 @@ use tokio::{net::TcpStream, runtime::Handle, task::JoinHandle, time::sleep};
 use walrus_core::ensure;
 
 use crate::{
-    client::Instance,
+    client::{local::LocalExecutor, Instance},
     error::{SshError, SshResult},
 };
 
@@ -103,6 +103,8 @@ pub struct SshConnectionManager {
     timeout: Option<Duration>,
     /// The number of retries before giving up to execute the command.
     retries: usize,
+    /// If set, commands run as local processes through this executor instead of over ssh.
+    local_executor: Option<LocalExecutor>,
 }
 
 impl SshConnectionManager {
@@ -116,9 +118,21 @@ impl SshConnectionManager {
             private_key_file,
             timeout: None,
             retries: 0,
+            local_executor: None,
         }
     }
 
+    /// Run the commands as local processes with the specified executor instead of over ssh.
+    pub fn with_local_executor(mut self, local_executor: Option<LocalExecutor>) -> Self {
+        self.local_executor = local_executor;
+        self
+    }
+
+    /// Return whether the commands run as local processes rather than on remote machines.
+    pub fn is_local(&self) -> bool {
+        self.local_executor.is_some()
+    }
+
     /// Set a timeout duration for the connections.
     pub fn with_timeout(mut self, timeout: Duration) -> Self {
         self.timeout = Some(timeout);
@@ -196,6 +210,9 @@ impl SshConnectionManager {
                 let context = context.clone();
 
                 tokio::spawn(async move {
+                    if let Some(executor) = &ssh_manager.local_executor {
+                        return executor.execute(&instance, context.apply(command)).await;
+                    }
                     let connection = ssh_manager.connect(instance.ssh_address()).await?;
                     // SshConnection::execute is a blocking call, needs to go to blocking pool
                     Handle::current()
@@ -255,6 +272,18 @@ impl SshConnectionManager {
         }
     }
 
+    /// Download a file from the home directory of the instance.
+    pub async fn download<P: AsRef<Path>>(
+        &self,
+        instance: &Instance,
+        path: P,
+    ) -> SshResult<String> {
+        match &self.local_executor {
+            Some(executor) => executor.download(instance, path),
+            None => self.connect(instance.ssh_address()).await?.download(path),
+        }
+    }
+
     /// Kill a command running in the background of the specified instances.
     pub async fn kill<I>(&self, instances: I, command_id: &str) -> SshResult<()>
     where
@@ -9,7 +9,7 @@ use tokio::time::{self, Instant};
 
 use super::client::Instance;
 use crate::{
-    client::ServerProviderClient,
+    client::{local::LocalExecutor, ServerProviderClient},
     display,
     error::{TestbedError, TestbedResult},
     settings::Settings,
@@ -29,8 +29,10 @@ pub struct Testbed<C> {
 impl<C: ServerProviderClient> Testbed<C> {
     /// Create a new testbed instance with the specified settings and client.
     pub async fn new(settings: Settings, client: C) -> TestbedResult<Self> {
-        let public_key = settings.load_ssh_public_key()?;
-        client.register_ssh_public_key(public_key).await?;
+        if client.local_executor().is_none() {
+            let public_key = settings.load_ssh_public_key()?;
+            client.register_ssh_public_key(public_key).await?;
+        }
         let instances = client.list_instances().await?;
 
         Ok(Self {
@@ -45,6 +47,11 @@ impl<C: ServerProviderClient> Testbed<C> {
         C::USERNAME
     }
 
+    /// Return the executor running commands on the instances if they are local processes.
+    pub fn local_executor(&self) -> Option<LocalExecutor> {
+        self.client.local_executor()
+    }
+
     /// Return the list of instances of the testbed.
     pub fn instances(&self) -> Vec<Instance> {
         self.instances
@@ -95,10 +102,21 @@ impl<C: ServerProviderClient> Testbed<C> {
                 if j % 5 == 0 {
                     table.add_row(row![]);
                 }
-                let private_key_file = self.settings.ssh_private_key_file.display();
-                let username = C::USERNAME;
-                let ip = instance.main_ip;
-                let connect = format!("ssh -i {private_key_file} {username}@{ip}");
+                let connect = match self.client.local_executor() {
+                    Some(executor) => {
+                        format!(
+                            "{} ({})",
+                            executor.home_dir(instance).display(),
+                            instance.main_ip
+                        )
+                    }
+                    None => {
+                        let private_key_file = self.settings.ssh_private_key_file.display();
+                        let username = C::USERNAME;
+                        let ip = instance.main_ip;
+                        format!("ssh -i {private_key_file} {username}@{ip}")
+                    }
+                };
                 if !instance.is_terminated() {
                     if instance.is_active() {
                         table.add_row(row![bFg->format!("{j}"), connect]);
@@ -224,6 +242,10 @@ impl<C: ServerProviderClient> Testbed<C> {
     where
         I: Iterator<Item = &'a Instance> + Clone,
     {
+        // Local instances are ready as soon as they are created.
+        if self.client.local_executor().is_some() {
+            return Ok(());
+        }
         let instances_ids: Vec<_> = instances.map(|x| x.id.clone()).collect();
 
         let mut interval = time::interval(Duration::from_secs(5));
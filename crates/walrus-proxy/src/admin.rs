@@ -16,9 +16,15 @@ use tracing::Level;
 use crate::{
     config::RemoteWriteConfig,
     consumer::Label,
+    fanout::RemoteWriteFanout,
     handlers,
     histogram_relay::HistogramRelay,
-    middleware::{expect_content_length, expect_valid_recoverable_pubkey},
+    limits::IngestionLimiter,
+    middleware::{
+        enforce_ingestion_limits,
+        expect_content_length,
+        expect_valid_recoverable_pubkey,
+    },
     providers::WalrusNodeProvider,
     var,
 };
@@ -50,8 +56,9 @@ pub fn make_reqwest_client(settings: RemoteWriteConfig, user_agent: &str) -> Req
 /// build our axum app
 pub fn app(
     labels: Vec<Label>,
-    client: ReqwestClient,
+    fanout: RemoteWriteFanout,
     relay: HistogramRelay,
+    limiter: Arc<IngestionLimiter>,
     allower: Option<WalrusNodeProvider>,
 ) -> Router {
     // build our application with a route and our sender mpsc
@@ -63,7 +70,9 @@ pub fn app(
         )))
         .route_layer(middleware::from_fn(expect_content_length));
     if let Some(allower) = allower {
+        // layers run in reverse order, the peer must be known to enforce its limits
         router = router
+            .route_layer(middleware::from_fn(enforce_ingestion_limits))
             .route_layer(middleware::from_fn(expect_valid_recoverable_pubkey))
             .layer(Extension(Arc::new(allower)));
     }
@@ -76,8 +85,9 @@ pub fn app(
             20
         ))))
         .layer(Extension(relay))
+        .layer(Extension(limiter))
         .layer(Extension(labels))
-        .layer(Extension(client))
+        .layer(Extension(fanout))
         .layer(
             ServiceBuilder::new().layer(
                 TraceLayer::new_for_http()
@@ -0,0 +1,335 @@
+// Copyright (c) Walrus Foundation
+// SPDX-License-Identifier: Apache-2.0
+use std::{
+    collections::VecDeque,
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use tokio::sync::{Mutex as AsyncMutex, Notify};
+use tracing::warn;
+
+/// extension of the files holding buffered payloads
+const ENTRY_EXTENSION: &str = "snappy";
+/// extension of payloads that are being written
+const TMP_EXTENSION: &str = "tmp";
+
+/// BufferEntry describes a payload held in a DiskBuffer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BufferEntry {
+    /// position of the entry in the buffer
+    pub sequence: u64,
+    /// number of samples contained in the payload
+    pub samples: u64,
+    /// size of the payload in bytes
+    pub bytes: u64,
+}
+
+impl BufferEntry {
+    fn file_name(&self) -> String {
+        format!("{:020}-{}.{ENTRY_EXTENSION}", self.sequence, self.samples)
+    }
+
+    /// parse an entry from the name of its file, eg 00000000000000000042-500.snappy
+    fn from_path(path: &Path, bytes: u64) -> Option<Self> {
+        if path.extension()? != ENTRY_EXTENSION {
+            return None;
+        }
+        let (sequence, samples) = path.file_stem()?.to_str()?.split_once('-')?;
+        Some(Self {
+            sequence: sequence.parse().ok()?,
+            samples: samples.parse().ok()?,
+            bytes,
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+struct BufferState {
+    entries: VecDeque<BufferEntry>,
+    next_sequence: u64,
+    total_bytes: u64,
+}
+
+/// DiskBuffer is a bounded FIFO queue of payloads persisted in a directory,
+/// one file per payload.  Payloads survive restarts of the proxy. When the
+/// buffer exceeds its maximum size, the oldest payloads are evicted.
+///
+/// The file operations run on the blocking thread pool of tokio, and a payload
+/// is only added to the queue once its file and the directory are synced to
+/// disk.
+#[derive(Debug)]
+pub struct DiskBuffer {
+    directory: PathBuf,
+    max_bytes: u64,
+    state: Mutex<BufferState>,
+    /// serializes the writers so that payloads enter the queue in the order
+    /// of their sequence numbers
+    writer: AsyncMutex<()>,
+    notify: Notify,
+}
+
+impl DiskBuffer {
+    /// open the buffer in the given directory, creating it if needed and
+    /// recovering the payloads left by a previous run
+    pub fn open(directory: PathBuf, max_bytes: u64) -> io::Result<Self> {
+        fs::create_dir_all(&directory)?;
+        let mut entries = vec![];
+        for file in fs::read_dir(&directory)? {
+            let file = file?;
+            let path = file.path();
+            if path.extension().is_some_and(|ext| ext == TMP_EXTENSION) {
+                // a write that was interrupted, the payload was never acknowledged
+                fs::remove_file(&path)?;
+                continue;
+            }
+            match BufferEntry::from_path(&path, file.metadata()?.len()) {
+                Some(entry) => entries.push(entry),
+                None => warn!("ignoring unexpected file in remote write buffer: {path:?}"),
+            }
+        }
+        entries.sort_by_key(|entry| entry.sequence);
+
+        let state = BufferState {
+            next_sequence: entries.last().map_or(0, |entry| entry.sequence + 1),
+            total_bytes: entries.iter().map(|entry| entry.bytes).sum(),
+            entries: entries.into(),
+        };
+        let buffer = Self {
+            directory,
+            max_bytes,
+            state: Mutex::new(state),
+            writer: AsyncMutex::new(()),
+            notify: Notify::new(),
+        };
+        if !buffer.is_empty() {
+            buffer.notify.notify_one();
+        }
+        Ok(buffer)
+    }
+
+    fn path(&self, entry: &BufferEntry) -> PathBuf {
+        self.directory.join(entry.file_name())
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, BufferState> {
+        self.state
+            .lock()
+            .expect("buffer state mutex should not be poisoned")
+    }
+
+    /// number of payloads in the buffer
+    pub fn len(&self) -> usize {
+        self.state().entries.len()
+    }
+
+    /// is_empty returns true if the buffer holds no payloads
+    pub fn is_empty(&self) -> bool {
+        self.state().entries.is_empty()
+    }
+
+    /// total size of the payloads in the buffer in bytes
+    pub fn bytes(&self) -> u64 {
+        self.state().total_bytes
+    }
+
+    /// append a payload to the buffer once it is durably written to disk.
+    /// returns the entries that were evicted to stay within the maximum size
+    /// of the buffer, which may include the new payload if it is larger than
+    /// the buffer itself
+    pub async fn push(&self, payload: Vec<u8>, samples: u64) -> io::Result<Vec<BufferEntry>> {
+        let _writer = self.writer.lock().await;
+        let entry = BufferEntry {
+            sequence: self.state().next_sequence,
+            samples,
+            bytes: payload.len() as u64,
+        };
+        let directory = self.directory.clone();
+        let path = self.path(&entry);
+        spawn_blocking(move || write_durably(&directory, &path, &payload)).await?;
+
+        let mut evicted = vec![];
+        {
+            let mut state = self.state();
+            state.next_sequence += 1;
+            state.total_bytes += entry.bytes;
+            state.entries.push_back(entry);
+            while state.total_bytes > self.max_bytes {
+                let Some(oldest) = state.entries.pop_front() else {
+                    break;
+                };
+                state.total_bytes -= oldest.bytes;
+                evicted.push(oldest);
+            }
+        }
+        self.notify.notify_one();
+
+        let paths: Vec<_> = evicted.iter().map(|entry| self.path(entry)).collect();
+        spawn_blocking(move || paths.iter().try_for_each(|path| remove_file(path))).await?;
+        Ok(evicted)
+    }
+
+    /// the oldest entry of the buffer, if any
+    pub fn front(&self) -> Option<BufferEntry> {
+        self.state().entries.front().copied()
+    }
+
+    /// wait until the buffer is not empty and return its oldest entry. only a
+    /// single task should consume the buffer
+    pub async fn next(&self) -> BufferEntry {
+        loop {
+            if let Some(entry) = self.front() {
+                return entry;
+            }
+            self.notify.notified().await;
+        }
+    }
+
+    /// read the payload of an entry. fails with io::ErrorKind::NotFound if
+    /// the entry has been evicted in the meantime
+    pub async fn read(&self, entry: &BufferEntry) -> io::Result<Vec<u8>> {
+        let path = self.path(entry);
+        spawn_blocking(move || fs::read(path)).await
+    }
+
+    /// remove the given entry if it is still the oldest entry of the buffer
+    pub async fn pop(&self, entry: &BufferEntry) -> io::Result<()> {
+        {
+            let mut state = self.state();
+            if state.entries.front() != Some(entry) {
+                // already evicted
+                return Ok(());
+            }
+            state.entries.pop_front();
+            state.total_bytes -= entry.bytes;
+        }
+        let path = self.path(entry);
+        spawn_blocking(move || remove_file(&path)).await
+    }
+}
+
+/// run a blocking file operation on the blocking thread pool of tokio
+async fn spawn_blocking<T, F>(operation: F) -> io::Result<T>
+where
+    F: FnOnce() -> io::Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(operation)
+        .await
+        .map_err(io::Error::other)?
+}
+
+/// write a payload to a temporary file and move it to its final path, syncing
+/// the file and the directory so that the payload survives a crash of the
+/// host once this returns
+fn write_durably(directory: &Path, path: &Path, payload: &[u8]) -> io::Result<()> {
+    let tmp_path = path.with_extension(TMP_EXTENSION);
+    let mut file = fs::File::create(&tmp_path)?;
+    file.write_all(payload)?;
+    file.sync_all()?;
+    drop(file);
+    fs::rename(&tmp_path, path)?;
+    fs::File::open(directory)?.sync_all()
+}
+
+/// remove a file, ignoring files that do not exist
+fn remove_file(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(error) if error.kind() != io::ErrorKind::NotFound => Err(error),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn entries_are_consumed_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let buffer = DiskBuffer::open(dir.path().to_path_buf(), 1024).unwrap();
+        assert!(buffer.push(b"first".to_vec(), 1).await.unwrap().is_empty());
+        assert!(buffer.push(b"second".to_vec(), 2).await.unwrap().is_empty());
+        assert_eq!(buffer.len(), 2);
+        assert_eq!(buffer.bytes(), 11);
+
+        let entry = buffer.next().await;
+        assert_eq!(buffer.read(&entry).await.unwrap(), b"first");
+        assert_eq!(entry.samples, 1);
+        buffer.pop(&entry).await.unwrap();
+
+        let entry = buffer.next().await;
+        assert_eq!(buffer.read(&entry).await.unwrap(), b"second");
+        buffer.pop(&entry).await.unwrap();
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.bytes(), 0);
+    }
+
+    #[tokio::test]
+    async fn oldest_entries_are_evicted() {
+        let dir = tempfile::tempdir().unwrap();
+        let buffer = DiskBuffer::open(dir.path().to_path_buf(), 10).unwrap();
+        buffer.push(b"aaaa".to_vec(), 4).await.unwrap();
+        buffer.push(b"bbbb".to_vec(), 4).await.unwrap();
+        let evicted = buffer.push(b"cccc".to_vec(), 4).await.unwrap();
+        assert_eq!(evicted.len(), 1);
+        assert_eq!(evicted[0].sequence, 0);
+        assert_eq!(buffer.len(), 2);
+        assert!(buffer.read(&evicted[0]).await.is_err());
+
+        // popping an evicted entry is a no-op
+        buffer.pop(&evicted[0]).await.unwrap();
+        assert_eq!(buffer.len(), 2);
+
+        // payloads larger than the buffer are evicted right away
+        let evicted = buffer.push(vec![0; 11], 1).await.unwrap();
+        assert_eq!(evicted.len(), 3);
+        assert!(buffer.is_empty());
+    }
+
+    #[tokio::test]
+    async fn concurrent_pushes_are_queued_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let buffer = DiskBuffer::open(dir.path().to_path_buf(), 1024).unwrap();
+        futures::future::try_join_all((0..10).map(|i| buffer.push(vec![i; 4], 1)))
+            .await
+            .unwrap();
+
+        let sequences: Vec<_> = buffer.state().entries.iter().map(|e| e.sequence).collect();
+        assert_eq!(sequences, (0..10).collect::<Vec<_>>());
+        let files: Vec<_> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|file| file.unwrap().path())
+            .collect();
+        assert_eq!(files.len(), 10);
+        assert!(files
+            .iter()
+            .all(|path| path.extension().unwrap() == ENTRY_EXTENSION));
+    }
+
+    #[tokio::test]
+    async fn entries_survive_reopening() {
+        let dir = tempfile::tempdir().unwrap();
+        {
+            let buffer = DiskBuffer::open(dir.path().to_path_buf(), 1024).unwrap();
+            buffer.push(b"first".to_vec(), 1).await.unwrap();
+            buffer.push(b"second".to_vec(), 2).await.unwrap();
+            let entry = buffer.front().unwrap();
+            buffer.pop(&entry).await.unwrap();
+        }
+        fs::write(dir.path().join("interrupted.tmp"), b"partial").unwrap();
+
+        let buffer = DiskBuffer::open(dir.path().to_path_buf(), 1024).unwrap();
+        assert_eq!(buffer.len(), 1);
+        let entry = buffer.front().unwrap();
+        assert_eq!(entry.sequence, 1);
+        assert_eq!(entry.samples, 2);
+        assert_eq!(buffer.read(&entry).await.unwrap(), b"second");
+        assert!(!dir.path().join("interrupted.tmp").exists());
+
+        buffer.push(b"third".to_vec(), 3).await.unwrap();
+        assert_eq!(buffer.state().entries.back().unwrap().sequence, 2);
+    }
+}
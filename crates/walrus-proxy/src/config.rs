@@ -5,9 +5,11 @@ use std::{collections::HashMap, net::SocketAddr, path::PathBuf};
 
 use anyhow::{Context, Result};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
-use serde_with::{serde_as, DurationSeconds};
+use serde_with::{serde_as, DurationMilliSeconds, DurationSeconds, OneOrMany};
 use tracing::debug;
 
+use crate::{limits::IngestionLimitsConfig, relabel::RemoteWriteRules};
+
 /// ProxyConfig is the walrus-proxy config type used when reading the yaml
 /// config
 #[serde_as]
@@ -18,8 +20,13 @@ pub struct ProxyConfig {
     pub labels: HashMap<String, String>,
     /// what address to bind to
     pub listen_address: SocketAddr,
-    /// remote write configuration
-    pub remote_write: RemoteWriteConfig,
+    /// remote write configuration, either a single backend or a list of
+    /// backends that all receive the metrics
+    #[serde_as(as = "OneOrMany<_>")]
+    pub remote_write: Vec<RemoteWriteConfig>,
+    /// configuration of the on-disk buffers of the remote write backends
+    #[serde(default)]
+    pub remote_write_buffer: RemoteWriteBufferConfig,
     /// configuration for how we fetch peer information
     pub dynamic_peers: DynamicPeerValidationConfig,
     /// metrics address for the service itself
@@ -27,6 +34,9 @@ pub struct ProxyConfig {
     /// histogram scrape address to get histogram data form clients that push
     /// data to us
     pub histogram_address: String,
+    /// limits applied to each node pushing metrics
+    #[serde(default)]
+    pub ingestion_limits: IngestionLimitsConfig,
 }
 
 /// RemoteWriteConfig defines the mimir config items for connecting to mimir
@@ -34,6 +44,10 @@ pub struct ProxyConfig {
 #[derive(Clone, Debug, Deserialize, Serialize, Default)]
 #[serde(rename_all = "kebab-case")]
 pub struct RemoteWriteConfig {
+    /// name of the backend, used in metrics and as the name of its buffer
+    /// directory
+    #[serde(default = "remote_write_name_default")]
+    pub name: String,
     /// the remote_write url to post data to
     pub url: String,
     /// username is used for posting data to the remote_write api
@@ -43,6 +57,48 @@ pub struct RemoteWriteConfig {
     /// Sets the maximum idle connection per host allowed in the pool.
     #[serde(default = "pool_max_idle_per_host_default")]
     pub pool_max_idle_per_host: usize,
+    /// allow, drop and relabel rules applied to the metrics sent to this
+    /// backend
+    #[serde(flatten)]
+    pub rules: RemoteWriteRules,
+}
+
+/// RemoteWriteBufferConfig controls the on-disk buffers holding the
+/// remote_write requests until they are delivered to the backends
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct RemoteWriteBufferConfig {
+    /// directory holding the buffers, each backend uses a subdirectory named
+    /// after the backend
+    pub directory: PathBuf,
+    /// maximum size of the buffer of each backend in bytes, the oldest
+    /// requests are dropped when it is exceeded
+    pub max_bytes_per_backend: u64,
+    /// minimum delay before retrying to send a request to a backend
+    #[serde_as(as = "DurationMilliSeconds<u64>")]
+    #[serde(rename = "min-backoff-millis")]
+    pub min_backoff: Duration,
+    /// maximum delay before retrying to send a request to a backend
+    #[serde_as(as = "DurationMilliSeconds<u64>")]
+    #[serde(rename = "max-backoff-millis")]
+    pub max_backoff: Duration,
+    /// number of times a request is retried after the backend answered with
+    /// a server error before it is dropped. requests are retried indefinitely
+    /// while the backend cannot be reached
+    pub max_server_error_retries: u32,
+}
+
+impl Default for RemoteWriteBufferConfig {
+    fn default() -> Self {
+        Self {
+            directory: PathBuf::from("./remote-write-buffer"),
+            max_bytes_per_backend: 1024 * 1024 * 1024,
+            min_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            max_server_error_retries: 5,
+        }
+    }
 }
 
 /// DynamicPeerValidationConfig controls what walrus-nodes we'll speak with.
@@ -63,6 +119,11 @@ pub struct DynamicPeerValidationConfig {
     pub allowlist_path: Option<PathBuf>,
 }
 
+/// the default name of a remote write backend
+fn remote_write_name_default() -> String {
+    "default".into()
+}
+
 /// the default idle worker per host (reqwest to remote write url call)
 fn pool_max_idle_per_host_default() -> usize {
     8
@@ -89,4 +150,26 @@ mod tests {
 
         let _template: ProxyConfig = serde_yaml::from_str(TEMPLATE).unwrap();
     }
+
+    /// test loading a config with multiple remote write backends
+    #[test]
+    fn config_load_multiple_backends() {
+        const TEMPLATE: &str = include_str!("./fixtures/config-fanout.yaml");
+
+        let config: ProxyConfig = serde_yaml::from_str(TEMPLATE).unwrap();
+        assert_eq!(config.remote_write.len(), 2);
+        assert_eq!(config.remote_write[0].name, "mimir");
+        assert!(config.remote_write[0].rules.is_empty());
+        assert_eq!(config.remote_write[1].rules.allow.len(), 1);
+        assert_eq!(config.remote_write[1].rules.relabel.len(), 1);
+        assert_eq!(
+            config.remote_write_buffer.max_bytes_per_backend,
+            64 * 1024 * 1024
+        );
+        assert_eq!(
+            config.remote_write_buffer.min_backoff,
+            Duration::from_millis(500)
+        );
+        assert_eq!(config.remote_write_buffer.max_server_error_retries, 3);
+    }
 }
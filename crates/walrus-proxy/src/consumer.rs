@@ -22,6 +22,7 @@ use tracing::{debug, error};
 
 use crate::{
     admin::ReqwestClient,
+    fanout::RemoteWriteFanout,
     middleware::MetricFamilyWithStaticLabels,
     prom_to_mimir::Mimir,
     register_metric,
@@ -181,7 +182,9 @@ pub fn populate_labels(
 }
 
 // encode and compress our metric data before it gets sent to mimir
-fn encode_compress(request: &WriteRequest) -> Result<Vec<u8>, (StatusCode, &'static str)> {
+pub(crate) fn encode_compress(
+    request: &WriteRequest,
+) -> Result<Vec<u8>, (StatusCode, &'static str)> {
     let observe = || {
         let timer = walrus_utils::with_label!(
             CONSUMER_ENCODE_COMPRESS_DURATION,
@@ -221,17 +224,35 @@ fn encode_compress(request: &WriteRequest) -> Result<Vec<u8>, (StatusCode, &'sta
     Ok(compressed)
 }
 
-async fn check_response(
-    request: WriteRequest,
-    response: reqwest::Response,
-) -> Result<(), (StatusCode, &'static str)> {
+/// RemoteWriteOutcome is the outcome of posting a payload to a remote write
+/// backend
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RemoteWriteOutcome {
+    /// the backend accepted the payload
+    Accepted,
+    /// the backend rejected the payload, retrying would not help
+    Rejected,
+    /// the backend could not be reached or is throttling, the payload should be
+    /// retried
+    Retry,
+    /// the backend failed with a server error, the payload should be retried a
+    /// limited number of times as the backend may never accept it
+    ServerError,
+}
+
+async fn check_response(response: reqwest::Response) -> RemoteWriteOutcome {
     match response.status() {
-        reqwest::StatusCode::OK => {
+        code if code.is_success() => {
             walrus_utils::with_label!(CONSUMER_OPS, "check_response", "OK").inc();
-            debug!("({}) SUCCESS: {:?}", reqwest::StatusCode::OK, request);
-            Ok(())
+            debug!("({}) SUCCESS", code);
+            RemoteWriteOutcome::Accepted
+        }
+        code @ (reqwest::StatusCode::TOO_MANY_REQUESTS | reqwest::StatusCode::REQUEST_TIMEOUT) => {
+            walrus_utils::with_label!(CONSUMER_OPS, "check_response", "RETRY").inc();
+            error!("({}) RETRYING: backend is throttling", code);
+            RemoteWriteOutcome::Retry
         }
-        reqwest::StatusCode::BAD_REQUEST => {
+        code if code.is_client_error() => {
             let body = response
                 .text()
                 .await
@@ -241,19 +262,13 @@ async fn check_response(
             // so we drop it.
             if body.contains("err-mimir-sample-out-of-order") {
                 walrus_utils::with_label!(CONSUMER_OPS, "check_response", "BAD_REQUEST").inc();
-                error!("({}) ERROR: {:?}", reqwest::StatusCode::BAD_REQUEST, body);
-                return Err((
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    "IGNORING METRICS due to err-mimir-sample-out-of-order",
-                ));
+                error!("({}) IGNORING METRICS: {:?}", code, body);
+                return RemoteWriteOutcome::Rejected;
             }
             walrus_utils::with_label!(CONSUMER_OPS, "check_response", "INTERNAL_SERVER_ERROR")
                 .inc();
-            error!("({}) ERROR: {:?}", reqwest::StatusCode::BAD_REQUEST, body);
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "unknown bad request error encountered in remote_push",
-            ))
+            error!("({}) DROPPING METRICS: {:?}", code, body);
+            RemoteWriteOutcome::Rejected
         }
         code => {
             let body = response
@@ -262,15 +277,39 @@ async fn check_response(
                 .unwrap_or_else(|_| "response body cannot be decoded".into());
             walrus_utils::with_label!(CONSUMER_OPS, "check_response", "INTERNAL_SERVER_ERROR")
                 .inc();
-            error!("({}) ERROR: {:?}", code, body);
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "unknown error encountered in remote_push",
-            ))
+            error!("({}) RETRYING: {:?}", code, body);
+            RemoteWriteOutcome::ServerError
         }
     }
 }
 
+/// post a compressed remote write payload to the backend of the given client
+pub async fn post_remote_write(rc: &ReqwestClient, compressed: Vec<u8>) -> RemoteWriteOutcome {
+    let response = match rc
+        .client
+        .post(rc.settings.url.to_owned())
+        .header(reqwest::header::CONTENT_ENCODING, "snappy")
+        .header(reqwest::header::CONTENT_TYPE, "application/x-protobuf")
+        .header("X-Prometheus-Remote-Write-Version", "0.1.0")
+        .basic_auth(
+            rc.settings.username.to_owned(),
+            Some(rc.settings.password.to_owned()),
+        )
+        .body(compressed)
+        .send()
+        .await
+    {
+        Ok(response) => response,
+        Err(error) => {
+            walrus_utils::with_label!(CONSUMER_OPS, "check_response", "INTERNAL_SERVER_ERROR")
+                .inc();
+            error!("RETRYING METRICS due to post error: {error}");
+            return RemoteWriteOutcome::Retry;
+        }
+    };
+    check_response(response).await
+}
+
 async fn convert(
     mfs: Vec<MetricFamily>,
 ) -> Result<impl Iterator<Item = WriteRequest>, (StatusCode, &'static str)> {
@@ -297,70 +336,29 @@ async fn convert(
     Ok(result)
 }
 
-/// convert_to_remote_write is an expensive method due to the time it takes to
-/// submit to mimir. other operations here are optimized for async, within
-/// reason.  The post process uses a single connection to mimir and thus incurs
-/// the seriliaztion delay for each metric family sent.
+/// convert_to_remote_write converts the node data to remote_write requests
+/// and submits them to the write-ahead buffers of all remote write backends.
+/// The requests are sent to the backends in the background.
 pub async fn convert_to_remote_write(
-    rc: ReqwestClient,
+    fanout: RemoteWriteFanout,
     node_metric: NodeMetric,
 ) -> (StatusCode, &'static str) {
     let timer = walrus_utils::with_label!(CONSUMER_OPERATION_DURATION, "convert_to_remote_write")
         .start_timer();
 
-    let remote_write_protos = match convert(node_metric.data).await {
-        Ok(v) => v,
+    let remote_write_protos: Vec<_> = match convert(node_metric.data).await {
+        Ok(v) => v.collect(),
         Err(err) => {
             timer.stop_and_discard();
             return err;
         }
     };
 
-    // a counter so we don't iterate the node data 2x
-    let mut mf_cnt = 0;
-    for request in remote_write_protos {
-        mf_cnt += 1;
-        let compressed = match encode_compress(&request) {
-            Ok(compressed) => compressed,
-            Err(error) => return error,
-        };
-
-        let response = match rc
-            .client
-            .post(rc.settings.url.to_owned())
-            .header(reqwest::header::CONTENT_ENCODING, "snappy")
-            .header(reqwest::header::CONTENT_TYPE, "application/x-protobuf")
-            .header("X-Prometheus-Remote-Write-Version", "0.1.0")
-            .basic_auth(
-                rc.settings.username.to_owned(),
-                Some(rc.settings.password.to_owned()),
-            )
-            .body(compressed)
-            .send()
-            .await
-        {
-            Ok(response) => response,
-            Err(error) => {
-                walrus_utils::with_label!(CONSUMER_OPS, "check_response", "INTERNAL_SERVER_ERROR")
-                    .inc();
-                error!("DROPPING METRICS due to post error: {error}");
-                timer.stop_and_discard();
-                return (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    "DROPPING METRICS due to post error",
-                );
-            }
-        };
-
-        match check_response(request, response).await {
-            Ok(_) => (),
-            Err(err) => {
-                timer.stop_and_discard();
-                return err;
-            }
-        }
+    if let Err(err) = fanout.submit(&remote_write_protos).await {
+        timer.stop_and_discard();
+        return err;
     }
-    CONSUMER_OPS_SUBMITTED.inc_by(mf_cnt as f64);
+    CONSUMER_OPS_SUBMITTED.inc_by(remote_write_protos.len() as f64);
     timer.observe_duration();
     (StatusCode::CREATED, "created")
 }
@@ -0,0 +1,433 @@
+// Copyright (c) Walrus Foundation
+// SPDX-License-Identifier: Apache-2.0
+use std::{collections::HashSet, io, sync::Arc};
+
+use anyhow::{bail, Context, Result};
+use axum::http::StatusCode;
+use futures::future::join_all;
+use once_cell::sync::Lazy;
+use prometheus::{IntCounterVec, IntGaugeVec, Opts};
+use rand::{rngs::StdRng, SeedableRng};
+use tracing::{error, info, warn};
+use walrus_utils::backoff::ExponentialBackoffState;
+
+use crate::{
+    admin::{make_reqwest_client, ReqwestClient},
+    buffer::{BufferEntry, DiskBuffer},
+    config::{RemoteWriteBufferConfig, RemoteWriteConfig},
+    consumer::{encode_compress, post_remote_write, RemoteWriteOutcome},
+    register_metric,
+    remote_write::WriteRequest,
+};
+
+static REMOTE_WRITE_BUFFER_DEPTH: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_metric!(IntGaugeVec::new(
+        Opts::new(
+            "remote_write_buffer_depth",
+            "The number of remote_write requests waiting in the buffer of a backend.",
+        ),
+        &["backend"]
+    )
+    .unwrap())
+});
+static REMOTE_WRITE_BUFFER_BYTES: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_metric!(IntGaugeVec::new(
+        Opts::new(
+            "remote_write_buffer_bytes",
+            "The size in bytes of the remote_write requests in the buffer of a backend.",
+        ),
+        &["backend"]
+    )
+    .unwrap())
+});
+static REMOTE_WRITE_DROPPED_SAMPLES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_metric!(IntCounterVec::new(
+        Opts::new(
+            "remote_write_dropped_samples",
+            "The number of samples that were never delivered to a backend.",
+        ),
+        &["backend", "reason"]
+    )
+    .unwrap())
+});
+static REMOTE_WRITE_REQUESTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_metric!(IntCounterVec::new(
+        Opts::new(
+            "remote_write_requests",
+            "The number of remote_write requests sent to a backend by outcome.",
+        ),
+        &["backend", "outcome"]
+    )
+    .unwrap())
+});
+
+/// RemoteWriteBackend is a remote write endpoint along with the buffer of
+/// requests that have yet to be delivered to it
+#[derive(Debug)]
+struct RemoteWriteBackend {
+    client: ReqwestClient,
+    buffer: DiskBuffer,
+}
+
+impl RemoteWriteBackend {
+    fn name(&self) -> &str {
+        &self.client.settings.name
+    }
+
+    fn update_buffer_metrics(&self) {
+        walrus_utils::with_label!(REMOTE_WRITE_BUFFER_DEPTH, self.name())
+            .set(self.buffer.len() as i64);
+        walrus_utils::with_label!(REMOTE_WRITE_BUFFER_BYTES, self.name())
+            .set(self.buffer.bytes() as i64);
+    }
+
+    fn drop_samples(&self, reason: &str, samples: u64) {
+        walrus_utils::with_label!(REMOTE_WRITE_DROPPED_SAMPLES, self.name(), reason)
+            .inc_by(samples);
+    }
+
+    /// apply the rules of the backend to the requests and append the
+    /// resulting requests to the buffer. a request that cannot be buffered is
+    /// dropped without affecting the other requests; fails only if none of
+    /// the requests could be buffered
+    async fn submit(&self, requests: &[WriteRequest]) -> Result<(), (StatusCode, &'static str)> {
+        let rules = &self.client.settings.rules;
+        let mut buffered = false;
+        let mut failure = None;
+        for request in requests {
+            let filtered;
+            let request = if rules.is_empty() {
+                request
+            } else {
+                filtered = WriteRequest {
+                    timeseries: request
+                        .timeseries
+                        .iter()
+                        .filter_map(|ts| rules.apply(ts.clone()))
+                        .collect(),
+                    ..Default::default()
+                };
+                &filtered
+            };
+            if request.timeseries.is_empty() {
+                buffered = true;
+                continue;
+            }
+            let samples = request
+                .timeseries
+                .iter()
+                .map(|ts| ts.samples.len() as u64)
+                .sum();
+            let compressed = match encode_compress(request) {
+                Ok(compressed) => compressed,
+                Err(error) => {
+                    self.drop_samples("encode_error", samples);
+                    failure = Some(error);
+                    continue;
+                }
+            };
+            match self.buffer.push(compressed, samples).await {
+                Ok(evicted) => {
+                    buffered = true;
+                    for entry in evicted {
+                        warn!(
+                            "DROPPING METRICS; remote_write buffer of {} is full",
+                            self.name()
+                        );
+                        self.drop_samples("buffer_full", entry.samples);
+                    }
+                }
+                Err(error) => {
+                    error!(
+                        "DROPPING METRICS; unable to buffer remote_write request for {}: {error}",
+                        self.name()
+                    );
+                    self.drop_samples("buffer_error", samples);
+                    failure = Some((
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        "unable to buffer remote_write request",
+                    ));
+                }
+            }
+        }
+        self.update_buffer_metrics();
+        match failure {
+            Some(error) if !buffered => Err(error),
+            _ => Ok(()),
+        }
+    }
+
+    /// deliver the buffered requests to the backend in order, retrying with
+    /// exponential backoff while the backend is unavailable. requests failing
+    /// with server errors are dropped after the configured number of retries
+    async fn run(self: Arc<Self>, config: RemoteWriteBufferConfig) {
+        let mut rng = StdRng::from_entropy();
+        let mut backoff =
+            ExponentialBackoffState::new_infinite(config.min_backoff, config.max_backoff);
+        // the entry being delivered and the number of server errors it caused
+        let mut current = None;
+        let mut server_errors = 0;
+        loop {
+            let entry = self.buffer.next().await;
+            if current != Some(entry.sequence) {
+                current = Some(entry.sequence);
+                server_errors = 0;
+            }
+            let outcome = match self.buffer.read(&entry).await {
+                Ok(payload) => post_remote_write(&self.client, payload).await,
+                // evicted while we were waiting for the backend
+                Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                    self.pop(&entry).await;
+                    continue;
+                }
+                Err(error) => {
+                    error!(
+                        "DROPPING METRICS; unable to read buffered remote_write request for {}: \
+                        {error}",
+                        self.name()
+                    );
+                    self.drop_samples("buffer_error", entry.samples);
+                    self.pop(&entry).await;
+                    continue;
+                }
+            };
+            let label = match outcome {
+                RemoteWriteOutcome::Accepted => "accepted",
+                RemoteWriteOutcome::Rejected => "rejected",
+                RemoteWriteOutcome::Retry => "retry",
+                RemoteWriteOutcome::ServerError => "server_error",
+            };
+            walrus_utils::with_label!(REMOTE_WRITE_REQUESTS, self.name(), label).inc();
+
+            match outcome {
+                RemoteWriteOutcome::Accepted => {
+                    backoff = ExponentialBackoffState::new_infinite(
+                        config.min_backoff,
+                        config.max_backoff,
+                    );
+                    self.pop(&entry).await;
+                }
+                RemoteWriteOutcome::Rejected => {
+                    self.drop_samples("rejected", entry.samples);
+                    self.pop(&entry).await;
+                }
+                RemoteWriteOutcome::ServerError
+                    if server_errors >= config.max_server_error_retries =>
+                {
+                    error!(
+                        "DROPPING METRICS; {} failed {} times to accept a remote_write request",
+                        self.name(),
+                        server_errors + 1
+                    );
+                    self.drop_samples("server_error", entry.samples);
+                    self.pop(&entry).await;
+                }
+                RemoteWriteOutcome::Retry | RemoteWriteOutcome::ServerError => {
+                    if outcome == RemoteWriteOutcome::ServerError {
+                        server_errors += 1;
+                    }
+                    let delay = backoff
+                        .next_delay(&mut rng)
+                        .expect("infinite backoff always yields a delay");
+                    tokio::time::sleep(delay).await;
+                }
+            }
+        }
+    }
+
+    async fn pop(&self, entry: &BufferEntry) {
+        if let Err(error) = self.buffer.pop(entry).await {
+            error!(
+                "unable to remove remote_write request from the buffer of {}: {error}",
+                self.name()
+            );
+        }
+        self.update_buffer_metrics();
+    }
+}
+
+/// RemoteWriteFanout submits remote_write requests to all configured
+/// backends. Requests are persisted to an on-disk buffer per backend before
+/// being delivered in the background, so that no data is lost while a backend
+/// is unavailable.
+#[derive(Debug, Clone)]
+pub struct RemoteWriteFanout {
+    backends: Arc<Vec<Arc<RemoteWriteBackend>>>,
+    config: RemoteWriteBufferConfig,
+}
+
+impl RemoteWriteFanout {
+    /// create the clients and open the buffers of the backends
+    pub fn new(
+        backends: Vec<RemoteWriteConfig>,
+        config: RemoteWriteBufferConfig,
+        user_agent: &str,
+    ) -> Result<Self> {
+        if backends.is_empty() {
+            bail!("at least one remote-write backend must be configured");
+        }
+        let mut names = HashSet::new();
+        let backends = backends
+            .into_iter()
+            .map(|settings| {
+                if !names.insert(settings.name.clone()) {
+                    bail!("duplicate remote-write backend name: {}", settings.name);
+                }
+                let directory = config.directory.join(&settings.name);
+                let buffer = DiskBuffer::open(directory.clone(), config.max_bytes_per_backend)
+                    .with_context(|| format!("cannot open remote-write buffer {directory:?}"))?;
+                let backend = RemoteWriteBackend {
+                    client: make_reqwest_client(settings, user_agent),
+                    buffer,
+                };
+                backend.update_buffer_metrics();
+                Ok(Arc::new(backend))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            backends: Arc::new(backends),
+            config,
+        })
+    }
+
+    /// spawn the tasks delivering the buffered requests to the backends
+    pub fn start(&self) {
+        for backend in self.backends.iter() {
+            info!(
+                "sending to remote-write backend {} at {:?} ({} buffered requests)",
+                backend.name(),
+                backend.client.settings.url,
+                backend.buffer.len()
+            );
+            tokio::spawn(backend.clone().run(self.config.clone()));
+        }
+    }
+
+    /// append the requests to the buffers of all backends. the delivery is
+    /// tracked per backend: as soon as one backend buffered the requests, the
+    /// submission succeeds so that the sender does not re-send requests that
+    /// other backends already hold, and the samples that could not be
+    /// buffered for a backend are counted as dropped. fails only if no
+    /// backend buffered the requests, in which case they can safely be re-sent
+    pub async fn submit(
+        &self,
+        requests: &[WriteRequest],
+    ) -> Result<(), (StatusCode, &'static str)> {
+        let results = join_all(self.backends.iter().map(|backend| backend.submit(requests))).await;
+        if results.iter().any(Result::is_ok) {
+            return Ok(());
+        }
+        results.into_iter().next().unwrap_or(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    use axum::{routing::post, Router};
+
+    use super::*;
+    use crate::remote_write::{Label, Sample, TimeSeries};
+
+    fn write_request() -> WriteRequest {
+        WriteRequest {
+            timeseries: vec![TimeSeries {
+                labels: vec![Label {
+                    name: "__name__".into(),
+                    value: "walrus_test".into(),
+                }],
+                samples: vec![Sample {
+                    value: 1.0,
+                    timestamp: 12345,
+                }],
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    fn buffer_config(directory: &std::path::Path) -> RemoteWriteBufferConfig {
+        RemoteWriteBufferConfig {
+            directory: directory.to_path_buf(),
+            min_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(1),
+            max_server_error_retries: 2,
+            ..Default::default()
+        }
+    }
+
+    fn backend(name: &str, url: String) -> RemoteWriteConfig {
+        RemoteWriteConfig {
+            name: name.into(),
+            url,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn submission_succeeds_once_one_backend_buffered_the_requests() {
+        let dir = tempfile::tempdir().unwrap();
+        let fanout = RemoteWriteFanout::new(
+            vec![
+                backend("first", "http://127.0.0.1:1".into()),
+                backend("second", "http://127.0.0.1:1".into()),
+            ],
+            buffer_config(dir.path()),
+            "test",
+        )
+        .unwrap();
+
+        // the buffer of the first backend can no longer be written
+        fs::remove_dir_all(dir.path().join("first")).unwrap();
+        fanout.submit(&[write_request()]).await.unwrap();
+        assert!(fanout.backends[0].buffer.is_empty());
+        assert_eq!(fanout.backends[1].buffer.len(), 1);
+
+        fs::remove_dir_all(dir.path().join("second")).unwrap();
+        assert!(fanout.submit(&[write_request()]).await.is_err());
+        assert_eq!(fanout.backends[1].buffer.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn requests_failing_with_server_errors_are_dropped_after_retries() {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let app = Router::new().route(
+            "/push",
+            post({
+                let hits = hits.clone();
+                move || async move {
+                    hits.fetch_add(1, Ordering::SeqCst);
+                    StatusCode::INTERNAL_SERVER_ERROR
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/push", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let dir = tempfile::tempdir().unwrap();
+        let fanout = RemoteWriteFanout::new(
+            vec![backend("failing", url)],
+            buffer_config(dir.path()),
+            "test",
+        )
+        .unwrap();
+        fanout.submit(&[write_request()]).await.unwrap();
+        fanout.start();
+
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while !fanout.backends[0].buffer.is_empty() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("the request should be dropped");
+        // the first attempt and the two retries
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+    }
+}
@@ -1,14 +1,17 @@
 // Copyright (c) Walrus Foundation
 // SPDX-License-Identifier: Apache-2.0
+use std::sync::Arc;
+
 use axum::{extract::Extension, http::StatusCode};
 use once_cell::sync::Lazy;
 use prometheus::{CounterVec, HistogramOpts, HistogramVec, Opts};
 
 use crate::{
-    admin::ReqwestClient,
     consumer::{convert_to_remote_write, populate_labels, Label, NodeMetric},
+    fanout::RemoteWriteFanout,
     histogram_relay::HistogramRelay,
-    middleware::LenDelimProtobuf,
+    limits::IngestionLimiter,
+    middleware::{LenDelimProtobuf, MetricFamilyWithStaticLabels},
     providers::NodeInfo,
     register_metric,
 };
@@ -37,26 +40,33 @@ static HTTP_HANDLER_DURATION: Lazy<HistogramVec> = Lazy::new(|| {
 });
 
 /// Publish handler which receives metrics from nodes.  Nodes will call us at
-/// this endpoint and we relay them to the upstream tsdb. Clients will receive
-/// a response after successfully relaying the metrics upstream
+/// this endpoint and we relay them to the upstream tsdbs. Clients will receive
+/// a response once the metrics are buffered for the upstream tsdbs
 pub async fn publish_metrics(
     Extension(labels): Extension<Vec<Label>>,
-    Extension(remote_write_client): Extension<ReqwestClient>,
-    Extension(NodeInfo {
-        name,
-        network_address,
-        ..
-    }): Extension<NodeInfo>,
+    Extension(fanout): Extension<RemoteWriteFanout>,
+    Extension(node): Extension<NodeInfo>,
     Extension(relay): Extension<HistogramRelay>,
+    Extension(limiter): Extension<Arc<IngestionLimiter>>,
     LenDelimProtobuf(data): LenDelimProtobuf,
 ) -> (StatusCode, &'static str) {
-    walrus_utils::with_label!(HANDLER_HITS, "publish_metrics", &name).inc();
-    let timer =
-        walrus_utils::with_label!(HTTP_HANDLER_DURATION, "publish_metrics", &name).start_timer();
+    walrus_utils::with_label!(HANDLER_HITS, "publish_metrics", &node.name).inc();
+    let timer = walrus_utils::with_label!(HTTP_HANDLER_DURATION, "publish_metrics", &node.name)
+        .start_timer();
+    // drop the metric families exceeding the series limits of the node
+    let data = MetricFamilyWithStaticLabels {
+        metric_families: limiter.filter(&node, data.metric_families),
+        ..data
+    };
+    let NodeInfo {
+        name,
+        network_address,
+        ..
+    } = node;
     let data = populate_labels(name, labels, data);
     relay.submit(data.clone());
     let response = convert_to_remote_write(
-        remote_write_client.clone(),
+        fanout,
         NodeMetric {
             data,
             network_address,
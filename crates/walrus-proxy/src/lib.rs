@@ -10,33 +10,46 @@
 //! ## Modules
 //!
 //! - `admin`: Handles administrative actions for the proxy.
+//! - `buffer`: On-disk buffer of remote write requests.
 //! - `config`: Config params for this service.
 //! - `consumer`: Manages the proxy code that consumes metrics from calling
 //!   nodes.
+//! - `fanout`: Delivers remote write requests to all configured backends.
 //! - `handlers`: Axum handler that clients hit.
 //! - `histogram_relay`: Export histograms to an agent for scraping. Remote
 //!   write restrictions.
+//! - `limits`: Per-node ingestion limits and cardinality guard.
 //! - `metrics`: Exposes metrics for the system to monitor the server itself
 //! - `middleware`: Middleware we use to validate incoming client connections.
 //! - `providers`: Implements the provider functionality for the proxy.
+//! - `relabel`: Filtering and relabeling rules of the remote write backends.
 
 use fastcrypto::secp256r1::Secp256r1PublicKey;
 
 /// Handles administrative actions for the proxy.
 pub mod admin;
 
+/// On-disk buffer of remote write requests.
+pub mod buffer;
+
 /// Config params for this service.
 pub mod config;
 
 /// Manages the proxy code that consumes metrics from calling nodes.
 pub mod consumer;
 
+/// Delivers remote write requests to all configured backends.
+pub mod fanout;
+
 /// Axum handler that clients hit.
 pub mod handlers;
 
 /// Export histograms to an agent for scraping. Remote write restrictions.
 pub mod histogram_relay;
 
+/// Per-node ingestion limits and cardinality guard.
+pub mod limits;
+
 /// Exposes metrics for the system to monitor the server itself.
 pub mod metrics;
 
@@ -49,6 +62,9 @@ pub mod providers;
 /// Implements the prometheus to remote write conversion.
 pub mod prom_to_mimir;
 
+/// Filtering and relabeling rules of the remote write backends.
+pub mod relabel;
+
 #[allow(clippy::all)]
 // Implements remote write details, generated from protobufs.
 pub mod remote_write;
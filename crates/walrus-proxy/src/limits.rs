@@ -0,0 +1,495 @@
+// Copyright (c) Walrus Foundation
+// SPDX-License-Identifier: Apache-2.0
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap, HashSet, VecDeque},
+    hash::{Hash, Hasher},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use axum::http::StatusCode;
+use once_cell::sync::Lazy;
+use prometheus::{proto::MetricFamily, IntCounterVec, Opts};
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DurationSeconds};
+use tracing::warn;
+
+use crate::{providers::NodeInfo, register_metric, NetworkPublicKey};
+
+/// the window over which pushes per minute are counted
+const PUSH_RATE_WINDOW: Duration = Duration::from_secs(60);
+
+static INGESTION_REJECTED_PUSHES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_metric!(IntCounterVec::new(
+        Opts::new(
+            "ingestion_rejected_pushes",
+            "The number of pushes rejected because a peer exceeded its ingestion limits.",
+        ),
+        &["peer", "reason"]
+    )
+    .unwrap())
+});
+static INGESTION_DROPPED_METRIC_FAMILIES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_metric!(IntCounterVec::new(
+        Opts::new(
+            "ingestion_dropped_metric_families",
+            "The number of metric families dropped because a peer exceeded its series limits.",
+        ),
+        &["peer", "reason"]
+    )
+    .unwrap())
+});
+static INGESTION_DROPPED_SERIES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_metric!(IntCounterVec::new(
+        Opts::new(
+            "ingestion_dropped_series",
+            "The number of series dropped because a peer exceeded its series limits.",
+        ),
+        &["peer", "reason"]
+    )
+    .unwrap())
+});
+
+/// IngestionLimitsConfig holds the limits applied to each peer pushing
+/// metrics, keyed by its public key. limits that are not set are not enforced
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct IngestionLimitsConfig {
+    /// maximum size of the body of a push in bytes
+    pub max_bytes_per_push: Option<u64>,
+    /// maximum number of pushes per peer in any 60 second window
+    pub max_pushes_per_minute: Option<usize>,
+    /// maximum number of series in a single push. metric families beyond the
+    /// limit are dropped
+    pub max_series_per_push: Option<usize>,
+    /// maximum number of distinct series a peer may push within
+    /// active-series-window. metric families adding series beyond the limit
+    /// are dropped
+    pub max_active_series: Option<usize>,
+    /// the window after which the set of active series of a peer is reset
+    #[serde_as(as = "DurationSeconds<u64>")]
+    pub active_series_window: Duration,
+}
+
+impl Default for IngestionLimitsConfig {
+    fn default() -> Self {
+        Self {
+            max_bytes_per_push: None,
+            max_pushes_per_minute: None,
+            max_series_per_push: None,
+            max_active_series: None,
+            active_series_window: Duration::from_secs(60 * 60),
+        }
+    }
+}
+
+/// LimitExceeded is the reason a push was rejected
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LimitExceeded {
+    /// the push is larger than max-bytes-per-push
+    BytesPerPush,
+    /// the peer pushed more than max-pushes-per-minute
+    PushesPerMinute,
+}
+
+impl LimitExceeded {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::BytesPerPush => "bytes_per_push",
+            Self::PushesPerMinute => "pushes_per_minute",
+        }
+    }
+}
+
+impl From<LimitExceeded> for (StatusCode, &'static str) {
+    fn from(value: LimitExceeded) -> Self {
+        match value {
+            LimitExceeded::BytesPerPush => (
+                StatusCode::PAYLOAD_TOO_LARGE,
+                "push exceeds the maximum number of bytes per push",
+            ),
+            LimitExceeded::PushesPerMinute => (
+                StatusCode::TOO_MANY_REQUESTS,
+                "peer exceeds the maximum number of pushes per minute",
+            ),
+        }
+    }
+}
+
+/// TalkerStats summarizes the ingestion of a peer since the proxy started
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct TalkerStats {
+    /// name of the peer
+    pub name: String,
+    /// the public key of the peer
+    pub network_public_key: String,
+    /// number of accepted pushes
+    pub pushes: u64,
+    /// total bytes of the accepted pushes
+    pub bytes: u64,
+    /// number of series in the last push
+    pub last_push_series: usize,
+    /// number of distinct series in the current window
+    pub active_series: usize,
+    /// number of rejected pushes
+    pub rejected_pushes: u64,
+    /// number of dropped metric families
+    pub dropped_metric_families: u64,
+}
+
+#[derive(Debug)]
+struct PeerState {
+    name: String,
+    recent_pushes: VecDeque<Instant>,
+    active_series: HashSet<u64>,
+    window_start: Instant,
+    pushes: u64,
+    bytes: u64,
+    last_push_series: usize,
+    rejected_pushes: u64,
+    dropped_metric_families: u64,
+}
+
+impl PeerState {
+    fn new(name: String, now: Instant) -> Self {
+        Self {
+            name,
+            recent_pushes: VecDeque::new(),
+            active_series: HashSet::new(),
+            window_start: now,
+            pushes: 0,
+            bytes: 0,
+            last_push_series: 0,
+            rejected_pushes: 0,
+            dropped_metric_families: 0,
+        }
+    }
+}
+
+/// hash identifying a series by its metric name and labels
+fn series_hash(name: &str, metric: &prometheus::proto::Metric) -> u64 {
+    let mut labels: Vec<_> = metric
+        .get_label()
+        .iter()
+        .map(|label| (label.get_name(), label.get_value()))
+        .collect();
+    labels.sort_unstable();
+    let mut hasher = DefaultHasher::new();
+    name.hash(&mut hasher);
+    labels.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// IngestionLimiter enforces the ingestion limits of each peer and keeps
+/// track of how much each peer pushes
+#[derive(Debug)]
+pub struct IngestionLimiter {
+    config: IngestionLimitsConfig,
+    peers: Mutex<HashMap<NetworkPublicKey, PeerState>>,
+}
+
+impl IngestionLimiter {
+    /// create a limiter enforcing the given limits
+    pub fn new(config: IngestionLimitsConfig) -> Self {
+        Self {
+            config,
+            peers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn with_peer<T>(
+        &self,
+        peer: &NodeInfo,
+        now: Instant,
+        f: impl FnOnce(&mut PeerState) -> T,
+    ) -> T {
+        let mut peers = self
+            .peers
+            .lock()
+            .expect("ingestion limiter mutex should not be poisoned");
+        let state = peers
+            .entry(peer.network_public_key.clone())
+            .or_insert_with(|| PeerState::new(peer.name.clone(), now));
+        // the name of a key may change on chain
+        if state.name != peer.name {
+            state.name = peer.name.clone();
+        }
+        f(state)
+    }
+
+    /// check a push of the given size against the push limits of the peer and
+    /// record it if it is accepted
+    pub fn check_push(&self, peer: &NodeInfo, bytes: u64) -> Result<(), LimitExceeded> {
+        self.check_push_at(peer, bytes, Instant::now())
+    }
+
+    fn check_push_at(
+        &self,
+        peer: &NodeInfo,
+        bytes: u64,
+        now: Instant,
+    ) -> Result<(), LimitExceeded> {
+        let result = self.with_peer(peer, now, |state| {
+            while state
+                .recent_pushes
+                .front()
+                .is_some_and(|push| now.duration_since(*push) >= PUSH_RATE_WINDOW)
+            {
+                state.recent_pushes.pop_front();
+            }
+            if self
+                .config
+                .max_bytes_per_push
+                .is_some_and(|max| bytes > max)
+            {
+                state.rejected_pushes += 1;
+                return Err(LimitExceeded::BytesPerPush);
+            }
+            if self
+                .config
+                .max_pushes_per_minute
+                .is_some_and(|max| state.recent_pushes.len() >= max)
+            {
+                state.rejected_pushes += 1;
+                return Err(LimitExceeded::PushesPerMinute);
+            }
+            state.recent_pushes.push_back(now);
+            state.pushes += 1;
+            state.bytes += bytes;
+            Ok(())
+        });
+        if let Err(limit) = result {
+            warn!("rejecting push from {}: {:?}", peer.name, limit);
+            walrus_utils::with_label!(INGESTION_REJECTED_PUSHES, &peer.name, limit.label()).inc();
+        }
+        result
+    }
+
+    /// drop the metric families of a push that exceed the series limits of
+    /// the peer
+    pub fn filter(&self, peer: &NodeInfo, metric_families: Vec<MetricFamily>) -> Vec<MetricFamily> {
+        self.filter_at(peer, metric_families, Instant::now())
+    }
+
+    fn filter_at(
+        &self,
+        peer: &NodeInfo,
+        metric_families: Vec<MetricFamily>,
+        now: Instant,
+    ) -> Vec<MetricFamily> {
+        let mut dropped: Vec<(&'static str, usize)> = vec![];
+        let accepted = self.with_peer(peer, now, |state| {
+            if now.duration_since(state.window_start) >= self.config.active_series_window {
+                state.active_series.clear();
+                state.window_start = now;
+            }
+            let mut push_series = 0;
+            let mut accepted = Vec::with_capacity(metric_families.len());
+            for mf in metric_families {
+                let series = mf.get_metric().len();
+                if self
+                    .config
+                    .max_series_per_push
+                    .is_some_and(|max| push_series + series > max)
+                {
+                    dropped.push(("series_per_push", series));
+                    continue;
+                }
+                if let Some(max) = self.config.max_active_series {
+                    let new_series: HashSet<_> = mf
+                        .get_metric()
+                        .iter()
+                        .map(|metric| series_hash(mf.get_name(), metric))
+                        .filter(|hash| !state.active_series.contains(hash))
+                        .collect();
+                    if state.active_series.len() + new_series.len() > max {
+                        dropped.push(("active_series", series));
+                        continue;
+                    }
+                    state.active_series.extend(new_series);
+                }
+                push_series += series;
+                accepted.push(mf);
+            }
+            state.last_push_series = push_series;
+            state.dropped_metric_families += dropped.len() as u64;
+            accepted
+        });
+
+        if !dropped.is_empty() {
+            warn!(
+                "dropping {} metric families from {} exceeding its series limits",
+                dropped.len(),
+                peer.name
+            );
+        }
+        for (reason, series) in dropped {
+            walrus_utils::with_label!(INGESTION_DROPPED_METRIC_FAMILIES, &peer.name, reason).inc();
+            walrus_utils::with_label!(INGESTION_DROPPED_SERIES, &peer.name, reason)
+                .inc_by(series as u64);
+        }
+        accepted
+    }
+
+    /// the peers that pushed the most bytes, in decreasing order
+    pub fn top_talkers(&self, limit: usize) -> Vec<TalkerStats> {
+        let peers = self
+            .peers
+            .lock()
+            .expect("ingestion limiter mutex should not be poisoned");
+        let mut talkers: Vec<_> = peers
+            .iter()
+            .map(|(key, state)| TalkerStats {
+                name: state.name.clone(),
+                network_public_key: key.to_string(),
+                pushes: state.pushes,
+                bytes: state.bytes,
+                last_push_series: state.last_push_series,
+                active_series: state.active_series.len(),
+                rejected_pushes: state.rejected_pushes,
+                dropped_metric_families: state.dropped_metric_families,
+            })
+            .collect();
+        talkers.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.name.cmp(&b.name)));
+        talkers.truncate(limit);
+        talkers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fastcrypto::{secp256r1::Secp256r1KeyPair, traits::KeyPair};
+    use prometheus::proto;
+    use protobuf::RepeatedField;
+    use rand::thread_rng;
+
+    use super::*;
+    use crate::prom_to_mimir::tests::{
+        create_counter,
+        create_labels,
+        create_metric_counter,
+        create_metric_family,
+    };
+
+    fn node(name: &str) -> NodeInfo {
+        NodeInfo {
+            name: name.into(),
+            network_address: "127.0.0.1:9185".into(),
+            network_public_key: Secp256r1KeyPair::generate(&mut thread_rng())
+                .public()
+                .clone(),
+        }
+    }
+
+    fn metric_family(name: &str, hosts: &[&str]) -> proto::MetricFamily {
+        let metrics = hosts
+            .iter()
+            .map(|host| {
+                create_metric_counter(
+                    RepeatedField::from_vec(create_labels(vec![("host", host)])),
+                    create_counter(1.0),
+                )
+            })
+            .collect();
+        create_metric_family(
+            name,
+            "help",
+            Some(proto::MetricType::COUNTER),
+            RepeatedField::from_vec(metrics),
+        )
+    }
+
+    #[test]
+    fn pushes_are_limited() {
+        let limiter = IngestionLimiter::new(IngestionLimitsConfig {
+            max_bytes_per_push: Some(100),
+            max_pushes_per_minute: Some(2),
+            ..Default::default()
+        });
+        let peer = node("peer");
+        let other = node("other");
+        let start = Instant::now();
+
+        assert_eq!(
+            limiter.check_push_at(&peer, 101, start),
+            Err(LimitExceeded::BytesPerPush)
+        );
+        assert!(limiter.check_push_at(&peer, 100, start).is_ok());
+        assert!(limiter.check_push_at(&peer, 100, start).is_ok());
+        assert_eq!(
+            limiter.check_push_at(&peer, 100, start + Duration::from_secs(30)),
+            Err(LimitExceeded::PushesPerMinute)
+        );
+        // limits are per peer
+        assert!(limiter.check_push_at(&other, 10, start).is_ok());
+        // the window slides
+        assert!(limiter
+            .check_push_at(&peer, 100, start + PUSH_RATE_WINDOW)
+            .is_ok());
+
+        let talkers = limiter.top_talkers(10);
+        assert_eq!(talkers.len(), 2);
+        assert_eq!(talkers[0].name, "peer");
+        assert_eq!(talkers[0].pushes, 3);
+        assert_eq!(talkers[0].bytes, 300);
+        assert_eq!(talkers[0].rejected_pushes, 2);
+        assert_eq!(talkers[1].name, "other");
+        assert_eq!(limiter.top_talkers(1).len(), 1);
+    }
+
+    #[test]
+    fn series_per_push_are_limited() {
+        let limiter = IngestionLimiter::new(IngestionLimitsConfig {
+            max_series_per_push: Some(3),
+            ..Default::default()
+        });
+        let accepted = limiter.filter(
+            &node("peer"),
+            vec![
+                metric_family("a", &["1", "2"]),
+                metric_family("b", &["1", "2"]),
+                metric_family("c", &["1"]),
+            ],
+        );
+        let names: Vec<_> = accepted.iter().map(|mf| mf.get_name()).collect();
+        assert_eq!(names, vec!["a", "c"]);
+    }
+
+    #[test]
+    fn active_series_are_limited() {
+        let limiter = IngestionLimiter::new(IngestionLimitsConfig {
+            max_active_series: Some(3),
+            active_series_window: Duration::from_secs(600),
+            ..Default::default()
+        });
+        let peer = node("peer");
+        let start = Instant::now();
+
+        let accepted = limiter.filter_at(&peer, vec![metric_family("a", &["1", "2"])], start);
+        assert_eq!(accepted.len(), 1);
+        // known series do not count against the limit
+        let accepted = limiter.filter_at(
+            &peer,
+            vec![
+                metric_family("a", &["1", "2"]),
+                metric_family("b", &["1", "2"]),
+                metric_family("c", &["1"]),
+            ],
+            start,
+        );
+        let names: Vec<_> = accepted.iter().map(|mf| mf.get_name()).collect();
+        assert_eq!(names, vec!["a", "c"]);
+        assert_eq!(limiter.top_talkers(1)[0].active_series, 3);
+        assert_eq!(limiter.top_talkers(1)[0].dropped_metric_families, 1);
+
+        // the active series are reset after the window
+        let accepted = limiter.filter_at(
+            &peer,
+            vec![metric_family("b", &["1", "2"])],
+            start + Duration::from_secs(600),
+        );
+        assert_eq!(accepted.len(), 1);
+        assert_eq!(limiter.top_talkers(1)[0].active_series, 2);
+    }
+}
@@ -5,7 +5,7 @@
 //! the walrus-proxy service acts as a relay for nodes to push metrics to and we
 //! in turn push them to a mimir cluster.
 
-use std::env;
+use std::{env, sync::Arc};
 
 use anyhow::Result;
 use clap::Parser;
@@ -14,7 +14,9 @@ use walrus_proxy::{
     admin,
     config::{load, ProxyConfig},
     consumer::Label,
+    fanout::RemoteWriteFanout,
     histogram_relay,
+    limits::IngestionLimiter,
     metrics,
     providers,
 };
@@ -56,7 +58,12 @@ async fn main() -> Result<()> {
 
     info!(
         "listen on {:?} send to {:?}",
-        config.listen_address, config.remote_write.url
+        config.listen_address,
+        config
+            .remote_write
+            .iter()
+            .map(|backend| &backend.url)
+            .collect::<Vec<_>>()
     );
 
     let listener = tokio::net::TcpListener::bind(config.listen_address)
@@ -65,9 +72,15 @@ async fn main() -> Result<()> {
     let histogram_listener = std::net::TcpListener::bind(config.histogram_address).unwrap();
     let metrics_listener = std::net::TcpListener::bind(config.metrics_address).unwrap();
 
-    let remote_write_client = admin::make_reqwest_client(config.remote_write, APP_USER_AGENT);
+    let remote_write_fanout = RemoteWriteFanout::new(
+        config.remote_write,
+        config.remote_write_buffer,
+        APP_USER_AGENT,
+    )?;
+    remote_write_fanout.start();
     let histogram_relay = histogram_relay::start_prometheus_server(histogram_listener);
-    metrics::start_prometheus_server(metrics_listener);
+    let limiter = Arc::new(IngestionLimiter::new(config.ingestion_limits));
+    metrics::start_prometheus_server(metrics_listener, limiter.clone());
 
     // setup committee provider
     let walrus_node_provider = providers::WalrusNodeProvider::new(
@@ -89,8 +102,9 @@ async fn main() -> Result<()> {
 
     let app = admin::app(
         labels,
-        remote_write_client,
+        remote_write_fanout,
         histogram_relay,
+        limiter,
         Some(walrus_node_provider),
     );
 
@@ -6,9 +6,16 @@ use std::{
     sync::{Arc, RwLock},
 };
 
-use axum::{extract::Extension, http::StatusCode, routing::get, Router};
+use axum::{
+    extract::{Extension, Query},
+    http::StatusCode,
+    routing::get,
+    Json,
+    Router,
+};
 use once_cell::sync::Lazy;
 use prometheus::{IntCounter, Opts, Registry, TextEncoder};
+use serde::Deserialize;
 use tower::ServiceBuilder;
 use tower_http::{
     trace::{DefaultOnResponse, TraceLayer},
@@ -16,6 +23,8 @@ use tower_http::{
 };
 use tracing::Level;
 
+use crate::limits::{IngestionLimiter, TalkerStats};
+
 // Walrus proxy prom registry, to avoid collisions with prometheus in some
 // shared counters from other crates, namely sui-proxy
 pub(crate) static WALRUS_PROXY_PROM_REGISTRY: Lazy<Registry> = Lazy::new(Registry::new);
@@ -39,6 +48,10 @@ macro_rules! register_metric {
 const METRICS_ROUTE: &str = "/metrics";
 const POD_HEALTH_ROUTE: &str = "/pod_health";
 const POD_LIVENESS_ROUTE: &str = "/liveness";
+const TOP_TALKERS_ROUTE: &str = "/top_talkers";
+
+/// the number of peers listed by the top talkers route by default
+const DEFAULT_TOP_TALKERS: usize = 10;
 
 type HealthCheckMetrics = Arc<RwLock<HealthCheck>>;
 
@@ -79,7 +92,8 @@ fn uptime_metric(registry: &Registry) {
 
 /// Creates a new http server that has as a sole purpose to expose
 /// and endpoint that prometheus agent can use to poll for the metrics.
-pub fn start_prometheus_server(listener: TcpListener) {
+/// It also lists the peers pushing the most data on the top talkers route.
+pub fn start_prometheus_server(listener: TcpListener, limiter: Arc<IngestionLimiter>) {
     let registry = walrus_proxy_prom_registry();
 
     uptime_metric(registry);
@@ -90,7 +104,9 @@ pub fn start_prometheus_server(listener: TcpListener) {
         .route(METRICS_ROUTE, get(metrics))
         .route(POD_HEALTH_ROUTE, get(pod_health))
         .route(POD_LIVENESS_ROUTE, get(liveness))
+        .route(TOP_TALKERS_ROUTE, get(top_talkers))
         .layer(Extension(registry))
+        .layer(Extension(limiter))
         .layer(Extension(pod_health_data.clone()))
         .layer(
             ServiceBuilder::new().layer(
@@ -165,6 +181,23 @@ async fn liveness(Extension(pod_health): Extension<HealthCheckMetrics>) -> (Stat
     }
 }
 
+/// TopTalkersQuery holds the query parameters of the top talkers route
+#[derive(Debug, Deserialize)]
+struct TopTalkersQuery {
+    /// the number of peers to list
+    limit: Option<usize>,
+}
+
+/// top_talkers lists the peers that pushed the most bytes along with their
+/// series counts and the pushes and metric families rejected by the
+/// ingestion limits
+async fn top_talkers(
+    Extension(limiter): Extension<Arc<IngestionLimiter>>,
+    Query(query): Query<TopTalkersQuery>,
+) -> Json<Vec<TalkerStats>> {
+    Json(limiter.top_talkers(query.limit.unwrap_or(DEFAULT_TOP_TALKERS)))
+}
+
 /// pod_health is called by k8s to know if this service is alive and able to respond
 async fn pod_health() -> StatusCode {
     StatusCode::OK
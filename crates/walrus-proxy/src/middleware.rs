@@ -26,7 +26,8 @@ use uuid::Uuid;
 
 use crate::{
     consumer::{Label, ProtobufDecoder},
-    providers::WalrusNodeProvider,
+    limits::IngestionLimiter,
+    providers::{NodeInfo, WalrusNodeProvider},
     register_metric,
 };
 
@@ -67,6 +68,20 @@ pub async fn expect_content_length(
     Ok(next.run(request).await)
 }
 
+/// we enforce the push limits of the peer recovered by
+/// expect_valid_recoverable_pubkey, which must run before this middleware
+pub async fn enforce_ingestion_limits(
+    Extension(limiter): Extension<Arc<IngestionLimiter>>,
+    TypedHeader(content_length): TypedHeader<ContentLength>,
+    request: Request<Body>,
+    next: Next,
+) -> Result<Response, (StatusCode, &'static str)> {
+    if let Some(peer) = request.extensions().get::<NodeInfo>() {
+        limiter.check_push(peer, content_length.0)?;
+    }
+    Ok(next.run(request).await)
+}
+
 /// AuthInfo is an intermediate type used to decode the auth header
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AuthInfo {
@@ -0,0 +1,275 @@
+// Copyright (c) Walrus Foundation
+// SPDX-License-Identifier: Apache-2.0
+use std::{fmt, str::FromStr};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+
+use crate::remote_write::{Label, TimeSeries};
+
+/// the label holding the metric name in remote write timeseries
+const METRIC_NAME_LABEL: &str = "__name__";
+
+/// LabelRegex is a regex that must match a whole label value, like the
+/// regexes of prometheus relabel configs
+#[derive(Clone, Debug, SerializeDisplay, DeserializeFromStr)]
+pub struct LabelRegex {
+    source: String,
+    regex: Regex,
+}
+
+impl LabelRegex {
+    /// is_match returns true if the regex matches the whole value
+    pub fn is_match(&self, value: &str) -> bool {
+        self.regex.is_match(value)
+    }
+}
+
+impl FromStr for LabelRegex {
+    type Err = regex::Error;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        Ok(Self {
+            source: source.to_owned(),
+            regex: Regex::new(&format!("^(?:{source})$"))?,
+        })
+    }
+}
+
+impl fmt::Display for LabelRegex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+/// RelabelRule is a rule applied to every timeseries sent to a remote write
+/// backend. Rules are applied in order.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "action", rename_all = "kebab-case")]
+pub enum RelabelRule {
+    /// set target-label to replacement if the value of source-label matches
+    /// regex. capture groups of the regex can be referenced as $1, $2, etc.
+    #[serde(rename_all = "kebab-case")]
+    Replace {
+        /// the label whose value is matched
+        source_label: String,
+        /// the regex to match against the source label value
+        regex: LabelRegex,
+        /// the label to set
+        target_label: String,
+        /// the value of the target label
+        replacement: String,
+    },
+    /// remove all labels whose name matches regex
+    #[serde(rename_all = "kebab-case")]
+    LabelDrop {
+        /// the regex to match against the label names
+        regex: LabelRegex,
+    },
+    /// drop the timeseries if the value of source-label matches regex
+    #[serde(rename_all = "kebab-case")]
+    Drop {
+        /// the label whose value is matched
+        source_label: String,
+        /// the regex to match against the source label value
+        regex: LabelRegex,
+    },
+    /// drop the timeseries unless the value of source-label matches regex
+    #[serde(rename_all = "kebab-case")]
+    Keep {
+        /// the label whose value is matched
+        source_label: String,
+        /// the regex to match against the source label value
+        regex: LabelRegex,
+    },
+}
+
+/// value of a label, missing labels have an empty value as in prometheus
+fn label_value<'a>(labels: &'a [Label], name: &str) -> &'a str {
+    labels
+        .iter()
+        .find(|label| label.name == name)
+        .map(|label| label.value.as_str())
+        .unwrap_or_default()
+}
+
+impl RelabelRule {
+    /// apply the rule to the labels of a timeseries, returns false if the
+    /// timeseries must be dropped
+    fn apply(&self, labels: &mut Vec<Label>) -> bool {
+        match self {
+            Self::Replace {
+                source_label,
+                regex,
+                target_label,
+                replacement,
+            } => {
+                let value = label_value(labels, source_label);
+                let Some(captures) = regex.regex.captures(value) else {
+                    return true;
+                };
+                let mut new_value = String::new();
+                captures.expand(replacement, &mut new_value);
+                labels.retain(|label| &label.name != target_label);
+                if !new_value.is_empty() {
+                    labels.push(Label {
+                        name: target_label.to_owned(),
+                        value: new_value,
+                    });
+                }
+                true
+            }
+            Self::LabelDrop { regex } => {
+                labels.retain(|label| !regex.is_match(&label.name));
+                true
+            }
+            Self::Drop {
+                source_label,
+                regex,
+            } => !regex.is_match(label_value(labels, source_label)),
+            Self::Keep {
+                source_label,
+                regex,
+            } => regex.is_match(label_value(labels, source_label)),
+        }
+    }
+}
+
+/// RemoteWriteRules hold the filtering and relabeling rules of a remote
+/// write backend
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct RemoteWriteRules {
+    /// if not empty, only metrics whose name matches one of these regexes are
+    /// sent to the backend
+    #[serde(default)]
+    pub allow: Vec<LabelRegex>,
+    /// metrics whose name matches one of these regexes are not sent to the
+    /// backend. drop rules take precedence over allow rules
+    #[serde(default)]
+    pub drop: Vec<LabelRegex>,
+    /// relabel rules applied to the allowed metrics
+    #[serde(default)]
+    pub relabel: Vec<RelabelRule>,
+}
+
+impl RemoteWriteRules {
+    /// is_empty returns true if the rules do not alter any timeseries
+    pub fn is_empty(&self) -> bool {
+        self.allow.is_empty() && self.drop.is_empty() && self.relabel.is_empty()
+    }
+
+    /// apply the rules to a timeseries, returns None if the timeseries must
+    /// not be sent to the backend
+    pub fn apply(&self, mut timeseries: TimeSeries) -> Option<TimeSeries> {
+        let name = label_value(&timeseries.labels, METRIC_NAME_LABEL);
+        if !self.allow.is_empty() && !self.allow.iter().any(|regex| regex.is_match(name)) {
+            return None;
+        }
+        if self.drop.iter().any(|regex| regex.is_match(name)) {
+            return None;
+        }
+        if self.relabel.is_empty() {
+            return Some(timeseries);
+        }
+        for rule in &self.relabel {
+            if !rule.apply(&mut timeseries.labels) {
+                return None;
+            }
+        }
+        // remote write requires the labels to be sorted by name
+        timeseries.labels.sort_by(|a, b| a.name.cmp(&b.name));
+        Some(timeseries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::remote_write::Sample;
+
+    fn timeseries(labels: Vec<(&str, &str)>) -> TimeSeries {
+        TimeSeries {
+            labels: labels
+                .into_iter()
+                .map(|(name, value)| Label {
+                    name: name.into(),
+                    value: value.into(),
+                })
+                .collect(),
+            samples: vec![Sample {
+                value: 1.0,
+                timestamp: 12345,
+            }],
+            ..Default::default()
+        }
+    }
+
+    fn rules(yaml: &str) -> RemoteWriteRules {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn allow_and_drop_match_metric_names() {
+        let rules = rules(
+            r#"
+allow: ["walrus_.*"]
+drop: ["walrus_debug_.*"]
+"#,
+        );
+        assert!(rules
+            .apply(timeseries(vec![("__name__", "walrus_uptime")]))
+            .is_some());
+        assert!(rules
+            .apply(timeseries(vec![("__name__", "walrus_debug_events")]))
+            .is_none());
+        assert!(rules
+            .apply(timeseries(vec![("__name__", "process_walrus_uptime")]))
+            .is_none());
+    }
+
+    #[test]
+    fn relabel_rules_are_applied_in_order() {
+        let rules = rules(
+            r#"
+relabel:
+  - action: replace
+    source-label: host
+    regex: "(.*)\\.walrus\\.space"
+    target-label: operator
+    replacement: "$1"
+  - action: label-drop
+    regex: "host|network"
+  - action: drop
+    source-label: operator
+    regex: "ignored"
+"#,
+        );
+        let relabeled = rules
+            .apply(timeseries(vec![
+                ("__name__", "walrus_uptime"),
+                ("host", "node-1.walrus.space"),
+                ("network", "testnet"),
+            ]))
+            .unwrap();
+        assert_eq!(
+            relabeled.labels,
+            timeseries(vec![("__name__", "walrus_uptime"), ("operator", "node-1")]).labels
+        );
+        assert!(rules
+            .apply(timeseries(vec![
+                ("__name__", "walrus_uptime"),
+                ("host", "ignored.walrus.space"),
+            ]))
+            .is_none());
+    }
+
+    #[test]
+    fn regexes_match_whole_values() {
+        let regex: LabelRegex = "walrus".parse().unwrap();
+        assert!(regex.is_match("walrus"));
+        assert!(!regex.is_match("walrus_uptime"));
+        assert_eq!(regex.to_string(), "walrus");
+    }
+}
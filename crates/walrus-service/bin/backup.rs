@@ -67,8 +67,9 @@ fn main() {
     let rt = tokio::runtime::Runtime::new().expect("creating tokio runtime");
     let _guard = rt.enter();
 
-    let metrics_runtime = MetricsAndLoggingRuntime::new(config.metrics_address, None)
-        .expect("starting metrics runtime");
+    let metrics_runtime =
+        MetricsAndLoggingRuntime::new(config.metrics_address, None, config.trace_export.as_ref())
+            .expect("starting metrics runtime");
 
     // Run migrations before starting the backup node.
     run_backup_database_migrations(&config);
@@ -9,10 +9,9 @@ use anyhow::Result;
 use clap::Parser;
 use serde::Deserialize;
 use walrus_service::{
-    client::cli::{error, App, ClientCommandRunner, Commands},
+    client::cli::{print_cli_error, App, ClientCommandRunner, Commands},
     utils::{self, MetricsAndLoggingRuntime},
 };
-use walrus_sui::client::retry_client::RetriableRpcError;
 
 /// The version of the Walrus client.
 pub const VERSION: &str = walrus_service::utils::version!();
@@ -41,7 +40,9 @@ fn client() -> Result<()> {
         &app.wallet,
         app.gas_budget,
         app.json,
-    );
+    )
+    .with_unsigned_tx_out(app.unsigned_tx_out)
+    .with_dry_run(app.dry_run);
 
     // Drop the temporary tracing subscriber, as the global ones are about to be initialized.
     drop(subscriber_guard);
@@ -54,7 +55,8 @@ fn client() -> Result<()> {
         Commands::Daemon(command) => {
             let metrics_address = command.get_metrics_address();
 
-            let runtime = MetricsAndLoggingRuntime::start(metrics_address)?;
+            let runtime =
+                MetricsAndLoggingRuntime::start(metrics_address, runner.trace_export_config())?;
             utils::export_build_info(&runtime.registry, VERSION);
 
             tracing::debug!(%metrics_address, "started metrics and logging on separate runtime");
@@ -68,14 +70,7 @@ fn client() -> Result<()> {
 /// The CLI entrypoint.
 pub fn main() -> ExitCode {
     if let Err(err) = client() {
-        // Print any error in a (relatively) user-friendly way.
-        let error_str = if err.is_retriable_rpc_error() {
-            "The Sui full node RPC seems to be overwhelmed by too many requests. \
-            Please try with another full node, or try again later.\nError: "
-        } else {
-            ""
-        };
-        eprintln!("{} {}{:#}", error(), error_str, err);
+        print_cli_error(&err);
         return ExitCode::FAILURE;
     }
     ExitCode::SUCCESS
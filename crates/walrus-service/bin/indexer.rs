@@ -0,0 +1,64 @@
+// Copyright (c) Walrus Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Walrus blob metadata indexer entry point.
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use walrus_service::{
+    backup::{run_indexer_database_migrations, start_blob_indexer, IndexerConfig, VERSION},
+    common::utils::MetricsAndLoggingRuntime,
+    utils::load_from_yaml,
+};
+
+/// Run the Walrus blob metadata indexer and serve its REST API.
+#[derive(Parser)]
+#[clap(rename_all = "kebab-case")]
+#[clap(name = env!("CARGO_BIN_NAME"))]
+#[clap(version = VERSION)]
+#[derive(Debug)]
+struct Args {
+    #[clap(long, short, help = "Specify the config file path to use")]
+    config: PathBuf,
+    #[clap(
+        long,
+        short,
+        help = "Override the metrics address to use (ie: 127.0.0.1:9185)"
+    )]
+    metrics_address: Option<std::net::SocketAddr>,
+    #[clap(
+        long,
+        short,
+        help = "Override the address on which to serve the REST API (ie: 0.0.0.0:31416)"
+    )]
+    api_address: Option<std::net::SocketAddr>,
+}
+
+fn main() {
+    let args = Args::parse();
+    let mut config: IndexerConfig = load_from_yaml(&args.config).expect("loading config from yaml");
+    if let Some(metrics_address) = args.metrics_address {
+        config.metrics_address = metrics_address;
+    }
+    if let Some(api_address) = args.api_address {
+        config.api_address = api_address;
+    }
+
+    let rt = tokio::runtime::Runtime::new().expect("creating tokio runtime");
+    let _guard = rt.enter();
+
+    let metrics_runtime = MetricsAndLoggingRuntime::new(config.metrics_address, None, None)
+        .expect("starting metrics runtime");
+
+    // Run migrations before starting the indexer.
+    run_indexer_database_migrations(&config);
+
+    rt.block_on(async move {
+        if let Err(error) = start_blob_indexer(config, &metrics_runtime).await {
+            tracing::error!(?error, "encountered error");
+        }
+        tracing::error!("blob indexer exited prematurely");
+        std::process::exit(1);
+    });
+}
@@ -507,7 +507,8 @@ mod commands {
             }
         }
 
-        let metrics_runtime = MetricsAndLoggingRuntime::start(config.metrics_address)?;
+        let metrics_runtime =
+            MetricsAndLoggingRuntime::start(config.metrics_address, config.trace_export.as_ref())?;
         let registry_clone = metrics_runtime.registry.clone();
         metrics_runtime
             .runtime
@@ -898,6 +899,8 @@ mod commands {
                 rpc_fallback_config: rpc_fallback_config_args
                     .clone()
                     .and_then(|args| args.to_config()),
+                additional_rpc_endpoints: Default::default(),
+                rpc_failover_config: Default::default(),
             }),
             tls: TlsConfig {
                 certificate_path,
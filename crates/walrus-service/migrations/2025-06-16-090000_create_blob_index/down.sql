@@ -0,0 +1,3 @@
+DROP TABLE indexer_cursor;
+DROP TABLE indexed_blob_attribute;
+DROP TABLE indexed_blob;
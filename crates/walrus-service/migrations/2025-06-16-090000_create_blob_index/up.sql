@@ -0,0 +1,73 @@
+-- Initialize the tables of the blob metadata indexer.
+
+CREATE TABLE indexed_blob (
+    -- The object ID of the Sui blob object.
+    object_id            BYTEA     NOT NULL,
+    -- The Walrus Blob ID.
+    blob_id              BYTEA     NOT NULL,
+    -- The address owning the blob object, if it is owned by an address.
+    owner                BYTEA         NULL,
+    -- The unencoded size of the blob.
+    size                 BIGINT    NOT NULL,
+    -- The encoding type of the blob.
+    encoding_type        SMALLINT  NOT NULL,
+    -- Whether the blob is deletable.
+    deletable            BOOLEAN   NOT NULL,
+    -- The epoch in which the blob was registered.
+    registered_epoch     BIGINT    NOT NULL,
+    -- The epoch in which the blob was first certified.
+    certified_epoch      BIGINT        NULL,
+    -- The first epoch in which the blob is no longer stored.
+    end_epoch            BIGINT    NOT NULL,
+    -- The epoch in which the blob object was deleted.
+    deleted_epoch        BIGINT        NULL,
+    -- The epoch in which the blob ID was marked as invalid.
+    invalidated_epoch    BIGINT        NULL,
+    -- Digest of the transaction that emitted the last event for the blob.
+    last_tx_digest       BYTEA     NOT NULL,
+    -- When the owner and the attribute of the blob object were last read from Sui.
+    refreshed_at         TIMESTAMP WITH TIME ZONE     NULL,
+    PRIMARY KEY (object_id),
+    CONSTRAINT valid_object_id
+    CHECK (LENGTH(object_id) = 32),
+    CONSTRAINT valid_indexed_blob_id
+    CHECK (LENGTH(blob_id) = 32)
+);
+
+CREATE INDEX indexed_blob_blob_id
+    ON indexed_blob (blob_id);
+
+CREATE INDEX indexed_blob_owner
+    ON indexed_blob (owner)
+    WHERE deleted_epoch IS NULL;
+
+CREATE INDEX indexed_blob_end_epoch
+    ON indexed_blob (end_epoch)
+    WHERE deleted_epoch IS NULL;
+
+CREATE INDEX indexed_blob_refresh
+    ON indexed_blob (refreshed_at NULLS FIRST)
+    WHERE deleted_epoch IS NULL;
+
+CREATE TABLE indexed_blob_attribute (
+    -- The object ID of the Sui blob object.
+    object_id BYTEA NOT NULL REFERENCES indexed_blob (object_id) ON DELETE CASCADE,
+    -- The key of the attribute pair.
+    key       TEXT  NOT NULL,
+    -- The value of the attribute pair.
+    value     TEXT  NOT NULL,
+    PRIMARY KEY (object_id, key)
+);
+
+CREATE INDEX indexed_blob_attribute_key_value
+    ON indexed_blob_attribute (key, value);
+
+CREATE TABLE indexer_cursor (
+    -- There is a single row in this table.
+    id                 BOOLEAN NOT NULL DEFAULT TRUE,
+    -- Index since first Walrus event of the next event to index.
+    next_element_index BIGINT  NOT NULL,
+    PRIMARY KEY (id),
+    CONSTRAINT single_row
+    CHECK (id)
+);
@@ -35,8 +35,30 @@
 //! backup archive might live longer than necessary. This is acceptable as the backup service is a
 //! last resort and does not need to be as efficient as the storage nodes themselves.
 //!
+//! The same machinery is used by the blob metadata indexer, which records the blob objects, their
+//! owners, and their attributes in the `indexed_blob` and `indexed_blob_attribute` tables, and
+//! serves them through a REST API. See [`IndexerClient`] for a client of that API.
+//!
 mod config;
-pub use config::{BackupConfig, BACKUP_BLOB_ARCHIVE_SUBDIR};
+pub use config::{BackupConfig, IndexerConfig, BACKUP_BLOB_ARCHIVE_SUBDIR};
+
+mod indexer_client;
+pub use indexer_client::{
+    BlobPage,
+    BlobQuery,
+    IndexedBlob,
+    IndexerClient,
+    IndexerStatus,
+    INDEXER_BLOBS_ENDPOINT,
+    INDEXER_BLOB_ENDPOINT,
+    INDEXER_STATUS_ENDPOINT,
+};
+
+#[cfg(feature = "backup")]
+mod indexer;
+
+#[cfg(feature = "backup")]
+mod indexer_api;
 
 #[cfg(feature = "backup")]
 mod garbage_collector;
@@ -56,8 +78,10 @@ mod metrics;
 #[cfg(feature = "backup")]
 pub use self::{
     garbage_collector::start_backup_garbage_collector,
+    indexer::start_blob_indexer,
     service::{
         run_backup_database_migrations,
+        run_indexer_database_migrations,
         start_backup_fetcher,
         start_backup_orchestrator,
         VERSION,
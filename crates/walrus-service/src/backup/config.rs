@@ -8,7 +8,10 @@ use std::{net::SocketAddr, path::PathBuf, time::Duration};
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DurationMilliSeconds, DurationSeconds};
 
-use crate::{common::config::SuiReaderConfig, node::events::EventProcessorConfig};
+use crate::{
+    common::config::{SuiReaderConfig, TraceExportConfig},
+    node::events::EventProcessorConfig,
+};
 
 /// The subdirectory in which to store the backup blobs when running without remote storage.
 pub const BACKUP_BLOB_ARCHIVE_SUBDIR: &str = "archive";
@@ -64,6 +67,9 @@ pub struct BackupConfig {
     /// Socket address on which the Prometheus server should export its metrics.
     #[serde(default = "defaults::metrics_address")]
     pub metrics_address: SocketAddr,
+    /// Configuration for exporting traces to an OpenTelemetry collector.
+    #[serde(default)]
+    pub trace_export: Option<TraceExportConfig>,
     /// Sui config for the node
     pub sui: SuiReaderConfig,
     /// Configuration for the event processor.
@@ -117,6 +123,7 @@ impl BackupConfig {
             blob_upload_timeout: defaults::blob_upload_timeout(),
             blob_delete_timeout: defaults::blob_delete_timeout(),
             metrics_address: defaults::metrics_address(),
+            trace_export: None,
             sui,
             event_processor_config: Default::default(),
             db_config: BackupDbConfig {
@@ -133,6 +140,51 @@ impl BackupConfig {
     }
 }
 
+/// Configuration of the Walrus blob metadata indexer.
+#[serde_as]
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct IndexerConfig {
+    /// Directory in which to persist the event processor database.
+    #[serde(deserialize_with = "walrus_utils::config::resolve_home_dir")]
+    pub indexer_storage_path: PathBuf,
+    /// Database configuration.
+    ///
+    /// The indexer can share the database with the backup orchestrator, as it uses separate
+    /// tables.
+    #[serde(flatten)]
+    pub db_config: BackupDbConfig,
+    /// Socket address on which the Prometheus server should export its metrics.
+    #[serde(default = "defaults::indexer_metrics_address")]
+    pub metrics_address: SocketAddr,
+    /// Socket address on which the REST API of the indexer is served.
+    #[serde(default = "defaults::indexer_api_address")]
+    pub api_address: SocketAddr,
+    /// Sui config for the indexer.
+    pub sui: SuiReaderConfig,
+    /// Configuration for the event processor.
+    #[serde(default, skip_serializing_if = "defaults::is_default")]
+    pub event_processor_config: EventProcessorConfig,
+    /// How often the owner and attribute of each live blob object are re-read from Sui.
+    ///
+    /// Changes of the attribute and transfers of the blob object do not emit Walrus events, so
+    /// they are only picked up when the blob object is refreshed.
+    #[serde_as(as = "DurationSeconds<u64>")]
+    #[serde(
+        rename = "refresh_interval_seconds",
+        default = "defaults::indexer_refresh_interval"
+    )]
+    pub refresh_interval: Duration,
+    /// The maximum number of blob objects refreshed at a time.
+    #[serde(default = "defaults::indexer_refresh_batch_size")]
+    pub refresh_batch_size: u32,
+    /// The maximum number of blob objects returned by a single API request.
+    #[serde(default = "defaults::indexer_max_page_size")]
+    pub max_page_size: usize,
+    /// The maximum number of database connections used to serve API requests.
+    #[serde(default = "defaults::indexer_api_db_pool_size")]
+    pub api_db_pool_size: u32,
+}
+
 /// Backup-related default values.
 pub mod defaults {
     use std::{
@@ -148,6 +200,42 @@ pub mod defaults {
         (Ipv4Addr::LOCALHOST, METRICS_PORT).into()
     }
 
+    /// Default indexer metrics port.
+    pub const INDEXER_METRICS_PORT: u16 = 10185;
+
+    /// Default indexer REST API port.
+    pub const INDEXER_API_PORT: u16 = 31416;
+
+    /// Returns the default metrics address of the indexer.
+    pub fn indexer_metrics_address() -> SocketAddr {
+        (Ipv4Addr::LOCALHOST, INDEXER_METRICS_PORT).into()
+    }
+
+    /// Returns the default REST API address of the indexer.
+    pub fn indexer_api_address() -> SocketAddr {
+        (Ipv4Addr::UNSPECIFIED, INDEXER_API_PORT).into()
+    }
+
+    /// Default interval between refreshes of the same blob object in the indexer.
+    pub fn indexer_refresh_interval() -> Duration {
+        Duration::from_secs(60 * 60)
+    }
+
+    /// Default number of blob objects refreshed at a time by the indexer.
+    pub fn indexer_refresh_batch_size() -> u32 {
+        100
+    }
+
+    /// Default maximum number of blob objects returned by a single indexer API request.
+    pub fn indexer_max_page_size() -> usize {
+        1000
+    }
+
+    /// Default maximum number of database connections of the indexer API.
+    pub fn indexer_api_db_pool_size() -> u32 {
+        10
+    }
+
     /// Returns true iff the value is the default and we don't run in test mode.
     pub fn is_default<T: PartialEq + Default>(t: &T) -> bool {
         // The `cfg!(test)` check is there to allow serializing the full configuration, specifically
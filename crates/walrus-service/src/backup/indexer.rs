@@ -0,0 +1,550 @@
+// Copyright (c) Walrus Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Blob metadata indexer.
+//!
+//! The indexer consumes the Walrus events from the checkpoint-based event processor, like the
+//! backup orchestrator, and maintains the `indexed_blob` table with one row per blob object. The
+//! registration, certification, extension, and deletion of blob objects, as well as the
+//! invalidation of blob IDs, are recorded directly from the events.
+//!
+//! The owner of a blob object and its `BlobAttribute` dynamic field are not part of the Walrus
+//! events. They are read from Sui by a separate refresh loop, once the blob object has been
+//! registered or certified, and then periodically for all blob objects that are not deleted.
+//!
+//! The indexed data is served through the REST API in [`super::indexer_api`].
+use std::{panic::Location, pin::Pin, sync::Arc, time::Duration};
+
+use anyhow::{bail, ensure, Context as _, Result};
+use diesel::{
+    result::Error,
+    sql_types::{Bool, Bytea, Int2, Int8},
+    ExpressionMethods as _,
+    QueryDsl as _,
+};
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncPgConnection, RunQueryDsl as _};
+use futures::{stream, StreamExt};
+use prometheus::core::{AtomicU64, GenericCounter};
+use sui_sdk::rpc_types::SuiObjectDataOptions;
+use sui_types::{base_types::ObjectID, object::Owner};
+use tokio_util::sync::CancellationToken;
+use walrus_sui::{
+    client::{
+        retry_client::{RetriableSuiClient, MULTI_GET_OBJ_LIMIT},
+        ReadClient as _,
+        SuiReadClient,
+    },
+    types::{
+        move_structs::BlobAttribute,
+        BlobEvent,
+        ContractEvent,
+        EpochChangeEvent,
+        EpochChangeStart,
+    },
+};
+
+use super::{
+    config::{BackupDbConfig, IndexerConfig},
+    indexer_api,
+    metrics::IndexerMetricSet,
+    models::{self, IndexedBlobAttributeRow, ObjectIdRow},
+    schema,
+    service::{establish_connection_async, retry_serializable_query, VERSION},
+};
+use crate::{
+    common::utils::{self, MetricsAndLoggingRuntime},
+    node::{
+        events::{
+            event_processor::EventProcessor,
+            event_processor_runtime::EventProcessorRuntime,
+            EventStreamElement,
+            PositionedStreamEvent,
+        },
+        metrics::TelemetryLabel as _,
+    },
+};
+
+/// The maximum time the refresh loop sleeps when there are no blob objects to refresh.
+const MAX_IDLE_REFRESH_SLEEP_TIME: Duration = Duration::from_secs(10);
+
+/// Starts the blob metadata indexer, consisting of the event indexing, the refresh of the blob
+/// objects, and the REST API.
+pub async fn start_blob_indexer(
+    config: IndexerConfig,
+    metrics_runtime: &MetricsAndLoggingRuntime,
+) -> Result<()> {
+    tracing::info!(?config, "starting blob indexer");
+
+    let registry_clone = metrics_runtime.registry.clone();
+    tokio::spawn(async move {
+        registry_clone
+            .register(mysten_metrics::uptime_metric(
+                "walrus_indexer",
+                VERSION,
+                "walrus",
+            ))
+            .unwrap();
+    });
+
+    tracing::info!(version = VERSION, "Walrus indexer binary version");
+    tracing::info!(
+        metrics_address = %config.metrics_address, "started Prometheus HTTP endpoint",
+    );
+
+    utils::export_build_info(&metrics_runtime.registry, VERSION);
+
+    let cancel_token = CancellationToken::new();
+    let event_processor = EventProcessorRuntime::start_async(
+        config.sui.clone(),
+        config.event_processor_config.clone(),
+        &config.indexer_storage_path,
+        &metrics_runtime.registry,
+        cancel_token.child_token(),
+    )
+    .await?;
+    let sui_read_client = SuiReadClient::new(
+        RetriableSuiClient::new_for_rpc(&config.sui.rpc, config.sui.backoff_config.clone())
+            .await
+            .context("[blob_indexer] cannot create RetriableSuiClient")?,
+        &config.sui.contract_config,
+    )
+    .await
+    .context("[blob_indexer] cannot create SuiReadClient")?;
+    let metric_set = IndexerMetricSet::new(&metrics_runtime.registry);
+
+    let result = tokio::select! {
+        result = index_events(event_processor, &config.db_config, &metric_set) => result,
+        result = refresh_blob_objects(&sui_read_client, &config, &metric_set) => result,
+        result = indexer_api::serve(&config) => result,
+    };
+    cancel_token.cancel();
+    result
+}
+
+async fn index_events(
+    event_processor: Arc<EventProcessor>,
+    db_config: &BackupDbConfig,
+    metric_set: &IndexerMetricSet,
+) -> Result<()> {
+    let mut pg_connection =
+        establish_connection_async(&db_config.database_url, "db connect for index_events").await?;
+
+    let event_cursor = models::get_indexer_cursor(&mut pg_connection).await?;
+    tracing::info!(?event_cursor, "[index_events] starting");
+    let event_stream = Pin::from(event_processor.events(event_cursor).await?);
+    let index_stream = stream::iter(event_cursor.element_index..);
+    let mut indexed_element_stream = index_stream.zip(event_stream);
+    let retry_counter = metric_set
+        .db_serializability_retries
+        .with_label_values(&["index_event"]);
+    while let Some((element_index, PositionedStreamEvent { element, .. })) =
+        indexed_element_stream.next().await
+    {
+        let EventStreamElement::ContractEvent(contract_event) = &element else {
+            // Checkpoint boundaries are not relevant for the indexer.
+            continue;
+        };
+        let tx_digest = element
+            .event_id()
+            .expect("contract events have an event ID")
+            .tx_digest
+            .into_inner();
+        index_event(
+            &mut pg_connection,
+            contract_event,
+            tx_digest,
+            element_index,
+            db_config,
+            &retry_counter,
+            &metric_set.db_reconnects,
+        )
+        .await?;
+        metric_set
+            .indexer_events_indexed
+            .with_label_values(&[contract_event.label()])
+            .inc();
+    }
+
+    bail!("event stream for the indexer stopped")
+}
+
+/// Records the event and advances the indexer cursor past it, in a single transaction.
+async fn index_event(
+    pg_connection: &mut AsyncPgConnection,
+    contract_event: &ContractEvent,
+    tx_digest: [u8; 32],
+    element_index: u64,
+    db_config: &BackupDbConfig,
+    retry_counter: &GenericCounter<AtomicU64>,
+    db_reconnects: &GenericCounter<AtomicU64>,
+) -> Result<(), Error> {
+    let next_element_index =
+        i64::try_from(element_index + 1).expect("element_index should fit into an i64");
+    retry_serializable_query(
+        pg_connection,
+        Location::caller(),
+        db_config,
+        retry_counter,
+        db_reconnects,
+        |conn| {
+            async move {
+                dispatch_contract_event(contract_event, &tx_digest, conn).await?;
+                diesel::sql_query(
+                    "
+                    INSERT INTO indexer_cursor (id, next_element_index) VALUES (TRUE, $1)
+                    ON CONFLICT (id) DO UPDATE SET next_element_index = $1",
+                )
+                .bind::<Int8, _>(next_element_index)
+                .execute(conn)
+                .await?;
+                Ok(())
+            }
+            .scope_boxed()
+        },
+    )
+    .await
+}
+
+async fn dispatch_contract_event(
+    contract_event: &ContractEvent,
+    tx_digest: &[u8; 32],
+    conn: &mut AsyncPgConnection,
+) -> Result<(), Error> {
+    match contract_event {
+        ContractEvent::BlobEvent(BlobEvent::Registered(registered)) => {
+            diesel::sql_query(
+                "
+                INSERT INTO indexed_blob (
+                    object_id,
+                    blob_id,
+                    size,
+                    encoding_type,
+                    deletable,
+                    registered_epoch,
+                    end_epoch,
+                    last_tx_digest
+                )
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                ON CONFLICT (object_id) DO NOTHING",
+            )
+            .bind::<Bytea, _>(registered.object_id.to_vec())
+            .bind::<Bytea, _>(registered.blob_id.0.to_vec())
+            .bind::<Int8, _>(i64::try_from(registered.size).expect("blob sizes fit into an i64"))
+            .bind::<Int2, _>(i16::from(u8::from(registered.encoding_type)))
+            .bind::<Bool, _>(registered.deletable)
+            .bind::<Int8, _>(i64::from(registered.epoch))
+            .bind::<Int8, _>(i64::from(registered.end_epoch))
+            .bind::<Bytea, _>(tx_digest.to_vec())
+            .execute(conn)
+            .await?;
+        }
+        ContractEvent::BlobEvent(BlobEvent::Certified(certified)) => {
+            // Certification and extension both emit this event. The blob object is refreshed
+            // afterwards, as the attribute is typically set in the same transaction.
+            let affected_rows = diesel::sql_query(
+                "
+                UPDATE indexed_blob SET
+                    certified_epoch = COALESCE(certified_epoch, $2),
+                    end_epoch = GREATEST(end_epoch, $3),
+                    last_tx_digest = $4,
+                    refreshed_at = NULL
+                WHERE object_id = $1",
+            )
+            .bind::<Bytea, _>(certified.object_id.to_vec())
+            .bind::<Int8, _>(i64::from(certified.epoch))
+            .bind::<Int8, _>(i64::from(certified.end_epoch))
+            .bind::<Bytea, _>(tx_digest.to_vec())
+            .execute(conn)
+            .await?;
+            if affected_rows == 0 {
+                tracing::warn!(
+                    object_id = %certified.object_id,
+                    "certified blob object was not registered in the index"
+                );
+            }
+        }
+        ContractEvent::BlobEvent(BlobEvent::Deleted(deleted)) => {
+            diesel::sql_query(
+                "
+                UPDATE indexed_blob SET
+                    deleted_epoch = $2,
+                    owner = NULL,
+                    last_tx_digest = $3
+                WHERE object_id = $1",
+            )
+            .bind::<Bytea, _>(deleted.object_id.to_vec())
+            .bind::<Int8, _>(i64::from(deleted.epoch))
+            .bind::<Bytea, _>(tx_digest.to_vec())
+            .execute(conn)
+            .await?;
+            diesel::delete(
+                schema::indexed_blob_attribute::table.filter(
+                    schema::indexed_blob_attribute::object_id.eq(deleted.object_id.to_vec()),
+                ),
+            )
+            .execute(conn)
+            .await?;
+        }
+        ContractEvent::BlobEvent(BlobEvent::InvalidBlobID(invalid)) => {
+            diesel::sql_query(
+                "
+                UPDATE indexed_blob SET
+                    invalidated_epoch = COALESCE(invalidated_epoch, $2)
+                WHERE blob_id = $1",
+            )
+            .bind::<Bytea, _>(invalid.blob_id.0.to_vec())
+            .bind::<Int8, _>(i64::from(invalid.epoch))
+            .execute(conn)
+            .await?;
+        }
+        ContractEvent::EpochChangeEvent(EpochChangeEvent::EpochChangeStart(EpochChangeStart {
+            epoch,
+            ..
+        })) => {
+            // The table is shared with the backup orchestrator, if both use the same database.
+            diesel::sql_query(
+                "
+                INSERT INTO epoch_change_start_event (epoch) VALUES ($1)
+                ON CONFLICT DO NOTHING",
+            )
+            .bind::<Int8, _>(i64::from(*epoch))
+            .execute(conn)
+            .await?;
+        }
+        event => {
+            tracing::debug!(?event, "ignoring event");
+        }
+    }
+    Ok(())
+}
+
+/// Continuously re-reads the owner and the attribute of the blob objects that were not refreshed
+/// within the configured refresh interval, starting with those that were never refreshed.
+async fn refresh_blob_objects(
+    sui_read_client: &SuiReadClient,
+    config: &IndexerConfig,
+    metric_set: &IndexerMetricSet,
+) -> Result<()> {
+    let mut conn = establish_connection_async(
+        &config.db_config.database_url,
+        "db connect for refresh_blob_objects",
+    )
+    .await?;
+    let refresh_interval_seconds =
+        i64::try_from(config.refresh_interval.as_secs()).expect("refresh_interval config overflow");
+    let retry_counter = metric_set
+        .db_serializability_retries
+        .with_label_values(&["refresh_blob_object"]);
+
+    loop {
+        let rows: Vec<ObjectIdRow> = diesel::sql_query(
+            "
+            SELECT object_id FROM indexed_blob
+            WHERE
+                deleted_epoch IS NULL
+                AND (refreshed_at IS NULL
+                    OR refreshed_at < NOW() - $1 * INTERVAL '1 second')
+            ORDER BY refreshed_at ASC NULLS FIRST
+            LIMIT $2",
+        )
+        .bind::<Int8, _>(refresh_interval_seconds)
+        .bind::<Int8, _>(i64::from(config.refresh_batch_size))
+        .get_results(&mut conn)
+        .await?;
+
+        if rows.is_empty() {
+            tokio::time::sleep(config.refresh_interval.min(MAX_IDLE_REFRESH_SLEEP_TIME)).await;
+            continue;
+        }
+
+        for rows in rows.chunks(MULTI_GET_OBJ_LIMIT) {
+            let object_ids = rows
+                .iter()
+                .map(|row| ObjectID::from_bytes(&row.object_id))
+                .collect::<Result<Vec<_>, _>>()
+                .context("bad object_id found in db")?;
+            // On errors, the blob objects are still marked as refreshed, to retry them only after
+            // the refresh interval instead of retrying them continuously.
+            let owners = match read_blob_owners(sui_read_client, &object_ids).await {
+                Ok(owners) => owners,
+                Err(error) => {
+                    metric_set
+                        .indexer_refresh_errors
+                        .inc_by(u64::try_from(rows.len()).expect("the batch size fits into a u64"));
+                    tracing::warn!(?error, "failed to read the owners of blob objects");
+                    for row in rows {
+                        mark_refreshed(&mut conn, &row.object_id).await?;
+                    }
+                    continue;
+                }
+            };
+
+            for ((row, object_id), owner) in rows.iter().zip(object_ids).zip(owners) {
+                // Blob objects that are not owned by an address have no attribute to index.
+                let attributes = if owner.is_some() {
+                    match sui_read_client.get_blob_attribute(&object_id).await {
+                        Ok(attribute) => attribute
+                            .map(|attribute| attribute_rows(object_id, &attribute))
+                            .unwrap_or_default(),
+                        Err(error) => {
+                            metric_set.indexer_refresh_errors.inc();
+                            tracing::warn!(
+                                ?error,
+                                %object_id,
+                                "failed to read the attribute of the blob object"
+                            );
+                            mark_refreshed(&mut conn, &row.object_id).await?;
+                            continue;
+                        }
+                    }
+                } else {
+                    vec![]
+                };
+                metric_set.indexer_blobs_refreshed.inc();
+                store_blob_object_state(
+                    &mut conn,
+                    &row.object_id,
+                    owner,
+                    &attributes,
+                    &config.db_config,
+                    &retry_counter,
+                    &metric_set.db_reconnects,
+                )
+                .await?;
+            }
+        }
+    }
+}
+
+/// Returns the addresses owning the given blob objects, in the same order as the object IDs.
+///
+/// Blob objects that have been burned or wrapped, e.g., in a shared blob, have no owner.
+async fn read_blob_owners(
+    sui_read_client: &SuiReadClient,
+    object_ids: &[ObjectID],
+) -> Result<Vec<Option<Vec<u8>>>> {
+    let responses = sui_read_client
+        .sui_client()
+        .multi_get_object_with_options(
+            object_ids.to_vec(),
+            SuiObjectDataOptions::new().with_owner(),
+        )
+        .await?;
+    ensure!(
+        responses.len() == object_ids.len(),
+        "expected {} objects from the RPC node, got {}",
+        object_ids.len(),
+        responses.len()
+    );
+    Ok(responses
+        .into_iter()
+        .map(|response| match response.data.and_then(|data| data.owner) {
+            Some(Owner::AddressOwner(address)) => Some(address.to_vec()),
+            _ => None,
+        })
+        .collect())
+}
+
+/// Returns the rows recording the key-value pairs of the attribute of the blob object.
+fn attribute_rows(object_id: ObjectID, attribute: &BlobAttribute) -> Vec<IndexedBlobAttributeRow> {
+    attribute
+        .iter()
+        .map(|(key, value)| IndexedBlobAttributeRow {
+            object_id: object_id.to_vec(),
+            key: key.clone(),
+            value: value.clone(),
+        })
+        .collect()
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn store_blob_object_state(
+    conn: &mut AsyncPgConnection,
+    object_id: &[u8],
+    owner: Option<Vec<u8>>,
+    attributes: &[IndexedBlobAttributeRow],
+    db_config: &BackupDbConfig,
+    retry_counter: &GenericCounter<AtomicU64>,
+    db_reconnects: &GenericCounter<AtomicU64>,
+) -> Result<(), Error> {
+    use schema::{indexed_blob::dsl as blob_dsl, indexed_blob_attribute::dsl as attribute_dsl};
+
+    retry_serializable_query(
+        conn,
+        Location::caller(),
+        db_config,
+        retry_counter,
+        db_reconnects,
+        |conn| {
+            let owner = owner.clone();
+            async move {
+                // The blob object may have been deleted since it was selected for the refresh.
+                let affected_rows = diesel::update(
+                    blob_dsl::indexed_blob
+                        .filter(blob_dsl::object_id.eq(object_id))
+                        .filter(blob_dsl::deleted_epoch.is_null()),
+                )
+                .set((
+                    blob_dsl::owner.eq(owner),
+                    blob_dsl::refreshed_at.eq(diesel::dsl::now),
+                ))
+                .execute(conn)
+                .await?;
+                if affected_rows == 0 {
+                    return Ok(());
+                }
+                diesel::delete(attribute_dsl::indexed_blob_attribute)
+                    .filter(attribute_dsl::object_id.eq(object_id))
+                    .execute(conn)
+                    .await?;
+                if !attributes.is_empty() {
+                    diesel::insert_into(attribute_dsl::indexed_blob_attribute)
+                        .values(attributes)
+                        .execute(conn)
+                        .await?;
+                }
+                Ok(())
+            }
+            .scope_boxed()
+        },
+    )
+    .await
+}
+
+async fn mark_refreshed(conn: &mut AsyncPgConnection, object_id: &[u8]) -> Result<()> {
+    use schema::indexed_blob::dsl;
+
+    diesel::update(dsl::indexed_blob.filter(dsl::object_id.eq(object_id)))
+        .set(dsl::refreshed_at.eq(diesel::dsl::now))
+        .execute(conn)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attribute_pairs_are_recorded_as_rows() {
+        let object_id = ObjectID::from_single_byte(1);
+        let attribute = BlobAttribute::from([("content-type", "text/plain"), ("name", "a b")]);
+
+        let rows = attribute_rows(object_id, &attribute);
+
+        let pairs: Vec<_> = rows
+            .iter()
+            .map(|row| {
+                assert_eq!(row.object_id, object_id.to_vec());
+                (row.key.as_str(), row.value.as_str())
+            })
+            .collect();
+        assert_eq!(pairs, [("content-type", "text/plain"), ("name", "a b")]);
+    }
+
+    #[test]
+    fn empty_attribute_has_no_rows() {
+        let rows = attribute_rows(ObjectID::from_single_byte(1), &BlobAttribute::default());
+        assert!(rows.is_empty());
+    }
+}
@@ -0,0 +1,414 @@
+// Copyright (c) Walrus Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! REST API of the blob metadata indexer.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+use anyhow::{anyhow, Context as _, Result};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json,
+    Router,
+};
+use diesel::{
+    dsl::max,
+    ExpressionMethods as _,
+    OptionalExtension as _,
+    QueryDsl as _,
+    SelectableHelper as _,
+};
+use diesel_async::{
+    pooled_connection::{bb8::Pool, AsyncDieselConnectionManager},
+    AsyncPgConnection,
+    RunQueryDsl as _,
+};
+use sui_types::base_types::{ObjectID, SuiAddress};
+use walrus_core::{BlobId, EncodingType};
+
+use super::{
+    config::IndexerConfig,
+    indexer_client::{
+        BlobPage,
+        BlobQuery,
+        IndexedBlob,
+        IndexerStatus,
+        INDEXER_BLOBS_ENDPOINT,
+        INDEXER_BLOB_ENDPOINT,
+        INDEXER_STATUS_ENDPOINT,
+    },
+    models::{IndexedBlobAttributeRow, IndexedBlobRow},
+    schema,
+};
+
+/// The state shared by the handlers of the API.
+struct ApiState {
+    max_page_size: usize,
+    /// The pool of database connections used by the handlers.
+    ///
+    /// Connections are checked before being handed out, such that broken connections are replaced.
+    pool: Pool<AsyncPgConnection>,
+}
+
+/// Errors returned by the indexer API.
+#[derive(Debug, thiserror::Error)]
+enum IndexerApiError {
+    #[error("invalid query: {0}")]
+    InvalidQuery(String),
+    #[error("the blob object is not indexed")]
+    NotFound,
+    #[error(transparent)]
+    Internal(#[from] anyhow::Error),
+}
+
+impl IntoResponse for IndexerApiError {
+    fn into_response(self) -> Response {
+        let status = match &self {
+            Self::InvalidQuery(_) => StatusCode::BAD_REQUEST,
+            Self::NotFound => StatusCode::NOT_FOUND,
+            Self::Internal(error) => {
+                tracing::error!(?error, "error while serving an indexer API request");
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        };
+        (status, self.to_string()).into_response()
+    }
+}
+
+/// Serves the REST API of the indexer on the configured address.
+pub(super) async fn serve(config: &IndexerConfig) -> Result<()> {
+    let pool = Pool::builder()
+        .max_size(config.api_db_pool_size)
+        .build(AsyncDieselConnectionManager::<AsyncPgConnection>::new(
+            &config.db_config.database_url,
+        ))
+        .await
+        .context("cannot create the database connection pool of the indexer API")?;
+    let state = Arc::new(ApiState {
+        max_page_size: config.max_page_size,
+        pool,
+    });
+    let router = Router::new()
+        .route(INDEXER_BLOBS_ENDPOINT, get(list_blobs))
+        .route(INDEXER_BLOB_ENDPOINT, get(get_blob))
+        .route(INDEXER_STATUS_ENDPOINT, get(status))
+        .with_state(state);
+
+    let listener = tokio::net::TcpListener::bind(config.api_address)
+        .await
+        .with_context(|| format!("cannot bind the indexer API to {}", config.api_address))?;
+    tracing::info!(address = %config.api_address, "serving the indexer API");
+    axum::serve(listener, router).await?;
+    Ok(())
+}
+
+impl ApiState {
+    /// Runs the database operation `f` on a connection from the pool.
+    async fn with_connection<T, F>(&self, f: F) -> Result<T>
+    where
+        F: for<'c> FnOnce(
+            &'c mut AsyncPgConnection,
+        ) -> futures::future::BoxFuture<'c, diesel::QueryResult<T>>,
+    {
+        let mut connection = self
+            .pool
+            .get()
+            .await
+            .context("cannot get a database connection from the pool")?;
+        Ok(f(&mut connection).await?)
+    }
+}
+
+async fn list_blobs(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<BlobQuery>,
+) -> Result<Json<BlobPage>, IndexerApiError> {
+    let limit = check_query(&query, state.max_page_size)?;
+
+    let (rows, attributes) = state
+        .with_connection(|conn| {
+            Box::pin(async move {
+                let rows = load_blob_rows(conn, &query, limit).await?;
+                let attributes =
+                    load_attributes(conn, rows.iter().map(|row| row.object_id.clone())).await?;
+                Ok((rows, attributes))
+            })
+        })
+        .await?;
+
+    let next_cursor = if rows.len() == limit {
+        rows.last()
+            .map(|row| ObjectID::from_bytes(&row.object_id))
+            .transpose()
+            .map_err(|error| anyhow!("bad object_id found in db: {error}"))?
+    } else {
+        None
+    };
+    let blobs = into_indexed_blobs(rows, attributes)?;
+    Ok(Json(BlobPage { blobs, next_cursor }))
+}
+
+/// Checks that the query is valid, and returns the number of blob objects to return for it.
+fn check_query(query: &BlobQuery, max_page_size: usize) -> Result<usize, IndexerApiError> {
+    if query.attribute_value.is_some() && query.attribute_key.is_none() {
+        return Err(IndexerApiError::InvalidQuery(
+            "`attribute_value` requires `attribute_key`".to_owned(),
+        ));
+    }
+    Ok(query.limit.unwrap_or(max_page_size).clamp(1, max_page_size))
+}
+
+async fn get_blob(
+    State(state): State<Arc<ApiState>>,
+    Path(object_id): Path<ObjectID>,
+) -> Result<Json<IndexedBlob>, IndexerApiError> {
+    use schema::indexed_blob::dsl;
+
+    let (rows, attributes) = state
+        .with_connection(|conn| {
+            Box::pin(async move {
+                let rows = dsl::indexed_blob
+                    .filter(dsl::object_id.eq(object_id.to_vec()))
+                    .select(IndexedBlobRow::as_select())
+                    .load(conn)
+                    .await?;
+                let attributes = load_attributes(conn, [object_id.to_vec()]).await?;
+                Ok((rows, attributes))
+            })
+        })
+        .await?;
+    into_indexed_blobs(rows, attributes)?
+        .pop()
+        .map(Json)
+        .ok_or(IndexerApiError::NotFound)
+}
+
+async fn status(
+    State(state): State<Arc<ApiState>>,
+) -> Result<Json<IndexerStatus>, IndexerApiError> {
+    use schema::{epoch_change_start_event, indexer_cursor};
+
+    let (next_element_index, latest_epoch) = state
+        .with_connection(|conn| {
+            Box::pin(async move {
+                let next_element_index = indexer_cursor::table
+                    .select(indexer_cursor::next_element_index)
+                    .first::<i64>(conn)
+                    .await
+                    .optional()?;
+                let latest_epoch = epoch_change_start_event::table
+                    .select(max(epoch_change_start_event::epoch))
+                    .first::<Option<i64>>(conn)
+                    .await?;
+                Ok((next_element_index, latest_epoch))
+            })
+        })
+        .await?;
+    Ok(Json(IndexerStatus {
+        next_element_index: from_db_int(next_element_index.unwrap_or_default())?,
+        latest_epoch: latest_epoch.map(from_db_int).transpose()?,
+    }))
+}
+
+async fn load_blob_rows(
+    conn: &mut AsyncPgConnection,
+    query: &BlobQuery,
+    limit: usize,
+) -> diesel::QueryResult<Vec<IndexedBlobRow>> {
+    use schema::{indexed_blob::dsl, indexed_blob_attribute::dsl as attribute_dsl};
+
+    let mut select = dsl::indexed_blob.into_boxed();
+    if let Some(owner) = query.owner {
+        select = select.filter(dsl::owner.eq(owner.to_vec()));
+    }
+    if let Some(blob_id) = query.blob_id {
+        select = select.filter(dsl::blob_id.eq(blob_id.0.to_vec()));
+    }
+    if let Some(epoch) = query.expiring_before {
+        select = select.filter(dsl::end_epoch.le(i64::from(epoch)));
+    }
+    if !query.include_deleted {
+        select = select.filter(dsl::deleted_epoch.is_null());
+    }
+    if let Some(after) = query.after {
+        select = select.filter(dsl::object_id.gt(after.to_vec()));
+    }
+    if let Some(key) = &query.attribute_key {
+        let mut attribute_select = attribute_dsl::indexed_blob_attribute
+            .select(attribute_dsl::object_id)
+            .filter(attribute_dsl::key.eq(key.clone()))
+            .into_boxed();
+        if let Some(value) = &query.attribute_value {
+            attribute_select = attribute_select.filter(attribute_dsl::value.eq(value.clone()));
+        }
+        select = select.filter(dsl::object_id.eq_any(attribute_select));
+    }
+    select
+        .order(dsl::object_id.asc())
+        .limit(i64::try_from(limit).expect("the page size fits into an i64"))
+        .select(IndexedBlobRow::as_select())
+        .load(conn)
+        .await
+}
+
+/// Loads the attribute pairs of the given blob objects, grouped by object ID.
+async fn load_attributes(
+    conn: &mut AsyncPgConnection,
+    object_ids: impl IntoIterator<Item = Vec<u8>>,
+) -> diesel::QueryResult<HashMap<Vec<u8>, BTreeMap<String, String>>> {
+    use schema::indexed_blob_attribute::dsl;
+
+    let object_ids: Vec<_> = object_ids.into_iter().collect();
+    let rows: Vec<IndexedBlobAttributeRow> = dsl::indexed_blob_attribute
+        .filter(dsl::object_id.eq_any(object_ids))
+        .select(IndexedBlobAttributeRow::as_select())
+        .load(conn)
+        .await?;
+    let mut attributes: HashMap<_, BTreeMap<_, _>> = HashMap::new();
+    for row in rows {
+        attributes
+            .entry(row.object_id)
+            .or_default()
+            .insert(row.key, row.value);
+    }
+    Ok(attributes)
+}
+
+fn into_indexed_blobs(
+    rows: Vec<IndexedBlobRow>,
+    mut attributes: HashMap<Vec<u8>, BTreeMap<String, String>>,
+) -> Result<Vec<IndexedBlob>> {
+    rows.into_iter()
+        .map(|row| {
+            let attributes = attributes.remove(&row.object_id).unwrap_or_default();
+            into_indexed_blob(row, attributes).context("invalid blob row found in db")
+        })
+        .collect()
+}
+
+fn into_indexed_blob(
+    row: IndexedBlobRow,
+    attributes: BTreeMap<String, String>,
+) -> Result<IndexedBlob> {
+    Ok(IndexedBlob {
+        object_id: ObjectID::from_bytes(&row.object_id)?,
+        blob_id: BlobId::try_from(row.blob_id.as_slice())?,
+        owner: row.owner.map(SuiAddress::from_bytes).transpose()?,
+        size: from_db_int(row.size)?,
+        encoding_type: EncodingType::try_from(u8::try_from(row.encoding_type)?)?,
+        deletable: row.deletable,
+        registered_epoch: from_db_int(row.registered_epoch)?,
+        certified_epoch: row.certified_epoch.map(from_db_int).transpose()?,
+        end_epoch: from_db_int(row.end_epoch)?,
+        deleted_epoch: row.deleted_epoch.map(from_db_int).transpose()?,
+        invalidated_epoch: row.invalidated_epoch.map(from_db_int).transpose()?,
+        attributes,
+    })
+}
+
+fn from_db_int<T: TryFrom<i64>>(value: i64) -> Result<T> {
+    T::try_from(value).map_err(|_| anyhow!("integer {value} out of range"))
+}
+
+#[cfg(test)]
+mod tests {
+    use walrus_test_utils::param_test;
+
+    use super::*;
+
+    const MAX_PAGE_SIZE: usize = 100;
+
+    param_test! {
+        test_check_query_limit: [
+            default_limit: (None, MAX_PAGE_SIZE),
+            smaller_limit: (Some(10), 10),
+            zero_limit: (Some(0), 1),
+            larger_limit: (Some(MAX_PAGE_SIZE + 1), MAX_PAGE_SIZE),
+        ]
+    }
+    fn test_check_query_limit(limit: Option<usize>, expected_limit: usize) {
+        let query = BlobQuery {
+            limit,
+            ..Default::default()
+        };
+        assert_eq!(
+            check_query(&query, MAX_PAGE_SIZE).expect("the query is valid"),
+            expected_limit
+        );
+    }
+
+    #[test]
+    fn attribute_value_requires_attribute_key() {
+        let query = BlobQuery {
+            attribute_value: Some("value".to_owned()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            check_query(&query, MAX_PAGE_SIZE),
+            Err(IndexerApiError::InvalidQuery(_))
+        ));
+
+        let query = BlobQuery {
+            attribute_key: Some("key".to_owned()),
+            ..query
+        };
+        assert!(check_query(&query, MAX_PAGE_SIZE).is_ok());
+    }
+
+    fn blob_row(object_id: u8) -> IndexedBlobRow {
+        IndexedBlobRow {
+            object_id: ObjectID::from_single_byte(object_id).to_vec(),
+            blob_id: vec![object_id; 32],
+            owner: Some(SuiAddress::ZERO.to_vec()),
+            size: 1024,
+            encoding_type: i16::from(u8::from(EncodingType::RS2)),
+            deletable: true,
+            registered_epoch: 1,
+            certified_epoch: Some(2),
+            end_epoch: 10,
+            deleted_epoch: None,
+            invalidated_epoch: None,
+        }
+    }
+
+    #[test]
+    fn blob_rows_are_combined_with_their_attributes() -> anyhow::Result<()> {
+        let rows = vec![blob_row(1), blob_row(2)];
+        let attributes = HashMap::from([(
+            ObjectID::from_single_byte(2).to_vec(),
+            BTreeMap::from([("key".to_owned(), "value".to_owned())]),
+        )]);
+
+        let blobs = into_indexed_blobs(rows, attributes)?;
+
+        assert_eq!(blobs.len(), 2);
+        assert_eq!(blobs[0].object_id, ObjectID::from_single_byte(1));
+        assert_eq!(blobs[0].blob_id, BlobId([1; 32]));
+        assert_eq!(blobs[0].owner, Some(SuiAddress::ZERO));
+        assert_eq!(blobs[0].encoding_type, EncodingType::RS2);
+        assert_eq!(blobs[0].certified_epoch, Some(2));
+        assert!(blobs[0].attributes.is_empty());
+        assert_eq!(
+            blobs[1].attributes,
+            BTreeMap::from([("key".to_owned(), "value".to_owned())])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_blob_rows_are_rejected() {
+        let mut row = blob_row(1);
+        row.end_epoch = -1;
+        assert!(into_indexed_blobs(vec![row], HashMap::new()).is_err());
+
+        let mut row = blob_row(1);
+        row.blob_id = vec![1; 31];
+        assert!(into_indexed_blobs(vec![row], HashMap::new()).is_err());
+    }
+}
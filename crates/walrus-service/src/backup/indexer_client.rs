@@ -0,0 +1,223 @@
+// Copyright (c) Walrus Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Types of the REST API of the blob metadata indexer, and a client for it.
+
+use std::collections::BTreeMap;
+
+use anyhow::Context as _;
+use reqwest::{StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+use sui_types::base_types::{ObjectID, SuiAddress};
+use utoipa::ToSchema;
+use walrus_core::{BlobId, EncodingType, Epoch};
+use walrus_sui::{ObjectIdSchema, SuiAddressSchema};
+
+/// The path to list the indexed blobs matching a [`BlobQuery`].
+pub const INDEXER_BLOBS_ENDPOINT: &str = "/v1/blobs";
+/// The path to get a single indexed blob by its object ID.
+pub const INDEXER_BLOB_ENDPOINT: &str = "/v1/blobs/{object_id}";
+/// The path to get the indexing progress.
+pub const INDEXER_STATUS_ENDPOINT: &str = "/v1/status";
+
+/// The filters to list indexed blobs.
+///
+/// All the filters that are set must match. The blobs are returned in the order of their object
+/// IDs.
+#[serde_as]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlobQuery {
+    /// Only return blob objects owned by this address.
+    #[serde(default)]
+    pub owner: Option<SuiAddress>,
+    /// Only return blob objects with this blob ID.
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub blob_id: Option<BlobId>,
+    /// Only return blob objects that have an attribute with this key.
+    #[serde(default)]
+    pub attribute_key: Option<String>,
+    /// Only return blob objects whose attribute `attribute_key` has this value.
+    ///
+    /// Requires `attribute_key` to be set.
+    #[serde(default)]
+    pub attribute_value: Option<String>,
+    /// Only return blob objects whose end epoch is at most this epoch, i.e., which expire at or
+    /// before the start of this epoch.
+    #[serde(default)]
+    pub expiring_before: Option<Epoch>,
+    /// Also return deleted blob objects.
+    #[serde(default)]
+    pub include_deleted: bool,
+    /// Only return blob objects whose object ID is greater than this one.
+    ///
+    /// Used to fetch the next page, see [`BlobPage::next_cursor`].
+    #[serde(default)]
+    pub after: Option<ObjectID>,
+    /// The maximum number of blob objects to return.
+    ///
+    /// Capped by the page size configured at the indexer.
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+/// A blob object, as recorded by the indexer.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexedBlob {
+    /// The object ID of the blob object.
+    #[schema(value_type = ObjectIdSchema)]
+    pub object_id: ObjectID,
+    /// The blob ID.
+    #[serde_as(as = "DisplayFromStr")]
+    pub blob_id: BlobId,
+    /// The address owning the blob object, if it is owned by an address.
+    #[schema(value_type = Option<SuiAddressSchema>)]
+    pub owner: Option<SuiAddress>,
+    /// The unencoded size of the blob.
+    pub size: u64,
+    /// The encoding type of the blob.
+    pub encoding_type: EncodingType,
+    /// Whether the blob is deletable.
+    pub deletable: bool,
+    /// The epoch in which the blob was registered.
+    pub registered_epoch: Epoch,
+    /// The epoch in which the blob was first certified, if it is certified.
+    pub certified_epoch: Option<Epoch>,
+    /// The first epoch in which the blob is no longer stored.
+    pub end_epoch: Epoch,
+    /// The epoch in which the blob object was deleted, if it was deleted.
+    pub deleted_epoch: Option<Epoch>,
+    /// The epoch in which the blob ID was marked as invalid, if it was.
+    pub invalidated_epoch: Option<Epoch>,
+    /// The key-value pairs of the attribute of the blob object.
+    pub attributes: BTreeMap<String, String>,
+}
+
+/// A page of indexed blobs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BlobPage {
+    /// The blob objects on this page.
+    pub blobs: Vec<IndexedBlob>,
+    /// The cursor to pass as `after` to get the next page, if there may be more blob objects.
+    #[schema(value_type = Option<ObjectIdSchema>)]
+    pub next_cursor: Option<ObjectID>,
+}
+
+/// The indexing progress of the indexer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexerStatus {
+    /// The index of the next Walrus event to be indexed.
+    pub next_element_index: u64,
+    /// The latest epoch for which the start of the epoch change was indexed.
+    pub latest_epoch: Option<Epoch>,
+}
+
+/// A client for the REST API of the blob metadata indexer.
+#[derive(Debug, Clone)]
+pub struct IndexerClient {
+    url: Url,
+    http_client: reqwest::Client,
+}
+
+impl IndexerClient {
+    /// Creates a new client for the indexer at the given URL.
+    pub fn new(url: &str) -> anyhow::Result<Self> {
+        Ok(Self {
+            url: Url::parse(url).with_context(|| format!("invalid indexer URL '{url}'"))?,
+            http_client: reqwest::Client::new(),
+        })
+    }
+
+    /// Returns the URL of the indexer.
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// Returns the page of indexed blobs matching the query.
+    pub async fn query_blobs(&self, query: &BlobQuery) -> anyhow::Result<BlobPage> {
+        let url = self.url.join(INDEXER_BLOBS_ENDPOINT)?;
+        self.http_client
+            .get(url)
+            .query(query)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .with_context(|| format!("request to the indexer at {} failed", self.url))?
+            .json()
+            .await
+            .with_context(|| format!("invalid response from the indexer at {}", self.url))
+    }
+
+    /// Returns the indexed blob object with the given ID, if it exists.
+    pub async fn get_blob(&self, object_id: ObjectID) -> anyhow::Result<Option<IndexedBlob>> {
+        let url = self
+            .url
+            .join(&INDEXER_BLOB_ENDPOINT.replace("{object_id}", &object_id.to_string()))?;
+        let response = self
+            .http_client
+            .get(url)
+            .send()
+            .await
+            .with_context(|| format!("request to the indexer at {} failed", self.url))?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        response
+            .error_for_status()
+            .with_context(|| format!("request to the indexer at {} failed", self.url))?
+            .json()
+            .await
+            .map(Some)
+            .with_context(|| format!("invalid response from the indexer at {}", self.url))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr as _;
+
+    use axum::{extract::Query, http::Uri};
+    use walrus_test_utils::{param_test, Result as TestResult};
+
+    use super::*;
+
+    const BLOB_ID: &str = "4BKcDC0Ih5RJ8R0tFMz3MZVNZV8b2goT6_JiEEwNHQo";
+
+    fn query_url(query: &BlobQuery) -> TestResult<Uri> {
+        let request = reqwest::Client::new()
+            .get(format!("http://localhost{INDEXER_BLOBS_ENDPOINT}"))
+            .query(query)
+            .build()?;
+        Ok(request.url().as_str().parse()?)
+    }
+
+    param_test! {
+        blob_query_round_trips_through_the_url -> TestResult: [
+            empty: (BlobQuery::default()),
+            all_filters: (BlobQuery {
+                owner: Some(SuiAddress::ZERO),
+                blob_id: Some(BlobId::from_str(BLOB_ID).expect("valid blob ID")),
+                attribute_key: Some("content-type".to_owned()),
+                attribute_value: Some("text/plain; charset=utf-8".to_owned()),
+                expiring_before: Some(42),
+                include_deleted: true,
+                after: Some(ObjectID::from_single_byte(7)),
+                limit: Some(10),
+            }),
+            attribute_key_only: (BlobQuery {
+                attribute_key: Some("key with spaces & symbols=".to_owned()),
+                ..Default::default()
+            }),
+        ]
+    }
+    fn blob_query_round_trips_through_the_url(query: BlobQuery) -> TestResult {
+        let url = query_url(&query)?;
+        let Query(parsed) = Query::<BlobQuery>::try_from_uri(&url)?;
+        assert_eq!(parsed, query);
+        Ok(())
+    }
+}
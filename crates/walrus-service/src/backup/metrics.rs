@@ -96,3 +96,24 @@ walrus_utils::metrics::define_metric_set! {
         idle_state: Gauge[],
     }
 }
+
+walrus_utils::metrics::define_metric_set! {
+    #[namespace = "walrus"]
+    /// Metrics exported by the blob metadata indexer.
+    pub(crate) struct IndexerMetricSet {
+        #[help = "The count of Walrus contract events indexed"]
+        indexer_events_indexed: IntCounterVec["event_type"],
+
+        #[help = "The count of blob objects refreshed from Sui"]
+        indexer_blobs_refreshed: IntCounter[],
+
+        #[help = "The count of failed refreshes of blob objects"]
+        indexer_refresh_errors: IntCounter[],
+
+        #[help = "The count of database reconnects"]
+        db_reconnects: IntCounter[],
+
+        #[help = "The number of retries due to serializability failures"]
+        db_serializability_retries: IntCounterVec["context"],
+    }
+}
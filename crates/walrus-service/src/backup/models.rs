@@ -81,3 +81,57 @@ pub async fn get_backup_node_cursor(
 pub struct BlobIdRow {
     pub blob_id: Vec<u8>,
 }
+
+#[derive(Debug, Queryable, Selectable, QueryableByName)]
+#[diesel(table_name = crate::backup::schema::indexed_blob)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct IndexedBlobRow {
+    pub object_id: Vec<u8>,
+    pub blob_id: Vec<u8>,
+    pub owner: Option<Vec<u8>>,
+    pub size: i64,
+    pub encoding_type: i16,
+    pub deletable: bool,
+    pub registered_epoch: i64,
+    pub certified_epoch: Option<i64>,
+    pub end_epoch: i64,
+    pub deleted_epoch: Option<i64>,
+    pub invalidated_epoch: Option<i64>,
+}
+
+#[derive(Debug, Queryable, Selectable, Insertable)]
+#[diesel(table_name = crate::backup::schema::indexed_blob_attribute)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct IndexedBlobAttributeRow {
+    pub object_id: Vec<u8>,
+    pub key: String,
+    pub value: String,
+}
+
+#[derive(Debug, Queryable, Selectable, QueryableByName)]
+#[diesel(table_name = crate::backup::schema::indexed_blob)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ObjectIdRow {
+    pub object_id: Vec<u8>,
+}
+
+pub async fn get_indexer_cursor(
+    pg_connection: &mut AsyncPgConnection,
+) -> anyhow::Result<EventStreamCursor> {
+    use super::schema::indexer_cursor::dsl;
+    let next_element_index = dsl::indexer_cursor
+        .select(dsl::next_element_index)
+        .first::<i64>(pg_connection)
+        .await
+        .optional()?
+        .unwrap_or_else(|| {
+            tracing::warn!("no prior indexer cursor. attempting to start from the beginning");
+            0
+        });
+    Ok(EventStreamCursor::new(
+        None,
+        next_element_index
+            .try_into()
+            .expect("next_element_index is non-negative"),
+    ))
+}
@@ -29,6 +29,39 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    indexed_blob (object_id) {
+        object_id -> Bytea,
+        blob_id -> Bytea,
+        owner -> Nullable<Bytea>,
+        size -> Int8,
+        encoding_type -> Int2,
+        deletable -> Bool,
+        registered_epoch -> Int8,
+        certified_epoch -> Nullable<Int8>,
+        end_epoch -> Int8,
+        deleted_epoch -> Nullable<Int8>,
+        invalidated_epoch -> Nullable<Int8>,
+        last_tx_digest -> Bytea,
+        refreshed_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    indexed_blob_attribute (object_id, key) {
+        object_id -> Bytea,
+        key -> Text,
+        value -> Text,
+    }
+}
+
+diesel::table! {
+    indexer_cursor (id) {
+        id -> Bool,
+        next_element_index -> Int8,
+    }
+}
+
 diesel::table! {
     stream_event (element_index) {
         checkpoint_sequence_number -> Int8,
@@ -40,4 +73,13 @@ diesel::table! {
     }
 }
 
-diesel::allow_tables_to_appear_in_same_query!(blob_state, epoch_change_start_event, stream_event,);
+diesel::joinable!(indexed_blob_attribute -> indexed_blob (object_id));
+
+diesel::allow_tables_to_appear_in_same_query!(
+    blob_state,
+    epoch_change_start_event,
+    indexed_blob,
+    indexed_blob_attribute,
+    indexer_cursor,
+    stream_event,
+);
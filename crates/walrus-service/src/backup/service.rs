@@ -34,7 +34,7 @@ use walrus_sui::{
 };
 
 use super::{
-    config::{BackupConfig, BackupDbConfig},
+    config::{BackupConfig, BackupDbConfig, IndexerConfig},
     models::{self, BlobIdRow, StreamEvent},
     schema,
     BACKUP_BLOB_ARCHIVE_SUBDIR,
@@ -272,18 +272,27 @@ pub async fn establish_connection_async(
 
 /// Run the database migrations for the backup node.
 pub fn run_backup_database_migrations(config: &BackupConfig) {
-    let mut connection = establish_connection(
-        &config.db_config.database_url,
-        "run_backup_database_migrations",
-    )
-    .inspect_err(|error| {
-        tracing::error!(
-            ?error,
-            "failed to connect to postgres for database migration"
-        );
-        std::process::exit(1);
-    })
-    .unwrap();
+    run_database_migrations(&config.db_config, "run_backup_database_migrations");
+}
+
+/// Run the database migrations for the blob metadata indexer.
+///
+/// The indexer shares the migrations with the backup node, such that both can use the same
+/// database.
+pub fn run_indexer_database_migrations(config: &IndexerConfig) {
+    run_database_migrations(&config.db_config, "run_indexer_database_migrations");
+}
+
+fn run_database_migrations(db_config: &BackupDbConfig, context: &str) {
+    let mut connection = establish_connection(&db_config.database_url, context)
+        .inspect_err(|error| {
+            tracing::error!(
+                ?error,
+                "failed to connect to postgres for database migration"
+            );
+            std::process::exit(1);
+        })
+        .unwrap();
     tracing::info!("running pending migrations");
     let versions = connection
         .run_pending_migrations(MIGRATIONS)
@@ -598,8 +607,13 @@ async fn backup_fetcher(
         contract_config: backup_config.sui.contract_config.clone(),
         exchange_objects: vec![],
         wallet_config: None,
+        sponsor: None,
         communication_config: ClientCommunicationConfig::default(),
         refresh_config: Default::default(),
+        rpc_urls: Default::default(),
+        rpc_failover_config: Default::default(),
+        trace_export: None,
+        blocklist: Default::default(),
     };
 
     let read_client =
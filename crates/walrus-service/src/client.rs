@@ -3,13 +3,22 @@
 
 //! Client for the Walrus service.
 
-use std::{collections::HashMap, fmt::Display, path::PathBuf, sync::Arc, time::Instant};
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    fmt::Display,
+    ops::Range,
+    path::PathBuf,
+    sync::Arc,
+    time::Instant,
+};
 
 use anyhow::anyhow;
 use cli::{styled_progress_bar, styled_spinner};
 use communication::NodeCommunicationFactory;
-use futures::{Future, FutureExt};
+use futures::{stream, Future, FutureExt, StreamExt as _, TryStreamExt as _};
 use indicatif::{HumanDuration, MultiProgress};
+use journal::JournaledConfirmation;
 use prometheus::Registry;
 use rand::{rngs::ThreadRng, RngCore as _};
 use rayon::{
@@ -30,6 +39,8 @@ use walrus_core::{
         EncodingAxis,
         EncodingConfig,
         EncodingConfigTrait as _,
+        Primary,
+        Secondary,
         SliverData,
         SliverPair,
     },
@@ -42,12 +53,15 @@ use walrus_core::{
     EpochCount,
     ShardIndex,
     Sliver,
+    SliverPairIndex,
 };
 use walrus_sdk::{api::BlobStatus, error::NodeError};
 use walrus_sui::{
     client::{
         BlobPersistence,
         CertifyAndExtendBlobParams,
+        ContractClient,
+        DeferredDryRun,
         ExpirySelectionPolicy,
         PostStoreAction,
         ReadClient,
@@ -68,7 +82,7 @@ use crate::common::active_committees::ActiveCommittees;
 pub mod cli;
 pub mod responses;
 
-pub use crate::common::blocklist::Blocklist;
+pub use crate::common::blocklist::{Blocklist, BlocklistConfig, RemoteBlocklistConfig};
 
 mod communication;
 
@@ -99,8 +113,29 @@ mod refill;
 pub use refill::{RefillHandles, Refiller};
 mod multiplexer;
 
+mod progress;
+pub use progress::{StoreProgress, StoreStage};
+
+mod journal;
+pub use journal::{JournalEntry, JournaledStoreParams, StoreJournal};
+
+mod renewer;
+pub use renewer::{
+    BlobRenewer,
+    RenewalCandidate,
+    RenewalReport,
+    RenewalStatus,
+    RenewerConfig,
+    RenewerMetrics,
+};
+
 type ClientResult<T> = Result<T, ClientError>;
 
+/// Converts an error while accessing the [`StoreJournal`] into a [`ClientError`].
+fn journal_error(error: anyhow::Error) -> ClientError {
+    ClientErrorKind::Other(error.into()).into()
+}
+
 /// The result of encoding as a list of sliver pairs and metadata and a
 /// mapping from blob id to file path.
 #[derive(Debug)]
@@ -316,6 +351,112 @@ impl<T: ReadClient> Client<T> {
         self.read_blob_internal(blob_id, Some(blob_status)).await
     }
 
+    /// Reads the bytes of the blob in the given range.
+    ///
+    /// The blob is laid out row by row in its primary source slivers, so only the slivers
+    /// overlapping the range are requested, each from the node holding the corresponding shard. If
+    /// any of them cannot be retrieved, the full blob is reconstructed instead.
+    #[tracing::instrument(level = Level::ERROR, skip_all, fields(%blob_id, ?range))]
+    pub async fn read_blob_range(
+        &self,
+        blob_id: &BlobId,
+        range: Range<u64>,
+    ) -> ClientResult<Vec<u8>> {
+        tracing::debug!("starting to read blob range");
+        self.check_blob_id(blob_id)?;
+        let certified_epoch = self.certified_epoch_for_read(blob_id, None).await?;
+        let metadata = self.retrieve_metadata(certified_epoch, blob_id).await?;
+
+        let blob_length = metadata.metadata().unencoded_length();
+        if range.start > range.end || range.end > blob_length {
+            return Err(ClientError::from(ClientErrorKind::Other(
+                anyhow!("the range {range:?} is not within the blob of length {blob_length}")
+                    .into(),
+            )));
+        }
+        if range.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let config = self
+            .encoding_config
+            .get_for_type(metadata.metadata().encoding_type());
+        let symbol_size = config
+            .symbol_size_for_blob(blob_length)
+            .map_err(ClientError::other)?;
+        let row_length =
+            u64::from(symbol_size.get()) * u64::from(config.n_source_symbols::<Secondary>().get());
+        let rows = range.start / row_length..range.end.div_ceil(row_length);
+        let offset = rows.start * row_length;
+        let (data, range) = match self
+            .retrieve_primary_source_slivers(certified_epoch, &metadata, rows)
+            .await
+        {
+            Ok(data) => (data, range.start - offset..range.end - offset),
+            Err(error) => {
+                tracing::info!(
+                    %error,
+                    "retrieving the slivers of the range failed; reconstructing the full blob"
+                );
+                let blob = self
+                    .request_slivers_and_decode::<Primary>(certified_epoch, &metadata)
+                    .await?;
+                (blob, range)
+            }
+        };
+        let to_index = |index: u64| usize::try_from(index).expect("the data is held in memory");
+        Ok(data[to_index(range.start)..to_index(range.end)].to_vec())
+    }
+
+    /// Retrieves the primary source slivers with the given indices from the nodes holding them,
+    /// and returns their concatenated symbols.
+    async fn retrieve_primary_source_slivers(
+        &self,
+        certified_epoch: Epoch,
+        metadata: &VerifiedBlobMetadataWithId,
+        indices: Range<u64>,
+    ) -> ClientResult<Vec<u8>> {
+        let committees = self.get_committees().await?;
+        let comms = self
+            .communication_factory
+            .node_read_communications(&committees, certified_epoch)?;
+        let n_shards = self.encoding_config.n_shards();
+        let futures = indices.map(|index| {
+            let shard_index = SliverPairIndex::new(
+                u16::try_from(index)
+                    .expect("the sliver index is smaller than the number of shards"),
+            )
+            .to_shard_index(n_shards, metadata.blob_id());
+            let comm = comms
+                .iter()
+                .find(|comm| comm.node.shard_ids.contains(&shard_index));
+            async move {
+                let comm =
+                    comm.ok_or_else(|| ClientError::from(ClientErrorKind::NotEnoughSlivers))?;
+                let NodeResult(_, _, _, result) = comm
+                    .retrieve_verified_sliver::<Primary>(metadata, shard_index)
+                    .instrument(comm.span.clone())
+                    .await;
+                result.map_err(ClientError::other)
+            }
+        });
+        let slivers = stream::iter(futures)
+            .buffered(
+                self.communication_limits
+                    .max_concurrent_sliver_reads_for_blob_size(
+                        metadata.metadata().unencoded_length(),
+                        &self.encoding_config,
+                        metadata.metadata().encoding_type(),
+                    ),
+            )
+            .try_collect::<Vec<_>>()
+            .await?;
+        Ok(slivers
+            .into_iter()
+            .flat_map(|sliver| sliver.symbols.into_vec())
+            .collect())
+    }
+
     /// Internal method to handle the common logic for reading blobs.
     async fn read_blob_internal<U>(
         &self,
@@ -328,6 +469,18 @@ impl<T: ReadClient> Client<T> {
     {
         tracing::debug!("starting to read blob");
         self.check_blob_id(blob_id)?;
+        let certified_epoch = self.certified_epoch_for_read(blob_id, blob_status).await?;
+        self.read_metadata_and_slivers::<U>(certified_epoch, blob_id)
+            .await
+    }
+
+    /// Returns the epoch from which the blob is read, which is the initial certified epoch of the
+    /// blob during epoch change, and the current epoch otherwise.
+    async fn certified_epoch_for_read(
+        &self,
+        blob_id: &BlobId,
+        blob_status: Option<BlobStatus>,
+    ) -> ClientResult<Epoch> {
         let committees = self.get_committees().await?;
 
         let certified_epoch = if committees.is_change_in_progress() {
@@ -355,9 +508,7 @@ impl<T: ReadClient> Client<T> {
                 certified_epoch,
             }));
         }
-
-        self.read_metadata_and_slivers::<U>(certified_epoch, blob_id)
-            .await
+        Ok(certified_epoch)
     }
 
     async fn read_metadata_and_slivers<U>(
@@ -507,6 +658,39 @@ impl Client<SuiContractClient> {
             .await)
     }
 
+    /// For each entry in `node_ids_with_amounts`, stakes the amount of WAL specified by the
+    /// second element of the pair with the node represented by the first element of the pair.
+    pub async fn stake_with_node_pools(
+        &self,
+        node_ids_with_amounts: &[(ObjectID, u64)],
+    ) -> ClientResult<Vec<StakedWal>> {
+        let staked_wal = self
+            .sui_client
+            .stake_with_pools(node_ids_with_amounts)
+            .await?;
+        Ok(staked_wal)
+    }
+
+    /// Stakes the specified amount of WAL with the node represented by `node_id`.
+    pub async fn stake_with_node_pool(&self, node_id: ObjectID, amount: u64) -> ClientResult<()> {
+        self.stake_with_node_pools(&[(node_id, amount)]).await?;
+        Ok(())
+    }
+
+    /// Exchanges the provided amount of SUI (in MIST) for WAL using the specified exchange.
+    pub async fn exchange_sui_for_wal(
+        &self,
+        exchange_id: ObjectID,
+        amount: u64,
+    ) -> ClientResult<()> {
+        Ok(self
+            .sui_client
+            .exchange_sui_for_wal(exchange_id, amount)
+            .await?)
+    }
+}
+
+impl<T: ContractClient> Client<T> {
     /// Stores a list of blobs to Walrus, retrying if it fails because of epoch change.
     #[tracing::instrument(skip_all, fields(blob_id))]
     pub async fn reserve_and_store_blobs_retry_committees(
@@ -518,6 +702,35 @@ impl Client<SuiContractClient> {
         persistence: BlobPersistence,
         post_store: PostStoreAction,
     ) -> ClientResult<Vec<BlobStoreResult>> {
+        self.reserve_and_store_blobs_retry_committees_with_progress(
+            blobs,
+            encoding_type,
+            epochs_ahead,
+            store_when,
+            persistence,
+            post_store,
+            &StoreProgress::noop(),
+        )
+        .await
+    }
+
+    /// Stores a list of blobs to Walrus, retrying if it fails because of epoch change.
+    ///
+    /// Similar to [`Client::reserve_and_store_blobs_retry_committees`], except that the stages of
+    /// the store operation are reported through `progress`.
+    #[allow(clippy::too_many_arguments)]
+    #[tracing::instrument(skip_all, fields(blob_id))]
+    pub async fn reserve_and_store_blobs_retry_committees_with_progress(
+        &self,
+        blobs: &[&[u8]],
+        encoding_type: EncodingType,
+        epochs_ahead: EpochCount,
+        store_when: StoreWhen,
+        persistence: BlobPersistence,
+        post_store: PostStoreAction,
+        progress: &StoreProgress,
+    ) -> ClientResult<Vec<BlobStoreResult>> {
+        progress.report(StoreStage::Encoding);
         let pairs_and_metadata = self.encode_blobs_to_pairs_and_metadata(blobs, encoding_type)?;
 
         self.retry_if_error_epoch_change(|| {
@@ -527,6 +740,8 @@ impl Client<SuiContractClient> {
                 store_when,
                 persistence,
                 post_store,
+                None,
+                progress,
             )
         })
         .await
@@ -560,6 +775,8 @@ impl Client<SuiContractClient> {
                     store_when,
                     persistence,
                     post_store,
+                    None,
+                    &StoreProgress::noop(),
                 )
             })
             .await?;
@@ -595,6 +812,294 @@ impl Client<SuiContractClient> {
             store_when,
             persistence,
             post_store,
+            None,
+            &StoreProgress::noop(),
+        )
+        .await
+    }
+
+    /// Stores a blob to Walrus using the given storage resource, retrying if it fails because of
+    /// epoch change.
+    ///
+    /// If the blob is not yet registered in the wallet, it is registered with `storage_object`,
+    /// which must be owned by the wallet and be large and long enough to store the blob for
+    /// `epochs_ahead` epochs.
+    #[allow(clippy::too_many_arguments)]
+    #[tracing::instrument(skip_all, fields(blob_id, %storage_object))]
+    pub async fn reserve_and_store_blob_with_storage_object(
+        &self,
+        blob: &[u8],
+        encoding_type: EncodingType,
+        storage_object: ObjectID,
+        epochs_ahead: EpochCount,
+        store_when: StoreWhen,
+        persistence: BlobPersistence,
+        post_store: PostStoreAction,
+    ) -> ClientResult<BlobStoreResult> {
+        let pairs_and_metadata = self.encode_blobs_to_pairs_and_metadata(&[blob], encoding_type)?;
+
+        let mut results = self
+            .retry_if_error_epoch_change(|| {
+                self.reserve_and_store_encoded_blobs(
+                    &pairs_and_metadata,
+                    epochs_ahead,
+                    store_when,
+                    persistence,
+                    post_store,
+                    Some(storage_object),
+                    &StoreProgress::noop(),
+                )
+            })
+            .await?;
+        Ok(results.pop().expect("there is one result per blob"))
+    }
+
+    /// Stores a blob, recording the progress of the operation in the given [`StoreJournal`].
+    ///
+    /// The parameters of the store operation are taken from the journal. If the journal contains
+    /// the progress of a previous, interrupted attempt, the completed steps are skipped: the
+    /// journaled metadata and blob registration are reused, and the slivers are only sent to the
+    /// storage nodes that have not yet confirmed the storage in the current epoch. If a quorum of
+    /// confirmations has already been collected, the blob is not even re-encoded.
+    ///
+    /// On success, the journal is removed. Otherwise, it is closed such that the store operation
+    /// can be resumed later.
+    #[tracing::instrument(skip_all, fields(journal = %journal.path().display()))]
+    pub async fn store_blob_with_journal(
+        &self,
+        blob: &[u8],
+        journal: StoreJournal,
+    ) -> ClientResult<BlobStoreResult> {
+        match self.store_blob_journaled(blob, journal).await {
+            Ok(result) => {
+                journal.remove().await.map_err(journal_error)?;
+                Ok(result)
+            }
+            Err(error) => {
+                if let Err(close_error) = journal.close().await {
+                    tracing::warn!(error = ?close_error, "failed to close the store journal");
+                }
+                Err(error)
+            }
+        }
+    }
+
+    /// Runs the store operation of [`Self::store_blob_with_journal`], without removing the journal.
+    async fn store_blob_journaled(
+        &self,
+        blob: &[u8],
+        journal: &StoreJournal,
+    ) -> ClientResult<BlobStoreResult> {
+        let entry = journal.entry();
+        let params = entry.params;
+        let committees = self.get_committees().await?;
+
+        // Use the journaled metadata if it is still valid for the current encoding config.
+        let mut pairs = None;
+        let metadata = match entry
+            .metadata
+            .and_then(|metadata| metadata.verify(&self.encoding_config).ok())
+        {
+            Some(metadata) => metadata,
+            None => {
+                let (encoded_pairs, metadata) = self.encode_with_journal(blob, journal, None)?;
+                pairs = Some(encoded_pairs);
+                metadata
+            }
+        };
+
+        // Reuse the journaled registration if the blob object still exists.
+        let journaled_blob = match entry.registration {
+            Some(registration) => {
+                match self
+                    .get_blob_by_object_id(&registration.blob_object_id)
+                    .await
+                {
+                    Ok(BlobWithAttribute { blob, .. }) if blob.blob_id == *metadata.blob_id() => {
+                        Some((blob, registration.operation))
+                    }
+                    result => {
+                        tracing::info!(
+                            blob_object_id = %registration.blob_object_id,
+                            error = ?result.err(),
+                            "the journaled blob object is no longer available"
+                        );
+                        journal.clear_registration().map_err(journal_error)?;
+                        None
+                    }
+                }
+            }
+            None => None,
+        };
+        if let Some((blob_object, operation)) = &journaled_blob {
+            if blob_object.certified_epoch.is_some()
+                && blob_object.storage.end_epoch
+                    >= committees.write_committee().epoch + params.epochs_ahead
+            {
+                // The previous attempt was interrupted after certifying the blob.
+                tracing::info!(blob_id = %blob_object.blob_id, "the blob is already certified");
+                let cost = self
+                    .get_price_computation()
+                    .await?
+                    .operation_cost(operation);
+                return Ok(BlobStoreResult::NewlyCreated {
+                    blob_object: blob_object.clone(),
+                    resource_operation: operation.clone(),
+                    cost,
+                    shared_blob_object: None,
+                });
+            }
+        }
+        let (blob_object, operation) = match journaled_blob {
+            Some(blob_and_operation) => blob_and_operation,
+            None => {
+                let status = self
+                    .get_blob_status_with_retries(metadata.blob_id(), &self.sui_client)
+                    .await?;
+                let store_op = self
+                    .resource_manager(&committees)
+                    .await
+                    .store_operation_for_blobs(
+                        &[(&metadata, status)],
+                        params.epochs_ahead,
+                        params.persistence(),
+                        params.store_when(),
+                    )
+                    .await?
+                    .pop()
+                    .expect("there is one store operation per blob");
+                match store_op {
+                    StoreOp::NoOp(result) => {
+                        return Ok(result);
+                    }
+                    StoreOp::RegisterNew { blob, operation } => {
+                        journal
+                            .record_registration(blob.id, operation.clone())
+                            .map_err(journal_error)?;
+                        journal.sync().await.map_err(journal_error)?;
+                        (blob, operation)
+                    }
+                }
+            }
+        };
+
+        let certificate = if operation.is_extend() {
+            // The blob is already certified, and only needs to be extended.
+            None
+        } else {
+            Some(
+                self.get_certificate_with_journal(blob, pairs, &metadata, &blob_object, journal)
+                    .await?,
+            )
+        };
+
+        let shared_blob_object_map = self
+            .sui_client
+            .certify_and_extend_blobs(
+                &[CertifyAndExtendBlobParams {
+                    blob: &blob_object,
+                    certificate,
+                    epochs_extended: operation.epochs_extended(),
+                }],
+                params.post_store(),
+            )
+            .await
+            .map_err(|e| {
+                tracing::warn!(error = %e, "failed to certify blob on Sui");
+                ClientError::from(ClientErrorKind::CertificationFailed(e))
+            })?;
+
+        let write_committee_epoch = committees.write_committee().epoch;
+        let mut blob_object = Blob {
+            certified_epoch: Some(write_committee_epoch),
+            ..blob_object
+        };
+        if operation.epochs_extended().is_some() {
+            blob_object.storage.end_epoch = write_committee_epoch + params.epochs_ahead;
+        }
+        let cost = self
+            .get_price_computation()
+            .await?
+            .operation_cost(&operation);
+        Ok(BlobStoreResult::NewlyCreated {
+            shared_blob_object: shared_blob_object_map.get(&blob_object.blob_id).copied(),
+            blob_object,
+            resource_operation: operation,
+            cost,
+        })
+    }
+
+    /// Encodes the blob and records the metadata in the journal.
+    ///
+    /// If `expected_metadata` is provided, checks that the blob still has the same blob ID.
+    fn encode_with_journal(
+        &self,
+        blob: &[u8],
+        journal: &StoreJournal,
+        expected_metadata: Option<&VerifiedBlobMetadataWithId>,
+    ) -> ClientResult<(Vec<SliverPair>, VerifiedBlobMetadataWithId)> {
+        let (pairs, metadata) = self.encode_pairs_and_metadata(
+            blob,
+            journal.entry().params.encoding_type,
+            &MultiProgress::new(),
+        )?;
+        if let Some(expected_metadata) = expected_metadata {
+            if expected_metadata.blob_id() != metadata.blob_id() {
+                return Err(ClientError::from(ClientErrorKind::Other(
+                    format!(
+                        "the blob ID {} does not match the journaled blob ID {}",
+                        metadata.blob_id(),
+                        expected_metadata.blob_id()
+                    )
+                    .into(),
+                )));
+            }
+        } else {
+            journal.record_metadata(&metadata).map_err(journal_error)?;
+        }
+        Ok((pairs, metadata))
+    }
+
+    /// Collects the confirmation certificate for the blob, taking the journaled confirmations
+    /// into account.
+    ///
+    /// The blob is encoded (if `pairs` is not provided) only if the journaled confirmations do not
+    /// suffice to create the certificate.
+    async fn get_certificate_with_journal(
+        &self,
+        blob: &[u8],
+        pairs: Option<Vec<SliverPair>>,
+        metadata: &VerifiedBlobMetadataWithId,
+        blob_object: &Blob,
+        journal: &StoreJournal,
+    ) -> ClientResult<ConfirmationCertificate> {
+        let committees = self.get_committees().await?;
+        let journaled = self.journaled_confirmations::<Infallible>(journal, &committees);
+        let journaled_weight: usize = journaled.iter().map(|result| result.1).sum();
+
+        if committees
+            .write_committee()
+            .is_at_least_min_n_correct(journaled_weight)
+        {
+            tracing::info!(
+                blob_id = %metadata.blob_id(),
+                "creating the certificate from the journaled confirmations"
+            );
+            return self
+                .confirmations_to_certificate(journaled, &committees)
+                .await;
+        }
+
+        let pairs = match pairs {
+            Some(pairs) => pairs,
+            None => self.encode_with_journal(blob, journal, Some(metadata))?.0,
+        };
+        self.send_blob_data_and_get_certificate_inner(
+            metadata,
+            &pairs,
+            &blob_object.blob_persistence_type(),
+            &MultiProgress::new(),
+            Some(journal),
         )
         .await
     }
@@ -728,8 +1233,12 @@ impl Client<SuiContractClient> {
 
     /// Stores the blobs on Walrus, reserving space or extending registered blobs, if necessary.
     ///
+    /// If `storage_object` is set, it is used to register the blobs that are not yet registered
+    /// (see [`ResourceManager::with_storage_object`]).
+    ///
     /// Returns a [`ClientErrorKind::CommitteeChangeNotified`] error if, during the registration or
     /// store operations, the client is notified that the committee has changed.
+    #[allow(clippy::too_many_arguments)]
     async fn reserve_and_store_encoded_blobs(
         &self,
         pairs_and_metadata: &[(Vec<SliverPair>, VerifiedBlobMetadataWithId)],
@@ -737,11 +1246,14 @@ impl Client<SuiContractClient> {
         store_when: StoreWhen,
         persistence: BlobPersistence,
         post_store: PostStoreAction,
+        storage_object: Option<ObjectID>,
+        progress: &StoreProgress,
     ) -> ClientResult<Vec<BlobStoreResult>> {
         tracing::info!(
             "storing {} sliver pairs with metadata",
             pairs_and_metadata.len()
         );
+        progress.report(StoreStage::Registering);
         let status_start_timer = Instant::now();
         let committees = self.get_committees().await?;
 
@@ -762,6 +1274,7 @@ impl Client<SuiContractClient> {
         let store_operations = self
             .resource_manager(&committees)
             .await
+            .with_storage_object(storage_object)
             .store_operation_for_blobs(
                 &blob_id_to_metadata_with_status
                     .values()
@@ -827,6 +1340,7 @@ impl Client<SuiContractClient> {
         // Get the blob certificates, possibly storing slivers, while checking if the committee has
         // changed in the meantime.
         // This operation can be safely interrupted as it does not require a wallet.
+        progress.report(StoreStage::Uploading);
         let blobs_with_certificates = self
             .await_while_checking_notification(
                 self.get_all_blob_certificates(&certify_blobs, &blob_id_to_metadata_with_status),
@@ -852,6 +1366,7 @@ impl Client<SuiContractClient> {
             .collect();
 
         // Certify all blobs on Sui.
+        progress.report(StoreStage::Certifying);
         let sui_cert_timer = Instant::now();
         let shared_blob_object_map = self
             .sui_client
@@ -1103,7 +1618,7 @@ impl Client<SuiContractClient> {
     }
 
     /// Creates a resource manager for the client.
-    pub async fn resource_manager(&self, committees: &ActiveCommittees) -> ResourceManager {
+    pub async fn resource_manager(&self, committees: &ActiveCommittees) -> ResourceManager<'_, T> {
         ResourceManager::new(&self.sui_client, committees.write_committee().epoch)
     }
 
@@ -1127,10 +1642,13 @@ impl Client<SuiContractClient> {
     /// Deletes all owned blobs that match the blob ID, and returns the number of deleted objects.
     pub async fn delete_owned_blob(&self, blob_id: &BlobId) -> ClientResult<usize> {
         let mut deleted = 0;
+        let mut dry_run = DeferredDryRun::default();
         for blob in self.deletable_blobs_by_id(blob_id).await? {
-            self.delete_owned_blob_by_object(blob.id).await?;
+            tracing::debug!(blob_object_id = %blob.id, "deleting blob object");
+            dry_run.defer(self.sui_client.delete_blob(blob.id).await)?;
             deleted += 1;
         }
+        dry_run.finish()?;
         Ok(deleted)
     }
 
@@ -1141,37 +1659,6 @@ impl Client<SuiContractClient> {
         Ok(())
     }
 
-    /// For each entry in `node_ids_with_amounts`, stakes the amount of WAL specified by the
-    /// second element of the pair with the node represented by the first element of the pair.
-    pub async fn stake_with_node_pools(
-        &self,
-        node_ids_with_amounts: &[(ObjectID, u64)],
-    ) -> ClientResult<Vec<StakedWal>> {
-        let staked_wal = self
-            .sui_client
-            .stake_with_pools(node_ids_with_amounts)
-            .await?;
-        Ok(staked_wal)
-    }
-
-    /// Stakes the specified amount of WAL with the node represented by `node_id`.
-    pub async fn stake_with_node_pool(&self, node_id: ObjectID, amount: u64) -> ClientResult<()> {
-        self.stake_with_node_pools(&[(node_id, amount)]).await?;
-        Ok(())
-    }
-
-    /// Exchanges the provided amount of SUI (in MIST) for WAL using the specified exchange.
-    pub async fn exchange_sui_for_wal(
-        &self,
-        exchange_id: ObjectID,
-        amount: u64,
-    ) -> ClientResult<()> {
-        Ok(self
-            .sui_client
-            .exchange_sui_for_wal(exchange_id, amount)
-            .await?)
-    }
-
     /// Returns the latest committees from the chain.
     #[cfg(any(test, feature = "test-utils"))]
     pub async fn get_latest_committees_in_test(&self) -> Result<ActiveCommittees, ClientError> {
@@ -1204,9 +1691,35 @@ impl<T> Client<T> {
         pairs: &[SliverPair],
         blob_persistence_type: &BlobPersistenceType,
         multi_pb: &MultiProgress,
+    ) -> ClientResult<ConfirmationCertificate> {
+        self.send_blob_data_and_get_certificate_inner(
+            metadata,
+            pairs,
+            blob_persistence_type,
+            multi_pb,
+            None,
+        )
+        .await
+    }
+
+    /// Stores the metadata and sliver pairs as [`Self::send_blob_data_and_get_certificate`].
+    ///
+    /// If a journal is provided, the storage nodes whose confirmations are already journaled for
+    /// the current epoch are skipped, and the newly received confirmations are journaled.
+    async fn send_blob_data_and_get_certificate_inner(
+        &self,
+        metadata: &VerifiedBlobMetadataWithId,
+        pairs: &[SliverPair],
+        blob_persistence_type: &BlobPersistenceType,
+        multi_pb: &MultiProgress,
+        journal: Option<&StoreJournal>,
     ) -> ClientResult<ConfirmationCertificate> {
         tracing::info!(blob_id = %metadata.blob_id(), "starting to send data to storage nodes");
         let committees = self.get_committees().await?;
+        let journaled = journal
+            .map(|journal| self.journaled_confirmations(journal, &committees))
+            .unwrap_or_default();
+        let journaled_weight: usize = journaled.iter().map(|result| result.1).sum();
         let mut pairs_per_node = self
             .pairs_per_node(metadata.blob_id(), pairs, &committees)
             .await;
@@ -1230,26 +1743,50 @@ impl<T> Client<T> {
         let progress_bar = {
             let pb = styled_progress_bar(bft::min_n_correct(committees.n_shards()).get().into());
             pb.set_message(format!("sending slivers ({})", metadata.blob_id()));
+            pb.inc(journaled_weight.try_into().expect("the weight fits a u64"));
             multi_pb.add(pb)
         };
 
-        let mut requests = WeightedFutures::new(comms.iter().map(|n| {
-            n.store_metadata_and_pairs(
-                metadata,
-                pairs_per_node
-                    .remove(&n.node_index)
-                    .expect("there are shards for each node"),
-                blob_persistence_type,
-            )
-            .inspect({
-                let value = progress_bar.clone();
-                move |result| {
-                    if result.is_ok() && !value.is_finished() {
-                        value.inc(result.1.try_into().expect("the weight fits a usize"))
-                    }
-                }
-            })
-        }));
+        let mut requests = WeightedFutures::new(
+            comms
+                .iter()
+                .filter(|n| !journaled.iter().any(|result| result.2 == n.node_index))
+                .map(|n| {
+                    n.store_metadata_and_pairs(
+                        metadata,
+                        pairs_per_node
+                            .remove(&n.node_index)
+                            .expect("there are shards for each node"),
+                        blob_persistence_type,
+                    )
+                    .inspect({
+                        let value = progress_bar.clone();
+                        move |result| {
+                            if result.is_ok() && !value.is_finished() {
+                                value.inc(result.1.try_into().expect("the weight fits a usize"))
+                            }
+                        }
+                    })
+                    .inspect(
+                        move |NodeResult(epoch, _, node_index, result)| {
+                            let (Some(journal), Ok(confirmation)) = (journal, result) else {
+                                return;
+                            };
+                            if let Err(error) = journal.record_confirmation(JournaledConfirmation {
+                                epoch: *epoch,
+                                node_index: *node_index,
+                                node_id: n.node.node_id,
+                                confirmation: confirmation.clone(),
+                            }) {
+                                tracing::warn!(
+                                    ?error,
+                                    "failed to journal the storage confirmation"
+                                );
+                            }
+                        },
+                    )
+                }),
+        );
         let start = Instant::now();
 
         // We do not limit the number of concurrent futures awaited here, because the number of
@@ -1259,7 +1796,7 @@ impl<T> Client<T> {
                 &|weight| {
                     committees
                         .write_committee()
-                        .is_at_least_min_n_correct(weight)
+                        .is_at_least_min_n_correct(weight + journaled_weight)
                 },
                 committees.n_shards().get().into(),
             )
@@ -1268,12 +1805,13 @@ impl<T> Client<T> {
             tracing::debug!(
                 elapsed_time = ?start.elapsed(),
                 executed_weight = weight,
+                journaled_weight,
                 responses = ?requests.into_results(),
                 blob_id = %metadata.blob_id(),
                 "all futures consumed before reaching a threshold of successful responses"
             );
             return Err(self
-                .not_enough_confirmations_error(weight, &committees)
+                .not_enough_confirmations_error(weight + journaled_weight, &committees)
                 .await);
         }
         tracing::debug!(
@@ -1322,12 +1860,39 @@ impl<T> Client<T> {
             metadata.blob_id()
         ));
 
-        let results = requests.into_results();
+        let mut results = requests.into_results();
+        results.extend(journaled);
 
         self.confirmations_to_certificate(results, &committees)
             .await
     }
 
+    /// Returns the journaled confirmations that are valid for the current write committee.
+    fn journaled_confirmations<E>(
+        &self,
+        journal: &StoreJournal,
+        committees: &ActiveCommittees,
+    ) -> Vec<NodeResult<SignedStorageConfirmation, E>> {
+        let write_committee = committees.write_committee();
+        journal
+            .entry()
+            .confirmations
+            .into_iter()
+            .filter_map(|journaled| {
+                let node = write_committee.members().get(journaled.node_index)?;
+                (journaled.epoch == write_committee.epoch && journaled.node_id == node.node_id)
+                    .then(|| {
+                        NodeResult(
+                            journaled.epoch,
+                            node.shard_ids.len(),
+                            journaled.node_index,
+                            Ok(journaled.confirmation),
+                        )
+                    })
+            })
+            .collect()
+    }
+
     /// Fetches confirmations for a blob from a quorum of nodes and returns the certificate.
     async fn get_certificate_standalone(
         &self,
@@ -1795,7 +2360,7 @@ impl<T> Client<T> {
     /// contained in the blocklist.
     fn check_blob_id(&self, blob_id: &BlobId) -> ClientResult<()> {
         if let Some(blocklist) = &self.blocklist {
-            if blocklist.is_blocked(blob_id) {
+            if blocklist.is_request_blocked(blob_id) {
                 tracing::debug!(%blob_id, "encountered blocked blob ID");
                 return Err(ClientErrorKind::BlobIdBlocked(*blob_id).into());
             }
@@ -8,6 +8,7 @@ use std::{
     fs,
     path::{Path, PathBuf},
     str::FromStr,
+    sync::Arc,
     time::Duration,
 };
 
@@ -40,7 +41,7 @@ pub use args::{
     PublisherArgs,
     SortBy,
 };
-pub use cli_output::CliOutput;
+pub use cli_output::{print_cli_error, CliOutput};
 pub use runner::ClientCommandRunner;
 
 /// Default URL of the testnet RPC node.
@@ -69,8 +70,9 @@ pub fn load_configuration(path: Option<impl AsRef<Path>>, context: Option<&str>)
 
 /// Creates a [`Client`] based on the provided [`Config`] with read-only access to Sui.
 ///
-/// The RPC URL is set based on the `rpc_url` parameter (if `Some`), the `wallet` (if `Ok`) or the
-/// default [`DEFAULT_RPC_URL`] if `allow_fallback_to_default` is true.
+/// The RPC URL is set based on the `rpc_url` parameter (if `Some`), the `rpc_urls` in the config
+/// (if non-empty), the `wallet` (if `Ok`) or the default [`DEFAULT_RPC_URL`] if
+/// `allow_fallback_to_default` is true.
 pub async fn get_read_client(
     config: Config,
     rpc_url: Option<String>,
@@ -90,15 +92,30 @@ pub async fn get_read_client(
         .refresh_config
         .build_refresher_and_run(sui_read_client.clone())
         .await?;
+    let blocklist = load_blocklist(blocklist_path, &config)?;
     let client = Client::new_read_client(config, refresh_handle, sui_read_client).await?;
 
-    if blocklist_path.is_some() {
-        Ok(client.with_blocklist(Blocklist::new(blocklist_path)?))
+    if let Some(blocklist) = blocklist {
+        Ok(client.with_blocklist(blocklist))
     } else {
         Ok(client)
     }
 }
 
+/// Loads the blocklist at `blocklist_path` along with the sources in the client configuration.
+///
+/// Returns `None` if neither a blocklist path nor additional sources are configured. Otherwise,
+/// the blocklist is refreshed periodically in the background, starting with a fetch of the remote
+/// lists. Must be called within a Tokio runtime.
+fn load_blocklist(blocklist_path: &Option<PathBuf>, config: &Config) -> Result<Option<Blocklist>> {
+    if blocklist_path.is_none() && config.blocklist.is_empty() {
+        return Ok(None);
+    }
+    let blocklist = Blocklist::new_with_config(blocklist_path, &config.blocklist, None)?;
+    Arc::new(blocklist.clone()).start_refresh_task();
+    Ok(Some(blocklist))
+}
+
 /// Creates a [`Client<SuiContractClient>`] based on the provided [`Config`] with write access to
 /// Sui.
 pub async fn get_contract_client(
@@ -113,10 +130,11 @@ pub async fn get_contract_client(
         .refresh_config
         .build_refresher_and_run(sui_client.read_client().clone())
         .await?;
+    let blocklist = load_blocklist(blocklist_path, &config)?;
     let client = Client::new_contract_client(config, refresh_handle, sui_client).await?;
 
-    if blocklist_path.is_some() {
-        Ok(client.with_blocklist(Blocklist::new(blocklist_path)?))
+    if let Some(blocklist) = blocklist {
+        Ok(client.with_blocklist(blocklist))
     } else {
         Ok(client)
     }
@@ -124,8 +142,9 @@ pub async fn get_contract_client(
 
 /// Creates a [`SuiReadClient`] from the provided RPC URL or wallet.
 ///
-/// The RPC URL is set based on the `rpc_url` parameter (if `Some`), the `wallet` (if `Ok`) or the
-/// default [`DEFAULT_RPC_URL`] if `allow_fallback_to_default` is true.
+/// The RPC URL is set based on the `rpc_url` parameter (if `Some`), the `rpc_urls` in the config
+/// (if non-empty), the `wallet` (if `Ok`) or the default [`DEFAULT_RPC_URL`] if
+/// `allow_fallback_to_default` is true.
 // NB: When making changes to the logic, make sure to update the docstring of `get_read_client` and
 // the argument docs in `crates/walrus-service/bin/client.rs`.
 pub async fn get_sui_read_client_from_rpc_node_or_wallet(
@@ -147,6 +166,16 @@ pub async fn get_sui_read_client_from_rpc_node_or_wallet(
                 .await
                 .context(format!("cannot connect to Sui RPC node at {url}"))
         }
+        None if !config.rpc_urls.is_empty() => {
+            tracing::info!(rpc_urls = ?config.rpc_urls, "using RPC URLs set in the configuration");
+            RetriableSuiClient::new_for_rpcs(
+                &config.rpc_urls,
+                backoff_config,
+                config.rpc_failover_config.clone(),
+            )
+            .await
+            .context("cannot connect to the Sui RPC nodes specified in the configuration")
+        }
         None => match wallet {
             Ok(wallet) => {
                 tracing::info!("using RPC URL set in wallet configuration");
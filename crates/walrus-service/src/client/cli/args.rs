@@ -7,15 +7,21 @@ use std::{
     net::SocketAddr,
     num::{NonZeroU16, NonZeroU32},
     path::PathBuf,
+    str::FromStr,
+    sync::Arc,
     time::{Duration, SystemTime},
 };
 
-use anyhow::{anyhow, Context as _, Result};
+use anyhow::{anyhow, bail, Context as _, Result};
 use clap::{Args, Parser, Subcommand};
+use fastcrypto::encoding::{Base64, Encoding as _};
 use jsonwebtoken::Algorithm;
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DisplayFromStr};
-use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::{
+    base_types::{ObjectID, SuiAddress},
+    transaction::TransactionData,
+};
 use walrus_core::{
     encoding::{EncodingConfig, EncodingConfigTrait},
     ensure,
@@ -26,12 +32,23 @@ use walrus_core::{
 };
 use walrus_sui::{
     client::{ExpirySelectionPolicy, ReadClient, SuiContractClient},
-    types::{move_structs::Authorized, StorageNode},
+    types::{
+        move_structs::{Authorized, Blob, BlobAttribute},
+        StorageNode,
+    },
     utils::SuiNetwork,
 };
 
 use super::{parse_blob_id, read_blob_from_file, BlobIdDecimal, HumanReadableBytes};
-use crate::client::{config::AuthConfig, daemon::CacheConfig};
+use crate::{
+    backup::IndexerClient,
+    client::{
+        config::AuthConfig,
+        daemon::{api_keys::ApiKeyStore, jobs::JobsConfig, CacheConfig},
+        RenewerConfig,
+    },
+    common::utils::load_from_yaml,
+};
 
 /// The command-line arguments for the Walrus client.
 #[derive(Parser, Debug, Clone, Deserialize)]
@@ -90,6 +107,33 @@ pub struct App {
     #[clap(long, action, global = true)]
     #[serde(default)]
     pub json: bool,
+    /// Do not sign and execute transactions with the wallet; instead, write the transaction that
+    /// would be executed next to the specified file, as base64-encoded BCS `TransactionData`.
+    ///
+    /// The transaction can then be signed externally (e.g., on an offline machine or with a
+    /// hardware wallet) and executed with `walrus submit-signed`. The wallet is only used to
+    /// determine the sender address and its gas coins.
+    ///
+    /// Supported by the `store`, `resume`, `extend`, `delete`, `stake`, and `node-admin`
+    /// commands.
+    #[clap(long, global = true, value_name = "PATH")]
+    #[serde(
+        default,
+        deserialize_with = "walrus_utils::config::resolve_home_dir_option"
+    )]
+    pub unsigned_tx_out: Option<PathBuf>,
+    /// Do not execute any transaction; instead, dry-run the transactions that would be executed
+    /// and print their gas cost, WAL cost, and the objects they would create, modify, or delete.
+    ///
+    /// Operations consisting of independent transactions (e.g., extending or burning many blobs)
+    /// dry-run all of them; operations whose transactions depend on each other stop at the first.
+    ///
+    /// Supported by all commands that execute transactions on Sui. For `store`, the blob is only
+    /// encoded and its cost is estimated, without contacting any storage node. For `renew-watch`,
+    /// a single check is performed and the extensions that would be made are printed.
+    #[clap(long, action, global = true)]
+    #[serde(default)]
+    pub dry_run: bool,
     /// The command to run.
     #[command(subcommand)]
     pub command: Commands,
@@ -206,8 +250,9 @@ pub enum CliCommands {
         epoch_arg: EpochArg,
         /// Perform a dry-run of the store without performing any actions on chain.
         ///
-        /// This assumes `--force`; i.e., it does not check the current status of the blob.
-        #[clap(long, action)]
+        /// This assumes `--force`; i.e., it does not check the current status of the blob. On the
+        /// command line, this is set with the global `--dry-run` flag.
+        #[clap(skip)]
         #[serde(default)]
         dry_run: bool,
         /// Do not check for the blob status before storing it.
@@ -238,6 +283,55 @@ pub enum CliCommands {
         #[clap(long, hide = true)]
         #[serde(default)]
         encoding_type: Option<EncodingType>,
+        /// The object ID of the storage resource to use to register the blob.
+        ///
+        /// The storage resource must be owned by the wallet, and be large enough and valid long
+        /// enough to store the blob for the requested epochs. Only a single file can be stored
+        /// with a given storage resource, and the blob must not already be registered in the
+        /// wallet.
+        #[clap(long, conflicts_with = "resumable")]
+        #[serde(default)]
+        storage_object: Option<ObjectID>,
+        /// Record the progress of the store operation in an on-disk journal.
+        ///
+        /// If the operation is interrupted, re-running the same command (or running `walrus
+        /// resume`) skips the completed steps. With this flag, the files are stored one at a time.
+        #[clap(long, action)]
+        #[serde(default)]
+        resumable: bool,
+        /// The directory in which the journals of resumable store operations are kept.
+        ///
+        /// Defaults to `~/.walrus/store-journal`.
+        #[clap(long)]
+        #[serde(
+            default,
+            deserialize_with = "walrus_utils::config::resolve_home_dir_option"
+        )]
+        journal_dir: Option<PathBuf>,
+    },
+    /// Resume interrupted resumable store operations.
+    ///
+    /// The store operations are resumed with the same parameters as the original `walrus store
+    /// --resumable` command. Only the steps that were not completed are performed.
+    Resume {
+        /// The files whose store operation should be resumed.
+        ///
+        /// If no file is specified, all the journaled store operations are resumed.
+        #[clap(value_name = "FILES")]
+        #[serde(
+            default,
+            deserialize_with = "walrus_utils::config::resolve_home_dir_vec"
+        )]
+        files: Vec<PathBuf>,
+        /// The directory in which the journals of resumable store operations are kept.
+        ///
+        /// Defaults to `~/.walrus/store-journal`.
+        #[clap(long)]
+        #[serde(
+            default,
+            deserialize_with = "walrus_utils::config::resolve_home_dir_option"
+        )]
+        journal_dir: Option<PathBuf>,
     },
     /// Read a blob from Walrus, given the blob ID.
     Read {
@@ -354,12 +448,53 @@ pub enum CliCommands {
         #[serde_as(as = "DisplayFromStr")]
         blob_id_decimal: BlobIdDecimal,
     },
+    /// Print the history of a blob: its registration, certification, extensions, and deletion or
+    /// invalidation, with the corresponding transactions.
+    ///
+    /// If an object ID is given, the history of that blob object is printed. If a blob ID is
+    /// given, the history of all the blob objects for that blob ID is printed; as the events
+    /// cannot be filtered by blob ID on chain, only the most recent events are scanned in that
+    /// case.
+    BlobHistory {
+        /// The blob ID or the object ID of the blob.
+        #[clap(allow_hyphen_values = true, value_name = "BLOB_ID|OBJECT_ID")]
+        #[serde_as(as = "DisplayFromStr")]
+        blob: BlobIdOrObjectId,
+        /// The maximum number of recent Walrus events to scan when looking up a blob ID.
+        #[clap(long, default_value_t = default::max_scanned_events())]
+        #[serde(default = "default::max_scanned_events")]
+        max_scanned_events: usize,
+        /// Read the events from the event database of a local storage node instead of querying
+        /// the Sui RPC node.
+        ///
+        /// This is the `events` directory in the storage path of the node. Note that the node
+        /// prunes the events that are included in certified event blobs.
+        #[clap(long)]
+        #[serde(default)]
+        event_db: Option<PathBuf>,
+        /// The URL of the Sui RPC node to use.
+        #[clap(flatten)]
+        #[serde(flatten)]
+        rpc_arg: RpcArg,
+    },
     /// List all registered blobs for the current wallet.
     ListBlobs {
         #[clap(long, action)]
         #[serde(default)]
         /// The output list of blobs will include expired blobs.
         include_expired: bool,
+        /// Filters on the listed blobs.
+        #[clap(flatten)]
+        #[serde(flatten)]
+        filter: BlobFilterArgs,
+        /// Sort configuration.
+        #[clap(flatten)]
+        #[serde(flatten)]
+        sort: SortBy<BlobSortBy>,
+        /// Print the list of blobs in CSV format.
+        #[clap(long, action)]
+        #[serde(default)]
+        csv: bool,
     },
     /// Delete a blob from Walrus.
     ///
@@ -474,20 +609,33 @@ pub enum CliCommands {
         #[clap(long)]
         amount: u64,
     },
-    /// Extend an owned or shared blob.
+    /// Extend an owned or shared blob, or all owned blobs expiring before a given epoch.
     Extend {
         /// The object ID of the blob to extend.
-        #[clap(long)]
-        blob_obj_id: ObjectID,
+        #[clap(long, required_unless_present = "expiring_before")]
+        #[serde(default)]
+        blob_obj_id: Option<ObjectID>,
         /// If the blob_obj_id refers to a shared blob object, this flag must be present.
-        #[clap(long)]
+        #[clap(long, requires = "blob_obj_id")]
+        #[serde(default)]
         shared: bool,
+        /// Extend all the owned blobs that expire before the given epoch.
+        ///
+        /// The blobs are extended in as few transactions as possible, after showing the total
+        /// cost of the extension.
+        #[clap(long, conflicts_with = "blob_obj_id")]
+        #[serde(default)]
+        expiring_before: Option<Epoch>,
         /// The number of epochs to extend the blob for.
         // TODO (WAL-614): Offer multiple options similar to the `store` command:
         // `--extended-epochs`, `--epochs-ahead`, `--max`, `--end-epoch`,
         // `--earliest-expiration-time`.
-        #[clap(long)]
+        #[clap(long, visible_alias = "by")]
         epochs_extended: EpochCount,
+        /// Proceed to extend the blobs expiring before the given epoch without confirmation.
+        #[clap(long, action, requires = "expiring_before")]
+        #[serde(default)]
+        yes: bool,
     },
     /// Share a blob.
     Share {
@@ -541,6 +689,60 @@ pub enum CliCommands {
         #[clap(index = 1)]
         blob_obj_id: ObjectID,
     },
+    /// Generate an API key for the publisher.
+    ///
+    /// Prints the key, and the entry to be added to the API keys file of the publisher. By
+    /// default, a static key is generated; with `--hmac`, the key is signed with an existing (or a
+    /// newly generated) HMAC secret, and can optionally expire.
+    GenerateApiKey {
+        /// The identifier of the key.
+        #[clap(long)]
+        id: String,
+        /// Generate an HMAC-signed key instead of a static one.
+        #[clap(long, action)]
+        #[serde(default)]
+        hmac: bool,
+        /// The file containing the hex-encoded HMAC secret with which to sign the key.
+        ///
+        /// If not specified, the secret is read from the `WALRUS_API_KEY_HMAC_SECRET` environment
+        /// variable; if the variable is not set either, a new secret is generated.
+        #[clap(long, requires = "hmac")]
+        #[serde(
+            default,
+            deserialize_with = "walrus_utils::config::resolve_home_dir_option"
+        )]
+        hmac_secret_file: Option<PathBuf>,
+        /// The duration after which the HMAC-signed key expires.
+        ///
+        /// If not specified, the key does not expire.
+        #[clap(long, value_parser = humantime::parse_duration, requires = "hmac")]
+        #[serde(default)]
+        valid_for: Option<Duration>,
+        /// The maximum size of the blobs stored with the key, in bytes.
+        #[clap(long)]
+        #[serde(default)]
+        max_size: Option<u64>,
+        /// The maximum number of epochs for which blobs can be stored with the key.
+        #[clap(long)]
+        #[serde(default)]
+        max_epochs: Option<EpochCount>,
+        /// The address to which the Blob objects stored with the key must be sent.
+        #[clap(long)]
+        #[serde(default)]
+        send_object_to: Option<SuiAddress>,
+        /// The encoding types that can be used with the key.
+        ///
+        /// Can be specified multiple times. If not specified, all encoding types are allowed.
+        #[clap(long = "encoding-type")]
+        #[serde(default)]
+        encoding_types: Vec<EncodingType>,
+    },
+    /// Manage the storage resources owned by the wallet.
+    Storage {
+        /// The specific storage command to run.
+        #[command(subcommand)]
+        command: StorageCommands,
+    },
     /// Administration subcommands for storage node operators.
     NodeAdmin {
         #[clap(long, global = true)]
@@ -550,6 +752,58 @@ pub enum CliCommands {
         #[command(subcommand)]
         command: NodeAdminCommands,
     },
+    /// Execute a transaction that was emitted with `--unsigned-tx-out` and signed externally.
+    SubmitSigned {
+        /// The transaction to execute.
+        #[clap(flatten)]
+        #[serde(flatten)]
+        transaction: TransactionInput,
+        /// The base64-encoded signatures over the transaction (e.g., as output in the
+        /// `suiSignature` field by `sui keytool sign`).
+        ///
+        /// For sponsored transactions, both the sender's and the sponsor's signatures are needed.
+        #[clap(long, required = true, num_args = 1..)]
+        signatures: Vec<String>,
+    },
+}
+
+impl CliCommands {
+    /// Returns `true` if the command supports emitting unsigned transactions with
+    /// `--unsigned-tx-out`.
+    pub fn supports_unsigned_tx_out(&self) -> bool {
+        matches!(
+            self,
+            CliCommands::Store { .. }
+                | CliCommands::Resume { .. }
+                | CliCommands::Extend { .. }
+                | CliCommands::Delete { .. }
+                | CliCommands::Stake { .. }
+                | CliCommands::Storage { .. }
+                | CliCommands::NodeAdmin { .. }
+        )
+    }
+
+    /// Returns `true` if the command executes transactions on Sui and can therefore be dry-run
+    /// with `--dry-run`.
+    pub fn supports_dry_run(&self) -> bool {
+        matches!(
+            self,
+            CliCommands::Store { .. }
+                | CliCommands::Resume { .. }
+                | CliCommands::Extend { .. }
+                | CliCommands::Delete { .. }
+                | CliCommands::Share { .. }
+                | CliCommands::FundSharedBlob { .. }
+                | CliCommands::Stake { .. }
+                | CliCommands::GetWal { .. }
+                | CliCommands::BurnBlobs { .. }
+                | CliCommands::SetBlobAttribute { .. }
+                | CliCommands::RemoveBlobAttributeFields { .. }
+                | CliCommands::RemoveBlobAttribute { .. }
+                | CliCommands::Storage { .. }
+                | CliCommands::NodeAdmin { .. }
+        )
+    }
 }
 
 /// Subcommands for the `info` command.
@@ -613,6 +867,178 @@ pub enum NodeAdminCommands {
     },
 }
 
+/// Filters on the blobs listed by the `list-blobs` command.
+#[derive(Debug, Clone, Default, Args, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BlobFilterArgs {
+    /// Only list the blobs that expire at or after the given epoch.
+    #[clap(long)]
+    #[serde(default)]
+    pub(crate) expiring_after: Option<Epoch>,
+    /// Only list the blobs that expire before the given epoch.
+    #[clap(long)]
+    #[serde(default)]
+    pub(crate) expiring_before: Option<Epoch>,
+    /// Only list deletable (`true`) or permanent (`false`) blobs.
+    #[clap(long)]
+    #[serde(default)]
+    pub(crate) deletable: Option<bool>,
+    /// Only list the blobs with an unencoded size of at least the given number of bytes.
+    #[clap(long)]
+    #[serde(default)]
+    pub(crate) min_size: Option<u64>,
+    /// Only list the blobs with an unencoded size of at most the given number of bytes.
+    #[clap(long)]
+    #[serde(default)]
+    pub(crate) max_size: Option<u64>,
+    /// Only list the blobs whose attribute contains the given key-value pair.
+    ///
+    /// Multiple pairs can be specified by repeating the flag, in which case all of them must
+    /// match. Example:
+    ///   --attr "key1" "value1" --attr "key2" "value2"
+    #[clap(
+        long = "attr",
+        value_names = &["KEY", "VALUE"],
+        num_args = 2,
+        action = clap::ArgAction::Append,
+        verbatim_doc_comment,
+    )]
+    #[serde(default)]
+    pub(crate) attributes: Vec<String>,
+}
+
+impl BlobFilterArgs {
+    /// Returns `true` if the blob matches the filters on its expiry, deletable flag, and size.
+    ///
+    /// The attribute filters are checked separately with [`Self::matches_attribute`], as they
+    /// require fetching the attribute of the blob.
+    pub(crate) fn matches(&self, blob: &Blob) -> bool {
+        self.expiring_after
+            .is_none_or(|epoch| blob.storage.end_epoch >= epoch)
+            && self
+                .expiring_before
+                .is_none_or(|epoch| blob.storage.end_epoch < epoch)
+            && self
+                .deletable
+                .is_none_or(|deletable| blob.deletable == deletable)
+            && self.min_size.is_none_or(|size| blob.size >= size)
+            && self.max_size.is_none_or(|size| blob.size <= size)
+    }
+
+    /// Returns `true` if the blob attribute contains all the key-value pairs of the filter.
+    pub(crate) fn matches_attribute(&self, attribute: Option<&BlobAttribute>) -> bool {
+        self.attribute_pairs().all(|(key, value)| {
+            attribute.is_some_and(|attribute| {
+                attribute
+                    .iter()
+                    .any(|(attr_key, attr_value)| attr_key == key && attr_value == value)
+            })
+        })
+    }
+
+    /// Returns `true` if the filter contains attribute key-value pairs.
+    pub(crate) fn has_attribute_filter(&self) -> bool {
+        !self.attributes.is_empty()
+    }
+
+    /// Checks that the arguments are consistent.
+    pub(crate) fn validate(&self) -> Result<()> {
+        ensure!(
+            self.attributes.len() % 2 == 0,
+            "attributes must be provided as key-value pairs"
+        );
+        if let (Some(min_size), Some(max_size)) = (self.min_size, self.max_size) {
+            ensure!(
+                min_size <= max_size,
+                "the minimum size must not be larger than the maximum size"
+            );
+        }
+        Ok(())
+    }
+
+    fn attribute_pairs(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.attributes
+            .chunks_exact(2)
+            .map(|pair| (&pair[0], &pair[1]))
+    }
+}
+
+/// Subcommands for the `storage` command.
+#[derive(Subcommand, Debug, Clone, Deserialize, PartialEq, Eq)]
+#[clap(rename_all = "kebab-case")]
+#[serde(rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum StorageCommands {
+    /// List the storage resources owned by the wallet.
+    List {
+        /// The output list of storage resources will include expired ones.
+        #[clap(long, action)]
+        #[serde(default)]
+        include_expired: bool,
+    },
+    /// Split a storage resource by epoch or by size.
+    ///
+    /// The original storage resource keeps the first part (the earlier epochs or the given size),
+    /// and a new storage resource is created for the remainder.
+    Split {
+        /// The object ID of the storage resource to split.
+        #[clap(long)]
+        storage_object: ObjectID,
+        /// Where to split the storage resource.
+        #[clap(flatten)]
+        #[serde(flatten)]
+        split_at: SplitAt,
+    },
+    /// Fuse two or more storage resources into one.
+    ///
+    /// The storage resources must either cover the same epochs, or adjacent epochs with the same
+    /// size. The first storage resource is kept, and all the others are merged into it.
+    Fuse {
+        /// The object IDs of the storage resources to fuse.
+        #[clap(required = true, num_args = 2.., value_name = "STORAGE_OBJECTS")]
+        storage_objects: Vec<ObjectID>,
+    },
+    /// Transfer storage resources to another address.
+    Transfer {
+        /// The object IDs of the storage resources to transfer.
+        #[clap(required = true, num_args = 1.., value_name = "STORAGE_OBJECTS")]
+        storage_objects: Vec<ObjectID>,
+        /// The address to which the storage resources are transferred.
+        #[clap(long)]
+        to: SuiAddress,
+    },
+    /// Buy a new storage resource.
+    Buy {
+        /// The size of the storage resource, in bytes.
+        ///
+        /// Note that blobs need storage for their encoded size, which is considerably larger than
+        /// their unencoded size (see `walrus info`).
+        #[clap(long)]
+        size: u64,
+        /// The epoch argument to specify either the number of epochs to buy the storage for, or
+        /// the end epoch, or the earliest expiry time in rfc3339 format.
+        #[clap(flatten)]
+        #[serde(flatten)]
+        epoch_arg: EpochArg,
+    },
+}
+
+/// The point at which a storage resource is split.
+#[derive(Debug, Clone, Args, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[group(required = true, multiple = false)]
+pub struct SplitAt {
+    /// The epoch at which to split the storage resource.
+    ///
+    /// The original storage resource ends at this epoch, and the new one starts at it.
+    #[clap(long)]
+    pub(crate) epoch: Option<Epoch>,
+    /// The size in bytes kept by the original storage resource.
+    ///
+    /// The new storage resource covers the remaining size for the same epochs.
+    #[clap(long)]
+    pub(crate) size: Option<u64>,
+}
+
 #[derive(Debug, Clone, Args, Deserialize, PartialEq, Eq)]
 #[group(required = true, multiple = false)]
 pub struct ObjectOrAddress {
@@ -685,6 +1111,16 @@ pub enum DaemonCommands {
         /// The aggregator args.
         aggregator_args: AggregatorArgs,
     },
+    /// Watch blobs and automatically extend them before they expire.
+    ///
+    /// By default, all valid blobs owned by the wallet are watched. Blobs that expire within the
+    /// configured number of epochs are extended, as long as the budget allows it.
+    RenewWatch {
+        #[clap(flatten)]
+        #[serde(flatten)]
+        /// The renewer args.
+        args: RenewerArgs,
+    },
 }
 
 impl DaemonCommands {
@@ -694,6 +1130,7 @@ impl DaemonCommands {
             DaemonCommands::Publisher { args } => args.daemon_args.metrics_address,
             DaemonCommands::Aggregator { daemon_args, .. } => daemon_args.metrics_address,
             DaemonCommands::Daemon { args, .. } => args.daemon_args.metrics_address,
+            DaemonCommands::RenewWatch { args } => args.metrics_address,
         }
     }
 }
@@ -711,6 +1148,123 @@ pub struct AggregatorArgs {
     #[clap(long, num_args = 1.., default_values_t = default::allowed_headers())]
     #[serde(default = "default::allowed_headers")]
     pub(crate) allowed_headers: Vec<String>,
+    /// The URL of a blob metadata indexer (`walrus-indexer`).
+    ///
+    /// If set, the aggregator exposes the /v1/blobs/by-attribute endpoint, which looks up the blob
+    /// objects with a given attribute through the indexer.
+    #[clap(long)]
+    #[serde(default)]
+    pub(crate) indexer_url: Option<String>,
+}
+
+impl AggregatorArgs {
+    /// Returns a client for the configured blob metadata indexer, if any.
+    pub(crate) fn indexer_client(&self) -> Result<Option<IndexerClient>> {
+        self.indexer_url
+            .as_deref()
+            .map(IndexerClient::new)
+            .transpose()
+    }
+}
+
+/// The arguments for the blob renewal service.
+#[derive(Debug, Clone, Args, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RenewerArgs {
+    /// Socket address on which the Prometheus server should export its metrics.
+    #[clap(short = 'a', long, default_value_t = default::metrics_address())]
+    #[serde(default = "default::metrics_address")]
+    pub(crate) metrics_address: SocketAddr,
+    /// Blobs are extended when they expire within this number of epochs.
+    #[clap(long, default_value_t = default::renew_within_epochs())]
+    #[serde(default = "default::renew_within_epochs")]
+    pub(crate) within_epochs: EpochCount,
+    /// The number of epochs by which the blobs are extended.
+    ///
+    /// The extension is capped such that blobs are not stored for more than the maximum number of
+    /// epochs ahead.
+    #[clap(long, default_value_t = default::renew_extend_by())]
+    #[serde(default = "default::renew_extend_by")]
+    pub(crate) extend_by: EpochCount,
+    /// The maximum amount of FROST (smallest unit of WAL token) to spend on extending owned
+    /// blobs during the lifetime of the service.
+    ///
+    /// Extensions of shared blobs are paid from the funds of the shared blob and do not count
+    /// towards the budget. If not specified, the amount is not limited.
+    #[clap(long)]
+    #[serde(default)]
+    pub(crate) budget: Option<u64>,
+    /// The file in which the renewer persists the amounts spent so far.
+    ///
+    /// If specified, the budget is preserved across restarts of the service, and the file is
+    /// created if it does not exist. Otherwise, the budget is reset on every restart.
+    #[clap(long)]
+    #[serde(
+        default,
+        deserialize_with = "walrus_utils::config::resolve_home_dir_option"
+    )]
+    pub(crate) state_file: Option<PathBuf>,
+    /// The object IDs of shared blobs to watch in addition to the owned blobs.
+    #[clap(long = "shared-blob", num_args = 1..)]
+    #[serde(default)]
+    pub(crate) shared_blobs: Vec<ObjectID>,
+    /// Do not watch the blobs owned by the wallet, only the specified shared blobs.
+    #[clap(long, action, requires = "shared_blobs")]
+    #[serde(default)]
+    pub(crate) skip_owned: bool,
+    /// Only watch the owned blobs whose attribute contains the given key-value pair.
+    ///
+    /// Multiple pairs can be specified by repeating the flag, in which case all of them must
+    /// match. Example:
+    ///   --attr "key1" "value1" --attr "key2" "value2"
+    #[clap(
+        long = "attr",
+        value_names = &["KEY", "VALUE"],
+        num_args = 2,
+        action = clap::ArgAction::Append,
+        verbatim_doc_comment,
+    )]
+    #[serde(default)]
+    pub(crate) attributes: Vec<String>,
+    /// The interval between two consecutive checks of the watched blobs.
+    #[clap(long, value_parser = humantime::parse_duration, default_value = "10m")]
+    #[serde(default = "default::renew_interval")]
+    pub(crate) interval: Duration,
+    /// Perform a single check and print the extensions that would be performed, without
+    /// executing them.
+    ///
+    /// On the command line, this is set with the global `--dry-run` flag.
+    #[clap(skip)]
+    #[serde(default)]
+    pub(crate) dry_run: bool,
+}
+
+impl RenewerArgs {
+    /// Returns the [`RenewerConfig`] corresponding to the arguments.
+    pub(crate) fn to_config(&self) -> Result<RenewerConfig> {
+        ensure!(
+            self.extend_by > 0,
+            "the number of epochs to extend by must be positive"
+        );
+        ensure!(
+            self.attributes.len() % 2 == 0,
+            "attributes must be provided as key-value pairs"
+        );
+        Ok(RenewerConfig {
+            within_epochs: self.within_epochs,
+            extend_by: self.extend_by,
+            budget: self.budget,
+            state_path: self.state_file.clone(),
+            watch_owned: !self.skip_owned,
+            attributes: self
+                .attributes
+                .chunks_exact(2)
+                .map(|pair| (pair[0].clone(), pair[1].clone()))
+                .collect(),
+            shared_blobs: self.shared_blobs.clone(),
+            interval: self.interval,
+        })
+    }
 }
 
 /// The arguments for the publisher service.
@@ -826,6 +1380,57 @@ pub struct PublisherArgs {
     #[serde(flatten)]
     /// The configuration for the JWT duplicate suppression cache.
     pub replay_suppression_config: CacheConfig,
+    /// The directory where the publisher persists the asynchronous store jobs.
+    ///
+    /// Unfinished jobs found in this directory are resumed when the publisher starts. If unset,
+    /// the `jobs` subdirectory of `--sub-wallets-dir` is used.
+    #[clap(long)]
+    #[serde(
+        default,
+        deserialize_with = "walrus_utils::config::resolve_home_dir_option"
+    )]
+    pub jobs_dir: Option<PathBuf>,
+    /// The time for which terminated store jobs are kept, before they are removed from the
+    /// publisher.
+    #[clap(long, value_parser = humantime::parse_duration, default_value = "1day")]
+    #[serde(default = "default::job_ttl")]
+    pub job_ttl: Duration,
+    /// The maximum number of store jobs that can be queued or running at the same time.
+    ///
+    /// Asynchronous store requests received while this maximum is reached are rejected with a 503
+    /// HTTP status code.
+    #[clap(long, default_value_t = default::max_pending_jobs())]
+    #[serde(default = "default::max_pending_jobs")]
+    pub max_pending_jobs: usize,
+    /// The hosts to which the publisher is allowed to deliver the results of store jobs.
+    ///
+    /// Webhooks are disabled unless at least one host is specified. Only `http` and `https`
+    /// webhook URLs with one of these hosts are accepted.
+    #[clap(long, num_args = 1..)]
+    #[serde(default)]
+    pub webhook_allowed_hosts: Vec<String>,
+    /// Path to a YAML file with the configuration of the per-subject quotas.
+    ///
+    /// If set, the publisher accounts the bytes uploaded, the WAL spent, and the number of uploads
+    /// to the subject of each JWT, and rejects uploads that exceed the configured quotas. Requires
+    /// JWT authentication to be enabled.
+    #[clap(long)]
+    #[serde(
+        default,
+        deserialize_with = "walrus_utils::config::resolve_home_dir_option"
+    )]
+    pub quota_config: Option<PathBuf>,
+    /// Path to a YAML file containing the API keys accepted by the publisher.
+    ///
+    /// If set, store requests can be authenticated with an API key in the `x-api-key` header,
+    /// instead of a JWT. The file is reloaded periodically, such that keys can be added and revoked
+    /// without restarting the publisher. If no JWT option is set, only API keys are accepted.
+    #[clap(long)]
+    #[serde(
+        default,
+        deserialize_with = "walrus_utils::config::resolve_home_dir_option"
+    )]
+    pub api_keys_file: Option<PathBuf>,
 }
 
 impl PublisherArgs {
@@ -833,6 +1438,22 @@ impl PublisherArgs {
         self.max_body_size_kib << 10
     }
 
+    /// Returns the configuration of the asynchronous store jobs.
+    ///
+    /// If no jobs directory is specified, the `jobs` subdirectory of the sub-wallets directory is
+    /// used.
+    pub(crate) fn jobs_config(&self) -> JobsConfig {
+        JobsConfig {
+            jobs_dir: self
+                .jobs_dir
+                .clone()
+                .unwrap_or_else(|| self.sub_wallets_dir.join("jobs")),
+            job_ttl: self.job_ttl,
+            webhook_allowed_hosts: self.webhook_allowed_hosts.clone(),
+            max_pending_jobs: self.max_pending_jobs,
+        }
+    }
+
     fn format_max_body_size(&self) -> String {
         format!(
             "{}",
@@ -853,12 +1474,28 @@ impl PublisherArgs {
     }
 
     pub(crate) fn generate_auth_config(&self) -> Result<Option<AuthConfig>> {
-        if self.jwt_decode_secret.is_some() || self.jwt_expiring_sec > 0 || self.jwt_verify_upload {
+        let jwt_enabled =
+            self.jwt_decode_secret.is_some() || self.jwt_expiring_sec > 0 || self.jwt_verify_upload;
+        if jwt_enabled || self.api_keys_file.is_some() {
             let mut auth_config = AuthConfig {
                 expiring_sec: self.jwt_expiring_sec,
                 verify_upload: self.jwt_verify_upload,
                 algorithm: self.jwt_algorithm,
                 replay_suppression_config: self.replay_suppression_config.clone(),
+                quota_config: self
+                    .quota_config
+                    .as_ref()
+                    .map(load_from_yaml)
+                    .transpose()
+                    .context("unable to load the quota configuration")?,
+                api_keys: self
+                    .api_keys_file
+                    .as_deref()
+                    .map(ApiKeyStore::load)
+                    .transpose()
+                    .context("unable to load the API keys")?
+                    .map(Arc::new),
+                api_keys_only: !jwt_enabled,
                 ..Default::default()
             };
 
@@ -868,6 +1505,8 @@ impl PublisherArgs {
 
             tracing::info!(config=?auth_config, "authentication config applied");
             Ok(Some(auth_config))
+        } else if self.quota_config.is_some() {
+            bail!("quotas can only be enforced if the authentication is enabled");
         } else {
             tracing::info!("auth disabled");
             Ok(None)
@@ -881,8 +1520,8 @@ impl PublisherArgs {
 pub struct RpcArg {
     /// The URL of the Sui RPC node to use.
     ///
-    /// If unset, the wallet configuration is applied (if set), or the fullnode at
-    /// `fullnode.testnet.sui.io:443` is used.
+    /// If unset, the `rpc_urls` of the client configuration are used (if set), then the wallet
+    /// configuration is applied (if set), or the fullnode at `fullnode.testnet.sui.io:443` is used.
     // NB: Keep this in sync with `crate::cli`.
     #[clap(long)]
     #[serde(default)]
@@ -909,6 +1548,81 @@ pub struct DaemonArgs {
     pub(crate) blocklist: Option<PathBuf>,
 }
 
+#[derive(Debug, Clone, Args, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[group(required = true, multiple = false)]
+pub struct TransactionInput {
+    /// The base64-encoded BCS bytes of the transaction data.
+    #[clap(long)]
+    #[serde(default)]
+    pub(crate) tx_bytes: Option<String>,
+    /// The file containing the base64-encoded transaction data, as written with
+    /// `--unsigned-tx-out`, or `-` to read it from standard input.
+    #[clap(long)]
+    #[serde(
+        default,
+        deserialize_with = "walrus_utils::config::resolve_home_dir_option"
+    )]
+    pub(crate) tx_file: Option<PathBuf>,
+}
+
+impl TransactionInput {
+    /// Reads and decodes the transaction data.
+    pub(crate) fn read_transaction_data(self) -> Result<TransactionData> {
+        self.read_transaction_data_from(std::io::stdin().lock())
+    }
+
+    /// Reads and decodes the transaction data, reading from `stdin` if the transaction file is
+    /// `-`.
+    fn read_transaction_data_from(self, mut stdin: impl std::io::Read) -> Result<TransactionData> {
+        let tx_bytes = match self {
+            TransactionInput {
+                tx_bytes: Some(tx_bytes),
+                ..
+            } => tx_bytes,
+            TransactionInput {
+                tx_file: Some(tx_file),
+                ..
+            } => {
+                let content = if tx_file.as_os_str() == "-" {
+                    let mut content = String::new();
+                    stdin
+                        .read_to_string(&mut content)
+                        .context("unable to read the transaction from standard input")?;
+                    content
+                } else {
+                    std::fs::read_to_string(&tx_file).with_context(|| {
+                        format!("unable to read the transaction file {}", tx_file.display())
+                    })?
+                };
+                single_transaction_line(&content)?.to_owned()
+            }
+            TransactionInput {
+                tx_bytes: None,
+                tx_file: None,
+            } => unreachable!("one of the arguments is required"),
+        };
+        let bytes = Base64::decode(tx_bytes.trim())
+            .map_err(|error| anyhow!("invalid base64 transaction data: {error}"))?;
+        bcs::from_bytes(&bytes).context("unable to deserialize the transaction data")
+    }
+}
+
+/// Returns the only non-empty line of a transaction file.
+fn single_transaction_line(content: &str) -> Result<&str> {
+    let mut lines = content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty());
+    let tx_bytes = lines.next().context("the transaction file is empty")?;
+    ensure!(
+        lines.next().is_none(),
+        "the transaction file contains multiple transactions; \
+        pass them one at a time with `--tx-bytes`"
+    );
+    Ok(tx_bytes)
+}
+
 #[serde_as]
 #[derive(Debug, Clone, Args, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
@@ -954,6 +1668,37 @@ impl FileOrBlobId {
     }
 }
 
+/// Either a blob ID or the object ID of a blob object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlobIdOrObjectId {
+    /// A blob ID.
+    BlobId(BlobId),
+    /// The object ID of a blob object.
+    ObjectId(ObjectID),
+}
+
+impl FromStr for BlobIdOrObjectId {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(blob_id) = BlobId::from_str(s) {
+            return Ok(Self::BlobId(blob_id));
+        }
+        ObjectID::from_str(s)
+            .map(Self::ObjectId)
+            .map_err(|_| anyhow!("'{s}' is neither a valid blob ID nor a valid object ID"))
+    }
+}
+
+impl std::fmt::Display for BlobIdOrObjectId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BlobId(blob_id) => write!(f, "{blob_id}"),
+            Self::ObjectId(object_id) => write!(f, "{object_id}"),
+        }
+    }
+}
+
 /// Represents a blob.
 #[serde_as]
 #[derive(Debug, Clone, Args, Default, Serialize, Deserialize, PartialEq, Eq)]
@@ -1285,7 +2030,7 @@ impl EpochArg {
 }
 
 pub(crate) mod default {
-    use std::{net::SocketAddr, time::Duration};
+    use std::{net::SocketAddr, path::PathBuf, time::Duration};
 
     use walrus_sui::utils::SuiNetwork;
 
@@ -1328,10 +2073,42 @@ pub(crate) mod default {
         Duration::from_secs(1)
     }
 
+    pub(crate) fn job_ttl() -> Duration {
+        Duration::from_secs(24 * 60 * 60)
+    }
+
+    pub(crate) fn max_pending_jobs() -> usize {
+        // As for the synchronous requests, allow as many queued jobs as running ones.
+        max_concurrent_requests() + max_request_buffer_size()
+    }
+
+    pub(crate) fn renew_within_epochs() -> u32 {
+        2
+    }
+
+    pub(crate) fn renew_extend_by() -> u32 {
+        5
+    }
+
+    pub(crate) fn renew_interval() -> Duration {
+        Duration::from_secs(600)
+    }
+
+    pub(crate) fn store_journal_dir() -> PathBuf {
+        home::home_dir()
+            .unwrap_or_default()
+            .join(".walrus")
+            .join("store-journal")
+    }
+
     pub(crate) fn status_timeout() -> Duration {
         Duration::from_secs(10)
     }
 
+    pub(crate) fn max_scanned_events() -> usize {
+        10_000
+    }
+
     pub(crate) fn bind_address() -> SocketAddr {
         "127.0.0.1:31415"
             .parse()
@@ -1387,6 +2164,24 @@ mod tests {
     const READ_STR: &str = r#"{"read": {"blobId": "4BKcDC0Ih5RJ8R0tFMz3MZVNZV8b2goT6_JiEEwNHQo"}}"#;
     const DAEMON_STR: &str =
         r#"{"daemon": {"bindAddress": "127.0.0.1:12345", "subWalletsDir": "/some/path"}}"#;
+    const LIST_BLOBS_STR: &str = r#"{"listBlobs": {
+        "expiringBefore": 10,
+        "deletable": true,
+        "attributes": ["key", "value"],
+        "sortBy": "size",
+        "desc": true
+    }}"#;
+
+    const OBJECT_ID_2: &str = "0x0000000000000000000000000000000000000000000000000000000000000002";
+    const OBJECT_ID_3: &str = "0x0000000000000000000000000000000000000000000000000000000000000003";
+    const STORAGE_SPLIT_STR: &str = r#"{"storage": {"split": {
+        "storageObject": "0x0000000000000000000000000000000000000000000000000000000000000002",
+        "epoch": 5
+    }}}"#;
+    const STORAGE_FUSE_STR: &str = r#"{"storage": {"fuse": {"storageObjects": [
+        "0x0000000000000000000000000000000000000000000000000000000000000002",
+        "0x0000000000000000000000000000000000000000000000000000000000000003"
+    ]}}}"#;
 
     // Creates the fixture for the JSON command string.
     fn make_cmd_str(command: &str) -> String {
@@ -1414,6 +2209,9 @@ mod tests {
             deletable: false,
             share: false,
             encoding_type: Default::default(),
+            storage_object: None,
+            resumable: false,
+            journal_dir: None,
         })
     }
 
@@ -1426,6 +2224,24 @@ mod tests {
         })
     }
 
+    // Fixture for the list-blobs command.
+    fn list_blobs_command() -> Commands {
+        Commands::Cli(CliCommands::ListBlobs {
+            include_expired: false,
+            filter: BlobFilterArgs {
+                expiring_before: Some(10),
+                deletable: Some(true),
+                attributes: vec!["key".to_owned(), "value".to_owned()],
+                ..Default::default()
+            },
+            sort: SortBy {
+                sort_by: Some(BlobSortBy::Size),
+                desc: true,
+            },
+            csv: false,
+        })
+    }
+
     // Fixture for the daemon command.
     fn daemon_command() -> Commands {
         Commands::Daemon(DaemonCommands::Daemon {
@@ -1451,13 +2267,25 @@ mod tests {
                 jwt_expiring_sec: 0,
                 jwt_verify_upload: false,
                 replay_suppression_config: Default::default(),
+                jobs_dir: None,
+                job_ttl: default::job_ttl(),
+                max_pending_jobs: default::max_pending_jobs(),
+                webhook_allowed_hosts: vec![],
+                quota_config: None,
+                api_keys_file: None,
             },
             aggregator_args: AggregatorArgs {
                 allowed_headers: default::allowed_headers(),
+                indexer_url: None,
             },
         })
     }
 
+    // Fixture for the storage commands.
+    fn storage_command(command: StorageCommands) -> Commands {
+        Commands::Cli(CliCommands::Storage { command })
+    }
+
     param_test! {
         test_json_string_extraction -> TestResult: [
             store_max: (&make_cmd_str(STORE_STR_MAX), store_command(EpochCountOrMax::Max)),
@@ -1466,7 +2294,24 @@ mod tests {
                 store_command(EpochCountOrMax::Epochs(NonZeroU32::new(1).expect("1 > 0")))
             ),
             read: (&make_cmd_str(READ_STR), read_command()),
-            daemon: (&make_cmd_str(DAEMON_STR), daemon_command())
+            list_blobs: (&make_cmd_str(LIST_BLOBS_STR), list_blobs_command()),
+            daemon: (&make_cmd_str(DAEMON_STR), daemon_command()),
+            storage_split: (
+                &make_cmd_str(STORAGE_SPLIT_STR),
+                storage_command(StorageCommands::Split {
+                    storage_object: ObjectID::from_single_byte(2),
+                    split_at: SplitAt { epoch: Some(5), size: None },
+                })
+            ),
+            storage_fuse: (
+                &make_cmd_str(STORAGE_FUSE_STR),
+                storage_command(StorageCommands::Fuse {
+                    storage_objects: vec![
+                        ObjectID::from_single_byte(2),
+                        ObjectID::from_single_byte(3),
+                    ],
+                })
+            )
         ]
     }
     /// Test that the command string in JSON mode is extracted correctly.
@@ -1477,6 +2322,8 @@ mod tests {
             wallet: None,
             gas_budget: None,
             json: false,
+            unsigned_tx_out: None,
+            dry_run: false,
             command: Commands::Json {
                 command_string: Some(json.to_string()),
             },
@@ -1485,6 +2332,196 @@ mod tests {
         assert_eq!(app.command, command);
         Ok(())
     }
+
+    fn parse_storage_command(args: &[&str]) -> Result<StorageCommands, clap::Error> {
+        let app = App::try_parse_from(
+            ["walrus", "storage"]
+                .into_iter()
+                .chain(args.iter().copied()),
+        )?;
+        match app.command {
+            Commands::Cli(CliCommands::Storage { command }) => Ok(command),
+            command => panic!("unexpected command: {command:?}"),
+        }
+    }
+
+    param_test! {
+        test_parse_storage_split -> TestResult: [
+            at_epoch: (&["--epoch", "5"], SplitAt { epoch: Some(5), size: None }),
+            at_size: (&["--size", "1024"], SplitAt { epoch: None, size: Some(1024) }),
+        ]
+    }
+    fn test_parse_storage_split(split_args: &[&str], split_at: SplitAt) -> TestResult {
+        let args = [&["split", "--storage-object", OBJECT_ID_2][..], split_args].concat();
+        assert_eq!(
+            parse_storage_command(&args)?,
+            StorageCommands::Split {
+                storage_object: ObjectID::from_single_byte(2),
+                split_at,
+            }
+        );
+        Ok(())
+    }
+
+    param_test! {
+        test_parse_storage_command_rejects_invalid_args: [
+            split_without_split_point: (&["split", "--storage-object", OBJECT_ID_2]),
+            split_at_epoch_and_size: (
+                &["split", "--storage-object", OBJECT_ID_2, "--epoch", "5", "--size", "1024"]
+            ),
+            fuse_single_object: (&["fuse", OBJECT_ID_2]),
+            transfer_without_recipient: (&["transfer", OBJECT_ID_2]),
+            transfer_without_objects: (&["transfer", "--to", OBJECT_ID_3]),
+            buy_without_epochs: (&["buy", "--size", "1024"]),
+        ]
+    }
+    fn test_parse_storage_command_rejects_invalid_args(args: &[&str]) {
+        assert!(parse_storage_command(args).is_err());
+    }
+
+    #[test]
+    fn parse_storage_commands() -> TestResult {
+        assert_eq!(
+            parse_storage_command(&["list", "--include-expired"])?,
+            StorageCommands::List {
+                include_expired: true
+            }
+        );
+        assert_eq!(
+            parse_storage_command(&["fuse", OBJECT_ID_2, OBJECT_ID_3])?,
+            StorageCommands::Fuse {
+                storage_objects: vec![ObjectID::from_single_byte(2), ObjectID::from_single_byte(3)],
+            }
+        );
+        assert_eq!(
+            parse_storage_command(&["transfer", OBJECT_ID_2, "--to", OBJECT_ID_3])?,
+            StorageCommands::Transfer {
+                storage_objects: vec![ObjectID::from_single_byte(2)],
+                to: SuiAddress::from_str(OBJECT_ID_3)?,
+            }
+        );
+        assert_eq!(
+            parse_storage_command(&["buy", "--size", "1024", "--epochs", "3"])?,
+            StorageCommands::Buy {
+                size: 1024,
+                epoch_arg: EpochArg {
+                    epochs: Some(EpochCountOrMax::Epochs(NonZeroU32::new(3).expect("3 > 0"))),
+                    earliest_expiry_time: None,
+                    end_epoch: None,
+                },
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn store_rejects_storage_object_with_resumable() {
+        let result = App::try_parse_from([
+            "walrus",
+            "store",
+            "README.md",
+            "--epochs",
+            "1",
+            "--storage-object",
+            OBJECT_ID_2,
+            "--resumable",
+        ]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn blob_id_or_object_id_from_str() -> TestResult {
+        let blob_id = "4BKcDC0Ih5RJ8R0tFMz3MZVNZV8b2goT6_JiEEwNHQo";
+        assert_eq!(
+            BlobIdOrObjectId::from_str(blob_id)?,
+            BlobIdOrObjectId::BlobId(BlobId::from_str(blob_id)?)
+        );
+        let object_id = "0x4f2b2f7c6a5a7b3d3dbd0a3c8e6de1d2b6e9c9e1f0d7a1e2b3c4d5e6f7a8b9c0";
+        assert_eq!(
+            BlobIdOrObjectId::from_str(object_id)?,
+            BlobIdOrObjectId::ObjectId(ObjectID::from_str(object_id)?)
+        );
+        assert!(BlobIdOrObjectId::from_str("not an ID").is_err());
+        Ok(())
+    }
+
+    fn encoded_transaction() -> (TransactionData, String) {
+        let transaction = TransactionData::new_programmable(
+            SuiAddress::random_for_testing_only(),
+            vec![sui_types::base_types::random_object_ref()],
+            sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder::new()
+                .finish(),
+            1_000_000,
+            1_000,
+        );
+        let encoded = Base64::encode(bcs::to_bytes(&transaction).expect("serialization succeeds"));
+        (transaction, encoded)
+    }
+
+    fn transaction_input(tx_bytes: Option<String>, tx_file: Option<PathBuf>) -> TransactionInput {
+        TransactionInput { tx_bytes, tx_file }
+    }
+
+    #[test]
+    fn transaction_input_from_base64() -> TestResult {
+        let (transaction, encoded) = encoded_transaction();
+        let input = transaction_input(Some(encoded), None);
+        assert_eq!(
+            input.read_transaction_data_from(std::io::empty())?,
+            transaction
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn transaction_input_from_file() -> TestResult {
+        let (transaction, encoded) = encoded_transaction();
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("tx.b64");
+        std::fs::write(&path, format!("\n{encoded}\n\n"))?;
+
+        let input = transaction_input(None, Some(path));
+        assert_eq!(
+            input.read_transaction_data_from(std::io::empty())?,
+            transaction
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn transaction_input_from_stdin() -> TestResult {
+        let (transaction, encoded) = encoded_transaction();
+        let input = transaction_input(None, Some(PathBuf::from("-")));
+        let stdin = std::io::Cursor::new(format!("{encoded}\n"));
+        assert_eq!(input.read_transaction_data_from(stdin)?, transaction);
+        Ok(())
+    }
+
+    #[test]
+    fn transaction_input_rejects_invalid_input() -> TestResult {
+        let (_, encoded) = encoded_transaction();
+        let stdin_input = || transaction_input(None, Some(PathBuf::from("-")));
+
+        let multiple = std::io::Cursor::new(format!("{encoded}\n{encoded}\n"));
+        assert!(stdin_input().read_transaction_data_from(multiple).is_err());
+        let empty = std::io::Cursor::new("\n\n");
+        assert!(stdin_input().read_transaction_data_from(empty).is_err());
+
+        let not_base64 = transaction_input(Some("not base64!".to_owned()), None);
+        assert!(not_base64
+            .read_transaction_data_from(std::io::empty())
+            .is_err());
+        let not_a_transaction = transaction_input(Some(Base64::encode([1, 2, 3])), None);
+        assert!(not_a_transaction
+            .read_transaction_data_from(std::io::empty())
+            .is_err());
+
+        let missing_file = transaction_input(None, Some(PathBuf::from("/does/not/exist")));
+        assert!(missing_file
+            .read_transaction_data_from(std::io::empty())
+            .is_err());
+        Ok(())
+    }
 }
 
 /// Specifies whether the user has granted the confirmation for the action, or if it is required.
@@ -1533,6 +2570,23 @@ pub enum NodeSortBy {
     Url,
 }
 
+/// Sort options for the list of blobs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum BlobSortBy {
+    /// Sort by expiry epoch
+    #[default]
+    Expiry,
+    /// Sort by unencoded size
+    Size,
+    /// Sort by blob ID
+    BlobId,
+    /// Sort by object ID
+    ObjectId,
+    /// Sort by registration epoch
+    RegisteredEpoch,
+}
+
 /// Sort options for health information display
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum, Default)]
 #[serde(rename_all = "kebab-case")]
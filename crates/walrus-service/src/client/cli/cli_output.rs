@@ -11,7 +11,10 @@ use prettytable::{format, row, Table};
 use serde::Serialize;
 use walrus_core::{BlobId, ShardIndex};
 use walrus_sdk::api::{BlobStatus, DeletableCounts, EventProgress};
-use walrus_sui::types::Blob;
+use walrus_sui::{
+    client::{retry_client::RetriableRpcError as _, SuiClientError},
+    types::{move_errors::MoveExecutionError, Blob, StorageResource},
+};
 
 use super::warning;
 use crate::client::{
@@ -27,18 +30,24 @@ use crate::client::{
     },
     resource::RegisterBlobOp,
     responses::{
+        BlobHistoryOutput,
         BlobIdConversionOutput,
         BlobIdOutput,
         BlobStatusOutput,
         BlobStoreResultWithPath,
+        BuyStorageOutput,
         DeleteOutput,
         DryRunOutput,
+        DryRunTransactionsOutput,
         EncodingDependentPriceInfo,
         EpochTimeOrMessage,
         ExampleBlobInfo,
         ExchangeOutput,
         ExtendBlobOutput,
+        ExtendBlobsOutput,
         FundSharedBlobOutput,
+        FuseStorageOutput,
+        GenerateApiKeyOutput,
         GetBlobAttributeOutput,
         InfoBftOutput,
         InfoCommitteeOutput,
@@ -51,13 +60,52 @@ use crate::client::{
         ReadOutput,
         ServiceHealthInfoOutput,
         ShareBlobOutput,
+        SplitStorageOutput,
         StakeOutput,
         StorageNodeInfo,
+        SubmitSignedOutput,
+        TransferStorageOutput,
+        UnsignedTransactionsOutput,
         WalletOutput,
     },
     BlobStoreResult,
+    ClientError,
+    RenewalReport,
+    RenewalStatus,
 };
 
+/// Prints the error that terminated the CLI in a (relatively) user-friendly way, along with a
+/// hint on how to resolve it, if there is one.
+pub fn print_cli_error(err: &anyhow::Error) {
+    let error_str = if err.is_retriable_rpc_error() {
+        "The Sui full node RPC seems to be overwhelmed by too many requests. \
+        Please try with another full node, or try again later.\nError: "
+    } else {
+        ""
+    };
+    eprintln!("{} {}{:#}", error(), error_str, err);
+    if let Some(hint) = remediation_hint(err) {
+        eprintln!("{} {}", "Hint:".bold().walrus_purple(), hint);
+    }
+}
+
+/// Returns a hint on how to resolve the error, if an error in its chain of causes has one.
+fn remediation_hint(err: &anyhow::Error) -> Option<&'static str> {
+    err.chain().find_map(|cause| {
+        if let Some(error) = cause.downcast_ref::<ClientError>() {
+            error.remediation_hint()
+        } else if let Some(SuiClientError::TransactionExecutionError(error)) =
+            cause.downcast_ref::<SuiClientError>()
+        {
+            error.remediation_hint()
+        } else {
+            cause
+                .downcast_ref::<MoveExecutionError>()
+                .and_then(MoveExecutionError::remediation_hint)
+        }
+    })
+}
+
 /// Trait to differentiate output depending on the output mode.
 pub trait CliOutput: Serialize {
     /// Writes the output to stdout as a human-readable string for the CLI.
@@ -693,6 +741,69 @@ impl CliOutput for Vec<Blob> {
     }
 }
 
+/// Prints the list of blobs to stdout in CSV format.
+pub(crate) fn print_blobs_csv(blobs: &[Blob]) {
+    println!(
+        "blob_id,object_id,unencoded_size,encoded_size,certified_epoch,deletable,\
+        registered_epoch,end_epoch"
+    );
+    for blob in blobs {
+        println!(
+            "{},{},{},{},{},{},{},{}",
+            blob.blob_id,
+            blob.id,
+            blob.size,
+            blob.storage.storage_size,
+            blob.certified_epoch
+                .map(|epoch| epoch.to_string())
+                .unwrap_or_default(),
+            blob.deletable,
+            blob.registered_epoch,
+            blob.storage.end_epoch,
+        );
+    }
+}
+
+impl CliOutput for Vec<StorageResource> {
+    fn print_cli_output(&self) {
+        let mut table = Table::new();
+        table.set_format(default_table_format());
+        table.set_titles(row![
+            b->"Object ID",
+            bc->"Size",
+            bc->"Start epoch",
+            bc->"End epoch",
+        ]);
+
+        for storage in self {
+            table.add_row(row![
+                storage.id,
+                c->HumanReadableBytes(storage.storage_size),
+                c->storage.start_epoch,
+                c->storage.end_epoch,
+            ]);
+        }
+        table.printstd();
+    }
+}
+
+impl CliOutput for StorageResource {
+    fn print_cli_output(&self) {
+        println!(
+            "{}: {}
+{}: {}
+{}: {} - {}",
+            "Object ID".bold().walrus_purple(),
+            self.id,
+            "Size".bold().walrus_purple(),
+            HumanReadableBytes(self.storage_size),
+            "Epochs".bold().walrus_purple(),
+            self.start_epoch,
+            self.end_epoch,
+        );
+    }
+}
+
 impl CliOutput for DeleteOutput {
     fn print_cli_output(&self) {
         let identity = self.blob_identity.to_string();
@@ -799,6 +910,22 @@ impl CliOutput for WalletOutput {
     }
 }
 
+impl CliOutput for GenerateApiKeyOutput {
+    fn print_cli_output(&self) {
+        println!(
+            "{} Generated a new API key with ID {}:\n{}",
+            success(),
+            self.entry.id,
+            self.key
+        );
+        println!(
+            "\nAdd the following entry to the `keys` of the API keys file of the publisher:\n{}",
+            serde_yaml::to_string(&[&self.entry])
+                .expect("the API key entry can be serialized to YAML")
+        );
+    }
+}
+
 impl CliOutput for ExchangeOutput {
     fn print_cli_output(&self) {
         println!(
@@ -844,6 +971,130 @@ impl CliOutput for ExtendBlobOutput {
     }
 }
 
+impl CliOutput for ExtendBlobsOutput {
+    fn print_cli_output(&self) {
+        println!(
+            "{} {} blob(s) have been extended by {} epochs for a total cost of {}",
+            success(),
+            self.extended.len(),
+            self.epochs_extended,
+            HumanReadableFrost::from(self.total_cost)
+        );
+        if !self.skipped.is_empty() {
+            println!(
+                "{} {} blob(s) were skipped, as they would exceed the maximum number of epochs \
+                ahead:\n{}",
+                warning(),
+                self.skipped.len(),
+                self.skipped.iter().join("\n")
+            );
+        }
+    }
+}
+
+impl CliOutput for BlobHistoryOutput {
+    fn print_cli_output(&self) {
+        println!(
+            "{} {} (source: {})",
+            "History of blob".bold().walrus_purple(),
+            self.blob.bold(),
+            self.source
+        );
+        if self.entries.is_empty() {
+            println!("No events found for the blob.");
+            return;
+        }
+        let mut table = Table::new();
+        table.set_format(default_table_format());
+        table.set_titles(row![
+            bc->"Epoch",
+            b->"Event",
+            b->"Object ID",
+            bc->"End epoch",
+            b->"Transaction",
+            bc->"Seq.",
+        ]);
+        for entry in &self.entries {
+            table.add_row(row![
+                c->entry.epoch,
+                entry.kind,
+                entry
+                    .object_id
+                    .map_or_else(|| "-".to_owned(), |object_id| object_id.to_string()),
+                c->entry
+                    .end_epoch
+                    .map_or_else(|| "-".to_owned(), |end_epoch| end_epoch.to_string()),
+                entry.event_id.tx_digest,
+                c->entry.event_id.event_seq,
+            ]);
+        }
+        table.printstd();
+    }
+}
+
+impl CliOutput for RenewalReport {
+    fn print_cli_output(&self) {
+        let mode = if self.dry_run { " (dry run)" } else { "" };
+        println!(
+            "{}{}",
+            "Blob renewal report".bold().walrus_purple(),
+            mode.bold().walrus_purple()
+        );
+        println!(
+            "Current epoch: {}\nWatched blobs: {}\nExpiring blobs: {}",
+            self.current_epoch,
+            self.watched_blobs,
+            self.candidates.len()
+        );
+        if !self.candidates.is_empty() {
+            let mut table = Table::new();
+            table.set_format(default_table_format());
+            table.set_titles(row![
+                b->"Object ID",
+                b->"Blob ID",
+                bc->"Shared?",
+                bc->"Exp. epoch",
+                bc->"Extension",
+                bc->"Est. cost",
+                b->"Status",
+            ]);
+            for candidate in &self.candidates {
+                let status = match &candidate.status {
+                    RenewalStatus::Planned => "planned".to_string(),
+                    RenewalStatus::Extended => "extended".to_string(),
+                    RenewalStatus::OverBudget => "over budget".to_string(),
+                    RenewalStatus::InsufficientSharedFunds => "insufficient funds".to_string(),
+                    RenewalStatus::AtMaxEpochsAhead => "at max epochs ahead".to_string(),
+                    RenewalStatus::Failed(error) => format!("failed: {error}"),
+                };
+                table.add_row(row![
+                    candidate.object_id,
+                    candidate.blob_id,
+                    c->candidate.shared,
+                    c->candidate.end_epoch,
+                    c->candidate.epochs_extended,
+                    c->HumanReadableFrost::from(candidate.estimated_cost),
+                    status,
+                ]);
+            }
+            table.printstd();
+        }
+        println!(
+            "Cost of owned blob extensions: {}",
+            HumanReadableFrost::from(self.spent)
+        );
+        if !self.dry_run {
+            println!("Gas spent: {}", HumanReadableMist::from(self.gas_spent));
+        }
+        if let Some(remaining_budget) = self.remaining_budget {
+            println!(
+                "Remaining budget: {}",
+                HumanReadableFrost::from(remaining_budget)
+            );
+        }
+    }
+}
+
 impl CliOutput for NodeHealthOutput {
     fn print_cli_output(&self) {
         printdoc! {"
@@ -1089,3 +1340,174 @@ impl CliOutput for GetBlobAttributeOutput {
         }
     }
 }
+
+impl CliOutput for UnsignedTransactionsOutput {
+    fn print_cli_output(&self) {
+        let Some(first) = self.transactions.first() else {
+            println!("{} No transaction needed to be signed.", success());
+            return;
+        };
+        println!(
+            "{} The command stopped at a transaction that needs to be signed by {}.\n\
+            Wrote {} unsigned transaction(s) to {}:",
+            warning(),
+            first.sender,
+            self.transactions.len(),
+            self.path.display(),
+        );
+        for transaction in &self.transactions {
+            println!(
+                "\n{}: {}\n{}: {}\n{}: {}",
+                "Digest".bold().walrus_purple(),
+                transaction.digest,
+                "Gas budget".bold().walrus_purple(),
+                HumanReadableMist::from(transaction.gas_budget),
+                "Transaction bytes".bold().walrus_purple(),
+                transaction.tx_bytes,
+            );
+        }
+        println!(
+            "\nSign the transaction (e.g., with `sui keytool sign --data <BYTES>`) and execute it \
+            with `walrus submit-signed`.\n\
+            Then re-run the command to continue with the next step of the operation, if any."
+        );
+    }
+}
+
+impl CliOutput for DryRunTransactionsOutput {
+    fn print_cli_output(&self) {
+        let Some(first) = self.transactions.first() else {
+            println!(
+                "{} The command completed without executing any transaction.",
+                success()
+            );
+            return;
+        };
+        println!(
+            "{} Dry run: the command stopped at the following transaction, which was not \
+            executed.",
+            warning(),
+        );
+        if let Some(failure) = &first.error {
+            println!("{} The transaction would fail: {}", error(), failure);
+        }
+        printdoc!(
+            "
+
+            {digest_title}: {digest}
+            {sender_title}: {sender}
+            {gas_title}:
+                Computation cost: {computation}
+                Storage cost: {storage}
+                Storage rebate: {rebate}
+                Non-refundable storage fee: {non_refundable}
+                Net gas cost: {net_gas} (budget: {budget})
+            {balance_title}:
+                SUI: {sui_change}
+                WAL: {wal_change}
+            ",
+            digest_title = "Digest".bold().walrus_purple(),
+            digest = first.digest,
+            sender_title = "Sender".bold().walrus_purple(),
+            sender = first.sender,
+            gas_title = "Gas".bold().walrus_purple(),
+            computation = HumanReadableMist::from(first.computation_cost),
+            storage = HumanReadableMist::from(first.storage_cost),
+            rebate = HumanReadableMist::from(first.storage_rebate),
+            non_refundable = HumanReadableMist::from(first.non_refundable_storage_fee),
+            net_gas = signed_amount(first.net_gas_cost.into(), HumanReadableMist::from),
+            budget = HumanReadableMist::from(first.gas_budget),
+            balance_title = "Balance changes".bold().walrus_purple(),
+            sui_change = signed_amount(first.sui_balance_change, HumanReadableMist::from),
+            wal_change = signed_amount(first.wal_balance_change, HumanReadableFrost::from),
+        );
+        if first.object_changes.is_empty() {
+            println!("{}: none", "Object changes".bold().walrus_purple());
+        } else {
+            println!("{}:", "Object changes".bold().walrus_purple());
+            for change in &first.object_changes {
+                println!(
+                    "    {} {}{}",
+                    change.kind,
+                    change.object_id,
+                    change
+                        .object_type
+                        .as_ref()
+                        .map(|object_type| format!(" ({object_type})"))
+                        .unwrap_or_default()
+                );
+            }
+        }
+        println!(
+            "\nFor operations consisting of several transactions, only the first one is shown.\n\
+            Re-run the command without `--dry-run` to execute it."
+        );
+    }
+}
+
+/// Formats a signed amount with its sign, using `format` for the absolute value.
+fn signed_amount<T: std::fmt::Display>(amount: i128, format: impl Fn(u64) -> T) -> String {
+    let sign = if amount < 0 { "-" } else { "" };
+    let absolute = u64::try_from(amount.unsigned_abs()).unwrap_or(u64::MAX);
+    format!("{sign}{}", format(absolute))
+}
+
+impl CliOutput for SplitStorageOutput {
+    fn print_cli_output(&self) {
+        println!("{} The storage resource has been split.", success());
+        vec![self.original.clone(), self.created.clone()].print_cli_output();
+    }
+}
+
+impl CliOutput for FuseStorageOutput {
+    fn print_cli_output(&self) {
+        println!(
+            "{} {} storage resources have been fused into {}:",
+            success(),
+            self.consumed.len() + 1,
+            self.fused.id
+        );
+        self.fused.print_cli_output();
+    }
+}
+
+impl CliOutput for TransferStorageOutput {
+    fn print_cli_output(&self) {
+        println!(
+            "{} {} storage resources have been transferred to {}:\n{}",
+            success(),
+            self.storage_objects.len(),
+            self.recipient,
+            self.storage_objects.iter().join("\n")
+        );
+    }
+}
+
+impl CliOutput for BuyStorageOutput {
+    fn print_cli_output(&self) {
+        println!(
+            "{} A new storage resource has been purchased for {}:",
+            success(),
+            HumanReadableFrost::from(self.cost)
+        );
+        self.storage_resource.print_cli_output();
+    }
+}
+
+impl CliOutput for SubmitSignedOutput {
+    fn print_cli_output(&self) {
+        println!(
+            "{} Transaction executed successfully.\n{}: {}",
+            success(),
+            "Digest".bold().walrus_purple(),
+            self.digest,
+        );
+        if !self.created_objects.is_empty() {
+            println!(
+                "{}:\n{}",
+                "Created objects".bold().walrus_purple(),
+                self.created_objects.iter().join("\n")
+            );
+        }
+    }
+}
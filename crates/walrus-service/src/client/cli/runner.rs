@@ -14,13 +14,17 @@ use std::{
 
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
+use fastcrypto::{
+    encoding::{Base64, Encoding as _},
+    traits::ToFromBytes as _,
+};
 use indicatif::MultiProgress;
 use itertools::Itertools as _;
 use prometheus::Registry;
 use rand::seq::SliceRandom;
 use sui_config::{sui_config_dir, SUI_CLIENT_CONFIG};
-use sui_sdk::wallet_context::WalletContext;
-use sui_types::base_types::ObjectID;
+use sui_sdk::{rpc_types::SuiTransactionBlockEffectsAPI as _, wallet_context::WalletContext};
+use sui_types::{base_types::ObjectID, signature::GenericSignature};
 use walrus_core::{
     encoding::{
         encoded_blob_length_for_n_shards,
@@ -32,6 +36,7 @@ use walrus_core::{
     metadata::BlobMetadataApi as _,
     BlobId,
     EncodingType,
+    Epoch,
     EpochCount,
     DEFAULT_ENCODING,
     SUPPORTED_ENCODING_TYPES,
@@ -39,21 +44,31 @@ use walrus_core::{
 use walrus_sdk::api::BlobStatus;
 use walrus_sui::{
     client::{
+        transaction_builder::MAX_EXTENSIONS_PER_PTB,
         BlobPersistence,
+        DryRunSink,
         ExpirySelectionPolicy,
         PostStoreAction,
         ReadClient,
         SuiContractClient,
+        UnsignedTransactionSink,
     },
     config::WalletConfig,
-    types::move_structs::{Authorized, BlobAttribute, EpochState},
-    utils::SuiNetwork,
+    types::{
+        move_structs::{Authorized, Blob, BlobAttribute, EpochState},
+        BlobEvent,
+    },
+    utils::{price_for_encoded_length, SuiNetwork},
 };
 
 use super::args::{
+    default,
     AggregatorArgs,
+    BlobFilterArgs,
+    BlobIdOrObjectId,
     BlobIdentifiers,
     BlobIdentity,
+    BlobSortBy,
     BurnSelection,
     CliCommands,
     DaemonArgs,
@@ -65,13 +80,18 @@ use super::args::{
     NodeAdminCommands,
     NodeSelection,
     PublisherArgs,
+    RenewerArgs,
     RpcArg,
     SortBy,
+    SplitAt,
+    StorageCommands,
+    TransactionInput,
     UserConfirmation,
 };
 use crate::{
     client::{
         cli::{
+            cli_output::print_blobs_csv,
             get_contract_client,
             get_read_client,
             get_sui_read_client_from_rpc_node_or_wallet,
@@ -85,17 +105,26 @@ use crate::{
             HumanReadableMist,
         },
         communication::NodeCommunicationFactory,
+        daemon::api_keys::{self, ApiKeyCredential, ApiKeyLimits},
         error::ClientErrorKind,
         multiplexer::ClientMultiplexer,
+        resource::RegisterBlobOp,
         responses::{
+            BlobHistoryOutput,
             BlobIdConversionOutput,
             BlobIdOutput,
             BlobStatusOutput,
+            BlobStoreResultWithPath,
+            BuyStorageOutput,
             DeleteOutput,
             DryRunOutput,
+            DryRunTransactionsOutput,
             ExchangeOutput,
             ExtendBlobOutput,
+            ExtendBlobsOutput,
             FundSharedBlobOutput,
+            FuseStorageOutput,
+            GenerateApiKeyOutput,
             GetBlobAttributeOutput,
             InfoBftOutput,
             InfoCommitteeOutput,
@@ -107,15 +136,25 @@ use crate::{
             ReadOutput,
             ServiceHealthInfoOutput,
             ShareBlobOutput,
+            SplitStorageOutput,
             StakeOutput,
+            SubmitSignedOutput,
+            TransactionDryRunOutput,
+            TransferStorageOutput,
+            UnsignedTransaction,
+            UnsignedTransactionsOutput,
             WalletOutput,
         },
         styled_spinner,
+        BlobRenewer,
         Client,
         ClientDaemon,
         Config,
+        JournaledStoreParams,
+        StoreJournal,
         StoreWhen,
     },
+    common::config::TraceExportConfig,
     utils::{self, generate_sui_wallet, MetricsAndLoggingRuntime},
 };
 
@@ -132,6 +171,12 @@ pub struct ClientCommandRunner {
     gas_budget: Option<u64>,
     /// Whether the wallet was set explicitly as a CLI argument or in the config.
     wallet_set_explicitly: bool,
+    /// The file to which transactions are written for external signing, if any.
+    unsigned_tx_out: Option<PathBuf>,
+    /// The sink collecting transactions for external signing, if `unsigned_tx_out` is set.
+    unsigned_tx_sink: Option<UnsignedTransactionSink>,
+    /// The sink collecting dry-run results, if the commands are run with `--dry-run`.
+    dry_run_sink: Option<DryRunSink>,
 }
 
 impl ClientCommandRunner {
@@ -159,14 +204,117 @@ impl ClientCommandRunner {
             gas_budget,
             json,
             wallet_set_explicitly: wallet_config.is_some(),
+            unsigned_tx_out: None,
+            unsigned_tx_sink: None,
+            dry_run_sink: None,
         }
     }
 
+    /// Configures the runner to write transactions to `unsigned_tx_out` for external signing
+    /// instead of signing and executing them with the wallet.
+    pub fn with_unsigned_tx_out(mut self, unsigned_tx_out: Option<PathBuf>) -> Self {
+        self.unsigned_tx_sink = unsigned_tx_out.is_some().then(UnsignedTransactionSink::new);
+        self.unsigned_tx_out = unsigned_tx_out;
+        self
+    }
+
+    /// Configures the runner to dry-run transactions and report their effects instead of
+    /// executing them.
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run_sink = dry_run.then(DryRunSink::new);
+        self
+    }
+
+    /// Returns the configuration for exporting traces, if the client configuration could be loaded
+    /// and sets one.
+    pub fn trace_export_config(&self) -> Option<&TraceExportConfig> {
+        self.config.as_ref().ok()?.trace_export.as_ref()
+    }
+
     /// Runs the binary commands in "cli" mode (i.e., without running a server).
     ///
     /// Consumes `self`.
     #[tokio::main]
     pub async fn run_cli_app(self, command: CliCommands) -> Result<()> {
+        if let Some(sink) = self.dry_run_sink.clone() {
+            return self.run_cli_command_dry_run(command, sink).await;
+        }
+        let (Some(unsigned_tx_out), Some(sink)) =
+            (self.unsigned_tx_out.clone(), self.unsigned_tx_sink.clone())
+        else {
+            return self.run_cli_command(command).await;
+        };
+        anyhow::ensure!(
+            command.supports_unsigned_tx_out(),
+            "the command does not support `--unsigned-tx-out`"
+        );
+
+        let json = self.json;
+        let result = self.run_cli_command(command).await;
+        let transactions = sink.take();
+        if transactions.is_empty() {
+            // The command completed without needing to execute any transaction.
+            return result;
+        }
+        if let Err(error) = result {
+            tracing::debug!(%error, "the command stopped at a transaction for external signing");
+        }
+
+        let transactions = transactions
+            .iter()
+            .map(UnsignedTransaction::new)
+            .collect::<Result<Vec<_>>>()?;
+        let mut contents = transactions
+            .iter()
+            .map(|transaction| transaction.tx_bytes.as_str())
+            .join("\n");
+        contents.push('\n');
+        std::fs::write(&unsigned_tx_out, contents).with_context(|| {
+            format!(
+                "unable to write the unsigned transactions to {}",
+                unsigned_tx_out.display()
+            )
+        })?;
+        UnsignedTransactionsOutput {
+            path: unsigned_tx_out,
+            transactions,
+        }
+        .print_output(json)
+    }
+
+    /// Runs the command, dry-running the transactions it would execute instead of executing them,
+    /// and prints the results of the dry runs.
+    async fn run_cli_command_dry_run(self, command: CliCommands, sink: DryRunSink) -> Result<()> {
+        anyhow::ensure!(
+            command.supports_dry_run(),
+            "the command does not support `--dry-run`"
+        );
+        anyhow::ensure!(
+            self.unsigned_tx_out.is_none(),
+            "`--dry-run` cannot be combined with `--unsigned-tx-out`"
+        );
+
+        let json = self.json;
+        let result = self.run_cli_command(command).await;
+        let transactions = sink.take();
+        if transactions.is_empty() {
+            // The command completed without needing to execute any transaction.
+            return result;
+        }
+        if let Err(error) = result {
+            tracing::debug!(%error, "the command stopped at a dry-run transaction");
+        }
+
+        DryRunTransactionsOutput {
+            transactions: transactions
+                .iter()
+                .map(TransactionDryRunOutput::new)
+                .collect(),
+        }
+        .print_output(json)
+    }
+
+    async fn run_cli_command(self, command: CliCommands) -> Result<()> {
         match command {
             CliCommands::Read {
                 blob_id,
@@ -183,7 +331,11 @@ impl ClientCommandRunner {
                 deletable,
                 share,
                 encoding_type,
+                storage_object,
+                resumable,
+                journal_dir,
             } => {
+                let dry_run = dry_run || self.dry_run_sink.is_some();
                 self.store(
                     files,
                     epoch_arg,
@@ -192,6 +344,16 @@ impl ClientCommandRunner {
                     BlobPersistence::from_deletable(deletable),
                     PostStoreAction::from_share(share),
                     encoding_type,
+                    storage_object,
+                    resumable.then(|| journal_dir.unwrap_or_else(default::store_journal_dir)),
+                )
+                .await
+            }
+
+            CliCommands::Resume { files, journal_dir } => {
+                self.resume(
+                    files,
+                    journal_dir.unwrap_or_else(default::store_journal_dir),
                 )
                 .await
             }
@@ -227,7 +389,22 @@ impl ClientCommandRunner {
 
             CliCommands::ConvertBlobId { blob_id_decimal } => self.convert_blob_id(blob_id_decimal),
 
-            CliCommands::ListBlobs { include_expired } => self.list_blobs(include_expired).await,
+            CliCommands::BlobHistory {
+                blob,
+                max_scanned_events,
+                event_db,
+                rpc_arg: RpcArg { rpc_url },
+            } => {
+                self.blob_history(blob, max_scanned_events, event_db, rpc_url)
+                    .await
+            }
+
+            CliCommands::ListBlobs {
+                include_expired,
+                filter,
+                sort,
+                csv,
+            } => self.list_blobs(include_expired, filter, sort, csv).await,
 
             CliCommands::Delete {
                 target,
@@ -287,10 +464,11 @@ impl ClientCommandRunner {
                 shared_blob_obj_id,
                 amount,
             } => {
-                let sui_client = self
+                let mut sui_client = self
                     .config?
                     .new_contract_client(self.wallet?, self.gas_budget)
                     .await?;
+                sui_client.set_dry_run_sink(self.dry_run_sink);
                 let spinner = styled_spinner();
                 spinner.set_message("funding blob...");
 
@@ -305,12 +483,27 @@ impl ClientCommandRunner {
             CliCommands::Extend {
                 blob_obj_id,
                 shared,
+                expiring_before,
                 epochs_extended,
+                yes,
             } => {
-                let sui_client = self
+                let blob_obj_id = match (blob_obj_id, expiring_before) {
+                    (Some(blob_obj_id), None) => blob_obj_id,
+                    (None, Some(expiring_before)) => {
+                        return self
+                            .extend_expiring_blobs(expiring_before, epochs_extended, yes.into())
+                            .await;
+                    }
+                    _ => anyhow::bail!(
+                        "exactly one of `blob-obj-id` or `expiring-before` must be specified"
+                    ),
+                };
+                let mut sui_client = self
                     .config?
                     .new_contract_client(self.wallet?, self.gas_budget)
                     .await?;
+                sui_client.set_unsigned_transaction_sink(self.unsigned_tx_sink);
+                sui_client.set_dry_run_sink(self.dry_run_sink);
                 let spinner = styled_spinner();
                 spinner.set_message("extending blob...");
 
@@ -330,10 +523,11 @@ impl ClientCommandRunner {
                 blob_obj_id,
                 amount,
             } => {
-                let sui_client = self
+                let mut sui_client = self
                     .config?
                     .new_contract_client(self.wallet?, self.gas_budget)
                     .await?;
+                sui_client.set_dry_run_sink(self.dry_run_sink);
                 let spinner = styled_spinner();
                 spinner.set_message("sharing blob...");
 
@@ -373,6 +567,7 @@ impl ClientCommandRunner {
                     .config?
                     .new_contract_client(self.wallet?, self.gas_budget)
                     .await?;
+                sui_client.set_dry_run_sink(self.dry_run_sink);
                 let attribute = BlobAttribute::from(pairs);
                 sui_client
                     .insert_or_update_blob_attribute_pairs(blob_obj_id, attribute.iter(), true)
@@ -392,6 +587,7 @@ impl ClientCommandRunner {
                     .config?
                     .new_contract_client(self.wallet?, self.gas_budget)
                     .await?;
+                sui_client.set_dry_run_sink(self.dry_run_sink);
                 sui_client
                     .remove_blob_attribute_pairs(blob_obj_id, keys)
                     .await?;
@@ -410,6 +606,7 @@ impl ClientCommandRunner {
                     .config?
                     .new_contract_client(self.wallet?, self.gas_budget)
                     .await?;
+                sui_client.set_dry_run_sink(self.dry_run_sink);
                 sui_client.remove_blob_attribute(blob_obj_id).await?;
                 if !self.json {
                     println!(
@@ -421,9 +618,38 @@ impl ClientCommandRunner {
                 Ok(())
             }
 
+            CliCommands::GenerateApiKey {
+                id,
+                hmac,
+                hmac_secret_file,
+                valid_for,
+                max_size,
+                max_epochs,
+                send_object_to,
+                encoding_types,
+            } => self.generate_api_key(
+                id,
+                hmac.then(|| read_hmac_secret(hmac_secret_file.as_deref()))
+                    .transpose()?,
+                valid_for,
+                ApiKeyLimits {
+                    max_size,
+                    max_epochs,
+                    send_object_to,
+                    encoding_types: (!encoding_types.is_empty()).then_some(encoding_types),
+                },
+            ),
+
+            CliCommands::Storage { command } => self.storage(command).await,
+
             CliCommands::NodeAdmin { node_id, command } => {
                 self.run_admin_command(node_id, command).await
             }
+
+            CliCommands::SubmitSigned {
+                transaction,
+                signatures,
+            } => self.submit_signed(transaction, signatures).await,
         }
     }
 
@@ -436,6 +662,14 @@ impl ClientCommandRunner {
         command: DaemonCommands,
         metrics_runtime: MetricsAndLoggingRuntime,
     ) -> Result<()> {
+        anyhow::ensure!(
+            self.unsigned_tx_out.is_none(),
+            "daemon commands do not support `--unsigned-tx-out`"
+        );
+        anyhow::ensure!(
+            self.dry_run_sink.is_none() || matches!(command, DaemonCommands::RenewWatch { .. }),
+            "only the `renew-watch` daemon command supports `--dry-run`"
+        );
         self.maybe_export_contract_info(&metrics_runtime.registry);
 
         match command {
@@ -464,6 +698,10 @@ impl ClientCommandRunner {
                 self.daemon(&metrics_runtime.registry, args, aggregator_args)
                     .await
             }
+
+            DaemonCommands::RenewWatch { args } => {
+                self.renew_watch(&metrics_runtime.registry, args).await
+            }
         }
     }
 
@@ -527,6 +765,8 @@ impl ClientCommandRunner {
         persistence: BlobPersistence,
         post_store: PostStoreAction,
         encoding_type: Option<EncodingType>,
+        storage_object: Option<ObjectID>,
+        journal_dir: Option<PathBuf>,
     ) -> Result<()> {
         epoch_arg.exactly_one_is_some()?;
         if encoding_type.is_some_and(|encoding| !encoding.is_supported()) {
@@ -535,7 +775,12 @@ impl ClientCommandRunner {
             ));
         }
 
-        let client = get_contract_client(self.config?, self.wallet, self.gas_budget, &None).await?;
+        let mut client =
+            get_contract_client(self.config?, self.wallet, self.gas_budget, &None).await?;
+        client
+            .sui_client_mut()
+            .set_unsigned_transaction_sink(self.unsigned_tx_sink);
+        client.sui_client_mut().set_dry_run_sink(self.dry_run_sink);
 
         let system_object = client.sui_client().read_client.get_system_object().await?;
         let epochs_ahead =
@@ -552,6 +797,47 @@ impl ClientCommandRunner {
                 .await;
         }
 
+        if let Some(journal_dir) = journal_dir {
+            let params = JournaledStoreParams {
+                encoding_type,
+                epochs_ahead,
+                force: store_when.is_ignore_status(),
+                ignore_resources: store_when.is_ignore_resources(),
+                deletable: persistence.is_deletable(),
+                share: post_store == PostStoreAction::Share,
+            };
+            let journals = collect_journals(
+                files
+                    .iter()
+                    .map(|file| StoreJournal::open(&journal_dir, file, params.clone())),
+            )
+            .await?;
+            return Self::store_with_journals(client, journals, self.json).await;
+        }
+
+        if let Some(storage_object) = storage_object {
+            let [file] = <[PathBuf; 1]>::try_from(files).map_err(|_| {
+                anyhow::anyhow!("only a single file can be stored with a given storage resource")
+            })?;
+            let blob = read_blob_from_file(&file)?;
+            let result = client
+                .reserve_and_store_blob_with_storage_object(
+                    &blob,
+                    encoding_type,
+                    storage_object,
+                    epochs_ahead,
+                    store_when,
+                    persistence,
+                    post_store,
+                )
+                .await?;
+            return vec![BlobStoreResultWithPath {
+                blob_store_result: result,
+                path: file,
+            }]
+            .print_output(self.json);
+        }
+
         tracing::info!("storing {} files as blobs on Walrus", files.len());
         let start_timer = std::time::Instant::now();
         let blobs = files
@@ -590,6 +876,90 @@ impl ClientCommandRunner {
         results.print_output(self.json)
     }
 
+    pub(crate) async fn resume(self, files: Vec<PathBuf>, journal_dir: PathBuf) -> Result<()> {
+        let journal_paths = if files.is_empty() {
+            StoreJournal::list(&journal_dir)?
+        } else {
+            files
+                .iter()
+                .map(|file| -> Result<PathBuf> {
+                    let file = file.canonicalize().with_context(|| {
+                        format!("unable to resolve the path {}", file.display())
+                    })?;
+                    let path = StoreJournal::journal_path(&journal_dir, &file);
+                    anyhow::ensure!(
+                        path.exists(),
+                        "no journaled store operation found for {}",
+                        file.display()
+                    );
+                    Ok(path)
+                })
+                .collect::<Result<Vec<_>>>()?
+        };
+        if journal_paths.is_empty() {
+            println!("{} No interrupted store operations found.", warning());
+            return Ok(());
+        }
+
+        let mut client =
+            get_contract_client(self.config?, self.wallet, self.gas_budget, &None).await?;
+        client
+            .sui_client_mut()
+            .set_unsigned_transaction_sink(self.unsigned_tx_sink);
+        client.sui_client_mut().set_dry_run_sink(self.dry_run_sink);
+        let journals = collect_journals(journal_paths.iter().map(StoreJournal::load)).await?;
+        if let Some(error) = journals
+            .iter()
+            .find_map(|journal| journal.check_file_unchanged().err())
+        {
+            close_journals(journals).await;
+            return Err(error);
+        }
+        Self::store_with_journals(client, journals, self.json).await
+    }
+
+    /// Stores the journaled files one at a time, resuming from the journals.
+    async fn store_with_journals(
+        client: Client<SuiContractClient>,
+        journals: Vec<StoreJournal>,
+        json: bool,
+    ) -> Result<()> {
+        tracing::info!(
+            "storing {} files as blobs on Walrus with journals",
+            journals.len()
+        );
+        let start_timer = std::time::Instant::now();
+        let mut results = Vec::with_capacity(journals.len());
+        let mut journals = journals.into_iter();
+        while let Some(journal) = journals.next() {
+            let path = journal.entry().file;
+            let blob = match read_blob_from_file(&path) {
+                Ok(blob) => blob,
+                Err(error) => {
+                    close_journals(iter::once(journal).chain(journals)).await;
+                    return Err(error);
+                }
+            };
+            // On failure, the journal of the interrupted operation has already been closed.
+            match client.store_blob_with_journal(&blob, journal).await {
+                Ok(blob_store_result) => results.push(BlobStoreResultWithPath {
+                    blob_store_result,
+                    path,
+                }),
+                Err(error) => {
+                    close_journals(journals).await;
+                    return Err(error.into());
+                }
+            }
+        }
+        tracing::info!(
+            duration = ?start_timer.elapsed(),
+            "{} blobs stored",
+            results.len()
+        );
+        results.print_output(json)
+    }
+
     async fn store_dry_run(
         client: Client<SuiContractClient>,
         files: Vec<PathBuf>,
@@ -820,20 +1190,207 @@ impl ClientCommandRunner {
         BlobIdOutput::new(&file, &metadata).print_output(self.json)
     }
 
-    pub(crate) async fn list_blobs(self, include_expired: bool) -> Result<()> {
+    pub(crate) async fn blob_history(
+        self,
+        blob: BlobIdOrObjectId,
+        max_scanned_events: usize,
+        event_db: Option<PathBuf>,
+        rpc_url: Option<String>,
+    ) -> Result<()> {
+        let (events, source) = if let Some(event_db) = event_db {
+            (
+                read_blob_events_from_db(&event_db, blob)?,
+                event_db.display().to_string(),
+            )
+        } else {
+            let config = self.config?;
+            let sui_read_client = get_sui_read_client_from_rpc_node_or_wallet(
+                &config,
+                rpc_url,
+                self.wallet,
+                !self.wallet_set_explicitly,
+            )
+            .await?;
+            let events = match blob {
+                BlobIdOrObjectId::ObjectId(object_id) => {
+                    sui_read_client
+                        .get_blob_events_for_object(object_id)
+                        .await?
+                }
+                BlobIdOrObjectId::BlobId(blob_id) => {
+                    sui_read_client
+                        .get_blob_events_for_blob_id(blob_id, max_scanned_events)
+                        .await?
+                }
+            };
+            (events, "Sui RPC".to_owned())
+        };
+        BlobHistoryOutput::new(blob.to_string(), source, &events).print_output(self.json)
+    }
+
+    pub(crate) async fn list_blobs(
+        self,
+        include_expired: bool,
+        filter: BlobFilterArgs,
+        sort: SortBy<BlobSortBy>,
+        csv: bool,
+    ) -> Result<()> {
+        filter.validate()?;
+        anyhow::ensure!(
+            !(csv && self.json),
+            "`--csv` and `--json` cannot be used together"
+        );
         let config = self.config?;
         let contract_client = config
             .new_contract_client(self.wallet?, self.gas_budget)
             .await?;
-        let blobs = contract_client
+        let mut blobs = contract_client
             .owned_blobs(
                 None,
                 ExpirySelectionPolicy::from_include_expired_flag(include_expired),
             )
             .await?;
+        blobs.retain(|blob| filter.matches(blob));
+
+        if filter.has_attribute_filter() {
+            let mut matching_blobs = Vec::with_capacity(blobs.len());
+            for blob in blobs {
+                let attribute = contract_client
+                    .read_client()
+                    .get_blob_attribute(&blob.id)
+                    .await?;
+                if filter.matches_attribute(attribute.as_ref()) {
+                    matching_blobs.push(blob);
+                }
+            }
+            blobs = matching_blobs;
+        }
+
+        if let Some(sort_by) = &sort.sort_by {
+            let cmp = |a: &Blob, b: &Blob| match sort_by {
+                BlobSortBy::Expiry => a.storage.end_epoch.cmp(&b.storage.end_epoch),
+                BlobSortBy::Size => a.size.cmp(&b.size),
+                BlobSortBy::BlobId => a.blob_id.cmp(&b.blob_id),
+                BlobSortBy::ObjectId => a.id.cmp(&b.id),
+                BlobSortBy::RegisteredEpoch => a.registered_epoch.cmp(&b.registered_epoch),
+            };
+            blobs.sort_by(|a, b| if sort.desc { cmp(b, a) } else { cmp(a, b) });
+        }
+
+        if csv {
+            print_blobs_csv(&blobs);
+            return Ok(());
+        }
         blobs.print_output(self.json)
     }
 
+    pub(crate) async fn extend_expiring_blobs(
+        self,
+        expiring_before: Epoch,
+        epochs_extended: EpochCount,
+        confirmation: UserConfirmation,
+    ) -> Result<()> {
+        anyhow::ensure!(
+            epochs_extended > 0,
+            "the number of epochs to extend by must be positive"
+        );
+        let mut client =
+            get_contract_client(self.config?, self.wallet, self.gas_budget, &None).await?;
+        client
+            .sui_client_mut()
+            .set_unsigned_transaction_sink(self.unsigned_tx_sink);
+        client.sui_client_mut().set_dry_run_sink(self.dry_run_sink);
+        let sui_client = client.sui_client();
+
+        let current_epoch = sui_client.current_epoch().await?;
+        let max_epochs_ahead = sui_client
+            .read_client
+            .get_system_object()
+            .await?
+            .max_epochs_ahead();
+        let (blobs, skipped): (Vec<_>, Vec<_>) = sui_client
+            .owned_blobs(None, ExpirySelectionPolicy::Valid)
+            .await?
+            .into_iter()
+            .filter(|blob| blob.storage.end_epoch < expiring_before)
+            .partition(|blob| {
+                blob.storage.end_epoch + epochs_extended <= current_epoch + max_epochs_ahead
+            });
+
+        // In JSON mode, the skipped blobs are only reported in the output.
+        if !skipped.is_empty() && !self.json {
+            println!(
+                "{} {} blob(s) cannot be extended by {} epochs, as they would exceed the maximum \
+                of {} epochs ahead:\n{}",
+                warning(),
+                skipped.len(),
+                epochs_extended,
+                max_epochs_ahead,
+                skipped.iter().map(|blob| blob.id.to_string()).join("\n")
+            );
+        }
+        if blobs.is_empty() {
+            if self.json {
+                return ExtendBlobsOutput {
+                    extended: vec![],
+                    skipped: skipped.iter().map(|blob| blob.id).collect(),
+                    epochs_extended,
+                    total_cost: 0,
+                }
+                .print_output(self.json);
+            }
+            println!(
+                "The wallet does not own any blob objects expiring before epoch {} that can be \
+                extended.",
+                expiring_before
+            );
+            return Ok(());
+        }
+
+        let price_computation = client.get_price_computation().await?;
+        let total_cost: u64 = blobs
+            .iter()
+            .map(|blob| {
+                price_computation.operation_cost(&RegisterBlobOp::ReuseAndExtend {
+                    encoded_length: blob.storage.storage_size,
+                    epochs_extended,
+                })
+            })
+            .sum();
+
+        if confirmation.is_required() && !self.json {
+            println!(
+                "{} You are about to extend the following {} blob object(s) by {} epochs:",
+                warning(),
+                blobs.len(),
+                epochs_extended
+            );
+            blobs.print_cli_output();
+            println!(
+                "Total cost: {} in {} transaction(s)",
+                HumanReadableFrost::from(total_cost),
+                blobs.len().div_ceil(MAX_EXTENSIONS_PER_PTB)
+            );
+            if !ask_for_confirmation()? {
+                println!("{} Aborting. No blobs were extended.", success());
+                return Ok(());
+            }
+        }
+
+        let spinner = styled_spinner();
+        spinner.set_message("extending blobs...");
+        sui_client.extend_blobs(&blobs, epochs_extended).await?;
+        spinner.finish_with_message("done");
+
+        ExtendBlobsOutput {
+            extended: blobs.iter().map(|blob| blob.id).collect(),
+            skipped: skipped.iter().map(|blob| blob.id).collect(),
+            epochs_extended,
+            total_cost,
+        }
+        .print_output(self.json)
+    }
+
     pub(crate) async fn publisher(self, registry: &Registry, args: PublisherArgs) -> Result<()> {
         args.print_debug_message("attempting to run the Walrus publisher");
         let client = ClientMultiplexer::new(
@@ -854,7 +1411,8 @@ impl ClientCommandRunner {
             registry,
             args.max_request_buffer_size,
             args.max_concurrent_requests,
-        )
+            args.jobs_config(),
+        )?
         .run()
         .await?;
         Ok(())
@@ -876,11 +1434,13 @@ impl ClientCommandRunner {
             &daemon_args.blocklist,
         )
         .await?;
+        let indexer_client = aggregator_args.indexer_client()?;
         ClientDaemon::new_aggregator(
             client,
             daemon_args.bind_address,
             registry,
             aggregator_args.allowed_headers,
+            indexer_client,
         )
         .run()
         .await?;
@@ -903,16 +1463,72 @@ impl ClientCommandRunner {
             &args.daemon_args.blocklist,
         )
         .await?;
-        ClientDaemon::new_daemon(client, auth_config, registry, &args, &aggregator_args)
+        ClientDaemon::new_daemon(client, auth_config, registry, &args, &aggregator_args)?
             .run()
             .await?;
         Ok(())
     }
 
+    pub(crate) async fn renew_watch(self, registry: &Registry, args: RenewerArgs) -> Result<()> {
+        let renewer_config = args.to_config()?;
+        let contract_client = self
+            .config?
+            .new_contract_client(self.wallet?, self.gas_budget)
+            .await?;
+
+        if args.dry_run || self.dry_run_sink.is_some() {
+            return BlobRenewer::new(contract_client, renewer_config, None)?
+                .renew_expiring_blobs(true)
+                .await?
+                .print_output(self.json);
+        }
+
+        tracing::info!(?renewer_config, "attempting to run the Walrus blob renewer");
+        BlobRenewer::new(contract_client, renewer_config, Some(registry))?
+            .run()
+            .await
+    }
+
     pub(crate) fn convert_blob_id(self, blob_id_decimal: BlobIdDecimal) -> Result<()> {
         BlobIdConversionOutput::from(blob_id_decimal).print_output(self.json)
     }
 
+    /// Generates an API key for the publisher.
+    ///
+    /// If `hmac_secret` is `Some`, an HMAC-signed key is generated with the contained secret, or
+    /// with a new secret if none is specified. Otherwise, a static key is generated.
+    pub(crate) fn generate_api_key(
+        self,
+        id: String,
+        hmac_secret: Option<Option<(String, ApiKeyCredential)>>,
+        valid_for: Option<Duration>,
+        limits: ApiKeyLimits,
+    ) -> Result<()> {
+        let (key, entry) = if let Some(hmac_secret) = hmac_secret {
+            let expires_at = valid_for
+                .map(|valid_for| {
+                    Ok::<_, anyhow::Error>(
+                        (Utc::now() + chrono::Duration::from_std(valid_for)?).timestamp(),
+                    )
+                })
+                .transpose()?;
+            let (key, mut entry) = api_keys::generate_hmac_key(
+                id,
+                hmac_secret.as_ref().map(|(secret, _)| secret.clone()),
+                expires_at,
+                limits,
+            )?;
+            // Existing secrets are referenced instead of being copied to the keys file.
+            if let Some((_, credential)) = hmac_secret {
+                entry.credential = credential;
+            }
+            (key, entry)
+        } else {
+            api_keys::generate_static_key(id, limits)
+        };
+        GenerateApiKeyOutput { key, entry }.print_output(self.json)
+    }
+
     pub(crate) async fn delete(
         self,
         target: BlobIdentifiers,
@@ -921,7 +1537,7 @@ impl ClientCommandRunner {
         encoding_type: Option<EncodingType>,
     ) -> Result<()> {
         // Create client once to be reused
-        let client =
+        let mut client =
             match get_contract_client(self.config?, self.wallet, self.gas_budget, &None).await {
                 Ok(client) => client,
                 Err(e) => {
@@ -931,6 +1547,10 @@ impl ClientCommandRunner {
                     return Err(e);
                 }
             };
+        client
+            .sui_client_mut()
+            .set_unsigned_transaction_sink(self.unsigned_tx_sink);
+        client.sui_client_mut().set_dry_run_sink(self.dry_run_sink);
 
         let mut delete_outputs = Vec::new();
 
@@ -1015,7 +1635,12 @@ impl ClientCommandRunner {
                 .zip(amounts.into_iter())
                 .collect::<Vec<_>>()
         };
-        let client = get_contract_client(self.config?, self.wallet, self.gas_budget, &None).await?;
+        let mut client =
+            get_contract_client(self.config?, self.wallet, self.gas_budget, &None).await?;
+        client
+            .sui_client_mut()
+            .set_unsigned_transaction_sink(self.unsigned_tx_sink);
+        client.sui_client_mut().set_dry_run_sink(self.dry_run_sink);
         let staked_wal = client.stake_with_node_pools(&node_ids_with_amounts).await?;
         StakeOutput { staked_wal }.print_output(self.json)
     }
@@ -1050,7 +1675,8 @@ impl ClientCommandRunner {
                 or as a command-line argument.\n\
                 Note that this command is only available on Testnet.",
             )?;
-        let client = get_contract_client(config, self.wallet, self.gas_budget, &None).await?;
+        let mut client = get_contract_client(config, self.wallet, self.gas_budget, &None).await?;
+        client.sui_client_mut().set_dry_run_sink(self.dry_run_sink);
         tracing::info!(
             "exchanging {} for WAL using exchange object {exchange_id}",
             HumanReadableMist::from(amount)
@@ -1064,10 +1690,11 @@ impl ClientCommandRunner {
         burn_selection: BurnSelection,
         confirmation: UserConfirmation,
     ) -> Result<()> {
-        let sui_client = self
+        let mut sui_client = self
             .config?
             .new_contract_client(self.wallet?, self.gas_budget)
             .await?;
+        sui_client.set_dry_run_sink(self.dry_run_sink);
         let object_ids = burn_selection.get_object_ids(&sui_client).await?;
 
         if object_ids.is_empty() {
@@ -1106,15 +1733,148 @@ impl ClientCommandRunner {
         Ok(())
     }
 
+    pub(crate) async fn submit_signed(
+        self,
+        transaction: TransactionInput,
+        signatures: Vec<String>,
+    ) -> Result<()> {
+        let transaction_data = transaction.read_transaction_data()?;
+        let signatures = signatures
+            .iter()
+            .map(|signature| -> Result<GenericSignature> {
+                let bytes = Base64::decode(signature)
+                    .map_err(|error| anyhow::anyhow!("invalid base64 signature: {error}"))?;
+                GenericSignature::from_bytes(&bytes)
+                    .map_err(|error| anyhow::anyhow!("invalid signature: {error}"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let sui_client = self
+            .config?
+            .new_contract_client(self.wallet?, self.gas_budget)
+            .await?;
+        let spinner = styled_spinner();
+        spinner.set_message("executing transaction...");
+        let response = sui_client
+            .execute_signed_transaction(transaction_data, signatures)
+            .await?;
+        spinner.finish_with_message("done");
+
+        let created_objects = response
+            .effects
+            .as_ref()
+            .map(|effects| {
+                effects
+                    .created()
+                    .iter()
+                    .map(|object| object.reference.object_id)
+                    .collect()
+            })
+            .unwrap_or_default();
+        SubmitSignedOutput {
+            digest: response.digest,
+            created_objects,
+        }
+        .print_output(self.json)
+    }
+
+    pub(crate) async fn storage(self, command: StorageCommands) -> Result<()> {
+        let mut client =
+            get_contract_client(self.config?, self.wallet, self.gas_budget, &None).await?;
+        client
+            .sui_client_mut()
+            .set_unsigned_transaction_sink(self.unsigned_tx_sink);
+        client.sui_client_mut().set_dry_run_sink(self.dry_run_sink);
+        let sui_client = client.sui_client();
+        match command {
+            StorageCommands::List { include_expired } => sui_client
+                .owned_storage(ExpirySelectionPolicy::from_include_expired_flag(
+                    include_expired,
+                ))
+                .await?
+                .print_output(self.json),
+            StorageCommands::Split {
+                storage_object,
+                split_at,
+            } => {
+                let created = match split_at {
+                    SplitAt {
+                        epoch: Some(epoch),
+                        size: None,
+                    } => {
+                        sui_client
+                            .split_storage_by_epoch(storage_object, epoch)
+                            .await?
+                    }
+                    SplitAt {
+                        epoch: None,
+                        size: Some(size),
+                    } => {
+                        sui_client
+                            .split_storage_by_size(storage_object, size)
+                            .await?
+                    }
+                    _ => anyhow::bail!("exactly one of `epoch` or `size` must be specified"),
+                };
+                let original = sui_client
+                    .sui_client()
+                    .get_sui_object(storage_object)
+                    .await?;
+                SplitStorageOutput { original, created }.print_output(self.json)
+            }
+            StorageCommands::Fuse { storage_objects } => {
+                let (first, others) = storage_objects
+                    .split_first()
+                    .expect("clap requires at least two storage objects");
+                let fused = sui_client.fuse_storage(*first, others).await?;
+                FuseStorageOutput {
+                    fused,
+                    consumed: others.to_vec(),
+                }
+                .print_output(self.json)
+            }
+            StorageCommands::Transfer {
+                storage_objects,
+                to,
+            } => {
+                sui_client.transfer_storage(&storage_objects, to).await?;
+                TransferStorageOutput {
+                    storage_objects,
+                    recipient: to,
+                }
+                .print_output(self.json)
+            }
+            StorageCommands::Buy { size, epoch_arg } => {
+                epoch_arg.exactly_one_is_some()?;
+                let system_object = sui_client.read_client.get_system_object().await?;
+                let epochs_ahead =
+                    get_epochs_ahead(epoch_arg, system_object.max_epochs_ahead(), &client).await?;
+                let cost = price_for_encoded_length(
+                    size,
+                    system_object.storage_price_per_unit_size(),
+                    epochs_ahead,
+                );
+                let storage_resource = sui_client.reserve_space(size, epochs_ahead).await?;
+                BuyStorageOutput {
+                    storage_resource,
+                    cost,
+                }
+                .print_output(self.json)
+            }
+        }
+    }
+
     pub(crate) async fn run_admin_command(
         self,
         node_id: ObjectID,
         command: NodeAdminCommands,
     ) -> Result<()> {
-        let sui_client = self
+        let mut sui_client = self
             .config?
             .new_contract_client(self.wallet?, self.gas_budget)
             .await?;
+        sui_client.set_unsigned_transaction_sink(self.unsigned_tx_sink);
+        sui_client.set_dry_run_sink(self.dry_run_sink);
         match command {
             NodeAdminCommands::VoteForUpgrade {
                 upgrade_manager_object_id,
@@ -1324,6 +2084,83 @@ async fn get_epochs_ahead(
     Ok(epochs_ahead)
 }
 
+/// Collects the opened store journals, closing them again if any of them fails to open.
+async fn collect_journals(
+    journals: impl IntoIterator<Item = Result<StoreJournal>>,
+) -> Result<Vec<StoreJournal>> {
+    let mut opened = vec![];
+    for journal in journals {
+        match journal {
+            Ok(journal) => opened.push(journal),
+            Err(error) => {
+                close_journals(opened).await;
+                return Err(error);
+            }
+        }
+    }
+    Ok(opened)
+}
+
+/// Closes the store journals of operations that were not attempted, such that they can be resumed.
+async fn close_journals(journals: impl IntoIterator<Item = StoreJournal>) {
+    for journal in journals {
+        if let Err(error) = journal.close().await {
+            tracing::warn!(?error, "failed to close the store journal");
+        }
+    }
+}
+
+/// Reads the blob events for the given blob from the event database of a local storage node.
+#[cfg(feature = "node")]
+fn read_blob_events_from_db(db_path: &Path, blob: BlobIdOrObjectId) -> Result<Vec<BlobEvent>> {
+    crate::node::dbtool::read_blob_events(db_path, |event| match blob {
+        BlobIdOrObjectId::BlobId(blob_id) => event.blob_id() == blob_id,
+        BlobIdOrObjectId::ObjectId(object_id) => event.object_id() == Some(object_id),
+    })
+}
+
+#[cfg(not(feature = "node"))]
+fn read_blob_events_from_db(_db_path: &Path, _blob: BlobIdOrObjectId) -> Result<Vec<BlobEvent>> {
+    anyhow::bail!("reading from an event database requires the `node` feature")
+}
+
+/// The environment variable from which the HMAC secret of API keys is read, if no file is given.
+const HMAC_SECRET_ENV_VAR: &str = "WALRUS_API_KEY_HMAC_SECRET";
+
+/// Reads the hex-encoded HMAC secret of an API key from the file, or from the
+/// [`HMAC_SECRET_ENV_VAR`] environment variable if no file is given.
+///
+/// Returns the secret together with the credential referencing it in the API keys file, or `None`
+/// if neither is set, in which case a new secret should be generated.
+fn read_hmac_secret(secret_file: Option<&Path>) -> Result<Option<(String, ApiKeyCredential)>> {
+    if let Some(secret_file) = secret_file {
+        let secret = api_keys::read_secret_file(secret_file)?;
+        let credential = ApiKeyCredential::Hmac {
+            secret: None,
+            // The publisher resolves relative paths against the directory of the keys file.
+            secret_file: Some(
+                secret_file
+                    .canonicalize()
+                    .unwrap_or_else(|_| secret_file.to_owned()),
+            ),
+            secret_env: None,
+        };
+        return Ok(Some((secret, credential)));
+    }
+    Ok(std::env::var(HMAC_SECRET_ENV_VAR)
+        .ok()
+        .map(|secret| secret.trim().to_owned())
+        .filter(|secret| !secret.is_empty())
+        .map(|secret| {
+            let credential = ApiKeyCredential::Hmac {
+                secret: None,
+                secret_file: None,
+                secret_env: Some(HMAC_SECRET_ENV_VAR.to_owned()),
+            };
+            (secret, credential)
+        }))
+}
+
 pub fn ask_for_confirmation() -> Result<bool> {
     println!("Do you want to proceed? [y/N]");
     let mut input = String::new();
@@ -7,6 +7,7 @@ use std::{
     fmt,
     num::{NonZeroU16, NonZeroUsize},
     path::{Path, PathBuf},
+    sync::Arc,
     time::Duration,
 };
 
@@ -27,18 +28,20 @@ use walrus_sui::{
     client::{
         contract_config::ContractConfig,
         retry_client::RetriableSuiClient,
+        rpc_config::RpcFailoverConfig,
+        GasSponsor,
         SuiClientError,
         SuiContractClient,
         SuiReadClient,
     },
-    config::WalletConfig,
+    config::{GasSponsorConfig, WalletConfig},
 };
 use walrus_utils::backoff::ExponentialBackoffConfig;
 
-use super::daemon::CacheConfig;
+use super::daemon::{api_keys::ApiKeyStore, quota::QuotaConfig, CacheConfig};
 use crate::{
     client::{error::JwtDecodeError, refresh::CommitteesRefreshConfig},
-    common::utils,
+    common::{blocklist::BlocklistConfig, config::TraceExportConfig, utils},
 };
 
 /// Multi config for the client.
@@ -65,12 +68,31 @@ pub struct Config {
     /// Path to the wallet configuration.
     #[serde(default)]
     pub wallet_config: Option<WalletConfig>,
+    /// The sponsor paying for the gas of the client's transactions, if any.
+    #[serde(default)]
+    pub sponsor: Option<GasSponsorConfig>,
     /// Configuration for the client's network communication.
     #[serde(default)]
     pub communication_config: ClientCommunicationConfig,
     /// The configuration of the committee refresh from chain.
     #[serde(default)]
     pub refresh_config: CommitteesRefreshConfig,
+    /// The URLs of the Sui RPC nodes to use, in order of preference.
+    ///
+    /// If set, the client fails over between these nodes, and they take precedence over the RPC
+    /// URL in the wallet configuration. An RPC URL passed on the command line takes precedence over
+    /// these nodes.
+    #[serde(default)]
+    pub rpc_urls: Vec<String>,
+    /// The configuration of the failover between the RPC nodes in `rpc_urls`.
+    #[serde(default)]
+    pub rpc_failover_config: RpcFailoverConfig,
+    /// Configuration for exporting traces of the daemons to an OpenTelemetry collector.
+    #[serde(default)]
+    pub trace_export: Option<TraceExportConfig>,
+    /// Allow list and remote deny lists that are merged with the blocklist passed to the daemons.
+    #[serde(default)]
+    pub blocklist: BlocklistConfig,
 }
 
 impl Config {
@@ -123,18 +145,58 @@ impl Config {
     }
 
     /// Creates a [`SuiContractClient`] based on the configuration.
+    ///
+    /// If a gas sponsor is configured, the gas of the client's transactions is paid by the sponsor.
     pub async fn new_contract_client(
         &self,
         wallet_context: WalletContext,
         gas_budget: Option<u64>,
     ) -> Result<SuiContractClient, SuiClientError> {
-        SuiContractClient::new(
-            wallet_context,
-            &self.contract_config,
-            self.backoff_config().clone(),
-            gas_budget,
-        )
-        .await
+        let gas_sponsor = self.gas_sponsor()?;
+        self.new_contract_client_with_sponsor(wallet_context, gas_budget, gas_sponsor)
+            .await
+    }
+
+    /// Creates a [`SuiContractClient`] based on the configuration, with the given gas sponsor
+    /// instead of the configured one.
+    pub async fn new_contract_client_with_sponsor(
+        &self,
+        wallet_context: WalletContext,
+        gas_budget: Option<u64>,
+        gas_sponsor: Option<GasSponsor>,
+    ) -> Result<SuiContractClient, SuiClientError> {
+        let mut contract_client = if self.rpc_urls.is_empty() {
+            SuiContractClient::new(
+                wallet_context,
+                &self.contract_config,
+                self.backoff_config().clone(),
+                gas_budget,
+            )
+            .await?
+        } else {
+            let read_client = SuiReadClient::new_for_rpcs(
+                &self.rpc_urls,
+                &self.contract_config,
+                self.backoff_config().clone(),
+                self.rpc_failover_config.clone(),
+            )
+            .await?;
+            SuiContractClient::new_with_read_client(
+                wallet_context,
+                gas_budget,
+                Arc::new(read_client),
+            )?
+        };
+        contract_client.set_gas_sponsor(gas_sponsor);
+        Ok(contract_client)
+    }
+
+    /// Creates the [`GasSponsor`] configured in the `sponsor` section, if any.
+    pub fn gas_sponsor(&self) -> anyhow::Result<Option<GasSponsor>> {
+        self.sponsor
+            .as_ref()
+            .map(GasSponsor::from_config)
+            .transpose()
     }
 
     /// Creates a [`SuiContractClient`] with a wallet configured in the client config.
@@ -185,6 +247,12 @@ pub struct AuthConfig {
     pub(crate) verify_upload: bool,
     /// The configuration for the replay suppression cache.
     pub(crate) replay_suppression_config: CacheConfig,
+    /// The configuration of the per-subject quotas, if enabled.
+    pub(crate) quota_config: Option<QuotaConfig>,
+    /// The API keys accepted by the publisher, if enabled.
+    pub(crate) api_keys: Option<Arc<ApiKeyStore>>,
+    /// If set, the publisher only accepts API keys, and rejects JWTs.
+    pub(crate) api_keys_only: bool,
 }
 
 impl fmt::Debug for AuthConfig {
@@ -193,6 +261,9 @@ impl fmt::Debug for AuthConfig {
             .field("algorithm", &self.algorithm)
             .field("expiring_sec", &self.expiring_sec)
             .field("verify_upload", &self.verify_upload)
+            .field("quota_config", &self.quota_config)
+            .field("api_keys", &self.api_keys)
+            .field("api_keys_only", &self.api_keys_only)
             .finish()
     }
 }
@@ -650,8 +721,16 @@ mod tests {
                 ObjectID::random_from_rng(&mut rng),
             ],
             wallet_config: None,
+            sponsor: None,
             communication_config: Default::default(),
             refresh_config: Default::default(),
+            rpc_urls: vec![
+                "https://fullnode.testnet.sui.io:443".to_string(),
+                "https://sui-testnet-rpc.example.com:443".to_string(),
+            ],
+            rpc_failover_config: Default::default(),
+            trace_export: None,
+            blocklist: Default::default(),
         };
 
         walrus_test_utils::overwrite_file_and_fail_if_not_equal(
@@ -674,6 +753,47 @@ mod tests {
         Ok(())
     }
 
+    param_test! {
+        parses_sponsor_config -> TestResult: [
+            keyfile: (
+                indoc! {"
+                    sponsor:
+                        keyfile:
+                            path: /path/to/sponsor.keystore
+                "},
+                GasSponsorConfig::Keyfile {
+                    path: PathBuf::from("/path/to/sponsor.keystore"),
+                    address: None,
+                },
+            ),
+            service: (
+                indoc! {"
+                    sponsor:
+                        service:
+                            url: http://localhost:9527/
+                            auth_token: secret
+                "},
+                GasSponsorConfig::Service {
+                    url: "http://localhost:9527/".parse().expect("this is a valid URL"),
+                    auth_token: Some("secret".to_owned()),
+                },
+            ),
+        ]
+    }
+    fn parses_sponsor_config(sponsor_yaml: &str, expected: GasSponsorConfig) -> TestResult {
+        let yaml = indoc! {"
+            system_object: 0xa2637d13d171b278eadfa8a3fbe8379b5e471e1f3739092e5243da17fc8090eb
+            staking_object: 0xca7cf321e47a1fc9bfd032abc31b253f5063521fd5b4c431f2cdd3fee1b4ec00
+        "}
+        .to_owned()
+            + sponsor_yaml;
+
+        let config: Config = serde_yaml::from_str(&yaml)?;
+        assert_eq!(config.sponsor, Some(expected));
+
+        Ok(())
+    }
+
     #[test]
     fn parses_no_exchange_object_config_file() -> TestResult {
         let yaml = indoc! {"
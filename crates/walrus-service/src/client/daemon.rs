@@ -17,13 +17,21 @@ use axum::{
 };
 use axum_extra::{
     headers::{authorization::Bearer, Authorization},
+    typed_header::TypedHeaderRejection,
     TypedHeader,
 };
 use openapi::{AggregatorApiDoc, DaemonApiDoc, PublisherApiDoc};
 use prometheus::Registry;
 use reqwest::StatusCode;
 pub use routes::PublisherQuery;
-use routes::{BLOB_GET_ENDPOINT, BLOB_OBJECT_GET_ENDPOINT, BLOB_PUT_ENDPOINT, STATUS_ENDPOINT};
+use routes::{
+    BLOB_BY_ATTRIBUTE_ENDPOINT,
+    BLOB_GET_ENDPOINT,
+    BLOB_OBJECT_GET_ENDPOINT,
+    BLOB_PUT_ENDPOINT,
+    JOB_GET_ENDPOINT,
+    STATUS_ENDPOINT,
+};
 use sui_types::base_types::ObjectID;
 use tower::{
     buffer::BufferLayer,
@@ -40,20 +48,43 @@ use walrus_sui::{
     types::move_structs::BlobWithAttribute,
 };
 
-use super::{responses::BlobStoreResult, Client, ClientResult, StoreWhen};
+use super::{
+    responses::BlobStoreResult,
+    Blocklist,
+    Client,
+    ClientResult,
+    StoreProgress,
+    StoreWhen,
+};
 use crate::{
+    backup::IndexerClient,
     client::{
         cli::{AggregatorArgs, PublisherArgs},
         config::AuthConfig,
-        daemon::auth::verify_jwt_claim,
+        daemon::auth::{
+            authenticate_api_key,
+            verify_api_key,
+            verify_jwt,
+            verify_jwt_claim,
+            PublisherAuthError,
+        },
+    },
+    common::{
+        api::RestApiError as _,
+        telemetry::{metrics_middleware, HttpServerMetrics, MakeHttpSpan},
     },
-    common::telemetry::{metrics_middleware, HttpServerMetrics, MakeHttpSpan},
 };
 
+pub mod api_keys;
+use api_keys::API_KEY_HEADER;
 pub mod auth;
 pub(crate) mod cache;
 pub(crate) use cache::{CacheConfig, CacheHandle};
+pub mod jobs;
+use jobs::{JobManager, JobsConfig};
 mod openapi;
+pub mod quota;
+use quota::{quota_layer, QuotaTracker, USAGE_GET_ENDPOINT, USAGE_LIST_ENDPOINT};
 mod routes;
 
 pub trait WalrusReadClient {
@@ -66,6 +97,9 @@ pub trait WalrusReadClient {
         &self,
         blob_object_id: &ObjectID,
     ) -> impl std::future::Future<Output = ClientResult<BlobWithAttribute>> + Send;
+
+    /// Returns the blocklist checked by the client, if any.
+    fn blocklist(&self) -> Option<&Blocklist>;
 }
 
 /// Trait representing a client that can write blobs to Walrus.
@@ -79,6 +113,29 @@ pub trait WalrusWriteClient: WalrusReadClient {
         store_when: StoreWhen,
         persistence: BlobPersistence,
         post_store: PostStoreAction,
+    ) -> impl std::future::Future<Output = ClientResult<BlobStoreResult>> + Send {
+        self.write_blob_with_progress(
+            blob,
+            encoding_type,
+            epochs_ahead,
+            store_when,
+            persistence,
+            post_store,
+            StoreProgress::noop(),
+        )
+    }
+
+    /// Writes a blob to Walrus, reporting the stages of the store operation through `progress`.
+    #[allow(clippy::too_many_arguments)]
+    fn write_blob_with_progress(
+        &self,
+        blob: &[u8],
+        encoding_type: Option<EncodingType>,
+        epochs_ahead: EpochCount,
+        store_when: StoreWhen,
+        persistence: BlobPersistence,
+        post_store: PostStoreAction,
+        progress: StoreProgress,
     ) -> impl std::future::Future<Output = ClientResult<BlobStoreResult>> + Send;
 
     /// Returns the default [`PostStoreAction`] for this client.
@@ -96,10 +153,14 @@ impl<T: ReadClient> WalrusReadClient for Client<T> {
     ) -> ClientResult<BlobWithAttribute> {
         self.get_blob_by_object_id(blob_object_id).await
     }
+
+    fn blocklist(&self) -> Option<&Blocklist> {
+        self.blocklist.as_ref()
+    }
 }
 
 impl WalrusWriteClient for Client<SuiContractClient> {
-    async fn write_blob(
+    async fn write_blob_with_progress(
         &self,
         blob: &[u8],
         encoding_type: Option<EncodingType>,
@@ -107,17 +168,19 @@ impl WalrusWriteClient for Client<SuiContractClient> {
         store_when: StoreWhen,
         persistence: BlobPersistence,
         post_store: PostStoreAction,
+        progress: StoreProgress,
     ) -> ClientResult<BlobStoreResult> {
         let encoding_type = encoding_type.unwrap_or(DEFAULT_ENCODING);
 
         let result = self
-            .reserve_and_store_blobs_retry_committees(
+            .reserve_and_store_blobs_retry_committees_with_progress(
                 &[blob],
                 encoding_type,
                 epochs_ahead,
                 store_when,
                 persistence,
                 post_store,
+                &progress,
             )
             .await?;
 
@@ -147,14 +210,18 @@ pub struct ClientDaemon<T> {
 
 impl<T: WalrusReadClient + Send + Sync + 'static> ClientDaemon<T> {
     /// Constructs a new [`ClientDaemon`] with aggregator functionality.
+    ///
+    /// If an `indexer_client` is provided, the aggregator also serves attribute-based lookups
+    /// through the blob metadata indexer.
     pub fn new_aggregator(
         client: T,
         network_address: SocketAddr,
         registry: &Registry,
         allowed_headers: Vec<String>,
+        indexer_client: Option<IndexerClient>,
     ) -> Self {
         Self::new::<AggregatorApiDoc>(client, network_address, registry)
-            .with_aggregator(allowed_headers)
+            .with_aggregator(allowed_headers, indexer_client)
     }
 
     /// Creates a new [`ClientDaemon`], which serves requests at the provided `network_address` and
@@ -169,13 +236,17 @@ impl<T: WalrusReadClient + Send + Sync + 'static> ClientDaemon<T> {
             metrics: HttpServerMetrics::new(registry),
             router: Router::new()
                 .merge(Redoc::with_url(routes::API_DOCS, A::openapi()))
-                .route(STATUS_ENDPOINT, get(routes::status)),
+                .route(STATUS_ENDPOINT, get(routes::status::<T>)),
             allowed_headers: Arc::new(HashSet::new()),
         }
     }
 
     /// Specifies that the daemon should expose the aggregator interface (read blobs).
-    fn with_aggregator(mut self, allowed_headers: Vec<String>) -> Self {
+    fn with_aggregator(
+        mut self,
+        allowed_headers: Vec<String>,
+        indexer_client: Option<IndexerClient>,
+    ) -> Self {
         self.with_allowed_headers(allowed_headers);
         tracing::info!("Aggregator allowed headers: {:?}", self.allowed_headers);
         self.router = self
@@ -186,6 +257,16 @@ impl<T: WalrusReadClient + Send + Sync + 'static> ClientDaemon<T> {
                 get(routes::get_blob_by_object_id)
                     .with_state((self.client.clone(), self.allowed_headers.clone())),
             );
+        if let Some(indexer_client) = indexer_client {
+            tracing::info!(
+                url = %indexer_client.url(),
+                "serving attribute lookups via the indexer"
+            );
+            self.router = self.router.route(
+                BLOB_BY_ATTRIBUTE_ENDPOINT,
+                get(routes::list_blobs_by_attribute).with_state(Arc::new(indexer_client)),
+            );
+        }
         self
     }
 
@@ -218,6 +299,9 @@ impl<T: WalrusReadClient + Send + Sync + 'static> ClientDaemon<T> {
 
 impl<T: WalrusWriteClient + Send + Sync + 'static> ClientDaemon<T> {
     /// Constructs a new [`ClientDaemon`] with publisher functionality.
+    ///
+    /// The asynchronous store jobs are configured by `jobs_config`.
+    #[allow(clippy::too_many_arguments)]
     pub fn new_publisher(
         client: T,
         auth_config: Option<AuthConfig>,
@@ -226,12 +310,14 @@ impl<T: WalrusWriteClient + Send + Sync + 'static> ClientDaemon<T> {
         registry: &Registry,
         max_request_buffer_size: usize,
         max_concurrent_requests: usize,
-    ) -> Self {
+        jobs_config: JobsConfig,
+    ) -> anyhow::Result<Self> {
         Self::new::<PublisherApiDoc>(client, network_address, registry).with_publisher(
             auth_config,
             max_body_limit,
             max_request_buffer_size,
             max_concurrent_requests,
+            jobs_config,
         )
     }
 
@@ -242,14 +328,18 @@ impl<T: WalrusWriteClient + Send + Sync + 'static> ClientDaemon<T> {
         registry: &Registry,
         publisher_args: &PublisherArgs,
         aggregator_args: &AggregatorArgs,
-    ) -> Self {
+    ) -> anyhow::Result<Self> {
         Self::new::<DaemonApiDoc>(client, publisher_args.daemon_args.bind_address, registry)
-            .with_aggregator(aggregator_args.allowed_headers.clone())
+            .with_aggregator(
+                aggregator_args.allowed_headers.clone(),
+                aggregator_args.indexer_client()?,
+            )
             .with_publisher(
                 auth_config,
                 publisher_args.max_body_size_kib,
                 publisher_args.max_request_buffer_size,
                 publisher_args.max_concurrent_requests,
+                publisher_args.jobs_config(),
             )
     }
 
@@ -260,14 +350,37 @@ impl<T: WalrusWriteClient + Send + Sync + 'static> ClientDaemon<T> {
         max_body_limit: usize,
         max_request_buffer_size: usize,
         max_concurrent_requests: usize,
-    ) -> Self {
+        jobs_config: JobsConfig,
+    ) -> anyhow::Result<Self> {
         tracing::debug!(
             %max_body_limit,
             %max_request_buffer_size,
             %max_concurrent_requests,
+            ?jobs_config,
             "configuring the publisher endpoint",
         );
 
+        let quota_tracker = auth_config
+            .as_ref()
+            .and_then(|auth_config| auth_config.quota_config.clone())
+            .map(QuotaTracker::new)
+            .transpose()?
+            .map(Arc::new);
+        if let Some(quota_tracker) = quota_tracker.as_ref() {
+            quota_tracker.clone().start_persist_task();
+            self.with_quota_admin_endpoints(quota_tracker.clone());
+        }
+
+        // The store jobs are executed in the background, and therefore do not count towards the
+        // concurrency limit of the requests. We apply the same limit to them separately.
+        let job_manager = JobManager::new(
+            self.client.clone(),
+            jobs_config,
+            max_concurrent_requests,
+            quota_tracker.clone(),
+        )?;
+        let publisher_state = (self.client.clone(), job_manager.clone());
+
         let base_layers = ServiceBuilder::new()
             .layer(DefaultBodyLimit::max(max_body_limit))
             .layer(HandleErrorLayer::new(handle_publisher_error))
@@ -278,28 +391,70 @@ impl<T: WalrusWriteClient + Send + Sync + 'static> ClientDaemon<T> {
         if let Some(auth_config) = auth_config {
             // Create and run the cache to track the used JWT tokens.
             let replay_suppression_cache = auth_config.replay_suppression_config.build_and_run();
+            if let Some(api_keys) = auth_config.api_keys.as_ref() {
+                api_keys.clone().start_reload_task();
+            }
+            let auth_config = Arc::new(auth_config);
+
+            self.router = self.router.route(
+                JOB_GET_ENDPOINT,
+                get(routes::get_job::<T>)
+                    .route_layer(axum::middleware::from_fn_with_state(
+                        auth_config.clone(),
+                        job_auth_layer,
+                    ))
+                    .with_state(job_manager),
+            );
             self.router = self.router.route(
                 BLOB_PUT_ENDPOINT,
                 put(routes::put_blob)
                     .route_layer(
                         ServiceBuilder::new()
                             .layer(axum::middleware::from_fn_with_state(
-                                (Arc::new(auth_config), Arc::new(replay_suppression_cache)),
+                                (auth_config, Arc::new(replay_suppression_cache)),
                                 auth_layer,
                             ))
+                            .layer(axum::middleware::from_fn_with_state(
+                                (quota_tracker, max_body_limit),
+                                quota_layer,
+                            ))
                             .layer(base_layers),
                     )
-                    .options(routes::store_blob_options),
+                    .options(routes::store_blob_options)
+                    .with_state(publisher_state),
             );
         } else {
+            self.router = self.router.route(
+                JOB_GET_ENDPOINT,
+                get(routes::get_job::<T>).with_state(job_manager),
+            );
             self.router = self.router.route(
                 BLOB_PUT_ENDPOINT,
                 put(routes::put_blob)
                     .route_layer(base_layers)
-                    .options(routes::store_blob_options),
+                    .options(routes::store_blob_options)
+                    .with_state(publisher_state),
             );
         }
-        self
+        Ok(self)
+    }
+
+    /// Exposes the endpoints to query the usage of the subjects, if an admin token is configured.
+    fn with_quota_admin_endpoints(&mut self, quota_tracker: Arc<QuotaTracker>) {
+        if quota_tracker.admin_token().is_none() {
+            tracing::info!("no admin token configured, the usage endpoints are disabled");
+            return;
+        }
+        self.router = self
+            .router
+            .route(
+                USAGE_LIST_ENDPOINT,
+                get(quota::list_usage).with_state(quota_tracker.clone()),
+            )
+            .route(
+                USAGE_GET_ENDPOINT,
+                get(quota::get_usage).with_state(quota_tracker),
+            );
     }
 }
 
@@ -312,8 +467,8 @@ impl<T> ClientDaemon<T> {
 pub(crate) async fn auth_layer(
     State((auth_config, token_cache)): State<(Arc<AuthConfig>, Arc<CacheHandle<String>>)>,
     query: Query<PublisherQuery>,
-    TypedHeader(bearer_header): TypedHeader<Authorization<Bearer>>,
-    request: Request,
+    bearer_header: Result<TypedHeader<Authorization<Bearer>>, TypedHeaderRejection>,
+    mut request: Request,
     next: Next,
 ) -> Response {
     // Get a hint on the body size if possible.
@@ -322,7 +477,28 @@ pub(crate) async fn auth_layer(
     // Walrus.
     tracing::debug!(query = ?query.0, "authenticating a request to store a blob");
 
-    if let Err(resp) = verify_jwt_claim(
+    // API keys take precedence over JWTs if both are provided.
+    if let Some(api_key) = request.headers().get(API_KEY_HEADER) {
+        return match verify_api_key(&query.0, api_key, &auth_config, request.body().size_hint()) {
+            Ok((key_id, limits)) => {
+                // The limits are made available to check the exact size of the received blob.
+                request.extensions_mut().insert(key_id);
+                request.extensions_mut().insert(limits);
+                next.run(request).await
+            }
+            Err(error) => error.to_response(),
+        };
+    }
+
+    if auth_config.api_keys_only {
+        return PublisherAuthError::ApiKeyRequired.to_response();
+    }
+    let TypedHeader(bearer_header) = match bearer_header {
+        Ok(bearer_header) => bearer_header,
+        Err(rejection) => return rejection.into_response(),
+    };
+
+    match verify_jwt_claim(
         query,
         bearer_header,
         &auth_config,
@@ -331,9 +507,40 @@ pub(crate) async fn auth_layer(
     )
     .await
     {
-        resp
+        Ok(claim) => {
+            // Make the verified claim available to the subsequent layers.
+            request.extensions_mut().insert(claim);
+            next.run(request).await
+        }
+        Err(resp) => resp,
+    }
+}
+
+/// Authenticates the requests for the status of store jobs with the same credentials as the store
+/// requests.
+///
+/// As no blob is uploaded, the upload limits of the credentials are not checked, and JWTs are not
+/// recorded for replay suppression, such that the same token can be used to query a job repeatedly.
+pub(crate) async fn job_auth_layer(
+    State(auth_config): State<Arc<AuthConfig>>,
+    bearer_header: Result<TypedHeader<Authorization<Bearer>>, TypedHeaderRejection>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let authenticated = if let Some(api_key) = request.headers().get(API_KEY_HEADER) {
+        authenticate_api_key(api_key, &auth_config).map(|_| ())
+    } else if auth_config.api_keys_only {
+        Err(PublisherAuthError::ApiKeyRequired)
     } else {
-        next.run(request).await
+        match bearer_header {
+            Ok(TypedHeader(bearer_header)) => verify_jwt(&bearer_header, &auth_config).map(|_| ()),
+            Err(rejection) => return rejection.into_response(),
+        }
+    };
+
+    match authenticated {
+        Ok(()) => next.run(request).await,
+        Err(error) => error.to_response(),
     }
 }
 
@@ -0,0 +1,612 @@
+// Copyright (c) Walrus Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! API-key authentication for the publisher.
+//!
+//! As an alternative to JWTs, the publisher can authenticate store requests with API keys
+//! provided in the [`API_KEY_HEADER`]. Two kinds of keys are supported:
+//!
+//! - _Static_ keys, which are random strings identified by their SHA-256 hash;
+//! - _HMAC-signed_ keys, which are derived from a secret shared between the key issuer and the
+//!   publisher, and can carry an expiration time. The secret can be kept out of the keys file, in
+//!   a separate file or an environment variable.
+//!
+//! The keys are loaded from a YAML file, which is periodically reloaded, and each key can restrict
+//! the size, epochs, `send_object_to` address, and encoding type of the uploads.
+
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use anyhow::{bail, Context as _};
+use fastcrypto::encoding::{Encoding as _, Hex};
+use hmac::{Hmac, Mac as _};
+use rand::{rngs::ThreadRng, RngCore as _};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest as _, Sha256};
+use sui_types::base_types::SuiAddress;
+use tokio::time::interval;
+use walrus_core::{EncodingType, EpochCount, DEFAULT_ENCODING};
+
+use super::{auth::PublisherAuthError, routes::PublisherQuery};
+use crate::common::utils::load_from_yaml;
+
+/// The HTTP header in which the API key is provided.
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// The prefix of static API keys.
+const STATIC_KEY_PREFIX: &str = "wsk_";
+/// The prefix of HMAC-signed API keys.
+const HMAC_KEY_PREFIX: &str = "whk";
+/// The separator between the components of HMAC-signed API keys.
+const HMAC_KEY_SEPARATOR: char = '.';
+/// The length of the random part of static keys and of generated HMAC secrets, in bytes.
+const KEY_LENGTH: usize = 32;
+/// The interval at which the API keys file is reloaded.
+const RELOAD_INTERVAL: Duration = Duration::from_secs(30);
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// The content of the API keys file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeysFile {
+    /// The API keys accepted by the publisher.
+    pub keys: Vec<ApiKeyEntry>,
+}
+
+/// An API key, or family of HMAC-signed API keys, accepted by the publisher.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyEntry {
+    /// The identifier of the key, used for logging and usage accounting.
+    pub id: String,
+    /// The credential used to verify the key.
+    #[serde(flatten)]
+    pub credential: ApiKeyCredential,
+    /// The limits on the uploads authenticated with the key.
+    #[serde(default)]
+    pub limits: ApiKeyLimits,
+}
+
+/// The credential used to verify an API key.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum ApiKeyCredential {
+    /// A static key, identified by the hex-encoded SHA-256 hash of the key.
+    Static {
+        /// The hex-encoded SHA-256 hash of the key.
+        key_sha256: String,
+    },
+    /// HMAC-signed keys, verified with the hex-encoded secret.
+    ///
+    /// Exactly one of `secret`, `secret_file`, and `secret_env` must be set.
+    Hmac {
+        /// The hex-encoded HMAC secret.
+        ///
+        /// To avoid storing the secret in plaintext in the keys file, set `secret_file` or
+        /// `secret_env` instead.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        secret: Option<String>,
+        /// The file containing the hex-encoded HMAC secret.
+        ///
+        /// Relative paths are resolved against the directory of the keys file.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        secret_file: Option<PathBuf>,
+        /// The environment variable containing the hex-encoded HMAC secret.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        secret_env: Option<String>,
+    },
+}
+
+impl fmt::Debug for ApiKeyCredential {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Static { key_sha256 } => f
+                .debug_struct("Static")
+                .field("key_sha256", key_sha256)
+                .finish(),
+            Self::Hmac {
+                secret_file,
+                secret_env,
+                ..
+            } => f
+                .debug_struct("Hmac")
+                .field("secret_file", secret_file)
+                .field("secret_env", secret_env)
+                .finish_non_exhaustive(),
+        }
+    }
+}
+
+/// The limits on the uploads authenticated with an API key.
+///
+/// Unset limits are not enforced.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyLimits {
+    /// The maximum size of a blob, in bytes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_size: Option<u64>,
+    /// The maximum number of epochs for which a blob can be stored.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_epochs: Option<EpochCount>,
+    /// The address to which the created Blob objects must be sent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub send_object_to: Option<SuiAddress>,
+    /// The encoding types that can be used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding_types: Option<Vec<EncodingType>>,
+}
+
+impl ApiKeyLimits {
+    /// Checks that the upload described by the query and body size hint is within the limits.
+    ///
+    /// Only the lower bound of the size hint is checked here, to reject oversized uploads early;
+    /// the exact size of the received blob must be checked with [`Self::check_size`].
+    pub fn check_upload(
+        &self,
+        query: &PublisherQuery,
+        body_size_hint: http_body::SizeHint,
+    ) -> Result<(), PublisherAuthError> {
+        self.check_size(body_size_hint.lower())?;
+        if self
+            .max_epochs
+            .is_some_and(|max_epochs| query.epochs > max_epochs)
+        {
+            return Err(PublisherAuthError::InvalidEpochs);
+        }
+        if self.send_object_to.is_some() && self.send_object_to != query.send_object_to {
+            return Err(PublisherAuthError::InvalidSendObjectTo);
+        }
+        if let Some(encoding_types) = self.encoding_types.as_ref() {
+            let encoding_type = query.encoding_type.unwrap_or(DEFAULT_ENCODING);
+            if !encoding_types.contains(&encoding_type) {
+                return Err(PublisherAuthError::InvalidEncodingType);
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that a blob of `blob_size` bytes is within the size limit.
+    pub fn check_size(&self, blob_size: u64) -> Result<(), PublisherAuthError> {
+        if self.max_size.is_some_and(|max_size| blob_size > max_size) {
+            return Err(PublisherAuthError::InvalidSize);
+        }
+        Ok(())
+    }
+}
+
+/// The identifier of the API key that authenticated a request.
+///
+/// Added as an extension to the requests authenticated with an API key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiKeyId(pub String);
+
+/// An API key entry, with the credential decoded.
+#[derive(Clone)]
+struct ParsedApiKey {
+    id: String,
+    credential: ParsedCredential,
+    limits: ApiKeyLimits,
+}
+
+#[derive(Clone)]
+enum ParsedCredential {
+    Static([u8; KEY_LENGTH]),
+    Hmac(Vec<u8>),
+}
+
+impl ParsedApiKey {
+    /// Parses the entry, resolving the secret files relative to `base_dir`.
+    fn parse(entry: ApiKeyEntry, base_dir: &Path) -> anyhow::Result<Self> {
+        let credential = match &entry.credential {
+            ApiKeyCredential::Static { key_sha256 } => ParsedCredential::Static(
+                Hex::decode(key_sha256)
+                    .ok()
+                    .and_then(|hash| hash.try_into().ok())
+                    .with_context(|| format!("invalid key hash for API key '{}'", entry.id))?,
+            ),
+            ApiKeyCredential::Hmac {
+                secret,
+                secret_file,
+                secret_env,
+            } => {
+                let secret = match (secret, secret_file, secret_env) {
+                    (Some(secret), None, None) => secret.clone(),
+                    (None, Some(secret_file), None) => {
+                        read_secret_file(&base_dir.join(secret_file))?
+                    }
+                    (None, None, Some(secret_env)) => std::env::var(secret_env)
+                        .with_context(|| {
+                            format!("the environment variable '{secret_env}' is not set")
+                        })?
+                        .trim()
+                        .to_owned(),
+                    _ => bail!(
+                        "exactly one of 'secret', 'secretFile', and 'secretEnv' must be set for \
+                        API key '{}'",
+                        entry.id
+                    ),
+                };
+                ParsedCredential::Hmac(
+                    Hex::decode(&secret)
+                        .with_context(|| format!("invalid secret for API key '{}'", entry.id))?,
+                )
+            }
+        };
+        Ok(Self {
+            id: entry.id,
+            credential,
+            limits: entry.limits,
+        })
+    }
+}
+
+/// Reads a hex-encoded secret from a file, ignoring surrounding whitespace.
+pub fn read_secret_file(path: &Path) -> anyhow::Result<String> {
+    let secret = std::fs::read_to_string(path)
+        .with_context(|| format!("unable to read the secret from '{}'", path.display()))?;
+    Ok(secret.trim().to_owned())
+}
+
+/// The API keys accepted by the publisher, reloaded periodically from a file.
+pub struct ApiKeyStore {
+    path: PathBuf,
+    keys: RwLock<Vec<ParsedApiKey>>,
+}
+
+impl fmt::Debug for ApiKeyStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ApiKeyStore")
+            .field("path", &self.path)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ApiKeyStore {
+    /// Loads the API keys from the YAML file at the given path.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let store = Self {
+            path: path.to_owned(),
+            keys: RwLock::new(vec![]),
+        };
+        store.reload()?;
+        Ok(store)
+    }
+
+    /// Reloads the API keys from the file.
+    ///
+    /// If the file is invalid, the previously loaded keys are kept.
+    pub fn reload(&self) -> anyhow::Result<()> {
+        let file: ApiKeysFile = load_from_yaml(&self.path)?;
+        let base_dir = self.path.parent().unwrap_or(Path::new(""));
+        let keys = file
+            .keys
+            .into_iter()
+            .map(|entry| ParsedApiKey::parse(entry, base_dir))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        tracing::debug!(n_keys = keys.len(), "loaded API keys");
+        *self.keys.write().expect("mutex should not be poisoned") = keys;
+        Ok(())
+    }
+
+    /// Starts a task to periodically reload the API keys from the file.
+    pub fn start_reload_task(self: Arc<Self>) {
+        tokio::spawn(async move {
+            let mut interval = interval(RELOAD_INTERVAL);
+            loop {
+                interval.tick().await;
+                if let Err(error) = self.reload() {
+                    tracing::error!(?error, "failed to reload the API keys");
+                }
+            }
+        });
+    }
+
+    /// Authenticates the API key, and returns its identifier and limits.
+    pub fn authenticate(
+        &self,
+        key: &str,
+        now_unix_secs: i64,
+    ) -> Result<(ApiKeyId, ApiKeyLimits), PublisherAuthError> {
+        let keys = self.keys.read().expect("mutex should not be poisoned");
+
+        let key = if let Some(static_key) = key.strip_prefix(STATIC_KEY_PREFIX) {
+            let hash: [u8; KEY_LENGTH] = Sha256::digest(static_key.as_bytes()).into();
+            keys.iter().find(|entry| {
+                matches!(entry.credential, ParsedCredential::Static(expected) if expected == hash)
+            })
+        } else {
+            let HmacKeyParts {
+                id,
+                expires_at,
+                signature,
+            } = HmacKeyParts::parse(key).ok_or(PublisherAuthError::InvalidApiKey)?;
+            let entry = keys.iter().find(|entry| entry.id == id);
+            match entry.map(|entry| &entry.credential) {
+                Some(ParsedCredential::Hmac(secret)) => {
+                    hmac_for(secret, id, expires_at)
+                        .verify_slice(&signature)
+                        .map_err(|_| PublisherAuthError::InvalidApiKey)?;
+                    if expires_at != 0 && expires_at < now_unix_secs {
+                        return Err(PublisherAuthError::ExpiredApiKey);
+                    }
+                    entry
+                }
+                _ => None,
+            }
+        };
+
+        key.map(|key| (ApiKeyId(key.id.clone()), key.limits.clone()))
+            .ok_or(PublisherAuthError::InvalidApiKey)
+    }
+}
+
+/// The components of an HMAC-signed key, formatted as `whk.<id>.<expires at>.<signature>`.
+///
+/// The expiration time is in seconds since the Unix epoch, with `0` indicating no expiration.
+struct HmacKeyParts<'a> {
+    id: &'a str,
+    expires_at: i64,
+    signature: Vec<u8>,
+}
+
+impl<'a> HmacKeyParts<'a> {
+    fn parse(key: &'a str) -> Option<Self> {
+        let mut parts = key.split(HMAC_KEY_SEPARATOR);
+        if parts.next()? != HMAC_KEY_PREFIX {
+            return None;
+        }
+        let id = parts.next()?;
+        let expires_at = parts.next()?.parse().ok()?;
+        let signature = Hex::decode(parts.next()?).ok()?;
+        parts.next().is_none().then_some(Self {
+            id,
+            expires_at,
+            signature,
+        })
+    }
+}
+
+fn hmac_for(secret: &[u8], id: &str, expires_at: i64) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(secret).expect("HMAC accepts keys of any size");
+    mac.update(format!("{id}{HMAC_KEY_SEPARATOR}{expires_at}").as_bytes());
+    mac
+}
+
+/// Generates a new static API key, and returns the key and the corresponding entry.
+pub fn generate_static_key(id: String, limits: ApiKeyLimits) -> (String, ApiKeyEntry) {
+    let mut random = [0; KEY_LENGTH];
+    ThreadRng::default().fill_bytes(&mut random);
+    let secret_part = Hex::encode(random);
+    let key_sha256 = Hex::encode(Sha256::digest(secret_part.as_bytes()));
+    (
+        format!("{STATIC_KEY_PREFIX}{secret_part}"),
+        ApiKeyEntry {
+            id,
+            credential: ApiKeyCredential::Static { key_sha256 },
+            limits,
+        },
+    )
+}
+
+/// Generates a new HMAC-signed API key with the given hex-encoded secret.
+///
+/// If no secret is provided, a new random secret is generated. Returns the key and the
+/// corresponding entry, which contains the secret.
+pub fn generate_hmac_key(
+    id: String,
+    secret: Option<String>,
+    expires_at: Option<i64>,
+    limits: ApiKeyLimits,
+) -> anyhow::Result<(String, ApiKeyEntry)> {
+    if id.contains(HMAC_KEY_SEPARATOR) {
+        bail!("the ID of an HMAC-signed key must not contain '{HMAC_KEY_SEPARATOR}'");
+    }
+    let secret = secret.unwrap_or_else(|| {
+        let mut random = [0; KEY_LENGTH];
+        ThreadRng::default().fill_bytes(&mut random);
+        Hex::encode(random)
+    });
+    let secret_bytes = Hex::decode(&secret).context("the HMAC secret must be hex-encoded")?;
+    let expires_at = expires_at.unwrap_or(0);
+    let signature = hmac_for(&secret_bytes, &id, expires_at)
+        .finalize()
+        .into_bytes();
+    let key = [
+        HMAC_KEY_PREFIX,
+        &id,
+        &expires_at.to_string(),
+        &Hex::encode(signature),
+    ]
+    .join(&HMAC_KEY_SEPARATOR.to_string());
+    Ok((
+        key,
+        ApiKeyEntry {
+            id,
+            credential: ApiKeyCredential::Hmac {
+                secret: Some(secret),
+                secret_file: None,
+                secret_env: None,
+            },
+            limits,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use walrus_test_utils::Result as TestResult;
+
+    use super::*;
+
+    fn store_with(entries: Vec<ApiKeyEntry>) -> TestResult<(tempfile::TempDir, ApiKeyStore)> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("api_keys.yaml");
+        std::fs::write(
+            &path,
+            serde_yaml::to_string(&ApiKeysFile { keys: entries })?,
+        )?;
+        let store = ApiKeyStore::load(&path)?;
+        Ok((dir, store))
+    }
+
+    #[test]
+    fn static_keys_are_authenticated() -> TestResult {
+        let (key, entry) = generate_static_key("static".to_owned(), ApiKeyLimits::default());
+        let (_dir, store) = store_with(vec![entry])?;
+
+        let (id, _) = store.authenticate(&key, 0)?;
+        assert_eq!(id, ApiKeyId("static".to_owned()));
+        assert!(matches!(
+            store.authenticate(&format!("{key}0"), 0),
+            Err(PublisherAuthError::InvalidApiKey)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn hmac_keys_are_authenticated_until_expiration() -> TestResult {
+        let (key, entry) =
+            generate_hmac_key("hmac".to_owned(), None, Some(100), ApiKeyLimits::default())?;
+        let ApiKeyCredential::Hmac {
+            secret: Some(secret),
+            ..
+        } = entry.credential.clone()
+        else {
+            panic!("expected an HMAC credential");
+        };
+        let (_dir, store) = store_with(vec![entry])?;
+
+        let (id, _) = store.authenticate(&key, 99)?;
+        assert_eq!(id, ApiKeyId("hmac".to_owned()));
+        assert!(matches!(
+            store.authenticate(&key, 101),
+            Err(PublisherAuthError::ExpiredApiKey)
+        ));
+
+        // Tampering with the expiration invalidates the signature.
+        let (tampered_key, _) = generate_hmac_key(
+            "hmac".to_owned(),
+            Some(secret),
+            Some(200),
+            ApiKeyLimits::default(),
+        )?;
+        let forged = tampered_key.replace(".200.", ".300.");
+        assert!(matches!(
+            store.authenticate(&forged, 101),
+            Err(PublisherAuthError::InvalidApiKey)
+        ));
+        store.authenticate(&tampered_key, 101)?;
+        Ok(())
+    }
+
+    #[test]
+    fn hmac_secrets_are_read_from_files_and_env() -> TestResult {
+        let (key, entry) = generate_hmac_key("hmac".to_owned(), None, None, Default::default())?;
+        let ApiKeyCredential::Hmac {
+            secret: Some(secret),
+            ..
+        } = entry.credential.clone()
+        else {
+            panic!("expected an HMAC credential");
+        };
+        let with_credential = |secret_file: Option<&str>, secret_env: Option<&str>| ApiKeyEntry {
+            credential: ApiKeyCredential::Hmac {
+                secret: None,
+                secret_file: secret_file.map(PathBuf::from),
+                secret_env: secret_env.map(str::to_owned),
+            },
+            ..entry.clone()
+        };
+
+        // Relative secret files are resolved against the directory of the keys file.
+        let dir = tempfile::tempdir()?;
+        std::fs::write(dir.path().join("hmac.secret"), format!("{secret}\n"))?;
+        let path = dir.path().join("api_keys.yaml");
+        std::fs::write(
+            &path,
+            serde_yaml::to_string(&ApiKeysFile {
+                keys: vec![with_credential(Some("hmac.secret"), None)],
+            })?,
+        )?;
+        ApiKeyStore::load(&path)?.authenticate(&key, 0)?;
+
+        const SECRET_ENV: &str = "WALRUS_TEST_API_KEYS_HMAC_SECRET";
+        std::env::set_var(SECRET_ENV, &secret);
+        let (_dir, store) = store_with(vec![with_credential(None, Some(SECRET_ENV))])?;
+        store.authenticate(&key, 0)?;
+
+        // Exactly one source of the secret must be set.
+        assert!(store_with(vec![with_credential(None, None)]).is_err());
+        assert!(store_with(vec![with_credential(Some("hmac.secret"), Some(SECRET_ENV))]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn limits_are_checked() {
+        let limits = ApiKeyLimits {
+            max_size: Some(10),
+            max_epochs: Some(5),
+            send_object_to: None,
+            encoding_types: Some(vec![EncodingType::RS2]),
+        };
+        let query = |epochs, encoding_type| PublisherQuery {
+            encoding_type,
+            epochs,
+            deletable: false,
+            send_object_to: None,
+            async_store: false,
+            webhook_url: None,
+        };
+
+        assert!(limits
+            .check_upload(
+                &query(5, Some(EncodingType::RS2)),
+                http_body::SizeHint::with_exact(10)
+            )
+            .is_ok());
+        assert!(matches!(
+            limits.check_upload(
+                &query(5, Some(EncodingType::RS2)),
+                http_body::SizeHint::with_exact(11)
+            ),
+            Err(PublisherAuthError::InvalidSize)
+        ));
+        assert!(matches!(
+            limits.check_upload(
+                &query(6, Some(EncodingType::RS2)),
+                http_body::SizeHint::with_exact(10)
+            ),
+            Err(PublisherAuthError::InvalidEpochs)
+        ));
+        assert!(matches!(
+            limits.check_upload(
+                &query(5, Some(EncodingType::RedStuffRaptorQ)),
+                http_body::SizeHint::with_exact(10)
+            ),
+            Err(PublisherAuthError::InvalidEncodingType)
+        ));
+
+        // Bodies without a size hint pass the early check, but not the check of the exact size.
+        assert!(limits
+            .check_upload(
+                &query(5, Some(EncodingType::RS2)),
+                http_body::SizeHint::default()
+            )
+            .is_ok());
+        assert!(limits.check_size(10).is_ok());
+        assert!(matches!(
+            limits.check_size(11),
+            Err(PublisherAuthError::InvalidSize)
+        ));
+    }
+}
@@ -1,9 +1,13 @@
 // Copyright (c) Walrus Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use axum::{body::Body, extract::Query, http::Response};
+use axum::{
+    body::Body,
+    extract::Query,
+    http::{HeaderValue, Response},
+};
 use axum_extra::headers::{authorization::Bearer, Authorization};
-use chrono::DateTime;
+use chrono::{DateTime, Utc};
 use jsonwebtoken::{
     decode,
     errors::{Error as JwtError, ErrorKind as JwtErrorKind},
@@ -15,7 +19,12 @@ use sui_types::base_types::SuiAddress;
 use walrus_core::EpochCount;
 use walrus_proc_macros::RestApiError;
 
-use super::{cache::CacheHandle, routes::PublisherQuery};
+use super::{
+    api_keys::{ApiKeyId, ApiKeyLimits},
+    cache::CacheHandle,
+    quota::{ClaimQuotas, QuotaPeriod, QuotaResource},
+    routes::PublisherQuery,
+};
 use crate::{client::config::AuthConfig, common::api::RestApiError};
 
 pub const PUBLISHER_AUTH_DOMAIN: &str = "auth.publisher.walrus.space";
@@ -70,6 +79,18 @@ pub struct Claim {
     /// and the claim is rejected.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub size: Option<u64>,
+
+    /// The subject of the token, to which the usage is accounted if quotas are enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sub: Option<String>,
+
+    /// The quotas of the subject, overriding the ones configured on the publisher.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quota: Option<ClaimQuotas>,
+
+    /// Any other claims present in the token.
+    #[serde(flatten)]
+    pub other_claims: serde_json::Map<String, serde_json::Value>,
 }
 
 impl Claim {
@@ -116,6 +137,20 @@ impl Claim {
         Ok(claim)
     }
 
+    /// Returns the value of the claim with the given name as a string, if present.
+    ///
+    /// String claims are returned as is, while other claims are returned in their JSON encoding.
+    pub fn custom_claim_as_string(&self, name: &str) -> Option<String> {
+        if name == "sub" {
+            return self.sub.clone();
+        }
+        match self.other_claims.get(name)? {
+            serde_json::Value::String(value) => Some(value.clone()),
+            serde_json::Value::Null => None,
+            value => Some(value.to_string()),
+        }
+    }
+
     /// Checks that the query matches the claim.
     pub fn check_valid_upload(
         &self,
@@ -259,13 +294,26 @@ impl Claim {
     }
 }
 
-pub async fn verify_jwt_claim(
-    query: Query<PublisherQuery>,
-    bearer: Authorization<Bearer>,
+/// Decodes the JWT and verifies its signature and expiration, without checking it against an
+/// upload.
+///
+/// Used to authenticate the requests that do not store a blob, which therefore do not consume the
+/// token.
+pub fn verify_jwt(
+    bearer: &Authorization<Bearer>,
     auth_config: &AuthConfig,
-    token_cache: &CacheHandle<String>,
-    body_size_hint: http_body::SizeHint,
-) -> Result<(), Response<Body>> {
+) -> Result<Claim, PublisherAuthError> {
+    let claim = decode_claim(bearer, auth_config)?;
+    if claim.check_expiring_sec(auth_config) {
+        return Err(PublisherAuthError::InvalidExpiration);
+    }
+    Ok(claim)
+}
+
+fn decode_claim(
+    bearer: &Authorization<Bearer>,
+    auth_config: &AuthConfig,
+) -> Result<Claim, PublisherAuthError> {
     let mut validation = if auth_config.decoding_key.is_some() {
         auth_config
             .algorithm
@@ -286,7 +334,17 @@ pub async fn verify_jwt_claim(
         validation.set_required_spec_claims(&["exp", "iat"]);
     }
 
-    match Claim::from_token(bearer.token().trim(), decode_key, &validation) {
+    Claim::from_token(bearer.token().trim(), decode_key, &validation)
+}
+
+pub async fn verify_jwt_claim(
+    query: Query<PublisherQuery>,
+    bearer: Authorization<Bearer>,
+    auth_config: &AuthConfig,
+    token_cache: &CacheHandle<String>,
+    body_size_hint: http_body::SizeHint,
+) -> Result<Claim, Response<Body>> {
+    match decode_claim(&bearer, auth_config) {
         Ok(claim) => {
             // To avoid race conditions between store requests, we insert the token into the cache
             // now, and later remove it if the JWT verification fails.
@@ -317,13 +375,52 @@ pub async fn verify_jwt_claim(
                     });
                 Err(error.to_response())
             } else {
-                Ok(())
+                Ok(claim)
             }
         }
         Err(code) => Err(code.to_response()),
     }
 }
 
+/// Verifies the API key provided with a store request, and checks the upload against its limits.
+///
+/// Returns the identifier and the limits of the API key on success.
+pub fn verify_api_key(
+    query: &PublisherQuery,
+    api_key: &HeaderValue,
+    auth_config: &AuthConfig,
+    body_size_hint: http_body::SizeHint,
+) -> Result<(ApiKeyId, ApiKeyLimits), PublisherAuthError> {
+    let (key_id, limits) = authenticate_api_key(api_key, auth_config)?;
+
+    if let Err(error) = limits.check_upload(query, body_size_hint) {
+        tracing::debug!(
+            key_id = key_id.0,
+            ?error,
+            "upload exceeds the limits of the API key"
+        );
+        return Err(error);
+    }
+    Ok((key_id, limits))
+}
+
+/// Authenticates the API key, without checking it against an upload.
+///
+/// Returns the identifier and the limits of the API key on success.
+pub fn authenticate_api_key(
+    api_key: &HeaderValue,
+    auth_config: &AuthConfig,
+) -> Result<(ApiKeyId, ApiKeyLimits), PublisherAuthError> {
+    let Some(api_keys) = auth_config.api_keys.as_ref() else {
+        tracing::debug!("received an API key, but API keys are not enabled");
+        return Err(PublisherAuthError::InvalidApiKey);
+    };
+    let api_key = api_key
+        .to_str()
+        .map_err(|_| PublisherAuthError::InvalidApiKey)?;
+    api_keys.authenticate(api_key.trim(), Utc::now().timestamp())
+}
+
 /// Type representing the possible errors that can occur during the authentication process.
 #[derive(Debug, thiserror::Error, RestApiError)]
 #[rest_api_error(domain = PUBLISHER_AUTH_DOMAIN)]
@@ -378,6 +475,41 @@ pub enum PublisherAuthError {
     #[rest_api_error(reason = "INVALID_TIMESTAMP", status = ApiStatusCode::FailedPrecondition)]
     InvalidTimestamp,
 
+    /// The claim identifying the subject of the token, required for quota accounting, is missing.
+    #[error("the token does not identify a subject")]
+    #[rest_api_error(reason = "MISSING_SUBJECT", status = ApiStatusCode::FailedPrecondition)]
+    MissingSubject,
+
+    /// The upload would exceed one of the quotas of the subject of the token.
+    #[error("the {period} quota for the {resource} is exhausted")]
+    #[rest_api_error(reason = "QUOTA_EXCEEDED", status = ApiStatusCode::ResourceExhausted)]
+    QuotaExceeded {
+        /// The period of the exhausted quota.
+        period: QuotaPeriod,
+        /// The resource of the exhausted quota.
+        resource: QuotaResource,
+    },
+
+    /// The API key is invalid or unknown.
+    #[error("the API key is invalid")]
+    #[rest_api_error(reason = "INVALID_API_KEY", status = ApiStatusCode::Unauthenticated)]
+    InvalidApiKey,
+
+    /// The API key has expired.
+    #[error("the API key has expired")]
+    #[rest_api_error(reason = "EXPIRED_API_KEY", status = ApiStatusCode::Unauthenticated)]
+    ExpiredApiKey,
+
+    /// The publisher only accepts API keys, but none was provided.
+    #[error("the publisher requires an API key")]
+    #[rest_api_error(reason = "API_KEY_REQUIRED", status = ApiStatusCode::Unauthenticated)]
+    ApiKeyRequired,
+
+    /// The encoding type in the query is not allowed by the API key.
+    #[error("the encoding type in the query is not allowed")]
+    #[rest_api_error(reason = "INVALID_ENCODING_TYPE", status = ApiStatusCode::FailedPrecondition)]
+    InvalidEncodingType,
+
     /// Other errors that are not covered by the other variants.
     #[error("an internal error occurred")]
     #[rest_api_error(delegate)]
@@ -402,7 +534,7 @@ mod tests {
     use super::*;
     use crate::client::{
         config::AuthConfig,
-        daemon::{auth_layer, cache::CacheConfig},
+        daemon::{auth_layer, cache::CacheConfig, job_auth_layer},
     };
 
     // Fixtures and helpers for tests.
@@ -542,6 +674,98 @@ mod tests {
         execute_requests(&router, requests).await;
     }
 
+    #[tokio::test]
+    async fn api_keys_only_requires_an_api_key() {
+        let auth_config = AuthConfig {
+            api_keys_only: true,
+            ..Default::default()
+        };
+        let token_cache = CacheConfig::default().build_and_run();
+        let router = Router::new().route(
+            "/v1/blobs",
+            get(|| async {}).route_layer(axum::middleware::from_fn_with_state(
+                (Arc::new(auth_config), Arc::new(token_cache)),
+                auth_layer,
+            )),
+        );
+
+        let requests = vec![
+            (
+                RequestHeadersAndData::new("/v1/blobs", None, None),
+                StatusCode::UNAUTHORIZED,
+            ),
+            (
+                RequestHeadersAndData::new(
+                    "/v1/blobs",
+                    correct_auth_header("token".to_owned()),
+                    None,
+                ),
+                StatusCode::UNAUTHORIZED,
+            ),
+        ];
+
+        execute_requests(&router, requests).await;
+    }
+
+    #[tokio::test]
+    async fn job_auth_layer_does_not_consume_tokens() {
+        let secret = Alphanumeric.sample_string(&mut rand::thread_rng(), 16);
+        let auth_config = auth_config_for_tests(Some(&secret), None, 0, true);
+        let claim = Claim {
+            jti: "test".to_string(),
+            iat: None,
+            exp: FAR_EXP,
+            epochs: Some(5),
+            ..Default::default()
+        };
+        let token = encode(
+            &Header::default(),
+            &claim,
+            &EncodingKey::from_secret(secret.as_bytes()),
+        )
+        .unwrap();
+        let other_token = encode(
+            &Header::default(),
+            &claim,
+            &EncodingKey::from_secret(b"another secret"),
+        )
+        .unwrap();
+
+        let router = Router::new().route(
+            "/v1/jobs/test",
+            get(|| async {}).route_layer(axum::middleware::from_fn_with_state(
+                Arc::new(auth_config),
+                job_auth_layer,
+            )),
+        );
+
+        let requests = vec![
+            (
+                RequestHeadersAndData::new("/v1/jobs/test", None, None),
+                StatusCode::BAD_REQUEST,
+            ),
+            (
+                RequestHeadersAndData::new("/v1/jobs/test", correct_auth_header(other_token), None),
+                StatusCode::UNAUTHORIZED,
+            ),
+            // The same token can be used repeatedly, and is not checked against the query.
+            (
+                RequestHeadersAndData::new(
+                    "/v1/jobs/test",
+                    correct_auth_header(token.clone()),
+                    None,
+                ),
+                StatusCode::OK,
+            ),
+            (
+                RequestHeadersAndData::new("/v1/jobs/test", correct_auth_header(token), None),
+                StatusCode::OK,
+            ),
+        ];
+
+        execute_requests(&router, requests).await;
+    }
+
     #[tokio::test]
     async fn verify_upload() {
         let (router, token, _) = setup_router_and_token(
@@ -0,0 +1,863 @@
+// Copyright (c) Walrus Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Asynchronous store jobs for the publisher.
+//!
+//! Instead of keeping the HTTP connection open for the whole duration of a store operation, a
+//! client can request the publisher to store the blob in the background. The publisher then
+//! immediately returns a job ID, which can be used to query the progress of the store operation.
+//! Optionally, the result of the job is sent to a webhook URL when the job terminates.
+//!
+//! Jobs are persisted to a directory on disk, together with the data of the blobs that are still
+//! to be stored, such that unfinished jobs are resumed when the publisher restarts. Terminated jobs
+//! are removed after a configurable time.
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use anyhow::Context as _;
+use axum::body::Bytes;
+use chrono::{DateTime, Utc};
+use rand::{rngs::ThreadRng, RngCore as _};
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+use sui_types::base_types::SuiAddress;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use walrus_core::{EncodingType, EpochCount};
+use walrus_sui::{
+    client::{BlobPersistence, PostStoreAction},
+    SuiAddressSchema,
+};
+use walrus_utils::backoff::ExponentialBackoff;
+
+use super::{
+    quota::{QuotaReservation, QuotaTracker, ReservedUsage},
+    WalrusWriteClient,
+};
+use crate::client::{BlobStoreResult, StoreProgress, StoreStage, StoreWhen};
+
+/// The extension of the files containing the job descriptions.
+const JOB_FILE_EXTENSION: &str = "json";
+/// The extension of the files containing the data of the blobs to be stored.
+const BLOB_FILE_EXTENSION: &str = "blob";
+/// The minimum backoff between webhook delivery attempts.
+const WEBHOOK_MIN_BACKOFF: Duration = Duration::from_secs(1);
+/// The maximum backoff between webhook delivery attempts.
+const WEBHOOK_MAX_BACKOFF: Duration = Duration::from_secs(60);
+/// The maximum number of retries for the delivery of a webhook.
+const WEBHOOK_MAX_RETRIES: u32 = 5;
+/// The timeout for a single webhook delivery attempt.
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+/// The interval between the removals of expired jobs.
+const JOB_GC_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// The configuration of the asynchronous store jobs.
+#[derive(Debug, Clone)]
+pub struct JobsConfig {
+    /// The directory where the jobs are persisted.
+    pub jobs_dir: PathBuf,
+    /// The time for which terminated jobs are kept before being removed.
+    pub job_ttl: Duration,
+    /// The hosts to which webhooks can be delivered.
+    ///
+    /// If empty, webhooks are disabled.
+    pub webhook_allowed_hosts: Vec<String>,
+    /// The maximum number of jobs that can be pending, i.e., queued or running, at the same time.
+    ///
+    /// New jobs are rejected while this maximum is reached.
+    pub max_pending_jobs: usize,
+}
+
+/// The error returned when a store job cannot be created.
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum SubmitJobError {
+    #[error("the publisher has too many pending store jobs")]
+    TooManyJobs,
+    #[error(transparent)]
+    Internal(#[from] anyhow::Error),
+}
+
+/// The error returned when a webhook URL is not accepted by the publisher.
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum WebhookUrlError {
+    #[error("webhooks are not enabled on this publisher")]
+    Disabled,
+    #[error("the webhook URL is invalid: {0}")]
+    Invalid(String),
+    #[error("the webhook URL must use the http or https scheme")]
+    UnsupportedScheme,
+    #[error("the host of the webhook URL is not allowed")]
+    HostNotAllowed,
+}
+
+/// The identifier of a store job.
+pub type JobId = Uuid;
+
+/// The parameters of a store request that is executed as a job.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StoreJobRequest {
+    /// The encoding type to use for the blob.
+    pub encoding_type: Option<EncodingType>,
+    /// The number of epochs, ahead of the current one, for which to store the blob.
+    pub epochs: EpochCount,
+    /// Whether the blob is deletable.
+    pub deletable: bool,
+    /// The Sui address to which the resulting Blob object is sent, if any.
+    #[schema(value_type = Option<SuiAddressSchema>)]
+    pub send_object_to: Option<SuiAddress>,
+    /// The URL to which the final [`BlobStoreResult`] is posted, if any.
+    pub webhook_url: Option<String>,
+}
+
+/// The status of a store job.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum StoreJobStatus {
+    /// The job is running, and is currently at the given stage.
+    InProgress {
+        /// The stage the store operation has reached.
+        stage: StoreStage,
+    },
+    /// The job completed successfully.
+    Completed {
+        /// The result of the store operation.
+        result: BlobStoreResult,
+    },
+    /// The job failed.
+    Failed {
+        /// The description of the error that caused the failure.
+        error: String,
+    },
+}
+
+impl StoreJobStatus {
+    /// Returns `true` if the job has terminated, either successfully or not.
+    pub fn is_terminal(&self) -> bool {
+        !matches!(self, Self::InProgress { .. })
+    }
+}
+
+/// A store job, as persisted by the publisher and returned by the jobs endpoint.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StoreJob {
+    /// The ID of the job.
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub job_id: JobId,
+    /// The time at which the job was created.
+    #[schema(value_type = String)]
+    pub created_at: DateTime<Utc>,
+    /// The time at which the job was last updated.
+    #[schema(value_type = String)]
+    pub updated_at: DateTime<Utc>,
+    /// The size of the blob to be stored, in bytes.
+    pub blob_size: u64,
+    /// The parameters of the store request.
+    pub request: StoreJobRequest,
+    /// The current status of the job.
+    pub status: StoreJobStatus,
+    /// The quota usage reserved for the job, if quotas are enforced.
+    ///
+    /// Only persisted on disk, and not exposed through the API.
+    #[serde(skip)]
+    pub(crate) quota_reservation: Option<ReservedUsage>,
+}
+
+impl StoreJob {
+    fn new(
+        request: StoreJobRequest,
+        blob_size: u64,
+        quota_reservation: Option<ReservedUsage>,
+    ) -> Self {
+        let now = Utc::now();
+        Self {
+            job_id: Uuid::now_v7(),
+            created_at: now,
+            updated_at: now,
+            blob_size,
+            request,
+            status: StoreJobStatus::InProgress {
+                stage: StoreStage::Queued,
+            },
+            quota_reservation,
+        }
+    }
+
+    fn set_status(&mut self, status: StoreJobStatus) {
+        self.status = status;
+        self.updated_at = Utc::now();
+    }
+
+    /// Returns `true` if the job terminated more than `ttl` before `now`.
+    fn is_expired(&self, now: DateTime<Utc>, ttl: Duration) -> bool {
+        self.status.is_terminal() && (now - self.updated_at).to_std().is_ok_and(|age| age > ttl)
+    }
+}
+
+/// Checks that the webhook URL uses http or https, and that its host is in `allowed_hosts`.
+///
+/// If `allowed_hosts` is empty, no webhook URL is allowed.
+fn check_webhook_url(webhook_url: &str, allowed_hosts: &[String]) -> Result<(), WebhookUrlError> {
+    if allowed_hosts.is_empty() {
+        return Err(WebhookUrlError::Disabled);
+    }
+    let url = reqwest::Url::parse(webhook_url)
+        .map_err(|error| WebhookUrlError::Invalid(error.to_string()))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(WebhookUrlError::UnsupportedScheme);
+    }
+    let host = url
+        .host_str()
+        .ok_or_else(|| WebhookUrlError::Invalid("the URL has no host".to_owned()))?;
+    if !allowed_hosts
+        .iter()
+        .any(|allowed| allowed.eq_ignore_ascii_case(host))
+    {
+        return Err(WebhookUrlError::HostNotAllowed);
+    }
+    Ok(())
+}
+
+/// A store job as persisted on disk, including the fields that are not exposed through the API.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PersistedJob {
+    #[serde(flatten)]
+    job: StoreJob,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    quota_reservation: Option<ReservedUsage>,
+}
+
+/// The on-disk storage of the store jobs.
+#[derive(Debug, Clone)]
+pub(crate) struct JobStore {
+    directory: PathBuf,
+}
+
+impl JobStore {
+    /// Opens the job store in the given directory, creating the directory if necessary.
+    pub(crate) fn open(directory: &Path) -> anyhow::Result<Self> {
+        fs::create_dir_all(directory).with_context(|| {
+            format!(
+                "unable to create the jobs directory '{}'",
+                directory.display()
+            )
+        })?;
+        Ok(Self {
+            directory: directory.to_owned(),
+        })
+    }
+
+    /// Writes the job to disk, replacing any previous version of it.
+    pub(crate) fn save_job(&self, job: &StoreJob) -> anyhow::Result<()> {
+        let path = self.job_path(&job.job_id);
+        let tmp_path = path.with_extension("tmp");
+        let persisted = PersistedJob {
+            job: job.clone(),
+            quota_reservation: job.quota_reservation.clone(),
+        };
+        fs::write(&tmp_path, serde_json::to_vec(&persisted)?)?;
+        // Rename to make the update atomic, such that a crash cannot leave a corrupted job file.
+        fs::rename(&tmp_path, &path)?;
+        Ok(())
+    }
+
+    /// Writes the data of the blob of the given job to disk.
+    pub(crate) async fn save_blob(&self, job_id: &JobId, blob: &[u8]) -> anyhow::Result<()> {
+        tokio::fs::write(self.blob_path(job_id), blob).await?;
+        Ok(())
+    }
+
+    /// Reads the data of the blob of the given job from disk.
+    pub(crate) fn read_blob(&self, job_id: &JobId) -> anyhow::Result<Vec<u8>> {
+        Ok(fs::read(self.blob_path(job_id))?)
+    }
+
+    /// Returns `true` if the data of the blob of the given job is stored on disk.
+    pub(crate) fn has_blob(&self, job_id: &JobId) -> bool {
+        self.blob_path(job_id).is_file()
+    }
+
+    /// Removes the data of the blob of the given job from disk, if present.
+    pub(crate) fn remove_blob(&self, job_id: &JobId) -> anyhow::Result<()> {
+        match fs::remove_file(self.blob_path(job_id)) {
+            Err(error) if error.kind() != std::io::ErrorKind::NotFound => Err(error.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Removes the job and the data of its blob from disk.
+    pub(crate) fn remove_job(&self, job_id: &JobId) -> anyhow::Result<()> {
+        self.remove_blob(job_id)?;
+        match fs::remove_file(self.job_path(job_id)) {
+            Err(error) if error.kind() != std::io::ErrorKind::NotFound => Err(error.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Loads all jobs stored on disk.
+    ///
+    /// Files that cannot be parsed are skipped with a warning.
+    pub(crate) fn load_jobs(&self) -> anyhow::Result<Vec<StoreJob>> {
+        let mut jobs = Vec::new();
+        for entry in fs::read_dir(&self.directory)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(JOB_FILE_EXTENSION) {
+                continue;
+            }
+            match fs::read(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|bytes| Ok(serde_json::from_slice::<PersistedJob>(&bytes)?))
+            {
+                Ok(PersistedJob {
+                    mut job,
+                    quota_reservation,
+                }) => {
+                    job.quota_reservation = quota_reservation;
+                    jobs.push(job)
+                }
+                Err(error) => {
+                    tracing::warn!(?error, path = %path.display(), "skipping invalid job file")
+                }
+            }
+        }
+        Ok(jobs)
+    }
+
+    fn job_path(&self, job_id: &JobId) -> PathBuf {
+        self.directory
+            .join(job_id.to_string())
+            .with_extension(JOB_FILE_EXTENSION)
+    }
+
+    fn blob_path(&self, job_id: &JobId) -> PathBuf {
+        self.directory
+            .join(job_id.to_string())
+            .with_extension(BLOB_FILE_EXTENSION)
+    }
+}
+
+/// Runs the store jobs in the background, and keeps track of their status.
+#[derive(Debug)]
+pub(crate) struct JobManager<T> {
+    client: Arc<T>,
+    store: JobStore,
+    jobs: Mutex<HashMap<JobId, StoreJob>>,
+    /// Limits the number of jobs that are executed concurrently.
+    concurrency_limit: Semaphore,
+    /// Limits the number of jobs that are pending, i.e., queued or running.
+    pending_limit: Arc<Semaphore>,
+    http_client: reqwest::Client,
+    /// The time for which terminated jobs are kept.
+    job_ttl: Duration,
+    /// The hosts to which webhooks can be delivered.
+    webhook_allowed_hosts: Vec<String>,
+    /// Resumes the quota reservations of the jobs after a restart, if quotas are enforced.
+    quota_tracker: Option<Arc<QuotaTracker>>,
+}
+
+impl<T: WalrusWriteClient + Send + Sync + 'static> JobManager<T> {
+    /// Creates a new job manager, and resumes any unfinished job found in the jobs directory.
+    ///
+    /// Also starts the task removing the expired jobs.
+    pub(crate) fn new(
+        client: Arc<T>,
+        config: JobsConfig,
+        max_concurrent_jobs: usize,
+        quota_tracker: Option<Arc<QuotaTracker>>,
+    ) -> anyhow::Result<Arc<Self>> {
+        let store = JobStore::open(&config.jobs_dir)?;
+        let jobs = store.load_jobs()?;
+        tracing::info!(
+            n_jobs = jobs.len(),
+            jobs_dir = %config.jobs_dir.display(),
+            "loaded store jobs from disk"
+        );
+
+        let manager = Arc::new(Self {
+            client,
+            store,
+            jobs: Mutex::new(HashMap::new()),
+            concurrency_limit: Semaphore::new(max_concurrent_jobs),
+            pending_limit: Arc::new(Semaphore::new(config.max_pending_jobs)),
+            http_client: reqwest::Client::builder()
+                .timeout(WEBHOOK_TIMEOUT)
+                // Following redirects would allow bypassing the allowed hosts.
+                .redirect(reqwest::redirect::Policy::none())
+                .build()
+                .context("unable to build the webhook HTTP client")?,
+            job_ttl: config.job_ttl,
+            webhook_allowed_hosts: config.webhook_allowed_hosts,
+            quota_tracker,
+        });
+
+        for mut job in jobs {
+            let job_id = job.job_id;
+            if job.status.is_terminal() {
+                manager.insert_job(job);
+                continue;
+            }
+            let quota_reservation = manager
+                .quota_tracker
+                .as_ref()
+                .zip(job.quota_reservation.clone())
+                .map(|(quota_tracker, reserved)| quota_tracker.resume_reservation(reserved));
+
+            if manager.store.has_blob(&job_id) {
+                tracing::info!(%job_id, "resuming unfinished store job");
+                manager.insert_job(job);
+                // Resumed jobs are never rejected, but they count towards the pending jobs if
+                // there is room for them.
+                let pending_permit = manager.pending_limit.clone().try_acquire_owned().ok();
+                // The blob may have been registered before the restart, so we check the wallet for
+                // a usable registration instead of registering it again.
+                manager.clone().spawn_job(
+                    job_id,
+                    StoreWhen::NotStored,
+                    quota_reservation,
+                    pending_permit,
+                );
+            } else {
+                tracing::warn!(%job_id, "the data of an unfinished job is missing");
+                let error = "the blob data was lost after a restart of the publisher".to_owned();
+                job.set_status(StoreJobStatus::Failed { error });
+                if let Err(error) = manager.store.save_job(&job) {
+                    tracing::error!(?error, %job_id, "failed to persist the store job");
+                }
+                manager.insert_job(job);
+                // The job failed, so its quota reservation is released.
+                drop(quota_reservation);
+            }
+        }
+
+        manager.clone().start_gc_task();
+        Ok(manager)
+    }
+
+    /// Checks that the webhook URL is allowed by the configuration of the publisher.
+    pub(crate) fn check_webhook_url(&self, webhook_url: &str) -> Result<(), WebhookUrlError> {
+        check_webhook_url(webhook_url, &self.webhook_allowed_hosts)
+    }
+
+    /// Creates a new store job for the given blob, and starts it in the background.
+    ///
+    /// Returns the ID of the newly created job. The `quota_reservation`, if any, is committed
+    /// together with the WAL spent if the job succeeds, and released otherwise. Fails with
+    /// [`SubmitJobError::TooManyJobs`] if the maximum number of pending jobs is reached.
+    pub(crate) async fn submit(
+        self: &Arc<Self>,
+        blob: Bytes,
+        request: StoreJobRequest,
+        quota_reservation: Option<QuotaReservation>,
+    ) -> Result<JobId, SubmitJobError> {
+        let pending_permit = self
+            .pending_limit
+            .clone()
+            .try_acquire_owned()
+            .map_err(|_| SubmitJobError::TooManyJobs)?;
+        let job = StoreJob::new(
+            request,
+            u64::try_from(blob.len()).expect("the blob size fits into a u64"),
+            quota_reservation
+                .as_ref()
+                .map(|reservation| reservation.reserved().clone()),
+        );
+        let job_id = job.job_id;
+
+        self.store.save_blob(&job_id, &blob).await?;
+        // The blob is read back from disk once the job runs, such that queued jobs do not keep it
+        // in memory.
+        drop(blob);
+        let saved_job = job.clone();
+        self.run_blocking(move |store| store.save_job(&saved_job))
+            .await?;
+        self.insert_job(job);
+        tracing::debug!(%job_id, "submitted new store job");
+
+        self.clone().spawn_job(
+            job_id,
+            StoreWhen::NotStoredIgnoreResources,
+            quota_reservation,
+            Some(pending_permit),
+        );
+        Ok(job_id)
+    }
+
+    /// Returns the current state of the job with the given ID, if it exists.
+    pub(crate) fn get(&self, job_id: &JobId) -> Option<StoreJob> {
+        self.jobs
+            .lock()
+            .expect("mutex should not be poisoned")
+            .get(job_id)
+            .cloned()
+    }
+
+    fn insert_job(&self, job: StoreJob) {
+        self.jobs
+            .lock()
+            .expect("mutex should not be poisoned")
+            .insert(job.job_id, job);
+    }
+
+    /// Updates the status of the job both in memory and on disk, and returns the updated job.
+    async fn update_status(&self, job_id: &JobId, status: StoreJobStatus) -> Option<StoreJob> {
+        let job = {
+            let mut jobs = self.jobs.lock().expect("mutex should not be poisoned");
+            let job = jobs.get_mut(job_id)?;
+            job.set_status(status);
+            job.clone()
+        };
+        let saved_job = job.clone();
+        if let Err(error) = self
+            .run_blocking(move |store| store.save_job(&saved_job))
+            .await
+        {
+            tracing::error!(?error, %job_id, "failed to persist the store job");
+        }
+        Some(job)
+    }
+
+    /// Runs the blocking `operation` on the job store on a dedicated thread.
+    async fn run_blocking<R: Send + 'static>(
+        &self,
+        operation: impl FnOnce(&JobStore) -> anyhow::Result<R> + Send + 'static,
+    ) -> anyhow::Result<R> {
+        let store = self.store.clone();
+        tokio::task::spawn_blocking(move || operation(&store))
+            .await
+            .context("the job store task failed")?
+    }
+
+    /// Removes the terminated jobs that were last updated more than the TTL ago.
+    fn remove_expired_jobs(&self) {
+        let now = Utc::now();
+        let expired: Vec<_> = {
+            let mut jobs = self.jobs.lock().expect("mutex should not be poisoned");
+            let expired = jobs
+                .values()
+                .filter(|job| job.is_expired(now, self.job_ttl))
+                .map(|job| job.job_id)
+                .collect();
+            for job_id in &expired {
+                jobs.remove(job_id);
+            }
+            expired
+        };
+
+        for job_id in expired {
+            tracing::debug!(%job_id, "removing expired store job");
+            if let Err(error) = self.store.remove_job(&job_id) {
+                tracing::warn!(?error, %job_id, "failed to remove an expired store job");
+            }
+        }
+    }
+
+    /// Starts a background task that periodically removes the expired jobs.
+    fn start_gc_task(self: Arc<Self>) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(JOB_GC_INTERVAL);
+            loop {
+                interval.tick().await;
+                let manager = self.clone();
+                if let Err(error) =
+                    tokio::task::spawn_blocking(move || manager.remove_expired_jobs()).await
+                {
+                    tracing::error!(?error, "the job removal task failed");
+                }
+            }
+        });
+    }
+
+    /// Spawns the job, which settles the `quota_reservation` and releases the `pending_permit`,
+    /// if any, once it terminates.
+    fn spawn_job(
+        self: Arc<Self>,
+        job_id: JobId,
+        store_when: StoreWhen,
+        quota_reservation: Option<QuotaReservation>,
+        pending_permit: Option<OwnedSemaphorePermit>,
+    ) {
+        tokio::spawn(async move {
+            self.run_job(job_id, store_when, quota_reservation, pending_permit)
+                .await
+        });
+    }
+
+    #[tracing::instrument(skip_all, fields(%job_id))]
+    async fn run_job(
+        self: Arc<Self>,
+        job_id: JobId,
+        store_when: StoreWhen,
+        quota_reservation: Option<QuotaReservation>,
+        pending_permit: Option<OwnedSemaphorePermit>,
+    ) {
+        let Some(request) = self.get(&job_id).map(|job| job.request) else {
+            tracing::error!("trying to run a job that does not exist");
+            return;
+        };
+
+        let _permit = self
+            .concurrency_limit
+            .acquire()
+            .await
+            .expect("the semaphore is never closed");
+        let status = self.store_blob(job_id, request, store_when).await;
+        tracing::info!(?status, "store job terminated");
+
+        let Some(job) = self.update_status(&job_id, status).await else {
+            return;
+        };
+        if let Some(quota_reservation) = quota_reservation {
+            // As for synchronous uploads, the reservation is released if the job failed.
+            if let StoreJobStatus::Completed { result } = &job.status {
+                let cost = match result {
+                    BlobStoreResult::NewlyCreated { cost, .. } => *cost,
+                    _ => 0,
+                };
+                quota_reservation.commit(cost);
+            }
+        }
+        if let Err(error) = self
+            .run_blocking(move |store| store.remove_blob(&job_id))
+            .await
+        {
+            tracing::warn!(?error, "failed to remove the blob data of a terminated job");
+        }
+        // The job no longer holds any resources, so it makes room for new jobs while the webhook
+        // is notified.
+        drop(pending_permit);
+        if let Some(webhook_url) = job.request.webhook_url.as_deref() {
+            self.notify_webhook(webhook_url, &job).await;
+        }
+    }
+
+    /// Reads the blob of the job from disk and stores it, and returns the resulting status.
+    async fn store_blob(
+        self: &Arc<Self>,
+        job_id: JobId,
+        request: StoreJobRequest,
+        store_when: StoreWhen,
+    ) -> StoreJobStatus {
+        let blob = match self
+            .run_blocking(move |store| store.read_blob(&job_id))
+            .await
+        {
+            Ok(blob) => blob,
+            Err(error) => {
+                tracing::error!(?error, "unable to read the blob data of the store job");
+                return StoreJobStatus::Failed {
+                    error: "the blob data of the job could not be read".to_owned(),
+                };
+            }
+        };
+
+        let (progress, mut progress_rx) = StoreProgress::new();
+        let progress_updater = {
+            let this = self.clone();
+            tokio::spawn(async move {
+                while progress_rx.changed().await.is_ok() {
+                    let stage = *progress_rx.borrow_and_update();
+                    tracing::debug!(?stage, "store job reached a new stage");
+                    this.update_status(&job_id, StoreJobStatus::InProgress { stage })
+                        .await;
+                }
+            })
+        };
+
+        let post_store = request
+            .send_object_to
+            .map(PostStoreAction::TransferTo)
+            .unwrap_or_else(|| self.client.default_post_store_action());
+        let result = self
+            .client
+            .write_blob_with_progress(
+                &blob,
+                request.encoding_type,
+                request.epochs,
+                store_when,
+                BlobPersistence::from_deletable(request.deletable),
+                post_store,
+                progress,
+            )
+            .await;
+        // The progress sender has been dropped at this point, so the updater terminates.
+        let _ = progress_updater.await;
+
+        match result {
+            Ok(BlobStoreResult::MarkedInvalid { .. }) => StoreJobStatus::Failed {
+                error: "the blob was marked invalid, which is likely a system error, please \
+                    report it"
+                    .to_owned(),
+            },
+            Ok(result) => StoreJobStatus::Completed { result },
+            Err(error) => {
+                tracing::error!(?error, "store job failed");
+                StoreJobStatus::Failed {
+                    error: error.to_string(),
+                }
+            }
+        }
+    }
+
+    /// Posts the terminated job to the webhook, retrying with exponential backoff on failures.
+    async fn notify_webhook(&self, webhook_url: &str, job: &StoreJob) {
+        let mut backoff = ExponentialBackoff::new_with_seed(
+            WEBHOOK_MIN_BACKOFF,
+            WEBHOOK_MAX_BACKOFF,
+            Some(WEBHOOK_MAX_RETRIES),
+            ThreadRng::default().next_u64(),
+        );
+
+        loop {
+            let result = self
+                .http_client
+                .post(webhook_url)
+                .json(job)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+            match result {
+                Ok(_) => {
+                    tracing::debug!(%webhook_url, "delivered the job result to the webhook");
+                    return;
+                }
+                Err(error) => {
+                    let Some(delay) = backoff.next() else {
+                        tracing::warn!(
+                            ?error,
+                            %webhook_url,
+                            "giving up delivering the job result to the webhook"
+                        );
+                        return;
+                    };
+                    tracing::debug!(?error, ?delay, "webhook delivery failed, retrying");
+                    tokio::time::sleep(delay).await;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use walrus_test_utils::Result as TestResult;
+
+    use super::*;
+
+    fn request() -> StoreJobRequest {
+        StoreJobRequest {
+            encoding_type: None,
+            epochs: 3,
+            deletable: true,
+            send_object_to: None,
+            webhook_url: Some("http://localhost:8080/hook".to_owned()),
+        }
+    }
+
+    #[tokio::test]
+    async fn job_store_roundtrip() -> TestResult {
+        let dir = tempfile::tempdir()?;
+        let store = JobStore::open(dir.path())?;
+
+        let reserved = ReservedUsage {
+            subject: "alice".to_owned(),
+            usage: Default::default(),
+            reserved_on: Utc::now().date_naive(),
+        };
+        let mut job = StoreJob::new(request(), 42, Some(reserved.clone()));
+        store.save_job(&job)?;
+        store.save_blob(&job.job_id, &[1, 2, 3]).await?;
+        assert!(store.has_blob(&job.job_id));
+        assert_eq!(store.read_blob(&job.job_id)?, vec![1, 2, 3]);
+
+        job.set_status(StoreJobStatus::Failed {
+            error: "test".to_owned(),
+        });
+        store.save_job(&job)?;
+        store.remove_blob(&job.job_id)?;
+        // Removing twice is not an error.
+        store.remove_blob(&job.job_id)?;
+
+        let loaded = store.load_jobs()?;
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].job_id, job.job_id);
+        assert_eq!(loaded[0].request, request());
+        assert_eq!(loaded[0].quota_reservation, Some(reserved));
+        assert!(loaded[0].status.is_terminal());
+        assert!(!store.has_blob(&job.job_id));
+        assert!(store.read_blob(&job.job_id).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_job_files_are_skipped() -> TestResult {
+        let dir = tempfile::tempdir()?;
+        let store = JobStore::open(dir.path())?;
+        fs::write(dir.path().join("garbage.json"), b"not a job")?;
+        store.save_job(&StoreJob::new(request(), 1, None))?;
+
+        assert_eq!(store.load_jobs()?.len(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn removed_jobs_are_not_loaded() -> TestResult {
+        let dir = tempfile::tempdir()?;
+        let store = JobStore::open(dir.path())?;
+        let job = StoreJob::new(request(), 3, None);
+        store.save_job(&job)?;
+        store.save_blob(&job.job_id, &[1, 2, 3]).await?;
+
+        store.remove_job(&job.job_id)?;
+        assert!(store.load_jobs()?.is_empty());
+        assert!(store.read_blob(&job.job_id).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn only_terminated_jobs_expire() {
+        let ttl = Duration::from_secs(60);
+        let mut job = StoreJob::new(request(), 1, None);
+        let later = job.updated_at + chrono::Duration::seconds(120);
+        assert!(!job.is_expired(later, ttl));
+
+        job.set_status(StoreJobStatus::Failed {
+            error: "test".to_owned(),
+        });
+        assert!(!job.is_expired(job.updated_at, ttl));
+        assert!(job.is_expired(job.updated_at + chrono::Duration::seconds(120), ttl));
+    }
+
+    #[test]
+    fn webhook_urls_are_checked() {
+        let allowed = vec!["hooks.example.com".to_owned()];
+        assert!(check_webhook_url("https://hooks.example.com/done", &allowed).is_ok());
+        assert!(check_webhook_url("http://HOOKS.example.com:8080/done", &allowed).is_ok());
+        assert!(matches!(
+            check_webhook_url("https://hooks.example.com/done", &[]),
+            Err(WebhookUrlError::Disabled)
+        ));
+        assert!(matches!(
+            check_webhook_url("file:///etc/passwd", &allowed),
+            Err(WebhookUrlError::UnsupportedScheme)
+        ));
+        assert!(matches!(
+            check_webhook_url("http://169.254.169.254/latest", &allowed),
+            Err(WebhookUrlError::HostNotAllowed)
+        ));
+        assert!(matches!(
+            check_webhook_url("not a url", &allowed),
+            Err(WebhookUrlError::Invalid(_))
+        ));
+    }
+}
@@ -11,24 +11,40 @@ use walrus_sui::{
     SuiAddressSchema,
 };
 
-use super::routes;
+use super::{
+    jobs::{StoreJob, StoreJobRequest, StoreJobStatus},
+    routes::{self, StoreJobCreated},
+};
 use crate::{
-    client::{resource::RegisterBlobOp, responses::EventOrObjectId, BlobStoreResult},
+    backup::{BlobPage, IndexedBlob},
+    client::{resource::RegisterBlobOp, responses::EventOrObjectId, BlobStoreResult, StoreStage},
     common::api::Binary,
 };
 
 #[derive(OpenApi)]
 #[openapi(
     info(title = "Walrus Aggregator"),
-    paths(routes::get_blob, routes::get_blob_by_object_id),
-    components(schemas(BlobId, Status,))
+    paths(
+        routes::get_blob,
+        routes::get_blob_by_object_id,
+        routes::list_blobs_by_attribute
+    ),
+    components(schemas(
+        BlobId,
+        BlobPage,
+        EncodingType,
+        IndexedBlob,
+        ObjectIdSchema,
+        Status,
+        SuiAddressSchema,
+    ))
 )]
 pub(super) struct AggregatorApiDoc;
 
 #[derive(OpenApi)]
 #[openapi(
     info(title = "Walrus Publisher"),
-    paths(routes::put_blob),
+    paths(routes::put_blob, routes::get_job),
     components(schemas(
         Blob,
         BlobId,
@@ -41,6 +57,11 @@ pub(super) struct AggregatorApiDoc;
         RegisterBlobOp,
         Status,
         StorageResource,
+        StoreJob,
+        StoreJobCreated,
+        StoreJobRequest,
+        StoreJobStatus,
+        StoreStage,
         SuiAddressSchema,
         Binary,
     ))
@@ -50,19 +71,32 @@ pub(super) struct PublisherApiDoc;
 #[derive(OpenApi)]
 #[openapi(
     info(title = "Walrus Daemon"),
-    paths(routes::get_blob, routes::put_blob, routes::get_blob_by_object_id),
+    paths(
+        routes::get_blob,
+        routes::put_blob,
+        routes::get_blob_by_object_id,
+        routes::list_blobs_by_attribute,
+        routes::get_job
+    ),
     components(schemas(
         Blob,
         BlobId,
+        BlobPage,
         BlobStoreResult,
         EncodingType,
         EpochSchema,
         EventIdSchema,
         EventOrObjectId,
+        IndexedBlob,
         ObjectIdSchema,
         RegisterBlobOp,
         Status,
         StorageResource,
+        StoreJob,
+        StoreJobCreated,
+        StoreJobRequest,
+        StoreJobStatus,
+        StoreStage,
         SuiAddressSchema,
         Binary,
     ))
@@ -105,9 +139,13 @@ mod tests {
         let mut spec = T::openapi();
         spec.info.version = "<VERSION>".to_string();
 
-        std::fs::write(html_path, Redoc::new(spec.clone()).to_html())?;
-
+        // Update both files before failing, such that a single run brings them in sync.
+        let html_result = walrus_test_utils::overwrite_file_and_fail_if_not_equal(
+            html_path,
+            Redoc::new(spec.clone()).to_html(),
+        );
         walrus_test_utils::overwrite_file_and_fail_if_not_equal(spec_path, spec.to_yaml()?)?;
+        html_result?;
 
         Ok(())
     }
@@ -0,0 +1,970 @@
+// Copyright (c) Walrus Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Per-subject quota accounting for JWT-authenticated publishers.
+//!
+//! The publisher tracks the cumulative number of bytes uploaded, the WAL spent, and the number of
+//! uploads for each subject of the JWT tokens it receives. The size of each upload is reserved
+//! against the daily, monthly, and absolute quotas before the upload starts, and released if the
+//! upload fails. The quotas are taken from the [`QuotaConfig`], and can be overridden per token
+//! through the `quota` claim.
+//!
+//! The usage is kept in memory and periodically persisted to a local file in the background.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+        Mutex,
+    },
+    time::Duration,
+};
+
+use anyhow::Context as _;
+use axum::{
+    body::Body,
+    extract::{Path as UrlPath, Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::{
+    headers::{authorization::Bearer, Authorization},
+    TypedHeader,
+};
+use chrono::{Datelike as _, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use subtle::ConstantTimeEq as _;
+
+use super::{
+    api_keys::ApiKeyId,
+    auth::{Claim, PublisherAuthError},
+};
+use crate::{client::responses::BlobStoreResult, common::api::RestApiError as _};
+
+/// The endpoint to query the usage of all subjects.
+pub const USAGE_LIST_ENDPOINT: &str = "/v1/admin/usage";
+/// The endpoint to query the usage of a single subject.
+///
+/// The subject is prefixed with the type of the principal, e.g., `jwt:alice` for the subject of a
+/// JWT, or `api-key:internal-service` for an API key.
+pub const USAGE_GET_ENDPOINT: &str = "/v1/admin/usage/{subject}";
+
+/// The maximum size of a store response that is inspected to account for the WAL spent.
+const MAX_STORE_RESPONSE_SIZE: usize = 1 << 20;
+/// The interval at which the usage is persisted, if it changed.
+const PERSIST_INTERVAL: Duration = Duration::from_secs(5);
+/// The prefix of the subjects identifying JWT subjects.
+const JWT_SUBJECT_PREFIX: &str = "jwt:";
+/// The prefix of the subjects identifying API keys.
+const API_KEY_SUBJECT_PREFIX: &str = "api-key:";
+
+/// The configuration of the per-subject quotas of the publisher.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotaConfig {
+    /// The claim of the JWT that identifies the subject to which the usage is accounted.
+    #[serde(default = "defaults::subject_claim")]
+    pub subject_claim: String,
+    /// The path of the file in which the usage of the subjects is persisted.
+    #[serde(deserialize_with = "walrus_utils::config::resolve_home_dir")]
+    pub usage_path: PathBuf,
+    /// The quotas that apply to a single day (UTC).
+    #[serde(default)]
+    pub daily: QuotaLimits,
+    /// The quotas that apply to a calendar month (UTC).
+    #[serde(default)]
+    pub monthly: QuotaLimits,
+    /// The quotas that apply to the whole lifetime of the subject.
+    #[serde(default)]
+    pub absolute: QuotaLimits,
+    /// The bearer token required to query the usage through the admin endpoints.
+    ///
+    /// If unset, the admin endpoints are disabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub admin_token: Option<String>,
+}
+
+impl fmt::Debug for QuotaConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QuotaConfig")
+            .field("subject_claim", &self.subject_claim)
+            .field("usage_path", &self.usage_path)
+            .field("daily", &self.daily)
+            .field("monthly", &self.monthly)
+            .field("absolute", &self.absolute)
+            .finish()
+    }
+}
+
+/// Limits on the usage of a subject over a period of time.
+///
+/// Unset limits are not enforced.
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotaLimits {
+    /// The maximum number of bytes that can be uploaded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_bytes: Option<u64>,
+    /// The maximum amount of WAL, in FROST, that can be spent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_frost: Option<u64>,
+    /// The maximum number of uploads.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_uploads: Option<u64>,
+}
+
+/// The quotas that can be specified in the `quota` claim of a JWT.
+///
+/// Each quota present in the claim replaces the corresponding quota in the [`QuotaConfig`].
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaimQuotas {
+    /// The quotas that apply to a single day (UTC).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub daily: Option<QuotaLimits>,
+    /// The quotas that apply to a calendar month (UTC).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monthly: Option<QuotaLimits>,
+    /// The quotas that apply to the whole lifetime of the subject.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub absolute: Option<QuotaLimits>,
+}
+
+/// The period over which a quota applies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuotaPeriod {
+    /// A single day (UTC).
+    Daily,
+    /// A calendar month (UTC).
+    Monthly,
+    /// The whole lifetime of the subject.
+    Absolute,
+}
+
+impl fmt::Display for QuotaPeriod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Daily => write!(f, "daily"),
+            Self::Monthly => write!(f, "monthly"),
+            Self::Absolute => write!(f, "absolute"),
+        }
+    }
+}
+
+/// The resource limited by a quota.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuotaResource {
+    /// The number of bytes uploaded.
+    Bytes,
+    /// The amount of WAL spent.
+    Wal,
+    /// The number of uploads.
+    Uploads,
+}
+
+impl fmt::Display for QuotaResource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bytes => write!(f, "uploaded bytes"),
+            Self::Wal => write!(f, "WAL spent"),
+            Self::Uploads => write!(f, "number of uploads"),
+        }
+    }
+}
+
+/// Counters of the resources used by a subject.
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageCounters {
+    /// The number of bytes uploaded.
+    pub bytes: u64,
+    /// The amount of WAL spent, in FROST.
+    pub frost: u64,
+    /// The number of uploads.
+    pub uploads: u64,
+}
+
+impl UsageCounters {
+    fn add(&mut self, other: &UsageCounters) {
+        self.bytes = self.bytes.saturating_add(other.bytes);
+        self.frost = self.frost.saturating_add(other.frost);
+        self.uploads = self.uploads.saturating_add(other.uploads);
+    }
+
+    fn subtract(&mut self, other: &UsageCounters) {
+        self.bytes = self.bytes.saturating_sub(other.bytes);
+        self.frost = self.frost.saturating_sub(other.frost);
+        self.uploads = self.uploads.saturating_sub(other.uploads);
+    }
+
+    /// Checks that an upload of `upload_size` bytes does not exceed the `limits`.
+    ///
+    /// As the cost of an upload is only known after the upload, the WAL quota is only checked
+    /// against the amount already spent.
+    fn check(&self, limits: &QuotaLimits, upload_size: u64) -> Result<(), QuotaResource> {
+        if limits
+            .max_bytes
+            .is_some_and(|max_bytes| self.bytes.saturating_add(upload_size) > max_bytes)
+        {
+            return Err(QuotaResource::Bytes);
+        }
+        if limits
+            .max_frost
+            .is_some_and(|max_frost| self.frost >= max_frost)
+        {
+            return Err(QuotaResource::Wal);
+        }
+        if limits
+            .max_uploads
+            .is_some_and(|max_uploads| self.uploads >= max_uploads)
+        {
+            return Err(QuotaResource::Uploads);
+        }
+        Ok(())
+    }
+}
+
+/// The usage of a single subject.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SubjectUsage {
+    /// The day (UTC) of the last update of the usage.
+    pub last_updated: NaiveDate,
+    /// The usage on the day of the last update.
+    pub daily: UsageCounters,
+    /// The usage in the month of the last update.
+    pub monthly: UsageCounters,
+    /// The total usage.
+    pub total: UsageCounters,
+}
+
+impl SubjectUsage {
+    fn new(today: NaiveDate) -> Self {
+        Self {
+            last_updated: today,
+            daily: UsageCounters::default(),
+            monthly: UsageCounters::default(),
+            total: UsageCounters::default(),
+        }
+    }
+
+    /// Returns the usage as of `today`, resetting the counters of the periods that have ended.
+    fn as_of(&self, today: NaiveDate) -> Self {
+        let mut usage = self.clone();
+        if usage.last_updated != today {
+            usage.daily = UsageCounters::default();
+        }
+        if !same_month(usage.last_updated, today) {
+            usage.monthly = UsageCounters::default();
+        }
+        usage.last_updated = today;
+        usage
+    }
+
+    /// Adds the `counters` to the usage of all periods, as of `today`.
+    fn add(&mut self, counters: &UsageCounters, today: NaiveDate) {
+        *self = self.as_of(today);
+        self.daily.add(counters);
+        self.monthly.add(counters);
+        self.total.add(counters);
+    }
+
+    /// Subtracts the `counters`, which were added on `added_on`, from the usage as of `today`.
+    ///
+    /// The counters of periods that have ended since `added_on` are left untouched.
+    fn subtract(&mut self, counters: &UsageCounters, added_on: NaiveDate, today: NaiveDate) {
+        *self = self.as_of(today);
+        if added_on == today {
+            self.daily.subtract(counters);
+        }
+        if same_month(added_on, today) {
+            self.monthly.subtract(counters);
+        }
+        self.total.subtract(counters);
+    }
+}
+
+fn same_month(first: NaiveDate, second: NaiveDate) -> bool {
+    (first.year(), first.month()) == (second.year(), second.month())
+}
+
+/// The usage reserved for an upload, as persisted with the asynchronous store jobs.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReservedUsage {
+    /// The subject for which the usage is reserved.
+    pub subject: String,
+    /// The reserved usage.
+    pub usage: UsageCounters,
+    /// The day (UTC) on which the usage was reserved.
+    pub reserved_on: NaiveDate,
+}
+
+/// The usage reserved for an upload that is in progress.
+///
+/// The reservation is released when dropped, unless it is [committed][Self::commit].
+#[derive(Debug)]
+pub struct QuotaReservation {
+    tracker: Arc<QuotaTracker>,
+    reserved: ReservedUsage,
+    committed: bool,
+}
+
+impl QuotaReservation {
+    /// Returns the subject for which the usage is reserved.
+    pub fn subject(&self) -> &str {
+        &self.reserved.subject
+    }
+
+    /// Returns the reserved usage.
+    pub fn reserved(&self) -> &ReservedUsage {
+        &self.reserved
+    }
+
+    /// Keeps the reserved usage, and additionally accounts `frost` FROST of WAL spent.
+    pub fn commit(mut self, frost: u64) {
+        self.committed = true;
+        self.tracker.charge(&self.reserved.subject, frost);
+    }
+}
+
+impl Drop for QuotaReservation {
+    fn drop(&mut self) {
+        if !self.committed {
+            let ReservedUsage {
+                subject,
+                usage,
+                reserved_on,
+            } = &self.reserved;
+            self.tracker
+                .release_at(subject, usage, *reserved_on, today());
+        }
+    }
+}
+
+/// Tracks the usage of the subjects, and enforces the quotas.
+#[derive(Debug)]
+pub struct QuotaTracker {
+    config: QuotaConfig,
+    usage: Mutex<HashMap<String, SubjectUsage>>,
+    /// Whether the usage changed since it was last persisted.
+    dirty: AtomicBool,
+}
+
+impl QuotaTracker {
+    /// Creates a new tracker, loading the usage persisted at the path specified in the `config`.
+    pub fn new(config: QuotaConfig) -> anyhow::Result<Self> {
+        let usage = load_usage(&config.usage_path)?;
+        tracing::info!(
+            n_subjects = usage.len(),
+            usage_path = %config.usage_path.display(),
+            "loaded the usage of the publisher subjects"
+        );
+        Ok(Self {
+            config,
+            usage: Mutex::new(usage),
+            dirty: AtomicBool::new(false),
+        })
+    }
+
+    /// Starts a background task that periodically persists the usage, if it changed.
+    pub fn start_persist_task(self: Arc<Self>) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(PERSIST_INTERVAL);
+            loop {
+                interval.tick().await;
+                let tracker = self.clone();
+                if let Err(error) = tokio::task::spawn_blocking(move || tracker.persist()).await {
+                    tracing::error!(?error, "the usage persistence task failed");
+                }
+            }
+        });
+    }
+
+    /// Persists the usage to the file specified in the config, if it changed since the last call.
+    pub fn persist(&self) {
+        if !self.dirty.swap(false, Ordering::AcqRel) {
+            return;
+        }
+        let usage = self
+            .usage
+            .lock()
+            .expect("mutex should not be poisoned")
+            .clone();
+        if let Err(error) = save_usage(&self.config.usage_path, &usage) {
+            // Retry with the next call.
+            self.dirty.store(true, Ordering::Release);
+            tracing::error!(
+                ?error,
+                "failed to persist the usage of the publisher subjects"
+            );
+        }
+    }
+
+    /// Returns the bearer token that is required to access the admin endpoints, if any.
+    pub fn admin_token(&self) -> Option<&str> {
+        self.config.admin_token.as_deref()
+    }
+
+    /// Returns the subject to which the usage of the claim is accounted.
+    pub fn subject(&self, claim: &Claim) -> Option<String> {
+        claim
+            .custom_claim_as_string(&self.config.subject_claim)
+            .map(|subject| jwt_subject(&subject))
+    }
+
+    /// Checks that an upload of `upload_size` bytes by `subject` is within the quotas, and
+    /// reserves the usage of the upload.
+    ///
+    /// Checking and reserving is atomic, such that concurrent uploads cannot together exceed the
+    /// quotas.
+    pub fn reserve(
+        self: &Arc<Self>,
+        subject: &str,
+        claim_quotas: Option<&ClaimQuotas>,
+        upload_size: u64,
+    ) -> Result<QuotaReservation, PublisherAuthError> {
+        self.reserve_at(subject, claim_quotas, upload_size, today())
+    }
+
+    fn reserve_at(
+        self: &Arc<Self>,
+        subject: &str,
+        claim_quotas: Option<&ClaimQuotas>,
+        upload_size: u64,
+        today: NaiveDate,
+    ) -> Result<QuotaReservation, PublisherAuthError> {
+        let mut all_usage = self.usage.lock().expect("mutex should not be poisoned");
+        let usage = all_usage
+            .get(subject)
+            .map(|usage| usage.as_of(today))
+            .unwrap_or_else(|| SubjectUsage::new(today));
+
+        let claim_quotas = claim_quotas.copied().unwrap_or_default();
+        for (period, counters, limits) in [
+            (
+                QuotaPeriod::Daily,
+                &usage.daily,
+                claim_quotas.daily.unwrap_or(self.config.daily),
+            ),
+            (
+                QuotaPeriod::Monthly,
+                &usage.monthly,
+                claim_quotas.monthly.unwrap_or(self.config.monthly),
+            ),
+            (
+                QuotaPeriod::Absolute,
+                &usage.total,
+                claim_quotas.absolute.unwrap_or(self.config.absolute),
+            ),
+        ] {
+            if let Err(resource) = counters.check(&limits, upload_size) {
+                tracing::debug!(%subject, %period, %resource, "upload exceeds the quota");
+                return Err(PublisherAuthError::QuotaExceeded { period, resource });
+            }
+        }
+
+        let reserved = UsageCounters {
+            bytes: upload_size,
+            frost: 0,
+            uploads: 1,
+        };
+        all_usage
+            .entry(subject.to_owned())
+            .or_insert(usage)
+            .add(&reserved, today);
+        self.dirty.store(true, Ordering::Release);
+
+        Ok(QuotaReservation {
+            tracker: self.clone(),
+            reserved: ReservedUsage {
+                subject: subject.to_owned(),
+                usage: reserved,
+                reserved_on: today,
+            },
+            committed: false,
+        })
+    }
+
+    /// Resumes a reservation made before a restart of the publisher.
+    ///
+    /// The reserved usage is already accounted to the subject, and is only released if the
+    /// returned reservation is dropped without being committed.
+    pub fn resume_reservation(self: &Arc<Self>, reserved: ReservedUsage) -> QuotaReservation {
+        QuotaReservation {
+            tracker: self.clone(),
+            reserved,
+            committed: false,
+        }
+    }
+
+    /// Accounts `frost` FROST of WAL spent by `subject`.
+    ///
+    /// Used to charge the cost of uploads, once it is known.
+    pub fn charge(&self, subject: &str, frost: u64) {
+        self.charge_at(subject, frost, today())
+    }
+
+    fn charge_at(&self, subject: &str, frost: u64, today: NaiveDate) {
+        if frost == 0 {
+            return;
+        }
+        self.usage
+            .lock()
+            .expect("mutex should not be poisoned")
+            .entry(subject.to_owned())
+            .or_insert_with(|| SubjectUsage::new(today))
+            .add(
+                &UsageCounters {
+                    frost,
+                    ..Default::default()
+                },
+                today,
+            );
+        self.dirty.store(true, Ordering::Release);
+    }
+
+    fn release_at(
+        &self,
+        subject: &str,
+        reserved: &UsageCounters,
+        reserved_on: NaiveDate,
+        today: NaiveDate,
+    ) {
+        if let Some(usage) = self
+            .usage
+            .lock()
+            .expect("mutex should not be poisoned")
+            .get_mut(subject)
+        {
+            usage.subtract(reserved, reserved_on, today);
+            self.dirty.store(true, Ordering::Release);
+        }
+    }
+
+    /// Returns the usage of `subject` as of today, if the subject is known.
+    pub fn usage(&self, subject: &str) -> Option<SubjectUsage> {
+        self.usage
+            .lock()
+            .expect("mutex should not be poisoned")
+            .get(subject)
+            .map(|usage| usage.as_of(today()))
+    }
+
+    /// Returns the usage of all known subjects as of today.
+    pub fn all_usage(&self) -> HashMap<String, SubjectUsage> {
+        let today = today();
+        self.usage
+            .lock()
+            .expect("mutex should not be poisoned")
+            .iter()
+            .map(|(subject, usage)| (subject.clone(), usage.as_of(today)))
+            .collect()
+    }
+}
+
+fn today() -> NaiveDate {
+    Utc::now().date_naive()
+}
+
+/// Returns the subject to which the usage of a JWT subject is accounted.
+///
+/// The subjects are prefixed with the type of the principal, such that a JWT subject and an API
+/// key with the same identifier do not share their usage.
+fn jwt_subject(subject: &str) -> String {
+    format!("{JWT_SUBJECT_PREFIX}{subject}")
+}
+
+/// Returns the subject to which the usage of an API key is accounted.
+fn api_key_subject(key_id: &str) -> String {
+    format!("{API_KEY_SUBJECT_PREFIX}{key_id}")
+}
+
+fn load_usage(path: &Path) -> anyhow::Result<HashMap<String, SubjectUsage>> {
+    match fs::read(path) {
+        Ok(bytes) => serde_json::from_slice(&bytes)
+            .with_context(|| format!("invalid usage file '{}'", path.display())),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(HashMap::new()),
+        Err(error) => Err(error)
+            .with_context(|| format!("unable to read the usage file '{}'", path.display())),
+    }
+}
+
+fn save_usage(path: &Path, usage: &HashMap<String, SubjectUsage>) -> anyhow::Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, serde_json::to_vec(usage)?)?;
+    // Rename to make the update atomic, such that a crash cannot leave a corrupted usage file.
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+/// Middleware that enforces the quotas of the subject of the JWT, and records its usage.
+///
+/// Must be placed after the [`auth_layer`][super::auth_layer], which provides the verified
+/// [`Claim`] or [`ApiKeyId`] as a request extension. If no quota tracker is provided, requests are
+/// passed through.
+pub(crate) async fn quota_layer(
+    State((quota_tracker, max_body_limit)): State<(Option<Arc<QuotaTracker>>, usize)>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(quota_tracker) = quota_tracker else {
+        return next.run(request).await;
+    };
+    // Requests authenticated with an API key are accounted to the key, without claim quotas.
+    let (subject, claim_quotas) = if let Some(claim) = request.extensions().get::<Claim>() {
+        let Some(subject) = quota_tracker.subject(claim) else {
+            return PublisherAuthError::MissingSubject.to_response();
+        };
+        (subject, claim.quota)
+    } else if let Some(ApiKeyId(key_id)) = request.extensions().get::<ApiKeyId>() {
+        (api_key_subject(key_id), None)
+    } else {
+        tracing::error!("the quota layer requires an authenticated request");
+        return PublisherAuthError::Internal(anyhow::anyhow!("unauthenticated request"))
+            .to_response();
+    };
+
+    // The body is buffered to account for its exact size, as the size hint may be missing.
+    let (parts, body) = request.into_parts();
+    let blob = match axum::body::to_bytes(body, max_body_limit).await {
+        Ok(blob) => blob,
+        Err(_) => return StatusCode::PAYLOAD_TOO_LARGE.into_response(),
+    };
+    let upload_size = u64::try_from(blob.len()).expect("the blob size fits into a u64");
+
+    // The reservation is released if the upload fails, including if the request is cancelled.
+    let reservation = match quota_tracker.reserve(&subject, claim_quotas.as_ref(), upload_size) {
+        Ok(reservation) => reservation,
+        Err(error) => return error.to_response(),
+    };
+
+    // Make the reservation available to the store handler, such that asynchronous store jobs can
+    // commit or release it once they terminate.
+    let pending_reservation = PendingReservation(Arc::new(Mutex::new(Some(reservation))));
+    let mut request = Request::from_parts(parts, Body::from(blob));
+    request.extensions_mut().insert(pending_reservation.clone());
+    let response = next.run(request).await;
+    let Some(reservation) = pending_reservation.take() else {
+        // The reservation was taken over by an asynchronous store job.
+        return response;
+    };
+    if !response.status().is_success() {
+        return response;
+    }
+
+    // Inspect the response to account for the WAL spent by the upload.
+    let (parts, body) = response.into_parts();
+    let body = match axum::body::to_bytes(body, MAX_STORE_RESPONSE_SIZE).await {
+        Ok(body) => body,
+        Err(error) => {
+            tracing::warn!(?error, "unable to read the store response");
+            reservation.commit(0);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let cost = match serde_json::from_slice::<BlobStoreResult>(&body) {
+        Ok(BlobStoreResult::NewlyCreated { cost, .. }) => cost,
+        _ => 0,
+    };
+    reservation.commit(cost);
+
+    Response::from_parts(parts, Body::from(body))
+}
+
+/// The quota reservation of a store request.
+///
+/// Provided as a request extension by the [`quota_layer`]. Asynchronous store requests take over
+/// the reservation, which is then committed or released by the store job once it terminates.
+#[derive(Debug, Clone)]
+pub struct PendingReservation(Arc<Mutex<Option<QuotaReservation>>>);
+
+impl PendingReservation {
+    /// Takes the reservation, if it was not taken yet.
+    pub fn take(&self) -> Option<QuotaReservation> {
+        self.0.lock().expect("mutex should not be poisoned").take()
+    }
+}
+
+/// Returns the usage of all subjects.
+pub(crate) async fn list_usage(
+    State(quota_tracker): State<Arc<QuotaTracker>>,
+    bearer_header: Option<TypedHeader<Authorization<Bearer>>>,
+) -> Response {
+    if let Err(response) = check_admin_token(&quota_tracker, bearer_header) {
+        return response;
+    }
+    Json(quota_tracker.all_usage()).into_response()
+}
+
+/// Returns the usage of a single subject.
+pub(crate) async fn get_usage(
+    State(quota_tracker): State<Arc<QuotaTracker>>,
+    UrlPath(subject): UrlPath<String>,
+    bearer_header: Option<TypedHeader<Authorization<Bearer>>>,
+) -> Response {
+    if let Err(response) = check_admin_token(&quota_tracker, bearer_header) {
+        return response;
+    }
+    match quota_tracker.usage(&subject) {
+        Some(usage) => Json(usage).into_response(),
+        None => (StatusCode::NOT_FOUND, "the subject has no recorded usage").into_response(),
+    }
+}
+
+fn check_admin_token(
+    quota_tracker: &QuotaTracker,
+    bearer_header: Option<TypedHeader<Authorization<Bearer>>>,
+) -> Result<(), Response> {
+    match (quota_tracker.admin_token(), bearer_header) {
+        (Some(expected), Some(TypedHeader(header)))
+            if bool::from(header.token().as_bytes().ct_eq(expected.as_bytes())) =>
+        {
+            Ok(())
+        }
+        _ => Err(StatusCode::UNAUTHORIZED.into_response()),
+    }
+}
+
+mod defaults {
+    pub fn subject_claim() -> String {
+        "sub".to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use walrus_test_utils::Result as TestResult;
+
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).expect("valid date")
+    }
+
+    fn tracker(dir: &Path) -> TestResult<Arc<QuotaTracker>> {
+        Ok(Arc::new(QuotaTracker::new(QuotaConfig {
+            subject_claim: defaults::subject_claim(),
+            usage_path: dir.join("usage.json"),
+            daily: QuotaLimits {
+                max_uploads: Some(2),
+                ..Default::default()
+            },
+            monthly: QuotaLimits {
+                max_bytes: Some(100),
+                ..Default::default()
+            },
+            absolute: QuotaLimits {
+                max_frost: Some(1_000),
+                ..Default::default()
+            },
+            admin_token: None,
+        })?))
+    }
+
+    fn assert_exceeded(
+        result: Result<QuotaReservation, PublisherAuthError>,
+        expected_period: QuotaPeriod,
+        expected_resource: QuotaResource,
+    ) {
+        match result {
+            Err(PublisherAuthError::QuotaExceeded { period, resource }) => {
+                assert_eq!(period, expected_period);
+                assert_eq!(resource, expected_resource);
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    fn upload(
+        tracker: &Arc<QuotaTracker>,
+        subject: &str,
+        bytes: u64,
+        frost: u64,
+        today: NaiveDate,
+    ) -> TestResult {
+        // Commit without charging the WAL at the current date.
+        let mut reservation = tracker.reserve_at(subject, None, bytes, today)?;
+        reservation.committed = true;
+        tracker.charge_at(subject, frost, today);
+        Ok(())
+    }
+
+    #[test]
+    fn quotas_are_enforced_per_period() -> TestResult {
+        let dir = tempfile::tempdir()?;
+        let tracker = tracker(dir.path())?;
+        let day_1 = date(2025, 3, 1);
+
+        upload(&tracker, "alice", 10, 100, day_1)?;
+        upload(&tracker, "alice", 10, 100, day_1)?;
+        assert_exceeded(
+            tracker.reserve_at("alice", None, 10, day_1),
+            QuotaPeriod::Daily,
+            QuotaResource::Uploads,
+        );
+        // Other subjects are not affected.
+        tracker.reserve_at("bob", None, 10, day_1)?;
+
+        // The daily quota is reset on the next day, but not the monthly one.
+        let day_2 = date(2025, 3, 2);
+        tracker.reserve_at("alice", None, 80, day_2)?;
+        assert_exceeded(
+            tracker.reserve_at("alice", None, 81, day_2),
+            QuotaPeriod::Monthly,
+            QuotaResource::Bytes,
+        );
+
+        // The absolute quota is never reset.
+        upload(&tracker, "alice", 0, 800, date(2025, 4, 1))?;
+        assert_exceeded(
+            tracker.reserve_at("alice", None, 0, date(2025, 5, 1)),
+            QuotaPeriod::Absolute,
+            QuotaResource::Wal,
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn concurrent_reservations_cannot_exceed_the_quota() -> TestResult {
+        let dir = tempfile::tempdir()?;
+        let tracker = tracker(dir.path())?;
+        let today = date(2025, 3, 1);
+
+        let first = tracker.reserve_at("alice", None, 60, today)?;
+        assert_exceeded(
+            tracker.reserve_at("alice", None, 60, today),
+            QuotaPeriod::Monthly,
+            QuotaResource::Bytes,
+        );
+
+        // Releasing the first reservation makes room for the second upload.
+        drop(first);
+        let second = tracker.reserve_at("alice", None, 60, today)?;
+        second.commit(10);
+
+        let usage = tracker.usage("alice").expect("alice has usage");
+        assert_eq!(
+            usage.total,
+            UsageCounters {
+                bytes: 60,
+                frost: 10,
+                uploads: 1
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn resumed_reservations_are_released_when_dropped() -> TestResult {
+        let dir = tempfile::tempdir()?;
+        let tracker = tracker(dir.path())?;
+
+        // Simulate a restart, after which the reservation is resumed from its persisted state.
+        let mut reservation = tracker.reserve("alice", None, 60)?;
+        let reserved = reservation.reserved().clone();
+        reservation.committed = true;
+        drop(reservation);
+        assert_eq!(
+            tracker.usage("alice").expect("alice has usage").total.bytes,
+            60
+        );
+
+        drop(tracker.resume_reservation(reserved.clone()));
+        assert_eq!(
+            tracker.usage("alice").expect("alice has usage").total,
+            UsageCounters::default()
+        );
+
+        // Committing a resumed reservation keeps the reserved usage.
+        let mut reservation = tracker.reserve("alice", None, 60)?;
+        reservation.committed = true;
+        drop(reservation);
+        tracker.resume_reservation(reserved).commit(10);
+        assert_eq!(
+            tracker.usage("alice").expect("alice has usage").total,
+            UsageCounters {
+                bytes: 60,
+                frost: 10,
+                uploads: 1
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn releases_only_affect_the_periods_of_the_reservation() {
+        let mut usage = SubjectUsage::new(date(2025, 3, 31));
+        let counters = UsageCounters {
+            bytes: 10,
+            frost: 0,
+            uploads: 1,
+        };
+        usage.add(&counters, date(2025, 3, 31));
+        usage.add(&counters, date(2025, 4, 1));
+
+        // A reservation of the previous month is only released from the total.
+        usage.subtract(&counters, date(2025, 3, 31), date(2025, 4, 1));
+        assert_eq!(usage.daily, counters);
+        assert_eq!(usage.monthly, counters);
+        assert_eq!(usage.total, counters);
+    }
+
+    #[test]
+    fn jwt_subjects_and_api_keys_do_not_share_usage() -> TestResult {
+        let dir = tempfile::tempdir()?;
+        let tracker = tracker(dir.path())?;
+        let today = date(2025, 3, 1);
+        upload(&tracker, &jwt_subject("alice"), 0, 0, today)?;
+        upload(&tracker, &jwt_subject("alice"), 0, 0, today)?;
+
+        assert_ne!(jwt_subject("alice"), api_key_subject("alice"));
+        tracker.reserve_at(&api_key_subject("alice"), None, 0, today)?;
+        Ok(())
+    }
+
+    #[test]
+    fn claim_quotas_override_config() -> TestResult {
+        let dir = tempfile::tempdir()?;
+        let tracker = tracker(dir.path())?;
+        let today = date(2025, 3, 1);
+        upload(&tracker, "alice", 0, 0, today)?;
+        upload(&tracker, "alice", 0, 0, today)?;
+
+        let claim_quotas = ClaimQuotas {
+            daily: Some(QuotaLimits {
+                max_uploads: Some(3),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        tracker.reserve_at("alice", Some(&claim_quotas), 0, today)?;
+        Ok(())
+    }
+
+    #[test]
+    fn usage_is_persisted() -> TestResult {
+        let dir = tempfile::tempdir()?;
+        let today = date(2025, 3, 1);
+        let tracker_1 = tracker(dir.path())?;
+        upload(&tracker_1, "alice", 42, 7, today)?;
+        tracker_1.persist();
+
+        let usage = tracker(dir.path())?.all_usage();
+        assert_eq!(
+            usage["alice"].total,
+            UsageCounters {
+                bytes: 42,
+                frost: 7,
+                uploads: 1
+            }
+        );
+        Ok(())
+    }
+}
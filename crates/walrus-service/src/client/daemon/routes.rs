@@ -9,6 +9,7 @@ use axum::{
     extract::{Path, Query, State},
     http::{HeaderMap, HeaderName, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
+    Extension,
     Json,
 };
 use axum_extra::{
@@ -26,25 +27,33 @@ use reqwest::header::{
     ETAG,
     X_CONTENT_TYPE_OPTIONS,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
 use sui_types::base_types::{ObjectID, SuiAddress};
 use tracing::Level;
-use utoipa::IntoParams;
+use utoipa::{IntoParams, ToSchema};
 use walrus_core::{BlobId, EncodingType, EpochCount};
 use walrus_proc_macros::RestApiError;
 use walrus_sdk::api::errors::DAEMON_ERROR_DOMAIN as ERROR_DOMAIN;
 use walrus_sui::{
-    client::BlobPersistence,
-    types::move_structs::{BlobAttribute, BlobWithAttribute},
+    client::{BlobPersistence, SuiClientError},
+    types::{
+        move_errors::MoveExecutionError,
+        move_structs::{BlobAttribute, BlobWithAttribute},
+    },
     ObjectIdSchema,
     SuiAddressSchema,
 };
 
 use super::{WalrusReadClient, WalrusWriteClient};
 use crate::{
+    backup::{BlobPage, BlobQuery, IndexerClient},
     client::{
         daemon::{
+            api_keys::ApiKeyLimits,
             auth::{Claim, PublisherAuthError},
+            jobs::{JobId, JobManager, StoreJob, StoreJobRequest, SubmitJobError},
+            quota::{PendingReservation, QuotaReservation},
             PostStoreAction,
         },
         BlobStoreResult,
@@ -52,7 +61,10 @@ use crate::{
         ClientErrorKind,
         StoreWhen,
     },
-    common::api::{Binary, BlobIdString, RestApiError},
+    common::{
+        api::{Binary, BlobIdString, RestApiError},
+        blocklist::BlocklistStatus,
+    },
 };
 
 /// The status endpoint, which always returns a 200 status when it is available.
@@ -63,8 +75,12 @@ pub const API_DOCS: &str = "/v1/api";
 pub const BLOB_GET_ENDPOINT: &str = "/v1/blobs/{blob_id}";
 /// The path to get the blob and its attribute with the given object ID.
 pub const BLOB_OBJECT_GET_ENDPOINT: &str = "/v1/blobs/by-object-id/{blob_object_id}";
+/// The path to look up the blob objects with a given attribute through the indexer.
+pub const BLOB_BY_ATTRIBUTE_ENDPOINT: &str = "/v1/blobs/by-attribute";
 /// The path to store a blob.
 pub const BLOB_PUT_ENDPOINT: &str = "/v1/blobs";
+/// The path to get the status of an asynchronous store job.
+pub const JOB_GET_ENDPOINT: &str = "/v1/jobs/{job_id}";
 
 /// Retrieve a Walrus blob.
 ///
@@ -216,6 +232,79 @@ pub(super) async fn get_blob_by_object_id<T: WalrusReadClient>(
     }
 }
 
+/// The query parameters to look up blob objects by attribute.
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct AttributeLookupQuery {
+    /// The key of the attribute pair.
+    pub key: String,
+    /// The value of the attribute pair. If not specified, all blob objects having an attribute
+    /// with the key are returned.
+    #[serde(default)]
+    pub value: Option<String>,
+    /// Only return blob objects owned by this address.
+    #[serde(default)]
+    #[param(value_type = Option<SuiAddressSchema>)]
+    pub owner: Option<SuiAddress>,
+    /// Only return blob objects whose object ID is greater than this one, to fetch the next page.
+    #[serde(default)]
+    #[param(value_type = Option<ObjectIdSchema>)]
+    pub after: Option<ObjectID>,
+    /// The maximum number of blob objects to return.
+    #[serde(default)]
+    #[param(value_type = Option<u32>)]
+    pub limit: Option<usize>,
+}
+
+impl From<AttributeLookupQuery> for BlobQuery {
+    fn from(query: AttributeLookupQuery) -> Self {
+        Self {
+            owner: query.owner,
+            attribute_key: Some(query.key),
+            attribute_value: query.value,
+            after: query.after,
+            limit: query.limit,
+            ..Default::default()
+        }
+    }
+}
+
+/// List the blob objects with a given attribute.
+///
+/// The lookup is served by the blob metadata indexer configured for the aggregator, and is only
+/// available if an indexer is configured. The blob objects are returned in pages, ordered by object
+/// ID; deleted blob objects are not returned.
+#[tracing::instrument(level = Level::ERROR, skip_all, fields(key = %query.key))]
+#[utoipa::path(
+    get,
+    path = BLOB_BY_ATTRIBUTE_ENDPOINT,
+    params(AttributeLookupQuery),
+    responses(
+        (status = 200, description = "The matching blob objects", body = BlobPage),
+        (status = 400, description = "The query parameters are malformed"),
+        AttributeLookupError,
+    ),
+)]
+pub(super) async fn list_blobs_by_attribute(
+    State(indexer_client): State<Arc<IndexerClient>>,
+    Query(query): Query<AttributeLookupQuery>,
+) -> Response {
+    match indexer_client.query_blobs(&query.into()).await {
+        Ok(page) => (StatusCode::OK, Json(page)).into_response(),
+        Err(error) => {
+            tracing::error!(?error, "error looking up blobs in the indexer");
+            AttributeLookupError::Internal(error).into_response()
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error, RestApiError)]
+#[rest_api_error(domain = ERROR_DOMAIN)]
+pub(crate) enum AttributeLookupError {
+    #[error(transparent)]
+    #[rest_api_error(delegate)]
+    Internal(#[from] anyhow::Error),
+}
+
 #[derive(Debug, thiserror::Error, RestApiError)]
 #[rest_api_error(domain = ERROR_DOMAIN)]
 pub(crate) enum GetBlobError {
@@ -250,6 +339,10 @@ impl From<ClientError> for GetBlobError {
 ///
 /// Store a (potentially deletable) blob on Walrus for 1 or more epochs. The associated on-Sui
 /// object can be sent to a specified Sui address.
+///
+/// If the `async` query parameter is set, the publisher stores the blob in the background and
+/// immediately returns the ID of the store job. The progress of the job can then be queried at
+/// the `/v1/jobs/{job_id}` endpoint.
 #[tracing::instrument(level = Level::ERROR, skip_all, fields(%epochs))]
 #[utoipa::path(
     put,
@@ -261,29 +354,69 @@ impl From<ClientError> for GetBlobError {
     params(PublisherQuery),
     responses(
         (status = 200, description = "The blob was stored successfully", body = BlobStoreResult),
-        (status = 400, description = "The request is malformed"),
-        (status = 413, description = "The blob is too large"),
+        (
+            status = 202,
+            description = "The store job was created successfully",
+            body = StoreJobCreated
+        ),
         StoreBlobError,
+        (
+            status = 400,
+            description = "The request is malformed, or the transaction storing the blob was \
+            rejected by the Walrus contracts"
+        ),
+        (status = 413, description = "The blob is too large"),
     ),
 )]
-pub(super) async fn put_blob<T: WalrusWriteClient>(
-    State(client): State<Arc<T>>,
+pub(super) async fn put_blob<T: WalrusWriteClient + Send + Sync + 'static>(
+    State((client, job_manager)): State<(Arc<T>, Arc<JobManager<T>>)>,
     Query(PublisherQuery {
         encoding_type,
         epochs,
         deletable,
         send_object_to,
+        async_store,
+        webhook_url,
     }): Query<PublisherQuery>,
     bearer_header: Option<TypedHeader<Authorization<Bearer>>>,
+    quota_reservation: Option<Extension<PendingReservation>>,
+    api_key_limits: Option<Extension<ApiKeyLimits>>,
     blob: Bytes,
 ) -> Response {
-    // Check if there is an authorization claim, and use it to check the size.
-    if let Some(TypedHeader(header)) = bearer_header {
+    // Check the size against the limits of the API key, if the request was authenticated with one,
+    // or against the authorization claim otherwise.
+    if let Some(Extension(limits)) = api_key_limits {
+        if let Err(error) = limits.check_size(blob.len() as u64) {
+            return error.into_response();
+        }
+    } else if let Some(TypedHeader(header)) = bearer_header {
         if let Err(error) = check_blob_size(header, blob.len()) {
             return error.into_response();
         }
     }
 
+    if async_store {
+        return submit_store_job(
+            &job_manager,
+            blob,
+            StoreJobRequest {
+                encoding_type,
+                epochs,
+                deletable,
+                send_object_to,
+                webhook_url,
+            },
+            quota_reservation.and_then(|Extension(pending)| pending.take()),
+        )
+        .await;
+    } else if webhook_url.is_some() {
+        return (
+            StatusCode::BAD_REQUEST,
+            "the webhook URL can only be specified for asynchronous store requests",
+        )
+            .into_response();
+    }
+
     let post_store_action = if let Some(address) = send_object_to {
         PostStoreAction::TransferTo(address)
     } else {
@@ -324,6 +457,88 @@ pub(super) async fn put_blob<T: WalrusWriteClient>(
     response
 }
 
+/// The response to the creation of an asynchronous store job.
+#[serde_as]
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct StoreJobCreated {
+    /// The ID of the created job.
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    job_id: JobId,
+}
+
+async fn submit_store_job<T: WalrusWriteClient + Send + Sync + 'static>(
+    job_manager: &Arc<JobManager<T>>,
+    blob: Bytes,
+    request: StoreJobRequest,
+    quota_reservation: Option<QuotaReservation>,
+) -> Response {
+    if let Some(webhook_url) = request.webhook_url.as_deref() {
+        if let Err(error) = job_manager.check_webhook_url(webhook_url) {
+            tracing::debug!(?error, %webhook_url, "rejected webhook URL");
+            return (StatusCode::BAD_REQUEST, error.to_string()).into_response();
+        }
+    }
+
+    let mut response = match job_manager.submit(blob, request, quota_reservation).await {
+        Ok(job_id) => (StatusCode::ACCEPTED, Json(StoreJobCreated { job_id })).into_response(),
+        Err(SubmitJobError::TooManyJobs) => {
+            tracing::debug!("rejected store job, as too many jobs are pending");
+            StoreBlobError::TooManyJobs.into_response()
+        }
+        Err(SubmitJobError::Internal(error)) => {
+            tracing::error!(?error, "error creating store job");
+            StoreBlobError::Internal(error).into_response()
+        }
+    };
+    response
+        .headers_mut()
+        .insert(ACCESS_CONTROL_ALLOW_ORIGIN, HeaderValue::from_static("*"));
+    response
+}
+
+/// Get the status of an asynchronous store job.
+///
+/// Returns the current stage of the store job while it is in progress, and the result of the
+/// store operation once it terminates.
+#[tracing::instrument(level = Level::ERROR, skip_all, fields(%job_id))]
+#[utoipa::path(
+    get,
+    path = JOB_GET_ENDPOINT,
+    params(("job_id" = String, description = "The ID of the store job.")),
+    responses(
+        (status = 200, description = "The status of the store job", body = StoreJob),
+        GetJobError,
+    ),
+)]
+pub(super) async fn get_job<T: WalrusWriteClient + Send + Sync + 'static>(
+    State(job_manager): State<Arc<JobManager<T>>>,
+    Path(job_id): Path<String>,
+) -> Response {
+    let job = JobId::from_str(&job_id)
+        .ok()
+        .and_then(|job_id| job_manager.get(&job_id));
+
+    let mut response = match job {
+        Some(job) => (StatusCode::OK, Json(job)).into_response(),
+        None => GetJobError::JobNotFound.into_response(),
+    };
+    response
+        .headers_mut()
+        .insert(ACCESS_CONTROL_ALLOW_ORIGIN, HeaderValue::from_static("*"));
+    response
+}
+
+#[derive(Debug, thiserror::Error, RestApiError)]
+#[rest_api_error(domain = ERROR_DOMAIN)]
+pub(crate) enum GetJobError {
+    /// The requested store job does not exist.
+    #[error("the requested store job does not exist")]
+    #[rest_api_error(reason = "JOB_NOT_FOUND", status = ApiStatusCode::NotFound)]
+    JobNotFound,
+}
+
 /// Checks if the JWT claim has a maximum size and if the blob exceeds it.
 ///
 /// IMPORTANT: This function does _not_ check the validity of the claim (i.e., does not
@@ -377,16 +592,57 @@ pub(crate) enum StoreBlobError {
     #[rest_api_error(reason = "FORBIDDEN_BLOB", status = ApiStatusCode::UnavailableForLegalReasons)]
     Blocked,
 
+    /// The storage capacity of Walrus is exhausted.
+    #[error("{0}")]
+    #[rest_api_error(reason = "STORAGE_CAPACITY_EXCEEDED", status = ApiStatusCode::Unavailable)]
+    StorageCapacityExceeded(String),
+
+    /// The publisher has too many pending store jobs, please try again later.
+    #[error("the publisher has too many pending store jobs, please try again later")]
+    #[rest_api_error(reason = "TOO_MANY_STORE_JOBS", status = ApiStatusCode::Unavailable)]
+    TooManyJobs,
+
+    /// The requested number of epochs exceeds the maximum number of epochs ahead.
+    #[error("{0}")]
+    #[rest_api_error(reason = "INVALID_EPOCHS_AHEAD", status = ApiStatusCode::InvalidArgument)]
+    InvalidEpochsAhead(String),
+
+    /// The transaction storing the blob was aborted by the Walrus contracts.
+    #[error("{0}")]
+    #[rest_api_error(reason = "CONTRACT_ABORTED", status = ApiStatusCode::FailedPrecondition)]
+    ContractAborted(String),
+
     #[error(transparent)]
     #[rest_api_error(delegate)]
     Internal(#[from] anyhow::Error),
 }
 
+impl StoreBlobError {
+    fn from_move_abort(error: &MoveExecutionError) -> Self {
+        let mut message = format!(
+            "the transaction was aborted by the Walrus contracts: {}",
+            error.description().unwrap_or("unknown error")
+        );
+        if let Some(hint) = error.remediation_hint() {
+            message = format!("{message} ({hint})");
+        }
+        match error.error_name() {
+            Some("EStorageExceeded") => Self::StorageCapacityExceeded(message),
+            Some("EInvalidEpochsAhead") => Self::InvalidEpochsAhead(message),
+            _ => Self::ContractAborted(message),
+        }
+    }
+}
+
 impl From<ClientError> for StoreBlobError {
     fn from(error: ClientError) -> Self {
         match error.kind() {
             ClientErrorKind::NotEnoughConfirmations(_, _) => Self::NotEnoughConfirmations,
             ClientErrorKind::BlobIdBlocked(_) => Self::Blocked,
+            ClientErrorKind::MoveAbort(move_error)
+            | ClientErrorKind::CertificationFailed(SuiClientError::TransactionExecutionError(
+                move_error,
+            )) if move_error.error_name().is_some() => Self::from_move_abort(move_error),
             _ => Self::Internal(anyhow!(error)),
         }
     }
@@ -402,16 +658,44 @@ pub(super) async fn store_blob_options() -> impl IntoResponse {
     ]
 }
 
+/// The query parameters of the status endpoint.
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct StatusQuery {
+    /// If specified, the response reports whether the blob ID is blocked, and by which list.
+    #[param(value_type = Option<String>)]
+    #[serde(default)]
+    blob_id: Option<BlobIdString>,
+}
+
 #[tracing::instrument(level = Level::ERROR, skip_all)]
 #[utoipa::path(
     get,
     path = STATUS_ENDPOINT,
+    params(StatusQuery),
     responses(
-        (status = 200, description = "The service is running"),
+        (
+            status = 200,
+            description = "The service is running; if a blob ID is specified, the response \
+                contains its blocklist status",
+            body = BlocklistStatus,
+        ),
     ),
 )]
-pub(super) async fn status() -> Response {
-    "OK".into_response()
+pub(super) async fn status<T: WalrusReadClient>(
+    State(client): State<Arc<T>>,
+    Query(query): Query<StatusQuery>,
+) -> Response {
+    let Some(BlobIdString(blob_id)) = query.blob_id else {
+        return "OK".into_response();
+    };
+    let status = match client.blocklist() {
+        Some(blocklist) => blocklist.status(&blob_id),
+        None => BlocklistStatus {
+            blob_id,
+            blocked_by: None,
+        },
+    };
+    Json(status).into_response()
 }
 
 /// The query parameters for a publisher.
@@ -433,6 +717,16 @@ pub struct PublisherQuery {
     /// this Sui address.
     #[param(value_type = Option<SuiAddressSchema>)]
     pub send_object_to: Option<SuiAddress>,
+    /// If true, the publisher stores the blob in the background and immediately returns the ID of
+    /// the store job.
+    #[serde(default, rename = "async")]
+    pub async_store: bool,
+    /// If specified, the publisher posts the final state of the store job to this URL.
+    ///
+    /// Only allowed for asynchronous store requests, and only for the hosts allowed by the
+    /// publisher.
+    #[serde(default)]
+    pub webhook_url: Option<String>,
 }
 
 pub(super) fn default_epochs() -> EpochCount {
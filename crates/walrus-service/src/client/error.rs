@@ -5,7 +5,10 @@
 
 use walrus_core::{BlobId, EncodingType, Epoch, SliverPairIndex, SliverType};
 use walrus_sdk::error::{ClientBuildError, NodeError};
-use walrus_sui::client::{SuiClientError, MIN_STAKING_THRESHOLD};
+use walrus_sui::{
+    client::{SuiClientError, MIN_STAKING_THRESHOLD},
+    types::move_errors::MoveExecutionError,
+};
 
 /// Storing the metadata and the set of sliver pairs onto the storage node, and retrieving the
 /// storage confirmation, failed.
@@ -68,8 +71,26 @@ impl ClientError {
         )
     }
 
+    /// Returns a hint on how to resolve the error, if there is one.
+    pub fn remediation_hint(&self) -> Option<&'static str> {
+        match &self.kind {
+            ClientErrorKind::MoveAbort(error)
+            | ClientErrorKind::CertificationFailed(SuiClientError::TransactionExecutionError(
+                error,
+            )) => error.remediation_hint(),
+            _ => None,
+        }
+    }
+
     /// Returns `true` if the error may have been caused by epoch change.
     pub fn may_be_caused_by_epoch_change(&self) -> bool {
+        if matches!(
+            &self.kind,
+            ClientErrorKind::CertificationFailed(SuiClientError::UnsignedTransactionEmitted(_))
+        ) {
+            // The certification was handed out for external signing; retrying cannot help.
+            return false;
+        }
         matches!(
             &self.kind,
             // Cannot get confirmations.
@@ -96,6 +117,7 @@ impl From<SuiClientError> for ClientError {
             SuiClientError::StakeBelowThreshold(amount) => {
                 ClientErrorKind::StakeBelowThreshold(amount)
             }
+            SuiClientError::TransactionExecutionError(error) => ClientErrorKind::MoveAbort(error),
             error => ClientErrorKind::Other(error.into()),
         };
         Self { kind }
@@ -170,6 +192,9 @@ pub enum ClientErrorKind {
         FROST for staking"
     )]
     StakeBelowThreshold(u64),
+    /// A transaction was aborted by the Walrus contracts.
+    #[error("transaction execution failed: {0}")]
+    MoveAbort(MoveExecutionError),
     /// Unable to load trusted certificates from the OS.
     #[error("unable to load trusted certificates from the OS: {0:?}")]
     FailedToLoadCerts(Vec<rustls_native_certs::Error>),
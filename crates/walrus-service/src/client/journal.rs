@@ -0,0 +1,716 @@
+// Copyright (c) Walrus Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! On-disk journal to resume interrupted store operations.
+//!
+//! For each file stored with a journal, the client records the encoded metadata, the registered
+//! blob object, and the storage confirmations received from the storage nodes. If the store
+//! operation is interrupted, a new attempt can use the journal to skip the completed steps.
+//!
+//! The journal file consists of JSON lines: a header with the file and the store parameters,
+//! followed by one line per recorded step. New steps are appended by a dedicated thread, such that
+//! the store operation is not blocked by disk I/O. When a journal is loaded, it is compacted to
+//! contain only the records that are still relevant.
+
+use std::{
+    fs,
+    io::Write as _,
+    path::{Path, PathBuf},
+    sync::{mpsc, Mutex},
+    thread,
+    time::SystemTime,
+};
+
+use anyhow::{Context as _, Result};
+use fastcrypto::encoding::{Encoding as _, Hex};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest as _, Sha256};
+use sui_types::base_types::ObjectID;
+use tokio::sync::oneshot;
+use walrus_core::{
+    messages::SignedStorageConfirmation,
+    metadata::{UnverifiedBlobMetadataWithId, VerifiedBlobMetadataWithId},
+    EncodingType,
+    Epoch,
+    EpochCount,
+};
+use walrus_sui::client::{BlobPersistence, PostStoreAction};
+
+use super::{resource::RegisterBlobOp, StoreWhen};
+
+const JOURNAL_FILE_EXTENSION: &str = "journal.jsonl";
+
+/// The parameters of a journaled store operation.
+///
+/// Resuming a store operation uses the same parameters as the original one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournaledStoreParams {
+    /// The encoding type of the blob.
+    pub encoding_type: EncodingType,
+    /// The number of epochs ahead for which to store the blob.
+    pub epochs_ahead: EpochCount,
+    /// Whether to store the blob even if it is already certified.
+    pub force: bool,
+    /// Whether to ignore the storage resources owned by the wallet.
+    pub ignore_resources: bool,
+    /// Whether the blob is deletable.
+    pub deletable: bool,
+    /// Whether to put the blob into a shared blob object.
+    pub share: bool,
+}
+
+impl JournaledStoreParams {
+    /// Returns the [`StoreWhen`] corresponding to the parameters.
+    pub fn store_when(&self) -> StoreWhen {
+        StoreWhen::from_flags(self.force, self.ignore_resources)
+    }
+
+    /// Returns the [`BlobPersistence`] corresponding to the parameters.
+    pub fn persistence(&self) -> BlobPersistence {
+        BlobPersistence::from_deletable(self.deletable)
+    }
+
+    /// Returns the [`PostStoreAction`] corresponding to the parameters.
+    pub fn post_store(&self) -> PostStoreAction {
+        PostStoreAction::from_share(self.share)
+    }
+}
+
+/// The registration of the blob on Sui.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournaledRegistration {
+    /// The object ID of the registered blob.
+    pub blob_object_id: ObjectID,
+    /// The operation performed to register the blob.
+    pub operation: RegisterBlobOp,
+}
+
+/// A storage confirmation received from a storage node.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournaledConfirmation {
+    /// The epoch of the committee to which the node belongs.
+    pub epoch: Epoch,
+    /// The index of the node in the committee.
+    pub node_index: usize,
+    /// The object ID of the node, to guard against changes in the committee.
+    pub node_id: ObjectID,
+    /// The signed confirmation.
+    pub confirmation: SignedStorageConfirmation,
+}
+
+/// The content of a journal file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalEntry {
+    /// The file being stored.
+    pub file: PathBuf,
+    /// The size of the file when the journal was created.
+    pub file_size: u64,
+    /// The modification time of the file when the journal was created.
+    pub file_modified: Option<SystemTime>,
+    /// The parameters of the store operation.
+    pub params: JournaledStoreParams,
+    /// The encoded metadata of the blob, if the blob has been encoded.
+    pub metadata: Option<UnverifiedBlobMetadataWithId>,
+    /// The registration of the blob, if the blob has been registered.
+    pub registration: Option<JournaledRegistration>,
+    /// The storage confirmations received so far.
+    #[serde(default)]
+    pub confirmations: Vec<JournaledConfirmation>,
+}
+
+impl JournalEntry {
+    /// Returns `true` if the journal was created for the current version of the file.
+    fn matches_file(&self, file_size: u64, file_modified: Option<SystemTime>) -> bool {
+        self.file_size == file_size && self.file_modified == file_modified
+    }
+
+    /// Applies a recorded step to the entry.
+    fn apply(&mut self, record: JournalRecord) {
+        match record {
+            JournalRecord::Metadata(metadata) => self.metadata = Some(metadata),
+            JournalRecord::Registration(registration) => self.registration = Some(registration),
+            JournalRecord::ClearRegistration => {
+                self.registration = None;
+                self.confirmations.clear();
+            }
+            JournalRecord::Confirmation(confirmation) => {
+                // Confirmations from previous epochs cannot be used for the certificate.
+                self.confirmations.retain(|existing| {
+                    existing.epoch == confirmation.epoch
+                        && existing.node_index != confirmation.node_index
+                });
+                self.confirmations.push(confirmation);
+            }
+        }
+    }
+
+    /// Returns the header and the records from which the entry can be reconstructed.
+    fn to_lines(&self) -> (JournalHeader, Vec<JournalRecord>) {
+        let header = JournalHeader {
+            file: self.file.clone(),
+            file_size: self.file_size,
+            file_modified: self.file_modified,
+            params: self.params.clone(),
+        };
+        let records = self
+            .metadata
+            .clone()
+            .map(JournalRecord::Metadata)
+            .into_iter()
+            .chain(self.registration.clone().map(JournalRecord::Registration))
+            .chain(
+                self.confirmations
+                    .iter()
+                    .cloned()
+                    .map(JournalRecord::Confirmation),
+            )
+            .collect();
+        (header, records)
+    }
+}
+
+/// The first line of a journal file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JournalHeader {
+    file: PathBuf,
+    file_size: u64,
+    file_modified: Option<SystemTime>,
+    params: JournaledStoreParams,
+}
+
+impl From<JournalHeader> for JournalEntry {
+    fn from(header: JournalHeader) -> Self {
+        Self {
+            file: header.file,
+            file_size: header.file_size,
+            file_modified: header.file_modified,
+            params: header.params,
+            metadata: None,
+            registration: None,
+            confirmations: vec![],
+        }
+    }
+}
+
+/// A step of the store operation, recorded as a line of the journal file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum JournalRecord {
+    Metadata(UnverifiedBlobMetadataWithId),
+    Registration(JournaledRegistration),
+    ClearRegistration,
+    Confirmation(JournaledConfirmation),
+}
+
+/// A command for the thread writing the journal file.
+#[derive(Debug)]
+enum WriterCommand {
+    /// Appends the line to the journal file.
+    Append(Vec<u8>),
+    /// Flushes the journal file to disk, and reports whether all writes so far succeeded.
+    Sync(oneshot::Sender<std::io::Result<()>>),
+}
+
+/// Appends lines to a journal file on a dedicated thread.
+#[derive(Debug)]
+struct JournalWriter {
+    sender: Option<mpsc::Sender<WriterCommand>>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl JournalWriter {
+    /// Starts the writer thread for the journal file at `path`, which must exist.
+    fn start(path: &Path) -> Result<Self> {
+        let mut file = fs::OpenOptions::new().append(true).open(path)?;
+        let (sender, receiver) = mpsc::channel();
+        let thread = thread::Builder::new()
+            .name("store-journal".to_owned())
+            .spawn(move || {
+                let mut failure = None;
+                for command in receiver {
+                    match command {
+                        WriterCommand::Append(line) => {
+                            if let Err(error) = file.write_all(&line) {
+                                tracing::warn!(?error, "failed to append to the store journal");
+                                failure = Some(error);
+                            }
+                        }
+                        WriterCommand::Sync(reply) => {
+                            let result = match failure.take() {
+                                Some(error) => Err(error),
+                                None => file.sync_data(),
+                            };
+                            let _ = reply.send(result);
+                        }
+                    }
+                }
+            })?;
+        Ok(Self {
+            sender: Some(sender),
+            thread: Some(thread),
+        })
+    }
+
+    fn send(&self, command: WriterCommand) -> Result<()> {
+        self.sender
+            .as_ref()
+            .and_then(|sender| sender.send(command).ok())
+            .ok_or_else(|| anyhow::anyhow!("the journal writer has terminated"))
+    }
+
+    /// Waits until all lines appended so far have been written to disk.
+    async fn sync(&self) -> Result<()> {
+        let (reply, result) = oneshot::channel();
+        self.send(WriterCommand::Sync(reply))?;
+        result
+            .await
+            .context("the journal writer has terminated")?
+            .context("failed to write the journal")
+    }
+
+    /// Stops the writer thread after it has written all pending lines.
+    async fn close(mut self) -> Result<()> {
+        self.sender = None;
+        if let Some(thread) = self.thread.take() {
+            tokio::task::spawn_blocking(move || thread.join())
+                .await?
+                .map_err(|_| anyhow::anyhow!("the journal writer panicked"))?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for JournalWriter {
+    fn drop(&mut self) {
+        // Joining here would block the async runtime. Dropping the sender lets the detached thread
+        // terminate once it has written the pending lines.
+        if self.thread.is_some() {
+            tracing::warn!("the store journal was dropped without being closed");
+        }
+    }
+}
+
+/// A journal recording the progress of the store operation of a single file.
+///
+/// Every update is applied in memory immediately, and appended to the journal file in the
+/// background.
+#[derive(Debug)]
+pub struct StoreJournal {
+    path: PathBuf,
+    entry: Mutex<JournalEntry>,
+    writer: JournalWriter,
+}
+
+impl StoreJournal {
+    /// Opens the journal for the given file in the journal directory.
+    ///
+    /// If a journal exists for the same version of the file and with the same parameters, it is
+    /// loaded; otherwise, a new journal is created.
+    pub fn open(journal_dir: &Path, file: &Path, params: JournaledStoreParams) -> Result<Self> {
+        let file = file
+            .canonicalize()
+            .with_context(|| format!("unable to resolve the path {}", file.display()))?;
+        let file_metadata = fs::metadata(&file)?;
+        let file_size = file_metadata.len();
+        let file_modified = file_metadata.modified().ok();
+        let path = Self::journal_path(journal_dir, &file);
+
+        if path.exists() {
+            match Self::load(&path) {
+                Ok(journal) => {
+                    let entry = journal.entry();
+                    if entry.params == params && entry.matches_file(file_size, file_modified) {
+                        tracing::info!(
+                            file = %file.display(),
+                            journal = %path.display(),
+                            "resuming the store operation from the journal"
+                        );
+                        return Ok(journal);
+                    }
+                    tracing::info!(
+                        file = %file.display(),
+                        "the file or the store parameters have changed; discarding the journal"
+                    );
+                }
+                Err(error) => {
+                    tracing::warn!(?error, journal = %path.display(), "discarding invalid journal")
+                }
+            }
+        }
+
+        fs::create_dir_all(journal_dir)?;
+        let header = JournalHeader {
+            file,
+            file_size,
+            file_modified,
+            params,
+        };
+        Self::write_compacted(&path, &header, &[])?;
+        Ok(Self {
+            writer: JournalWriter::start(&path)?,
+            path,
+            entry: Mutex::new(header.into()),
+        })
+    }
+
+    /// Loads an existing journal from its path.
+    ///
+    /// Lines that cannot be parsed, e.g., because the previous attempt was interrupted while
+    /// writing them, are skipped. The journal file is then compacted.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)?;
+        let mut lines = content.lines();
+        let header: JournalHeader = serde_json::from_str(lines.next().unwrap_or_default())
+            .with_context(|| format!("unable to parse the journal {}", path.display()))?;
+        let mut entry = JournalEntry::from(header);
+        for line in lines {
+            match serde_json::from_str(line) {
+                Ok(record) => entry.apply(record),
+                Err(error) => {
+                    tracing::warn!(?error, journal = %path.display(), "skipping invalid journal line")
+                }
+            }
+        }
+
+        let (header, records) = entry.to_lines();
+        Self::write_compacted(path, &header, &records)?;
+        Ok(Self {
+            writer: JournalWriter::start(path)?,
+            path: path.to_owned(),
+            entry: Mutex::new(entry),
+        })
+    }
+
+    /// Returns the paths of all the journals in the journal directory.
+    pub fn list(journal_dir: &Path) -> Result<Vec<PathBuf>> {
+        if !journal_dir.exists() {
+            return Ok(vec![]);
+        }
+        let mut paths = fs::read_dir(journal_dir)?
+            .map(|dir_entry| dir_entry.map(|dir_entry| dir_entry.path()))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.ends_with(JOURNAL_FILE_EXTENSION))
+            })
+            .collect::<Vec<_>>();
+        paths.sort();
+        Ok(paths)
+    }
+
+    /// Returns the path of the journal for the given (canonical) file path.
+    pub fn journal_path(journal_dir: &Path, file: &Path) -> PathBuf {
+        let digest = Sha256::digest(file.as_os_str().as_encoded_bytes());
+        journal_dir.join(format!(
+            "{}.{JOURNAL_FILE_EXTENSION}",
+            Hex::encode(&digest[..16])
+        ))
+    }
+
+    /// Returns the path of the journal file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns a copy of the current content of the journal.
+    pub fn entry(&self) -> JournalEntry {
+        self.entry
+            .lock()
+            .expect("mutex should not be poisoned")
+            .clone()
+    }
+
+    /// Checks that the journaled file has not changed since the journal was created.
+    pub fn check_file_unchanged(&self) -> Result<()> {
+        let entry = self.entry();
+        let file_metadata = fs::metadata(&entry.file)?;
+        anyhow::ensure!(
+            entry.matches_file(file_metadata.len(), file_metadata.modified().ok()),
+            "the file {} has changed since the journal was created",
+            entry.file.display()
+        );
+        Ok(())
+    }
+
+    /// Records the encoded metadata of the blob.
+    pub fn record_metadata(&self, metadata: &VerifiedBlobMetadataWithId) -> Result<()> {
+        self.record(JournalRecord::Metadata(metadata.clone().into_unverified()))
+    }
+
+    /// Records the registration of the blob.
+    ///
+    /// As registering the blob is costly, callers should [`sync`][Self::sync] the journal before
+    /// proceeding.
+    pub fn record_registration(
+        &self,
+        blob_object_id: ObjectID,
+        operation: RegisterBlobOp,
+    ) -> Result<()> {
+        self.record(JournalRecord::Registration(JournaledRegistration {
+            blob_object_id,
+            operation,
+        }))
+    }
+
+    /// Removes the registration and the confirmations from the journal.
+    ///
+    /// This is used if the registered blob object is no longer available.
+    pub fn clear_registration(&self) -> Result<()> {
+        self.record(JournalRecord::ClearRegistration)
+    }
+
+    /// Records a storage confirmation received from a storage node.
+    ///
+    /// Confirmations from previous epochs are dropped, as they cannot be used for the certificate.
+    pub fn record_confirmation(&self, confirmation: JournaledConfirmation) -> Result<()> {
+        self.record(JournalRecord::Confirmation(confirmation))
+    }
+
+    /// Waits until all the updates recorded so far have been written to disk.
+    pub async fn sync(&self) -> Result<()> {
+        self.writer.sync().await
+    }
+
+    /// Stops writing the journal, after all the updates recorded so far have been written.
+    ///
+    /// Must be called when the store operation is interrupted, such that the journal can be
+    /// reopened to resume it.
+    pub async fn close(self) -> Result<()> {
+        self.writer.close().await
+    }
+
+    /// Removes the journal from disk, after the store operation has completed.
+    pub async fn remove(self) -> Result<()> {
+        self.writer.close().await?;
+        match tokio::fs::remove_file(&self.path).await {
+            Err(error) if error.kind() != std::io::ErrorKind::NotFound => Err(error.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Applies the record to the journal entry, and appends it to the journal file.
+    fn record(&self, record: JournalRecord) -> Result<()> {
+        let mut line = serde_json::to_vec(&record)?;
+        line.push(b'\n');
+        let mut entry = self.entry.lock().expect("mutex should not be poisoned");
+        entry.apply(record);
+        // Sending while holding the lock keeps the order of the lines consistent with the entry.
+        self.writer.send(WriterCommand::Append(line))
+    }
+
+    /// Writes the header and the records to the journal file, replacing it atomically.
+    fn write_compacted(
+        path: &Path,
+        header: &JournalHeader,
+        records: &[JournalRecord],
+    ) -> Result<()> {
+        let mut content = serde_json::to_vec(header)?;
+        content.push(b'\n');
+        for record in records {
+            serde_json::to_writer(&mut content, record)?;
+            content.push(b'\n');
+        }
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, content)?;
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use walrus_core::test_utils;
+    use walrus_test_utils::async_param_test;
+
+    use super::*;
+
+    fn params() -> JournaledStoreParams {
+        JournaledStoreParams {
+            encoding_type: EncodingType::RS2,
+            epochs_ahead: 5,
+            force: false,
+            ignore_resources: false,
+            deletable: false,
+            share: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn journal_is_reloaded_for_unchanged_file() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let file = dir.path().join("blob");
+        fs::write(&file, b"some blob")?;
+        let journal_dir = dir.path().join("journal");
+
+        let journal = StoreJournal::open(&journal_dir, &file, params())?;
+        journal.record_registration(
+            ObjectID::ZERO,
+            RegisterBlobOp::RegisterFromScratch {
+                encoded_length: 100,
+                epochs_ahead: 5,
+            },
+        )?;
+        journal.close().await?;
+
+        assert_eq!(StoreJournal::list(&journal_dir)?.len(), 1);
+        let journal = StoreJournal::open(&journal_dir, &file, params())?;
+        assert!(journal.entry().registration.is_some());
+        journal.remove().await?;
+        assert!(StoreJournal::list(&journal_dir)?.is_empty());
+        Ok(())
+    }
+
+    async_param_test! {
+        journal_is_discarded -> Result<()>: [
+            changed_file: (true, false),
+            changed_params: (false, true),
+        ]
+    }
+    async fn journal_is_discarded(change_file: bool, change_params: bool) -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let file = dir.path().join("blob");
+        fs::write(&file, b"some blob")?;
+        let journal_dir = dir.path().join("journal");
+
+        let journal = StoreJournal::open(&journal_dir, &file, params())?;
+        journal.record_registration(
+            ObjectID::ZERO,
+            RegisterBlobOp::ReuseStorage {
+                encoded_length: 100,
+            },
+        )?;
+        journal.close().await?;
+
+        if change_file {
+            fs::write(&file, b"some other blob")?;
+        }
+        let mut new_params = params();
+        new_params.deletable = change_params;
+
+        let journal = StoreJournal::open(&journal_dir, &file, new_params)?;
+        assert!(journal.entry().registration.is_none());
+        Ok(())
+    }
+
+    fn confirmation(epoch: Epoch, node_index: usize) -> JournaledConfirmation {
+        JournaledConfirmation {
+            epoch,
+            node_index,
+            node_id: ObjectID::ZERO,
+            confirmation: test_utils::random_signed_message(),
+        }
+    }
+
+    fn open_journal(dir: &Path) -> Result<StoreJournal> {
+        let file = dir.join("blob");
+        fs::write(&file, b"some blob")?;
+        StoreJournal::open(&dir.join("journal"), &file, params())
+    }
+
+    #[tokio::test]
+    async fn progress_is_resumed_and_compacted() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let journal = open_journal(dir.path())?;
+        let path = journal.path().to_owned();
+        let metadata = test_utils::verified_blob_metadata();
+
+        journal.record_metadata(&metadata)?;
+        journal.record_registration(
+            ObjectID::ZERO,
+            RegisterBlobOp::ReuseStorage {
+                encoded_length: 100,
+            },
+        )?;
+        journal.record_confirmation(confirmation(1, 0))?;
+        journal.record_confirmation(confirmation(1, 1))?;
+        // A confirmation from a new epoch supersedes the ones from previous epochs.
+        journal.record_confirmation(confirmation(2, 1))?;
+        // A new confirmation from the same node replaces the previous one.
+        journal.record_confirmation(confirmation(2, 1))?;
+        journal.record_confirmation(confirmation(2, 3))?;
+        journal.close().await?;
+        assert_eq!(fs::read_to_string(&path)?.lines().count(), 8);
+
+        let journal = StoreJournal::load(&path)?;
+        let entry = journal.entry();
+        assert_eq!(
+            entry.metadata.as_ref().map(|metadata| *metadata.blob_id()),
+            Some(*metadata.blob_id())
+        );
+        assert!(entry.registration.is_some());
+        assert_eq!(
+            entry
+                .confirmations
+                .iter()
+                .map(|confirmation| (confirmation.epoch, confirmation.node_index))
+                .collect::<Vec<_>>(),
+            vec![(2, 1), (2, 3)]
+        );
+        // The loaded journal only contains the relevant records.
+        assert_eq!(fs::read_to_string(&path)?.lines().count(), 5);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn cleared_registration_is_not_resumed() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let journal = open_journal(dir.path())?;
+        let path = journal.path().to_owned();
+
+        journal.record_registration(
+            ObjectID::ZERO,
+            RegisterBlobOp::ReuseStorage {
+                encoded_length: 100,
+            },
+        )?;
+        journal.record_confirmation(confirmation(1, 0))?;
+        journal.clear_registration()?;
+        journal.close().await?;
+
+        let entry = StoreJournal::load(&path)?.entry();
+        assert!(entry.registration.is_none());
+        assert!(entry.confirmations.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn interrupted_write_is_skipped() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let journal = open_journal(dir.path())?;
+        let path = journal.path().to_owned();
+
+        journal.record_confirmation(confirmation(1, 0))?;
+        journal.close().await?;
+        let mut file = fs::OpenOptions::new().append(true).open(&path)?;
+        file.write_all(b"{\"confirmation\":{\"epo")?;
+        drop(file);
+
+        let entry = StoreJournal::load(&path)?.entry();
+        assert_eq!(entry.confirmations.len(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn synced_records_are_on_disk() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let journal = open_journal(dir.path())?;
+
+        journal.record_registration(
+            ObjectID::ZERO,
+            RegisterBlobOp::ReuseStorage {
+                encoded_length: 100,
+            },
+        )?;
+        journal.sync().await?;
+        assert_eq!(fs::read_to_string(journal.path())?.lines().count(), 2);
+        journal.close().await?;
+        Ok(())
+    }
+}
@@ -8,10 +8,12 @@ use std::time::Duration;
 use prometheus::{
     register_counter_vec_with_registry,
     register_histogram_vec_with_registry,
+    register_int_counter_vec_with_registry,
     register_int_counter_with_registry,
     CounterVec,
     HistogramVec,
     IntCounter,
+    IntCounterVec,
     Registry,
 };
 
@@ -42,6 +44,10 @@ pub struct ClientMetrics {
     pub gas_refill: IntCounter,
     /// Number of WAL refills performed by the client.
     pub wal_refill: IntCounter,
+    /// Number of requests sent to storage nodes by byzantine workloads.
+    pub byzantine_requests: IntCounterVec,
+    /// Number of requests of byzantine workloads rejected by storage nodes.
+    pub byzantine_rejected: IntCounterVec,
 }
 
 impl ClientMetrics {
@@ -95,6 +101,20 @@ impl ClientMetrics {
                 registry,
             )
             .expect("this is a valid metrics registration"),
+            byzantine_requests: register_int_counter_vec_with_registry!(
+                "byzantine_requests",
+                "Number of requests sent to storage nodes by byzantine workloads",
+                &["workload"],
+                registry,
+            )
+            .expect("this is a valid metrics registration"),
+            byzantine_rejected: register_int_counter_vec_with_registry!(
+                "byzantine_rejected",
+                "Number of requests of byzantine workloads rejected by storage nodes",
+                &["workload"],
+                registry,
+            )
+            .expect("this is a valid metrics registration"),
         }
     }
 
@@ -130,4 +150,10 @@ impl ClientMetrics {
     pub fn observe_wal_refill(&self) {
         self.wal_refill.inc();
     }
+
+    /// Increments the counters of requests sent and rejected for the given byzantine workload.
+    pub fn observe_byzantine_requests(&self, workload: &str, requests: u64, rejected: u64) {
+        walrus_utils::with_label!(self.byzantine_requests, workload).inc_by(requests);
+        walrus_utils::with_label!(self.byzantine_rejected, workload).inc_by(rejected);
+    }
 }
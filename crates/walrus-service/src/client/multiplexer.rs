@@ -23,6 +23,7 @@ use walrus_sui::{
     client::{
         retry_client::RetriableSuiClient,
         BlobPersistence,
+        GasSponsor,
         PostStoreAction,
         SuiContractClient,
         SuiReadClient,
@@ -40,9 +41,10 @@ use super::{
     responses::BlobStoreResult,
     Client,
     ClientResult,
+    StoreProgress,
     StoreWhen,
 };
-use crate::client::{refill::should_refill, CommitteesRefresherHandle, Config};
+use crate::client::{refill::should_refill, Blocklist, CommitteesRefresherHandle, Config};
 
 pub struct ClientMultiplexer {
     client_pool: WriteClientPool,
@@ -60,7 +62,12 @@ impl ClientMultiplexer {
         args: &PublisherArgs,
     ) -> anyhow::Result<Self> {
         let sui_env = wallet.config.get_active_env()?.clone();
-        let contract_client = config.new_contract_client(wallet, gas_budget).await?;
+        // The main wallet pays for its own gas, as it sends SUI and WAL to the sub-wallets.
+        let contract_client = config
+            .new_contract_client_with_sponsor(wallet, gas_budget, None)
+            .await?;
+        // The sub-wallets share the sponsor, if any, such that they do not need any SUI.
+        let gas_sponsor = config.gas_sponsor()?;
         let main_address = contract_client.address();
 
         let sui_client = contract_client.sui_client().clone();
@@ -78,12 +85,16 @@ impl ClientMultiplexer {
         )
         .await?;
 
-        let refiller = Refiller::new(
+        let mut refiller = Refiller::new(
             contract_client,
             args.gas_refill_amount,
             args.wal_refill_amount,
             args.sub_wallets_min_balance,
         );
+        if gas_sponsor.is_some() {
+            tracing::info!("the gas of the sub-wallets is sponsored; not refilling their gas");
+            refiller = refiller.without_gas_refills();
+        }
 
         let client_pool = WriteClientPool::new(
             config,
@@ -93,6 +104,7 @@ impl ClientMultiplexer {
                 gas_budget,
                 args.sub_wallets_dir.clone(),
                 args.sub_wallets_min_balance,
+                gas_sponsor,
             ),
             &refiller,
             refresh_handle.clone(),
@@ -126,6 +138,7 @@ impl ClientMultiplexer {
     }
 
     /// Submits a write request to the client pool.
+    #[allow(clippy::too_many_arguments)]
     #[tracing::instrument(err, skip_all)]
     pub async fn submit_write(
         &self,
@@ -135,18 +148,20 @@ impl ClientMultiplexer {
         store_when: StoreWhen,
         persistence: BlobPersistence,
         post_store: PostStoreAction,
+        progress: StoreProgress,
     ) -> ClientResult<BlobStoreResult> {
         let client = self.client_pool.next_client().await;
         tracing::debug!("submitting write request to client in pool");
 
         let result = client
-            .write_blob(
+            .write_blob_with_progress(
                 blob,
                 encoding_type,
                 epochs_ahead,
                 store_when,
                 persistence,
                 post_store,
+                progress,
             )
             .await?;
 
@@ -165,10 +180,14 @@ impl WalrusReadClient for ClientMultiplexer {
     ) -> ClientResult<BlobWithAttribute> {
         self.read_client.get_blob_by_object_id(blob_object_id).await
     }
+
+    fn blocklist(&self) -> Option<&Blocklist> {
+        WalrusReadClient::blocklist(&self.read_client)
+    }
 }
 
 impl WalrusWriteClient for ClientMultiplexer {
-    async fn write_blob(
+    async fn write_blob_with_progress(
         &self,
         blob: &[u8],
         encoding_type: Option<EncodingType>,
@@ -176,6 +195,7 @@ impl WalrusWriteClient for ClientMultiplexer {
         store_when: StoreWhen,
         persistence: BlobPersistence,
         post_store: PostStoreAction,
+        progress: StoreProgress,
     ) -> ClientResult<BlobStoreResult> {
         self.submit_write(
             blob,
@@ -184,6 +204,7 @@ impl WalrusWriteClient for ClientMultiplexer {
             store_when,
             persistence,
             post_store,
+            progress,
         )
         .await
     }
@@ -200,6 +221,7 @@ pub struct WriteClientPoolConfig {
     gas_budget: Option<u64>,
     sub_wallets_dir: PathBuf,
     min_balance: u64,
+    gas_sponsor: Option<GasSponsor>,
 }
 
 impl WriteClientPoolConfig {
@@ -209,6 +231,7 @@ impl WriteClientPoolConfig {
         gas_budget: Option<u64>,
         sub_wallets_dir: PathBuf,
         min_balance: u64,
+        gas_sponsor: Option<GasSponsor>,
     ) -> Self {
         Self {
             n_clients,
@@ -216,6 +239,7 @@ impl WriteClientPoolConfig {
             gas_budget,
             sub_wallets_dir,
             min_balance,
+            gas_sponsor,
         }
     }
 }
@@ -243,6 +267,7 @@ impl WriteClientPool {
             pool_config.gas_budget,
             refiller,
             pool_config.min_balance,
+            pool_config.gas_sponsor,
         )
         .create_or_load_sub_clients(pool_config.n_clients, refresh_handle)
         .await?;
@@ -284,6 +309,8 @@ struct SubClientLoader<'a> {
     refiller: &'a Refiller,
     /// The minimum balance the sub-wallets should have, below which they are refilled at startup.
     min_balance: u64,
+    /// The sponsor paying for the gas of the sub-wallets, if any.
+    gas_sponsor: Option<GasSponsor>,
 }
 
 impl<'a> SubClientLoader<'a> {
@@ -294,6 +321,7 @@ impl<'a> SubClientLoader<'a> {
         gas_budget: Option<u64>,
         refiller: &'a Refiller,
         min_balance: u64,
+        gas_sponsor: Option<GasSponsor>,
     ) -> Self {
         Self {
             config,
@@ -302,6 +330,7 @@ impl<'a> SubClientLoader<'a> {
             gas_budget,
             refiller,
             min_balance,
+            gas_sponsor,
         }
     }
 
@@ -334,7 +363,7 @@ impl<'a> SubClientLoader<'a> {
 
         let sui_client = self
             .config
-            .new_contract_client(wallet, self.gas_budget)
+            .new_contract_client_with_sponsor(wallet, self.gas_budget, self.gas_sponsor.clone())
             .await?;
         // Merge existing coins to avoid fragmentation.
         sui_client.merge_coins().await?;
@@ -383,7 +412,9 @@ impl<'a> SubClientLoader<'a> {
             RetriableSuiClient::new_from_wallet(wallet, self.config.backoff_config().clone())
                 .await?;
 
-        if should_refill(&sui_client, address, None, min_balance).await {
+        if !self.refiller.refills_gas() {
+            tracing::debug!(%address, "sub-wallet gas is sponsored, skipping SUI refill");
+        } else if should_refill(&sui_client, address, None, min_balance).await {
             self.refiller.send_gas_request(address).await?;
         } else {
             tracing::debug!(%address, "sub-wallet has enough SUI, skipping refill");
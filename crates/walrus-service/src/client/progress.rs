@@ -0,0 +1,60 @@
+// Copyright (c) Walrus Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reporting of the progress of store operations.
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
+use utoipa::ToSchema;
+
+/// The stages a store operation goes through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum StoreStage {
+    /// The operation has not started yet.
+    Queued,
+    /// The blob is being encoded into sliver pairs and metadata.
+    Encoding,
+    /// The blob status is being checked, and the blob is being registered on Sui.
+    Registering,
+    /// The slivers are being uploaded to the storage nodes to collect the storage confirmations.
+    Uploading,
+    /// The certificate is being posted on Sui.
+    Certifying,
+}
+
+/// Handle used by the client to report the [`StoreStage`] of a store operation.
+///
+/// The default value does not report the progress anywhere.
+#[derive(Debug, Default)]
+pub struct StoreProgress {
+    sender: Option<watch::Sender<StoreStage>>,
+}
+
+impl StoreProgress {
+    /// Creates a new [`StoreProgress`] that does not report the progress.
+    pub fn noop() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new [`StoreProgress`], and returns the receiver on which the progress of the store
+    /// operation can be observed.
+    pub fn new() -> (Self, watch::Receiver<StoreStage>) {
+        let (sender, receiver) = watch::channel(StoreStage::Queued);
+        (
+            Self {
+                sender: Some(sender),
+            },
+            receiver,
+        )
+    }
+
+    /// Reports that the store operation has reached the given stage.
+    pub(crate) fn report(&self, stage: StoreStage) {
+        if let Some(sender) = self.sender.as_ref() {
+            // The receiver may have been dropped, in which case no one is interested in the
+            // progress anymore.
+            let _ = sender.send(stage);
+        }
+    }
+}
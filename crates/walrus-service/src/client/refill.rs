@@ -24,6 +24,8 @@ pub struct Refiller {
     wal_refill_size: u64,
     /// The minimum balance the wallet should have before refilling.
     min_balance: u64,
+    /// Whether to refill gas; not needed if the gas of the clients is paid by a sponsor.
+    refill_gas: bool,
 }
 
 impl Refiller {
@@ -39,9 +41,21 @@ impl Refiller {
             gas_refill_size,
             wal_refill_size,
             min_balance,
+            refill_gas: true,
         }
     }
 
+    /// Disables the refilling of gas, e.g., because the gas of the clients is paid by a sponsor.
+    pub fn without_gas_refills(mut self) -> Self {
+        self.refill_gas = false;
+        self
+    }
+
+    /// Returns `true` if the refiller refills gas.
+    pub fn refills_gas(&self) -> bool {
+        self.refill_gas
+    }
+
     /// Refills gas and WAL for the clients.
     pub fn refill_gas_and_wal(
         &self,
@@ -50,12 +64,14 @@ impl Refiller {
         metrics: Arc<ClientMetrics>,
         sui_client: RetriableSuiClient,
     ) -> RefillHandles {
-        let _gas_refill_handle = self.refill_gas(
-            addresses.clone(),
-            period,
-            metrics.clone(),
-            sui_client.clone(),
-        );
+        let _gas_refill_handle = self.refill_gas.then(|| {
+            self.refill_gas(
+                addresses.clone(),
+                period,
+                metrics.clone(),
+                sui_client.clone(),
+            )
+        });
         let _wal_refill_handle =
             self.refill_wal(addresses, period, metrics.clone(), sui_client.clone());
 
@@ -196,8 +212,8 @@ impl Refiller {
 /// Helper struct to hold the handles for the refiller tasks.
 #[derive(Debug)]
 pub struct RefillHandles {
-    /// The handle for the gas refill task.
-    pub _gas_refill_handle: JoinHandle<anyhow::Result<()>>,
+    /// The handle for the gas refill task, if gas is refilled.
+    pub _gas_refill_handle: Option<JoinHandle<anyhow::Result<()>>>,
     /// The handle for the WAL refill task.
     pub _wal_refill_handle: JoinHandle<anyhow::Result<()>>,
 }
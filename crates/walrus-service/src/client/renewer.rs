@@ -0,0 +1,646 @@
+// Copyright (c) Walrus Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Service that automatically extends blobs before they expire.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::{Context as _, Result};
+use prometheus::{
+    register_int_counter_vec_with_registry,
+    register_int_counter_with_registry,
+    register_int_gauge_with_registry,
+    IntCounter,
+    IntCounterVec,
+    IntGauge,
+    Registry,
+};
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+use sui_types::base_types::ObjectID;
+use tokio::time::MissedTickBehavior;
+use walrus_core::{BlobId, Epoch, EpochCount};
+use walrus_sui::{
+    client::{ExpirySelectionPolicy, ReadClient, SuiContractClient, TransactionCost},
+    types::{move_structs::SharedBlob, Blob},
+    utils::price_for_encoded_length,
+};
+
+/// The configuration of the [`BlobRenewer`].
+#[derive(Debug, Clone)]
+pub struct RenewerConfig {
+    /// Blobs are extended when they expire within this number of epochs.
+    pub within_epochs: EpochCount,
+    /// The number of epochs by which blobs are extended.
+    pub extend_by: EpochCount,
+    /// The maximum amount of FROST the renewer may spend on extending owned blobs.
+    ///
+    /// If `None`, the amount is not limited.
+    pub budget: Option<u64>,
+    /// The file in which the amounts spent by the renewer are persisted.
+    ///
+    /// If `None`, the amounts are only kept in memory, and the budget is reset on restart.
+    pub state_path: Option<PathBuf>,
+    /// Whether to watch the blobs owned by the wallet.
+    pub watch_owned: bool,
+    /// If non-empty, only the owned blobs that have all these attribute key-value pairs are
+    /// watched.
+    pub attributes: Vec<(String, String)>,
+    /// The object IDs of the shared blobs to watch.
+    pub shared_blobs: Vec<ObjectID>,
+    /// The interval between two consecutive checks.
+    pub interval: Duration,
+}
+
+/// The outcome of the renewal of a blob.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RenewalStatus {
+    /// The blob would be extended, but the renewer is running in dry-run mode.
+    Planned,
+    /// The blob has been extended.
+    Extended,
+    /// The blob was not extended, as the cost would exceed the remaining budget.
+    OverBudget,
+    /// The shared blob was not extended, as its funds do not cover the cost.
+    InsufficientSharedFunds,
+    /// The blob was not extended, as it is already stored for the maximum number of epochs.
+    AtMaxEpochsAhead,
+    /// The extension failed with the given error.
+    Failed(String),
+}
+
+/// A blob that is about to expire, and the result of its renewal.
+#[serde_as]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenewalCandidate {
+    /// The object ID of the blob, or of the shared blob wrapping it.
+    pub object_id: ObjectID,
+    /// The blob ID.
+    #[serde_as(as = "DisplayFromStr")]
+    pub blob_id: BlobId,
+    /// Whether the object is a shared blob.
+    pub shared: bool,
+    /// The current end epoch of the blob.
+    pub end_epoch: Epoch,
+    /// The number of epochs by which the blob is extended.
+    pub epochs_extended: EpochCount,
+    /// The estimated cost of the extension in FROST.
+    pub estimated_cost: u64,
+    /// The outcome of the renewal.
+    pub status: RenewalStatus,
+}
+
+/// The report of a single pass of the [`BlobRenewer`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenewalReport {
+    /// The epoch in which the check was performed.
+    pub current_epoch: Epoch,
+    /// The number of blobs that are watched.
+    pub watched_blobs: usize,
+    /// The blobs that expire within the configured number of epochs.
+    pub candidates: Vec<RenewalCandidate>,
+    /// The amount of FROST spent (or, in dry-run mode, that would be spent) in this pass.
+    ///
+    /// Outside of dry-run mode, this is the amount actually paid in the transactions.
+    pub spent: u64,
+    /// The amount of MIST spent on gas in this pass.
+    pub gas_spent: u64,
+    /// The remaining budget in FROST, if a budget is configured.
+    pub remaining_budget: Option<u64>,
+    /// Whether the report was produced in dry-run mode.
+    pub dry_run: bool,
+}
+
+/// Metrics of the [`BlobRenewer`].
+#[derive(Debug)]
+pub struct RenewerMetrics {
+    /// Number of blobs currently watched.
+    pub watched_blobs: IntGauge,
+    /// Number of blobs expiring within the configured number of epochs.
+    pub expiring_blobs: IntGauge,
+    /// Number of renewal attempts by outcome.
+    pub renewals: IntCounterVec,
+    /// Total amount of FROST spent on extensions of owned blobs.
+    pub spent_frost: IntCounter,
+    /// Total amount of MIST spent on gas.
+    pub spent_gas_mist: IntCounter,
+    /// The remaining budget in FROST, or -1 if no budget is configured.
+    pub remaining_budget_frost: IntGauge,
+}
+
+impl RenewerMetrics {
+    /// Creates a new instance of the renewer metrics from an existing registry.
+    pub fn new(registry: &Registry) -> Self {
+        Self {
+            watched_blobs: register_int_gauge_with_registry!(
+                "renewer_watched_blobs",
+                "Number of blobs currently watched by the renewer",
+                registry,
+            )
+            .expect("this is a valid metrics registration"),
+            expiring_blobs: register_int_gauge_with_registry!(
+                "renewer_expiring_blobs",
+                "Number of watched blobs expiring within the renewal window",
+                registry,
+            )
+            .expect("this is a valid metrics registration"),
+            renewals: register_int_counter_vec_with_registry!(
+                "renewer_renewals_total",
+                "Number of blob renewals by outcome",
+                &["status"],
+                registry,
+            )
+            .expect("this is a valid metrics registration"),
+            spent_frost: register_int_counter_with_registry!(
+                "renewer_spent_frost_total",
+                "Total amount of FROST spent on extending owned blobs",
+                registry,
+            )
+            .expect("this is a valid metrics registration"),
+            spent_gas_mist: register_int_counter_with_registry!(
+                "renewer_spent_gas_mist_total",
+                "Total amount of MIST spent on gas for extending blobs",
+                registry,
+            )
+            .expect("this is a valid metrics registration"),
+            remaining_budget_frost: register_int_gauge_with_registry!(
+                "renewer_remaining_budget_frost",
+                "Remaining renewal budget in FROST, -1 if unlimited",
+                registry,
+            )
+            .expect("this is a valid metrics registration"),
+        }
+    }
+
+    fn observe_report(&self, report: &RenewalReport) {
+        self.watched_blobs
+            .set(i64::try_from(report.watched_blobs).unwrap_or(i64::MAX));
+        self.expiring_blobs
+            .set(i64::try_from(report.candidates.len()).unwrap_or(i64::MAX));
+        for candidate in &report.candidates {
+            walrus_utils::with_label!(self.renewals, candidate.status.label()).inc();
+        }
+        self.remaining_budget_frost.set(
+            report
+                .remaining_budget
+                .map_or(-1, |budget| i64::try_from(budget).unwrap_or(i64::MAX)),
+        );
+    }
+}
+
+impl RenewalStatus {
+    fn label(&self) -> &'static str {
+        match self {
+            RenewalStatus::Planned => "planned",
+            RenewalStatus::Extended => "extended",
+            RenewalStatus::OverBudget => "over_budget",
+            RenewalStatus::InsufficientSharedFunds => "insufficient_shared_funds",
+            RenewalStatus::AtMaxEpochsAhead => "at_max_epochs_ahead",
+            RenewalStatus::Failed(_) => "failed",
+        }
+    }
+}
+
+/// The amounts spent by the renewer, persisted across restarts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RenewerSpending {
+    /// The amount of FROST paid for extensions.
+    wal: u64,
+    /// The amount of MIST paid for gas.
+    gas: u64,
+}
+
+/// Keeps track of the amounts spent by the renewer and of the remaining budget.
+#[derive(Debug)]
+struct RenewalBudget {
+    /// The maximum amount of FROST that may be spent, if any.
+    limit: Option<u64>,
+    spent: RenewerSpending,
+    /// The file in which the spent amounts are persisted, if any.
+    path: Option<PathBuf>,
+}
+
+impl RenewalBudget {
+    /// Creates a new budget, loading the amounts spent so far from `path` if it exists.
+    fn load(limit: Option<u64>, path: Option<PathBuf>) -> Result<Self> {
+        let spent = match path.as_deref() {
+            Some(path) if path.exists() => {
+                let bytes = fs::read(path).with_context(|| {
+                    format!("unable to read the renewer state '{}'", path.display())
+                })?;
+                serde_json::from_slice(&bytes).with_context(|| {
+                    format!("unable to parse the renewer state '{}'", path.display())
+                })?
+            }
+            _ => RenewerSpending::default(),
+        };
+        Ok(Self { limit, spent, path })
+    }
+
+    /// Returns the remaining budget, taking into account the `planned` amount that has not been
+    /// spent yet.
+    fn remaining(&self, planned: u64) -> Option<u64> {
+        self.limit
+            .map(|limit| limit.saturating_sub(self.spent.wal.saturating_add(planned)))
+    }
+
+    /// Charges the actual cost of a transaction to the budget, and persists the new amounts.
+    async fn charge(&mut self, cost: TransactionCost) {
+        self.spent.wal = self.spent.wal.saturating_add(cost.wal_cost);
+        self.spent.gas = self.spent.gas.saturating_add(cost.gas_cost);
+
+        let Some(path) = self.path.clone() else {
+            return;
+        };
+        let spent = self.spent;
+        let result = tokio::task::spawn_blocking(move || save_spending(&path, &spent))
+            .await
+            .map_err(anyhow::Error::from)
+            .and_then(|result| result);
+        if let Err(error) = result {
+            tracing::error!(?error, "failed to persist the amounts spent by the renewer");
+        }
+    }
+}
+
+/// Writes the spent amounts to `path`, replacing the file atomically.
+fn save_spending(path: &Path, spent: &RenewerSpending) -> Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, serde_json::to_vec(spent)?)?;
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+/// Returns the number of epochs by which a blob ending in `end_epoch` is extended, or `None` if
+/// the blob does not expire within the configured number of epochs.
+///
+/// The end epoch cannot be pushed beyond the maximum number of epochs ahead, so the returned
+/// number of epochs may be 0.
+fn epochs_to_extend(
+    config: &RenewerConfig,
+    end_epoch: Epoch,
+    current_epoch: Epoch,
+    max_epochs_ahead: EpochCount,
+) -> Option<EpochCount> {
+    if end_epoch > current_epoch + config.within_epochs {
+        return None;
+    }
+    Some(
+        config
+            .extend_by
+            .min((current_epoch + max_epochs_ahead).saturating_sub(end_epoch)),
+    )
+}
+
+/// Returns the status of a renewal that cannot be performed, or `None` if the blob can be
+/// extended.
+///
+/// `shared_funds` are the funds of a shared blob, and `None` for owned blobs; the
+/// `remaining_budget` only applies to owned blobs.
+fn blocked_renewal_status(
+    epochs_extended: EpochCount,
+    estimated_cost: u64,
+    shared_funds: Option<u64>,
+    remaining_budget: Option<u64>,
+) -> Option<RenewalStatus> {
+    if epochs_extended == 0 {
+        Some(RenewalStatus::AtMaxEpochsAhead)
+    } else if let Some(funds) = shared_funds {
+        (funds < estimated_cost).then_some(RenewalStatus::InsufficientSharedFunds)
+    } else {
+        remaining_budget
+            .is_some_and(|budget| budget < estimated_cost)
+            .then_some(RenewalStatus::OverBudget)
+    }
+}
+
+/// A watched blob, either owned by the wallet or shared.
+#[derive(Debug)]
+struct WatchedBlob {
+    object_id: ObjectID,
+    blob: Blob,
+    /// The funds of the shared blob, `None` for owned blobs.
+    shared_funds: Option<u64>,
+}
+
+/// Periodically extends the watched blobs that are about to expire.
+#[derive(Debug)]
+pub struct BlobRenewer {
+    contract_client: SuiContractClient,
+    config: RenewerConfig,
+    metrics: Option<RenewerMetrics>,
+    budget: RenewalBudget,
+}
+
+impl BlobRenewer {
+    /// Creates a new [`BlobRenewer`].
+    ///
+    /// The amounts spent so far are loaded from the state file of the config, if it exists. If a
+    /// registry is provided, the metrics of the renewer are registered with it.
+    pub fn new(
+        contract_client: SuiContractClient,
+        config: RenewerConfig,
+        registry: Option<&Registry>,
+    ) -> Result<Self> {
+        let budget = RenewalBudget::load(config.budget, config.state_path.clone())?;
+        Ok(Self {
+            contract_client,
+            config,
+            metrics: registry.map(RenewerMetrics::new),
+            budget,
+        })
+    }
+    /// Runs the renewer until an unrecoverable error occurs.
+    ///
+    /// Errors in individual passes are logged, and the renewer retries at the next interval.
+    pub async fn run(mut self) -> Result<()> {
+        let mut interval = tokio::time::interval(self.config.interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            match self.renew_expiring_blobs(false).await {
+                Ok(report) => tracing::info!(
+                    current_epoch = report.current_epoch,
+                    watched_blobs = report.watched_blobs,
+                    expiring_blobs = report.candidates.len(),
+                    spent = report.spent,
+                    remaining_budget = ?report.remaining_budget,
+                    "completed blob renewal pass"
+                ),
+                Err(error) => tracing::warn!(?error, "blob renewal pass failed"),
+            }
+        }
+    }
+
+    /// Checks the watched blobs once, and extends the ones that are about to expire.
+    ///
+    /// If `dry_run` is set, no blob is extended, and the returned report contains the extensions
+    /// that would be performed.
+    pub async fn renew_expiring_blobs(&mut self, dry_run: bool) -> Result<RenewalReport> {
+        let read_client = self.contract_client.read_client();
+        let current_epoch = read_client.current_committee().await?.epoch;
+        let max_epochs_ahead = read_client
+            .fixed_system_parameters()
+            .await?
+            .max_epochs_ahead;
+        let price_per_unit_size = read_client.storage_price_per_unit_size().await?;
+
+        let watched = self.watched_blobs().await?;
+        let watched_blobs = watched.len();
+        let mut candidates = vec![];
+        let mut spent = 0;
+        let mut gas_spent = 0;
+
+        for watched_blob in watched {
+            let end_epoch = watched_blob.blob.storage.end_epoch;
+            let Some(epochs_extended) =
+                epochs_to_extend(&self.config, end_epoch, current_epoch, max_epochs_ahead)
+            else {
+                continue;
+            };
+            let estimated_cost = price_for_encoded_length(
+                watched_blob.blob.storage.storage_size,
+                price_per_unit_size,
+                epochs_extended,
+            );
+            let shared = watched_blob.shared_funds.is_some();
+            let remaining_budget = self.budget.remaining(if dry_run { spent } else { 0 });
+
+            let status = if let Some(status) = blocked_renewal_status(
+                epochs_extended,
+                estimated_cost,
+                watched_blob.shared_funds,
+                remaining_budget,
+            ) {
+                status
+            } else if dry_run {
+                if !shared {
+                    spent += estimated_cost;
+                }
+                RenewalStatus::Planned
+            } else {
+                match self
+                    .extend(watched_blob.object_id, shared, epochs_extended)
+                    .await
+                {
+                    Ok(cost) => {
+                        spent += cost.wal_cost;
+                        gas_spent += cost.gas_cost;
+                        self.budget.charge(cost).await;
+                        if let Some(metrics) = self.metrics.as_ref() {
+                            metrics.spent_frost.inc_by(cost.wal_cost);
+                            metrics.spent_gas_mist.inc_by(cost.gas_cost);
+                        }
+                        RenewalStatus::Extended
+                    }
+                    Err(error) => {
+                        tracing::warn!(
+                            object_id = %watched_blob.object_id,
+                            ?error,
+                            "failed to extend blob"
+                        );
+                        RenewalStatus::Failed(error.to_string())
+                    }
+                }
+            };
+
+            candidates.push(RenewalCandidate {
+                object_id: watched_blob.object_id,
+                blob_id: watched_blob.blob.blob_id,
+                shared,
+                end_epoch,
+                epochs_extended,
+                estimated_cost,
+                status,
+            });
+        }
+
+        let report = RenewalReport {
+            current_epoch,
+            watched_blobs,
+            candidates,
+            spent,
+            gas_spent,
+            remaining_budget: self.budget.remaining(if dry_run { spent } else { 0 }),
+            dry_run,
+        };
+        if let Some(metrics) = self.metrics.as_ref() {
+            metrics.observe_report(&report);
+        }
+        Ok(report)
+    }
+
+    /// Extends the blob, and returns the actual cost of the transaction.
+    async fn extend(
+        &self,
+        object_id: ObjectID,
+        shared: bool,
+        epochs_extended: EpochCount,
+    ) -> Result<TransactionCost> {
+        let cost = if shared {
+            self.contract_client
+                .extend_shared_blob(object_id, epochs_extended)
+                .await?
+        } else {
+            self.contract_client
+                .extend_blob(object_id, epochs_extended)
+                .await?
+        };
+        tracing::info!(
+            %object_id,
+            shared,
+            epochs_extended,
+            wal_cost = cost.wal_cost,
+            gas_cost = cost.gas_cost,
+            "extended blob"
+        );
+        Ok(cost)
+    }
+
+    /// Returns the currently valid blobs watched by the renewer.
+    async fn watched_blobs(&self) -> Result<Vec<WatchedBlob>> {
+        let mut watched = vec![];
+
+        if self.config.watch_owned {
+            for blob in self
+                .contract_client
+                .owned_blobs(None, ExpirySelectionPolicy::Valid)
+                .await?
+            {
+                if !self.config.attributes.is_empty() && !self.matches_attribute(&blob.id).await? {
+                    continue;
+                }
+                watched.push(WatchedBlob {
+                    object_id: blob.id,
+                    blob,
+                    shared_funds: None,
+                });
+            }
+        }
+
+        for shared_blob_id in &self.config.shared_blobs {
+            let shared_blob = self
+                .contract_client
+                .sui_client()
+                .get_sui_object::<SharedBlob>(*shared_blob_id)
+                .await?;
+            watched.push(WatchedBlob {
+                object_id: shared_blob.id,
+                blob: shared_blob.blob,
+                shared_funds: Some(shared_blob.funds),
+            });
+        }
+
+        Ok(watched)
+    }
+
+    async fn matches_attribute(&self, blob_object_id: &ObjectID) -> Result<bool> {
+        let Some(attribute) = self
+            .contract_client
+            .read_client()
+            .get_blob_attribute(blob_object_id)
+            .await?
+        else {
+            return Ok(false);
+        };
+        Ok(self.config.attributes.iter().all(|(key, value)| {
+            attribute
+                .iter()
+                .any(|(attr_key, attr_value)| attr_key == key && attr_value == value)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use walrus_test_utils::Result as TestResult;
+
+    use super::*;
+
+    fn config() -> RenewerConfig {
+        RenewerConfig {
+            within_epochs: 2,
+            extend_by: 5,
+            budget: Some(1_000),
+            state_path: None,
+            watch_owned: true,
+            attributes: vec![],
+            shared_blobs: vec![],
+            interval: Duration::from_secs(60),
+        }
+    }
+
+    #[test]
+    fn only_expiring_blobs_are_selected() {
+        let config = config();
+        assert_eq!(epochs_to_extend(&config, 13, 10, 50), None);
+        assert_eq!(epochs_to_extend(&config, 12, 10, 50), Some(5));
+        assert_eq!(epochs_to_extend(&config, 10, 10, 50), Some(5));
+    }
+
+    #[test]
+    fn extension_is_capped_at_max_epochs_ahead() {
+        let config = config();
+        assert_eq!(epochs_to_extend(&config, 12, 10, 4), Some(2));
+        assert_eq!(epochs_to_extend(&config, 12, 10, 2), Some(0));
+    }
+
+    #[test]
+    fn blocked_renewals() {
+        assert_eq!(
+            blocked_renewal_status(0, 10, None, None),
+            Some(RenewalStatus::AtMaxEpochsAhead)
+        );
+        assert_eq!(
+            blocked_renewal_status(1, 10, Some(9), None),
+            Some(RenewalStatus::InsufficientSharedFunds)
+        );
+        // The budget does not apply to shared blobs.
+        assert_eq!(blocked_renewal_status(1, 10, Some(10), Some(0)), None);
+        assert_eq!(
+            blocked_renewal_status(1, 10, None, Some(9)),
+            Some(RenewalStatus::OverBudget)
+        );
+        assert_eq!(blocked_renewal_status(1, 10, None, Some(10)), None);
+        assert_eq!(blocked_renewal_status(1, 10, None, None), None);
+    }
+
+    #[tokio::test]
+    async fn budget_is_charged_and_persisted() -> TestResult {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("renewer.json");
+
+        let mut budget = RenewalBudget::load(Some(1_000), Some(path.clone()))?;
+        assert_eq!(budget.remaining(0), Some(1_000));
+        assert_eq!(budget.remaining(400), Some(600));
+        budget
+            .charge(TransactionCost {
+                gas_cost: 7,
+                wal_cost: 300,
+            })
+            .await;
+        assert_eq!(budget.remaining(0), Some(700));
+        assert_eq!(budget.remaining(800), Some(0));
+
+        let reloaded = RenewalBudget::load(Some(1_000), Some(path))?;
+        assert_eq!(reloaded.spent, RenewerSpending { wal: 300, gas: 7 });
+        assert_eq!(reloaded.remaining(0), Some(700));
+        Ok(())
+    }
+
+    #[test]
+    fn unlimited_budget_has_no_remaining_amount() -> TestResult {
+        let budget = RenewalBudget::load(None, None)?;
+        assert_eq!(budget.remaining(u64::MAX), None);
+        Ok(())
+    }
+}
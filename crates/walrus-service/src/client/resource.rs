@@ -5,6 +5,7 @@
 
 use anyhow::anyhow;
 use serde::{Deserialize, Serialize};
+use sui_types::base_types::ObjectID;
 use tracing::Level;
 use utoipa::ToSchema;
 use walrus_core::{
@@ -15,8 +16,8 @@ use walrus_core::{
 };
 use walrus_sdk::api::BlobStatus;
 use walrus_sui::{
-    client::{BlobPersistence, ExpirySelectionPolicy, SuiContractClient},
-    types::Blob,
+    client::{BlobPersistence, ContractClient, ExpirySelectionPolicy},
+    types::{Blob, StorageResource},
     utils::price_for_encoded_length,
 };
 
@@ -171,20 +172,33 @@ pub enum StoreOp {
 
 /// Manages the storage and blob resources in the Wallet on behalf of the client.
 #[derive(Debug)]
-pub struct ResourceManager<'a> {
-    sui_client: &'a SuiContractClient,
+pub struct ResourceManager<'a, C> {
+    sui_client: &'a C,
     write_committee_epoch: Epoch,
+    storage_object: Option<ObjectID>,
 }
 
-impl<'a> ResourceManager<'a> {
+impl<'a, C: ContractClient> ResourceManager<'a, C> {
     /// Creates a new resource manager.
-    pub fn new(sui_client: &'a SuiContractClient, write_committee_epoch: Epoch) -> Self {
+    pub fn new(sui_client: &'a C, write_committee_epoch: Epoch) -> Self {
         Self {
             sui_client,
             write_committee_epoch,
+            storage_object: None,
         }
     }
 
+    /// Pins the storage resource used to register new blobs.
+    ///
+    /// If set, blobs that are not yet registered in the wallet are registered with the given
+    /// storage resource instead of selecting or purchasing one. The storage resource must be owned
+    /// by the wallet and cover the encoded size and the requested epochs; only a single blob can
+    /// be stored with it.
+    pub fn with_storage_object(mut self, storage_object: Option<ObjectID>) -> Self {
+        self.storage_object = storage_object;
+        self
+    }
+
     /// Returns a list of appropriate store operation for the given blobs.
     ///
     /// The function considers the requirements given to the store operation (epochs ahead,
@@ -287,7 +301,18 @@ impl<'a> ResourceManager<'a> {
                 })
                 .collect();
 
-        if store_when.is_ignore_resources() {
+        if let Some(storage_object) = self.storage_object {
+            if metadata_list.len() > 1 {
+                return Err(ClientErrorKind::Other(
+                    anyhow!("a pinned storage resource can only be used to store a single blob")
+                        .into(),
+                )
+                .into());
+            }
+            tracing::debug!(%storage_object, "using the pinned storage resource");
+        }
+
+        if store_when.is_ignore_resources() && self.storage_object.is_none() {
             tracing::debug!(
                 "ignoring existing resources and creating a new registration from scratch"
             );
@@ -357,6 +382,18 @@ impl<'a> ResourceManager<'a> {
                 )
                 .await?
             {
+                if let Some(storage_object) = self.storage_object {
+                    return Err(ClientErrorKind::Other(
+                        anyhow!(
+                            "the blob {} is already registered in the wallet with object {}; \
+                            the storage resource {storage_object} cannot be used to store it",
+                            blob.blob_id,
+                            blob.id
+                        )
+                        .into(),
+                    )
+                    .into());
+                }
                 tracing::debug!(
                     end_epoch=%blob.storage.end_epoch,
                     blob_id=%blob.blob_id,
@@ -395,8 +432,7 @@ impl<'a> ResourceManager<'a> {
                     ));
                 }
             } else if let Some(storage_resource) = self
-                .sui_client
-                .owned_storage_for_size_and_epoch(
+                .select_storage(
                     *encoded_length,
                     epochs_ahead + self.write_committee_epoch,
                     &excluded,
@@ -459,6 +495,44 @@ impl<'a> ResourceManager<'a> {
         Ok(results)
     }
 
+    /// Selects the storage resource to register a blob of the given encoded length until
+    /// `end_epoch`.
+    ///
+    /// Returns the pinned storage resource if any, checking that it is suitable, and otherwise the
+    /// closest-matching storage resource owned by the wallet.
+    async fn select_storage(
+        &self,
+        encoded_length: u64,
+        end_epoch: Epoch,
+        excluded: &[ObjectID],
+    ) -> ClientResult<Option<StorageResource>> {
+        let Some(storage_object) = self.storage_object else {
+            return Ok(self
+                .sui_client
+                .owned_storage_for_size_and_epoch(encoded_length, end_epoch, excluded)
+                .await?);
+        };
+
+        let storage_resource = self
+            .sui_client
+            .owned_storage_by_id(storage_object)
+            .await?
+            .ok_or_else(|| {
+                ClientError::from(ClientErrorKind::Other(
+                    anyhow!("the storage resource {storage_object} is not owned by the wallet")
+                        .into(),
+                ))
+            })?;
+        check_pinned_storage(
+            &storage_resource,
+            encoded_length,
+            self.write_committee_epoch,
+            end_epoch,
+        )
+        .map_err(|error| ClientError::from(ClientErrorKind::Other(error.into())))?;
+        Ok(Some(storage_resource))
+    }
+
     async fn reserve_and_register_blob_op(
         &self,
         encoded_lengths: &[u64],
@@ -569,6 +643,40 @@ impl<'a> ResourceManager<'a> {
     }
 }
 
+/// Checks that the pinned storage resource can be used to register a blob of the given encoded
+/// length in `current_epoch` until `end_epoch`.
+fn check_pinned_storage(
+    storage_resource: &StorageResource,
+    encoded_length: u64,
+    current_epoch: Epoch,
+    end_epoch: Epoch,
+) -> anyhow::Result<()> {
+    let storage_object = storage_resource.id;
+    if storage_resource.storage_size < encoded_length {
+        anyhow::bail!(
+            "the storage resource {storage_object} is too small: {} < {} bytes",
+            storage_resource.storage_size,
+            encoded_length
+        );
+    }
+    if storage_resource.start_epoch > current_epoch {
+        anyhow::bail!(
+            "the storage resource {storage_object} is not valid yet: it starts at epoch {}, the \
+            current epoch is {}",
+            storage_resource.start_epoch,
+            current_epoch
+        );
+    }
+    if storage_resource.end_epoch < end_epoch {
+        anyhow::bail!(
+            "the storage resource {storage_object} expires too early: epoch {} < {}",
+            storage_resource.end_epoch,
+            end_epoch
+        );
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use walrus_sui::utils::BYTES_PER_UNIT_SIZE;
@@ -609,4 +717,40 @@ mod tests {
         );
         assert_eq!(computation.operation_cost(&registration), 0);
     }
+
+    fn storage_resource(
+        start_epoch: Epoch,
+        end_epoch: Epoch,
+        storage_size: u64,
+    ) -> StorageResource {
+        StorageResource {
+            id: ObjectID::random(),
+            start_epoch,
+            end_epoch,
+            storage_size,
+        }
+    }
+
+    param_test! {
+        test_check_pinned_storage: [
+            exact_fit: (storage_resource(1, 5, 100), 100, 5, true),
+            larger_and_longer: (storage_resource(0, 10, 200), 100, 5, true),
+            too_small: (storage_resource(1, 5, 99), 100, 5, false),
+            expires_too_early: (storage_resource(1, 4, 100), 100, 5, false),
+            starts_in_the_future: (storage_resource(2, 5, 100), 100, 5, false),
+        ]
+    }
+    fn test_check_pinned_storage(
+        storage_resource: StorageResource,
+        encoded_length: u64,
+        end_epoch: Epoch,
+        is_suitable: bool,
+    ) {
+        let current_epoch = 1;
+        assert_eq!(
+            check_pinned_storage(&storage_resource, encoded_length, current_epoch, end_epoch)
+                .is_ok(),
+            is_suitable
+        );
+    }
 }
@@ -13,12 +13,17 @@ use std::{
 
 use anyhow;
 use chrono::{DateTime, Utc};
+use fastcrypto::encoding::Encoding as _;
 use futures::{stream, StreamExt as _};
 use serde::{Deserialize, Serialize};
 use serde_with::{base64::Base64, serde_as, DisplayFromStr};
+use sui_sdk::rpc_types::{ObjectChange, SuiExecutionStatus, SuiTransactionBlockEffectsAPI as _};
 use sui_types::{
     base_types::{ObjectID, SuiAddress},
+    digests::TransactionDigest,
     event::EventID,
+    gas_coin::GAS,
+    transaction::{TransactionData, TransactionDataAPI as _},
 };
 use utoipa::ToSchema;
 use walrus_core::{
@@ -43,13 +48,15 @@ use walrus_core::{
 };
 use walrus_sdk::api::{BlobStatus, ServiceHealthInfo};
 use walrus_sui::{
-    client::ReadClient,
+    client::{ReadClient, TransactionDryRun},
     types::{
         move_structs::{Blob, BlobAttribute, EpochState},
+        BlobEvent,
         Committee,
         NetworkAddress,
         StakedWal,
         StorageNode,
+        StorageResource,
     },
     utils::{price_for_encoded_length, storage_units_from_size, BYTES_PER_UNIT_SIZE},
     EventIdSchema,
@@ -59,6 +66,7 @@ use walrus_sui::{
 use super::{
     cli::{BlobIdDecimal, BlobIdentity, HumanReadableBytes},
     communication::NodeCommunicationFactory,
+    daemon::api_keys::ApiKeyEntry,
     resource::RegisterBlobOp,
 };
 use crate::client::cli::{format_event_id, HealthSortBy, HumanReadableFrost, NodeSortBy, SortBy};
@@ -721,6 +729,16 @@ pub struct WalletOutput {
     pub wallet_address: SuiAddress,
 }
 
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+/// The output of the `walrus generate-api-key` command.
+pub struct GenerateApiKeyOutput {
+    /// The generated API key.
+    pub key: String,
+    /// The entry to add to the API keys file of the publisher.
+    pub entry: ApiKeyEntry,
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 /// The output of the `walrus get-wal` command.
@@ -763,6 +781,326 @@ pub struct ExtendBlobOutput {
     pub epochs_extended: EpochCount,
 }
 
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+/// The output of the `walrus extend --expiring-before` command.
+pub struct ExtendBlobsOutput {
+    /// The object IDs of the extended blobs.
+    pub extended: Vec<ObjectID>,
+    /// The object IDs of the matching blobs that could not be extended, as they would exceed the
+    /// maximum number of epochs ahead.
+    pub skipped: Vec<ObjectID>,
+    /// The number of epochs the blobs were extended by.
+    pub epochs_extended: EpochCount,
+    /// The total cost of the extensions, in FROST.
+    pub total_cost: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+/// The output of the `walrus blob-history` command.
+pub struct BlobHistoryOutput {
+    /// The blob ID or object ID whose history was requested.
+    pub blob: String,
+    /// The source of the events, either the Sui RPC or the path of the event database.
+    pub source: String,
+    /// The events for the blob, in chronological order.
+    pub entries: Vec<BlobHistoryEntry>,
+}
+
+impl BlobHistoryOutput {
+    /// Creates a new output from the blob events.
+    pub fn new(blob: String, source: String, events: &[BlobEvent]) -> Self {
+        Self {
+            blob,
+            source,
+            entries: events.iter().map(BlobHistoryEntry::from).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+/// The kind of an entry in the history of a blob.
+pub enum BlobHistoryEventKind {
+    /// The blob was registered.
+    Registered,
+    /// The blob was certified.
+    Certified,
+    /// The lifetime of the blob was extended.
+    Extended,
+    /// The blob was deleted.
+    Deleted,
+    /// The blob ID was marked as invalid.
+    Invalidated,
+    /// The blob was deleted from the storage nodes, as it is on a deny list.
+    DenyListDeleted,
+}
+
+impl Display for BlobHistoryEventKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match self {
+            Self::Registered => "registered",
+            Self::Certified => "certified",
+            Self::Extended => "extended",
+            Self::Deleted => "deleted",
+            Self::Invalidated => "invalidated",
+            Self::DenyListDeleted => "deny-list deleted",
+        };
+        write!(f, "{kind}")
+    }
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+/// A single entry in the history of a blob.
+pub struct BlobHistoryEntry {
+    /// The epoch in which the event was emitted.
+    pub epoch: Epoch,
+    /// The kind of the event.
+    pub kind: BlobHistoryEventKind,
+    /// The blob ID.
+    #[serde_as(as = "DisplayFromStr")]
+    pub blob_id: BlobId,
+    /// The object ID of the blob object, if the event concerns a blob object.
+    pub object_id: Option<ObjectID>,
+    /// The end epoch of the blob after the event, if applicable.
+    pub end_epoch: Option<Epoch>,
+    /// The ID of the event, including the digest of the transaction that emitted it.
+    pub event_id: EventID,
+}
+
+impl From<&BlobEvent> for BlobHistoryEntry {
+    fn from(event: &BlobEvent) -> Self {
+        let (kind, end_epoch) = match event {
+            BlobEvent::Registered(event) => {
+                (BlobHistoryEventKind::Registered, Some(event.end_epoch))
+            }
+            BlobEvent::Certified(event) if event.is_extension => {
+                (BlobHistoryEventKind::Extended, Some(event.end_epoch))
+            }
+            BlobEvent::Certified(event) => (BlobHistoryEventKind::Certified, Some(event.end_epoch)),
+            BlobEvent::Deleted(_) => (BlobHistoryEventKind::Deleted, None),
+            BlobEvent::InvalidBlobID(_) => (BlobHistoryEventKind::Invalidated, None),
+            BlobEvent::DenyListBlobDeleted(_) => (BlobHistoryEventKind::DenyListDeleted, None),
+        };
+        Self {
+            epoch: event.event_epoch(),
+            kind,
+            blob_id: event.blob_id(),
+            object_id: event.object_id(),
+            end_epoch,
+            event_id: event.event_id(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+/// The output of the `walrus storage split` command.
+pub struct SplitStorageOutput {
+    /// The original storage resource, after the split.
+    pub original: StorageResource,
+    /// The storage resource created by the split.
+    pub created: StorageResource,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+/// The output of the `walrus storage fuse` command.
+pub struct FuseStorageOutput {
+    /// The fused storage resource.
+    pub fused: StorageResource,
+    /// The IDs of the storage resources that were fused into it, and no longer exist.
+    pub consumed: Vec<ObjectID>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+/// The output of the `walrus storage transfer` command.
+pub struct TransferStorageOutput {
+    /// The IDs of the transferred storage resources.
+    pub storage_objects: Vec<ObjectID>,
+    /// The address to which the storage resources were transferred.
+    pub recipient: SuiAddress,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+/// The output of the `walrus storage buy` command.
+pub struct BuyStorageOutput {
+    /// The purchased storage resource.
+    pub storage_resource: StorageResource,
+    /// The price paid for the storage resource, in FROST.
+    pub cost: u64,
+}
+
+/// A transaction that was emitted for external signing.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnsignedTransaction {
+    /// The digest of the transaction.
+    pub digest: TransactionDigest,
+    /// The sender of the transaction, who needs to sign it.
+    pub sender: SuiAddress,
+    /// The gas budget of the transaction (in MIST).
+    pub gas_budget: u64,
+    /// The base64-encoded BCS bytes of the transaction data.
+    pub tx_bytes: String,
+}
+
+impl UnsignedTransaction {
+    /// Creates the output for the given transaction data.
+    pub fn new(transaction: &TransactionData) -> anyhow::Result<Self> {
+        Ok(Self {
+            digest: transaction.digest(),
+            sender: transaction.sender(),
+            gas_budget: transaction.gas_budget(),
+            tx_bytes: fastcrypto::encoding::Base64::encode(bcs::to_bytes(transaction)?),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+/// The output of a command run with `--unsigned-tx-out`.
+pub struct UnsignedTransactionsOutput {
+    /// The file to which the transactions were written.
+    pub path: PathBuf,
+    /// The transactions that need to be signed and submitted.
+    pub transactions: Vec<UnsignedTransaction>,
+}
+
+/// A change to an object that a dry-run transaction would make.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DryRunObjectChange {
+    /// The kind of change (e.g., `created`, `mutated`, or `deleted`).
+    pub kind: &'static str,
+    /// The ID of the changed object or published package.
+    pub object_id: ObjectID,
+    /// The type of the changed object, if it is not a package.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub object_type: Option<String>,
+}
+
+impl From<&ObjectChange> for DryRunObjectChange {
+    fn from(change: &ObjectChange) -> Self {
+        let (kind, object_type) = match change {
+            ObjectChange::Published { .. } => ("published", None),
+            ObjectChange::Transferred { object_type, .. } => ("transferred", Some(object_type)),
+            ObjectChange::Mutated { object_type, .. } => ("mutated", Some(object_type)),
+            ObjectChange::Deleted { object_type, .. } => ("deleted", Some(object_type)),
+            ObjectChange::Wrapped { object_type, .. } => ("wrapped", Some(object_type)),
+            ObjectChange::Created { object_type, .. } => ("created", Some(object_type)),
+        };
+        Self {
+            kind,
+            object_id: change.object_id(),
+            object_type: object_type.map(|object_type| object_type.to_canonical_string(true)),
+        }
+    }
+}
+
+/// The result of dry-running a transaction instead of executing it.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionDryRunOutput {
+    /// The digest of the transaction.
+    pub digest: TransactionDigest,
+    /// The sender of the transaction.
+    pub sender: SuiAddress,
+    /// The gas budget of the transaction (in MIST).
+    pub gas_budget: u64,
+    /// The error the transaction would abort with, if it would fail.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The computation cost of the transaction (in MIST).
+    pub computation_cost: u64,
+    /// The storage cost of the transaction (in MIST).
+    pub storage_cost: u64,
+    /// The storage rebate of the transaction (in MIST).
+    pub storage_rebate: u64,
+    /// The part of the storage cost that is not refundable (in MIST).
+    pub non_refundable_storage_fee: u64,
+    /// The net gas cost of the transaction (in MIST); negative if the rebate exceeds the costs.
+    pub net_gas_cost: i64,
+    /// The change of the sender's SUI balance, including gas (in MIST).
+    pub sui_balance_change: i128,
+    /// The change of the sender's WAL balance (in FROST).
+    pub wal_balance_change: i128,
+    /// The objects the transaction would create, modify, or delete.
+    pub object_changes: Vec<DryRunObjectChange>,
+}
+
+impl TransactionDryRunOutput {
+    /// Creates the output for the given dry-run result.
+    pub fn new(dry_run: &TransactionDryRun) -> Self {
+        let transaction = &dry_run.transaction;
+        let response = &dry_run.response;
+        let sender = transaction.sender();
+        let gas_summary = response.effects.gas_cost_summary();
+        let error = match response.effects.status() {
+            SuiExecutionStatus::Success => None,
+            SuiExecutionStatus::Failure { error } => Some(error.clone()),
+        };
+
+        let sui_type = GAS::type_tag();
+        let mut sui_balance_change = 0;
+        let mut wal_balance_change = 0;
+        for change in response.balance_changes.iter().filter(|change| {
+            change
+                .owner
+                .get_address_owner_address()
+                .is_ok_and(|owner| owner == sender)
+        }) {
+            if change.coin_type == sui_type {
+                sui_balance_change += change.amount;
+            } else if change.coin_type == dry_run.wal_type {
+                wal_balance_change += change.amount;
+            }
+        }
+
+        Self {
+            digest: transaction.digest(),
+            sender,
+            gas_budget: transaction.gas_budget(),
+            error,
+            computation_cost: gas_summary.computation_cost,
+            storage_cost: gas_summary.storage_cost,
+            storage_rebate: gas_summary.storage_rebate,
+            non_refundable_storage_fee: gas_summary.non_refundable_storage_fee,
+            net_gas_cost: gas_summary.net_gas_usage(),
+            sui_balance_change,
+            wal_balance_change,
+            object_changes: response
+                .object_changes
+                .iter()
+                .map(DryRunObjectChange::from)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+/// The output of a command run with `--dry-run`.
+pub struct DryRunTransactionsOutput {
+    /// The transactions that were dry-run instead of being executed.
+    pub transactions: Vec<TransactionDryRunOutput>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+/// The output of the `walrus submit-signed` command.
+pub struct SubmitSignedOutput {
+    /// The digest of the executed transaction.
+    pub digest: TransactionDigest,
+    /// The IDs of the objects created by the transaction.
+    pub created_objects: Vec<ObjectID>,
+}
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 /// The health information of a storage node.
@@ -2,31 +2,288 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::{
-    collections::HashSet,
-    path::PathBuf,
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
     sync::{Arc, RwLock},
     time::Duration,
 };
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, ensure, Context, Result};
+use fastcrypto::{
+    ed25519::{Ed25519PublicKey, Ed25519Signature},
+    traits::{EncodeDecodeBase64, VerifyingKey},
+};
+use prometheus::{IntCounterVec, IntGaugeVec, Registry};
+use reqwest::Url;
 use serde::{Deserialize, Serialize};
-use serde_with::{serde_as, DisplayFromStr};
+use serde_with::{serde_as, DisplayFromStr, DurationSeconds};
 use tokio::time::interval;
 use tokio_util::sync::CancellationToken;
+use utoipa::ToSchema;
 use walrus_core::BlobId;
 
+/// The name under which the entries of the local deny list are reported.
+pub const LOCAL_BLOCKLIST_NAME: &str = "local";
+
+/// The suffix appended to the source of a remote list to obtain the source of its signature.
+pub const SIGNATURE_SUFFIX: &str = ".sig";
+
+/// The timeout for fetching a remote list or its signature over HTTP.
+const REMOTE_FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The maximum size in bytes of a remote list or its signature.
+const MAX_REMOTE_LIST_SIZE: u64 = 64 * 1024 * 1024;
+
+walrus_utils::metrics::define_metric_set! {
+    #[namespace = "walrus_blocklist"]
+    /// Metrics exported by the blocklist.
+    struct BlocklistMetrics {
+        #[help = "The number of blob IDs contained in each list"]
+        entries: IntGaugeVec["list"],
+
+        #[help = "The number of client requests for blocked blob IDs, by the blocking list"]
+        blocked_requests: IntCounterVec["list"],
+
+        #[help = "The number of failed refreshes of each remote list"]
+        refresh_failures: IntCounterVec["list"],
+    }
+}
+
 /// Internal blocklist struct to deserialize from YAML.
 #[serde_as]
 #[derive(Debug, Deserialize, Serialize, Clone, Default)]
 struct BlocklistInner(#[serde_as(as = "Vec<DisplayFromStr>")] pub Vec<BlobId>);
 
+/// Configuration of the sources of a blocklist, in addition to its local deny list.
+#[serde_as]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct BlocklistConfig {
+    /// Path to a list (in YAML syntax) of blob IDs that are never blocked, even if they are
+    /// contained in the local deny list or in a remote list.
+    pub allow_list_path: Option<PathBuf>,
+    /// Remote deny lists to which the blocklist subscribes.
+    pub remote_lists: Vec<RemoteBlocklistConfig>,
+    /// Directory in which the last verified version of each remote list is cached.
+    ///
+    /// On startup, the blocklist uses the cached lists until the remote lists are fetched again.
+    pub cache_dir: Option<PathBuf>,
+    /// Interval with which the local and remote lists are reloaded, in seconds.
+    #[serde_as(as = "DurationSeconds<u64>")]
+    #[serde(rename = "refresh_interval_secs")]
+    pub refresh_interval: Duration,
+}
+
+impl Default for BlocklistConfig {
+    fn default() -> Self {
+        Self {
+            allow_list_path: None,
+            remote_lists: vec![],
+            cache_dir: None,
+            refresh_interval: Duration::from_secs(30),
+        }
+    }
+}
+
+impl BlocklistConfig {
+    /// Returns true if the configuration does not add any source to the local deny list.
+    pub fn is_empty(&self) -> bool {
+        self.allow_list_path.is_none() && self.remote_lists.is_empty()
+    }
+
+    /// Checks that the names of the remote lists are unique, valid file names, and distinct from
+    /// the name of the local deny list.
+    pub fn validate(&self) -> Result<()> {
+        let mut names = HashSet::new();
+        for remote in &self.remote_lists {
+            let name = remote.name.as_str();
+            ensure!(
+                !name.is_empty()
+                    && name
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'),
+                "invalid remote blocklist name {name:?}: only ASCII letters, digits, '-' and '_' \
+                are allowed"
+            );
+            ensure!(
+                name != LOCAL_BLOCKLIST_NAME,
+                "the remote blocklist name {name:?} is reserved for the local deny list"
+            );
+            ensure!(
+                names.insert(name),
+                "the remote blocklist name {name:?} is used more than once"
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Configuration of a remote deny list.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+pub struct RemoteBlocklistConfig {
+    /// The name of the list, which is reported as the provenance of its entries.
+    pub name: String,
+    /// The location of the list (in YAML syntax), either an HTTP(S) URL, a `file://` URL, or an
+    /// object-store URL such as `gs://bucket/path/blocklist.yaml`.
+    ///
+    /// Object-store URLs are only supported if the `backup` feature is enabled.
+    pub source: String,
+    /// If set, the list must be signed with the corresponding Ed25519 key.
+    ///
+    /// The Base64-encoded signature over the content of the list is read from the location of the
+    /// list with the suffix `.sig` appended.
+    #[serde(default)]
+    pub public_key: Option<Ed25519PublicKey>,
+}
+
+impl RemoteBlocklistConfig {
+    /// Fetches the list and checks its signature, if a public key is configured.
+    async fn fetch(&self, http_client: &reqwest::Client) -> Result<HashSet<BlobId>> {
+        let content = read_source(&self.source, http_client).await?;
+        if let Some(public_key) = &self.public_key {
+            let signature_source = format!("{}{SIGNATURE_SUFFIX}", self.source);
+            let signature = read_source(&signature_source, http_client).await?;
+            let signature = std::str::from_utf8(&signature)
+                .ok()
+                .and_then(|signature| Ed25519Signature::decode_base64(signature.trim()).ok())
+                .ok_or_else(|| anyhow!("invalid signature at {signature_source}"))?;
+            public_key
+                .verify(&content, &signature)
+                .map_err(|_| anyhow!("the signature of the list does not match its public key"))?;
+        }
+        parse_list(&content).with_context(|| format!("parsing blocklist {} failed", self.source))
+    }
+}
+
+/// Reads the content at the given HTTP(S), file, or object-store URL.
+///
+/// Fails if the content exceeds [`MAX_REMOTE_LIST_SIZE`].
+async fn read_source(source: &str, http_client: &reqwest::Client) -> Result<Vec<u8>> {
+    let url = Url::parse(source).with_context(|| format!("invalid blocklist source {source}"))?;
+    match url.scheme() {
+        "http" | "https" => read_http_source(url, http_client).await,
+        "file" => {
+            let path = url
+                .to_file_path()
+                .map_err(|_| anyhow!("invalid file URL {source}"))?;
+            check_source_size(std::fs::metadata(&path)?.len())?;
+            Ok(std::fs::read(&path)?)
+        }
+        _ => read_object_store_source(&url).await,
+    }
+}
+
+/// Reads the content at the given HTTP(S) URL, stopping as soon as it exceeds the size limit.
+async fn read_http_source(url: Url, http_client: &reqwest::Client) -> Result<Vec<u8>> {
+    let mut response = http_client.get(url).send().await?.error_for_status()?;
+    if let Some(length) = response.content_length() {
+        check_source_size(length)?;
+    }
+    let mut content = vec![];
+    while let Some(chunk) = response.chunk().await? {
+        content.extend_from_slice(&chunk);
+        check_source_size(content.len() as u64)?;
+    }
+    Ok(content)
+}
+
+/// Reads the content at the given object-store URL.
+#[cfg(feature = "backup")]
+async fn read_object_store_source(url: &Url) -> Result<Vec<u8>> {
+    let (store, path) = object_store::parse_url(url)?;
+    let result = store.get(&path).await?;
+    check_source_size(result.meta.size as u64)?;
+    Ok(result.bytes().await?.to_vec())
+}
+
+/// Reads the content at the given object-store URL.
+#[cfg(not(feature = "backup"))]
+async fn read_object_store_source(url: &Url) -> Result<Vec<u8>> {
+    anyhow::bail!(
+        "reading blocklists from {}:// URLs requires the `backup` feature",
+        url.scheme()
+    )
+}
+
+/// Checks that the size of a remote list or its signature does not exceed the limit.
+fn check_source_size(size: u64) -> Result<()> {
+    ensure!(
+        size <= MAX_REMOTE_LIST_SIZE,
+        "the size of the list exceeds the limit of {MAX_REMOTE_LIST_SIZE} bytes"
+    );
+    Ok(())
+}
+
+/// Parses a list of blob IDs in YAML format. An empty file is an empty list.
+fn parse_list(content: &[u8]) -> Result<HashSet<BlobId>> {
+    if content.iter().all(u8::is_ascii_whitespace) {
+        return Ok(HashSet::new());
+    }
+    let list: BlocklistInner = serde_yaml::from_slice(content)?;
+    Ok(list.0.into_iter().collect())
+}
+
+/// Reads a list of blob IDs in YAML format from the given path.
+///
+/// Returns an empty list if the file does not exist.
+fn read_list(path: &Path) -> Result<HashSet<BlobId>> {
+    if !path.exists() {
+        return Ok(HashSet::new());
+    }
+    let content = std::fs::read(path)
+        .with_context(|| format!("Unable to read blocklist file at {}", path.display()))?;
+    parse_list(&content).with_context(|| format!("Parsing blocklist at {} failed", path.display()))
+}
+
+/// Writes a list of blob IDs in YAML format to the given path.
+fn write_list(path: &Path, entries: &HashSet<BlobId>) -> Result<()> {
+    let blobs = BlocklistInner(entries.iter().cloned().collect());
+    std::fs::write(path, serde_yaml::to_string(&blobs)?)
+        .with_context(|| format!("Unable to write blocklist file at {}", path.display()))
+}
+
+/// The blocklist status of a blob ID.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BlocklistStatus {
+    /// The blob ID.
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub blob_id: BlobId,
+    /// The list blocking the blob ID, if it is blocked.
+    pub blocked_by: Option<String>,
+}
+
+/// The lists making up the blocklist.
+#[derive(Debug, Default)]
+struct BlocklistState {
+    /// The entries of the local deny list.
+    local: HashSet<BlobId>,
+    /// The local allow-exceptions.
+    allowed: HashSet<BlobId>,
+    /// The entries of the remote lists, by name of the list.
+    remote: HashMap<String, HashSet<BlobId>>,
+    /// The blocked blob IDs along with the first list, in order of precedence, that contains them.
+    blocked: HashMap<BlobId, Arc<str>>,
+}
+
 /// A blocklist of blob IDs.
 ///
-/// Supports checking if a blob ID is blocked and inserting/removing blob IDs.
+/// The blocklist merges a local deny list with any number of remote deny lists. Blob IDs in the
+/// local allow list are never blocked. Each blocked blob ID keeps track of the list that blocks
+/// it, where the local deny list takes precedence over the remote lists, in the order in which
+/// they are configured.
+///
+/// Supports checking if a blob ID is blocked and inserting/removing blob IDs in the local deny
+/// list.
 #[derive(Debug, Default, Clone)]
 pub struct Blocklist {
-    blocked_blobs: Arc<RwLock<HashSet<BlobId>>>,
+    state: Arc<RwLock<BlocklistState>>,
     deny_list_path: PathBuf,
+    config: Arc<BlocklistConfig>,
+    http_client: reqwest::Client,
+    metrics: Option<BlocklistMetrics>,
     shutdown: CancellationToken,
 }
 
@@ -42,20 +299,53 @@ impl Blocklist {
         };
 
         let blocklist = Self {
-            blocked_blobs: Arc::new(RwLock::new(HashSet::new())),
             deny_list_path: path.clone(),
-            shutdown: CancellationToken::new(),
+            ..Self::default()
+        };
+
+        blocklist.load()?;
+
+        Ok(blocklist)
+    }
+
+    /// Creates a blocklist from the local deny list at the provided path and the sources in the
+    /// configuration.
+    ///
+    /// The remote lists start from their cached versions, if any, and are fetched in the
+    /// background by the task started with [`Self::start_refresh_task`]. Remote lists that cannot
+    /// be fetched or verified are logged and retried on the next refresh.
+    ///
+    /// Returns an error if the configuration is invalid or if a local list cannot be read or
+    /// parsed.
+    pub fn new_with_config(
+        path: &Option<PathBuf>,
+        config: &BlocklistConfig,
+        registry: Option<&Registry>,
+    ) -> Result<Self> {
+        config.validate()?;
+        let blocklist = Self {
+            deny_list_path: path.clone().unwrap_or_default(),
+            config: Arc::new(config.clone()),
+            http_client: reqwest::Client::builder()
+                .timeout(REMOTE_FETCH_TIMEOUT)
+                .build()
+                .context("failed to create the HTTP client for the remote blocklists")?,
+            metrics: registry.map(BlocklistMetrics::new),
+            ..Self::default()
         };
 
         blocklist.load()?;
+        blocklist.load_cached_remote_lists();
 
         Ok(blocklist)
     }
 
     /// Starts a task to periodically refresh the blocklist.
+    ///
+    /// The first refresh, including the fetch of the remote lists, happens immediately.
     pub fn start_refresh_task(self: Arc<Self>) {
         tokio::spawn(async move {
-            let mut interval = interval(Duration::from_secs(30));
+            let mut interval = interval(self.config.refresh_interval);
             loop {
                 tokio::select! {
                     _ = interval.tick() => {
@@ -63,6 +353,7 @@ impl Blocklist {
                         if let Err(e) = self.load() {
                             tracing::error!("Failed to refresh deny list: {}", e);
                         }
+                        self.refresh_remote_lists().await;
                     }
                     _ = self.shutdown.cancelled() => {
                         tracing::info!("Received shutdown signal");
@@ -76,20 +367,47 @@ impl Blocklist {
     /// Checks if a blob ID is blocked.
     #[inline]
     pub fn is_blocked(&self, blob_id: &BlobId) -> bool {
-        let guard = self.blocked_blobs.read().expect("mutex poisoned");
-        guard.contains(blob_id)
+        self.blocked_by(blob_id).is_some()
     }
 
-    /// Adds a blob ID to the blocklist.
+    /// Checks if a blob ID requested by a client is blocked.
+    ///
+    /// Blocked requests are counted in the metrics, by the list blocking the blob ID. Checks that
+    /// do not correspond to a client request should use [`Self::is_blocked`] instead.
+    pub fn is_request_blocked(&self, blob_id: &BlobId) -> bool {
+        let Some(list) = self.blocked_by(blob_id) else {
+            return false;
+        };
+        if let Some(metrics) = &self.metrics {
+            walrus_utils::with_label!(metrics.blocked_requests, list).inc();
+        }
+        true
+    }
+
+    /// Returns the name of the list blocking the blob ID, if it is blocked.
+    pub fn blocked_by(&self, blob_id: &BlobId) -> Option<Arc<str>> {
+        let guard = self.state.read().expect("mutex poisoned");
+        guard.blocked.get(blob_id).cloned()
+    }
+
+    /// Returns the blocklist status of the blob ID, including the list blocking it.
+    pub fn status(&self, blob_id: &BlobId) -> BlocklistStatus {
+        BlocklistStatus {
+            blob_id: *blob_id,
+            blocked_by: self.blocked_by(blob_id).map(|list| list.to_string()),
+        }
+    }
+
+    /// Adds a blob ID to the local deny list.
     ///
     /// Returns whether the ID was newly inserted.
     #[inline]
     pub fn insert(&mut self, blob_id: BlobId) -> Result<bool> {
-        let mut guard: std::sync::RwLockWriteGuard<'_, HashSet<BlobId>> =
-            self.blocked_blobs.write().expect("mutex poisoned");
-        guard.insert(blob_id);
+        let mut guard = self.state.write().expect("mutex poisoned");
+        guard.local.insert(blob_id);
+        self.rebuild(&mut guard);
         // Update yaml file to add this blob id
-        let blobs = BlocklistInner(guard.iter().cloned().collect::<Vec<_>>());
+        let blobs = BlocklistInner(guard.local.iter().cloned().collect::<Vec<_>>());
         let mut file = std::fs::OpenOptions::new()
             .write(true)
             .truncate(true)
@@ -99,15 +417,15 @@ impl Blocklist {
         Ok(true)
     }
 
-    /// Removes a blob ID from the blocklist.
+    /// Removes a blob ID from the local deny list.
     ///
     /// Returns whether the ID was previously blocked.
     #[inline]
     pub fn remove(&mut self, blob_id: &BlobId) -> Result<bool> {
-        let mut guard: std::sync::RwLockWriteGuard<'_, HashSet<BlobId>> =
-            self.blocked_blobs.write().expect("mutex poisoned");
-        guard.remove(blob_id);
-        let blobs = BlocklistInner(guard.iter().cloned().collect::<Vec<_>>());
+        let mut guard = self.state.write().expect("mutex poisoned");
+        guard.local.remove(blob_id);
+        self.rebuild(&mut guard);
+        let blobs = BlocklistInner(guard.local.iter().cloned().collect::<Vec<_>>());
 
         if !self.deny_list_path.exists() {
             return Ok(false);
@@ -121,41 +439,319 @@ impl Blocklist {
         Ok(true)
     }
 
-    /// Loads the blocklist from the file at the given path.
+    /// Loads the local deny list and allow list from their files.
     fn load(&self) -> Result<()> {
-        if !self.deny_list_path.exists() {
-            return Ok(());
+        let local = read_list(&self.deny_list_path)?;
+        let allowed = match &self.config.allow_list_path {
+            Some(path) => read_list(path)?,
+            None => HashSet::new(),
+        };
+
+        let mut guard = self.state.write().expect("mutex poisoned");
+        guard.local = local;
+        guard.allowed = allowed;
+        self.rebuild(&mut guard);
+        Ok(())
+    }
+
+    /// Returns the path at which the given remote list is cached, if a cache directory is
+    /// configured.
+    fn cache_path(&self, remote: &RemoteBlocklistConfig) -> Option<PathBuf> {
+        let cache_dir = self.config.cache_dir.as_ref()?;
+        Some(cache_dir.join(format!("{}.yaml", remote.name)))
+    }
+
+    /// Loads the cached versions of the remote lists. Lists that are not cached or cannot be read
+    /// start empty.
+    fn load_cached_remote_lists(&self) {
+        let mut guard = self.state.write().expect("mutex poisoned");
+        for remote in &self.config.remote_lists {
+            let Some(path) = self.cache_path(remote).filter(|path| path.exists()) else {
+                continue;
+            };
+            match read_list(&path) {
+                Ok(entries) => {
+                    guard.remote.insert(remote.name.clone(), entries);
+                }
+                Err(error) => tracing::warn!(
+                    list = %remote.name,
+                    ?error,
+                    "failed to read the cached remote blocklist"
+                ),
+            }
         }
+        self.rebuild(&mut guard);
+    }
 
-        let content = std::fs::read_to_string(&self.deny_list_path).context(format!(
-            "Unable to read blocklist file at {}",
-            self.deny_list_path.display()
-        ))?;
+    /// Caches the entries of a remote list that were fetched and verified.
+    fn cache_remote_list(&self, remote: &RemoteBlocklistConfig, entries: &HashSet<BlobId>) {
+        let Some(path) = self.cache_path(remote) else {
+            return;
+        };
+        let result = path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .map_err(anyhow::Error::from)
+            .and_then(|()| write_list(&path, entries));
+        if let Err(error) = result {
+            tracing::warn!(list = %remote.name, ?error, "failed to cache the remote blocklist");
+        }
+    }
 
-        if content.is_empty() {
-            return Ok(());
+    /// Fetches the remote lists. Lists that cannot be fetched or verified keep their previous
+    /// entries.
+    async fn refresh_remote_lists(&self) {
+        for remote in &self.config.remote_lists {
+            match remote.fetch(&self.http_client).await {
+                Ok(entries) => {
+                    self.cache_remote_list(remote, &entries);
+                    let mut guard = self.state.write().expect("mutex poisoned");
+                    guard.remote.insert(remote.name.clone(), entries);
+                    self.rebuild(&mut guard);
+                }
+                Err(error) => {
+                    tracing::error!(
+                        list = %remote.name,
+                        source = %remote.source,
+                        ?error,
+                        "failed to refresh remote blocklist"
+                    );
+                    if let Some(metrics) = &self.metrics {
+                        walrus_utils::with_label!(metrics.refresh_failures, remote.name).inc();
+                    }
+                }
+            }
         }
+    }
 
-        let blocklist: BlocklistInner = serde_yaml::from_str(&content).context(format!(
-            "Parsing blocklist at {} failed",
-            self.deny_list_path.display()
-        ))?;
+    /// Recomputes the blocked blob IDs from the lists, logging the changes.
+    fn rebuild(&self, state: &mut BlocklistState) {
+        let local_name: Arc<str> = LOCAL_BLOCKLIST_NAME.into();
+        let lists = std::iter::once((local_name, Some(&state.local))).chain(
+            self.config
+                .remote_lists
+                .iter()
+                .map(|remote| (remote.name.as_str().into(), state.remote.get(&remote.name))),
+        );
 
-        let mut guard = self.blocked_blobs.write().expect("mutex poisoned");
-        let old_blobs = guard.iter().cloned().collect::<HashSet<_>>();
-        let new_blobs = blocklist.0.iter().cloned().collect::<HashSet<_>>();
-        let added_blobs = new_blobs.difference(&old_blobs).collect::<Vec<_>>();
-        let removed_blobs = old_blobs.difference(&new_blobs).collect::<Vec<_>>();
+        let mut blocked = HashMap::new();
+        for (name, entries) in lists {
+            let entries = entries.into_iter().flatten();
+            let mut count = 0;
+            for blob_id in entries.filter(|blob_id| !state.allowed.contains(*blob_id)) {
+                count += 1;
+                blocked.entry(*blob_id).or_insert_with(|| name.clone());
+            }
+            if let Some(metrics) = &self.metrics {
+                walrus_utils::with_label!(metrics.entries, name).set(count);
+            }
+        }
 
-        added_blobs.iter().for_each(|blob_id| {
-            tracing::info!("Added blob to deny list: {}", blob_id);
-        });
+        for (blob_id, list) in &blocked {
+            if !state.blocked.contains_key(blob_id) {
+                tracing::info!(%list, "Added blob to deny list: {}", blob_id);
+            }
+        }
+        for (blob_id, list) in &state.blocked {
+            if !blocked.contains_key(blob_id) {
+                tracing::info!(%list, "Removed blob from deny list: {}", blob_id);
+            }
+        }
 
-        removed_blobs.iter().for_each(|blob_id| {
-            tracing::info!("Removed blob from deny list: {}", blob_id);
-        });
+        state.blocked = blocked;
+    }
+}
 
-        *guard = blocklist.0.into_iter().collect();
-        Ok(())
+#[cfg(test)]
+mod tests {
+    use walrus_core::test_utils::random_blob_id;
+
+    use super::*;
+
+    fn write_blob_ids(path: &Path, blob_ids: &[BlobId]) {
+        std::fs::write(
+            path,
+            serde_yaml::to_string(&BlocklistInner(blob_ids.to_vec())).unwrap(),
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn entries_keep_their_provenance() {
+        let dir = tempfile::tempdir().unwrap();
+        let [local, remote, both, allowed, none] = [(); 5].map(|_| random_blob_id());
+        write_blob_ids(&dir.path().join("deny.yaml"), &[local, both]);
+        write_blob_ids(&dir.path().join("remote.yaml"), &[remote, both, allowed]);
+        write_blob_ids(&dir.path().join("allow.yaml"), &[allowed]);
+
+        let config = BlocklistConfig {
+            allow_list_path: Some(dir.path().join("allow.yaml")),
+            remote_lists: vec![RemoteBlocklistConfig {
+                name: "remote".to_owned(),
+                source: format!("file://{}", dir.path().join("remote.yaml").display()),
+                public_key: None,
+            }],
+            ..Default::default()
+        };
+        let mut blocklist = Blocklist::new_with_config(
+            &Some(dir.path().join("deny.yaml")),
+            &config,
+            Some(&Registry::new()),
+        )
+        .unwrap();
+        blocklist.refresh_remote_lists().await;
+
+        assert_eq!(blocklist.blocked_by(&local).as_deref(), Some("local"));
+        assert_eq!(blocklist.blocked_by(&remote).as_deref(), Some("remote"));
+        assert_eq!(blocklist.blocked_by(&both).as_deref(), Some("local"));
+        assert!(!blocklist.is_blocked(&allowed));
+        assert!(!blocklist.is_blocked(&none));
+
+        // Removing a blob ID from the local list exposes the remote list.
+        blocklist.remove(&both).unwrap();
+        assert_eq!(blocklist.blocked_by(&both).as_deref(), Some("remote"));
+        assert_eq!(read_list(&dir.path().join("deny.yaml")).unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn remote_lists_must_match_their_signature() {
+        use fastcrypto::{
+            ed25519::Ed25519KeyPair,
+            traits::{KeyPair, Signer},
+        };
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let dir = tempfile::tempdir().unwrap();
+        let blob_id = random_blob_id();
+        let list_path = dir.path().join("remote.yaml");
+        write_blob_ids(&list_path, &[blob_id]);
+
+        let key_pair = Ed25519KeyPair::generate(&mut StdRng::seed_from_u64(42));
+        let other_key_pair = Ed25519KeyPair::generate(&mut StdRng::seed_from_u64(43));
+        let signature: Ed25519Signature = key_pair.sign(&std::fs::read(&list_path).unwrap());
+        std::fs::write(
+            dir.path().join(format!("remote.yaml{SIGNATURE_SUFFIX}")),
+            signature.encode_base64(),
+        )
+        .unwrap();
+
+        let remote = |public_key: &Ed25519PublicKey| RemoteBlocklistConfig {
+            name: "remote".to_owned(),
+            source: format!("file://{}", list_path.display()),
+            public_key: Some(public_key.clone()),
+        };
+        let http_client = reqwest::Client::new();
+        assert_eq!(
+            remote(key_pair.public())
+                .fetch(&http_client)
+                .await
+                .unwrap()
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec![blob_id]
+        );
+        assert!(remote(other_key_pair.public())
+            .fetch(&http_client)
+            .await
+            .is_err());
+    }
+
+    fn remote(name: &str, path: &Path) -> RemoteBlocklistConfig {
+        RemoteBlocklistConfig {
+            name: name.to_owned(),
+            source: format!("file://{}", path.display()),
+            public_key: None,
+        }
+    }
+
+    #[test]
+    fn remote_list_names_are_validated() {
+        let config = |names: &[&str]| BlocklistConfig {
+            remote_lists: names
+                .iter()
+                .map(|name| remote(name, Path::new("/list.yaml")))
+                .collect(),
+            ..Default::default()
+        };
+        config(&["first", "second-list_2"]).validate().unwrap();
+        assert!(config(&["first", "first"]).validate().is_err());
+        assert!(config(&[LOCAL_BLOCKLIST_NAME]).validate().is_err());
+        assert!(config(&["../list"]).validate().is_err());
+        assert!(config(&[""]).validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn remote_lists_start_from_their_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let blob_id = random_blob_id();
+        let list_path = dir.path().join("remote.yaml");
+        write_blob_ids(&list_path, &[blob_id]);
+        let config = BlocklistConfig {
+            remote_lists: vec![remote("remote", &list_path)],
+            cache_dir: Some(dir.path().join("cache")),
+            ..Default::default()
+        };
+
+        // The remote list is only available once it has been fetched.
+        let blocklist = Blocklist::new_with_config(&None, &config, None).unwrap();
+        assert!(!blocklist.is_blocked(&blob_id));
+        blocklist.refresh_remote_lists().await;
+        assert_eq!(blocklist.blocked_by(&blob_id).as_deref(), Some("remote"));
+
+        // A new blocklist starts from the cached list, even if the remote list is unavailable.
+        std::fs::remove_file(&list_path).unwrap();
+        let blocklist = Blocklist::new_with_config(&None, &config, None).unwrap();
+        assert_eq!(blocklist.blocked_by(&blob_id).as_deref(), Some("remote"));
+        blocklist.refresh_remote_lists().await;
+        assert!(blocklist.is_blocked(&blob_id));
+    }
+
+    #[tokio::test]
+    async fn oversized_remote_lists_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let list_path = dir.path().join("remote.yaml");
+        std::fs::File::create(&list_path)
+            .unwrap()
+            .set_len(MAX_REMOTE_LIST_SIZE + 1)
+            .unwrap();
+
+        let error = remote("remote", &list_path)
+            .fetch(&reqwest::Client::new())
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("exceeds the limit"));
+    }
+
+    #[test]
+    fn only_client_requests_are_counted() {
+        let dir = tempfile::tempdir().unwrap();
+        let blob_id = random_blob_id();
+        let deny_list_path = dir.path().join("deny.yaml");
+        write_blob_ids(&deny_list_path, &[blob_id]);
+        let blocklist = Blocklist::new_with_config(
+            &Some(deny_list_path),
+            &BlocklistConfig::default(),
+            Some(&Registry::new()),
+        )
+        .unwrap();
+        let blocked_requests = || {
+            blocklist
+                .metrics
+                .as_ref()
+                .unwrap()
+                .blocked_requests
+                .with_label_values(&[LOCAL_BLOCKLIST_NAME])
+                .get()
+        };
+
+        assert!(blocklist.is_blocked(&blob_id));
+        assert_eq!(blocked_requests(), 0);
+        assert!(blocklist.is_request_blocked(&blob_id));
+        assert!(!blocklist.is_request_blocked(&random_blob_id()));
+        assert_eq!(blocked_requests(), 1);
+        assert_eq!(
+            blocklist.status(&blob_id).blocked_by.as_deref(),
+            Some(LOCAL_BLOCKLIST_NAME)
+        );
     }
 }
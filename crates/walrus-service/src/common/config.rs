@@ -3,14 +3,14 @@
 
 //! Common configuration module.
 
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DurationMilliSeconds};
 use walrus_sui::{
     client::{
         contract_config::ContractConfig,
-        rpc_config::RpcFallbackConfig,
+        rpc_config::{RpcFailoverConfig, RpcFallbackConfig},
         SuiClientError,
         SuiContractClient,
         SuiReadClient,
@@ -48,31 +48,61 @@ pub struct SuiConfig {
     /// The config for rpc fallback.
     #[serde(default, skip_serializing_if = "defaults::is_none")]
     pub rpc_fallback_config: Option<RpcFallbackConfig>,
+    /// Additional HTTP URLs of Sui full-node RPC endpoints, to which requests fail over if the
+    /// endpoint in `rpc` is unhealthy.
+    ///
+    /// If set, the endpoints are also used for write operations instead of the RPC URL from the
+    /// wallet.
+    #[serde(default, skip_serializing_if = "defaults::is_default")]
+    pub additional_rpc_endpoints: Vec<String>,
+    /// The configuration of the failover between the RPC endpoints.
+    #[serde(default, skip_serializing_if = "defaults::is_default")]
+    pub rpc_failover_config: RpcFailoverConfig,
 }
 
 impl SuiConfig {
+    /// Returns the URLs of all configured RPC endpoints, starting with `rpc`.
+    pub fn rpc_endpoints(&self) -> Vec<&str> {
+        rpc_endpoints(&self.rpc, &self.additional_rpc_endpoints)
+    }
+
     /// Creates a new [`SuiReadClient`] based on the configuration.
     pub async fn new_read_client(&self) -> Result<SuiReadClient, SuiClientError> {
-        SuiReadClient::new_for_rpc(
-            &self.rpc,
+        SuiReadClient::new_for_rpcs(
+            &self.rpc_endpoints(),
             &self.contract_config,
             self.backoff_config.clone(),
+            self.rpc_failover_config.clone(),
         )
         .await
     }
 
     /// Creates a [`SuiContractClient`] based on the configuration.
+    ///
+    /// If additional RPC endpoints are configured, the client fails over between the configured
+    /// endpoints; otherwise, it uses the RPC URL from the wallet.
     pub async fn new_contract_client(&self) -> Result<SuiContractClient, SuiClientError> {
-        SuiContractClient::new(
-            WalletConfig::load_wallet_context(Some(&self.wallet_config))?,
-            &self.contract_config,
-            self.backoff_config.clone(),
-            self.gas_budget,
-        )
-        .await
+        let wallet = WalletConfig::load_wallet_context(Some(&self.wallet_config))?;
+        if self.additional_rpc_endpoints.is_empty() {
+            return SuiContractClient::new(
+                wallet,
+                &self.contract_config,
+                self.backoff_config.clone(),
+                self.gas_budget,
+            )
+            .await;
+        }
+        let read_client = self.new_read_client().await?;
+        SuiContractClient::new_with_read_client(wallet, self.gas_budget, Arc::new(read_client))
     }
 }
 
+fn rpc_endpoints<'a>(rpc: &'a str, additional_rpc_endpoints: &'a [String]) -> Vec<&'a str> {
+    std::iter::once(rpc)
+        .chain(additional_rpc_endpoints.iter().map(String::as_str))
+        .collect()
+}
+
 impl From<&SuiConfig> for SuiReaderConfig {
     fn from(config: &SuiConfig) -> Self {
         Self {
@@ -81,6 +111,8 @@ impl From<&SuiConfig> for SuiReaderConfig {
             event_polling_interval: config.event_polling_interval,
             backoff_config: config.backoff_config.clone(),
             rpc_fallback_config: config.rpc_fallback_config.clone(),
+            additional_rpc_endpoints: config.additional_rpc_endpoints.clone(),
+            rpc_failover_config: config.rpc_failover_config.clone(),
         }
     }
 }
@@ -109,20 +141,60 @@ pub struct SuiReaderConfig {
     /// The URL of the checkpoint download fallback endpoint.
     #[serde(default, skip_serializing_if = "defaults::is_none")]
     pub rpc_fallback_config: Option<RpcFallbackConfig>,
+    /// Additional HTTP URLs of Sui full-node RPC endpoints, to which requests fail over if the
+    /// endpoint in `rpc` is unhealthy.
+    #[serde(default, skip_serializing_if = "defaults::is_default")]
+    pub additional_rpc_endpoints: Vec<String>,
+    /// The configuration of the failover between the RPC endpoints.
+    #[serde(default, skip_serializing_if = "defaults::is_default")]
+    pub rpc_failover_config: RpcFailoverConfig,
 }
 
 impl SuiReaderConfig {
+    /// Returns the URLs of all configured RPC endpoints, starting with `rpc`.
+    pub fn rpc_endpoints(&self) -> Vec<&str> {
+        rpc_endpoints(&self.rpc, &self.additional_rpc_endpoints)
+    }
+
     /// Creates a new [`SuiReadClient`] based on the configuration.
     pub async fn new_read_client(&self) -> Result<SuiReadClient, SuiClientError> {
-        SuiReadClient::new_for_rpc(
-            &self.rpc,
+        SuiReadClient::new_for_rpcs(
+            &self.rpc_endpoints(),
             &self.contract_config,
             self.backoff_config.clone(),
+            self.rpc_failover_config.clone(),
         )
         .await
     }
 }
 
+/// Configuration of the export of traces to an OpenTelemetry collector using OTLP over gRPC.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct TraceExportConfig {
+    /// The URL of the gRPC endpoint of the collector, e.g., `http://localhost:4317`.
+    pub endpoint: String,
+    /// The fraction of traces that are sampled, between 0 and 1.
+    ///
+    /// The sampling decision of the caller is respected for requests that carry a trace context.
+    #[serde(default = "defaults::trace_sample_ratio")]
+    pub sample_ratio: f64,
+    /// The name of the service reported to the collector. Defaults to the name of the binary.
+    #[serde(default, skip_serializing_if = "defaults::is_none")]
+    pub service_name: Option<String>,
+}
+
+impl TraceExportConfig {
+    /// Checks that the sample ratio is between 0 and 1.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            (0.0..=1.0).contains(&self.sample_ratio),
+            "the trace sample ratio must be between 0 and 1, got {}",
+            self.sample_ratio
+        );
+        Ok(())
+    }
+}
+
 /// Shared configuration defaults.
 pub mod defaults {
     use super::*;
@@ -135,6 +207,11 @@ pub mod defaults {
         Duration::from_millis(POLLING_INTERVAL_MS)
     }
 
+    /// Returns the default fraction of exported traces.
+    pub fn trace_sample_ratio() -> f64 {
+        1.0
+    }
+
     /// Returns true iff the value is the default and we don't run in test mode.
     pub fn is_default<T: PartialEq + Default>(t: &T) -> bool {
         // The `cfg!(test)` check is there to allow serializing the full configuration, specifically
@@ -149,3 +226,34 @@ pub mod defaults {
         !cfg!(test) && t.is_none()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trace_export_config_defaults_and_validation() {
+        let config: TraceExportConfig =
+            serde_yaml::from_str("endpoint: http://localhost:4317").unwrap();
+        assert_eq!(config.sample_ratio, 1.0);
+        assert_eq!(config.service_name, None);
+        config.validate().unwrap();
+
+        for sample_ratio in [0.0, 0.25] {
+            TraceExportConfig {
+                sample_ratio,
+                ..config.clone()
+            }
+            .validate()
+            .unwrap();
+        }
+        for sample_ratio in [-0.1, 1.01, f64::NAN] {
+            assert!(TraceExportConfig {
+                sample_ratio,
+                ..config.clone()
+            }
+            .validate()
+            .is_err());
+        }
+    }
+}
@@ -7,6 +7,7 @@
 #![allow(dead_code)]
 
 use std::{
+    env,
     net::{IpAddr, SocketAddr},
     str::FromStr as _,
     sync::{
@@ -37,6 +38,7 @@ use prometheus::{
     IntGaugeVec,
     Opts,
 };
+use telemetry_subscribers::TelemetryConfig;
 use tokio::time::Instant;
 use tower_http::trace::{MakeSpan, OnResponse};
 use tracing::{field, Span};
@@ -44,7 +46,7 @@ use tracing_opentelemetry::OpenTelemetrySpanExt;
 use walrus_core::Epoch;
 use walrus_utils::http::{http_body::Frame, BodyVisitor, VisitBody};
 
-use super::active_committees::ActiveCommittees;
+use super::{active_committees::ActiveCommittees, config::TraceExportConfig};
 
 /// Route string used in metrics for invalid routes.
 pub(crate) const UNMATCHED_ROUTE: &str = "invalid-route";
@@ -565,3 +567,106 @@ impl From<CurrentEpochStateMetric> for Box<dyn Collector> {
         Box::new(value.0)
     }
 }
+
+/// The environment variable holding the endpoint of the OTLP exporter of `telemetry_subscribers`.
+const OTLP_ENDPOINT_ENV: &str = "OTLP_ENDPOINT";
+/// The environment variable holding the service name reported by the OTLP exporter.
+const SERVICE_NAME_ENV: &str = "OTEL_SERVICE_NAME";
+/// The environment variable holding the filter of the spans exported by the OTLP exporter.
+const TRACE_FILTER_ENV: &str = "TRACE_FILTER";
+/// The filter of the exported spans if none is set in the environment.
+const DEFAULT_TRACE_FILTER: &str = "info";
+
+/// Shuts down the export of traces to an OpenTelemetry collector when dropped.
+///
+/// Dropping the guard flushes the pending spans.
+#[derive(Debug)]
+pub(crate) struct TraceExportGuard(());
+
+impl Drop for TraceExportGuard {
+    fn drop(&mut self) {
+        tracing::debug!("shutting down the trace exporter");
+        opentelemetry::global::shutdown_tracer_provider();
+    }
+}
+
+/// Enables the OTLP layer of the `telemetry_subscribers` subscriber to export traces to the
+/// configured collector.
+///
+/// The layer is part of the same subscriber as the logs and span-latency metrics, and exports the
+/// spans in batches on the Tokio runtime in which the subscriber is initialized. It also installs
+/// the W3C trace-context propagator, such that the trace context is propagated across HTTP
+/// requests between clients and storage nodes.
+pub(crate) fn enable_trace_export(
+    telemetry_config: &mut TelemetryConfig,
+    config: &TraceExportConfig,
+    default_service_name: &str,
+) -> anyhow::Result<TraceExportGuard> {
+    config.validate()?;
+    for (name, value) in trace_export_env(config, default_service_name) {
+        // A trace filter set explicitly in the environment takes precedence.
+        if name != TRACE_FILTER_ENV || env::var_os(name).is_none() {
+            env::set_var(name, value);
+        }
+    }
+    telemetry_config.enable_otlp_tracing = true;
+    telemetry_config.sample_rate = config.sample_ratio;
+    Ok(TraceExportGuard(()))
+}
+
+/// Returns the environment variables through which `telemetry_subscribers` configures its OTLP
+/// exporter.
+fn trace_export_env(
+    config: &TraceExportConfig,
+    default_service_name: &str,
+) -> [(&'static str, String); 3] {
+    let service_name = config
+        .service_name
+        .clone()
+        .unwrap_or_else(|| default_service_name.to_owned());
+    [
+        (OTLP_ENDPOINT_ENV, config.endpoint.clone()),
+        (SERVICE_NAME_ENV, service_name),
+        (TRACE_FILTER_ENV, DEFAULT_TRACE_FILTER.to_owned()),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trace_export_env_uses_configured_service_name() {
+        let mut config = TraceExportConfig {
+            endpoint: "http://collector:4317".to_owned(),
+            sample_ratio: 0.5,
+            service_name: None,
+        };
+        assert_eq!(
+            trace_export_env(&config, "walrus-node"),
+            [
+                (OTLP_ENDPOINT_ENV, "http://collector:4317".to_owned()),
+                (SERVICE_NAME_ENV, "walrus-node".to_owned()),
+                (TRACE_FILTER_ENV, DEFAULT_TRACE_FILTER.to_owned()),
+            ]
+        );
+
+        config.service_name = Some("aggregator".to_owned());
+        assert_eq!(
+            trace_export_env(&config, "walrus-node")[1],
+            (SERVICE_NAME_ENV, "aggregator".to_owned())
+        );
+    }
+
+    #[test]
+    fn enable_trace_export_rejects_invalid_sample_ratio() {
+        let config = TraceExportConfig {
+            endpoint: "http://collector:4317".to_owned(),
+            sample_ratio: 1.5,
+            service_name: None,
+        };
+        let mut telemetry_config = TelemetryConfig::new();
+        assert!(enable_trace_export(&mut telemetry_config, &config, "walrus").is_err());
+        assert!(!telemetry_config.enable_otlp_tracing);
+    }
+}
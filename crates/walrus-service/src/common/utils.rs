@@ -59,7 +59,11 @@ use walrus_sui::{
     utils::SuiNetwork,
 };
 
-use super::active_committees::ActiveCommittees;
+use super::{
+    active_committees::ActiveCommittees,
+    config::TraceExportConfig,
+    telemetry::{self, TraceExportGuard},
+};
 use crate::node::{config::MetricsPushConfig, events::event_processor::EventProcessorMetrics};
 
 /// The maximum length of the storage node name. Keep in sync with `MAX_NODE_NAME_LENGTH` in
@@ -252,6 +256,7 @@ pub struct MetricsAndLoggingRuntime {
     pub registry: Registry,
     _telemetry_guards: TelemetryGuards,
     _tracing_handle: TracingHandle,
+    _trace_export_guard: Option<TraceExportGuard>,
     /// The runtime for metrics and logging.
     // INV: Runtime must be dropped last.
     pub runtime: Option<Runtime>,
@@ -259,7 +264,13 @@ pub struct MetricsAndLoggingRuntime {
 
 impl MetricsAndLoggingRuntime {
     /// Start metrics and log collection in a new runtime
-    pub fn start(metrics_address: SocketAddr) -> anyhow::Result<Self> {
+    ///
+    /// If `trace_export` is set, traces are exported to an OpenTelemetry collector from the new
+    /// runtime.
+    pub fn start(
+        metrics_address: SocketAddr,
+        trace_export: Option<&TraceExportConfig>,
+    ) -> anyhow::Result<Self> {
         let runtime = runtime::Builder::new_multi_thread()
             .thread_name("metrics-runtime")
             .worker_threads(2)
@@ -268,34 +279,68 @@ impl MetricsAndLoggingRuntime {
             .context("metrics runtime creation failed")?;
         let _guard = runtime.enter();
 
-        Self::new(metrics_address, Some(runtime))
+        Self::new(metrics_address, Some(runtime), trace_export)
     }
 
     /// Create a new runtime for metrics and logging.
-    pub fn new(mut metrics_address: SocketAddr, runtime: Option<Runtime>) -> anyhow::Result<Self> {
+    ///
+    /// If `trace_export` is set, the OTLP layer of the subscriber is enabled and this must be
+    /// called within a Tokio runtime, which is used to export the traces.
+    pub fn new(
+        mut metrics_address: SocketAddr,
+        runtime: Option<Runtime>,
+        trace_export: Option<&TraceExportConfig>,
+    ) -> anyhow::Result<Self> {
         metrics_address.set_ip(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
         let registry_service = mysten_metrics::start_prometheus_server(metrics_address);
         let walrus_registry = registry_service.default_registry();
 
         // Initialize logging subscriber
-        let (telemetry_guards, tracing_handle) = telemetry_subscribers::TelemetryConfig::new()
+        let mut telemetry_config = telemetry_subscribers::TelemetryConfig::new()
             .with_env()
             .with_prom_registry(&walrus_registry)
-            .with_json()
-            .init();
+            .with_json();
+        let trace_export_guard = trace_export
+            .map(|trace_export| {
+                telemetry::enable_trace_export(
+                    &mut telemetry_config,
+                    trace_export,
+                    &default_service_name(),
+                )
+            })
+            .transpose()?;
+        let (telemetry_guards, tracing_handle) = telemetry_config.init();
+        if let Some(trace_export) = trace_export {
+            tracing::info!(
+                endpoint = %trace_export.endpoint,
+                "exporting traces to an OpenTelemetry collector"
+            );
+        }
 
         // Initialize metrics to track db usage before we create any db instances.
         DBMetrics::init(&walrus_registry);
+        // Initialize the metrics of the Sui RPC endpoints before we create any Sui clients.
+        walrus_sui::client::retry_client::SuiRpcEndpointMetrics::init(&walrus_registry);
 
         Ok(Self {
             runtime,
             registry: walrus_registry,
             _telemetry_guards: telemetry_guards,
             _tracing_handle: tracing_handle,
+            _trace_export_guard: trace_export_guard,
         })
     }
 }
 
+/// Returns the name of the running binary, which is used as the default service name of exported
+/// traces.
+fn default_service_name() -> String {
+    env::current_exe()
+        .ok()
+        .and_then(|path| path.file_stem()?.to_str().map(str::to_owned))
+        .unwrap_or_else(|| "walrus".to_owned())
+}
+
 /// A config struct to initialize the push metrics. Some binaries that depend on
 /// MetricPushRuntime do not need nor is it appropriate to have push metrics.
 #[derive(Debug)]
@@ -758,8 +803,13 @@ pub async fn collect_event_blobs_for_catchup(
         contract_config,
         exchange_objects: vec![],
         wallet_config: None,
+        sponsor: None,
         communication_config: Default::default(),
         refresh_config: Default::default(),
+        rpc_urls: Default::default(),
+        rpc_failover_config: Default::default(),
+        trace_export: None,
+        blocklist: Default::default(),
     };
 
     let walrus_client =
@@ -119,6 +119,7 @@ use walrus_sui::{
 
 use self::{
     blob_sync::BlobSyncHandler,
+    blob_sync_queue::BlobSyncQueue,
     committee::{CommitteeService, NodeCommitteeService},
     config::StorageNodeConfig,
     contract_service::{SuiSystemContractService, SystemContractService},
@@ -128,6 +129,7 @@ use self::{
         InconsistencyProofError,
         IndexOutOfRange,
         InvalidEpochError,
+        PromoteBlobSyncError,
         RetrieveMetadataError,
         RetrieveSliverError,
         RetrieveSymbolError,
@@ -154,6 +156,7 @@ use crate::{
     client::Blocklist,
     common::{
         active_committees::ActiveCommittees,
+        blocklist::BlocklistStatus,
         config::SuiConfig,
         utils::should_reposition_cursor,
     },
@@ -172,6 +175,7 @@ pub(crate) mod metrics;
 
 mod blob_retirement_notifier;
 mod blob_sync;
+mod blob_sync_queue;
 mod consistency_check;
 mod epoch_change_driver;
 mod node_recovery;
@@ -271,6 +275,9 @@ pub trait ServiceState {
     /// Retrieves the blob status for the given `blob_id`.
     fn blob_status(&self, blob_id: &BlobId) -> Result<BlobStatus, BlobStatusError>;
 
+    /// Returns the blocklist status of the given `blob_id`, including the list blocking it.
+    fn blocklist_status(&self, blob_id: &BlobId) -> BlocklistStatus;
+
     /// Returns the number of shards the node is currently operating with.
     fn n_shards(&self) -> NonZeroU16;
 
@@ -290,6 +297,9 @@ pub trait ServiceState {
         public_key: PublicKey,
         signed_request: SignedSyncShardRequest,
     ) -> impl Future<Output = Result<SyncShardResponse, SyncShardServiceError>> + Send;
+
+    /// Moves the queued sync of the blob to the front of the blob sync queue.
+    fn promote_blob_sync(&self, blob_id: &BlobId) -> Result<(), PromoteBlobSyncError>;
 }
 
 /// Builder to construct a [`StorageNode`].
@@ -509,6 +519,7 @@ pub struct StorageNodeInner {
     node_capability: ObjectID,
     blob_retirement_notifier: Arc<BlobRetirementNotifier>,
     symbol_service: RecoverySymbolService,
+    blob_sync_queue: Arc<BlobSyncQueue>,
 }
 
 /// Parameters for configuring and initializing a node.
@@ -575,7 +586,17 @@ impl StorageNode {
         };
         tracing::info!("successfully opened the node database");
 
-        let blocklist: Arc<Blocklist> = Arc::new(Blocklist::new(&config.blocklist_path)?);
+        let blocklist: Arc<Blocklist> = Arc::new(Blocklist::new_with_config(
+            &config.blocklist_path,
+            &config.blocklist,
+            Some(registry),
+        )?);
+        let metrics = NodeMetricSet::new(registry);
+        let blob_sync_queue = Arc::new(BlobSyncQueue::new(
+            storage.clone(),
+            config.blob_recovery.max_concurrent_blob_syncs,
+            &metrics,
+        )?);
         let inner = Arc::new(StorageNodeInner {
             protocol_key_pair: config
                 .protocol_key_pair
@@ -587,7 +608,7 @@ impl StorageNode {
             contract_service: contract_service.clone(),
             current_epoch: watch::Sender::new(committee_service.get_epoch()),
             committee_service,
-            metrics: NodeMetricSet::new(registry),
+            metrics,
             start_time,
             is_shutting_down: false.into(),
             blocklist: blocklist.clone(),
@@ -603,6 +624,7 @@ impl StorageNode {
                 registry,
             ),
             encoding_config,
+            blob_sync_queue,
         });
 
         blocklist.start_refresh_task();
@@ -611,7 +633,6 @@ impl StorageNode {
 
         let blob_sync_handler = Arc::new(BlobSyncHandler::new(
             inner.clone(),
-            config.blob_recovery.max_concurrent_blob_syncs,
             config.blob_recovery.max_concurrent_sliver_syncs,
         ));
 
@@ -632,6 +653,8 @@ impl StorageNode {
         let node_recovery_handler =
             NodeRecoveryHandler::new(inner.clone(), blob_sync_handler.clone());
         node_recovery_handler.restart_recovery().await?;
+        // Resume the blob syncs that were queued before the restart.
+        blob_sync_handler.restore_queued_syncs().await?;
 
         // TODO(WAL-667): remove special case
         let num_checkpoints_per_blob = Self::get_num_checkpoints_per_blob(&config.sui).await?;
@@ -696,6 +719,12 @@ impl StorageNode {
             _ = cancel_token.cancelled() => {
                 self.inner.shut_down();
                 self.blob_sync_handler.cancel_all().await?;
+                if let Err(error) = self.inner.blob_sync_queue.persist_read_demand() {
+                    tracing::warn!(?error, "failed to persist blob sync read demand on shutdown");
+                }
+            },
+            () = self.inner.blob_sync_queue.clone().run_read_demand_persistence() => {
+                unreachable!("read demand persistence never completes");
             },
             blob_sync_result = self.blob_sync_handler.spawn_task_monitor() => {
                 match blob_sync_result {
@@ -1799,6 +1828,14 @@ impl StorageNodeInner {
         self.blocklist.is_blocked(blob_id)
     }
 
+    /// Counts a client request for the blob ID in the blocklist metrics if the blob ID is blocked.
+    ///
+    /// The request itself is rejected when the blob ID is checked by the service methods, which
+    /// are also used internally and therefore do not count blocked requests.
+    fn observe_client_request(&self, blob_id: &BlobId) {
+        let _ = self.blocklist.is_request_blocked(blob_id);
+    }
+
     async fn get_shard_for_sliver_pair(
         &self,
         sliver_pair_index: SliverPairIndex,
@@ -2040,6 +2077,7 @@ impl ServiceState for StorageNode {
         &self,
         blob_id: &BlobId,
     ) -> Result<VerifiedBlobMetadataWithId, RetrieveMetadataError> {
+        self.inner.observe_client_request(blob_id);
         self.inner.retrieve_metadata(blob_id)
     }
 
@@ -2063,6 +2101,7 @@ impl ServiceState for StorageNode {
         sliver_pair_index: SliverPairIndex,
         sliver_type: SliverType,
     ) -> impl Future<Output = Result<Sliver, RetrieveSliverError>> + Send {
+        self.inner.observe_client_request(blob_id);
         self.inner
             .retrieve_sliver(blob_id, sliver_pair_index, sliver_type)
     }
@@ -2102,6 +2141,7 @@ impl ServiceState for StorageNode {
         symbol_id: SymbolId,
         sliver_type: Option<SliverType>,
     ) -> impl Future<Output = Result<GeneralRecoverySymbol, RetrieveSymbolError>> + Send {
+        self.inner.observe_client_request(blob_id);
         self.inner
             .retrieve_recovery_symbol(blob_id, symbol_id, sliver_type)
     }
@@ -2111,6 +2151,7 @@ impl ServiceState for StorageNode {
         blob_id: &BlobId,
         filter: RecoverySymbolsFilter,
     ) -> impl Future<Output = Result<Vec<GeneralRecoverySymbol>, ListSymbolsError>> + Send {
+        self.inner.observe_client_request(blob_id);
         self.inner
             .retrieve_multiple_recovery_symbols(blob_id, filter)
     }
@@ -2119,6 +2160,10 @@ impl ServiceState for StorageNode {
         self.inner.blob_status(blob_id)
     }
 
+    fn blocklist_status(&self, blob_id: &BlobId) -> BlocklistStatus {
+        self.inner.blocklist_status(blob_id)
+    }
+
     fn n_shards(&self) -> NonZeroU16 {
         self.inner.n_shards()
     }
@@ -2142,6 +2187,10 @@ impl ServiceState for StorageNode {
     ) -> impl Future<Output = Result<SyncShardResponse, SyncShardServiceError>> + Send {
         self.inner.sync_shard(public_key, signed_request)
     }
+
+    fn promote_blob_sync(&self, blob_id: &BlobId) -> Result<(), PromoteBlobSyncError> {
+        self.inner.promote_blob_sync(blob_id)
+    }
 }
 
 impl ServiceState for StorageNodeInner {
@@ -2171,7 +2220,10 @@ impl ServiceState for StorageNodeInner {
         self.storage
             .get_metadata(blob_id)
             .context("database error when retrieving metadata")?
-            .ok_or(RetrieveMetadataError::Unavailable)
+            .ok_or_else(|| {
+                self.blob_sync_queue.record_read_demand(blob_id);
+                RetrieveMetadataError::Unavailable
+            })
             .inspect(|_| self.metrics.metadata_retrieved_total.inc())
     }
 
@@ -2252,7 +2304,10 @@ impl ServiceState for StorageNodeInner {
         shard_storage
             .get_sliver(blob_id, sliver_type)
             .context("unable to retrieve sliver")?
-            .ok_or(RetrieveSliverError::Unavailable)
+            .ok_or_else(|| {
+                self.blob_sync_queue.record_read_demand(blob_id);
+                RetrieveSliverError::Unavailable
+            })
             .inspect(|sliver| {
                 walrus_utils::with_label!(self.metrics.slivers_retrieved_total, sliver.r#type())
                     .inc();
@@ -2335,6 +2390,10 @@ impl ServiceState for StorageNodeInner {
             .unwrap_or_default())
     }
 
+    fn blocklist_status(&self, blob_id: &BlobId) -> BlocklistStatus {
+        self.blocklist.status(blob_id)
+    }
+
     async fn verify_inconsistency_proof(
         &self,
         blob_id: &BlobId,
@@ -2542,6 +2601,15 @@ impl ServiceState for StorageNodeInner {
             .handle_sync_shard_request(request, self.current_epoch())
             .await
     }
+
+    fn promote_blob_sync(&self, blob_id: &BlobId) -> Result<(), PromoteBlobSyncError> {
+        let promoted = self
+            .blob_sync_queue
+            .promote(blob_id)
+            .context("unable to persist the promoted blob sync")?;
+        ensure!(promoted, PromoteBlobSyncError::NotQueued);
+        Ok(())
+    }
 }
 
 #[tracing::instrument(skip_all, err)]
@@ -37,7 +37,7 @@ use super::{
     committee::CommitteeService,
     contract_service::SystemContractService,
     metrics::{self, NodeMetricSet, STATUS_IN_PROGRESS, STATUS_QUEUED},
-    storage::Storage,
+    storage::{blob_info::BlobInfoApi as _, Storage},
     system_events::{CompletableHandle, EventHandle},
     StorageNodeInner,
 };
@@ -45,7 +45,6 @@ use crate::common::utils::FutureHelpers as _;
 
 #[derive(Debug, Clone)]
 struct Permits {
-    blob: Arc<Semaphore>,
     sliver_pairs: Arc<Semaphore>,
 }
 
@@ -58,16 +57,13 @@ pub(crate) struct BlobSyncHandler {
 }
 
 impl BlobSyncHandler {
-    pub fn new(
-        node: Arc<StorageNodeInner>,
-        max_concurrent_blob_syncs: usize,
-        max_concurrent_sliver_syncs: usize,
-    ) -> Self {
+    /// Creates a new handler; the number of concurrent blob syncs is limited by the node's blob
+    /// sync queue.
+    pub fn new(node: Arc<StorageNodeInner>, max_concurrent_sliver_syncs: usize) -> Self {
         Self {
             blob_syncs_in_progress: Arc::default(),
             node,
             permits: Permits {
-                blob: Arc::new(Semaphore::new(max_concurrent_blob_syncs)),
                 sliver_pairs: Arc::new(Semaphore::new(max_concurrent_sliver_syncs)),
             },
         }
@@ -169,6 +165,7 @@ impl BlobSyncHandler {
         };
 
         handle.await?.mark_as_complete();
+        self.remove_from_queue(blob_id);
 
         Ok(())
     }
@@ -187,7 +184,7 @@ impl BlobSyncHandler {
     ) -> anyhow::Result<usize> {
         tracing::info!("cancelling all blob syncs for expired blobs");
 
-        let join_handles: Vec<_> = {
+        let (blob_ids, join_handles): (Vec<_>, Vec<_>) = {
             let mut in_progress_guard = self
                 .blob_syncs_in_progress
                 .lock()
@@ -200,15 +197,13 @@ impl BlobSyncHandler {
                     .is_ok_and(Not::not)
                     .then(|| sync.cancel())
                     .flatten()
+                    .map(|handle| (*blob_id, handle))
             };
 
             if cfg!(not(msim)) {
-                in_progress_guard
-                    .par_iter_mut()
-                    .filter_map(closure)
-                    .collect()
+                in_progress_guard.par_iter_mut().filter_map(closure).unzip()
             } else {
-                in_progress_guard.iter_mut().filter_map(closure).collect()
+                in_progress_guard.iter_mut().filter_map(closure).unzip()
             }
         };
         tracing::info!("released lock on the in-progress blob recoveries");
@@ -219,16 +214,49 @@ impl BlobSyncHandler {
             .await?
             .into_iter()
             .for_each(CompletableHandle::mark_as_complete);
+        blob_ids
+            .iter()
+            .for_each(|blob_id| self.remove_from_queue(blob_id));
 
         tracing::info!("cancelled {count} blob syncs for now expired blobs");
         Ok(count)
     }
 
-    async fn remove_sync_handle(&self, blob_id: &BlobId) {
+    // Removes the handle of the sync, and returns the event attached to it while it was running.
+    async fn remove_sync_handle(&self, blob_id: &BlobId) -> Option<EventHandle> {
         self.blob_syncs_in_progress
             .lock()
             .expect("should be able to acquire lock")
-            .remove(blob_id);
+            .remove(blob_id)
+            .and_then(|sync| sync.attached_event)
+    }
+
+    fn remove_from_queue(&self, blob_id: &BlobId) {
+        if let Err(error) = self.node.blob_sync_queue.remove(blob_id) {
+            tracing::warn!(?error, walrus.blob_id = %blob_id, "failed to remove queued blob sync");
+        }
+    }
+
+    /// Restarts the blob syncs persisted in the blob sync queue before the node was restarted.
+    ///
+    /// Syncs for blobs that are no longer certified are dropped from the queue. Returns the number
+    /// of restarted syncs.
+    #[tracing::instrument(skip_all)]
+    pub async fn restore_queued_syncs(&self) -> anyhow::Result<usize> {
+        let mut count = 0;
+        for (blob_id, entry) in self.node.blob_sync_queue.queued() {
+            if !self.node.is_blob_certified(&blob_id)? {
+                tracing::debug!(walrus.blob_id = %blob_id, "dropping queued sync for expired blob");
+                self.node.blob_sync_queue.remove(&blob_id)?;
+                continue;
+            }
+            self.start_sync(blob_id, entry.certified_epoch, None)
+                .await?;
+            count += 1;
+        }
+
+        tracing::info!("restarted {count} queued blob syncs");
+        Ok(count)
     }
 
     #[tracing::instrument(skip_all, fields(otel.kind = "PRODUCER"))]
@@ -246,6 +274,13 @@ impl BlobSyncHandler {
         let finish_notify = Arc::new(Notify::new());
         match in_progress.entry(blob_id) {
             Entry::Vacant(entry) => {
+                let holds_event = event_handle.is_some();
+                self.node.blob_sync_queue.enqueue(
+                    blob_id,
+                    certified_epoch,
+                    self.blob_end_epoch(&blob_id)?,
+                )?;
+
                 let spawned_trace = tracing::info_span!(
                     parent: &Span::current(),
                     "blob_sync",
@@ -288,19 +323,39 @@ impl BlobSyncHandler {
                 entry.insert(InProgressSyncHandle {
                     cancel_token,
                     blob_sync_handle: Some(sync_handle),
+                    holds_event,
+                    attached_event: None,
                 });
             }
-            Entry::Occupied(_) => {
-                // A blob sync with a lower sequence number is already in progress. We can safely
-                // try to increase the event cursor since it will only be advanced once that sync is
-                // finished or cancelled due to an invalid blob event.
-                event_handle.mark_as_complete();
+            Entry::Occupied(mut entry) => {
+                let sync = entry.get_mut();
+                if !sync.holds_event && sync.attached_event.is_none() {
+                    // The sync was started without an event, e.g., when it was restored from the
+                    // queue after a restart and the certification event is now replayed. The
+                    // event is only completed with the sync, such that the event cursor does not
+                    // move past it before the blob is synced.
+                    sync.attached_event = event_handle;
+                } else {
+                    // A blob sync with a lower sequence number is already in progress. We can
+                    // safely try to increase the event cursor since it will only be advanced once
+                    // that sync is finished or cancelled due to an invalid blob event.
+                    event_handle.mark_as_complete();
+                }
                 finish_notify.notify_one();
             }
         }
         Ok(finish_notify)
     }
 
+    // Returns the latest end epoch of the certified permanent and deletable `Blob` objects.
+    fn blob_end_epoch(&self, blob_id: &BlobId) -> Result<Option<Epoch>, TypedStoreError> {
+        Ok(self
+            .node
+            .storage
+            .get_blob_info(blob_id)?
+            .and_then(|blob_info| blob_info.certified_end_epoch()))
+    }
+
     #[tracing::instrument(skip_all)]
     pub async fn sync_blob_for_all_shards(
         self,
@@ -328,12 +383,12 @@ impl BlobSyncHandler {
             guard = async {
                 // Await claiming the permit inside this async closure, to enable cancellation to
                 // also cancel waiting for the permit.
-                let _permit = permits
-                    .blob
-                    .acquire_owned()
+                let _permit = self
+                    .node
+                    .blob_sync_queue
+                    .acquire(blob_id)
                     .count_in_flight(&queued_gauge)
-                    .await
-                    .expect("semaphore should not be dropped");
+                    .await;
 
                 let decrement_guard = GaugeGuard::acquire(&in_progress_gauge);
 
@@ -343,12 +398,18 @@ impl BlobSyncHandler {
             } => {
                 event_handle.mark_as_complete();
                 event_handle = None;
+                self.remove_from_queue(&blob_id);
                 (metrics::STATUS_SUCCESS, Some(guard))
             }
         };
 
         // We remove the blob handler regardless of the result.
-        self.remove_sync_handle(&blob_id).await;
+        let attached_event = self.remove_sync_handle(&blob_id).await;
+        if label == metrics::STATUS_SUCCESS {
+            attached_event.mark_as_complete();
+        } else {
+            event_handle = event_handle.or(attached_event);
+        }
 
         walrus_utils::with_label!(self.node.metrics.recover_blob_duration_seconds, label)
             .observe(start.elapsed().as_secs_f64());
@@ -380,6 +441,10 @@ type SyncJoinHandle = JoinHandle<Option<EventHandle>>;
 struct InProgressSyncHandle {
     cancel_token: CancellationToken,
     blob_sync_handle: Option<SyncJoinHandle>,
+    // Whether the sync was started by an event, which it completes when it finishes.
+    holds_event: bool,
+    // An event for the blob received while a sync without an event is running.
+    attached_event: Option<EventHandle>,
 }
 
 impl InProgressSyncHandle {
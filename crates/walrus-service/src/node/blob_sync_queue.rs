@@ -0,0 +1,500 @@
+// Copyright (c) Walrus Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Persistent priority queue for blob syncs.
+//!
+//! Every blob sync started by the [`BlobSyncHandler`][super::blob_sync::BlobSyncHandler] is
+//! recorded in the node database until it completes or is cancelled because the blob is no longer
+//! certified. Sync permits are handed out in priority order: manually promoted syncs first, then
+//! syncs for blobs with the highest unserved read demand, then the blobs certified earliest and,
+//! among those, the blobs expiring first.
+//!
+//! Read demand is counted in memory on the read path and persisted in periodic batches.
+
+use std::{
+    cmp::Reverse,
+    collections::{BTreeSet, HashMap, HashSet},
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
+};
+
+use prometheus::{IntCounter, IntGauge};
+use tokio::sync::{oneshot, OwnedSemaphorePermit, Semaphore};
+use typed_store::TypedStoreError;
+use walrus_core::{BlobId, Epoch};
+
+use super::{
+    metrics::{NodeMetricSet, STATUS_PERSISTED, STATUS_QUEUED},
+    storage::{QueuedBlobSync, Storage},
+};
+
+/// The interval at which the read demand recorded in memory is persisted.
+const READ_DEMAND_PERSIST_INTERVAL: Duration = Duration::from_secs(10);
+
+/// The priority of a queued blob sync; smaller values are dispatched first.
+type SyncPriority = (Reverse<bool>, Reverse<u64>, Epoch, Epoch, u64);
+
+/// The priority used for syncs that wait for a permit without a queue entry.
+const LOWEST_PRIORITY: SyncPriority =
+    (Reverse(false), Reverse(0), Epoch::MAX, Epoch::MAX, u64::MAX);
+
+fn priority(entry: &QueuedBlobSync) -> SyncPriority {
+    (
+        Reverse(entry.promoted),
+        Reverse(entry.read_demand),
+        entry.certified_epoch,
+        // Blobs with an unknown end epoch are synced after all blobs with a known one.
+        entry.end_epoch.unwrap_or(Epoch::MAX),
+        entry.sequence,
+    )
+}
+
+#[derive(Debug, Default)]
+struct QueueState {
+    entries: HashMap<BlobId, QueuedBlobSync>,
+    // Syncs waiting for a permit, ordered by their priority at the time they were last (re-)queued.
+    waiting: BTreeSet<(SyncPriority, BlobId)>,
+    waiters: HashMap<BlobId, (SyncPriority, oneshot::Sender<BlobSyncPermit>)>,
+    // Entries whose read demand changed since it was last persisted.
+    unpersisted_read_demand: HashSet<BlobId>,
+    next_sequence: u64,
+}
+
+impl QueueState {
+    fn reprioritize(&mut self, blob_id: &BlobId) {
+        let Some(new_priority) = self.entries.get(blob_id).map(priority) else {
+            return;
+        };
+        if let Some((current_priority, _)) = self.waiters.get_mut(blob_id) {
+            self.waiting.remove(&(*current_priority, *blob_id));
+            self.waiting.insert((new_priority, *blob_id));
+            *current_priority = new_priority;
+        }
+    }
+}
+
+/// Queue of blob syncs persisted in the node database, limiting the number of concurrent syncs.
+#[derive(Debug)]
+pub(crate) struct BlobSyncQueue {
+    storage: Storage,
+    permits: Arc<Semaphore>,
+    state: Mutex<QueueState>,
+    // Serializes the database writes, so that persisting the read demand cannot overwrite the
+    // result of a concurrent removal or promotion with an outdated entry.
+    write_lock: Mutex<()>,
+    persisted_gauge: IntGauge,
+    queued_gauge: IntGauge,
+    read_demand_total: IntCounter,
+    promotions_total: IntCounter,
+}
+
+impl BlobSyncQueue {
+    /// Creates a new queue allowing `max_concurrent_blob_syncs` syncs to run concurrently,
+    /// restoring any entries persisted in the `storage`.
+    pub fn new(
+        storage: Storage,
+        max_concurrent_blob_syncs: usize,
+        metrics: &NodeMetricSet,
+    ) -> Result<Self, TypedStoreError> {
+        let entries: HashMap<_, _> = storage.queued_blob_syncs()?.into_iter().collect();
+        let next_sequence = entries
+            .values()
+            .map(|entry| entry.sequence + 1)
+            .max()
+            .unwrap_or_default();
+        tracing::info!(
+            queued_blob_syncs = entries.len(),
+            "restored the blob sync queue from the database"
+        );
+
+        let queue = Self {
+            storage,
+            permits: Arc::new(Semaphore::new(max_concurrent_blob_syncs)),
+            state: Mutex::new(QueueState {
+                entries,
+                next_sequence,
+                ..Default::default()
+            }),
+            write_lock: Mutex::new(()),
+            persisted_gauge: walrus_utils::with_label!(
+                metrics.blob_sync_queue_length,
+                STATUS_PERSISTED
+            ),
+            queued_gauge: walrus_utils::with_label!(metrics.blob_sync_queue_length, STATUS_QUEUED),
+            read_demand_total: metrics.blob_sync_queue_read_demand_total.clone(),
+            promotions_total: metrics.blob_sync_queue_promotions_total.clone(),
+        };
+        queue.update_gauges(&queue.lock_state());
+
+        Ok(queue)
+    }
+
+    /// Adds a sync for the blob to the queue, unless one is already queued.
+    pub fn enqueue(
+        &self,
+        blob_id: BlobId,
+        certified_epoch: Epoch,
+        end_epoch: Option<Epoch>,
+    ) -> Result<(), TypedStoreError> {
+        let _write_guard = self.lock_writes();
+        let mut state = self.lock_state();
+        if state.entries.contains_key(&blob_id) {
+            return Ok(());
+        }
+
+        let entry = QueuedBlobSync {
+            certified_epoch,
+            end_epoch,
+            sequence: state.next_sequence,
+            read_demand: 0,
+            promoted: false,
+        };
+        self.storage.put_queued_blob_sync(&blob_id, &entry)?;
+        state.next_sequence += 1;
+        state.entries.insert(blob_id, entry);
+        self.update_gauges(&state);
+
+        Ok(())
+    }
+
+    /// Removes the sync for the blob from the queue.
+    ///
+    /// This does not affect a sync that is already waiting for or holding a permit.
+    pub fn remove(&self, blob_id: &BlobId) -> Result<(), TypedStoreError> {
+        let _write_guard = self.lock_writes();
+        let mut state = self.lock_state();
+        state.unpersisted_read_demand.remove(blob_id);
+        if state.entries.remove(blob_id).is_some() {
+            self.storage.remove_queued_blob_sync(blob_id)?;
+        }
+        self.update_gauges(&state);
+        Ok(())
+    }
+
+    /// Moves the sync for the blob to the front of the queue.
+    ///
+    /// Returns `false` if no sync is queued for the blob.
+    pub fn promote(&self, blob_id: &BlobId) -> Result<bool, TypedStoreError> {
+        let _write_guard = self.lock_writes();
+        let mut state = self.lock_state();
+        let Some(entry) = state.entries.get_mut(blob_id) else {
+            return Ok(false);
+        };
+        if !entry.promoted {
+            entry.promoted = true;
+            self.storage.put_queued_blob_sync(blob_id, entry)?;
+            state.reprioritize(blob_id);
+            self.promotions_total.inc();
+            tracing::info!(walrus.blob_id = %blob_id, "promoted blob sync");
+        }
+        Ok(true)
+    }
+
+    /// Records a read for the blob that could not be served, if a sync for it is queued.
+    ///
+    /// Syncs for blobs with higher read demand are dispatched earlier. The demand is only counted
+    /// in memory here and persisted by [`Self::persist_read_demand`].
+    pub fn record_read_demand(&self, blob_id: &BlobId) {
+        let mut state = self.lock_state();
+        let Some(entry) = state.entries.get_mut(blob_id) else {
+            return;
+        };
+        entry.read_demand = entry.read_demand.saturating_add(1);
+        state.unpersisted_read_demand.insert(*blob_id);
+        state.reprioritize(blob_id);
+        self.read_demand_total.inc();
+    }
+
+    /// Writes the read demand recorded since the last call to the database in a single batch.
+    pub fn persist_read_demand(&self) -> Result<(), TypedStoreError> {
+        let _write_guard = self.lock_writes();
+        let updates: Vec<_> = {
+            let mut state = self.lock_state();
+            std::mem::take(&mut state.unpersisted_read_demand)
+                .into_iter()
+                .filter_map(|blob_id| {
+                    state
+                        .entries
+                        .get(&blob_id)
+                        .map(|entry| (blob_id, entry.clone()))
+                })
+                .collect()
+        };
+        if updates.is_empty() {
+            return Ok(());
+        }
+        tracing::debug!(count = updates.len(), "persisting blob sync read demand");
+        self.storage.put_queued_blob_syncs(&updates)
+    }
+
+    /// Periodically persists the recorded read demand; never completes.
+    pub async fn run_read_demand_persistence(self: Arc<Self>) {
+        let mut interval = tokio::time::interval(READ_DEMAND_PERSIST_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            let queue = self.clone();
+            match tokio::task::spawn_blocking(move || queue.persist_read_demand()).await {
+                Ok(Ok(())) => (),
+                Ok(Err(error)) => {
+                    tracing::warn!(?error, "failed to persist blob sync read demand")
+                }
+                Err(error) => tracing::warn!(?error, "read demand persistence task failed"),
+            }
+        }
+    }
+
+    /// Returns the queued blob syncs, in the order in which they would be dispatched.
+    pub fn queued(&self) -> Vec<(BlobId, QueuedBlobSync)> {
+        let state = self.lock_state();
+        let mut queued: Vec<_> = state
+            .entries
+            .iter()
+            .map(|(blob_id, entry)| (*blob_id, entry.clone()))
+            .collect();
+        queued.sort_by_key(|(blob_id, entry)| (priority(entry), *blob_id));
+        queued
+    }
+
+    /// Waits until the sync for the blob is granted a permit.
+    ///
+    /// The sync keeps running until the returned permit is dropped.
+    pub async fn acquire(self: &Arc<Self>, blob_id: BlobId) -> BlobSyncPermit {
+        loop {
+            let receiver = {
+                let mut state = self.lock_state();
+                let sync_priority = state
+                    .entries
+                    .get(&blob_id)
+                    .map(priority)
+                    .unwrap_or(LOWEST_PRIORITY);
+                let (sender, receiver) = oneshot::channel();
+                if let Some((previous, _)) = state.waiters.insert(blob_id, (sync_priority, sender))
+                {
+                    state.waiting.remove(&(previous, blob_id));
+                }
+                state.waiting.insert((sync_priority, blob_id));
+                self.dispatch(&mut state);
+                receiver
+            };
+
+            // The sender is only dropped without sending if another sync for the same blob
+            // replaced this waiter, in which case we simply queue up again.
+            if let Ok(permit) = receiver.await {
+                return permit;
+            }
+        }
+    }
+
+    /// Hands out available permits to the waiting syncs with the highest priority.
+    fn dispatch(self: &Arc<Self>, state: &mut QueueState) {
+        while let Some(&(_, blob_id)) = state.waiting.first() {
+            // Syncs that were cancelled while waiting no longer need a permit.
+            if state
+                .waiters
+                .get(&blob_id)
+                .is_none_or(|(_, sender)| sender.is_closed())
+            {
+                state.waiting.pop_first();
+                state.waiters.remove(&blob_id);
+                continue;
+            }
+
+            let Ok(permit) = self.permits.clone().try_acquire_owned() else {
+                break;
+            };
+            state.waiting.pop_first();
+            let (_, sender) = state
+                .waiters
+                .remove(&blob_id)
+                .expect("waiting syncs have a waiter");
+            let permit = BlobSyncPermit {
+                permit: Some(permit),
+                queue: self.clone(),
+            };
+            if let Err(mut permit) = sender.send(permit) {
+                // The receiver was closed in the meantime. Return the permit to the semaphore
+                // without re-entering the dispatch, as we already hold the lock.
+                permit.permit.take();
+            }
+        }
+        self.update_gauges(state);
+    }
+
+    fn update_gauges(&self, state: &QueueState) {
+        self.persisted_gauge
+            .set(i64::try_from(state.entries.len()).unwrap_or(i64::MAX));
+        self.queued_gauge
+            .set(i64::try_from(state.waiting.len()).unwrap_or(i64::MAX));
+    }
+
+    fn lock_state(&self) -> MutexGuard<'_, QueueState> {
+        self.state.lock().expect("should be able to acquire lock")
+    }
+
+    fn lock_writes(&self) -> MutexGuard<'_, ()> {
+        self.write_lock
+            .lock()
+            .expect("should be able to acquire lock")
+    }
+}
+
+/// A permit allowing a single blob sync to run; the next queued sync is dispatched on drop.
+///
+/// Permits that are granted to a sync after it was cancelled are dropped without being received,
+/// which equally dispatches the next queued sync.
+#[derive(Debug)]
+pub(crate) struct BlobSyncPermit {
+    permit: Option<OwnedSemaphorePermit>,
+    queue: Arc<BlobSyncQueue>,
+}
+
+impl Drop for BlobSyncPermit {
+    fn drop(&mut self) {
+        if self.permit.take().is_some() {
+            let mut state = self.queue.lock_state();
+            self.queue.dispatch(&mut state);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use prometheus::Registry;
+    use walrus_test_utils::{Result as TestResult, WithTempDir};
+
+    use super::*;
+    use crate::test_utils::empty_storage_with_shards;
+
+    const TIMEOUT: Duration = Duration::from_secs(1);
+
+    async fn queue(max_concurrent_blob_syncs: usize) -> WithTempDir<Arc<BlobSyncQueue>> {
+        typed_store::metrics::DBMetrics::init(&Registry::new());
+        let storage = empty_storage_with_shards(&[]).await;
+        let metrics = NodeMetricSet::new(&Registry::new());
+        storage.map(|storage| {
+            Arc::new(
+                BlobSyncQueue::new(storage, max_concurrent_blob_syncs, &metrics)
+                    .expect("queue creation must succeed"),
+            )
+        })
+    }
+
+    fn blob_id(seed: u8) -> BlobId {
+        BlobId([seed; 32])
+    }
+
+    fn queued_ids(queue: &BlobSyncQueue) -> Vec<BlobId> {
+        queue
+            .queued()
+            .into_iter()
+            .map(|(blob_id, _)| blob_id)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn orders_by_promotion_demand_certification_and_expiry() -> TestResult {
+        let queue = queue(1).await;
+        let queue = queue.as_ref();
+
+        queue.enqueue(blob_id(1), 2, Some(10))?;
+        queue.enqueue(blob_id(2), 1, Some(10))?;
+        queue.enqueue(blob_id(3), 1, Some(20))?;
+        queue.enqueue(blob_id(4), 3, None)?;
+        assert_eq!(
+            queued_ids(queue),
+            [blob_id(2), blob_id(3), blob_id(1), blob_id(4)]
+        );
+
+        queue.record_read_demand(&blob_id(1));
+        assert_eq!(
+            queued_ids(queue),
+            [blob_id(1), blob_id(2), blob_id(3), blob_id(4)]
+        );
+
+        assert!(queue.promote(&blob_id(4))?);
+        assert!(!queue.promote(&blob_id(5))?);
+        assert_eq!(
+            queued_ids(queue),
+            [blob_id(4), blob_id(1), blob_id(2), blob_id(3)]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn dispatches_waiting_syncs_in_priority_order() -> TestResult {
+        let queue = queue(1).await;
+        let queue = queue.as_ref();
+
+        for seed in 1..=3 {
+            queue.enqueue(blob_id(seed), u32::from(seed), None)?;
+        }
+        let first = queue.acquire(blob_id(1)).await;
+
+        let second = tokio::spawn({
+            let queue = queue.clone();
+            async move { queue.acquire(blob_id(2)).await }
+        });
+        let third = tokio::spawn({
+            let queue = queue.clone();
+            async move { queue.acquire(blob_id(3)).await }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(queue.promote(&blob_id(3))?);
+
+        drop(first);
+        let third = tokio::time::timeout(TIMEOUT, third).await??;
+        assert!(!second.is_finished());
+
+        drop(third);
+        tokio::time::timeout(TIMEOUT, second).await??;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn restores_entries_from_storage() -> TestResult {
+        let queue = queue(1).await;
+
+        queue.as_ref().enqueue(blob_id(1), 1, Some(5))?;
+        queue.as_ref().enqueue(blob_id(2), 1, Some(5))?;
+        queue.as_ref().enqueue(blob_id(3), 1, Some(5))?;
+        queue.as_ref().record_read_demand(&blob_id(2));
+        queue.as_ref().record_read_demand(&blob_id(1));
+        queue.as_ref().remove(&blob_id(1))?;
+        queue.as_ref().persist_read_demand()?;
+
+        let restored = BlobSyncQueue::new(
+            queue.as_ref().storage.clone(),
+            1,
+            &NodeMetricSet::new(&Registry::new()),
+        )?;
+        assert_eq!(queued_ids(&restored), [blob_id(2), blob_id(3)]);
+
+        restored.enqueue(blob_id(4), 1, Some(5))?;
+        assert_eq!(restored.queued()[2].1.sequence, 3);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn read_demand_is_only_persisted_in_batches() -> TestResult {
+        let queue = queue(1).await;
+        let queue = queue.as_ref();
+        queue.enqueue(blob_id(1), 1, Some(5))?;
+
+        queue.record_read_demand(&blob_id(1));
+        queue.record_read_demand(&blob_id(1));
+        let persisted_demand =
+            || -> TestResult<u64> { Ok(queue.storage.queued_blob_syncs()?[0].1.read_demand) };
+        assert_eq!(persisted_demand()?, 0);
+
+        queue.persist_read_demand()?;
+        assert_eq!(persisted_demand()?, 2);
+
+        Ok(())
+    }
+}
@@ -42,7 +42,11 @@ use walrus_sui::types::{
 
 use super::storage::DatabaseConfig;
 use crate::{
-    common::{config::SuiConfig, utils},
+    common::{
+        blocklist::BlocklistConfig,
+        config::{SuiConfig, TraceExportConfig},
+        utils,
+    },
     node::events::EventProcessorConfig,
 };
 
@@ -81,6 +85,9 @@ pub struct StorageNodeConfig {
     /// File path to the blocklist.
     #[serde(default, skip_serializing_if = "defaults::is_none")]
     pub blocklist_path: Option<PathBuf>,
+    /// Allow list and remote deny lists that are merged with the blocklist at `blocklist_path`.
+    #[serde(default, skip_serializing_if = "defaults::is_default")]
+    pub blocklist: BlocklistConfig,
     /// Optional "config" to tune storage database.
     #[serde(default, skip_serializing_if = "defaults::is_default")]
     pub db_config: DatabaseConfig,
@@ -155,6 +162,9 @@ pub struct StorageNodeConfig {
     /// Metric push configuration.
     #[serde(default, skip_serializing_if = "defaults::is_none")]
     pub metrics_push: Option<MetricsPushConfig>,
+    /// Configuration for exporting traces to an OpenTelemetry collector.
+    #[serde(default, skip_serializing_if = "defaults::is_none")]
+    pub trace_export: Option<TraceExportConfig>,
     /// Configuration for the config synchronizer.
     #[serde(default, skip_serializing_if = "defaults::is_default")]
     pub config_synchronizer: ConfigSynchronizerConfig,
@@ -178,6 +188,7 @@ impl Default for StorageNodeConfig {
         Self {
             storage_path: PathBuf::from("/opt/walrus/db"),
             blocklist_path: Default::default(),
+            blocklist: Default::default(),
             db_config: Default::default(),
             protocol_key_pair: PathOrInPlace::from_path("/opt/walrus/config/protocol.key"),
             next_protocol_key_pair: None,
@@ -203,6 +214,7 @@ impl Default for StorageNodeConfig {
             },
             name: Default::default(),
             metrics_push: None,
+            trace_export: None,
             metadata: Default::default(),
             config_synchronizer: Default::default(),
             storage_node_cap: None,
@@ -941,12 +953,29 @@ pub struct NodeRegistrationParamsForThirdPartyRegistration {
 }
 
 /// Configuration for the REST server.
-#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
 #[serde(default)]
 pub struct RestServerConfig {
     /// Configuration for incoming HTTP/2 connections.
     #[serde(flatten, skip_serializing_if = "defaults::is_default")]
     pub http2_config: Http2Config,
+    /// The bearer token required by the admin endpoints, such as promoting a queued blob sync.
+    ///
+    /// If unset, the admin endpoints are disabled.
+    #[serde(skip_serializing_if = "defaults::is_none")]
+    pub admin_token: Option<String>,
+}
+
+impl std::fmt::Debug for RestServerConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RestServerConfig")
+            .field("http2_config", &self.http2_config)
+            .field(
+                "admin_token",
+                &self.admin_token.as_ref().map(|_| "<redacted>"),
+            )
+            .finish()
+    }
 }
 
 /// Configuration of the HTTP/2 connections established by the REST API.
@@ -1052,6 +1081,8 @@ mod tests {
                 backoff_config: Default::default(),
                 gas_budget: None,
                 rpc_fallback_config: None,
+                additional_rpc_endpoints: Default::default(),
+                rpc_failover_config: Default::default(),
             }),
             config_synchronizer: ConfigSynchronizerConfig {
                 interval: Duration::from_secs(defaults::CONFIG_SYNCHRONIZER_INTERVAL_SECS),
@@ -3,7 +3,7 @@
 
 //! Tools for inspecting and maintaining the RocksDB database.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::Result;
 use bincode::Options;
@@ -14,6 +14,7 @@ use serde_with::serde_as;
 use sui_types::base_types::ObjectID;
 use typed_store::rocks::be_fix_int_ser;
 use walrus_core::{BlobId, BlobMetadata, Epoch, ShardIndex};
+use walrus_sui::types::{BlobEvent, ContractEvent};
 
 use crate::node::{
     events::{
@@ -28,6 +29,7 @@ use crate::node::{
             PendingEventBlobMetadata,
         },
         event_processor::event_store_cf_name,
+        EventStreamElement,
         PositionedStreamEvent,
     },
     storage::{
@@ -316,6 +318,36 @@ fn scan_events(db_path: PathBuf, start_event_index: u64, count: u64) -> Result<(
     Ok(())
 }
 
+/// Returns the blob events in the event store of the database at `db_path` that match the
+/// `filter`, in the order in which they were processed.
+///
+/// Note that the event store only contains the events that have not yet been pruned after being
+/// included in a certified event blob.
+pub fn read_blob_events(
+    db_path: &Path,
+    filter: impl Fn(&BlobEvent) -> bool,
+) -> Result<Vec<BlobEvent>> {
+    let opts = RocksdbOptions::default();
+    let db = DB::open_cf_for_read_only(&opts, db_path, [event_store_cf_name()], false)?;
+    let cf = db
+        .cf_handle(event_store_cf_name())
+        .expect("Event store column family should exist");
+
+    let mut events = vec![];
+    for entry in db.iterator_cf(&cf, rocksdb::IteratorMode::Start) {
+        let (_, value) = entry?;
+        let event: PositionedStreamEvent = bcs::from_bytes(&value)?;
+        if let EventStreamElement::ContractEvent(ContractEvent::BlobEvent(blob_event)) =
+            event.element
+        {
+            if filter(&blob_event) {
+                events.push(blob_event);
+            }
+        }
+    }
+    Ok(events)
+}
+
 fn read_blob_info(db_path: PathBuf, start_blob_id: Option<BlobId>, count: u64) -> Result<()> {
     let blob_info_options = blob_info_cf_options(&DatabaseConfig::default());
     let db = DB::open_cf_with_opts_for_read_only(
@@ -319,6 +319,20 @@ pub enum BlobStatusError {
     Internal(#[from] InternalError),
 }
 
+#[derive(Debug, thiserror::Error, RestApiError)]
+#[rest_api_error(domain = ERROR_DOMAIN)]
+pub enum PromoteBlobSyncError {
+    /// No sync is queued for the blob at this storage node, either because the blob is already
+    /// fully stored or because it is not certified.
+    #[error("no sync is queued for the blob")]
+    #[rest_api_error(reason = "BLOB_SYNC_NOT_QUEUED", status = ApiStatusCode::NotFound)]
+    NotQueued,
+
+    #[error(transparent)]
+    #[rest_api_error(delegate)]
+    Internal(#[from] InternalError),
+}
+
 /// Error returned when the epoch in a request is invalid.
 #[derive(Debug, Clone, thiserror::Error, Serialize, RestApiError)]
 #[error("the request's epoch ({request_epoch}) is invalid, server epoch {server_epoch}")]
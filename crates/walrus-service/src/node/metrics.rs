@@ -92,6 +92,16 @@ walrus_utils::metrics::define_metric_set! {
         #[help = "The number of blob recoveries currently pending"]
         recover_blob_backlog: IntGaugeVec["state"],
 
+        #[help = "The number of blob syncs in the persistent blob sync queue, either persisted in \
+        total or queued waiting for a sync permit"]
+        blob_sync_queue_length: IntGaugeVec["state"],
+
+        #[help = "The total number of reads for blobs whose sync was still queued"]
+        blob_sync_queue_read_demand_total: IntCounter[],
+
+        #[help = "The total number of blob syncs promoted manually in the blob sync queue"]
+        blob_sync_queue_promotions_total: IntCounter[],
+
         #[help = "Time (in seconds) spent processing events"]
         event_process_duration_seconds: HistogramVec["event_type"],
 
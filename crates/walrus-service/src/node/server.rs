@@ -67,6 +67,11 @@ pub struct RestApiConfig {
 
     /// Configuration of HTTP/2 connections.
     pub http2_config: Http2Config,
+
+    /// The bearer token required by the admin endpoints.
+    ///
+    /// If None, the admin endpoints are disabled.
+    pub admin_token: Option<String>,
 }
 
 impl From<&StorageNodeConfig> for RestApiConfig {
@@ -105,6 +110,7 @@ impl From<&StorageNodeConfig> for RestApiConfig {
             tls_certificate,
             graceful_shutdown_period,
             http2_config: config.rest_server.http2_config.clone(),
+            admin_token: config.rest_server.admin_token.clone(),
         }
     }
 }
@@ -328,7 +334,7 @@ where
     }
 
     fn define_routes(&self) -> Router<Arc<S>> {
-        Router::new()
+        let router = Router::new()
             .merge(Redoc::with_url(
                 routes::API_DOCS_ENDPOINT,
                 RestApiDoc::openapi(),
@@ -385,8 +391,24 @@ where
                 post(routes::inconsistency_proof),
             )
             .route(routes::BLOB_STATUS_ENDPOINT, get(routes::get_blob_status))
+            .route(
+                routes::BLOCKLIST_STATUS_ENDPOINT,
+                get(routes::get_blocklist_status),
+            )
             .route(routes::HEALTH_ENDPOINT, get(routes::health_info))
-            .route(routes::SYNC_SHARD_ENDPOINT, post(routes::sync_shard))
+            .route(routes::SYNC_SHARD_ENDPOINT, post(routes::sync_shard));
+
+        let Some(admin_token) = self.config.admin_token.as_deref() else {
+            tracing::info!("no admin token configured, the admin endpoints are disabled");
+            return router;
+        };
+        router.route(
+            routes::BLOB_SYNC_PROMOTE_ENDPOINT,
+            post(routes::promote_blob_sync).route_layer(middleware::from_fn_with_state(
+                Arc::<str>::from(admin_token),
+                routes::require_admin_token,
+            )),
+        )
     }
 }
 
@@ -474,12 +496,14 @@ mod tests {
 
     use super::*;
     use crate::{
+        common::blocklist::{BlocklistStatus, LOCAL_BLOCKLIST_NAME},
         node::{
             config::StorageNodeConfig,
             errors::ListSymbolsError,
             BlobStatusError,
             ComputeStorageConfirmationError,
             InconsistencyProofError,
+            PromoteBlobSyncError,
             RetrieveMetadataError,
             RetrieveSliverError,
             RetrieveSymbolError,
@@ -637,6 +661,14 @@ mod tests {
             }
         }
 
+        /// Returns that blob IDs starting with zero are blocked by the local deny list.
+        fn blocklist_status(&self, blob_id: &BlobId) -> BlocklistStatus {
+            BlocklistStatus {
+                blob_id: *blob_id,
+                blocked_by: (blob_id.0[0] == 0).then(|| LOCAL_BLOCKLIST_NAME.to_owned()),
+            }
+        }
+
         async fn sliver_status<A: EncodingAxis>(
             &self,
             _blob_id: &BlobId,
@@ -690,6 +722,15 @@ mod tests {
         ) -> Result<SyncShardResponse, SyncShardServiceError> {
             Ok(SyncShardResponse::V1(vec![]))
         }
+
+        /// Successfully promotes syncs only for blob IDs with the first byte 0.
+        fn promote_blob_sync(&self, blob_id: &BlobId) -> Result<(), PromoteBlobSyncError> {
+            if blob_id.0[0] == 0 {
+                Ok(())
+            } else {
+                Err(PromoteBlobSyncError::NotQueued)
+            }
+        }
     }
 
     async fn start_rest_api_with_config(
@@ -862,6 +903,32 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn get_blocklist_status() -> TestResult {
+        let (config, _handle) = start_rest_api_with_test_config().await;
+        let client = storage_node_client(config.as_ref()).into_inner();
+
+        for (blob_id, blocked_by) in [
+            (blob_id_for_valid_response(), Some(LOCAL_BLOCKLIST_NAME)),
+            (blob_id_for_nonexistent(), None),
+        ] {
+            let path = routes::BLOCKLIST_STATUS_ENDPOINT.replace("{blob_id}", &blob_id.to_string());
+            let url = format!("https://{}{path}", config.as_ref().rest_api_address);
+            let response = client.get(url).send().await?;
+            assert_eq!(response.status(), StatusCode::OK);
+
+            let body: serde_json::Value = serde_json::from_slice(&response.bytes().await?)?;
+            assert_eq!(
+                body["success"]["data"],
+                serde_json::json!({
+                    "blobId": blob_id.to_string(),
+                    "blockedBy": blocked_by,
+                })
+            );
+        }
+        Ok(())
+    }
+
     #[tokio::test]
     async fn store_metadata() {
         let (config, _handle) = start_rest_api_with_test_config().await;
@@ -1084,6 +1151,54 @@ mod tests {
         assert_eq!(err.http_status_code(), Some(StatusCode::NOT_FOUND));
     }
 
+    #[tokio::test]
+    async fn promote_blob_sync_requires_admin_token() -> TestResult {
+        const ADMIN_TOKEN: &str = "admin-token";
+
+        let mut config = test_utils::storage_node_config();
+        config.as_mut().tls.disable_tls = true;
+        config.as_mut().rest_server.admin_token = Some(ADMIN_TOKEN.to_owned());
+        let _handle = start_rest_api_with_config(config.as_ref()).await;
+
+        let promote_url = |blob_id: BlobId| {
+            format!(
+                "http://{}{}",
+                config.as_ref().rest_api_address,
+                routes::BLOB_SYNC_PROMOTE_ENDPOINT.replace("{blob_id}", &blob_id.to_string())
+            )
+        };
+        let client = reqwest::Client::builder().no_proxy().build()?;
+
+        let response = client
+            .post(promote_url(blob_id_for_valid_response()))
+            .send()
+            .await?;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = client
+            .post(promote_url(blob_id_for_valid_response()))
+            .bearer_auth("wrong-token")
+            .send()
+            .await?;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = client
+            .post(promote_url(blob_id_for_valid_response()))
+            .bearer_auth(ADMIN_TOKEN)
+            .send()
+            .await?;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = client
+            .post(promote_url(blob_id_for_nonexistent()))
+            .bearer_auth(ADMIN_TOKEN)
+            .send()
+            .await?;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+
     mod tls {
         use walrus_sdk::error::NodeError;
 
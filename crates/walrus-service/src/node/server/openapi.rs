@@ -24,6 +24,7 @@ pub(super) const GROUP_SYNC_SHARD: &str = "Sync Shard";
 #[openapi(
     paths(
         routes::get_blob_status,
+        routes::get_blocklist_status,
         routes::get_deletable_blob_confirmation,
         routes::get_metadata,
         routes::get_permanent_blob_confirmation,
@@ -72,12 +73,16 @@ mod tests {
         let mut spec = RestApiDoc::openapi();
         spec.info.version = "<VERSION>".to_string();
 
-        std::fs::write(NODE_OPENAPI_HTML_PATH, Redoc::new(spec.clone()).to_html())?;
-
+        // Update both files before failing, such that a single run brings them in sync.
+        let html_result = walrus_test_utils::overwrite_file_and_fail_if_not_equal(
+            NODE_OPENAPI_HTML_PATH,
+            Redoc::new(spec.clone()).to_html(),
+        );
         walrus_test_utils::overwrite_file_and_fail_if_not_equal(
             NODE_OPENAPI_SPEC_PATH,
             spec.to_yaml()?,
         )?;
+        html_result?;
 
         Ok(())
     }
@@ -4,13 +4,19 @@
 use std::{num::NonZeroU16, sync::Arc};
 
 use axum::{
-    extract::{Path, Query, State},
+    extract::{Path, Query, Request, State},
     http::StatusCode,
+    middleware::Next,
     response::{IntoResponse, Response},
 };
-use axum_extra::extract::Query as ExtraQuery;
+use axum_extra::{
+    extract::Query as ExtraQuery,
+    headers::{authorization::Bearer, Authorization as AuthorizationHeader},
+    TypedHeader,
+};
 use serde::Deserialize;
 use serde_with::{serde_as, DisplayFromStr, OneOrMany};
+use subtle::ConstantTimeEq as _;
 use sui_types::base_types::ObjectID;
 use tracing::Level;
 use walrus_core::{
@@ -44,12 +50,16 @@ use super::{
     responses::OrRejection,
 };
 use crate::{
-    common::api::{ApiSuccess, BlobIdString},
+    common::{
+        api::{ApiSuccess, BlobIdString},
+        blocklist::BlocklistStatus,
+    },
     node::{
         errors::{IndexOutOfRange, ListSymbolsError},
         BlobStatusError,
         ComputeStorageConfirmationError,
         InconsistencyProofError,
+        PromoteBlobSyncError,
         RetrieveMetadataError,
         RetrieveSliverError,
         RetrieveSymbolError,
@@ -88,8 +98,12 @@ pub const INCONSISTENCY_PROOF_ENDPOINT: &str =
     "/v1/blobs/{blob_id}/inconsistencyProof/{sliver_type}";
 /// The path to get the status of a blob.
 pub const BLOB_STATUS_ENDPOINT: &str = "/v1/blobs/{blob_id}/status";
+/// The path to get the blocklist status of a blob.
+pub const BLOCKLIST_STATUS_ENDPOINT: &str = "/v1/blobs/{blob_id}/blocklist";
 pub const HEALTH_ENDPOINT: &str = "/v1/health";
 pub const SYNC_SHARD_ENDPOINT: &str = "/v1/migrate/sync_shard";
+/// The path to move the queued sync of a blob to the front of the blob sync queue.
+pub const BLOB_SYNC_PROMOTE_ENDPOINT: &str = "/v1/blobs/{blob_id}/sync/promote";
 
 /// Convenience trait to apply bounds on the ServiceState.
 trait SyncServiceState: ServiceState + Send + Sync + 'static {}
@@ -637,6 +651,31 @@ pub async fn get_blob_status<S: SyncServiceState>(
     Ok(ApiSuccess::ok(state.blob_status(&blob_id)?))
 }
 
+/// Get the blocklist status of a blob.
+///
+/// Gets whether the blob is blocked by this storage node and, if so, the name of the list blocking
+/// it, which is either the local deny list or one of the remote lists of the node.
+#[tracing::instrument(skip_all, fields(walrus.blob_id = %blob_id))]
+#[utoipa::path(
+    get,
+    path = BLOCKLIST_STATUS_ENDPOINT,
+    params(("blob_id" = BlobId,)),
+    responses(
+        (
+            status = 200,
+            description = "The blocklist status of the blob",
+            body = ApiSuccess<BlocklistStatus>,
+        ),
+    ),
+    tag = openapi::GROUP_STATUS
+)]
+pub async fn get_blocklist_status<S: SyncServiceState>(
+    State(state): State<Arc<S>>,
+    Path(BlobIdString(blob_id)): Path<BlobIdString>,
+) -> ApiSuccess<BlocklistStatus> {
+    ApiSuccess::ok(state.blocklist_status(&blob_id))
+}
+
 #[derive(Debug, Clone, serde::Deserialize, utoipa::IntoParams)]
 #[serde(rename_all = "camelCase")]
 pub struct HealthInfoQuery {
@@ -686,3 +725,46 @@ pub async fn sync_shard<S: SyncServiceState>(
 ) -> Result<Response, OrRejection<SyncShardServiceError>> {
     Ok(Bcs(state.sync_shard(public_key, signed_request).await?).into_response())
 }
+
+/// Promote the sync of a blob.
+///
+/// Moves the queued sync of a certified blob that is not yet fully stored at this storage node to
+/// the front of the blob sync queue. Requires the admin token as a bearer token.
+#[tracing::instrument(skip_all, fields(walrus.blob_id = %blob_id), err(level = Level::DEBUG))]
+#[utoipa::path(
+    post,
+    path = BLOB_SYNC_PROMOTE_ENDPOINT,
+    params(
+        ("blob_id" = BlobId,),
+        ("Authorization" = String, Header, description = "Bearer admin token")
+    ),
+    responses(
+        (status = 200, description = "The blob sync was promoted", body = ApiSuccess<String>),
+        PromoteBlobSyncError
+    ),
+    tag = openapi::GROUP_STATUS
+)]
+pub async fn promote_blob_sync<S: SyncServiceState>(
+    State(state): State<Arc<S>>,
+    Path(BlobIdString(blob_id)): Path<BlobIdString>,
+) -> Result<ApiSuccess<&'static str>, PromoteBlobSyncError> {
+    state.promote_blob_sync(&blob_id)?;
+    Ok(ApiSuccess::ok("blob sync promoted"))
+}
+
+/// Rejects requests that do not carry the configured admin token as a bearer token.
+pub async fn require_admin_token(
+    State(admin_token): State<Arc<str>>,
+    bearer_header: Option<TypedHeader<AuthorizationHeader<Bearer>>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    match bearer_header {
+        Some(TypedHeader(header))
+            if bool::from(header.token().as_bytes().ct_eq(admin_token.as_bytes())) =>
+        {
+            next.run(request).await
+        }
+        _ => StatusCode::UNAUTHORIZED.into_response(),
+    }
+}
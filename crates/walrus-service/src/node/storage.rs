@@ -38,6 +38,7 @@ use walrus_sui::types::BlobEvent;
 use self::{
     blob_info::{BlobInfo, BlobInfoApi, BlobInfoTable},
     constants::{
+        blob_sync_queue_cf_name,
         metadata_cf_name,
         node_status_cf_name,
         pending_recover_slivers_column_family_name,
@@ -83,6 +84,10 @@ pub(crate) fn node_status_options(db_config: &DatabaseConfig) -> Options {
     db_config.node_status().to_options()
 }
 
+pub(crate) fn blob_sync_queue_options(db_config: &DatabaseConfig) -> Options {
+    db_config.blob_sync_queue().to_options()
+}
+
 /// Error returned if a requested operation would block.
 #[derive(Debug, Clone, Copy)]
 pub struct WouldBlockError;
@@ -136,6 +141,24 @@ impl Display for NodeStatus {
     }
 }
 
+/// A blob sync that is waiting for or holding a sync permit, as persisted in the database.
+///
+/// Important: this struct is committed to database. Do not modify the existing fields. Only add new
+/// fields at the end.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub(crate) struct QueuedBlobSync {
+    /// The epoch in which the blob was certified.
+    pub certified_epoch: Epoch,
+    /// The epoch at which the blob expires, if known.
+    pub end_epoch: Option<Epoch>,
+    /// Monotonically increasing sequence number recording the order in which syncs were queued.
+    pub sequence: u64,
+    /// The number of reads for the blob that could not be served while the sync was queued.
+    pub read_demand: u64,
+    /// Whether the sync was manually promoted to the front of the queue.
+    pub promoted: bool,
+}
+
 /// Storage backing a [`StorageNode`][crate::node::StorageNode].
 ///
 /// Enables storing blob metadata, which is shared across all shards. The method
@@ -147,6 +170,7 @@ pub struct Storage {
     metadata: DBMap<BlobId, BlobMetadata>,
     blob_info: BlobInfoTable,
     event_cursor: EventCursorTable,
+    blob_sync_queue: DBMap<BlobId, QueuedBlobSync>,
     shards: Arc<RwLock<HashMap<ShardIndex, Arc<ShardStorage>>>>,
     config: DatabaseConfig,
     metrics: Arc<CommonDatabaseMetrics>,
@@ -223,6 +247,8 @@ impl Storage {
         let metadata_cf_name = metadata_cf_name();
         let blob_info_column_families = BlobInfoTable::options(&db_config);
         let (event_cursor_cf_name, event_cursor_options) = EventCursorTable::options(&db_config);
+        let blob_sync_queue_cf_name = blob_sync_queue_cf_name();
+        let blob_sync_queue_options = blob_sync_queue_options(&db_config);
 
         let expected_column_families: Vec<_> = shard_column_families
             .iter_mut()
@@ -231,6 +257,7 @@ impl Storage {
                 (node_status_cf_name, node_status_options),
                 (metadata_cf_name, metadata_options),
                 (event_cursor_cf_name, event_cursor_options),
+                (blob_sync_queue_cf_name, blob_sync_queue_options),
             ])
             .chain(blob_info_column_families)
             .collect::<Vec<_>>();
@@ -259,6 +286,13 @@ impl Storage {
             false,
         )?;
 
+        let blob_sync_queue = DBMap::reopen(
+            &database,
+            Some(blob_sync_queue_cf_name),
+            &ReadWriteOptions::default(),
+            false,
+        )?;
+
         let event_cursor = EventCursorTable::reopen(&database)?;
         let blob_info = BlobInfoTable::reopen(&database)?;
         let shards = Arc::new(RwLock::new(
@@ -277,6 +311,7 @@ impl Storage {
             metadata,
             blob_info,
             event_cursor,
+            blob_sync_queue,
             shards,
             config: db_config,
             metrics: Arc::new(CommonDatabaseMetrics::new_with_id(
@@ -724,6 +759,37 @@ impl Storage {
         self.event_cursor.get_event_cursor_progress()
     }
 
+    /// Persists the blob sync queue entry for the provided blob ID.
+    pub(crate) fn put_queued_blob_sync(
+        &self,
+        blob_id: &BlobId,
+        entry: &QueuedBlobSync,
+    ) -> Result<(), TypedStoreError> {
+        self.blob_sync_queue.insert(blob_id, entry)
+    }
+
+    /// Stores the provided blob sync queue entries in a single batch.
+    pub(crate) fn put_queued_blob_syncs(
+        &self,
+        entries: &[(BlobId, QueuedBlobSync)],
+    ) -> Result<(), TypedStoreError> {
+        let mut batch = self.blob_sync_queue.batch();
+        batch.insert_batch(&self.blob_sync_queue, entries.iter().map(|(k, v)| (k, v)))?;
+        batch.write()
+    }
+
+    /// Removes the blob sync queue entry for the provided blob ID, if any.
+    pub(crate) fn remove_queued_blob_sync(&self, blob_id: &BlobId) -> Result<(), TypedStoreError> {
+        self.blob_sync_queue.remove(blob_id)
+    }
+
+    /// Returns all blob sync queue entries persisted in the database.
+    pub(crate) fn queued_blob_syncs(
+        &self,
+    ) -> Result<Vec<(BlobId, QueuedBlobSync)>, TypedStoreError> {
+        self.blob_sync_queue.safe_iter().collect()
+    }
+
     /// Clears the metadata in the storage for testing purposes.
     #[cfg(test)]
     pub fn clear_metadata_in_test(&self) -> Result<(), TypedStoreError> {
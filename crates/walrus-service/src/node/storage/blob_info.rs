@@ -1190,6 +1190,27 @@ impl BlobInfo {
     }
 }
 
+impl BlobInfo {
+    /// Returns the latest end epoch of the certified permanent and deletable `Blob` objects.
+    ///
+    /// Returns `None` if the blob is not certified or is invalid.
+    // TODO: Deleted deletable blobs are still taken into account until we have proper cleanup
+    // (WAL-473).
+    pub(crate) fn certified_end_epoch(&self) -> Option<Epoch> {
+        let Self::V1(BlobInfoV1::Valid(blob_info)) = self else {
+            return None;
+        };
+        let permanent_end_epoch = blob_info
+            .permanent_certified
+            .as_ref()
+            .map(|permanent| permanent.end_epoch);
+        let deletable_end_epoch = (blob_info.count_deletable_certified > 0)
+            .then_some(blob_info.latest_seen_deletable_certified_epoch)
+            .flatten();
+        permanent_end_epoch.max(deletable_end_epoch)
+    }
+}
+
 impl ToBytes for BlobInfo {}
 
 impl Mergeable for BlobInfo {
@@ -1920,4 +1941,54 @@ mod tests {
             BlobStatus::Nonexistent,
         ));
     }
+
+    param_test! {
+        test_certified_end_epoch: [
+            registered: (
+                ValidBlobInfoV1 {
+                    permanent_total: Some(PermanentBlobInfoV1::new_fixed_for_testing(1, 5, 0)),
+                    count_deletable_total: 1,
+                    latest_seen_deletable_registered_epoch: Some(7),
+                    ..Default::default()
+                },
+                None,
+            ),
+            permanent: (
+                ValidBlobInfoV1 {
+                    permanent_total: Some(PermanentBlobInfoV1::new_fixed_for_testing(2, 8, 0)),
+                    permanent_certified: Some(PermanentBlobInfoV1::new_fixed_for_testing(1, 5, 0)),
+                    ..Default::default()
+                },
+                Some(5),
+            ),
+            deletable: (
+                ValidBlobInfoV1 {
+                    count_deletable_total: 1,
+                    latest_seen_deletable_registered_epoch: Some(7),
+                    count_deletable_certified: 1,
+                    latest_seen_deletable_certified_epoch: Some(7),
+                    ..Default::default()
+                },
+                Some(7),
+            ),
+            deletable_outlives_permanent: (
+                ValidBlobInfoV1 {
+                    permanent_total: Some(PermanentBlobInfoV1::new_fixed_for_testing(1, 5, 0)),
+                    permanent_certified: Some(PermanentBlobInfoV1::new_fixed_for_testing(1, 5, 0)),
+                    count_deletable_total: 1,
+                    latest_seen_deletable_registered_epoch: Some(7),
+                    count_deletable_certified: 1,
+                    latest_seen_deletable_certified_epoch: Some(7),
+                    ..Default::default()
+                },
+                Some(7),
+            ),
+        ]
+    }
+    fn test_certified_end_epoch(blob_info: ValidBlobInfoV1, expected: Option<Epoch>) {
+        assert_eq!(
+            BlobInfo::V1(BlobInfoV1::Valid(blob_info)).certified_end_epoch(),
+            expected
+        );
+    }
 }
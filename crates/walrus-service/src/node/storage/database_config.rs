@@ -157,7 +157,7 @@ impl From<&GlobalDatabaseOptions> for Options {
 ///
 /// Options for all individual tables can be set as well through the `node_status`, `metadata`,
 /// `blob_info`, `per_object_blob_info`, `event_cursor`, `shard`, `shard_status`,
-/// `shard_sync_progress`, and `pending_recover_slivers` fields.
+/// `shard_sync_progress`, `pending_recover_slivers`, and `blob_sync_queue` fields.
 ///
 /// **Warning:** Note that the configuration is currently not properly hierarchical. For example, if
 /// the `metadata` options are defined, they are *not* merged with the `optimized_for_blobs` or
@@ -191,6 +191,8 @@ pub struct DatabaseConfig {
     pub(super) shard_sync_progress: Option<DatabaseTableOptions>,
     /// Pending recover slivers database options.
     pub(super) pending_recover_slivers: Option<DatabaseTableOptions>,
+    /// Blob sync queue database options.
+    pub(super) blob_sync_queue: Option<DatabaseTableOptions>,
 }
 
 impl DatabaseConfig {
@@ -240,6 +242,11 @@ impl DatabaseConfig {
             .as_ref()
             .unwrap_or(&self.standard)
     }
+
+    /// Returns the blob sync queue database option.
+    pub fn blob_sync_queue(&self) -> &DatabaseTableOptions {
+        self.blob_sync_queue.as_ref().unwrap_or(&self.standard)
+    }
 }
 
 impl Default for DatabaseConfig {
@@ -257,6 +264,7 @@ impl Default for DatabaseConfig {
             shard_status: None,
             shard_sync_progress: None,
             pending_recover_slivers: None,
+            blob_sync_queue: None,
         }
     }
 }
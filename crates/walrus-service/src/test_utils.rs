@@ -53,10 +53,12 @@ use walrus_sui::{
     client::{
         retry_client::RetriableRpcClient,
         BlobObjectMetadata,
+        ContractClient,
         FixedSystemParameters,
+        ReadClient as _,
         SuiClientError,
     },
-    test_utils::system_setup::SystemContext,
+    test_utils::{fake_sui::FakeSuiClient, system_setup::SystemContext},
     types::{
         move_structs::{EpochState, EventBlob, NodeMetadata, VotingParams},
         Committee,
@@ -1032,6 +1034,8 @@ impl StorageNodeHandleBuilder {
                 backoff_config: ExponentialBackoffConfig::default(),
                 gas_budget: None,
                 rpc_fallback_config: None,
+                additional_rpc_endpoints: Default::default(),
+                rpc_failover_config: Default::default(),
             }),
             config_synchronizer: ConfigSynchronizerConfig {
                 interval: Duration::from_secs(5),
@@ -1476,6 +1480,120 @@ impl SystemContractService for StubContractService {
     }
 }
 
+/// A [`SystemContractService`] that sends the transactions of a storage node through a
+/// [`ContractClient`], e.g., a [`FakeSuiClient`].
+///
+/// Only the transactions supported by [`ContractClient`] are sent; ending the voting phase and
+/// initiating epoch changes are not supported, and event blobs are not certified.
+#[derive(Debug, Clone)]
+pub struct ContractClientService<C> {
+    contract_client: C,
+    committee_service: Arc<dyn CommitteeService>,
+    node_capability_object: StorageNodeCap,
+}
+
+impl<C: ContractClient + std::fmt::Debug> ContractClientService<C> {
+    /// Creates a new service sending transactions for the node owning `node_capability_object`
+    /// through `contract_client`.
+    pub fn new(
+        contract_client: C,
+        committee_service: Arc<dyn CommitteeService>,
+        node_capability_object: StorageNodeCap,
+    ) -> Self {
+        Self {
+            contract_client,
+            committee_service,
+            node_capability_object,
+        }
+    }
+}
+
+#[async_trait]
+impl<C: ContractClient + std::fmt::Debug> SystemContractService for ContractClientService<C> {
+    async fn sync_node_params(
+        &self,
+        _config: &StorageNodeConfig,
+        _node_capability_object_id: ObjectID,
+    ) -> Result<(), SyncNodeConfigError> {
+        Ok(())
+    }
+
+    async fn invalidate_blob_id(&self, certificate: &InvalidBlobCertificate) {
+        if let Err(error) = self.contract_client.invalidate_blob_id(certificate).await {
+            tracing::warn!(
+                ?error,
+                "submitting invalidity certificate to contract failed"
+            );
+        }
+    }
+
+    async fn epoch_sync_done(&self, epoch: Epoch, node_capability_object_id: ObjectID) {
+        match self
+            .contract_client
+            .epoch_sync_done(epoch, node_capability_object_id)
+            .await
+        {
+            Ok(()) | Err(SuiClientError::LatestAttestedIsMoreRecent) => (),
+            Err(error) => {
+                tracing::warn!(?error, "submitting epoch sync done to contract failed");
+            }
+        }
+    }
+
+    async fn get_epoch_and_state(&self) -> Result<(Epoch, EpochState), anyhow::Error> {
+        let committees = self.contract_client.get_committees_and_state().await?;
+        Ok((committees.current.epoch, committees.epoch_state))
+    }
+
+    fn current_epoch(&self) -> Epoch {
+        self.committee_service.active_committees().epoch()
+    }
+
+    async fn fixed_system_parameters(&self) -> Result<FixedSystemParameters, anyhow::Error> {
+        self.contract_client
+            .fixed_system_parameters()
+            .await
+            .context("failed to retrieve system parameters")
+    }
+
+    async fn end_voting(&self) -> Result<(), anyhow::Error> {
+        anyhow::bail!("the contract client cannot end voting")
+    }
+
+    async fn initiate_epoch_change(&self) -> Result<(), anyhow::Error> {
+        anyhow::bail!("the contract client cannot initiate epoch change")
+    }
+
+    async fn certify_event_blob(
+        &self,
+        _blob_metadata: BlobObjectMetadata,
+        _ending_checkpoint_seq_num: u64,
+        _epoch: u32,
+        _node_capability_object_id: ObjectID,
+    ) -> Result<(), SuiClientError> {
+        Ok(())
+    }
+
+    async fn refresh_contract_package(&self) -> Result<(), anyhow::Error> {
+        Ok(self.contract_client.refresh_package_id().await?)
+    }
+
+    async fn get_node_capability_object(
+        &self,
+        _node_capability_object_id: Option<ObjectID>,
+    ) -> Result<StorageNodeCap, SuiClientError> {
+        Ok(self.node_capability_object.clone())
+    }
+
+    async fn get_system_object_version(&self) -> Result<u64, SuiClientError> {
+        self.contract_client.system_object_version().await
+    }
+
+    async fn last_certified_event_blob(&self) -> Result<Option<EventBlob>, SuiClientError> {
+        self.contract_client.last_certified_event_blob().await
+    }
+}
+
 /// Returns a socket address that is not currently in use on the system.
 ///
 /// distinct_ip: If true, the returned address will have a distinct IP address from the local
@@ -1563,6 +1681,31 @@ impl SystemEventProvider for tokio::sync::broadcast::Sender<ContractEvent> {
     }
 }
 
+#[async_trait::async_trait]
+impl SystemEventProvider for FakeSuiClient {
+    async fn events(
+        &self,
+        cursor: EventStreamCursor,
+    ) -> Result<Box<dyn Stream<Item = PositionedStreamEvent> + Send + Sync + 'life0>, anyhow::Error>
+    {
+        let events = self.event_stream(Duration::ZERO, cursor.event_id).await?;
+        Ok(Box::new(events.map(|event| {
+            PositionedStreamEvent::new(event, CheckpointEventPosition::new(0, 0))
+        })))
+    }
+
+    async fn init_state(
+        &self,
+        _from: EventStreamCursor,
+    ) -> Result<Option<InitState>, anyhow::Error> {
+        Ok(None)
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
 /// A cluster of [`StorageNodeHandle`]s corresponding to several running storage nodes.
 #[derive(Debug)]
 pub struct TestCluster<T: StorageNodeHandleTrait = StorageNodeHandle> {
@@ -2200,7 +2343,7 @@ pub mod test_cluster {
     use futures::future;
     use tokio::sync::Mutex as TokioMutex;
     use walrus_sui::{
-        client::{SuiContractClient, SuiReadClient},
+        client::{contract_config::ContractConfig, SuiContractClient, SuiReadClient},
         test_utils::{
             self,
             system_setup::{
@@ -2229,6 +2372,87 @@ pub mod test_cluster {
     /// The weight of each storage node in the test cluster.
     pub const FROST_PER_NODE_WEIGHT: u64 = 1_000_000_000_000;
 
+    /// Sets up a test cluster whose storage nodes and client run against an in-memory
+    /// [`FakeSuiClient`] instead of a Sui test cluster.
+    ///
+    /// Returns the fake, which can be used to inspect the simulated chain and to advance its
+    /// epochs, together with the cluster and a client sending its transactions to the fake.
+    pub async fn fake_setup(
+    ) -> anyhow::Result<(FakeSuiClient, TestCluster, client::Client<FakeSuiClient>)> {
+        let cluster_builder = TestCluster::<StorageNodeHandle>::builder();
+        let members: Vec<_> = cluster_builder
+            .storage_node_test_configs()
+            .iter()
+            .enumerate()
+            .map(|(i, info)| info.to_storage_node_info(&format!("node-{i}")))
+            .collect();
+        let fake = FakeSuiClient::new(committee_from_members(members.clone(), Some(1)));
+
+        let n_nodes = members.len();
+        let mut committee_services = Vec::with_capacity(n_nodes);
+        let mut contract_services = Vec::with_capacity(n_nodes);
+        let mut storage_capabilities = Vec::with_capacity(n_nodes);
+        for member in members {
+            let committee_service: Arc<dyn CommitteeService> = Arc::new(
+                NodeCommitteeService::builder()
+                    .local_identity(member.public_key.clone())
+                    .build_with_factory(
+                        fake.clone(),
+                        DefaultNodeServiceFactory::avoid_system_services(),
+                    )
+                    .await?,
+            );
+            // The fake identifies the capabilities of the storage nodes with their node IDs.
+            let capability = StorageNodeCap {
+                id: member.node_id,
+                node_id: member.node_id,
+                ..StorageNodeCap::new_for_testing()
+            };
+            contract_services.push(ContractClientService::new(
+                fake.clone(),
+                committee_service.clone(),
+                capability.clone(),
+            ));
+            committee_services.push(committee_service);
+            storage_capabilities.push(capability);
+        }
+
+        let cluster_builder = cluster_builder
+            .with_committee_services(&committee_services)
+            .with_system_contract_services(contract_services)
+            .with_system_event_providers(fake.clone())
+            .with_storage_capabilities(storage_capabilities)
+            .with_disable_event_blob_writer(vec![true; n_nodes]);
+        let cluster = {
+            // Lock to avoid race conditions.
+            let _lock = global_test_lock().lock().await;
+            cluster_builder.build().await?
+        };
+
+        let config = Config {
+            contract_config: ContractConfig::new(ObjectID::random(), ObjectID::random()),
+            exchange_objects: vec![],
+            wallet_config: None,
+            sponsor: None,
+            communication_config: ClientCommunicationConfig::default_for_test(),
+            refresh_config: Default::default(),
+            rpc_urls: Default::default(),
+            rpc_failover_config: Default::default(),
+            trace_export: None,
+            blocklist: Default::default(),
+        };
+        let committees_handle = config
+            .refresh_config
+            .build_refresher_and_run(fake.clone())
+            .await?;
+        let client = client::Client::new(config, committees_handle)
+            .await?
+            .with_client(fake.clone())
+            .await;
+
+        Ok((fake, cluster, client))
+    }
+
     /// Performs the default setup for the test cluster using StorageNodeHandle as default storage
     /// node handle.
     pub async fn default_setup() -> anyhow::Result<(
@@ -2528,8 +2752,13 @@ pub mod test_cluster {
             contract_config,
             exchange_objects: vec![],
             wallet_config: None,
+            sponsor: None,
             communication_config,
             refresh_config: Default::default(),
+            rpc_urls: Default::default(),
+            rpc_failover_config: Default::default(),
+            trace_export: None,
+            blocklist: Default::default(),
         };
 
         let client = admin_contract_client
@@ -2613,6 +2842,7 @@ pub fn storage_node_config() -> WithTempDir<StorageNodeConfig> {
             db_config: Default::default(),
             rest_server: Default::default(),
             blocklist_path: None,
+            blocklist: Default::default(),
             sui: None,
             blob_recovery: Default::default(),
             tls: Default::default(),
@@ -2634,6 +2864,7 @@ pub fn storage_node_config() -> WithTempDir<StorageNodeConfig> {
             public_host: rest_api_address.ip().to_string(),
             public_port: rest_api_address.port(),
             metrics_push: None,
+            trace_export: None,
             metadata: Default::default(),
             config_synchronizer: Default::default(),
             storage_node_cap: None,
@@ -560,8 +560,13 @@ pub async fn create_client_config(
         contract_config,
         exchange_objects,
         wallet_config: Some(WalletConfig::from_path(wallet_path)),
+        sponsor: None,
         communication_config: Default::default(),
         refresh_config: Default::default(),
+        rpc_urls: Default::default(),
+        rpc_failover_config: Default::default(),
+        trace_export: None,
+        blocklist: Default::default(),
     };
 
     Ok(client_config)
@@ -583,6 +588,8 @@ pub async fn create_backup_config(
             backoff_config: ExponentialBackoffConfig::default(),
             event_polling_interval: defaults::polling_interval(),
             rpc_fallback_config,
+            additional_rpc_endpoints: Default::default(),
+            rpc_failover_config: Default::default(),
         },
         database_url.to_string(),
     ))
@@ -709,6 +716,8 @@ pub async fn create_storage_node_configs(
             backoff_config: ExponentialBackoffConfig::default(),
             gas_budget: None,
             rpc_fallback_config: rpc_fallback_config.clone(),
+            additional_rpc_endpoints: Default::default(),
+            rpc_failover_config: Default::default(),
         });
 
         let storage_path = set_db_path
@@ -727,6 +736,7 @@ pub async fn create_storage_node_configs(
             name: node.name.clone(),
             storage_path,
             blocklist_path: None,
+            blocklist: Default::default(),
             protocol_key_pair,
             next_protocol_key_pair: None,
             network_key_pair: node.network_keypair.into(),
@@ -754,6 +764,7 @@ pub async fn create_storage_node_configs(
                 node_capacity: node.node_capacity,
             },
             metrics_push: None,
+            trace_export: None,
             metadata: Default::default(),
             config_synchronizer: Default::default(),
             storage_node_cap: None,
@@ -4,13 +4,14 @@
 //! Generate writes and reads for stress tests.
 
 use std::{
-    sync::Arc,
+    path::Path,
+    sync::{Arc, Mutex},
     time::{Duration, Instant},
 };
 
 use futures::future::try_join_all;
 use rand::{thread_rng, Rng};
-use sui_sdk::types::base_types::SuiAddress;
+use sui_sdk::types::base_types::{ObjectID, SuiAddress};
 use tokio::{
     sync::mpsc::{self, error::TryRecvError, Receiver, Sender},
     time::{Interval, MissedTickBehavior},
@@ -25,18 +26,31 @@ use walrus_service::client::{
     Refiller,
 };
 use walrus_sui::{
-    client::{retry_client::RetriableSuiClient, SuiReadClient},
+    client::{retry_client::RetriableSuiClient, BlobPersistence, ReadClient, SuiReadClient},
     utils::SuiNetwork,
 };
 
+use crate::scenario::{
+    self,
+    BlobPool,
+    Phase,
+    PhaseResults,
+    ReadKind,
+    ReadKindSampler,
+    Scenario,
+    WrittenBlob,
+};
+
 /// Minimum burst duration.
 const MIN_BURST_DURATION: Duration = Duration::from_millis(100);
 /// Number of seconds per load period.
 const SECS_PER_LOAD_PERIOD: u64 = 60;
 
 mod blob;
+pub mod byzantine;
 
 mod write_client;
+use byzantine::ByzantineFraction;
 use walrus_utils::backoff::ExponentialBackoffConfig;
 use write_client::WriteClient;
 
@@ -62,8 +76,10 @@ impl LoadGenerator {
         gas_refill_period: Duration,
         metrics: Arc<ClientMetrics>,
         refiller: Refiller,
+        byzantine_fractions: &[ByzantineFraction],
     ) -> anyhow::Result<Self> {
         tracing::info!("initializing clients...");
+        let byzantine_behaviours = byzantine::assign_behaviours(byzantine_fractions, n_clients)?;
 
         // Set up read clients
         let (read_client_pool_tx, read_client_pool) = mpsc::channel(n_clients);
@@ -95,7 +111,10 @@ impl LoadGenerator {
         let (write_client_pool_tx, write_client_pool) = mpsc::channel(n_clients);
         let mut write_clients = Vec::with_capacity(n_clients);
 
-        for _ in 0..n_clients {
+        for byzantine_behaviour in byzantine_behaviours {
+            if let Some(behaviour) = byzantine_behaviour {
+                tracing::info!(%behaviour, "initializing byzantine write client");
+            }
             write_clients.push(
                 WriteClient::new(
                     &client_config,
@@ -105,6 +124,7 @@ impl LoadGenerator {
                     max_size_log2,
                     refresher_handle.clone(),
                     refiller.clone(),
+                    byzantine_behaviour,
                 )
                 .await?,
             )
@@ -150,6 +170,10 @@ impl LoadGenerator {
                 panic!("write client channel was disconnected");
             }
         };
+        if client.byzantine_behaviour().is_some() {
+            self.submit_byzantine_operation(client);
+            return true;
+        }
         let sender = self.write_client_pool_tx.clone();
         let metrics = self.metrics.clone();
         tokio::spawn(async move {
@@ -183,6 +207,39 @@ impl LoadGenerator {
         true
     }
 
+    /// Performs an operation of the byzantine behaviour of the client in the background.
+    fn submit_byzantine_operation(&self, mut client: WriteClient) {
+        let behaviour = client
+            .byzantine_behaviour()
+            .expect("only called with byzantine clients");
+        let sender = self.write_client_pool_tx.clone();
+        let metrics = self.metrics.clone();
+        tokio::spawn(async move {
+            match client.run_byzantine().await {
+                Ok((outcome, elapsed)) => {
+                    tracing::info!(
+                        %behaviour,
+                        requests = outcome.requests,
+                        rejected = outcome.rejected,
+                        "byzantine operation finished"
+                    );
+                    outcome.observe(&metrics, behaviour);
+                    metrics.observe_latency(behaviour.workload(), elapsed);
+                }
+                Err(error) => {
+                    tracing::warn!(?error, %behaviour, "byzantine operation failed");
+                    metrics.observe_error("failed byzantine operation");
+                }
+            }
+            sender
+                .send(client)
+                .await
+                .expect("write client channel should not be closed");
+        });
+
+        self.metrics.observe_submitted(behaviour.workload());
+    }
+
     fn submit_read(&mut self, blob_id: BlobId) -> bool {
         let client = match self.read_client_pool.try_recv() {
             Ok(client) => client,
@@ -325,6 +382,256 @@ impl LoadGenerator {
         Ok(())
     }
 
+    /// Run the phases of the scenario one after the other, and write the per-phase latency
+    /// histograms to `results_path` after each phase.
+    pub async fn run_scenario(
+        &mut self,
+        scenario: &Scenario,
+        results_path: &Path,
+    ) -> anyhow::Result<()> {
+        tracing::info!("starting scenario with {} phases...", scenario.phases.len());
+        let pool = Arc::new(Mutex::new(BlobPool::new()));
+
+        // Seed the pool with a blob that is read while no blob was written yet, of a size
+        // written by the first phase with reads.
+        if let Some(phase) = scenario.phases.iter().find(|phase| phase.read_load > 0) {
+            let size = phase.size_sampler().sample(&mut thread_rng());
+            let (blob_id, object_id) = self
+                .single_write_with_size(size, scenario.max_epochs_to_store())
+                .await
+                .inspect_err(|error| tracing::error!(?error, "initial write failed"))?;
+            pool.lock()
+                .expect("mutex should not be poisoned")
+                .set_fallback(WrittenBlob {
+                    blob_id,
+                    object_id,
+                    size,
+                });
+        }
+
+        let mut all_results = Vec::with_capacity(scenario.phases.len());
+        let start = Instant::now();
+        for phase in &scenario.phases {
+            tracing::info!(
+                phase = %phase.name,
+                "starting phase: {} writes/min, {} reads/min for {}s",
+                phase.write_load,
+                phase.read_load,
+                phase.duration_secs
+            );
+            let results = Arc::new(Mutex::new(PhaseResults::new(phase.name.clone())));
+            pool.lock()
+                .expect("mutex should not be poisoned")
+                .set_popularity(phase.popularity);
+            self.run_phase(phase, start, &pool, &results).await;
+
+            // Operations still in flight are recorded in the results of the phase they were
+            // submitted in, but may complete after the results are written.
+            let results = results
+                .lock()
+                .expect("mutex should not be poisoned")
+                .clone();
+            all_results.push(results);
+            scenario::write_results(results_path, &all_results)?;
+        }
+        tracing::info!(
+            "scenario finished, results written to {}",
+            results_path.display()
+        );
+        Ok(())
+    }
+
+    async fn run_phase(
+        &mut self,
+        phase: &Phase,
+        start: Instant,
+        pool: &Arc<Mutex<BlobPool>>,
+        results: &Arc<Mutex<PhaseResults>>,
+    ) {
+        let size_sampler = phase.size_sampler();
+        let read_sampler = phase.reads.sampler();
+        let (writes_per_burst, write_interval) = burst_load(phase.write_load);
+        let (reads_per_burst, read_interval) = burst_load(phase.read_load);
+        tokio::pin!(write_interval);
+        tokio::pin!(read_interval);
+        let phase_end = tokio::time::sleep(phase.duration());
+        tokio::pin!(phase_end);
+
+        loop {
+            tokio::select! {
+                _ = write_interval.tick() => {
+                    self.metrics.observe_execution_duration(Instant::now().duration_since(start));
+                    for _ in 0..writes_per_burst {
+                        let size = size_sampler.sample(&mut thread_rng());
+                        let deletable = phase.sample_deletable(&mut thread_rng());
+                        if !self.submit_scenario_write(
+                            size,
+                            phase.epochs_to_store,
+                            deletable,
+                            pool,
+                            results,
+                        ) {
+                            break;
+                        }
+                    }
+                }
+                _ = read_interval.tick() => {
+                    self.metrics.observe_execution_duration(Instant::now().duration_since(start));
+                    for _ in 0..reads_per_burst {
+                        if !self.submit_scenario_read(&read_sampler, pool, results) {
+                            break;
+                        }
+                    }
+                }
+                _ = &mut phase_end => break,
+            }
+        }
+    }
+
+    fn submit_scenario_write(
+        &mut self,
+        size: usize,
+        epochs_to_store: EpochCount,
+        deletable: bool,
+        pool: &Arc<Mutex<BlobPool>>,
+        results: &Arc<Mutex<PhaseResults>>,
+    ) -> bool {
+        let mut client = match self.write_client_pool.try_recv() {
+            Ok(client) => client,
+            Err(TryRecvError::Empty) => {
+                tracing::warn!("no client available to submit write");
+                return false;
+            }
+            Err(TryRecvError::Disconnected) => {
+                panic!("write client channel was disconnected");
+            }
+        };
+        if client.byzantine_behaviour().is_some() {
+            self.submit_byzantine_operation(client);
+            return true;
+        }
+        let sender = self.write_client_pool_tx.clone();
+        let metrics = self.metrics.clone();
+        let pool = pool.clone();
+        let results = results.clone();
+        let persistence = if deletable {
+            BlobPersistence::Deletable
+        } else {
+            BlobPersistence::Permanent
+        };
+        tokio::spawn(async move {
+            let result = client
+                .write_fresh_blob_with_size(size, epochs_to_store, persistence)
+                .await;
+            match result {
+                Ok((blob_id, object_id, elapsed)) => {
+                    tracing::info!(size, deletable, "write finished");
+                    metrics.observe_latency(metrics::WRITE_WORKLOAD, elapsed);
+                    results
+                        .lock()
+                        .expect("mutex should not be poisoned")
+                        .writes
+                        .observe(elapsed);
+                    pool.lock()
+                        .expect("mutex should not be poisoned")
+                        .push(WrittenBlob {
+                            blob_id,
+                            object_id,
+                            size,
+                        });
+                }
+                Err(error) => {
+                    tracing::warn!(?error, "failed to write blob");
+                    metrics.observe_error("failed to write blob");
+                    results
+                        .lock()
+                        .expect("mutex should not be poisoned")
+                        .writes
+                        .observe_error();
+                }
+            }
+            sender
+                .send(client)
+                .await
+                .expect("write client channel should not be closed");
+        });
+
+        self.metrics.observe_submitted(metrics::WRITE_WORKLOAD);
+        true
+    }
+
+    fn submit_scenario_read(
+        &mut self,
+        read_sampler: &ReadKindSampler,
+        pool: &Arc<Mutex<BlobPool>>,
+        results: &Arc<Mutex<PhaseResults>>,
+    ) -> bool {
+        let Some(blob) = pool
+            .lock()
+            .expect("mutex should not be poisoned")
+            .sample(&mut thread_rng())
+        else {
+            results
+                .lock()
+                .expect("mutex should not be poisoned")
+                .skipped_reads += 1;
+            return true;
+        };
+        let mut kind = read_sampler.sample(&mut thread_rng());
+        if blob.object_id.is_none() && kind == ReadKind::ObjectId {
+            // The object ID is unknown for blobs that were already certified.
+            kind = ReadKind::BlobId;
+        }
+
+        let client = match self.read_client_pool.try_recv() {
+            Ok(client) => client,
+            Err(TryRecvError::Empty) => {
+                tracing::warn!("no client available to submit read");
+                return false;
+            }
+            Err(TryRecvError::Disconnected) => {
+                panic!("read client channel was disconnected");
+            }
+        };
+        let sender = self.read_client_pool_tx.clone();
+        let metrics = self.metrics.clone();
+        let results = results.clone();
+        tokio::spawn(async move {
+            let now = Instant::now();
+            let result = read_blob(&client, kind, blob).await;
+            let elapsed = now.elapsed();
+
+            {
+                let mut results = results.lock().expect("mutex should not be poisoned");
+                let histogram = results.reads.entry(kind).or_default();
+                match result {
+                    Ok(()) => {
+                        tracing::info!(?kind, "read finished");
+                        metrics.observe_latency(metrics::READ_WORKLOAD, elapsed);
+                        histogram.observe(elapsed);
+                    }
+                    Err(error) => {
+                        tracing::error!(
+                            ?error,
+                            ?kind,
+                            blob_id = %blob.blob_id,
+                            "failed to read blob"
+                        );
+                        metrics.observe_error("failed to read blob");
+                        histogram.observe_error();
+                    }
+                }
+            }
+            sender
+                .send(client)
+                .await
+                .expect("read client channel should not be closed");
+        });
+
+        self.metrics.observe_submitted(metrics::READ_WORKLOAD);
+        true
+    }
+
     async fn single_write(&mut self, epochs_to_store: EpochCount) -> Result<BlobId, ClientError> {
         let mut client = self
             .write_client_pool
@@ -341,6 +648,60 @@ impl LoadGenerator {
             .expect("channel should not be closed");
         result
     }
+
+    /// Writes a single permanent blob of the given size, and returns its blob ID and the ID of
+    /// its blob object (if known).
+    async fn single_write_with_size(
+        &mut self,
+        size: usize,
+        epochs_to_store: EpochCount,
+    ) -> Result<(BlobId, Option<ObjectID>), ClientError> {
+        let mut client = self
+            .write_client_pool
+            .recv()
+            .await
+            .expect("write client should be available");
+        let result = client
+            .write_fresh_blob_with_size(size, epochs_to_store, BlobPersistence::Permanent)
+            .await
+            .map(|(blob_id, object_id, _)| (blob_id, object_id));
+        self.write_client_pool_tx
+            .send(client)
+            .await
+            .expect("channel should not be closed");
+        result
+    }
+}
+
+/// Reads the blob with the given kind of read operation.
+async fn read_blob(
+    client: &Client<SuiReadClient>,
+    kind: ReadKind,
+    blob: WrittenBlob,
+) -> anyhow::Result<()> {
+    let blob_id = match (kind, blob.object_id) {
+        (ReadKind::ObjectId, Some(object_id)) => {
+            client
+                .sui_client()
+                .get_blob_by_object_id(&object_id)
+                .await?
+                .blob
+                .blob_id
+        }
+        (ReadKind::Range, _) if blob.size > 0 => {
+            let range = {
+                let mut rng = thread_rng();
+                let size = u64::try_from(blob.size).expect("the blob size fits a u64");
+                let start = rng.gen_range(0..size);
+                start..rng.gen_range(start..size) + 1
+            };
+            client.read_blob_range(&blob.blob_id, range).await?;
+            return Ok(());
+        }
+        _ => blob.blob_id,
+    };
+    client.read_blob::<Primary>(&blob_id).await?;
+    Ok(())
 }
 
 fn burst_load(load: u64) -> (u64, Interval) {
@@ -36,24 +36,41 @@ impl BlobData {
         }
     }
 
-    /// Changes the blob by incrementing (wrapping) a randomly chosen byte excluding
-    /// the `TESTBLOB` tag at the start of the blob data.
-    pub fn refresh(&mut self) {
-        let index = self.rng.gen_range(TAG.len()..self.bytes.len());
+    /// Changes the first `size` bytes of the blob by incrementing (wrapping) a randomly chosen
+    /// byte among them, excluding the `TESTBLOB` tag at the start of the blob data unless the
+    /// blob is not longer than the tag.
+    ///
+    /// Only changing bytes within the returned slice ensures that each refresh yields a new blob.
+    fn refresh_prefix(&mut self, size: usize) {
+        let start = TAG.len().min(size - 1);
+        let index = self.rng.gen_range(start..size);
         self.bytes[index] = self.bytes[index].wrapping_add(1);
     }
 
-    /// Returns a slice of the blob with a size `2^x`, where `x` is chosen uniformly at random
-    /// between `min_size_log2` and `max_size_log2`.
-    pub fn random_size_slice(&self) -> &[u8] {
+    /// Chooses a size `2^x`, where `x` is chosen uniformly at random between `min_size_log2`
+    /// and `max_size_log2`, changes the blob within that size, and returns the size.
+    pub fn refresh_random_size(&mut self) -> usize {
         let blob_size_log2 = thread_rng().gen_range(self.min_size_log2..=self.max_size_log2);
         let blob_size = 2_usize.pow(blob_size_log2 as u32);
-        &self.bytes[..blob_size]
+        self.refresh_prefix(blob_size);
+        blob_size
     }
 
+    /// Changes the blob and returns a slice of it with a random size; see
+    /// [`Self::refresh_random_size`].
     pub fn refresh_and_get_random_slice(&mut self) -> &[u8] {
-        self.refresh();
-        self.random_size_slice()
+        let blob_size = self.refresh_random_size();
+        &self.bytes[..blob_size]
+    }
+
+    /// Changes the blob and returns a slice of it with the given size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the size is zero or exceeds `2^max_size_log2`.
+    pub fn refresh_and_get_slice_of_size(&mut self, size: usize) -> &[u8] {
+        self.refresh_prefix(size);
+        &self.bytes[..size]
     }
 }
 
@@ -62,3 +79,17 @@ impl AsRef<[u8]> for BlobData {
         &self.bytes
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn refresh_changes_the_returned_slice() {
+        let mut blob = BlobData::random(StdRng::seed_from_u64(42), 4, 10).await;
+        let first = blob.refresh_and_get_slice_of_size(16).to_vec();
+        let second = blob.refresh_and_get_slice_of_size(16).to_vec();
+        assert_ne!(first, second);
+        assert_eq!(&second[..TAG.len()], TAG);
+    }
+}
@@ -0,0 +1,501 @@
+// Copyright (c) Walrus Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Adversarial write clients.
+
+use std::{collections::HashMap, fmt, num::NonZeroU16, str::FromStr};
+
+use anyhow::{anyhow, ensure};
+use rand::{seq::SliceRandom, thread_rng, Rng};
+use sui_sdk::types::base_types::ObjectID;
+use walrus_core::{
+    encoding::{EncodingConfigTrait as _, Primary, Secondary, SliverPair},
+    merkle::Node,
+    metadata::VerifiedBlobMetadataWithId,
+    BlobId,
+    ShardIndex,
+    SliverIndex,
+    SymbolId,
+    DEFAULT_ENCODING,
+};
+use walrus_sdk::client::Client as StorageNodeClient;
+use walrus_service::client::{metrics::ClientMetrics, Client, ClientError, StoreWhen};
+use walrus_sui::{
+    client::{BlobPersistence, PostStoreAction, SuiContractClient},
+    types::StorageNode,
+};
+
+/// The number of recovery-symbol requests sent per operation by a spamming client.
+const RECOVERY_SYMBOL_REQUESTS_PER_OPERATION: usize = 50;
+
+/// An adversarial behaviour of a write client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, clap::ValueEnum)]
+#[clap(rename_all = "kebab-case")]
+pub enum ByzantineBehaviour {
+    /// Register blobs on Sui but never upload them to the storage nodes.
+    RegisterOnly,
+    /// Register blobs and upload them only to nodes holding less than a quorum of shards; the
+    /// blobs are never certified.
+    PartialUpload,
+    /// Register blobs and upload metadata that does not match the registered blob ID.
+    WrongMetadata,
+    /// Upload metadata and slivers of blobs that were never registered.
+    UnregisteredSlivers,
+    /// Repeatedly request random recovery symbols of a stored blob from nodes holding them.
+    RecoverySymbolSpam,
+}
+
+impl ByzantineBehaviour {
+    /// The workload label under which the latency of the behaviour is reported.
+    pub fn workload(&self) -> &'static str {
+        match self {
+            Self::RegisterOnly => "byzantine-register-only",
+            Self::PartialUpload => "byzantine-partial-upload",
+            Self::WrongMetadata => "byzantine-wrong-metadata",
+            Self::UnregisteredSlivers => "byzantine-unregistered-slivers",
+            Self::RecoverySymbolSpam => "byzantine-recovery-symbol-spam",
+        }
+    }
+}
+
+impl fmt::Display for ByzantineBehaviour {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = clap::ValueEnum::to_possible_value(self)
+            .expect("no variant is skipped")
+            .get_name()
+            .to_owned();
+        write!(f, "{name}")
+    }
+}
+
+/// The fraction of write clients exhibiting a byzantine behaviour, in the form
+/// `<behaviour>=<fraction>`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ByzantineFraction {
+    /// The behaviour of the clients.
+    pub behaviour: ByzantineBehaviour,
+    /// The fraction of the write clients exhibiting the behaviour.
+    pub fraction: f64,
+}
+
+impl FromStr for ByzantineFraction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (behaviour, fraction) = s
+            .split_once('=')
+            .ok_or_else(|| anyhow!("expected '<behaviour>=<fraction>', got '{s}'"))?;
+        let behaviour = <ByzantineBehaviour as clap::ValueEnum>::from_str(behaviour, true)
+            .map_err(|error| anyhow!("invalid byzantine behaviour: {error}"))?;
+        let fraction: f64 = fraction.parse()?;
+        ensure!(
+            (0.0..=1.0).contains(&fraction),
+            "the fraction of byzantine clients must be between 0 and 1"
+        );
+        Ok(Self {
+            behaviour,
+            fraction,
+        })
+    }
+}
+
+/// Assigns the byzantine behaviours to `n_clients` clients according to the fractions. The
+/// number of clients with each behaviour is rounded down; the remaining clients are honest.
+pub fn assign_behaviours(
+    fractions: &[ByzantineFraction],
+    n_clients: usize,
+) -> anyhow::Result<Vec<Option<ByzantineBehaviour>>> {
+    let total: f64 = fractions.iter().map(|x| x.fraction).sum();
+    ensure!(
+        total <= 1.0,
+        "the fractions of byzantine clients must not exceed 1 in total"
+    );
+    let mut behaviours = Vec::with_capacity(n_clients);
+    for fraction in fractions {
+        let count = (fraction.fraction * n_clients as f64).floor() as usize;
+        behaviours.extend(std::iter::repeat(Some(fraction.behaviour)).take(count));
+    }
+    behaviours.resize(n_clients, None);
+    Ok(behaviours)
+}
+
+/// The outcome of a byzantine operation.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ByzantineOutcome {
+    /// The number of requests sent to storage nodes.
+    pub requests: usize,
+    /// The number of requests rejected by storage nodes.
+    pub rejected: usize,
+}
+
+impl ByzantineOutcome {
+    /// Records the requests of the operation in the metrics of the behaviour's workload.
+    pub fn observe(&self, metrics: &ClientMetrics, behaviour: ByzantineBehaviour) {
+        metrics.observe_byzantine_requests(
+            behaviour.workload(),
+            self.requests as u64,
+            self.rejected as u64,
+        );
+    }
+}
+
+/// The state of a byzantine write client.
+#[derive(Debug)]
+pub(crate) struct ByzantineClient {
+    behaviour: ByzantineBehaviour,
+    /// Clients for the storage nodes, by node ID.
+    node_clients: HashMap<ObjectID, StorageNodeClient>,
+    /// The blob targeted by recovery-symbol spam.
+    spam_target: Option<BlobId>,
+}
+
+impl ByzantineClient {
+    pub fn new(behaviour: ByzantineBehaviour) -> Self {
+        Self {
+            behaviour,
+            node_clients: HashMap::new(),
+            spam_target: None,
+        }
+    }
+
+    pub fn behaviour(&self) -> ByzantineBehaviour {
+        self.behaviour
+    }
+
+    /// Performs one byzantine operation with the given blob.
+    pub async fn run(
+        &mut self,
+        client: &Client<SuiContractClient>,
+        blob: &[u8],
+    ) -> Result<ByzantineOutcome, ClientError> {
+        match self.behaviour {
+            ByzantineBehaviour::RegisterOnly => {
+                let (_, metadata) = encode(client, blob)?;
+                register(client, &metadata).await?;
+                Ok(ByzantineOutcome::default())
+            }
+            ByzantineBehaviour::PartialUpload => {
+                let (pairs, metadata) = encode(client, blob)?;
+                register(client, &metadata).await?;
+                let mut nodes = self.nodes(client).await?;
+                nodes.shuffle(&mut thread_rng());
+
+                // Upload to nodes holding less than a quorum of shards, so that the blob can
+                // never be certified.
+                let n_shards = client.encoding_config().n_shards().get() as usize;
+                let mut uploaded_shards = 0;
+                let mut outcome = ByzantineOutcome::default();
+                for (node, node_client) in nodes {
+                    uploaded_shards += node.shard_ids.len();
+                    if 3 * uploaded_shards >= 2 * n_shards {
+                        break;
+                    }
+                    upload(client, &node, &node_client, &metadata, &pairs, &mut outcome).await;
+                }
+                Ok(outcome)
+            }
+            ByzantineBehaviour::WrongMetadata => {
+                let (_, metadata) = encode(client, blob)?;
+                register(client, &metadata).await?;
+                let mut wrong_metadata = metadata.metadata().to_owned();
+                wrong_metadata.mut_inner().hashes[0].primary_hash = Node::Digest([0; 32]);
+                let wrong_metadata = VerifiedBlobMetadataWithId::new_verified_unchecked(
+                    *metadata.blob_id(),
+                    wrong_metadata,
+                );
+
+                let mut outcome = ByzantineOutcome::default();
+                for (_, node_client) in self.nodes(client).await? {
+                    outcome.requests += 1;
+                    if node_client.store_metadata(&wrong_metadata).await.is_err() {
+                        outcome.rejected += 1;
+                    }
+                }
+                Ok(outcome)
+            }
+            ByzantineBehaviour::UnregisteredSlivers => {
+                let (pairs, metadata) = encode(client, blob)?;
+                let mut outcome = ByzantineOutcome::default();
+                for (node, node_client) in self.nodes(client).await? {
+                    upload(client, &node, &node_client, &metadata, &pairs, &mut outcome).await;
+                }
+                Ok(outcome)
+            }
+            ByzantineBehaviour::RecoverySymbolSpam => {
+                let blob_id = match self.spam_target {
+                    Some(blob_id) => blob_id,
+                    None => {
+                        let blob_id = store_target_blob(client, blob).await?;
+                        self.spam_target = Some(blob_id);
+                        blob_id
+                    }
+                };
+                let mut nodes = self.nodes(client).await?;
+                nodes.retain(|(node, _)| !node.shard_ids.is_empty());
+                let n_shards = client.encoding_config().n_shards();
+                let mut outcome = ByzantineOutcome::default();
+                for _ in 0..RECOVERY_SYMBOL_REQUESTS_PER_OPERATION {
+                    let (symbol_id, node_client) = {
+                        let mut rng = thread_rng();
+                        let Some((node, node_client)) = nodes.choose(&mut rng) else {
+                            break;
+                        };
+                        let symbol_id =
+                            held_symbol_id(&node.shard_ids, &blob_id, n_shards, &mut rng)
+                                .expect("the node holds at least one shard");
+                        (symbol_id, node_client)
+                    };
+                    outcome.requests += 1;
+                    if node_client
+                        .get_recovery_symbol(&blob_id, symbol_id)
+                        .await
+                        .is_err()
+                    {
+                        outcome.rejected += 1;
+                    }
+                }
+                Ok(outcome)
+            }
+        }
+    }
+
+    /// Returns the members of the current write committee with a client for each of them.
+    async fn nodes(
+        &mut self,
+        client: &Client<SuiContractClient>,
+    ) -> Result<Vec<(StorageNode, StorageNodeClient)>, ClientError> {
+        let committees = client.get_committees().await?;
+        let mut nodes = Vec::new();
+        for node in committees.write_committee().members() {
+            let node_client = match self.node_clients.get(&node.node_id) {
+                Some(node_client) => node_client.clone(),
+                None => {
+                    let node_client = StorageNodeClient::builder()
+                        .authenticate_with_public_key(node.network_public_key.clone())
+                        .no_proxy()
+                        .build(&node.network_address.0)
+                        .map_err(ClientError::other)?;
+                    self.node_clients.insert(node.node_id, node_client.clone());
+                    node_client
+                }
+            };
+            nodes.push((node.clone(), node_client));
+        }
+        Ok(nodes)
+    }
+}
+
+/// Returns the ID of a random recovery symbol of the blob that can be served by a node holding
+/// the given shards, or `None` if the node does not hold any shard.
+///
+/// A node serves a recovery symbol if it holds the sliver pair of the symbol's primary sliver
+/// index, or the sliver pair of its secondary sliver index.
+fn held_symbol_id<R: Rng + ?Sized>(
+    shard_ids: &[ShardIndex],
+    blob_id: &BlobId,
+    n_shards: NonZeroU16,
+    rng: &mut R,
+) -> Option<SymbolId> {
+    let pair_index = shard_ids.choose(rng)?.to_pair_index(n_shards, blob_id);
+    let other_index = SliverIndex(rng.gen_range(0..n_shards.get()));
+    Some(if rng.gen_bool(0.5) {
+        SymbolId::new(pair_index.to_sliver_index::<Primary>(n_shards), other_index)
+    } else {
+        SymbolId::new(
+            other_index,
+            pair_index.to_sliver_index::<Secondary>(n_shards),
+        )
+    })
+}
+
+/// Encodes the blob.
+fn encode(
+    client: &Client<SuiContractClient>,
+    blob: &[u8],
+) -> Result<(Vec<SliverPair>, VerifiedBlobMetadataWithId), ClientError> {
+    client
+        .encoding_config()
+        .get_for_type(DEFAULT_ENCODING)
+        .encode_with_metadata(blob)
+        .map_err(ClientError::other)
+}
+
+/// Registers the blob on Sui for one epoch.
+async fn register(
+    client: &Client<SuiContractClient>,
+    metadata: &VerifiedBlobMetadataWithId,
+) -> Result<(), ClientError> {
+    let committees = client.get_committees().await?;
+    client
+        .resource_manager(&committees)
+        .await
+        .get_existing_or_register(
+            &[metadata],
+            1,
+            BlobPersistence::Permanent,
+            StoreWhen::NotStored,
+        )
+        .await?;
+    Ok(())
+}
+
+/// Stores the blob targeted by recovery-symbol spam.
+async fn store_target_blob(
+    client: &Client<SuiContractClient>,
+    blob: &[u8],
+) -> Result<BlobId, ClientError> {
+    // Store the blob long enough to remain the target for the whole run.
+    let epochs_to_store = 7;
+    Ok(*client
+        .reserve_and_store_blobs_retry_committees(
+            &[blob],
+            DEFAULT_ENCODING,
+            epochs_to_store,
+            StoreWhen::Always,
+            BlobPersistence::Permanent,
+            PostStoreAction::Keep,
+        )
+        .await?
+        .first()
+        .expect("should have one blob store result")
+        .blob_id())
+}
+
+/// Uploads the metadata and the slivers of the shards of the node, recording the rejected
+/// requests in the outcome.
+async fn upload(
+    client: &Client<SuiContractClient>,
+    node: &StorageNode,
+    node_client: &StorageNodeClient,
+    metadata: &VerifiedBlobMetadataWithId,
+    pairs: &[SliverPair],
+    outcome: &mut ByzantineOutcome,
+) {
+    let blob_id = metadata.blob_id();
+    let n_shards = client.encoding_config().n_shards();
+
+    outcome.requests += 1;
+    if node_client.store_metadata(metadata).await.is_err() {
+        outcome.rejected += 1;
+    }
+    for shard in &node.shard_ids {
+        let pair_index = shard.to_pair_index(n_shards, blob_id);
+        let Some(pair) = pairs.iter().find(|pair| pair.index() == pair_index) else {
+            continue;
+        };
+        outcome.requests += 2;
+        if node_client
+            .store_sliver(blob_id, pair_index, &pair.primary)
+            .await
+            .is_err()
+        {
+            outcome.rejected += 1;
+        }
+        if node_client
+            .store_sliver(blob_id, pair_index, &pair.secondary)
+            .await
+            .is_err()
+        {
+            outcome.rejected += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_byzantine_fraction() {
+        let fraction: ByzantineFraction = "partial-upload=0.25".parse().unwrap();
+        assert_eq!(fraction.behaviour, ByzantineBehaviour::PartialUpload);
+        assert_eq!(fraction.fraction, 0.25);
+        assert!("partial-upload".parse::<ByzantineFraction>().is_err());
+        assert!("unknown=0.1".parse::<ByzantineFraction>().is_err());
+        assert!("register-only=1.5".parse::<ByzantineFraction>().is_err());
+    }
+
+    #[test]
+    fn assign_byzantine_behaviours() {
+        let fractions = [
+            "register-only=0.2".parse().unwrap(),
+            "recovery-symbol-spam=0.3".parse().unwrap(),
+        ];
+        let behaviours = assign_behaviours(&fractions, 10).unwrap();
+        assert_eq!(behaviours.len(), 10);
+        let count = |behaviour| behaviours.iter().filter(|x| **x == behaviour).count();
+        assert_eq!(count(Some(ByzantineBehaviour::RegisterOnly)), 2);
+        assert_eq!(count(Some(ByzantineBehaviour::RecoverySymbolSpam)), 3);
+        assert_eq!(count(None), 5);
+
+        let too_many = ["wrong-metadata=0.6".parse().unwrap(); 2];
+        assert!(assign_behaviours(&too_many, 10).is_err());
+    }
+
+    #[test]
+    fn byzantine_behaviour_display_round_trip() {
+        for behaviour in <ByzantineBehaviour as clap::ValueEnum>::value_variants() {
+            let fraction: ByzantineFraction = format!("{behaviour}=0.5").parse().unwrap();
+            assert_eq!(fraction.behaviour, *behaviour);
+        }
+    }
+
+    #[test]
+    fn held_symbol_id_is_served_by_the_node() {
+        let n_shards = NonZeroU16::new(10).unwrap();
+        let blob_id = BlobId([7; 32]);
+        let shard_ids = [ShardIndex(2), ShardIndex(5)];
+        let mut rng = thread_rng();
+        for _ in 0..100 {
+            let symbol_id = held_symbol_id(&shard_ids, &blob_id, n_shards, &mut rng).unwrap();
+            let primary_shard = symbol_id
+                .primary_sliver_index()
+                .to_pair_index::<Primary>(n_shards)
+                .to_shard_index(n_shards, &blob_id);
+            let secondary_shard = symbol_id
+                .secondary_sliver_index()
+                .to_pair_index::<Secondary>(n_shards)
+                .to_shard_index(n_shards, &blob_id);
+            assert!(
+                shard_ids.contains(&primary_shard) || shard_ids.contains(&secondary_shard),
+                "{symbol_id:?} is not held by shards {shard_ids:?}"
+            );
+        }
+        assert!(held_symbol_id(&[], &blob_id, n_shards, &mut rng).is_none());
+    }
+
+    #[test]
+    fn byzantine_outcome_is_counted_per_workload() {
+        let metrics = ClientMetrics::new(&prometheus::Registry::new());
+        let behaviour = ByzantineBehaviour::RecoverySymbolSpam;
+        let outcome = ByzantineOutcome {
+            requests: 50,
+            rejected: 12,
+        };
+        outcome.observe(&metrics, behaviour);
+        outcome.observe(&metrics, behaviour);
+
+        let workload = behaviour.workload();
+        assert_eq!(
+            metrics
+                .byzantine_requests
+                .with_label_values(&[workload])
+                .get(),
+            100
+        );
+        assert_eq!(
+            metrics
+                .byzantine_rejected
+                .with_label_values(&[workload])
+                .get(),
+            24
+        );
+        assert_eq!(
+            metrics
+                .byzantine_requests
+                .with_label_values(&[ByzantineBehaviour::RegisterOnly.workload()])
+                .get(),
+            0
+        );
+    }
+}
@@ -8,7 +8,10 @@ use std::{
 
 use indicatif::MultiProgress;
 use rand::{rngs::StdRng, thread_rng, SeedableRng};
-use sui_sdk::{types::base_types::SuiAddress, wallet_context::WalletContext};
+use sui_sdk::{
+    types::base_types::{ObjectID, SuiAddress},
+    wallet_context::WalletContext,
+};
 use walrus_core::{
     encoding::EncodingConfigTrait as _,
     merkle::Node,
@@ -19,6 +22,7 @@ use walrus_core::{
     DEFAULT_ENCODING,
 };
 use walrus_service::client::{
+    responses::{BlobStoreResult, EventOrObjectId},
     Client,
     ClientError,
     CommitteesRefresherHandle,
@@ -39,7 +43,10 @@ use walrus_sui::{
 };
 use walrus_test_utils::WithTempDir;
 
-use super::blob::BlobData;
+use super::{
+    blob::BlobData,
+    byzantine::{ByzantineBehaviour, ByzantineClient, ByzantineOutcome},
+};
 
 /// Client for writing test blobs to storage nodes
 /// Client for writing test blobs to storage nodes
@@ -47,11 +54,15 @@ use super::blob::BlobData;
 pub(crate) struct WriteClient {
     client: WithTempDir<Client<SuiContractClient>>,
     blob: BlobData,
+    byzantine: Option<ByzantineClient>,
 }
 
 impl WriteClient {
     /// Creates a new WriteClient with the given configuration
+    ///
+    /// If a byzantine behaviour is specified, the client performs it instead of honest writes.
     #[tracing::instrument(err, skip_all)]
+    #[allow(clippy::too_many_arguments)]
     pub async fn new(
         config: &Config,
         network: &SuiNetwork,
@@ -60,6 +71,7 @@ impl WriteClient {
         max_size_log2: u8,
         refresher_handle: CommitteesRefresherHandle,
         refiller: Refiller,
+        byzantine_behaviour: Option<ByzantineBehaviour>,
     ) -> anyhow::Result<Self> {
         let blob = BlobData::random(
             StdRng::from_rng(thread_rng()).expect("rng should be seedable from thread_rng"),
@@ -68,7 +80,33 @@ impl WriteClient {
         )
         .await;
         let client = new_client(config, network, gas_budget, refresher_handle, refiller).await?;
-        Ok(Self { client, blob })
+        Ok(Self {
+            client,
+            blob,
+            byzantine: byzantine_behaviour.map(ByzantineClient::new),
+        })
+    }
+
+    /// Returns the byzantine behaviour of the client, if any.
+    pub fn byzantine_behaviour(&self) -> Option<ByzantineBehaviour> {
+        self.byzantine.as_ref().map(ByzantineClient::behaviour)
+    }
+
+    /// Performs one operation of the byzantine behaviour of the client with a fresh blob, and
+    /// returns its outcome and the elapsed time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the client is honest.
+    pub async fn run_byzantine(&mut self) -> Result<(ByzantineOutcome, Duration), ClientError> {
+        let byzantine = self
+            .byzantine
+            .as_mut()
+            .expect("only called on byzantine clients");
+        let blob = self.blob.refresh_and_get_random_slice();
+        let now = Instant::now();
+        let outcome = byzantine.run(self.client.as_ref(), blob).await?;
+        Ok((outcome, now.elapsed()))
     }
 
     /// Returns the active address of the client.
@@ -89,33 +127,47 @@ impl WriteClient {
     ) -> Result<(BlobId, Duration), ClientError> {
         let blob = self.blob.refresh_and_get_random_slice();
         let now = Instant::now();
-        let blob_id = self
-            .client
-            .as_ref()
-            // TODO(giac): add also some deletable blobs in the mix (#800).
-            .reserve_and_store_blobs_retry_committees(
-                &[blob],
-                DEFAULT_ENCODING,
-                epochs_to_store,
-                StoreWhen::Always,
-                BlobPersistence::Permanent,
-                PostStoreAction::Keep,
-            )
-            .await?
-            .first()
-            .expect("should have one blob store result")
-            .blob_id()
-            .to_owned();
+        let blob_id = *store_blob(
+            self.client.as_ref(),
+            blob,
+            epochs_to_store,
+            BlobPersistence::Permanent,
+        )
+        .await?
+        .blob_id();
         Ok((blob_id, now.elapsed()))
     }
 
+    /// Stores a fresh blob of the given size and persistence for the given number of epochs
+    /// ahead, and returns the blob id, the ID of the blob object (if known), and the elapsed time.
+    pub async fn write_fresh_blob_with_size(
+        &mut self,
+        size: usize,
+        epochs_to_store: EpochCount,
+        persistence: BlobPersistence,
+    ) -> Result<(BlobId, Option<ObjectID>, Duration), ClientError> {
+        let blob = self.blob.refresh_and_get_slice_of_size(size);
+        let now = Instant::now();
+        let result = store_blob(self.client.as_ref(), blob, epochs_to_store, persistence).await?;
+        let elapsed = now.elapsed();
+        let object_id = match &result {
+            BlobStoreResult::NewlyCreated { blob_object, .. } => Some(blob_object.id),
+            BlobStoreResult::AlreadyCertified {
+                event_or_object: EventOrObjectId::Object(object_id),
+                ..
+            } => Some(*object_id),
+            _ => None,
+        };
+        Ok((*result.blob_id(), object_id, elapsed))
+    }
+
     /// Stores a fresh blob that is inconsistent in primary sliver 0 and returns
     /// the blob id and elapsed time.
     pub async fn write_fresh_inconsistent_blob(
         &mut self,
     ) -> Result<(BlobId, Duration), ClientError> {
-        self.blob.refresh();
-        let blob = self.blob.random_size_slice();
+        let size = self.blob.refresh_random_size();
+        let blob = &self.blob.as_ref()[..size];
         let now = Instant::now();
         let blob_id = self.reserve_and_store_inconsistent_blob(blob).await?;
         Ok((blob_id, now.elapsed()))
@@ -214,6 +266,28 @@ impl WriteClient {
     }
 }
 
+/// Stores a single blob and returns the store result.
+async fn store_blob(
+    client: &Client<SuiContractClient>,
+    blob: &[u8],
+    epochs_to_store: EpochCount,
+    persistence: BlobPersistence,
+) -> Result<BlobStoreResult, ClientError> {
+    Ok(client
+        .reserve_and_store_blobs_retry_committees(
+            &[blob],
+            DEFAULT_ENCODING,
+            epochs_to_store,
+            StoreWhen::Always,
+            persistence,
+            PostStoreAction::Keep,
+        )
+        .await?
+        .into_iter()
+        .next()
+        .expect("should have one blob store result"))
+}
+
 /// Creates a new client with a separate wallet.
 async fn new_client(
     config: &Config,
@@ -19,9 +19,13 @@ use walrus_service::{
 };
 use walrus_sui::{config::load_wallet_context_from_path, utils::SuiNetwork};
 
-use crate::generator::LoadGenerator;
+use crate::{
+    generator::{byzantine::ByzantineFraction, LoadGenerator},
+    scenario::Scenario,
+};
 
 mod generator;
+mod scenario;
 
 /// The amount of gas or MIST to refil each time.
 const COIN_REFILL_AMOUNT: u64 = 500_000_000;
@@ -77,6 +81,22 @@ struct Args {
     /// the stress client will try to use the faucet.
     #[clap(long)]
     wallet_path: Option<PathBuf>,
+    /// The path to a YAML scenario file defining phases of read and write workloads.
+    ///
+    /// If specified, the scenario replaces the write and read loads and the blob size
+    /// distribution given on the command line.
+    #[clap(long)]
+    scenario_path: Option<PathBuf>,
+    /// The file to which the per-phase latency histograms of the scenario are written.
+    #[clap(long, default_value = "./scenario-results.json")]
+    scenario_results_path: PathBuf,
+    /// The fraction of write clients exhibiting a byzantine behaviour, as `BEHAVIOUR=FRACTION`.
+    ///
+    /// Can be repeated for multiple behaviours. The supported behaviours are `register-only`,
+    /// `partial-upload`, `wrong-metadata`, `unregistered-slivers`, and `recovery-symbol-spam`.
+    /// Byzantine clients perform their behaviour instead of the regular writes.
+    #[clap(long = "byzantine", value_name = "BEHAVIOUR=FRACTION")]
+    byzantine: Vec<ByzantineFraction>,
 }
 
 #[tokio::main]
@@ -87,6 +107,23 @@ async fn main() -> anyhow::Result<()> {
     let config: Config =
         load_from_yaml(args.config_path).context("Failed to load client config")?;
     let n_clients = args.n_clients.get();
+    let scenario = args
+        .scenario_path
+        .as_ref()
+        .map(Scenario::load)
+        .transpose()?;
+
+    // The write clients hold a buffer large enough for the largest blob of the scenario.
+    let (min_size_log2, max_size_log2) = match &scenario {
+        Some(scenario) => {
+            let max_size_log2 = scenario
+                .max_blob_size()
+                .next_power_of_two()
+                .trailing_zeros() as u8;
+            (args.min_size_log2, max_size_log2.max(args.min_size_log2))
+        }
+        None => (args.min_size_log2, args.max_size_log2),
+    };
 
     // Start the metrics server.
     let metrics_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), args.metrics_port);
@@ -109,18 +146,28 @@ async fn main() -> anyhow::Result<()> {
     );
     let mut load_generator = LoadGenerator::new(
         n_clients,
-        args.min_size_log2,
-        args.max_size_log2,
+        min_size_log2,
+        max_size_log2,
         config,
         args.sui_network,
         gas_refill_period,
         metrics,
         refiller,
+        &args.byzantine,
     )
     .await?;
 
-    load_generator
-        .start(args.write_load, args.read_load, args.inconsistent_blob_rate)
-        .await?;
+    match scenario {
+        Some(scenario) => {
+            load_generator
+                .run_scenario(&scenario, &args.scenario_results_path)
+                .await?
+        }
+        None => {
+            load_generator
+                .start(args.write_load, args.read_load, args.inconsistent_blob_rate)
+                .await?
+        }
+    }
     Ok(())
 }
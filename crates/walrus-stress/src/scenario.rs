@@ -0,0 +1,602 @@
+// Copyright (c) Walrus Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Scenario files describing phased read and write workloads.
+
+use std::{collections::BTreeMap, fs, path::Path, time::Duration};
+
+use anyhow::{ensure, Context};
+use rand::{
+    distributions::{Distribution, WeightedIndex},
+    Rng,
+};
+use serde::{Deserialize, Serialize};
+use sui_sdk::types::base_types::ObjectID;
+use walrus_core::{BlobId, EpochCount};
+
+/// A scenario, i.e., a sequence of phases run one after the other.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct Scenario {
+    /// The phases of the scenario.
+    pub phases: Vec<Phase>,
+}
+
+impl Scenario {
+    /// Loads and validates a scenario from a YAML file.
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("unable to read scenario file {}", path.display()))?;
+        let scenario: Self = serde_yaml::from_str(&content)
+            .with_context(|| format!("unable to parse scenario file {}", path.display()))?;
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        ensure!(
+            !self.phases.is_empty(),
+            "the scenario must have at least one phase"
+        );
+        for phase in &self.phases {
+            ensure!(
+                (0.0..=1.0).contains(&phase.deletable_ratio),
+                "the deletable ratio of phase '{}' must be between 0 and 1",
+                phase.name
+            );
+            ensure!(
+                phase.epochs_to_store > 0,
+                "the blobs written in phase '{}' must be stored for at least one epoch",
+                phase.name
+            );
+            ensure!(
+                !phase.sizes.is_empty(),
+                "phase '{}' must define at least one size bucket",
+                phase.name
+            );
+            for bucket in &phase.sizes {
+                // Empty blobs cannot be refreshed to yield a new blob for each write.
+                ensure!(
+                    bucket.min > 0,
+                    "the size buckets of phase '{}' must not include empty blobs",
+                    phase.name
+                );
+                ensure!(
+                    bucket.min <= bucket.max,
+                    "invalid size bucket [{}, {}] in phase '{}'",
+                    bucket.min,
+                    bucket.max,
+                    phase.name
+                );
+            }
+            phase
+                .size_distribution()
+                .with_context(|| format!("invalid size weights in phase '{}'", phase.name))?;
+            phase
+                .reads
+                .distribution()
+                .with_context(|| format!("invalid read mix in phase '{}'", phase.name))?;
+            if let Popularity::Zipf { exponent } = phase.popularity {
+                ensure!(
+                    exponent >= 0.0,
+                    "the Zipf exponent of phase '{}' must be non-negative",
+                    phase.name
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the largest number of epochs ahead for which the scenario stores blobs.
+    pub fn max_epochs_to_store(&self) -> EpochCount {
+        self.phases
+            .iter()
+            .map(|phase| phase.epochs_to_store)
+            .max()
+            .unwrap_or(DEFAULT_EPOCHS_TO_STORE)
+    }
+
+    /// Returns the largest blob size written by the scenario.
+    pub fn max_blob_size(&self) -> usize {
+        self.phases
+            .iter()
+            .flat_map(|phase| phase.sizes.iter().map(|bucket| bucket.max))
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+/// The default number of epochs ahead for which the blobs of a phase are stored, such that they
+/// can still be read in later phases.
+const DEFAULT_EPOCHS_TO_STORE: EpochCount = 7;
+
+fn default_epochs_to_store() -> EpochCount {
+    DEFAULT_EPOCHS_TO_STORE
+}
+
+/// A phase of a scenario.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct Phase {
+    /// The name of the phase, used in the results file.
+    pub name: String,
+    /// The duration of the phase in seconds.
+    pub duration_secs: u64,
+    /// The write load of the phase (writes/minute).
+    #[serde(default)]
+    pub write_load: u64,
+    /// The read load of the phase (reads/minute).
+    #[serde(default)]
+    pub read_load: u64,
+    /// The fraction of the written blobs that are deletable; the others are permanent.
+    #[serde(default)]
+    pub deletable_ratio: f64,
+    /// The number of epochs ahead for which the written blobs are stored.
+    #[serde(default = "default_epochs_to_store")]
+    pub epochs_to_store: EpochCount,
+    /// The histogram of the sizes of the written blobs.
+    pub sizes: Vec<SizeBucket>,
+    /// The popularity of the previously written blobs for reads.
+    #[serde(default)]
+    pub popularity: Popularity,
+    /// The mix of read operations.
+    #[serde(default)]
+    pub reads: ReadMix,
+}
+
+impl Phase {
+    /// Returns the duration of the phase.
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs(self.duration_secs)
+    }
+
+    fn size_distribution(&self) -> anyhow::Result<WeightedIndex<u64>> {
+        Ok(WeightedIndex::new(
+            self.sizes.iter().map(|bucket| bucket.weight),
+        )?)
+    }
+
+    /// Returns a sampler for the sizes of the blobs written in this phase.
+    pub fn size_sampler(&self) -> SizeSampler {
+        SizeSampler {
+            buckets: self.sizes.clone(),
+            distribution: self
+                .size_distribution()
+                .expect("the scenario was validated when loading it"),
+        }
+    }
+
+    /// Samples the persistence of a blob written in this phase; returns true if deletable.
+    pub fn sample_deletable<R: Rng + ?Sized>(&self, rng: &mut R) -> bool {
+        rng.gen_bool(self.deletable_ratio)
+    }
+}
+
+/// A bucket of the histogram of blob sizes. Blob sizes are uniformly distributed in the bucket.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct SizeBucket {
+    /// The minimum size of the blobs in the bucket (in bytes).
+    pub min: usize,
+    /// The maximum size of the blobs in the bucket (in bytes).
+    pub max: usize,
+    /// The relative weight of the bucket.
+    pub weight: u64,
+}
+
+/// Samples blob sizes from a histogram.
+#[derive(Debug, Clone)]
+pub struct SizeSampler {
+    buckets: Vec<SizeBucket>,
+    distribution: WeightedIndex<u64>,
+}
+
+impl SizeSampler {
+    /// Samples a blob size.
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> usize {
+        let bucket = &self.buckets[self.distribution.sample(rng)];
+        rng.gen_range(bucket.min..=bucket.max)
+    }
+}
+
+/// The popularity distribution of the previously written blobs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "distribution")]
+pub enum Popularity {
+    /// All blobs are equally popular.
+    #[default]
+    Uniform,
+    /// The popularity of the blobs follows a Zipf distribution over the order in which they
+    /// were written: the `k`-th written blob is read with a probability proportional to
+    /// `1 / k^exponent`.
+    Zipf {
+        /// The exponent of the distribution.
+        exponent: f64,
+    },
+}
+
+impl Popularity {
+    fn weight(&self, rank: usize) -> f64 {
+        match self {
+            Self::Uniform => 1.0,
+            Self::Zipf { exponent } => 1.0 / (rank as f64).powf(*exponent),
+        }
+    }
+}
+
+/// The kind of a read operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReadKind {
+    /// Read the full blob by blob ID.
+    BlobId,
+    /// Look up the blob object on Sui and read the blob it refers to.
+    ObjectId,
+    /// Read a random byte range of the blob by blob ID.
+    ///
+    /// Only the primary slivers containing the range are retrieved, unless some of them are
+    /// unavailable and the full blob has to be reconstructed.
+    Range,
+}
+
+/// The relative weights of the kinds of read operations.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct ReadMix {
+    /// The weight of reads by blob ID.
+    #[serde(default)]
+    pub blob_id: u32,
+    /// The weight of reads by object ID.
+    #[serde(default)]
+    pub object_id: u32,
+    /// The weight of range reads.
+    #[serde(default)]
+    pub range: u32,
+}
+
+impl Default for ReadMix {
+    fn default() -> Self {
+        Self {
+            blob_id: 1,
+            object_id: 0,
+            range: 0,
+        }
+    }
+}
+
+impl ReadMix {
+    const KINDS: [ReadKind; 3] = [ReadKind::BlobId, ReadKind::ObjectId, ReadKind::Range];
+
+    fn distribution(&self) -> anyhow::Result<WeightedIndex<u32>> {
+        Ok(WeightedIndex::new([
+            self.blob_id,
+            self.object_id,
+            self.range,
+        ])?)
+    }
+
+    /// Returns a sampler for the kinds of read operations.
+    pub fn sampler(&self) -> ReadKindSampler {
+        ReadKindSampler(
+            self.distribution()
+                .expect("the scenario was validated when loading it"),
+        )
+    }
+}
+
+/// Samples the kinds of read operations.
+#[derive(Debug, Clone)]
+pub struct ReadKindSampler(WeightedIndex<u32>);
+
+impl ReadKindSampler {
+    /// Samples the kind of a read operation.
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> ReadKind {
+        ReadMix::KINDS[self.0.sample(rng)]
+    }
+}
+
+/// A blob written during the scenario.
+#[derive(Debug, Clone, Copy)]
+pub struct WrittenBlob {
+    /// The ID of the blob.
+    pub blob_id: BlobId,
+    /// The ID of the Sui object of the blob, if known.
+    pub object_id: Option<ObjectID>,
+    /// The size of the blob.
+    pub size: usize,
+}
+
+/// The blobs written so far, from which reads are sampled.
+#[derive(Debug)]
+pub struct BlobPool {
+    blobs: Vec<WrittenBlob>,
+    /// The cumulative popularity weights of the blobs, in write order.
+    cumulative_weights: Vec<f64>,
+    popularity: Popularity,
+    /// The blob read while no blob was written yet.
+    fallback: Option<WrittenBlob>,
+}
+
+impl BlobPool {
+    /// Creates an empty pool with uniform popularity.
+    pub fn new() -> Self {
+        Self {
+            blobs: vec![],
+            cumulative_weights: vec![],
+            popularity: Popularity::Uniform,
+            fallback: None,
+        }
+    }
+
+    /// Sets the blob that is read while no blob was written yet.
+    ///
+    /// The fallback blob is not part of the popularity distribution, so that it does not take
+    /// the most popular rank once blobs are written.
+    pub fn set_fallback(&mut self, blob: WrittenBlob) {
+        self.fallback = Some(blob);
+    }
+
+    /// Returns the number of blobs in the pool.
+    pub fn len(&self) -> usize {
+        self.blobs.len()
+    }
+
+    /// Sets the popularity distribution of the blobs.
+    pub fn set_popularity(&mut self, popularity: Popularity) {
+        if self.popularity == popularity {
+            return;
+        }
+        self.popularity = popularity;
+        let blobs = std::mem::take(&mut self.blobs);
+        self.cumulative_weights.clear();
+        for blob in blobs {
+            self.push(blob);
+        }
+    }
+
+    /// Adds a newly written blob to the pool.
+    pub fn push(&mut self, blob: WrittenBlob) {
+        let weight = self.popularity.weight(self.blobs.len() + 1);
+        let total = self.cumulative_weights.last().copied().unwrap_or_default();
+        self.cumulative_weights.push(total + weight);
+        self.blobs.push(blob);
+    }
+
+    /// Samples a blob according to the popularity distribution, or returns the fallback blob if
+    /// no blob was written yet.
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<WrittenBlob> {
+        let Some(total) = self.cumulative_weights.last().copied() else {
+            return self.fallback;
+        };
+        let target = rng.gen_range(0.0..total);
+        let index = self
+            .cumulative_weights
+            .partition_point(|weight| *weight <= target)
+            .min(self.blobs.len() - 1);
+        Some(self.blobs[index])
+    }
+}
+
+/// The upper bounds (in milliseconds) of the buckets of the latency histograms.
+const LATENCY_BUCKETS_MS: &[u64] = &[
+    10, 25, 50, 100, 250, 500, 1_000, 2_500, 5_000, 10_000, 25_000, 50_000, 100_000,
+];
+
+/// A bucket of a latency histogram.
+#[derive(Debug, Clone, Serialize)]
+pub struct LatencyBucket {
+    /// The upper bound of the bucket (in milliseconds), or `inf`.
+    le: String,
+    /// The number of operations with a latency up to the bound.
+    count: u64,
+}
+
+/// A cumulative latency histogram.
+#[derive(Debug, Clone, Serialize)]
+pub struct LatencyHistogram {
+    /// The buckets of the histogram, in increasing order of their bounds.
+    buckets: Vec<LatencyBucket>,
+    /// The number of successful operations.
+    count: u64,
+    /// The number of failed operations.
+    errors: u64,
+    /// The sum of all latencies (in milliseconds).
+    sum_ms: u64,
+    /// The maximum latency (in milliseconds).
+    max_ms: u64,
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self {
+            buckets: LATENCY_BUCKETS_MS
+                .iter()
+                .map(ToString::to_string)
+                .chain(["inf".to_owned()])
+                .map(|le| LatencyBucket { le, count: 0 })
+                .collect(),
+            count: 0,
+            errors: 0,
+            sum_ms: 0,
+            max_ms: 0,
+        }
+    }
+}
+
+impl LatencyHistogram {
+    /// Records the latency of a successful operation.
+    pub fn observe(&mut self, latency: Duration) {
+        let latency_ms = u64::try_from(latency.as_millis()).unwrap_or(u64::MAX);
+        for (bucket, bound) in self.buckets.iter_mut().zip(LATENCY_BUCKETS_MS) {
+            if latency_ms <= *bound {
+                bucket.count += 1;
+            }
+        }
+        if let Some(bucket) = self.buckets.last_mut() {
+            bucket.count += 1;
+        }
+        self.count += 1;
+        self.sum_ms = self.sum_ms.saturating_add(latency_ms);
+        self.max_ms = self.max_ms.max(latency_ms);
+    }
+
+    /// Records a failed operation.
+    pub fn observe_error(&mut self) {
+        self.errors += 1;
+    }
+}
+
+/// The latency histograms of the operations of a phase.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PhaseResults {
+    /// The name of the phase.
+    pub name: String,
+    /// The latency histogram of the writes.
+    pub writes: LatencyHistogram,
+    /// The latency histograms of the reads, by kind.
+    pub reads: BTreeMap<ReadKind, LatencyHistogram>,
+    /// The number of reads skipped because no blob was available yet.
+    pub skipped_reads: u64,
+}
+
+impl PhaseResults {
+    /// Creates empty results for the phase with the given name.
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            ..Default::default()
+        }
+    }
+}
+
+/// Writes the results of the phases run so far to the given file, as JSON.
+pub fn write_results(path: &Path, results: &[PhaseResults]) -> anyhow::Result<()> {
+    let json = serde_json::to_string_pretty(results)?;
+    fs::write(path, json)
+        .with_context(|| format!("unable to write scenario results to {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    const SCENARIO: &str = r#"
+phases:
+  - name: warmup
+    duration_secs: 60
+    write_load: 60
+    sizes:
+      - { min: 1024, max: 4096, weight: 1 }
+  - name: hot-reads
+    duration_secs: 120
+    write_load: 10
+    read_load: 600
+    deletable_ratio: 0.25
+    popularity:
+      distribution: zipf
+      exponent: 1.2
+    epochs_to_store: 3
+    reads:
+      blob_id: 2
+      object_id: 1
+      range: 1
+    sizes:
+      - { min: 1024, max: 1024, weight: 9 }
+      - { min: 1048576, max: 2097152, weight: 1 }
+"#;
+
+    fn blob(size: usize) -> WrittenBlob {
+        WrittenBlob {
+            blob_id: BlobId([0; 32]),
+            object_id: None,
+            size,
+        }
+    }
+
+    #[test]
+    fn parse_scenario() {
+        let scenario: Scenario = serde_yaml::from_str(SCENARIO).unwrap();
+        scenario.validate().unwrap();
+        assert_eq!(scenario.phases.len(), 2);
+        assert_eq!(scenario.max_blob_size(), 2_097_152);
+        assert_eq!(scenario.phases[1].epochs_to_store, 3);
+        assert_eq!(scenario.phases[1].reads.range, 1);
+        assert_eq!(scenario.max_epochs_to_store(), DEFAULT_EPOCHS_TO_STORE);
+        assert_eq!(scenario.phases[0].popularity, Popularity::Uniform);
+        assert_eq!(
+            scenario.phases[1].popularity,
+            Popularity::Zipf { exponent: 1.2 }
+        );
+    }
+
+    #[test]
+    fn reject_invalid_read_mix() {
+        let mut scenario: Scenario = serde_yaml::from_str(SCENARIO).unwrap();
+        scenario.phases[1].reads = ReadMix {
+            blob_id: 0,
+            object_id: 0,
+            range: 0,
+        };
+        assert!(scenario.validate().is_err());
+    }
+
+    #[test]
+    fn reject_empty_blob_sizes() {
+        let mut scenario: Scenario = serde_yaml::from_str(SCENARIO).unwrap();
+        scenario.phases[0].sizes[0].min = 0;
+        assert!(scenario.validate().is_err());
+    }
+
+    #[test]
+    fn reject_zero_epochs_to_store() {
+        let mut scenario: Scenario = serde_yaml::from_str(SCENARIO).unwrap();
+        scenario.phases[0].epochs_to_store = 0;
+        assert!(scenario.validate().is_err());
+    }
+
+    #[test]
+    fn fallback_blob_is_only_read_while_the_pool_is_empty() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut pool = BlobPool::new();
+        pool.set_popularity(Popularity::Zipf { exponent: 1.5 });
+        pool.set_fallback(blob(1000));
+        assert_eq!(pool.sample(&mut rng).unwrap().size, 1000);
+
+        for size in 0..10 {
+            pool.push(blob(size));
+        }
+        assert_eq!(pool.len(), 10);
+        assert!((0..1000).all(|_| pool.sample(&mut rng).unwrap().size < 10));
+    }
+
+    #[test]
+    fn zipf_popularity_favors_early_blobs() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut pool = BlobPool::new();
+        assert!(pool.sample(&mut rng).is_none());
+        for size in 0..100 {
+            pool.push(blob(size));
+        }
+        pool.set_popularity(Popularity::Zipf { exponent: 1.5 });
+
+        let samples = 10_000;
+        let first = (0..samples)
+            .filter(|_| pool.sample(&mut rng).unwrap().size == 0)
+            .count();
+        // The first blob has probability 1 / H(100, 1.5) ~ 0.41.
+        assert!(first > samples * 35 / 100 && first < samples * 47 / 100);
+    }
+
+    #[test]
+    fn latency_histogram_is_cumulative() {
+        let mut histogram = LatencyHistogram::default();
+        histogram.observe(Duration::from_millis(30));
+        histogram.observe(Duration::from_secs(200));
+        let counts: Vec<_> = histogram.buckets.iter().map(|x| x.count).collect();
+        assert_eq!(counts, vec![0, 0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 2]);
+        assert_eq!(histogram.max_ms, 200_000);
+    }
+}
@@ -10,9 +10,16 @@ use inflections::Inflect;
 use regex::Regex;
 use walkdir::WalkDir;
 
+struct ErrorDef {
+    name: String,
+    code: u64,
+    /// The doc comment of the error constant, used as human-readable description.
+    description: String,
+}
+
 struct ModuleErrorDefs {
     fully_qualified_module: String,
-    error_defs: Vec<(String, u64)>,
+    error_defs: Vec<ErrorDef>,
 }
 
 impl ModuleErrorDefs {
@@ -74,8 +81,9 @@ fn generate_error_defs_for_contracts(contracts_dir: &Path, out_dir: &Path) {
 fn collect_error_definitions(contracts_dir: &Path) -> Vec<ModuleErrorDefs> {
     let module_pattern =
         Regex::new(r"module\s+([a-z_]+::[a-z_]+)\s*;").expect("should be able to compile regex");
-    let error_pattern = Regex::new(r"const\s+(E(?:[A-Z][a-z]+)*)\s*:\s*u64\s*=\s*(\d+)")
+    let error_pattern = Regex::new(r"^\s*const\s+(E[A-Z][A-Za-z0-9]*)\s*:\s*u64\s*=\s*(\d+)")
         .expect("should be able to compile regex");
+    let doc_pattern = Regex::new(r"^\s*///\s?(.*)$").expect("should be able to compile regex");
     let mut module_error_defs = Vec::new();
 
     for entry in WalkDir::new(contracts_dir)
@@ -101,19 +109,36 @@ fn collect_error_definitions(contracts_dir: &Path) -> Vec<ModuleErrorDefs> {
         let module_name = module_captures[1].to_owned();
 
         let mut error_defs = Vec::new();
-        for cap in error_pattern.captures_iter(&content) {
-            let error_name = cap[1].to_owned();
-            let error_code = cap[2]
-                .parse::<u64>()
-                .expect("error code captures digits only");
-            error_defs.push((error_name, error_code));
+        let mut doc_lines = Vec::new();
+        for line in content.lines() {
+            if let Some(cap) = doc_pattern.captures(line) {
+                doc_lines.push(cap[1].trim().to_owned());
+                continue;
+            }
+            if let Some(cap) = error_pattern.captures(line) {
+                let name = cap[1].to_owned();
+                let code = cap[2]
+                    .parse::<u64>()
+                    .expect("error code captures digits only");
+                let description = if doc_lines.is_empty() {
+                    describe_error_name(&name)
+                } else {
+                    doc_lines.join(" ")
+                };
+                error_defs.push(ErrorDef {
+                    name,
+                    code,
+                    description,
+                });
+            }
+            doc_lines.clear();
         }
 
         if error_defs.is_empty() {
             continue;
         }
 
-        error_defs.sort_by_key(|e| e.1);
+        error_defs.sort_by_key(|e| e.code);
 
         module_error_defs.push(ModuleErrorDefs {
             fully_qualified_module: module_name,
@@ -124,10 +149,20 @@ fn collect_error_definitions(contracts_dir: &Path) -> Vec<ModuleErrorDefs> {
     module_error_defs
 }
 
+/// Derives a description from the name of an error without doc comment, e.g., `EPoolNotFound`
+/// becomes "Pool not found.".
+fn describe_error_name(name: &str) -> String {
+    format!(
+        "{}.",
+        name.strip_prefix('E').unwrap_or(name).to_sentence_case()
+    )
+}
+
 /// Generate the definitions for the `MoveExecutionError` enum and all error kinds.
 fn generate_all_error_defs(module_error_defs: &[ModuleErrorDefs]) -> String {
     let mut code = generate_move_error_enum(module_error_defs);
     code.push_str(&generate_move_error_from_impl(module_error_defs));
+    code.push_str(&generate_move_error_module_and_kind_impl(module_error_defs));
     code.push_str(&generate_error_kind_defs(module_error_defs));
     code
 }
@@ -142,8 +177,11 @@ fn generate_error_kind_defs(module_error_defs: &[ModuleErrorDefs]) -> String {
             module_error.error_kind_name(),
             module_error.fully_qualified_module
         ));
-        for (error_name, error_code) in module_error.error_defs.iter() {
-            code.push_str(&format!("    {}, {},\n", error_name, error_code));
+        for error_def in module_error.error_defs.iter() {
+            code.push_str(&format!(
+                "    {}, {}, {:?},\n",
+                error_def.name, error_def.code, error_def.description
+            ));
         }
         code.push_str(");\n\n");
     }
@@ -212,3 +250,28 @@ fn generate_move_error_from_impl(module_error_defs: &[ModuleErrorDefs]) -> Strin
 
     code
 }
+
+fn generate_move_error_module_and_kind_impl(module_error_defs: &[ModuleErrorDefs]) -> String {
+    let mut code = String::new();
+
+    code.push_str("impl MoveExecutionError {\n");
+    code.push_str(
+        "    fn module_and_kind(&self) -> Option<(&'static str, &dyn MoveErrorKind)> {\n",
+    );
+    code.push_str("        match self {\n");
+
+    for module_error in module_error_defs {
+        code.push_str(&format!(
+            "            Self::{}(kind) => Some((\"{}\", kind)),\n",
+            module_error.enum_variant_name(),
+            module_error.module_name(),
+        ));
+    }
+
+    code.push_str("            Self::OtherMoveModule(_) | Self::NotParsable(_) => None,\n");
+    code.push_str("        }\n");
+    code.push_str("    }\n");
+    code.push_str("}\n\n");
+
+    code
+}
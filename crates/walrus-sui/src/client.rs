@@ -23,6 +23,7 @@ use sui_sdk::{
     rpc_types::{
         get_new_package_obj_from_response,
         Coin,
+        DryRunTransactionBlockResponse,
         SuiExecutionStatus,
         SuiTransactionBlockEffectsAPI,
         SuiTransactionBlockResponse,
@@ -32,15 +33,28 @@ use sui_sdk::{
 };
 use sui_types::{
     base_types::SuiAddress,
+    digests::TransactionDigest,
     event::EventID,
     programmable_transaction_builder::ProgrammableTransactionBuilder,
-    transaction::{Argument, ProgrammableTransaction, TransactionData, TransactionKind},
+    signature::GenericSignature,
+    transaction::{
+        Argument,
+        ProgrammableTransaction,
+        Transaction,
+        TransactionData,
+        TransactionKind,
+    },
     TypeTag,
 };
 use tokio::sync::Mutex;
 use tokio_stream::Stream;
 use tracing::Level;
-use transaction_builder::{WalrusPtbBuilder, MAX_BURNS_PER_PTB};
+use transaction_builder::{
+    ArgumentOrOwnedObject,
+    WalrusPtbBuilder,
+    MAX_BURNS_PER_PTB,
+    MAX_EXTENSIONS_PER_PTB,
+};
 use walrus_core::{
     ensure,
     merkle::Node as MerkleNode,
@@ -80,6 +94,10 @@ use crate::{
     utils::get_created_sui_object_ids_by_type,
 };
 
+mod gas_sponsor;
+pub use gas_sponsor::GasSponsor;
+use gas_sponsor::SponsoredGas;
+
 mod read_client;
 pub use read_client::{
     CoinType,
@@ -184,6 +202,14 @@ pub enum SuiClientError {
         FROST for staking"
     )]
     StakeBelowThreshold(u64),
+    /// The transaction was handed to an [`UnsignedTransactionSink`] instead of being signed and
+    /// executed.
+    #[error("the transaction with digest {0} was emitted for external signing")]
+    UnsignedTransactionEmitted(TransactionDigest),
+    /// The transaction was dry-run and handed to a [`DryRunSink`] instead of being signed and
+    /// executed.
+    #[error("the transaction with digest {0} was dry-run and not executed")]
+    TransactionDryRun(TransactionDigest),
 }
 
 impl SuiClientError {
@@ -211,6 +237,113 @@ impl SuiClientError {
     }
 }
 
+/// Collects transactions that are to be signed outside of the wallet (e.g., on an offline machine
+/// or a hardware wallet) instead of being signed and executed by the [`SuiContractClient`].
+///
+/// When a sink is set on a [`SuiContractClient`], the first transaction the client would execute is
+/// pushed into the sink and the operation aborts with
+/// [`SuiClientError::UnsignedTransactionEmitted`].
+#[derive(Debug, Clone, Default)]
+pub struct UnsignedTransactionSink(Arc<std::sync::Mutex<Vec<TransactionData>>>);
+
+impl UnsignedTransactionSink {
+    /// Creates a new, empty sink.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a transaction to the sink.
+    fn push(&self, transaction: TransactionData) {
+        self.0
+            .lock()
+            .expect("the mutex should not be poisoned")
+            .push(transaction);
+    }
+
+    /// Removes and returns all transactions collected so far.
+    pub fn take(&self) -> Vec<TransactionData> {
+        std::mem::take(&mut *self.0.lock().expect("the mutex should not be poisoned"))
+    }
+}
+
+/// A transaction together with the result of dry-running it.
+#[derive(Debug, Clone)]
+pub struct TransactionDryRun {
+    /// The transaction that was dry-run.
+    pub transaction: TransactionData,
+    /// The response of the full node to the dry run.
+    pub response: DryRunTransactionBlockResponse,
+    /// The type of the WAL coin of the Walrus deployment the transaction was built for.
+    pub wal_type: TypeTag,
+}
+
+/// Collects the results of dry-running transactions instead of executing them.
+///
+/// When a sink is set on a [`SuiContractClient`], each transaction the client would execute is
+/// dry-run against the full node, the result is pushed into the sink, and the transaction fails
+/// with [`SuiClientError::TransactionDryRun`]. Operations consisting of several independent
+/// transactions dry-run all of them before returning this error; operations whose transactions
+/// depend on each other stop at the first one.
+#[derive(Debug, Clone, Default)]
+pub struct DryRunSink(Arc<std::sync::Mutex<Vec<TransactionDryRun>>>);
+
+impl DryRunSink {
+    /// Creates a new, empty sink.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a dry-run result to the sink.
+    fn push(&self, dry_run: TransactionDryRun) {
+        self.0
+            .lock()
+            .expect("the mutex should not be poisoned")
+            .push(dry_run);
+    }
+
+    /// Removes and returns all dry-run results collected so far.
+    pub fn take(&self) -> Vec<TransactionDryRun> {
+        std::mem::take(&mut *self.0.lock().expect("the mutex should not be poisoned"))
+    }
+}
+
+/// The actual cost of an executed transaction, as reported in its effects and balance changes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TransactionCost {
+    /// The net gas used by the transaction in MIST, i.e., excluding the storage rebate.
+    pub gas_cost: u64,
+    /// The amount of WAL (in FROST) paid by the sender.
+    pub wal_cost: u64,
+}
+
+/// Defers the [`SuiClientError::TransactionDryRun`] error of a sequence of independent
+/// transactions, such that all of them are dry-run before the error is returned.
+#[derive(Debug, Default)]
+pub struct DeferredDryRun(Option<TransactionDigest>);
+
+impl DeferredDryRun {
+    /// Returns the result of a transaction of the sequence, or `Ok(())` if it was dry-run.
+    pub fn defer<T>(&mut self, result: SuiClientResult<T>) -> SuiClientResult<()> {
+        match result {
+            Ok(_) => Ok(()),
+            Err(SuiClientError::TransactionDryRun(digest)) => {
+                self.0.get_or_insert(digest);
+                Ok(())
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Returns the deferred [`SuiClientError::TransactionDryRun`] error, if any transaction of
+    /// the sequence was dry-run.
+    pub fn finish(self) -> SuiClientResult<()> {
+        match self.0 {
+            Some(digest) => Err(SuiClientError::TransactionDryRun(digest)),
+            None => Ok(()),
+        }
+    }
+}
+
 /// Parameters for certifying and extending a blob.
 ///
 /// When certificate is present, the blob will be certified on Sui.
@@ -372,6 +505,145 @@ impl UpgradeType {
 /// Result alias for functions returning a `SuiClientError`.
 pub type SuiClientResult<T> = Result<T, SuiClientError>;
 
+/// Trait to execute the blob-lifecycle and committee transactions of the Walrus contracts.
+///
+/// This covers the operations required by clients to store, extend, and delete blobs, and by
+/// storage nodes to take part in epoch changes. It is implemented by [`SuiContractClient`] and,
+/// with the `test-utils` feature, by the in-memory
+/// [`FakeSuiClient`][crate::test_utils::fake_sui::FakeSuiClient].
+pub trait ContractClient: ReadClient {
+    /// Returns the address of the account used to send transactions.
+    fn address(&self) -> SuiAddress;
+
+    /// Purchases blob storage for the next `epochs_ahead` Walrus epochs and an encoded
+    /// size of `encoded_size` and returns the created storage resource.
+    fn reserve_space(
+        &self,
+        encoded_size: u64,
+        epochs_ahead: EpochCount,
+    ) -> impl Future<Output = SuiClientResult<StorageResource>> + Send;
+
+    /// Registers blobs with the specified [`BlobObjectMetadata`] and [`StorageResource`]s,
+    /// and returns the created blob objects.
+    fn register_blobs(
+        &self,
+        blob_metadata_and_storage: Vec<(BlobObjectMetadata, StorageResource)>,
+        persistence: BlobPersistence,
+    ) -> impl Future<Output = SuiClientResult<Vec<Blob>>> + Send;
+
+    /// Purchases blob storage for the next `epochs_ahead` Walrus epochs and uses the resulting
+    /// storage resource to register the blobs with the provided `blob_metadata_list`.
+    fn reserve_and_register_blobs(
+        &self,
+        epochs_ahead: EpochCount,
+        blob_metadata_list: Vec<BlobObjectMetadata>,
+        persistence: BlobPersistence,
+    ) -> impl Future<Output = SuiClientResult<Vec<Blob>>> + Send;
+
+    /// Certifies the specified blobs, given certificates that confirm their storage.
+    ///
+    /// If the post store action is `share`, returns a mapping blob ID -> shared_blob_object_id.
+    fn certify_blobs(
+        &self,
+        blobs_with_certificates: &[(&Blob, ConfirmationCertificate)],
+        post_store: PostStoreAction,
+    ) -> impl Future<Output = SuiClientResult<HashMap<BlobId, ObjectID>>> + Send;
+
+    /// Certifies and extends the specified blobs in a single transaction.
+    ///
+    /// If the post store action is `share`, returns a mapping blob ID -> shared_blob_object_id.
+    fn certify_and_extend_blobs(
+        &self,
+        blobs_with_certificates: &[CertifyAndExtendBlobParams<'_>],
+        post_store: PostStoreAction,
+    ) -> impl Future<Output = SuiClientResult<HashMap<BlobId, ObjectID>>> + Send;
+
+    /// Invalidates the specified blob ID, given a certificate that confirms that it is invalid.
+    fn invalidate_blob_id(
+        &self,
+        certificate: &InvalidBlobCertificate,
+    ) -> impl Future<Output = SuiClientResult<()>> + Send;
+
+    /// Deletes the specified blob object.
+    fn delete_blob(
+        &self,
+        blob_object_id: ObjectID,
+    ) -> impl Future<Output = SuiClientResult<()>> + Send;
+
+    /// Extends the owned blob object by `epochs_extended` epochs.
+    fn extend_blob(
+        &self,
+        blob_obj_id: ObjectID,
+        epochs_extended: EpochCount,
+    ) -> impl Future<Output = SuiClientResult<()>> + Send;
+
+    /// Returns the [`Blob`] objects owned by `owner`, or by [`Self::address`] if it is `None`.
+    fn owned_blobs(
+        &self,
+        owner: Option<SuiAddress>,
+        selection_policy: ExpirySelectionPolicy,
+    ) -> impl Future<Output = SuiClientResult<Vec<Blob>>> + Send;
+
+    /// Returns the [`StorageResource`] objects owned by [`Self::address`].
+    fn owned_storage(
+        &self,
+        selection_policy: ExpirySelectionPolicy,
+    ) -> impl Future<Output = SuiClientResult<Vec<StorageResource>>> + Send;
+
+    /// Returns the closest-matching owned storage resources for given size and number of epochs.
+    ///
+    /// Among all the owned [`StorageResource`] objects, returns the one that:
+    /// - has the closest size to `storage_size`; and
+    /// - breaks ties by taking the one with the smallest end epoch that is greater or equal to the
+    ///   requested `end_epoch`.
+    /// - If object id is in the excluded list, do not select.
+    ///
+    /// Returns `None` if no matching storage resource is found.
+    fn owned_storage_for_size_and_epoch(
+        &self,
+        storage_size: u64,
+        end_epoch: Epoch,
+        excluded: &[ObjectID],
+    ) -> impl Future<Output = SuiClientResult<Option<StorageResource>>> + Send {
+        async move {
+            Ok(self
+                .owned_storage(ExpirySelectionPolicy::Valid)
+                .await?
+                .into_iter()
+                .filter(|storage| {
+                    storage.storage_size >= storage_size && storage.end_epoch >= end_epoch
+                })
+                .filter(|storage| !excluded.contains(&storage.id))
+                // Pick the smallest storage size. Break ties by comparing the end epoch, and take
+                // the one that is the closest to `end_epoch`. NOTE: we are already sure that these
+                // values are above the minimum.
+                .min_by_key(|a| (a.storage_size, a.end_epoch)))
+        }
+    }
+
+    /// Returns the [`StorageResource`] with the given ID if it is owned by [`Self::address`] and
+    /// has not expired.
+    fn owned_storage_by_id(
+        &self,
+        storage_id: ObjectID,
+    ) -> impl Future<Output = SuiClientResult<Option<StorageResource>>> + Send {
+        async move {
+            Ok(self
+                .owned_storage(ExpirySelectionPolicy::Valid)
+                .await?
+                .into_iter()
+                .find(|storage| storage.id == storage_id))
+        }
+    }
+
+    /// Notifies the contract that the node with the given capability is done syncing `epoch`.
+    fn epoch_sync_done(
+        &self,
+        epoch: Epoch,
+        node_capability_object_id: ObjectID,
+    ) -> impl Future<Output = SuiClientResult<()>> + Send;
+}
+
 /// Client implementation for interacting with the Walrus smart contracts.
 pub struct SuiContractClient {
     /// SuiContractClientInner executes Sui transactions in a mutually exclusive manner. It makes
@@ -464,6 +736,81 @@ impl SuiContractClient {
         .await
     }
 
+    /// Splits the storage resource at `split_epoch` and returns the newly created storage resource.
+    ///
+    /// The original storage resource is shortened to end at `split_epoch`, and the new one covers
+    /// the period from `split_epoch` to the original end epoch.
+    pub async fn split_storage_by_epoch(
+        &self,
+        storage_id: ObjectID,
+        split_epoch: Epoch,
+    ) -> SuiClientResult<StorageResource> {
+        self.retry_on_wrong_version(|| async {
+            self.inner
+                .lock()
+                .await
+                .split_storage_by_epoch(storage_id, split_epoch)
+                .await
+        })
+        .await
+    }
+
+    /// Splits off `split_size` from the storage resource and returns the newly created storage
+    /// resource.
+    ///
+    /// The original storage resource keeps `split_size` bytes, and the new one covers the
+    /// remaining size for the same period.
+    pub async fn split_storage_by_size(
+        &self,
+        storage_id: ObjectID,
+        split_size: u64,
+    ) -> SuiClientResult<StorageResource> {
+        self.retry_on_wrong_version(|| async {
+            self.inner
+                .lock()
+                .await
+                .split_storage_by_size(storage_id, split_size)
+                .await
+        })
+        .await
+    }
+
+    /// Fuses the `others` storage resources into the storage resource `storage_id` and returns the
+    /// fused storage resource.
+    ///
+    /// Each of the storage resources must either cover the same period as the (partially fused)
+    /// storage resource, or an adjacent period with the same storage size.
+    pub async fn fuse_storage(
+        &self,
+        storage_id: ObjectID,
+        others: &[ObjectID],
+    ) -> SuiClientResult<StorageResource> {
+        self.retry_on_wrong_version(|| async {
+            self.inner
+                .lock()
+                .await
+                .fuse_storage(storage_id, others)
+                .await
+        })
+        .await
+    }
+
+    /// Transfers the storage resources to the `recipient`.
+    pub async fn transfer_storage(
+        &self,
+        storage_ids: &[ObjectID],
+        recipient: SuiAddress,
+    ) -> SuiClientResult<()> {
+        self.retry_on_wrong_version(|| async {
+            self.inner
+                .lock()
+                .await
+                .transfer_storage(storage_ids, recipient)
+                .await
+        })
+        .await
+    }
+
     /// Registers blobs with the specified [`BlobObjectMetadata`] and [`StorageResource`]s,
     /// and returns the created blob objects.
     pub async fn register_blobs(
@@ -818,35 +1165,6 @@ impl SuiContractClient {
             .collect())
     }
 
-    /// Returns the closest-matching owned storage resources for given size and number of epochs.
-    ///
-    /// Among all the owned [`StorageResource`] objects, returns the one that:
-    /// - has the closest size to `storage_size`; and
-    /// - breaks ties by taking the one with the smallest end epoch that is greater or equal to the
-    ///   requested `end_epoch`.
-    /// - If object id is in the excluded list, do not select.
-    ///
-    /// Returns `None` if no matching storage resource is found.
-    pub async fn owned_storage_for_size_and_epoch(
-        &self,
-        storage_size: u64,
-        end_epoch: Epoch,
-        excluded: &[ObjectID],
-    ) -> SuiClientResult<Option<StorageResource>> {
-        Ok(self
-            .owned_storage(ExpirySelectionPolicy::Valid)
-            .await?
-            .into_iter()
-            .filter(|storage| {
-                storage.storage_size >= storage_size && storage.end_epoch >= end_epoch
-            })
-            .filter(|storage| !excluded.contains(&storage.id))
-            // Pick the smallest storage size. Break ties by comparing the end epoch, and take the
-            // one that is the closest to `end_epoch`. NOTE: we are already sure that these values
-            // are above the minimum.
-            .min_by_key(|a| (a.storage_size, a.end_epoch)))
-    }
-
     /// Deletes the specified blob from the wallet's storage.
     pub async fn delete_blob(&self, blob_object_id: ObjectID) -> SuiClientResult<()> {
         self.retry_on_wrong_version(|| async {
@@ -897,11 +1215,13 @@ impl SuiContractClient {
     }
 
     /// Extends the shared blob's lifetime by `epochs_extended` epochs.
+    ///
+    /// Returns the actual cost of the transaction.
     pub async fn extend_shared_blob(
         &self,
         shared_blob_obj_id: ObjectID,
         epochs_extended: EpochCount,
-    ) -> SuiClientResult<()> {
+    ) -> SuiClientResult<TransactionCost> {
         self.retry_on_wrong_version(|| async {
             self.inner
                 .lock()
@@ -929,11 +1249,13 @@ impl SuiContractClient {
     }
 
     /// Extends the owned blob object by `epochs_extended` epochs.
+    ///
+    /// Returns the actual cost of the transaction.
     pub async fn extend_blob(
         &self,
         blob_obj_id: ObjectID,
         epochs_extended: EpochCount,
-    ) -> SuiClientResult<()> {
+    ) -> SuiClientResult<TransactionCost> {
         self.retry_on_wrong_version(|| async {
             self.inner
                 .lock()
@@ -944,6 +1266,32 @@ impl SuiContractClient {
         .await
     }
 
+    /// Extends the owned blob objects by `epochs_extended` epochs.
+    ///
+    /// May use multiple PTBs in sequence to extend all the given blobs. Each PTB is retried
+    /// separately, such that the blobs extended by earlier PTBs are not extended again.
+    pub async fn extend_blobs(
+        &self,
+        blobs: &[Blob],
+        epochs_extended: EpochCount,
+    ) -> SuiClientResult<()> {
+        tracing::debug!(n_blobs = blobs.len(), epochs_extended, "extending blobs");
+        let mut dry_run = DeferredDryRun::default();
+        for blob_chunk in blobs.chunks(MAX_EXTENSIONS_PER_PTB) {
+            dry_run.defer(
+                self.retry_on_wrong_version(|| async {
+                    self.inner
+                        .lock()
+                        .await
+                        .extend_blobs(blob_chunk, epochs_extended)
+                        .await
+                })
+                .await,
+            )?;
+        }
+        dry_run.finish()
+    }
+
     /// Updates the parameters for a storage node.
     pub async fn update_node_params(
         &self,
@@ -1095,6 +1443,44 @@ impl SuiContractClient {
         }
     }
 
+    /// Sets the sink that receives transactions for external signing.
+    ///
+    /// While a sink is set, the client does not sign or execute any transactions; see
+    /// [`UnsignedTransactionSink`].
+    pub fn set_unsigned_transaction_sink(&mut self, sink: Option<UnsignedTransactionSink>) {
+        self.inner.get_mut().unsigned_transaction_sink = sink;
+    }
+
+    /// Sets the sink that receives the results of dry-running transactions.
+    ///
+    /// While a sink is set, the client does not sign or execute any transactions; see
+    /// [`DryRunSink`].
+    pub fn set_dry_run_sink(&mut self, sink: Option<DryRunSink>) {
+        self.inner.get_mut().dry_run_sink = sink;
+    }
+
+    /// Sets the sponsor paying for the gas of the client's transactions.
+    ///
+    /// With a sponsor, transactions that spend SUI from the gas coin (e.g., sending SUI or
+    /// exchanging SUI for WAL) are rejected, as they would spend the sponsor's SUI.
+    pub fn set_gas_sponsor(&mut self, gas_sponsor: Option<GasSponsor>) {
+        self.inner.get_mut().gas_sponsor = gas_sponsor;
+    }
+
+    /// Executes a transaction that was signed externally.
+    ///
+    /// The `signatures` must contain the signatures of the sender and, for sponsored transactions,
+    /// of the gas owner.
+    pub async fn execute_signed_transaction(
+        &self,
+        transaction_data: TransactionData,
+        signatures: Vec<GenericSignature>,
+    ) -> SuiClientResult<SuiTransactionBlockResponse> {
+        let transaction = Transaction::from_generic_sig_data(transaction_data, signatures);
+        let response = self.sui_client().execute_transaction(transaction).await?;
+        check_transaction_response_status(response)
+    }
+
     /// Returns a mutable reference to the wallet.
     ///
     /// This is mainly useful for deployment code where a wallet is used to provide
@@ -1182,6 +1568,13 @@ struct SuiContractClientInner {
     /// The gas budget used by the client. If not set, the client will use a dry run to estimate
     /// the required gas budget.
     gas_budget: Option<u64>,
+    /// If set, transactions are pushed into this sink instead of being signed and executed.
+    unsigned_transaction_sink: Option<UnsignedTransactionSink>,
+    /// If set, transactions are dry-run and their results pushed into this sink instead of being
+    /// signed and executed.
+    dry_run_sink: Option<DryRunSink>,
+    /// If set, the gas of all transactions is paid by this sponsor.
+    gas_sponsor: Option<GasSponsor>,
 }
 
 impl SuiContractClientInner {
@@ -1195,6 +1588,9 @@ impl SuiContractClientInner {
             wallet,
             read_client,
             gas_budget,
+            unsigned_transaction_sink: None,
+            dry_run_sink: None,
+            gas_sponsor: None,
         })
     }
 
@@ -1362,6 +1758,97 @@ impl SuiContractClientInner {
         self.sui_client().get_sui_object(storage_id[0]).await
     }
 
+    /// Splits the storage resource at `split_epoch` and returns the newly created storage resource.
+    async fn split_storage_by_epoch(
+        &mut self,
+        storage_id: ObjectID,
+        split_epoch: Epoch,
+    ) -> SuiClientResult<StorageResource> {
+        let mut pt_builder = self.transaction_builder()?;
+        pt_builder
+            .split_storage_by_epoch(storage_id.into(), split_epoch)
+            .await?;
+        let (ptb, _sui_cost) = pt_builder.finish().await?;
+        let res = self.sign_and_send_ptb(ptb).await?;
+        self.get_created_storage_resource(&res).await
+    }
+
+    /// Splits off `split_size` from the storage resource and returns the newly created storage
+    /// resource.
+    async fn split_storage_by_size(
+        &mut self,
+        storage_id: ObjectID,
+        split_size: u64,
+    ) -> SuiClientResult<StorageResource> {
+        let mut pt_builder = self.transaction_builder()?;
+        pt_builder
+            .split_storage_by_size(storage_id.into(), split_size)
+            .await?;
+        let (ptb, _sui_cost) = pt_builder.finish().await?;
+        let res = self.sign_and_send_ptb(ptb).await?;
+        self.get_created_storage_resource(&res).await
+    }
+
+    /// Fuses the `others` storage resources into `storage_id` and returns the fused resource.
+    async fn fuse_storage(
+        &mut self,
+        storage_id: ObjectID,
+        others: &[ObjectID],
+    ) -> SuiClientResult<StorageResource> {
+        ensure!(
+            !others.is_empty(),
+            "at least one storage resource to fuse must be provided"
+        );
+        let mut pt_builder = self.transaction_builder()?;
+        // The PTB builder deduplicates the object inputs, so all calls fuse into the same object.
+        for other in others {
+            pt_builder
+                .fuse_storage(storage_id.into(), (*other).into())
+                .await?;
+        }
+        let (ptb, _sui_cost) = pt_builder.finish().await?;
+        self.sign_and_send_ptb(ptb).await?;
+        self.sui_client().get_sui_object(storage_id).await
+    }
+
+    /// Transfers the storage resources to the `recipient`.
+    async fn transfer_storage(
+        &mut self,
+        storage_ids: &[ObjectID],
+        recipient: SuiAddress,
+    ) -> SuiClientResult<()> {
+        let mut pt_builder = self.transaction_builder()?;
+        pt_builder
+            .transfer(
+                Some(recipient),
+                storage_ids.iter().map(ArgumentOrOwnedObject::from),
+            )
+            .await?;
+        let (ptb, _sui_cost) = pt_builder.finish().await?;
+        self.sign_and_send_ptb(ptb).await?;
+        Ok(())
+    }
+
+    /// Returns the single storage resource created by the transaction.
+    async fn get_created_storage_resource(
+        &self,
+        res: &SuiTransactionBlockResponse,
+    ) -> SuiClientResult<StorageResource> {
+        let storage_id = get_created_sui_object_ids_by_type(
+            res,
+            &contracts::storage_resource::Storage
+                .to_move_struct_tag_with_type_map(&self.read_client.type_origin_map(), &[])?,
+        )?;
+
+        ensure!(
+            storage_id.len() == 1,
+            "unexpected number of storage resources created: {}",
+            storage_id.len()
+        );
+
+        self.sui_client().get_sui_object(storage_id[0]).await
+    }
+
     /// Registers a blob with the specified [`BlobId`] using the provided [`StorageResource`],
     /// and returns the created blob object.
     ///
@@ -2089,20 +2576,83 @@ impl SuiContractClientInner {
                 .await?
         };
 
-        let min_gas_coin_balance =
-            minimum_gas_coin_balance.max(gas_budget + additional_gas_coin_balance);
+        let Some(gas_sponsor) = self.gas_sponsor.clone() else {
+            let min_gas_coin_balance =
+                minimum_gas_coin_balance.max(gas_budget + additional_gas_coin_balance);
+
+            // Construct the transaction with gas coins that meet the minimum balance requirement
+            let transaction = TransactionData::new_programmable(
+                wallet_address,
+                self.get_compatible_gas_coins(min_gas_coin_balance).await?,
+                programmable_transaction,
+                gas_budget,
+                gas_price,
+            );
+            return self.sign_and_execute_transaction(transaction, None).await;
+        };
 
-        // Construct the transaction with gas coins that meet the minimum balance requirement
-        let transaction = TransactionData::new_programmable(
+        ensure!(
+            additional_gas_coin_balance == 0,
+            "the transaction spends SUI from the gas coin, which is not supported with a gas \
+            sponsor"
+        );
+        let sponsored_gas = gas_sponsor
+            .reserve_gas(&self.read_client, gas_budget)
+            .await?;
+        let transaction = TransactionData::new_programmable_allow_sponsor(
             wallet_address,
-            self.get_compatible_gas_coins(min_gas_coin_balance).await?,
+            sponsored_gas.coins.clone(),
             programmable_transaction,
             gas_budget,
             gas_price,
+            sponsored_gas.sponsor,
         );
+        let result = self
+            .sign_and_execute_transaction(transaction, Some((&gas_sponsor, &sponsored_gas)))
+            .await;
+        gas_sponsor.release_gas(sponsored_gas).await;
+        result
+    }
+
+    /// Signs the transaction with the wallet's keys (and the sponsor's, if any) and executes it.
+    async fn sign_and_execute_transaction(
+        &self,
+        transaction: TransactionData,
+        sponsor: Option<(&GasSponsor, &SponsoredGas)>,
+    ) -> SuiClientResult<SuiTransactionBlockResponse> {
+        if let Some(sink) = &self.unsigned_transaction_sink {
+            let digest = transaction.digest();
+            tracing::debug!(%digest, "emitting transaction for external signing");
+            sink.push(transaction);
+            return Err(SuiClientError::UnsignedTransactionEmitted(digest));
+        }
+
+        if let Some(sink) = &self.dry_run_sink {
+            let digest = transaction.digest();
+            tracing::debug!(%digest, "dry-running transaction instead of executing it");
+            let response = self
+                .sui_client()
+                .dry_run_transaction_block(transaction.clone())
+                .await?;
+            sink.push(TransactionDryRun {
+                transaction,
+                response,
+                wal_type: TypeTag::from_str(self.read_client.wal_coin_type())?,
+            });
+            return Err(SuiClientError::TransactionDryRun(digest));
+        }
 
         // Sign the transaction with the wallet's keys
-        let signed_transaction = self.wallet.sign_transaction(&transaction);
+        let mut signatures = self
+            .wallet
+            .sign_transaction(&transaction)
+            .data()
+            .tx_signatures()
+            .to_vec();
+        if let Some((gas_sponsor, sponsored_gas)) = sponsor {
+            signatures.push(gas_sponsor.sign(sponsored_gas, &transaction).await?);
+        }
+        let signed_transaction = Transaction::from_generic_sig_data(transaction, signatures);
 
         // Execute the transaction and wait for response
         let response = self
@@ -2110,24 +2660,7 @@ impl SuiContractClientInner {
             .execute_transaction(signed_transaction)
             .await?;
 
-        // Check transaction execution status from effects
-        match response
-            .effects
-            .as_ref()
-            .ok_or_else(|| anyhow!("No transaction effects in response"))?
-            .status()
-        {
-            SuiExecutionStatus::Success => Ok(response),
-            SuiExecutionStatus::Failure { error } => {
-                // Convert execution error into client error
-                // Try parsing congestion error first, fallback to general execution error
-                Err(
-                    SuiClientError::parse_congestion_error(error.as_str()).unwrap_or_else(|_| {
-                        SuiClientError::TransactionExecutionError(error.as_str().into())
-                    }),
-                )
-            }
-        }
+        check_transaction_response_status(response)
     }
 
     async fn get_compatible_gas_coins(
@@ -2202,16 +2735,16 @@ impl SuiContractClientInner {
     pub async fn burn_blobs(&mut self, blob_object_ids: &[ObjectID]) -> SuiClientResult<()> {
         tracing::debug!(n_blobs = blob_object_ids.len(), "burning blobs");
 
+        let mut dry_run = DeferredDryRun::default();
         for id_block in blob_object_ids.chunks(MAX_BURNS_PER_PTB) {
             let mut pt_builder = self.transaction_builder()?;
             for id in id_block {
                 pt_builder.burn_blob(id.into()).await?;
             }
             let (ptb, _) = pt_builder.finish().await?;
-            self.sign_and_send_ptb(ptb).await?;
+            dry_run.defer(self.sign_and_send_ptb(ptb).await)?;
         }
-
-        Ok(())
+        dry_run.finish()
     }
 
     /// Funds the shared blob object.
@@ -2234,14 +2767,14 @@ impl SuiContractClientInner {
         &mut self,
         shared_blob_obj_id: ObjectID,
         epochs_extended: EpochCount,
-    ) -> SuiClientResult<()> {
+    ) -> SuiClientResult<TransactionCost> {
         let mut pt_builder = self.transaction_builder()?;
         pt_builder
             .extend_shared_blob(shared_blob_obj_id, epochs_extended)
             .await?;
         let (ptb, _) = pt_builder.finish().await?;
-        self.sign_and_send_ptb(ptb).await?;
-        Ok(())
+        let response = self.sign_and_send_ptb(ptb).await?;
+        self.transaction_cost(&response)
     }
 
     /// Shares the blob object with the given object ID. If amount is specified, also fund the blob.
@@ -2286,7 +2819,7 @@ impl SuiContractClientInner {
         &mut self,
         blob_obj_id: ObjectID,
         epochs_extended: EpochCount,
-    ) -> SuiClientResult<()> {
+    ) -> SuiClientResult<TransactionCost> {
         let blob: Blob = self
             .read_client
             .sui_client()
@@ -2301,8 +2834,8 @@ impl SuiContractClientInner {
             )
             .await?;
         let (ptb, _) = pt_builder.finish().await?;
-        self.sign_and_send_ptb(ptb).await?;
-        Ok(())
+        let response = self.sign_and_send_ptb(ptb).await?;
+        self.transaction_cost(&response)
     }
 
     /// Extends the owned blob object by `epochs_extended` epochs with subsidies package id.
@@ -2311,7 +2844,7 @@ impl SuiContractClientInner {
         blob_obj_id: ObjectID,
         epochs_extended: EpochCount,
         subsidies_package_id: ObjectID,
-    ) -> SuiClientResult<()> {
+    ) -> SuiClientResult<TransactionCost> {
         let blob: Blob = self
             .read_client
             .sui_client()
@@ -2327,8 +2860,8 @@ impl SuiContractClientInner {
             )
             .await?;
         let (ptb, _) = pt_builder.finish().await?;
-        self.sign_and_send_ptb(ptb).await?;
-        Ok(())
+        let response = self.sign_and_send_ptb(ptb).await?;
+        self.transaction_cost(&response)
     }
 
     /// Extends the owned blob object by `epochs_extended` epochs.
@@ -2336,7 +2869,7 @@ impl SuiContractClientInner {
         &mut self,
         blob_obj_id: ObjectID,
         epochs_extended: EpochCount,
-    ) -> SuiClientResult<()> {
+    ) -> SuiClientResult<TransactionCost> {
         let subsidies_package_id = self.read_client.get_subsidies_package_id();
         match subsidies_package_id {
             Some(pkg_id) => {
@@ -2365,6 +2898,75 @@ impl SuiContractClientInner {
         }
     }
 
+    /// Extends the owned blob objects by `epochs_extended` epochs in a single PTB.
+    ///
+    /// At most [`MAX_EXTENSIONS_PER_PTB`] blobs can be extended at once. As for
+    /// [`Self::extend_blob`], the subsidies package is used if available, falling back to a
+    /// direct contract call on a version mismatch.
+    pub async fn extend_blobs(
+        &mut self,
+        blobs: &[Blob],
+        epochs_extended: EpochCount,
+    ) -> SuiClientResult<()> {
+        debug_assert!(
+            blobs.len() <= MAX_EXTENSIONS_PER_PTB,
+            "too many blobs to extend in a single PTB"
+        );
+        let subsidies_package_id = self.read_client.get_subsidies_package_id();
+        match self
+            .extend_blobs_in_ptb(blobs, epochs_extended, subsidies_package_id)
+            .await
+        {
+            Err(SuiClientError::TransactionExecutionError(MoveExecutionError::System(
+                SystemError::EWrongVersion(_),
+            ))) if subsidies_package_id.is_some() => {
+                tracing::warn!(
+                    "Walrus package version mismatch in subsidies call, falling back to \
+                    direct contract call"
+                );
+                self.extend_blobs_in_ptb(blobs, epochs_extended, None).await
+            }
+            result => result,
+        }
+    }
+
+    /// Extends the owned blob objects by `epochs_extended` epochs in a single PTB, using the
+    /// subsidies package if given.
+    async fn extend_blobs_in_ptb(
+        &mut self,
+        blobs: &[Blob],
+        epochs_extended: EpochCount,
+        subsidies_package_id: Option<ObjectID>,
+    ) -> SuiClientResult<()> {
+        let mut pt_builder = self.transaction_builder()?;
+        for blob in blobs {
+            match subsidies_package_id {
+                Some(subsidies_package_id) => {
+                    pt_builder
+                        .extend_blob_with_subsidies(
+                            blob.id.into(),
+                            epochs_extended,
+                            blob.storage.storage_size,
+                            subsidies_package_id,
+                        )
+                        .await?
+                }
+                None => {
+                    pt_builder
+                        .extend_blob_without_subsidies(
+                            blob.id.into(),
+                            epochs_extended,
+                            blob.storage.storage_size,
+                        )
+                        .await?
+                }
+            }
+        }
+        let (ptb, _) = pt_builder.finish().await?;
+        self.sign_and_send_ptb(ptb).await?;
+        Ok(())
+    }
+
     /// Updates the parameters for a storage node.
     pub async fn update_node_params(
         &mut self,
@@ -2388,6 +2990,39 @@ impl SuiContractClientInner {
         Ok(())
     }
 
+    /// Returns the gas used by the transaction and the WAL paid by the sender.
+    fn transaction_cost(
+        &self,
+        response: &SuiTransactionBlockResponse,
+    ) -> SuiClientResult<TransactionCost> {
+        let gas_cost = response
+            .effects
+            .as_ref()
+            .ok_or_else(|| anyhow!("transaction response does not contain effects"))?
+            .gas_cost_summary()
+            .net_gas_usage();
+        let wal_type_tag = TypeTag::from_str(self.read_client.wal_coin_type())?;
+        let sender_address = self.wallet.active_address()?;
+        let wal_balance_change: i128 = response
+            .balance_changes
+            .as_ref()
+            .ok_or_else(|| anyhow!("transaction response does not contain balance changes"))?
+            .iter()
+            .filter(|change| {
+                change.coin_type == wal_type_tag
+                    && change
+                        .owner
+                        .get_address_owner_address()
+                        .is_ok_and(|address| address == sender_address)
+            })
+            .map(|change| change.amount)
+            .sum();
+        Ok(TransactionCost {
+            gas_cost: u64::try_from(gas_cost.max(0)).expect("non-negative i64 fits into a u64"),
+            wal_cost: u64::try_from(-wal_balance_change.min(0)).unwrap_or(u64::MAX),
+        })
+    }
+
     /// Withdraws the commission for the pool with id `node_id` and returns the
     /// withdrawn amount in FROST.
     pub async fn collect_commission(&mut self, node_id: ObjectID) -> SuiClientResult<u64> {
@@ -2783,6 +3418,103 @@ impl ReadClient for SuiContractClient {
     }
 }
 
+impl ContractClient for SuiContractClient {
+    fn address(&self) -> SuiAddress {
+        self.wallet_address
+    }
+
+    async fn reserve_space(
+        &self,
+        encoded_size: u64,
+        epochs_ahead: EpochCount,
+    ) -> SuiClientResult<StorageResource> {
+        SuiContractClient::reserve_space(self, encoded_size, epochs_ahead).await
+    }
+
+    async fn register_blobs(
+        &self,
+        blob_metadata_and_storage: Vec<(BlobObjectMetadata, StorageResource)>,
+        persistence: BlobPersistence,
+    ) -> SuiClientResult<Vec<Blob>> {
+        SuiContractClient::register_blobs(self, blob_metadata_and_storage, persistence).await
+    }
+
+    async fn reserve_and_register_blobs(
+        &self,
+        epochs_ahead: EpochCount,
+        blob_metadata_list: Vec<BlobObjectMetadata>,
+        persistence: BlobPersistence,
+    ) -> SuiClientResult<Vec<Blob>> {
+        SuiContractClient::reserve_and_register_blobs(
+            self,
+            epochs_ahead,
+            blob_metadata_list,
+            persistence,
+        )
+        .await
+    }
+
+    async fn certify_blobs(
+        &self,
+        blobs_with_certificates: &[(&Blob, ConfirmationCertificate)],
+        post_store: PostStoreAction,
+    ) -> SuiClientResult<HashMap<BlobId, ObjectID>> {
+        SuiContractClient::certify_blobs(self, blobs_with_certificates, post_store).await
+    }
+
+    async fn certify_and_extend_blobs(
+        &self,
+        blobs_with_certificates: &[CertifyAndExtendBlobParams<'_>],
+        post_store: PostStoreAction,
+    ) -> SuiClientResult<HashMap<BlobId, ObjectID>> {
+        SuiContractClient::certify_and_extend_blobs(self, blobs_with_certificates, post_store).await
+    }
+
+    async fn invalidate_blob_id(
+        &self,
+        certificate: &InvalidBlobCertificate,
+    ) -> SuiClientResult<()> {
+        SuiContractClient::invalidate_blob_id(self, certificate).await
+    }
+
+    async fn delete_blob(&self, blob_object_id: ObjectID) -> SuiClientResult<()> {
+        SuiContractClient::delete_blob(self, blob_object_id).await
+    }
+
+    async fn extend_blob(
+        &self,
+        blob_obj_id: ObjectID,
+        epochs_extended: EpochCount,
+    ) -> SuiClientResult<()> {
+        SuiContractClient::extend_blob(self, blob_obj_id, epochs_extended)
+            .await
+            .map(|_| ())
+    }
+
+    async fn owned_blobs(
+        &self,
+        owner: Option<SuiAddress>,
+        selection_policy: ExpirySelectionPolicy,
+    ) -> SuiClientResult<Vec<Blob>> {
+        SuiContractClient::owned_blobs(self, owner, selection_policy).await
+    }
+
+    async fn owned_storage(
+        &self,
+        selection_policy: ExpirySelectionPolicy,
+    ) -> SuiClientResult<Vec<StorageResource>> {
+        SuiContractClient::owned_storage(self, selection_policy).await
+    }
+
+    async fn epoch_sync_done(
+        &self,
+        epoch: Epoch,
+        node_capability_object_id: ObjectID,
+    ) -> SuiClientResult<()> {
+        SuiContractClient::epoch_sync_done(self, epoch, node_capability_object_id).await
+    }
+}
+
 impl fmt::Debug for SuiContractClient {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("SuiContractClient")
@@ -2794,6 +3526,29 @@ impl fmt::Debug for SuiContractClient {
     }
 }
 
+/// Checks the execution status in the effects of the transaction response.
+fn check_transaction_response_status(
+    response: SuiTransactionBlockResponse,
+) -> SuiClientResult<SuiTransactionBlockResponse> {
+    match response
+        .effects
+        .as_ref()
+        .ok_or_else(|| anyhow!("No transaction effects in response"))?
+        .status()
+    {
+        SuiExecutionStatus::Success => Ok(response),
+        SuiExecutionStatus::Failure { error } => {
+            // Convert execution error into client error
+            // Try parsing congestion error first, fallback to general execution error
+            Err(
+                SuiClientError::parse_congestion_error(error.as_str()).unwrap_or_else(|_| {
+                    SuiClientError::TransactionExecutionError(error.as_str().into())
+                }),
+            )
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2816,4 +3571,33 @@ mod tests {
                 ).unwrap()
         ));
     }
+
+    #[test]
+    fn deferred_dry_run_continues_after_dry_runs_and_reports_the_first() {
+        let first = TransactionDigest::random();
+        let mut dry_run = DeferredDryRun::default();
+        assert!(dry_run.defer(Ok(())).is_ok());
+        assert!(dry_run
+            .defer::<()>(Err(SuiClientError::TransactionDryRun(first)))
+            .is_ok());
+        assert!(dry_run
+            .defer::<()>(Err(SuiClientError::TransactionDryRun(
+                TransactionDigest::random()
+            )))
+            .is_ok());
+        assert!(matches!(
+            dry_run.finish(),
+            Err(SuiClientError::TransactionDryRun(digest)) if digest == first
+        ));
+    }
+
+    #[test]
+    fn deferred_dry_run_propagates_other_errors() {
+        let mut dry_run = DeferredDryRun::default();
+        assert!(matches!(
+            dry_run.defer::<()>(Err(SuiClientError::NoCompatibleWalCoins)),
+            Err(SuiClientError::NoCompatibleWalCoins)
+        ));
+        assert!(dry_run.finish().is_ok());
+    }
 }
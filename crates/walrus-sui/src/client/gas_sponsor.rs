@@ -0,0 +1,462 @@
+// Copyright (c) Walrus Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Sponsoring of the gas of the client's transactions by a third party.
+
+use std::{
+    collections::HashSet,
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::{anyhow, Context as _};
+use fastcrypto::traits::ToFromBytes as _;
+use serde::{Deserialize, Serialize};
+use serde_with::{base64::Base64, serde_as};
+use shared_crypto::intent::Intent;
+use sui_keys::keystore::{AccountKeystore as _, FileBasedKeystore, Keystore};
+use sui_sdk::rpc_types::Coin;
+use sui_types::{
+    base_types::{ObjectID, ObjectRef, SuiAddress},
+    signature::GenericSignature,
+    transaction::TransactionData,
+};
+use url::Url;
+
+use super::{CoinType, SuiClientResult, SuiReadClient};
+use crate::config::GasSponsorConfig;
+
+/// The maximum number of times the gas coins of a keystore sponsor are selected for a transaction.
+///
+/// The selection is repeated if another transaction reserves one of the selected coins
+/// concurrently.
+const MAX_COIN_SELECTION_ATTEMPTS: usize = 5;
+
+/// A sponsor paying for the gas of the transactions of a
+/// [`SuiContractClient`][super::SuiContractClient].
+///
+/// For each transaction, the client reserves gas coins from the sponsor, builds the transaction
+/// with the sponsor as the gas owner, and collects the sponsor's signature in addition to the
+/// sender's signature.
+///
+/// The sponsor is either backed by a local keystore or by a signing service. A signing service
+/// has to expose the following endpoints, which accept and return JSON:
+///
+/// - `POST /v1/reserve_gas` with `{ "gasBudget": <u64> }`, returning `{ "sponsorAddress":
+///   <address>, "gasCoins": [<object ref>, ...], "reservationId": <u64> }`;
+/// - `POST /v1/sign` with `{ "reservationId": <u64>, "txBytes": <base64> }`, returning `{
+///   "signature": <base64> }`, where the transaction bytes are the BCS-encoded
+///   `TransactionData` and the signature is a serialized `GenericSignature`.
+///
+/// Cloning the sponsor is cheap. Clones share the gas coins currently reserved from a keystore
+/// sponsor, such that multiple clients can use the same sponsor concurrently without selecting
+/// the same coins.
+#[derive(Clone)]
+pub struct GasSponsor(Arc<GasSponsorInner>);
+
+enum GasSponsorInner {
+    Keystore {
+        keystore: Keystore,
+        address: SuiAddress,
+        reserved_coins: CoinReservations,
+    },
+    Service {
+        url: Url,
+        auth_token: Option<String>,
+        http_client: reqwest::Client,
+    },
+}
+
+impl fmt::Debug for GasSponsor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0.as_ref() {
+            GasSponsorInner::Keystore { address, .. } => f
+                .debug_struct("GasSponsor::Keystore")
+                .field("address", address)
+                .finish_non_exhaustive(),
+            GasSponsorInner::Service { url, .. } => f
+                .debug_struct("GasSponsor::Service")
+                .field("url", url)
+                .finish_non_exhaustive(),
+        }
+    }
+}
+
+/// The gas coins of a keystore sponsor that are currently reserved for transactions.
+#[derive(Debug, Default)]
+struct CoinReservations(Mutex<HashSet<ObjectID>>);
+
+impl CoinReservations {
+    /// Returns the IDs of the coins that are currently reserved.
+    fn reserved(&self) -> Vec<ObjectID> {
+        self.0
+            .lock()
+            .expect("mutex should not be poisoned")
+            .iter()
+            .copied()
+            .collect()
+    }
+
+    /// Reserves all the given coins, unless any of them is already reserved.
+    ///
+    /// Returns `true` if the coins were reserved.
+    fn try_reserve(&self, coins: &[ObjectRef]) -> bool {
+        let mut reserved = self.0.lock().expect("mutex should not be poisoned");
+        if coins.iter().any(|coin| reserved.contains(&coin.0)) {
+            return false;
+        }
+        reserved.extend(coins.iter().map(|coin| coin.0));
+        true
+    }
+
+    /// Releases the reservation of the given coins.
+    fn release(&self, coins: &[ObjectRef]) {
+        let mut reserved = self.0.lock().expect("mutex should not be poisoned");
+        for coin in coins {
+            reserved.remove(&coin.0);
+        }
+    }
+}
+
+/// The gas coins reserved from a [`GasSponsor`] for a single transaction.
+#[derive(Debug, Clone)]
+pub(crate) struct SponsoredGas {
+    /// The address of the sponsor, owning the gas coins.
+    pub sponsor: SuiAddress,
+    /// The gas coins to use for the transaction.
+    pub coins: Vec<ObjectRef>,
+    /// The ID of the reservation at the signing service, if any.
+    reservation_id: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReserveGasRequest {
+    gas_budget: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReserveGasResponse {
+    sponsor_address: SuiAddress,
+    gas_coins: Vec<ObjectRef>,
+    reservation_id: u64,
+}
+
+#[serde_as]
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SignRequest {
+    reservation_id: u64,
+    #[serde_as(as = "Base64")]
+    tx_bytes: Vec<u8>,
+}
+
+#[serde_as]
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SignResponse {
+    #[serde_as(as = "Base64")]
+    signature: Vec<u8>,
+}
+
+impl GasSponsor {
+    /// Creates a new sponsor from the configuration.
+    pub fn from_config(config: &GasSponsorConfig) -> anyhow::Result<Self> {
+        let inner = match config {
+            GasSponsorConfig::Keyfile { path, address } => {
+                let keystore = FileBasedKeystore::new(path).with_context(|| {
+                    format!("unable to load the sponsor keystore {}", path.display())
+                })?;
+                let address = match address {
+                    Some(address) => {
+                        anyhow::ensure!(
+                            keystore.addresses().contains(address),
+                            "the sponsor keystore does not contain a key for {address}"
+                        );
+                        *address
+                    }
+                    None => {
+                        let addresses = keystore.addresses();
+                        anyhow::ensure!(
+                            addresses.len() == 1,
+                            "the sponsor keystore contains {} keys; specify the sponsor address",
+                            addresses.len()
+                        );
+                        addresses[0]
+                    }
+                };
+                GasSponsorInner::Keystore {
+                    keystore: keystore.into(),
+                    address,
+                    reserved_coins: CoinReservations::default(),
+                }
+            }
+            GasSponsorConfig::Service { url, auth_token } => GasSponsorInner::Service {
+                url: url.clone(),
+                auth_token: auth_token.clone(),
+                http_client: reqwest::Client::new(),
+            },
+        };
+        Ok(Self(Arc::new(inner)))
+    }
+
+    /// Reserves gas coins with a total balance of at least `gas_budget` for a transaction.
+    ///
+    /// The reservation must be released with [`Self::release_gas`] once the transaction has been
+    /// executed or has failed.
+    pub(crate) async fn reserve_gas(
+        &self,
+        read_client: &SuiReadClient,
+        gas_budget: u64,
+    ) -> SuiClientResult<SponsoredGas> {
+        match self.0.as_ref() {
+            GasSponsorInner::Keystore {
+                address,
+                reserved_coins,
+                ..
+            } => {
+                // The coins are selected without holding the lock, such that concurrent
+                // transactions do not wait for each other's RPC calls. If another transaction
+                // reserves one of the selected coins in the meantime, the selection is repeated.
+                for attempt in 1..=MAX_COIN_SELECTION_ATTEMPTS {
+                    let coins = read_client
+                        .get_coins_with_total_balance(
+                            *address,
+                            CoinType::Sui,
+                            gas_budget,
+                            reserved_coins.reserved(),
+                        )
+                        .await?
+                        .iter()
+                        .map(Coin::object_ref)
+                        .collect::<Vec<_>>();
+                    if reserved_coins.try_reserve(&coins) {
+                        return Ok(SponsoredGas {
+                            sponsor: *address,
+                            coins,
+                            reservation_id: None,
+                        });
+                    }
+                    tracing::debug!(
+                        attempt,
+                        "the selected gas coins were reserved concurrently; selecting again"
+                    );
+                }
+                Err(anyhow!(
+                    "unable to reserve gas coins of the sponsor after \
+                    {MAX_COIN_SELECTION_ATTEMPTS} attempts"
+                )
+                .into())
+            }
+            GasSponsorInner::Service { .. } => {
+                let response: ReserveGasResponse = self
+                    .post_to_service("v1/reserve_gas", &ReserveGasRequest { gas_budget })
+                    .await?;
+                Ok(SponsoredGas {
+                    sponsor: response.sponsor_address,
+                    coins: response.gas_coins,
+                    reservation_id: Some(response.reservation_id),
+                })
+            }
+        }
+    }
+
+    /// Returns the sponsor's signature over the transaction using the reserved gas.
+    pub(crate) async fn sign(
+        &self,
+        gas: &SponsoredGas,
+        transaction: &TransactionData,
+    ) -> SuiClientResult<GenericSignature> {
+        match self.0.as_ref() {
+            GasSponsorInner::Keystore {
+                keystore, address, ..
+            } => Ok(keystore
+                .sign_secure(address, transaction, Intent::sui_transaction())
+                .map_err(|error| anyhow!("the sponsor could not sign the transaction: {error}"))?
+                .into()),
+            GasSponsorInner::Service { .. } => {
+                let request = SignRequest {
+                    reservation_id: gas
+                        .reservation_id
+                        .expect("gas reserved from a signing service has a reservation ID"),
+                    tx_bytes: bcs::to_bytes(transaction).context("serializing the transaction")?,
+                };
+                let response: SignResponse = self.post_to_service("v1/sign", &request).await?;
+                Ok(
+                    GenericSignature::from_bytes(&response.signature).map_err(|error| {
+                        anyhow!("the signing service returned an invalid signature: {error}")
+                    })?,
+                )
+            }
+        }
+    }
+
+    /// Releases the gas coins reserved for a transaction.
+    ///
+    /// Reservations at a signing service are released by the service itself once the transaction
+    /// is signed or the reservation expires.
+    pub(crate) async fn release_gas(&self, gas: SponsoredGas) {
+        if let GasSponsorInner::Keystore { reserved_coins, .. } = self.0.as_ref() {
+            reserved_coins.release(&gas.coins);
+        }
+    }
+
+    async fn post_to_service<Req, Resp>(&self, path: &str, request: &Req) -> anyhow::Result<Resp>
+    where
+        Req: Serialize,
+        Resp: for<'de> Deserialize<'de>,
+    {
+        let GasSponsorInner::Service {
+            url,
+            auth_token,
+            http_client,
+        } = self.0.as_ref()
+        else {
+            unreachable!("only called for signing services");
+        };
+        let mut builder = http_client.post(url.join(path)?).json(request);
+        if let Some(token) = auth_token {
+            builder = builder.bearer_auth(token);
+        }
+        builder
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .with_context(|| format!("request to the gas sponsor at {url} failed"))?
+            .json()
+            .await
+            .with_context(|| format!("invalid response from the gas sponsor at {url}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sui_keys::keystore::InMemKeystore;
+    use sui_types::{
+        base_types::SequenceNumber,
+        digests::ObjectDigest,
+        programmable_transaction_builder::ProgrammableTransactionBuilder,
+    };
+
+    use super::*;
+
+    fn object_ref() -> ObjectRef {
+        (
+            ObjectID::random(),
+            SequenceNumber::new(),
+            ObjectDigest::random(),
+        )
+    }
+
+    fn keystore_sponsor() -> GasSponsor {
+        let keystore = InMemKeystore::new_insecure_for_tests(1);
+        let address = keystore.addresses()[0];
+        GasSponsor(Arc::new(GasSponsorInner::Keystore {
+            keystore: Keystore::InMem(keystore),
+            address,
+            reserved_coins: CoinReservations::default(),
+        }))
+    }
+
+    #[test]
+    fn coins_are_reserved_at_most_once() {
+        let reservations = CoinReservations::default();
+        let (coin_a, coin_b, coin_c) = (object_ref(), object_ref(), object_ref());
+
+        assert!(reservations.try_reserve(&[coin_a, coin_b]));
+        // A selection containing a reserved coin is rejected as a whole.
+        assert!(!reservations.try_reserve(&[coin_b, coin_c]));
+        assert!(reservations.try_reserve(&[coin_c]));
+
+        let mut reserved = reservations.reserved();
+        reserved.sort();
+        let mut expected = vec![coin_a.0, coin_b.0, coin_c.0];
+        expected.sort();
+        assert_eq!(reserved, expected);
+
+        reservations.release(&[coin_a, coin_b]);
+        assert_eq!(reservations.reserved(), vec![coin_c.0]);
+        assert!(reservations.try_reserve(&[coin_b]));
+    }
+
+    #[tokio::test]
+    async fn released_gas_can_be_reserved_again() {
+        let sponsor = keystore_sponsor();
+        let GasSponsorInner::Keystore { reserved_coins, .. } = sponsor.0.as_ref() else {
+            unreachable!("the sponsor is backed by a keystore");
+        };
+        let coins = vec![object_ref()];
+        assert!(reserved_coins.try_reserve(&coins));
+
+        // Clones share the reservations.
+        let cloned_sponsor = sponsor.clone();
+        let GasSponsorInner::Keystore {
+            reserved_coins: cloned_reservations,
+            ..
+        } = cloned_sponsor.0.as_ref()
+        else {
+            unreachable!("the sponsor is backed by a keystore");
+        };
+        assert!(!cloned_reservations.try_reserve(&coins));
+
+        sponsor
+            .release_gas(SponsoredGas {
+                sponsor: SuiAddress::ZERO,
+                coins: coins.clone(),
+                reservation_id: None,
+            })
+            .await;
+        assert!(reserved_coins.try_reserve(&coins));
+    }
+
+    #[tokio::test]
+    async fn keystore_sponsor_signs_as_gas_owner() -> anyhow::Result<()> {
+        let sponsor = keystore_sponsor();
+        let keystore = InMemKeystore::new_insecure_for_tests(1);
+        let sponsor_address = keystore.addresses()[0];
+        let gas = SponsoredGas {
+            sponsor: sponsor_address,
+            coins: vec![object_ref()],
+            reservation_id: None,
+        };
+        let transaction = TransactionData::new_programmable_allow_sponsor(
+            SuiAddress::random_for_testing_only(),
+            gas.coins.clone(),
+            ProgrammableTransactionBuilder::new().finish(),
+            1_000_000,
+            1_000,
+            gas.sponsor,
+        );
+
+        let signature = sponsor.sign(&gas, &transaction).await?;
+        let expected: GenericSignature = keystore
+            .sign_secure(&sponsor_address, &transaction, Intent::sui_transaction())?
+            .into();
+        assert_eq!(signature.as_ref(), expected.as_ref());
+        Ok(())
+    }
+
+    #[test]
+    fn signing_service_messages_use_the_documented_format() -> anyhow::Result<()> {
+        let request = serde_json::to_value(SignRequest {
+            reservation_id: 7,
+            tx_bytes: vec![1, 2, 3],
+        })?;
+        assert_eq!(
+            request,
+            serde_json::json!({ "reservationId": 7, "txBytes": "AQID" })
+        );
+
+        let coin = object_ref();
+        let response: ReserveGasResponse = serde_json::from_value(serde_json::json!({
+            "sponsorAddress": SuiAddress::ZERO,
+            "gasCoins": [coin],
+            "reservationId": 7,
+        }))?;
+        assert_eq!(response.sponsor_address, SuiAddress::ZERO);
+        assert_eq!(response.gas_coins, vec![coin]);
+        assert_eq!(response.reservation_id, 7);
+        Ok(())
+    }
+}
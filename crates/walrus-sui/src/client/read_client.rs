@@ -4,7 +4,7 @@
 //! Client to call Walrus move functions from rust.
 
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fmt::{self, Debug},
     future::Future,
     num::NonZeroU16,
@@ -18,6 +18,7 @@ use chrono::{DateTime, Utc};
 use sui_sdk::{
     apis::EventApi,
     rpc_types::{
+        CheckpointId,
         Coin,
         EventFilter,
         SuiEvent,
@@ -25,11 +26,14 @@ use sui_sdk::{
         SuiObjectDataFilter,
         SuiObjectDataOptions,
         SuiObjectResponseQuery,
+        SuiTransactionBlockResponseOptions,
+        SuiTransactionBlockResponseQuery,
+        TransactionFilter,
     },
     types::base_types::ObjectID,
 };
 use sui_types::{
-    base_types::{ObjectRef, SequenceNumber, SuiAddress},
+    base_types::{ObjectRef, SequenceNumber, SuiAddress, TransactionDigest},
     event::EventID,
     object::Owner,
     transaction::ObjectArg,
@@ -39,12 +43,13 @@ use sui_types::{
 use tokio::sync::{mpsc, OnceCell};
 use tokio_stream::{wrappers::ReceiverStream, Stream};
 use tracing::Instrument as _;
-use walrus_core::{ensure, Epoch};
+use walrus_core::{ensure, BlobId, Epoch};
 use walrus_utils::backoff::ExponentialBackoffConfig;
 
 use super::{
     contract_config::ContractConfig,
     retry_client::{RetriableSuiClient, MULTI_GET_OBJ_LIMIT},
+    rpc_config::RpcFailoverConfig,
     SuiClientError,
     SuiClientResult,
 };
@@ -349,11 +354,210 @@ impl SuiReadClient {
         Self::new(client, contract_config).await
     }
 
+    /// Constructs a new `SuiReadClient` around a [`RetriableSuiClient`] that fails over between
+    /// the provided fullnodes' RPC addresses.
+    ///
+    /// See [`RetriableSuiClient::new_for_rpcs`] for details.
+    pub async fn new_for_rpcs<S: AsRef<str>>(
+        rpc_addresses: &[S],
+        contract_config: &ContractConfig,
+        backoff_config: ExponentialBackoffConfig,
+        failover_config: RpcFailoverConfig,
+    ) -> SuiClientResult<Self> {
+        let client =
+            RetriableSuiClient::new_for_rpcs(rpc_addresses, backoff_config, failover_config)
+                .await?;
+        Self::new(client, contract_config).await
+    }
+
     /// Gets the [`RetriableSuiClient`] from the associated read client.
     pub fn sui_client(&self) -> &RetriableSuiClient {
         &self.sui_client
     }
 
+    /// Returns the blob events concerning the blob object with the given ID, in chronological
+    /// order.
+    ///
+    /// The events are collected from all the transactions that created, modified, or deleted the
+    /// blob object. In addition, the events marking the blob ID of the object as invalid are
+    /// included.
+    pub async fn get_blob_events_for_object(
+        &self,
+        object_id: ObjectID,
+    ) -> SuiClientResult<Vec<BlobEvent>> {
+        let mut seen_events = HashSet::new();
+        // The events, with the checkpoint and digest of the transaction that emitted them.
+        let mut events = vec![];
+        // The object is not an input of the transaction creating it, and not changed by the
+        // transaction deleting it, so both filters are needed.
+        for filter in [
+            TransactionFilter::ChangedObject(object_id),
+            TransactionFilter::InputObject(object_id),
+        ] {
+            let query = SuiTransactionBlockResponseQuery::new(
+                Some(filter),
+                Some(SuiTransactionBlockResponseOptions::new().with_events()),
+            );
+            let mut cursor = None;
+            loop {
+                let page = self
+                    .sui_client
+                    .query_transaction_blocks(query.clone(), cursor, None, false)
+                    .await?;
+                for transaction in page.data {
+                    for event in transaction
+                        .events
+                        .map(|events| events.data)
+                        .unwrap_or_default()
+                    {
+                        let Ok(event) = BlobEvent::try_from(event) else {
+                            continue;
+                        };
+                        if event.object_id() == Some(object_id)
+                            && seen_events.insert(event.event_id())
+                        {
+                            events.push((transaction.checkpoint, transaction.digest, event));
+                        }
+                    }
+                }
+                if !page.has_next_page {
+                    break;
+                }
+                cursor = page.next_cursor;
+            }
+        }
+
+        // Invalid blob ID events concern the blob ID instead of the object, and are therefore
+        // emitted by transactions that do not touch the object.
+        if let Some(blob_id) = events.first().map(|(_, _, event)| event.blob_id()) {
+            for event in self.get_invalid_blob_id_events(blob_id).await? {
+                if !seen_events.insert(event.event_id()) {
+                    continue;
+                }
+                let digest = event.event_id().tx_digest;
+                let checkpoint = self
+                    .sui_client
+                    .get_transaction_with_options(digest, SuiTransactionBlockResponseOptions::new())
+                    .await?
+                    .checkpoint;
+                events.push((checkpoint, digest, event));
+            }
+        }
+
+        // Multiple transactions in the same checkpoint are ordered by their index in the
+        // checkpoint, and the events of a transaction by their sequence number.
+        let transaction_indices = self
+            .transaction_indices_in_checkpoints(
+                events
+                    .iter()
+                    .filter_map(|(checkpoint, _, _)| *checkpoint)
+                    .collect(),
+            )
+            .await?;
+        events.sort_by_key(|(checkpoint, digest, event)| {
+            (
+                checkpoint.unwrap_or(u64::MAX),
+                transaction_indices
+                    .get(digest)
+                    .copied()
+                    .unwrap_or(usize::MAX),
+                event.event_id().event_seq,
+            )
+        });
+        Ok(events.into_iter().map(|(_, _, event)| event).collect())
+    }
+
+    /// Returns all the events marking the given blob ID as invalid.
+    async fn get_invalid_blob_id_events(&self, blob_id: BlobId) -> SuiClientResult<Vec<BlobEvent>> {
+        let event_filter = EventFilter::MoveEventType(
+            contracts::events::InvalidBlobID
+                .to_move_struct_tag_with_type_map(&self.type_origin_map(), &[])?,
+        );
+        let mut events = vec![];
+        let mut cursor = None;
+        loop {
+            let page = self
+                .sui_client
+                .query_events(event_filter.clone(), cursor, None, false)
+                .await?;
+            events.extend(
+                page.data
+                    .into_iter()
+                    .filter_map(|event| BlobEvent::try_from(event).ok())
+                    .filter(|event| event.blob_id() == blob_id),
+            );
+            if !page.has_next_page {
+                break;
+            }
+            cursor = page.next_cursor;
+        }
+        Ok(events)
+    }
+
+    /// Returns the index of each transaction in the given checkpoints.
+    async fn transaction_indices_in_checkpoints(
+        &self,
+        checkpoints: HashSet<u64>,
+    ) -> SuiClientResult<HashMap<TransactionDigest, usize>> {
+        let mut transaction_indices = HashMap::new();
+        for checkpoint in checkpoints {
+            let checkpoint = self
+                .sui_client
+                .get_checkpoint(CheckpointId::SequenceNumber(checkpoint))
+                .await?;
+            transaction_indices.extend(
+                checkpoint
+                    .transactions
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, digest)| (digest, index)),
+            );
+        }
+        Ok(transaction_indices)
+    }
+
+    /// Returns the blob events for the given blob ID, in chronological order.
+    ///
+    /// As the events cannot be filtered by blob ID on chain, this scans the most recent Walrus
+    /// events, stopping once at least `max_scanned_events` have been scanned. Older events for the
+    /// blob are therefore not returned.
+    pub async fn get_blob_events_for_blob_id(
+        &self,
+        blob_id: BlobId,
+        max_scanned_events: usize,
+    ) -> SuiClientResult<Vec<BlobEvent>> {
+        let event_filter = EventFilter::MoveEventModule {
+            package: *self
+                .walrus_package_id
+                .read()
+                .expect("lock should not be poisoned"),
+            module: Identifier::new(EVENT_MODULE)?,
+        };
+        let mut events = vec![];
+        let mut cursor = None;
+        let mut n_scanned_events = 0;
+        while n_scanned_events < max_scanned_events {
+            let page = self
+                .sui_client
+                .query_events(event_filter.clone(), cursor, None, true)
+                .await?;
+            n_scanned_events += page.data.len();
+            events.extend(
+                page.data
+                    .into_iter()
+                    .filter_map(|event| BlobEvent::try_from(event).ok())
+                    .filter(|event| event.blob_id() == blob_id),
+            );
+            if !page.has_next_page {
+                break;
+            }
+            cursor = page.next_cursor;
+        }
+        // The events were queried in descending order.
+        events.reverse();
+        Ok(events)
+    }
+
     pub(crate) async fn object_arg_for_shared_obj(
         &self,
         object_id: ObjectID,
@@ -10,6 +10,7 @@ use std::{
     fmt::Debug,
     future::Future,
     str::FromStr,
+    sync::Arc,
     time::{self, Duration},
 };
 
@@ -33,8 +34,12 @@ use sui_sdk::{
     error::SuiRpcResult,
     rpc_types::{
         Balance,
+        Checkpoint,
+        CheckpointId,
         Coin,
         DryRunTransactionBlockResponse,
+        EventFilter,
+        EventPage,
         ObjectsPage,
         SuiCommittee,
         SuiMoveNormalizedModule,
@@ -46,6 +51,8 @@ use sui_sdk::{
         SuiTransactionBlockEffectsAPI,
         SuiTransactionBlockResponse,
         SuiTransactionBlockResponseOptions,
+        SuiTransactionBlockResponseQuery,
+        TransactionBlocksPage,
     },
     wallet_context::WalletContext,
     SuiClient,
@@ -57,6 +64,7 @@ use sui_types::transaction::TransactionDataAPI;
 use sui_types::{
     base_types::{ObjectID, SuiAddress, TransactionDigest},
     dynamic_field::derive_dynamic_field_id,
+    event::EventID,
     full_checkpoint_content::CheckpointData,
     messages_checkpoint::CertifiedCheckpointSummary,
     object::Object,
@@ -71,13 +79,24 @@ use url::ParseError;
 use walrus_core::ensure;
 use walrus_utils::backoff::{BackoffStrategy, ExponentialBackoff, ExponentialBackoffConfig};
 
-use super::{rpc_config::RpcFallbackConfig, SuiClientError, SuiClientResult};
+pub use self::failover::SuiRpcEndpointMetrics;
+use self::failover::{SuiRpcEndpoint, SuiRpcEndpoints};
+use super::{
+    rpc_config::{RpcFailoverConfig, RpcFallbackConfig},
+    SuiClientError,
+    SuiClientResult,
+};
 use crate::{
     contracts::{self, AssociatedContractStruct, TypeOriginMap},
     types::move_structs::{Key, Subsidies, SuiDynamicField, SystemObjectForDeserialization},
     utils::get_sui_object_from_object_response,
 };
 
+mod failover;
+
+/// The URL label of endpoints whose URL is not known.
+const UNKNOWN_RPC_URL: &str = "unknown";
+
 /// The list of HTTP status codes that are retriable.
 const RETRIABLE_RPC_ERRORS: &[&str] = &["429", "500", "502"];
 /// The list of gRPC status codes that are retriable.
@@ -96,7 +115,7 @@ const GAS_SAFE_OVERHEAD: u64 = 1000;
 const MAX_GAS_BUDGET: u64 = 50_000_000_000;
 
 /// The maximum number of objects to get in a single RPC call.
-pub(crate) const MULTI_GET_OBJ_LIMIT: usize = 50;
+pub const MULTI_GET_OBJ_LIMIT: usize = 50;
 
 /// Trait to test if an error is produced by a temporary RPC failure and can be retried.
 pub trait RetriableRpcError: Debug {
@@ -207,10 +226,14 @@ where
 /// This retriable client wraps functions from the [`CoinReadApi`][sui_sdk::apis::CoinReadApi] and
 /// the [`ReadApi`][sui_sdk::apis::ReadApi] of the [`SuiClient`], and
 /// additionally provides some convenience methods.
+///
+/// The client can be connected to multiple RPC endpoints, see
+/// [`RetriableSuiClient::new_for_rpcs`]. Each attempt of a call is then sent to the healthiest
+/// endpoint, so that retries fail over to other endpoints.
 #[allow(missing_debug_implementations)]
 #[derive(Clone)]
 pub struct RetriableSuiClient {
-    sui_client: SuiClient,
+    endpoints: Arc<SuiRpcEndpoints>,
     backoff_config: ExponentialBackoffConfig,
 }
 
@@ -222,12 +245,29 @@ impl RetriableSuiClient {
     /// make a call to the RPC server in [`WalletContext::get_client`], which may fail without any
     /// retries. `new_from_wallet` will handle this case correctly.
     pub fn new(sui_client: SuiClient, backoff_config: ExponentialBackoffConfig) -> Self {
+        Self::new_with_endpoints(
+            vec![SuiRpcEndpoint::new(UNKNOWN_RPC_URL.to_owned(), sui_client)],
+            backoff_config,
+            RpcFailoverConfig::default(),
+        )
+    }
+
+    fn new_with_endpoints(
+        endpoints: Vec<SuiRpcEndpoint>,
+        backoff_config: ExponentialBackoffConfig,
+        failover_config: RpcFailoverConfig,
+    ) -> Self {
         RetriableSuiClient {
-            sui_client,
+            endpoints: Arc::new(SuiRpcEndpoints::new(endpoints, failover_config)),
             backoff_config,
         }
     }
 
+    /// Returns the URLs of the RPC endpoints of the client.
+    pub fn rpc_urls(&self) -> impl Iterator<Item = &str> {
+        self.endpoints.endpoints().iter().map(SuiRpcEndpoint::url)
+    }
+
     /// Returns a reference to the inner backoff configuration.
     pub fn backoff_config(&self) -> &ExponentialBackoffConfig {
         &self.backoff_config
@@ -238,8 +278,49 @@ impl RetriableSuiClient {
         rpc_address: S,
         backoff_config: ExponentialBackoffConfig,
     ) -> SuiClientResult<Self> {
-        let client = SuiClientBuilder::default().build(rpc_address).await?;
-        Ok(Self::new(client, backoff_config))
+        Self::new_for_rpcs(&[rpc_address], backoff_config, RpcFailoverConfig::default()).await
+    }
+
+    /// Creates a new retriable client that fails over between the given RPC addresses.
+    ///
+    /// The first address is preferred as long as the endpoints are equally healthy. Addresses for
+    /// which no client can be built are skipped; an error is only returned if this is the case for
+    /// all of them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rpc_addresses` is empty.
+    pub async fn new_for_rpcs<S: AsRef<str>>(
+        rpc_addresses: &[S],
+        backoff_config: ExponentialBackoffConfig,
+        failover_config: RpcFailoverConfig,
+    ) -> SuiClientResult<Self> {
+        let mut endpoints = Vec::with_capacity(rpc_addresses.len());
+        let mut last_error = None;
+        for rpc_address in rpc_addresses {
+            let rpc_address = rpc_address.as_ref();
+            match SuiClientBuilder::default().build(rpc_address).await {
+                Ok(client) => endpoints.push(SuiRpcEndpoint::new(rpc_address.to_owned(), client)),
+                Err(error) => {
+                    tracing::warn!(
+                        rpc_address,
+                        %error,
+                        "failed to connect to the RPC endpoint, skipping it"
+                    );
+                    last_error = Some(error);
+                }
+            }
+        }
+        if endpoints.is_empty() {
+            if let Some(error) = last_error {
+                return Err(error.into());
+            }
+        }
+        Ok(Self::new_with_endpoints(
+            endpoints,
+            backoff_config,
+            failover_config,
+        ))
     }
 
     /// Creates a new retriable client from a wallet context.
@@ -250,7 +331,15 @@ impl RetriableSuiClient {
     ) -> SuiClientResult<Self> {
         let strategy = backoff_config.get_strategy(ThreadRng::default().gen());
         let client = retry_rpc_errors(strategy, || async { wallet.get_client().await }).await?;
-        Ok(Self::new(client, backoff_config))
+        let url = wallet
+            .config
+            .get_active_env()
+            .map_or_else(|_| UNKNOWN_RPC_URL.to_owned(), |env| env.rpc.clone());
+        Ok(Self::new_with_endpoints(
+            vec![SuiRpcEndpoint::new(url, client)],
+            backoff_config,
+            RpcFailoverConfig::default(),
+        ))
     }
 
     // Reimplementation of the `SuiClient` methods.
@@ -324,17 +413,20 @@ impl RetriableSuiClient {
                 if let Some(item) = data.pop() {
                     Some((item, (data, cursor, /* has_next_page */ true, coin_type)))
                 } else if has_next_page {
-                    let page = retry_rpc_errors(self.get_strategy(), || async {
-                        self.sui_client
-                            .coin_read_api()
-                            .get_coins(owner, coin_type.clone(), cursor.clone(), Some(100))
-                            .await
-                    })
-                    .await
-                    .inspect_err(
-                        |error| tracing::warn!(%error, "failed to get coins after retries"),
-                    )
-                    .ok()?;
+                    let page = self
+                        .retry_with_failover(|client| {
+                            client.coin_read_api().get_coins(
+                                owner,
+                                coin_type.clone(),
+                                cursor.clone(),
+                                Some(100),
+                            )
+                        })
+                        .await
+                        .inspect_err(
+                            |error| tracing::warn!(%error, "failed to get coins after retries"),
+                        )
+                        .ok()?;
 
                     let mut data = page.data;
                     data.reverse();
@@ -360,11 +452,8 @@ impl RetriableSuiClient {
         owner: SuiAddress,
         coin_type: Option<String>,
     ) -> SuiRpcResult<Balance> {
-        retry_rpc_errors(self.get_strategy(), || async {
-            self.sui_client
-                .coin_read_api()
-                .get_balance(owner, coin_type.clone())
-                .await
+        self.retry_with_failover(|client| {
+            client.coin_read_api().get_balance(owner, coin_type.clone())
         })
         .await
     }
@@ -380,11 +469,10 @@ impl RetriableSuiClient {
         cursor: Option<ObjectID>,
         limit: Option<usize>,
     ) -> SuiRpcResult<ObjectsPage> {
-        retry_rpc_errors(self.get_strategy(), || async {
-            self.sui_client
+        self.retry_with_failover(|client| {
+            client
                 .read_api()
                 .get_owned_objects(address, query.clone(), cursor, limit)
-                .await
         })
         .await
     }
@@ -398,11 +486,10 @@ impl RetriableSuiClient {
         object_id: ObjectID,
         options: SuiObjectDataOptions,
     ) -> SuiRpcResult<SuiObjectResponse> {
-        retry_rpc_errors(self.get_strategy(), || async {
-            self.sui_client
+        self.retry_with_failover(|client| {
+            client
                 .read_api()
                 .get_object_with_options(object_id, options.clone())
-                .await
         })
         .await
     }
@@ -415,11 +502,59 @@ impl RetriableSuiClient {
         digest: TransactionDigest,
         options: SuiTransactionBlockResponseOptions,
     ) -> SuiRpcResult<SuiTransactionBlockResponse> {
-        retry_rpc_errors(self.get_strategy(), || async {
-            self.sui_client
+        self.retry_with_failover(|client| {
+            client
                 .read_api()
                 .get_transaction_with_options(digest, options.clone())
-                .await
+        })
+        .await
+    }
+
+    /// Returns the [`Checkpoint`] with the given ID.
+    ///
+    /// Calls [`sui_sdk::apis::ReadApi::get_checkpoint`] internally.
+    pub async fn get_checkpoint(&self, id: CheckpointId) -> SuiRpcResult<Checkpoint> {
+        self.retry_with_failover(|client| client.read_api().get_checkpoint(id))
+            .await
+    }
+
+    /// Returns a page of transactions matching the `query`.
+    ///
+    /// Calls [`sui_sdk::apis::ReadApi::query_transaction_blocks`] internally.
+    #[tracing::instrument(level = Level::DEBUG, skip_all)]
+    pub async fn query_transaction_blocks(
+        &self,
+        query: SuiTransactionBlockResponseQuery,
+        cursor: Option<TransactionDigest>,
+        limit: Option<usize>,
+        descending_order: bool,
+    ) -> SuiRpcResult<TransactionBlocksPage> {
+        self.retry_with_failover(|client| {
+            client.read_api().query_transaction_blocks(
+                query.clone(),
+                cursor,
+                limit,
+                descending_order,
+            )
+        })
+        .await
+    }
+
+    /// Returns a page of events matching the `query`.
+    ///
+    /// Calls [`sui_sdk::apis::EventApi::query_events`] internally.
+    #[tracing::instrument(level = Level::DEBUG, skip_all)]
+    pub async fn query_events(
+        &self,
+        query: EventFilter,
+        cursor: Option<EventID>,
+        limit: Option<usize>,
+        descending_order: bool,
+    ) -> SuiRpcResult<EventPage> {
+        self.retry_with_failover(|client| {
+            client
+                .event_api()
+                .query_events(query.clone(), cursor, limit, descending_order)
         })
         .await
     }
@@ -433,11 +568,10 @@ impl RetriableSuiClient {
         object_ids: Vec<ObjectID>,
         options: SuiObjectDataOptions,
     ) -> SuiRpcResult<Vec<SuiObjectResponse>> {
-        retry_rpc_errors(self.get_strategy(), || async {
-            self.sui_client
+        self.retry_with_failover(|client| {
+            client
                 .read_api()
                 .multi_get_object_with_options(object_ids.clone(), options.clone())
-                .await
         })
         .await
     }
@@ -450,11 +584,10 @@ impl RetriableSuiClient {
         &self,
         package_id: ObjectID,
     ) -> SuiRpcResult<BTreeMap<String, SuiMoveNormalizedModule>> {
-        retry_rpc_errors(self.get_strategy(), || async {
-            self.sui_client
+        self.retry_with_failover(|client| {
+            client
                 .read_api()
                 .get_normalized_move_modules_by_package(package_id)
-                .await
         })
         .await
     }
@@ -466,23 +599,16 @@ impl RetriableSuiClient {
         &self,
         epoch: Option<BigInt<u64>>,
     ) -> SuiRpcResult<SuiCommittee> {
-        retry_rpc_errors(self.get_strategy(), || async {
-            self.sui_client
-                .governance_api()
-                .get_committee_info(epoch)
-                .await
-        })
-        .await
+        self.retry_with_failover(|client| client.governance_api().get_committee_info(epoch))
+            .await
     }
 
     /// Returns the reference gas price.
     ///
     /// Calls [`sui_sdk::apis::ReadApi::get_reference_gas_price`] internally.
     pub async fn get_reference_gas_price(&self) -> SuiRpcResult<u64> {
-        retry_rpc_errors(self.get_strategy(), || async {
-            self.sui_client.read_api().get_reference_gas_price().await
-        })
-        .await
+        self.retry_with_failover(|client| client.read_api().get_reference_gas_price())
+            .await
     }
 
     /// Executes a transaction dry run.
@@ -492,29 +618,28 @@ impl RetriableSuiClient {
         &self,
         transaction: TransactionData,
     ) -> SuiRpcResult<DryRunTransactionBlockResponse> {
-        retry_rpc_errors(self.get_strategy(), || async {
-            self.sui_client
+        self.retry_with_failover(|client| {
+            client
                 .read_api()
                 .dry_run_transaction_block(transaction.clone())
-                .await
         })
         .await
     }
 
-    /// Returns a reference to the [`EventApi`].
+    /// Returns a reference to the [`EventApi`] of the currently healthiest endpoint.
     ///
     /// Internally calls the [`SuiClient::event_api`] function. Note that no retries are
     /// implemented for this function.
     pub fn event_api(&self) -> &EventApi {
-        self.sui_client.event_api()
+        self.endpoints.select().client().event_api()
     }
 
-    /// Returns a reference to the [`GovernanceApi`].
+    /// Returns a reference to the [`GovernanceApi`] of the currently healthiest endpoint.
     ///
     /// Internally calls the [`SuiClient::governance_api`] function. Note that no retries are
     /// implemented for this function.
     pub fn governance_api(&self) -> &GovernanceApi {
-        self.sui_client.governance_api()
+        self.endpoints.select().client().governance_api()
     }
 
     /// Returns a [`SuiObjectResponse`] based on the provided [`ObjectID`].
@@ -542,10 +667,8 @@ impl RetriableSuiClient {
     ///
     /// Calls [`sui_sdk::apis::ReadApi::get_chain_identifier`] internally.
     pub async fn get_chain_identifier(&self) -> SuiRpcResult<String> {
-        retry_rpc_errors(self.get_strategy(), || async {
-            self.sui_client.read_api().get_chain_identifier().await
-        })
-        .await
+        self.retry_with_failover(|client| client.read_api().get_chain_identifier())
+            .await
     }
 
     // Other wrapper methods.
@@ -763,7 +886,9 @@ impl RetriableSuiClient {
         gas_price: u64,
     ) -> SuiClientResult<u64> {
         let dry_run_tx_data = self
-            .sui_client
+            .endpoints
+            .select()
+            .client()
             .transaction_builder()
             .tx_data_for_dry_run(signer, kind, MAX_GAS_BUDGET, gas_price, None, None)
             .await;
@@ -794,24 +919,47 @@ impl RetriableSuiClient {
             {
                 maybe_return_injected_error_in_stake_pool_transaction(&transaction)?;
             }
+            let endpoint = self.endpoints.select();
             Ok(self
-                .sui_client
-                .quorum_driver_api()
-                .execute_transaction_block(
-                    transaction.clone(),
-                    SuiTransactionBlockResponseOptions::new()
-                        .with_effects()
-                        .with_input()
-                        .with_events()
-                        .with_object_changes()
-                        .with_balance_changes(),
-                    Some(WaitForLocalExecution),
+                .endpoints
+                .observe(
+                    endpoint,
+                    endpoint
+                        .client()
+                        .quorum_driver_api()
+                        .execute_transaction_block(
+                            transaction.clone(),
+                            SuiTransactionBlockResponseOptions::new()
+                                .with_effects()
+                                .with_input()
+                                .with_events()
+                                .with_object_changes()
+                                .with_balance_changes(),
+                            Some(WaitForLocalExecution),
+                        ),
                 )
                 .await?)
         })
         .await
     }
 
+    /// Calls `call` with the client of the healthiest endpoint, retrying on RPC errors.
+    ///
+    /// Each attempt selects an endpoint anew, so that retries fail over to other endpoints once
+    /// an endpoint becomes unhealthy.
+    async fn retry_with_failover<'a, F, Fut, T, E>(&'a self, mut call: F) -> Result<T, E>
+    where
+        F: FnMut(&'a SuiClient) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+        E: RetriableRpcError,
+    {
+        retry_rpc_errors(self.get_strategy(), || {
+            let endpoint = self.endpoints.select();
+            self.endpoints.observe(endpoint, call(endpoint.client()))
+        })
+        .await
+    }
+
     /// Gets a backoff strategy, seeded from the internal RNG.
     fn get_strategy(&self) -> ExponentialBackoff<StdRng> {
         self.backoff_config.get_strategy(ThreadRng::default().gen())
@@ -0,0 +1,459 @@
+// Copyright (c) Walrus Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Health tracking of multiple Sui RPC endpoints, to route calls to the healthiest one.
+
+use std::{
+    cmp::Ordering,
+    future::Future,
+    sync::{Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+
+use prometheus::{GaugeVec, HistogramVec, IntCounterVec, IntGaugeVec, Registry};
+use sui_sdk::SuiClient;
+
+use super::RetriableRpcError;
+use crate::client::rpc_config::RpcFailoverConfig;
+
+/// The weight of the latest request in the moving averages of latency and error rate.
+const SMOOTHING_FACTOR: f64 = 0.2;
+/// The factor by which the latency of an endpoint is scaled up for its error rate in its score.
+const ERROR_RATE_PENALTY: f64 = 10.0;
+
+walrus_utils::metrics::define_metric_set! {
+    #[namespace = "walrus_sui_rpc"]
+    /// Metrics of the Sui RPC endpoints used by the retriable Sui clients.
+    pub struct SuiRpcEndpointMetrics {
+        #[help = "The number of requests sent to the endpoint, by outcome"]
+        requests: IntCounterVec["endpoint", "outcome"],
+
+        #[help = "The latency of the requests to the endpoint"]
+        request_latency_seconds: HistogramVec {
+            labels: ["endpoint"],
+            buckets: prometheus::exponential_buckets(0.005, 2.0, 14)
+                .expect("count, start, and factor are valid"),
+        },
+
+        #[help = "The moving average of the error rate of the endpoint"]
+        error_rate: GaugeVec["endpoint"],
+
+        #[help = "Whether the circuit of the endpoint is open, i.e., the endpoint is only probed"]
+        circuit_open: IntGaugeVec["endpoint"],
+    }
+}
+
+static METRICS: OnceLock<SuiRpcEndpointMetrics> = OnceLock::new();
+
+impl SuiRpcEndpointMetrics {
+    /// Registers the metrics of the Sui RPC endpoints of all clients in the process on the
+    /// registry.
+    ///
+    /// Only the first call has an effect. Until it is called, no endpoint metrics are recorded.
+    pub fn init(registry: &Registry) {
+        METRICS.get_or_init(|| Self::new(registry));
+    }
+}
+
+/// The health of an endpoint, based on the recent requests sent to it.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct EndpointHealth {
+    /// The moving average of the latency, `None` until the first successful request.
+    latency: Option<Duration>,
+    /// The moving average of the error rate, between 0 and 1.
+    error_rate: f64,
+    consecutive_failures: u32,
+    /// The time until which the circuit is open, if it is open.
+    open_until: Option<Instant>,
+    /// The time at which the running probe of an open circuit was started, if any.
+    probe_started: Option<Instant>,
+}
+
+impl EndpointHealth {
+    /// Returns the score of the endpoint; lower is better.
+    ///
+    /// The score is `None` if no request to the endpoint has succeeded yet, as its latency is
+    /// unknown.
+    fn score(&self) -> Option<f64> {
+        self.latency
+            .map(|latency| latency.as_secs_f64() * (1.0 + ERROR_RATE_PENALTY * self.error_rate))
+    }
+
+    /// Compares the health of two endpoints; the healthier endpoint is less.
+    ///
+    /// Endpoints without a score rank behind all endpoints with a score, and are ordered by their
+    /// error rate among themselves.
+    fn cmp_health(&self, other: &Self) -> Ordering {
+        match (self.score(), other.score()) {
+            (Some(score), Some(other_score)) => score.total_cmp(&other_score),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => self.error_rate.total_cmp(&other.error_rate),
+        }
+    }
+
+    fn is_open(&self) -> bool {
+        self.open_until.is_some()
+    }
+
+    /// Returns true if the circuit is open, and a probe can be sent to the endpoint.
+    fn can_probe(&self, now: Instant, probe_interval: Duration) -> bool {
+        let Some(open_until) = self.open_until else {
+            return false;
+        };
+        now >= open_until
+            && self
+                .probe_started
+                .is_none_or(|started| now.duration_since(started) >= probe_interval)
+    }
+
+    fn record_success(&mut self, latency: Duration) {
+        self.latency = Some(match self.latency {
+            Some(average) => {
+                average.mul_f64(1.0 - SMOOTHING_FACTOR) + latency.mul_f64(SMOOTHING_FACTOR)
+            }
+            None => latency,
+        });
+        self.error_rate *= 1.0 - SMOOTHING_FACTOR;
+        self.consecutive_failures = 0;
+        self.open_until = None;
+        self.probe_started = None;
+    }
+
+    fn record_failure(&mut self, now: Instant, config: &RpcFailoverConfig) {
+        self.error_rate = self.error_rate * (1.0 - SMOOTHING_FACTOR) + SMOOTHING_FACTOR;
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        if self.is_open() || self.consecutive_failures >= config.failure_threshold {
+            self.open_until = Some(now + config.probe_interval);
+            self.probe_started = None;
+        }
+    }
+}
+
+/// A Sui RPC endpoint along with its health.
+pub(crate) struct SuiRpcEndpoint<C = SuiClient> {
+    url: String,
+    client: C,
+    health: Mutex<EndpointHealth>,
+}
+
+impl<C> std::fmt::Debug for SuiRpcEndpoint<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SuiRpcEndpoint")
+            .field("url", &self.url)
+            .field("health", &self.health)
+            .finish()
+    }
+}
+
+impl<C> SuiRpcEndpoint<C> {
+    pub(crate) fn new(url: String, client: C) -> Self {
+        Self {
+            url,
+            client,
+            health: Default::default(),
+        }
+    }
+
+    /// Returns the URL of the endpoint.
+    pub(crate) fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Returns the client connected to the endpoint.
+    pub(crate) fn client(&self) -> &C {
+        &self.client
+    }
+
+    fn health(&self) -> std::sync::MutexGuard<'_, EndpointHealth> {
+        self.health.lock().expect("the lock should not be poisoned")
+    }
+}
+
+/// A set of Sui RPC endpoints that selects the healthiest one for each request.
+///
+/// Requests that fail with retriable RPC errors count as failures of the endpoint; all other
+/// results count as successes, as they show that the endpoint is reachable.
+pub(crate) struct SuiRpcEndpoints<C = SuiClient> {
+    endpoints: Vec<SuiRpcEndpoint<C>>,
+    config: RpcFailoverConfig,
+}
+
+impl<C> std::fmt::Debug for SuiRpcEndpoints<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SuiRpcEndpoints")
+            .field("endpoints", &self.endpoints)
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
+impl<C> SuiRpcEndpoints<C> {
+    /// Creates a new set of endpoints.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `endpoints` is empty.
+    pub(crate) fn new(endpoints: Vec<SuiRpcEndpoint<C>>, config: RpcFailoverConfig) -> Self {
+        assert!(
+            !endpoints.is_empty(),
+            "at least one RPC endpoint is required"
+        );
+        Self { endpoints, config }
+    }
+
+    /// Returns the endpoints.
+    pub(crate) fn endpoints(&self) -> &[SuiRpcEndpoint<C>] {
+        &self.endpoints
+    }
+
+    /// Selects the endpoint to send the next request to.
+    ///
+    /// If the probe interval of an endpoint with an open circuit has elapsed, the endpoint is
+    /// selected to probe it. Otherwise, the healthiest endpoint among the endpoints with a closed
+    /// circuit is selected, preferring earlier endpoints in case of equal health. If all circuits
+    /// are open, the endpoint whose circuit closes first is selected.
+    pub(crate) fn select(&self) -> &SuiRpcEndpoint<C> {
+        if let [endpoint] = self.endpoints.as_slice() {
+            return endpoint;
+        }
+
+        let now = Instant::now();
+        for endpoint in &self.endpoints {
+            let mut health = endpoint.health();
+            if health.can_probe(now, self.config.probe_interval) {
+                health.probe_started = Some(now);
+                tracing::debug!(
+                    url = endpoint.url,
+                    "probing RPC endpoint with an open circuit"
+                );
+                return endpoint;
+            }
+        }
+
+        let healths: Vec<_> = self
+            .endpoints
+            .iter()
+            .map(|endpoint| endpoint.health().clone())
+            .collect();
+        let closed = healths
+            .iter()
+            .enumerate()
+            .filter(|(_, health)| !health.is_open())
+            .min_by(|(_, a), (_, b)| a.cmp_health(b));
+        let index = match closed {
+            Some((index, _)) => index,
+            None => healths
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, health)| health.open_until)
+                .map(|(index, _)| index)
+                .expect("there is at least one endpoint"),
+        };
+        &self.endpoints[index]
+    }
+
+    /// Awaits the request `future` sent to `endpoint`, and records its outcome.
+    pub(crate) async fn observe<T, E, Fut>(
+        &self,
+        endpoint: &SuiRpcEndpoint<C>,
+        future: Fut,
+    ) -> Result<T, E>
+    where
+        E: RetriableRpcError,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let start = Instant::now();
+        let result = future.await;
+        let latency = start.elapsed();
+        let failed = matches!(&result, Err(error) if error.is_retriable_rpc_error());
+
+        let health = {
+            let mut health = endpoint.health();
+            if failed {
+                let was_open = health.is_open();
+                health.record_failure(Instant::now(), &self.config);
+                if !was_open && health.is_open() {
+                    tracing::warn!(
+                        url = endpoint.url,
+                        consecutive_failures = health.consecutive_failures,
+                        "opening the circuit of the RPC endpoint"
+                    );
+                }
+            } else {
+                if health.is_open() {
+                    tracing::info!(
+                        url = endpoint.url,
+                        "closing the circuit of the RPC endpoint"
+                    );
+                }
+                health.record_success(latency);
+            }
+            health.clone()
+        };
+
+        if let Some(metrics) = METRICS.get() {
+            let outcome = if failed { "failure" } else { "success" };
+            metrics
+                .requests
+                .with_label_values(&[endpoint.url.as_str(), outcome])
+                .inc();
+            metrics
+                .request_latency_seconds
+                .with_label_values(&[endpoint.url.as_str()])
+                .observe(latency.as_secs_f64());
+            metrics
+                .error_rate
+                .with_label_values(&[endpoint.url.as_str()])
+                .set(health.error_rate);
+            metrics
+                .circuit_open
+                .with_label_values(&[endpoint.url.as_str()])
+                .set(health.is_open().into());
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> RpcFailoverConfig {
+        RpcFailoverConfig {
+            failure_threshold: 2,
+            probe_interval: Duration::from_secs(10),
+        }
+    }
+
+    #[test]
+    fn errors_penalize_the_score() {
+        let mut fast_but_failing = EndpointHealth::default();
+        fast_but_failing.record_success(Duration::from_millis(10));
+        fast_but_failing.record_failure(Instant::now(), &config());
+
+        let mut slow = EndpointHealth::default();
+        slow.record_success(Duration::from_millis(20));
+
+        assert!(slow.score() < fast_but_failing.score());
+        assert_eq!(slow.cmp_health(&fast_but_failing), Ordering::Less);
+    }
+
+    #[test]
+    fn endpoints_without_latency_rank_behind_measured_ones() {
+        let mut failing = EndpointHealth::default();
+        failing.record_success(Duration::from_millis(10));
+        failing.record_failure(Instant::now(), &config());
+
+        let unknown = EndpointHealth::default();
+        assert_eq!(unknown.score(), None);
+        assert_eq!(failing.cmp_health(&unknown), Ordering::Less);
+
+        let mut unknown_and_failing = EndpointHealth::default();
+        unknown_and_failing.record_failure(Instant::now(), &config());
+        assert_eq!(unknown.cmp_health(&unknown_and_failing), Ordering::Less);
+    }
+
+    fn endpoints(n_endpoints: usize) -> SuiRpcEndpoints<()> {
+        SuiRpcEndpoints::new(
+            (0..n_endpoints)
+                .map(|index| SuiRpcEndpoint::new(format!("endpoint-{index}"), ()))
+                .collect(),
+            config(),
+        )
+    }
+
+    fn selected_url(endpoints: &SuiRpcEndpoints<()>) -> &str {
+        endpoints.select().url()
+    }
+
+    #[test]
+    fn select_prefers_the_first_endpoint_while_latencies_are_unknown() {
+        let endpoints = endpoints(3);
+        assert_eq!(selected_url(&endpoints), "endpoint-0");
+    }
+
+    #[test]
+    fn select_prefers_the_lowest_score() {
+        let endpoints = endpoints(3);
+        endpoints.endpoints()[0]
+            .health()
+            .record_success(Duration::from_millis(50));
+        endpoints.endpoints()[1]
+            .health()
+            .record_success(Duration::from_millis(10));
+        assert_eq!(selected_url(&endpoints), "endpoint-1");
+    }
+
+    #[test]
+    fn select_prefers_measured_endpoints() {
+        let endpoints = endpoints(2);
+        endpoints.endpoints()[1]
+            .health()
+            .record_success(Duration::from_millis(50));
+        assert_eq!(selected_url(&endpoints), "endpoint-1");
+    }
+
+    #[test]
+    fn select_skips_endpoints_with_open_circuits() {
+        let endpoints = endpoints(2);
+        let now = Instant::now();
+        {
+            let mut health = endpoints.endpoints()[0].health();
+            health.record_failure(now, &config());
+            health.record_failure(now, &config());
+        }
+        assert_eq!(selected_url(&endpoints), "endpoint-1");
+    }
+
+    #[test]
+    fn select_probes_an_open_circuit_once_per_interval() {
+        let endpoints = endpoints(2);
+        endpoints.endpoints()[1]
+            .health()
+            .record_success(Duration::from_millis(50));
+        endpoints.endpoints()[0].health().open_until = Some(Instant::now());
+
+        assert_eq!(selected_url(&endpoints), "endpoint-0");
+        assert!(endpoints.endpoints()[0].health().probe_started.is_some());
+        // While the probe is running, requests go to the endpoint with a closed circuit.
+        assert_eq!(selected_url(&endpoints), "endpoint-1");
+    }
+
+    #[test]
+    fn select_picks_the_circuit_closing_first_if_all_are_open() {
+        let endpoints = endpoints(3);
+        let now = Instant::now();
+        for (index, endpoint) in endpoints.endpoints().iter().enumerate() {
+            let offset = Duration::from_secs(if index == 2 { 10 } else { 20 });
+            endpoint.health().open_until = Some(now + offset);
+        }
+        assert_eq!(selected_url(&endpoints), "endpoint-2");
+    }
+
+    #[test]
+    fn circuit_opens_after_consecutive_failures_and_closes_on_success() {
+        let config = config();
+        let now = Instant::now();
+        let mut health = EndpointHealth::default();
+
+        health.record_failure(now, &config);
+        assert!(!health.is_open());
+        health.record_failure(now, &config);
+        assert!(health.is_open());
+
+        assert!(!health.can_probe(now, config.probe_interval));
+        let later = now + config.probe_interval;
+        assert!(health.can_probe(later, config.probe_interval));
+
+        // A running probe blocks further probes, and a failed probe re-opens the circuit.
+        health.probe_started = Some(later);
+        assert!(!health.can_probe(later, config.probe_interval));
+        health.record_failure(later, &config);
+        assert_eq!(health.open_until, Some(later + config.probe_interval));
+
+        health.record_success(Duration::from_millis(5));
+        assert!(!health.is_open());
+        assert_eq!(health.consecutive_failures, 0);
+    }
+}
@@ -6,6 +6,7 @@
 use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DurationMilliSeconds};
 use walrus_utils::backoff::ExponentialBackoffConfig;
 
 /// Configuration for the RPC endpoint fallback.
@@ -79,3 +80,37 @@ impl RpcFallbackConfigArgs {
         })
     }
 }
+
+/// Configuration for the failover between multiple Sui RPC endpoints.
+///
+/// Requests are routed to the healthiest endpoint, based on its recent latency and error rate.
+/// An endpoint whose requests failed `failure_threshold` times in a row is taken out of the
+/// rotation, and is probed with a single request every `probe_interval` until it recovers.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct RpcFailoverConfig {
+    /// The number of consecutive failed requests after which the circuit of an endpoint is
+    /// opened.
+    pub failure_threshold: u32,
+    /// The interval, in milliseconds, with which an endpoint with an open circuit is probed.
+    #[serde_as(as = "DurationMilliSeconds")]
+    #[serde(rename = "probe_interval_millis")]
+    pub probe_interval: Duration,
+}
+
+impl RpcFailoverConfig {
+    /// The default number of consecutive failures after which the circuit is opened.
+    pub const DEFAULT_FAILURE_THRESHOLD: u32 = 3;
+    /// The default interval with which endpoints with an open circuit are probed.
+    pub const DEFAULT_PROBE_INTERVAL: Duration = Duration::from_secs(30);
+}
+
+impl Default for RpcFailoverConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: Self::DEFAULT_FAILURE_THRESHOLD,
+            probe_interval: Self::DEFAULT_PROBE_INTERVAL,
+        }
+    }
+}
@@ -65,6 +65,24 @@ const CLOCK_OBJECT_ARG: ObjectArg = ObjectArg::SharedObject {
 // NB: this should be kept in sync with the maximum number of commands in the Sui `ProtocolConfig`.
 pub const MAX_BURNS_PER_PTB: usize = 1000;
 
+/// The maximum number of commands in a PTB.
+// NB: this should be kept in sync with the maximum number of commands in the Sui `ProtocolConfig`.
+const MAX_COMMANDS_PER_PTB: usize = 1024;
+
+/// The number of commands kept free in a PTB for commands that are not specific to a single blob,
+/// e.g., the transfer of the remaining outputs.
+const RESERVED_COMMANDS_PER_PTB: usize = 24;
+
+/// The maximum number of commands added to a PTB by extending a blob.
+///
+/// Each extension adds the Move call to extend the blob and, if the WAL balance of the PTB is
+/// insufficient, a command merging additional WAL coins into the payment coin.
+const COMMANDS_PER_EXTENSION: usize = 2;
+
+/// The maximum number of blobs that can be extended in a single PTB.
+pub const MAX_EXTENSIONS_PER_PTB: usize =
+    (MAX_COMMANDS_PER_PTB - RESERVED_COMMANDS_PER_PTB) / COMMANDS_PER_EXTENSION;
+
 #[derive(Debug, Clone, Copy)]
 /// A wrapper around an [`Argument`] or an [`ObjectID`] for use in [`WalrusPtbBuilder`].
 pub enum ArgumentOrOwnedObject {
@@ -335,6 +353,63 @@ impl WalrusPtbBuilder {
         Ok(result_arg)
     }
 
+    /// Adds a call to `split_by_epoch` to the `pt_builder` and returns the result [`Argument`].
+    ///
+    /// The `storage_resource` is shortened to end at `split_epoch`, and the returned storage
+    /// resource covers the period from `split_epoch` to the original end epoch.
+    pub async fn split_storage_by_epoch(
+        &mut self,
+        storage_resource: ArgumentOrOwnedObject,
+        split_epoch: Epoch,
+    ) -> SuiClientResult<Argument> {
+        let split_arguments = vec![
+            self.argument_from_arg_or_obj(storage_resource).await?,
+            self.pt_builder.pure(split_epoch)?,
+        ];
+        let result_arg =
+            self.walrus_move_call(contracts::storage_resource::split_by_epoch, split_arguments)?;
+        self.add_result_to_be_consumed(result_arg);
+        Ok(result_arg)
+    }
+
+    /// Adds a call to `split_by_size` to the `pt_builder` and returns the result [`Argument`].
+    ///
+    /// The `storage_resource` keeps `split_size` bytes, and the returned storage resource covers
+    /// the remaining size.
+    pub async fn split_storage_by_size(
+        &mut self,
+        storage_resource: ArgumentOrOwnedObject,
+        split_size: u64,
+    ) -> SuiClientResult<Argument> {
+        let split_arguments = vec![
+            self.argument_from_arg_or_obj(storage_resource).await?,
+            self.pt_builder.pure(split_size)?,
+        ];
+        let result_arg =
+            self.walrus_move_call(contracts::storage_resource::split_by_size, split_arguments)?;
+        self.add_result_to_be_consumed(result_arg);
+        Ok(result_arg)
+    }
+
+    /// Adds a call to `fuse` to the `pt_builder`, merging `second` into `first`.
+    ///
+    /// The storage resources must either cover the same period, or adjacent periods with the same
+    /// storage size.
+    pub async fn fuse_storage(
+        &mut self,
+        first: ArgumentOrOwnedObject,
+        second: ArgumentOrOwnedObject,
+    ) -> SuiClientResult<()> {
+        let first_arg = self.argument_from_arg_or_obj(first).await?;
+        let second_arg = self.argument_from_arg_or_obj(second).await?;
+        self.walrus_move_call(
+            contracts::storage_resource::fuse,
+            vec![first_arg, second_arg],
+        )?;
+        self.mark_arg_as_consumed(&second_arg);
+        Ok(())
+    }
+
     /// Adds a call to `certify_blob` to the `pt_builder`.
     pub async fn certify_blob(
         &mut self,
@@ -1,7 +1,7 @@
 // Copyright (c) Walrus Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-//! Wallet configuration utilities.
+//! Wallet and gas sponsor configuration utilities.
 use std::{
     fmt::Debug,
     path::{Path, PathBuf},
@@ -11,6 +11,7 @@ use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 use sui_sdk::wallet_context::WalletContext;
 use sui_types::base_types::SuiAddress;
+use url::Url;
 use walrus_utils::config::{path_or_defaults_if_exist, resolve_home_dir};
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
@@ -119,3 +120,33 @@ impl WalletConfig {
         Ok(wallet_context)
     }
 }
+
+/// The configuration of a sponsor paying for the gas of the client's transactions.
+///
+/// With a sponsor, the transactions are signed by both the wallet (as the sender) and the
+/// sponsor (as the gas owner), such that the wallet does not need to own any SUI.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GasSponsorConfig {
+    /// The sponsor's key is stored in a local Sui keystore file, and the gas coins are selected
+    /// from the coins owned by the sponsor's address.
+    Keyfile {
+        /// The path to the Sui keystore file (in the format of `sui.keystore`).
+        #[serde(deserialize_with = "resolve_home_dir")]
+        path: PathBuf,
+        /// The address of the sponsor; required if the keystore contains more than one key.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        address: Option<SuiAddress>,
+    },
+    /// The gas coins are reserved and the transactions are signed by a signing service.
+    ///
+    /// The service has to expose the `POST /v1/reserve_gas` and `POST /v1/sign` endpoints; see
+    /// [`GasSponsor`][crate::client::GasSponsor] for the details of the protocol.
+    Service {
+        /// The URL of the signing service.
+        url: Url,
+        /// The bearer token used to authenticate with the signing service, if any.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        auth_token: Option<String>,
+    },
+}
@@ -3,6 +3,7 @@
 
 //! Test utilities for `walrus-sui`.
 
+pub mod fake_sui;
 pub mod system_setup;
 
 #[cfg(not(msim))]
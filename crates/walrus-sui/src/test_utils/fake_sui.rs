@@ -0,0 +1,1132 @@
+// Copyright (c) Walrus Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! An in-memory fake of the Walrus contracts, to run clients and storage nodes without Sui.
+//!
+//! The [`FakeSuiClient`] simulates the system and staking objects, blob objects, storage
+//! resources, events, and epoch changes of a Walrus system, and implements both [`ReadClient`]
+//! and [`ContractClient`]. Clones of a `FakeSuiClient`, as well as the clients returned by
+//! [`FakeSuiClient::with_address`], share the same simulated chain.
+//!
+//! Epochs only change when the test calls [`FakeSuiClient::start_epoch_change`],
+//! [`FakeSuiClient::finish_epoch_change`], or [`FakeSuiClient::advance_epoch`], and all object
+//! IDs, event IDs, and timestamps are derived deterministically from the state of the fake.
+//!
+//! Certificates are checked to refer to the correct blob ID, but their signatures are not
+//! verified. The fake identifies storage-node capabilities with the IDs of the nodes, i.e.,
+//! [`ContractClient::epoch_sync_done`] expects the node ID as the capability object ID.
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt::{self, Debug, Formatter},
+    num::NonZeroU16,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use anyhow::anyhow;
+use chrono::{DateTime, TimeDelta, Utc};
+use futures::{stream, StreamExt as _};
+use sui_types::{
+    base_types::{ObjectID, SuiAddress},
+    digests::TransactionDigest,
+    event::EventID,
+};
+use tokio::sync::mpsc;
+use tokio_stream::{wrappers::UnboundedReceiverStream, Stream};
+use walrus_core::{
+    messages::{Confirmation, ConfirmationCertificate, InvalidBlobCertificate, InvalidBlobIdMsg},
+    BlobId,
+    Epoch,
+    EpochCount,
+    ShardIndex,
+};
+
+use crate::{
+    client::{
+        BlobObjectMetadata,
+        BlobPersistence,
+        CertifyAndExtendBlobParams,
+        CommitteesAndState,
+        ContractClient,
+        ExpirySelectionPolicy,
+        FixedSystemParameters,
+        PostStoreAction,
+        ReadClient,
+        SuiClientError,
+        SuiClientResult,
+        MIN_STAKING_THRESHOLD,
+    },
+    types::{
+        move_structs::{BlobAttribute, BlobWithAttribute, EpochState, EventBlob},
+        Blob,
+        BlobCertified,
+        BlobDeleted,
+        BlobEvent,
+        BlobRegistered,
+        Committee,
+        ContractEvent,
+        EpochChangeDone,
+        EpochChangeEvent,
+        EpochChangeStart,
+        EpochParametersSelected,
+        InvalidBlobId,
+        StorageNode,
+        StorageResource,
+    },
+};
+
+/// The default price for one unit of storage per epoch used by the fake.
+pub const DEFAULT_STORAGE_PRICE_PER_UNIT_SIZE: u64 = 100;
+/// The default price to write one unit of storage used by the fake.
+pub const DEFAULT_WRITE_PRICE_PER_UNIT_SIZE: u64 = 10;
+/// The default maximum number of epochs ahead for which storage can be purchased.
+pub const DEFAULT_MAX_EPOCHS_AHEAD: EpochCount = 53;
+/// The default epoch duration of the fake.
+pub const DEFAULT_EPOCH_DURATION: Duration = Duration::from_secs(3600);
+
+/// An in-memory Walrus system implementing [`ReadClient`] and [`ContractClient`].
+#[derive(Clone)]
+pub struct FakeSuiClient {
+    state: Arc<Mutex<FakeSuiState>>,
+    address: SuiAddress,
+}
+
+impl Debug for FakeSuiClient {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let state = self.state();
+        f.debug_struct("FakeSuiClient")
+            .field("address", &self.address)
+            .field("epoch", &state.current.epoch)
+            .field("epoch_state", &state.epoch_state)
+            .field("n_events", &state.events.len())
+            .finish()
+    }
+}
+
+impl FakeSuiClient {
+    /// Creates a new fake Walrus system with the given committee, using a random address.
+    ///
+    /// The epoch of the committee is the current epoch of the system, and the number of shards of
+    /// the committee is used for the whole lifetime of the system.
+    pub fn new(committee: Committee) -> Self {
+        Self::new_with_address(committee, SuiAddress::random_for_testing_only())
+    }
+
+    /// Creates a new fake Walrus system with the given committee, sending transactions from
+    /// `address`.
+    pub fn new_with_address(committee: Committee, address: SuiAddress) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(FakeSuiState::new(committee))),
+            address,
+        }
+    }
+
+    /// Returns a client for the same simulated system that sends transactions from `address`.
+    pub fn with_address(&self, address: SuiAddress) -> Self {
+        Self {
+            state: self.state.clone(),
+            address,
+        }
+    }
+
+    /// Sets the storage and write prices per unit size.
+    pub fn set_prices(&self, storage_price_per_unit_size: u64, write_price_per_unit_size: u64) {
+        let mut state = self.state();
+        state.storage_price_per_unit_size = storage_price_per_unit_size;
+        state.write_price_per_unit_size = write_price_per_unit_size;
+    }
+
+    /// Sets the stake of the node with the given ID, adding it to the active set if needed.
+    ///
+    /// A stake of zero removes the node from the active set.
+    pub fn set_stake(&self, node: StorageNode, stake: u64) {
+        let mut state = self.state();
+        if stake == 0 {
+            state.stake.remove(&node.node_id);
+        } else {
+            state.stake.insert(node.node_id, stake);
+        }
+        state.nodes.insert(node.node_id, node);
+    }
+
+    /// Sets the parameters for the next epoch, selecting `committee` as the next committee.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the epoch of `committee` is not the next epoch, if the number of shards differs
+    /// from the one of the system, or if an epoch change is in progress.
+    pub fn set_next_committee(&self, committee: Committee) {
+        let mut state = self.state();
+        assert!(
+            !state.epoch_state.is_transitioning(),
+            "cannot select the next committee during an epoch change"
+        );
+        assert_eq!(committee.epoch, state.current.epoch + 1);
+        assert_eq!(committee.n_shards(), state.fixed_params.n_shards);
+        state.select_next_committee(committee);
+    }
+
+    /// Starts the change to the next epoch and returns the new epoch.
+    ///
+    /// If no next committee has been selected, the members of the current committee keep their
+    /// shards. Afterwards, the epoch state is [`EpochState::EpochChangeSync`] until a quorum of
+    /// shards calls [`ContractClient::epoch_sync_done`] or [`Self::finish_epoch_change`] is
+    /// called.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an epoch change is already in progress.
+    pub fn start_epoch_change(&self) -> Epoch {
+        let mut state = self.state();
+        assert!(
+            !state.epoch_state.is_transitioning(),
+            "an epoch change is already in progress"
+        );
+        if state.next.is_none() {
+            let next = state.current_members_in_epoch(state.current.epoch + 1);
+            state.select_next_committee(next);
+        }
+        let next = state
+            .next
+            .take()
+            .expect("the next committee was just selected");
+        let epoch = next.epoch;
+        let previous = std::mem::replace(&mut state.current, next);
+        state.previous = Some(previous);
+        state.epoch_state = EpochState::EpochChangeSync(0);
+        state.synced_nodes.clear();
+        let event_id = state.next_transaction().next_event_id();
+        state.emit(ContractEvent::EpochChangeEvent(
+            EpochChangeEvent::EpochChangeStart(EpochChangeStart { epoch, event_id }),
+        ));
+        epoch
+    }
+
+    /// Completes the ongoing epoch change, independently of the nodes that finished syncing.
+    ///
+    /// Does nothing if no epoch change is in progress.
+    pub fn finish_epoch_change(&self) {
+        self.state().finish_epoch_change();
+    }
+
+    /// Starts and completes the change to the next epoch and returns the new epoch.
+    pub fn advance_epoch(&self) -> Epoch {
+        let epoch = self.start_epoch_change();
+        self.finish_epoch_change();
+        epoch
+    }
+
+    /// Sets the attribute of the blob object with the given ID.
+    pub fn set_blob_attribute(
+        &self,
+        blob_object_id: ObjectID,
+        attribute: BlobAttribute,
+    ) -> SuiClientResult<()> {
+        let mut state = self.state();
+        state.next_transaction();
+        state.blob_object_mut(blob_object_id, None)?.attribute = Some(attribute);
+        Ok(())
+    }
+
+    /// Returns all the events emitted so far, in order.
+    pub fn events(&self) -> Vec<ContractEvent> {
+        self.state().events.clone()
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, FakeSuiState> {
+        self.state.lock().expect("the lock should not be poisoned")
+    }
+}
+
+/// A blob object in the fake system.
+#[derive(Debug, Clone)]
+struct FakeBlobObject {
+    blob: Blob,
+    /// The owner of the object, `None` if the object is shared.
+    owner: Option<SuiAddress>,
+    attribute: Option<BlobAttribute>,
+}
+
+#[derive(Debug)]
+struct FakeSuiState {
+    fixed_params: FixedSystemParameters,
+    storage_price_per_unit_size: u64,
+    write_price_per_unit_size: u64,
+    current: Committee,
+    previous: Option<Committee>,
+    next: Option<Committee>,
+    epoch_state: EpochState,
+    synced_nodes: HashSet<ObjectID>,
+    nodes: HashMap<ObjectID, StorageNode>,
+    stake: HashMap<ObjectID, u64>,
+    blobs: BTreeMap<ObjectID, FakeBlobObject>,
+    storage: BTreeMap<ObjectID, (StorageResource, SuiAddress)>,
+    invalid_blob_ids: HashSet<BlobId>,
+    events: Vec<ContractEvent>,
+    subscribers: Vec<mpsc::UnboundedSender<ContractEvent>>,
+    n_created_objects: u64,
+    n_transactions: u64,
+    n_events_in_transaction: u64,
+}
+
+impl FakeSuiState {
+    fn new(committee: Committee) -> Self {
+        let fixed_params = FixedSystemParameters {
+            n_shards: committee.n_shards(),
+            max_epochs_ahead: DEFAULT_MAX_EPOCHS_AHEAD,
+            epoch_duration: DEFAULT_EPOCH_DURATION,
+            epoch_zero_end: DateTime::<Utc>::UNIX_EPOCH,
+        };
+        let nodes = committee
+            .members()
+            .iter()
+            .map(|node| (node.node_id, node.clone()))
+            .collect();
+        let stake = committee
+            .members()
+            .iter()
+            .map(|node| {
+                (
+                    node.node_id,
+                    MIN_STAKING_THRESHOLD * node.shard_ids.len() as u64,
+                )
+            })
+            .collect();
+        let mut state = Self {
+            fixed_params,
+            storage_price_per_unit_size: DEFAULT_STORAGE_PRICE_PER_UNIT_SIZE,
+            write_price_per_unit_size: DEFAULT_WRITE_PRICE_PER_UNIT_SIZE,
+            previous: None,
+            next: None,
+            epoch_state: EpochState::EpochChangeDone(DateTime::<Utc>::UNIX_EPOCH),
+            synced_nodes: HashSet::new(),
+            nodes,
+            stake,
+            blobs: BTreeMap::new(),
+            storage: BTreeMap::new(),
+            invalid_blob_ids: HashSet::new(),
+            events: vec![],
+            subscribers: vec![],
+            n_created_objects: 0,
+            n_transactions: 0,
+            n_events_in_transaction: 0,
+            current: committee,
+        };
+        state.epoch_state = EpochState::EpochChangeDone(state.epoch_start(state.current.epoch));
+        if state.current.epoch > 0 {
+            state.previous = Some(state.current_members_in_epoch(state.current.epoch - 1));
+        }
+        state
+    }
+
+    fn epoch(&self) -> Epoch {
+        self.current.epoch
+    }
+
+    /// Returns the deterministic start time of `epoch`.
+    fn epoch_start(&self, epoch: Epoch) -> DateTime<Utc> {
+        let epochs_after_zero = epoch.saturating_sub(1);
+        self.fixed_params.epoch_zero_end
+            + TimeDelta::from_std(self.fixed_params.epoch_duration * epochs_after_zero)
+                .expect("the epoch start is in range")
+    }
+
+    /// Returns a committee with the members and shards of the current committee for `epoch`.
+    fn current_members_in_epoch(&self, epoch: Epoch) -> Committee {
+        Committee::new(
+            self.current.members().to_vec(),
+            epoch,
+            self.fixed_params.n_shards,
+        )
+        .expect("the current committee is valid")
+    }
+
+    fn select_next_committee(&mut self, committee: Committee) {
+        for node in committee.members() {
+            self.nodes.insert(node.node_id, node.clone());
+        }
+        let next_epoch = committee.epoch;
+        self.next = Some(committee);
+        self.epoch_state = EpochState::NextParamsSelected(self.epoch_start(self.epoch()));
+        let event_id = self.next_transaction().next_event_id();
+        self.emit(ContractEvent::EpochChangeEvent(
+            EpochChangeEvent::EpochParametersSelected(EpochParametersSelected {
+                next_epoch,
+                event_id,
+            }),
+        ));
+    }
+
+    fn finish_epoch_change(&mut self) {
+        if !self.epoch_state.is_transitioning() {
+            return;
+        }
+        let epoch = self.epoch();
+        self.epoch_state = EpochState::EpochChangeDone(self.epoch_start(epoch));
+        let event_id = self.next_event_id();
+        self.emit(ContractEvent::EpochChangeEvent(
+            EpochChangeEvent::EpochChangeDone(EpochChangeDone { epoch, event_id }),
+        ));
+    }
+
+    /// Starts a new simulated transaction, which the following events are part of.
+    fn next_transaction(&mut self) -> &mut Self {
+        self.n_transactions += 1;
+        self.n_events_in_transaction = 0;
+        self
+    }
+
+    fn next_event_id(&mut self) -> EventID {
+        let mut digest = [0; 32];
+        digest[24..].copy_from_slice(&self.n_transactions.to_be_bytes());
+        let event_seq = self.n_events_in_transaction;
+        self.n_events_in_transaction += 1;
+        EventID {
+            tx_digest: TransactionDigest::new(digest),
+            event_seq,
+        }
+    }
+
+    fn new_object_id(&mut self) -> ObjectID {
+        self.n_created_objects += 1;
+        let mut id = [0; ObjectID::LENGTH];
+        id[0] = 0xfa;
+        id[24..].copy_from_slice(&self.n_created_objects.to_be_bytes());
+        ObjectID::new(id)
+    }
+
+    fn emit(&mut self, event: ContractEvent) {
+        self.subscribers
+            .retain(|subscriber| subscriber.send(event.clone()).is_ok());
+        self.events.push(event);
+    }
+
+    fn emit_blob_event(&mut self, event: impl Into<BlobEvent>) {
+        self.emit(ContractEvent::BlobEvent(event.into()));
+    }
+
+    fn check_epochs_ahead(&self, end_epoch: Epoch) -> SuiClientResult<()> {
+        if end_epoch > self.epoch() + self.fixed_params.max_epochs_ahead {
+            return Err(anyhow!(
+                "the end epoch {end_epoch} is more than {} epochs ahead of the current epoch",
+                self.fixed_params.max_epochs_ahead
+            )
+            .into());
+        }
+        Ok(())
+    }
+
+    fn reserve_space(
+        &mut self,
+        owner: SuiAddress,
+        encoded_size: u64,
+        epochs_ahead: EpochCount,
+    ) -> SuiClientResult<StorageResource> {
+        if epochs_ahead == 0 {
+            return Err(anyhow!("storage must be reserved for at least one epoch").into());
+        }
+        let end_epoch = self.epoch() + epochs_ahead;
+        self.check_epochs_ahead(end_epoch)?;
+        let storage = StorageResource {
+            id: self.new_object_id(),
+            start_epoch: self.epoch(),
+            end_epoch,
+            storage_size: encoded_size,
+        };
+        self.storage.insert(storage.id, (storage.clone(), owner));
+        Ok(storage)
+    }
+
+    fn register_blob(
+        &mut self,
+        owner: SuiAddress,
+        metadata: BlobObjectMetadata,
+        storage: StorageResource,
+        persistence: BlobPersistence,
+    ) -> SuiClientResult<Blob> {
+        match self.storage.get(&storage.id) {
+            Some((_, storage_owner)) if *storage_owner == owner => (),
+            _ => return Err(object_not_found(storage.id)),
+        }
+        let (storage, _) = self
+            .storage
+            .remove(&storage.id)
+            .expect("the storage resource exists");
+        if storage.storage_size < metadata.encoded_size {
+            return Err(anyhow!(
+                "the storage resource {} is too small for blob {}",
+                storage.id,
+                metadata.blob_id
+            )
+            .into());
+        }
+        if storage.end_epoch <= self.epoch() {
+            return Err(anyhow!("the storage resource {} has expired", storage.id).into());
+        }
+        if self.invalid_blob_ids.contains(&metadata.blob_id) {
+            return Err(anyhow!("the blob ID {} is marked as invalid", metadata.blob_id).into());
+        }
+
+        let blob = Blob {
+            id: self.new_object_id(),
+            registered_epoch: self.epoch(),
+            blob_id: metadata.blob_id,
+            size: metadata.unencoded_size,
+            encoding_type: metadata.encoding_type,
+            certified_epoch: None,
+            storage,
+            deletable: matches!(persistence, BlobPersistence::Deletable),
+        };
+        let event_id = self.next_event_id();
+        self.emit_blob_event(BlobRegistered {
+            epoch: self.epoch(),
+            blob_id: blob.blob_id,
+            size: blob.size,
+            encoding_type: blob.encoding_type,
+            end_epoch: blob.storage.end_epoch,
+            deletable: blob.deletable,
+            object_id: blob.id,
+            event_id,
+        });
+        self.blobs.insert(
+            blob.id,
+            FakeBlobObject {
+                blob: blob.clone(),
+                owner: Some(owner),
+                attribute: None,
+            },
+        );
+        Ok(blob)
+    }
+
+    fn certify_blob(
+        &mut self,
+        owner: SuiAddress,
+        blob: &Blob,
+        certificate: &ConfirmationCertificate,
+    ) -> SuiClientResult<()> {
+        let confirmation: Confirmation = bcs::from_bytes(&certificate.serialized_message)
+            .map_err(|error| anyhow!("invalid confirmation certificate: {error}"))?;
+        let certified_blob_id = confirmation.as_ref().contents().blob_id;
+        if certified_blob_id != blob.blob_id {
+            return Err(anyhow!(
+                "the certificate is for blob {certified_blob_id} instead of {}",
+                blob.blob_id
+            )
+            .into());
+        }
+
+        let epoch = self.epoch();
+        let object = self.blob_object_mut(blob.id, Some(owner))?;
+        if object.blob.certified_epoch.is_some() {
+            return Err(anyhow!("the blob object {} is already certified", blob.id).into());
+        }
+        if object.blob.storage.end_epoch <= epoch {
+            return Err(anyhow!("the blob object {} has expired", blob.id).into());
+        }
+        object.blob.certified_epoch = Some(epoch);
+        let certified = object.blob.clone();
+        let event_id = self.next_event_id();
+        self.emit_blob_event(BlobCertified {
+            epoch,
+            blob_id: certified.blob_id,
+            end_epoch: certified.storage.end_epoch,
+            deletable: certified.deletable,
+            object_id: certified.id,
+            is_extension: false,
+            event_id,
+        });
+        Ok(())
+    }
+
+    fn extend_blob(
+        &mut self,
+        owner: SuiAddress,
+        blob_obj_id: ObjectID,
+        epochs_extended: EpochCount,
+    ) -> SuiClientResult<()> {
+        let epoch = self.epoch();
+        let object = self.blob_object_mut(blob_obj_id, Some(owner))?;
+        if object.blob.certified_epoch.is_none() {
+            return Err(anyhow!("the blob object {blob_obj_id} is not certified").into());
+        }
+        if object.blob.storage.end_epoch <= epoch {
+            return Err(anyhow!("the blob object {blob_obj_id} has expired").into());
+        }
+        let end_epoch = object.blob.storage.end_epoch + epochs_extended;
+        self.check_epochs_ahead(end_epoch)?;
+
+        let object = self.blob_object_mut(blob_obj_id, None)?;
+        object.blob.storage.end_epoch = end_epoch;
+        let extended = object.blob.clone();
+        let event_id = self.next_event_id();
+        self.emit_blob_event(BlobCertified {
+            epoch,
+            blob_id: extended.blob_id,
+            end_epoch,
+            deletable: extended.deletable,
+            object_id: extended.id,
+            is_extension: true,
+            event_id,
+        });
+        Ok(())
+    }
+
+    /// Applies the post-store action to the blob object, recording the ID of the shared blob
+    /// object in `shared_blobs` if the blob is shared.
+    fn apply_post_store_action(
+        &mut self,
+        blob: &Blob,
+        post_store: PostStoreAction,
+        shared_blobs: &mut HashMap<BlobId, ObjectID>,
+    ) -> SuiClientResult<()> {
+        match post_store {
+            PostStoreAction::Keep => (),
+            PostStoreAction::Burn => {
+                self.blobs.remove(&blob.id);
+            }
+            PostStoreAction::TransferTo(recipient) => {
+                self.blob_object_mut(blob.id, None)?.owner = Some(recipient);
+            }
+            PostStoreAction::Share => {
+                self.blob_object_mut(blob.id, None)?.owner = None;
+                let shared_blob_id = self.new_object_id();
+                shared_blobs.insert(blob.blob_id, shared_blob_id);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the blob object with the given ID, checking that it is owned by `owner` if set.
+    fn blob_object_mut(
+        &mut self,
+        object_id: ObjectID,
+        owner: Option<SuiAddress>,
+    ) -> SuiClientResult<&mut FakeBlobObject> {
+        match self.blobs.get_mut(&object_id) {
+            Some(object) if owner.is_none() || object.owner == owner => Ok(object),
+            _ => Err(object_not_found(object_id)),
+        }
+    }
+
+    fn blob_object(&self, object_id: ObjectID) -> SuiClientResult<&FakeBlobObject> {
+        self.blobs
+            .get(&object_id)
+            .ok_or_else(|| object_not_found(object_id))
+    }
+}
+
+fn object_not_found(object_id: ObjectID) -> SuiClientError {
+    anyhow!("the object {object_id} does not exist or is not owned by the sender").into()
+}
+
+impl ReadClient for FakeSuiClient {
+    async fn storage_price_per_unit_size(&self) -> SuiClientResult<u64> {
+        Ok(self.state().storage_price_per_unit_size)
+    }
+
+    async fn write_price_per_unit_size(&self) -> SuiClientResult<u64> {
+        Ok(self.state().write_price_per_unit_size)
+    }
+
+    async fn storage_and_write_price_per_unit_size(&self) -> SuiClientResult<(u64, u64)> {
+        let state = self.state();
+        Ok((
+            state.storage_price_per_unit_size,
+            state.write_price_per_unit_size,
+        ))
+    }
+
+    async fn event_stream(
+        &self,
+        _polling_interval: Duration,
+        cursor: Option<EventID>,
+    ) -> SuiClientResult<impl Stream<Item = ContractEvent>> {
+        let mut state = self.state();
+        let start = cursor
+            .and_then(|cursor| {
+                state
+                    .events
+                    .iter()
+                    .position(|event| event.event_id() == cursor)
+            })
+            .map_or(0, |index| index + 1);
+        let past_events = state.events[start..].to_vec();
+        let (tx_event, rx_event) = mpsc::unbounded_channel();
+        state.subscribers.push(tx_event);
+        Ok(stream::iter(past_events).chain(UnboundedReceiverStream::new(rx_event)))
+    }
+
+    async fn get_blob_event(&self, event_id: EventID) -> SuiClientResult<BlobEvent> {
+        self.state()
+            .events
+            .iter()
+            .find_map(|event| match event {
+                ContractEvent::BlobEvent(blob_event) if blob_event.event_id() == event_id => {
+                    Some(blob_event.clone())
+                }
+                _ => None,
+            })
+            .ok_or(SuiClientError::NoCorrespondingBlobEvent(event_id))
+    }
+
+    async fn current_committee(&self) -> SuiClientResult<Committee> {
+        Ok(self.state().current.clone())
+    }
+
+    async fn previous_committee(&self) -> SuiClientResult<Committee> {
+        let state = self.state();
+        Ok(state.previous.clone().unwrap_or_else(|| {
+            Committee::new(vec![], 0, state.fixed_params.n_shards)
+                .expect("an empty committee is valid in epoch 0")
+        }))
+    }
+
+    async fn next_committee(&self) -> SuiClientResult<Option<Committee>> {
+        Ok(self.state().next.clone())
+    }
+
+    async fn get_storage_nodes_from_active_set(&self) -> anyhow::Result<Vec<StorageNode>> {
+        let node_ids: Vec<_> = self.state().stake.keys().copied().collect();
+        self.get_storage_nodes_by_ids(&node_ids).await
+    }
+
+    async fn get_storage_nodes_from_committee(&self) -> SuiClientResult<Vec<StorageNode>> {
+        Ok(self.state().current.members().to_vec())
+    }
+
+    async fn get_storage_nodes_by_ids(
+        &self,
+        node_ids: &[ObjectID],
+    ) -> anyhow::Result<Vec<StorageNode>> {
+        let state = self.state();
+        node_ids
+            .iter()
+            .map(|node_id| {
+                state
+                    .nodes
+                    .get(node_id)
+                    .cloned()
+                    .ok_or_else(|| anyhow!("one or multiple node IDs were not found"))
+            })
+            .collect()
+    }
+
+    async fn get_blob_attribute(
+        &self,
+        blob_obj_id: &ObjectID,
+    ) -> SuiClientResult<Option<BlobAttribute>> {
+        Ok(self.state().blob_object(*blob_obj_id)?.attribute.clone())
+    }
+
+    async fn get_blob_by_object_id(
+        &self,
+        blob_obj_id: &ObjectID,
+    ) -> SuiClientResult<BlobWithAttribute> {
+        let state = self.state();
+        let object = state.blob_object(*blob_obj_id)?;
+        Ok(BlobWithAttribute {
+            blob: object.blob.clone(),
+            attribute: object.attribute.clone(),
+        })
+    }
+
+    async fn epoch_state(&self) -> SuiClientResult<EpochState> {
+        Ok(self.state().epoch_state.clone())
+    }
+
+    async fn current_epoch(&self) -> SuiClientResult<Epoch> {
+        Ok(self.state().epoch())
+    }
+
+    async fn get_committees_and_state(&self) -> SuiClientResult<CommitteesAndState> {
+        let state = self.state();
+        Ok(CommitteesAndState {
+            current: state.current.clone(),
+            previous: state.previous.clone(),
+            next: state.next.clone(),
+            epoch_state: state.epoch_state.clone(),
+        })
+    }
+
+    async fn fixed_system_parameters(&self) -> SuiClientResult<FixedSystemParameters> {
+        Ok(self.state().fixed_params.clone())
+    }
+
+    async fn stake_assignment(&self) -> SuiClientResult<HashMap<ObjectID, u64>> {
+        Ok(self.state().stake.clone())
+    }
+
+    async fn last_certified_event_blob(&self) -> SuiClientResult<Option<EventBlob>> {
+        // Event blobs are not simulated.
+        Ok(None)
+    }
+
+    async fn refresh_package_id(&self) -> SuiClientResult<()> {
+        Ok(())
+    }
+
+    async fn refresh_subsidies_package_id(&self) -> SuiClientResult<()> {
+        Ok(())
+    }
+
+    async fn system_object_version(&self) -> SuiClientResult<u64> {
+        Ok(self.state().n_transactions)
+    }
+}
+
+impl ContractClient for FakeSuiClient {
+    fn address(&self) -> SuiAddress {
+        self.address
+    }
+
+    async fn reserve_space(
+        &self,
+        encoded_size: u64,
+        epochs_ahead: EpochCount,
+    ) -> SuiClientResult<StorageResource> {
+        self.state()
+            .next_transaction()
+            .reserve_space(self.address, encoded_size, epochs_ahead)
+    }
+
+    async fn register_blobs(
+        &self,
+        blob_metadata_and_storage: Vec<(BlobObjectMetadata, StorageResource)>,
+        persistence: BlobPersistence,
+    ) -> SuiClientResult<Vec<Blob>> {
+        let mut state = self.state();
+        state.next_transaction();
+        blob_metadata_and_storage
+            .into_iter()
+            .map(|(metadata, storage)| {
+                state.register_blob(self.address, metadata, storage, persistence)
+            })
+            .collect()
+    }
+
+    async fn reserve_and_register_blobs(
+        &self,
+        epochs_ahead: EpochCount,
+        blob_metadata_list: Vec<BlobObjectMetadata>,
+        persistence: BlobPersistence,
+    ) -> SuiClientResult<Vec<Blob>> {
+        let mut state = self.state();
+        state.next_transaction();
+        blob_metadata_list
+            .into_iter()
+            .map(|metadata| {
+                let storage =
+                    state.reserve_space(self.address, metadata.encoded_size, epochs_ahead)?;
+                state.register_blob(self.address, metadata, storage, persistence)
+            })
+            .collect()
+    }
+
+    async fn certify_blobs(
+        &self,
+        blobs_with_certificates: &[(&Blob, ConfirmationCertificate)],
+        post_store: PostStoreAction,
+    ) -> SuiClientResult<HashMap<BlobId, ObjectID>> {
+        let mut state = self.state();
+        state.next_transaction();
+        let mut shared_blobs = HashMap::new();
+        for (blob, certificate) in blobs_with_certificates {
+            state.certify_blob(self.address, blob, certificate)?;
+            state.apply_post_store_action(blob, post_store, &mut shared_blobs)?;
+        }
+        Ok(shared_blobs)
+    }
+
+    async fn certify_and_extend_blobs(
+        &self,
+        blobs_with_certificates: &[CertifyAndExtendBlobParams<'_>],
+        post_store: PostStoreAction,
+    ) -> SuiClientResult<HashMap<BlobId, ObjectID>> {
+        let mut state = self.state();
+        state.next_transaction();
+        let mut shared_blobs = HashMap::new();
+        for blob_params in blobs_with_certificates {
+            if let Some(certificate) = blob_params.certificate.as_ref() {
+                state.certify_blob(self.address, blob_params.blob, certificate)?;
+            }
+            if let Some(epochs_extended) = blob_params.epochs_extended {
+                state.extend_blob(self.address, blob_params.blob.id, epochs_extended)?;
+            }
+            state.apply_post_store_action(blob_params.blob, post_store, &mut shared_blobs)?;
+        }
+        Ok(shared_blobs)
+    }
+
+    async fn invalidate_blob_id(
+        &self,
+        certificate: &InvalidBlobCertificate,
+    ) -> SuiClientResult<()> {
+        let message: InvalidBlobIdMsg = bcs::from_bytes(&certificate.serialized_message)
+            .map_err(|error| anyhow!("invalid invalid-blob certificate: {error}"))?;
+        let blob_id = *message.as_ref().contents();
+
+        let mut state = self.state();
+        state.next_transaction();
+        if state.invalid_blob_ids.insert(blob_id) {
+            let epoch = state.epoch();
+            let event_id = state.next_event_id();
+            state.emit_blob_event(InvalidBlobId {
+                epoch,
+                blob_id,
+                event_id,
+            });
+        }
+        Ok(())
+    }
+
+    async fn delete_blob(&self, blob_object_id: ObjectID) -> SuiClientResult<()> {
+        let mut state = self.state();
+        state.next_transaction();
+        let epoch = state.epoch();
+        let object = state.blob_object_mut(blob_object_id, Some(self.address))?;
+        if !object.blob.deletable {
+            return Err(anyhow!("the blob object {blob_object_id} is not deletable").into());
+        }
+        let FakeBlobObject { blob, .. } = state
+            .blobs
+            .remove(&blob_object_id)
+            .expect("the blob object exists");
+
+        let event_id = state.next_event_id();
+        state.emit_blob_event(BlobDeleted {
+            epoch,
+            blob_id: blob.blob_id,
+            end_epoch: blob.storage.end_epoch,
+            object_id: blob.id,
+            was_certified: blob.certified_epoch.is_some(),
+            event_id,
+        });
+        // As in the contracts, the storage resource is returned to the owner of the blob.
+        if blob.storage.end_epoch > epoch {
+            state
+                .storage
+                .insert(blob.storage.id, (blob.storage, self.address));
+        }
+        Ok(())
+    }
+
+    async fn extend_blob(
+        &self,
+        blob_obj_id: ObjectID,
+        epochs_extended: EpochCount,
+    ) -> SuiClientResult<()> {
+        let mut state = self.state();
+        state.next_transaction();
+        state.extend_blob(self.address, blob_obj_id, epochs_extended)
+    }
+
+    async fn owned_blobs(
+        &self,
+        owner: Option<SuiAddress>,
+        selection_policy: ExpirySelectionPolicy,
+    ) -> SuiClientResult<Vec<Blob>> {
+        let owner = owner.unwrap_or(self.address);
+        let state = self.state();
+        Ok(state
+            .blobs
+            .values()
+            .filter(|object| object.owner == Some(owner))
+            .filter(|object| selection_policy.matches(object.blob.storage.end_epoch, state.epoch()))
+            .map(|object| object.blob.clone())
+            .collect())
+    }
+
+    async fn owned_storage(
+        &self,
+        selection_policy: ExpirySelectionPolicy,
+    ) -> SuiClientResult<Vec<StorageResource>> {
+        let state = self.state();
+        Ok(state
+            .storage
+            .values()
+            .filter(|(_, owner)| *owner == self.address)
+            .filter(|(storage, _)| selection_policy.matches(storage.end_epoch, state.epoch()))
+            .map(|(storage, _)| storage.clone())
+            .collect())
+    }
+
+    async fn epoch_sync_done(
+        &self,
+        epoch: Epoch,
+        node_capability_object_id: ObjectID,
+    ) -> SuiClientResult<()> {
+        let mut state = self.state();
+        state.next_transaction();
+        if epoch != state.epoch() || !state.epoch_state.is_transitioning() {
+            // The contracts ignore outdated attestations.
+            return Ok(());
+        }
+        let n_node_shards = state
+            .current
+            .members()
+            .iter()
+            .find(|node| node.node_id == node_capability_object_id)
+            .map(|node| node.shard_ids.len())
+            .ok_or_else(|| anyhow!("the node is not part of the current committee"))?;
+        if !state.synced_nodes.insert(node_capability_object_id) {
+            return Err(SuiClientError::LatestAttestedIsMoreRecent);
+        }
+        let EpochState::EpochChangeSync(weight) = state.epoch_state else {
+            unreachable!("the epoch change is in progress");
+        };
+        let weight =
+            weight + u16::try_from(n_node_shards).expect("the number of shards fits into a u16");
+        state.epoch_state = EpochState::EpochChangeSync(weight);
+        if state.current.is_quorum(weight.into()) {
+            state.finish_epoch_change();
+        }
+        Ok(())
+    }
+}
+
+/// Returns a committee of `n_nodes` test nodes for `epoch`, assigning the `n_shards` shards to
+/// the nodes in a round-robin manner.
+pub fn fake_committee(n_nodes: u16, n_shards: NonZeroU16, epoch: Epoch) -> Committee {
+    assert!(
+        n_nodes > 0 && n_nodes <= n_shards.get(),
+        "each node needs at least one shard"
+    );
+    let mut members: Vec<_> = (0..n_nodes)
+        .map(|_| super::new_move_storage_node_for_testing())
+        .collect();
+    for shard in 0..n_shards.get() {
+        members[usize::from(shard % n_nodes)]
+            .shard_ids
+            .push(ShardIndex(shard));
+    }
+    Committee::new(members, epoch, n_shards).expect("the committee is valid")
+}
+
+#[cfg(test)]
+mod tests {
+    use walrus_core::{
+        merkle::Node as MerkleNode,
+        test_utils::{protocol_key_pair, random_blob_id},
+        EncodingType,
+    };
+
+    use super::*;
+
+    fn fake_client() -> FakeSuiClient {
+        FakeSuiClient::new(fake_committee(4, NonZeroU16::new(10).unwrap(), 1))
+    }
+
+    fn blob_metadata() -> BlobObjectMetadata {
+        BlobObjectMetadata {
+            blob_id: random_blob_id(),
+            root_hash: MerkleNode::Empty,
+            unencoded_size: 1_000,
+            encoded_size: 10_000,
+            encoding_type: EncodingType::RS2,
+        }
+    }
+
+    fn certificate_for(blob: &Blob, epoch: Epoch) -> ConfirmationCertificate {
+        let confirmation = Confirmation::new(epoch, blob.blob_id, blob.blob_persistence_type());
+        let signed = protocol_key_pair().sign_message(&confirmation);
+        ConfirmationCertificate::from_signed_messages_and_indices([signed], vec![0])
+            .expect("a single signed message forms a certificate")
+    }
+
+    #[tokio::test]
+    async fn blob_lifecycle_emits_events() -> anyhow::Result<()> {
+        let client = fake_client();
+        let mut events = Box::pin(client.event_stream(Duration::ZERO, None).await?);
+
+        let blob = client
+            .reserve_and_register_blobs(2, vec![blob_metadata()], BlobPersistence::Deletable)
+            .await?
+            .pop()
+            .expect("one blob was registered");
+        assert_eq!(blob.storage.end_epoch, 3);
+        client
+            .certify_blobs(&[(&blob, certificate_for(&blob, 1))], PostStoreAction::Keep)
+            .await?;
+        client.extend_blob(blob.id, 1).await?;
+        client.delete_blob(blob.id).await?;
+
+        let mut names = vec![];
+        for _ in 0..4 {
+            let Some(ContractEvent::BlobEvent(event)) = events.next().await else {
+                panic!("expected a blob event");
+            };
+            assert_eq!(event.blob_id(), blob.blob_id);
+            names.push(event.name());
+        }
+        assert_eq!(
+            names,
+            [
+                "BlobRegistered",
+                "BlobCertified",
+                "BlobCertified",
+                "BlobDeleted"
+            ]
+        );
+        assert!(client
+            .owned_blobs(None, ExpirySelectionPolicy::All)
+            .await?
+            .is_empty());
+        assert_eq!(
+            client.owned_storage(ExpirySelectionPolicy::Valid).await?[0].end_epoch,
+            4
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn epochs_advance_under_test_control() -> anyhow::Result<()> {
+        let client = fake_client();
+        assert_eq!(client.current_epoch().await?, 1);
+
+        assert_eq!(client.start_epoch_change(), 2);
+        assert!(client.epoch_state().await?.is_transitioning());
+        assert_eq!(client.previous_committee().await?.epoch, 1);
+
+        // Nodes holding a quorum of the shards finish the epoch change.
+        let members = client.current_committee().await?.members().to_vec();
+        for node in &members[..3] {
+            client.epoch_sync_done(2, node.node_id).await?;
+        }
+        assert!(!client.epoch_state().await?.is_transitioning());
+
+        assert_eq!(client.advance_epoch(), 3);
+        assert_eq!(client.current_epoch().await?, 3);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn expired_blobs_are_not_selected() -> anyhow::Result<()> {
+        let client = fake_client();
+        client
+            .reserve_and_register_blobs(1, vec![blob_metadata()], BlobPersistence::Permanent)
+            .await?;
+        assert_eq!(
+            client
+                .owned_blobs(None, ExpirySelectionPolicy::Valid)
+                .await?
+                .len(),
+            1
+        );
+
+        client.advance_epoch();
+        assert!(client
+            .owned_blobs(None, ExpirySelectionPolicy::Valid)
+            .await?
+            .is_empty());
+        assert_eq!(
+            client
+                .owned_blobs(None, ExpirySelectionPolicy::Expired)
+                .await?
+                .len(),
+            1
+        );
+        Ok(())
+    }
+}
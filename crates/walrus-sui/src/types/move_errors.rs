@@ -18,34 +18,52 @@ macro_rules! move_error_kind {
     (
         $errorname:ident,
         $packagename:ident::$modulename:ident,
-        $($errortype:ident, $errorcode:expr),+ $(,)?
+        $($errortype:ident, $errorcode:expr, $description:expr),+ $(,)?
     ) => {
         #[doc=stringify!(Error kinds for the Move $modulename in package $packagename)]
         #[derive(Debug, Clone)]
         #[allow(clippy::enum_variant_names)]
         pub enum $errorname {
             $(
-                #[doc=stringify!(Error kind for the move error $errortype)]
+                #[doc=$description]
                 $errortype(Box<RawMoveError>)
             ),*
         }
 
+        impl MoveErrorKind for $errorname {
+            fn error_name(&self) -> &'static str {
+                match self {
+                    $(Self::$errortype(_) => stringify!($errortype)),*
+                }
+            }
+
+            fn description(&self) -> &'static str {
+                match self {
+                    $(Self::$errortype(_) => $description),*
+                }
+            }
+
+            fn raw_error(&self) -> &RawMoveError {
+                match self {
+                    $(Self::$errortype(raw) => raw),*
+                }
+            }
+        }
 
         impl Display for $errorname {
             fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                let (raw, variant) = match self {
-                    $(
-                        Self::$errortype(raw) => (raw, stringify!($errortype))
-                    ),*
-                };
+                let raw = self.raw_error();
                 write!(
                     f,
-                    "contract execution failed in {}::{}::{} at address {} with error type {}",
+                    "contract execution failed in {}::{}::{} at address {} with error type {} \
+                    (abort code {}): {}",
                     stringify!($packagename),
                     stringify!($modulename),
                     raw.function,
                     raw.package,
-                    variant,
+                    self.error_name(),
+                    raw.error_code,
+                    self.description(),
                 )
             }
         }
@@ -63,6 +81,16 @@ macro_rules! move_error_kind {
     };
 }
 
+/// Common interface of the typed errors of the Walrus Move modules.
+pub trait MoveErrorKind {
+    /// Returns the name of the error constant in the Move module, e.g., `ENotCertified`.
+    fn error_name(&self) -> &'static str;
+    /// Returns a human-readable description of the error, taken from the Move sources.
+    fn description(&self) -> &'static str;
+    /// Returns the parsed, but non-typed Move execution error.
+    fn raw_error(&self) -> &RawMoveError;
+}
+
 /// Parsed, but non-typed Move execution error.
 #[derive(Debug, Clone)]
 pub struct RawMoveError {
@@ -148,6 +176,209 @@ impl From<&str> for MoveExecutionError {
     }
 }
 
+impl MoveExecutionError {
+    /// Returns the name of the error constant in the Move module, if the error is known.
+    pub fn error_name(&self) -> Option<&'static str> {
+        self.module_and_kind().map(|(_, kind)| kind.error_name())
+    }
+
+    /// Returns a human-readable description of the error, if the error is known.
+    pub fn description(&self) -> Option<&'static str> {
+        self.module_and_kind().map(|(_, kind)| kind.description())
+    }
+
+    /// Returns the parsed Move execution error, if the error could be parsed.
+    pub fn raw_error(&self) -> Option<&RawMoveError> {
+        match self {
+            Self::OtherMoveModule(raw) => Some(raw),
+            Self::NotParsable(_) => None,
+            _ => self.module_and_kind().map(|(_, kind)| kind.raw_error()),
+        }
+    }
+
+    /// Returns a hint on how to resolve the error, if there is one for the error.
+    pub fn remediation_hint(&self) -> Option<&'static str> {
+        let (module, kind) = self.module_and_kind()?;
+        remediation_hint(module, kind.error_name())
+    }
+}
+
+/// Hints on how to resolve Move errors, by module name and error name.
+///
+/// Only errors that can be caused by users of the client have hints; the remaining errors
+/// indicate bugs or are only relevant to storage node operators.
+const REMEDIATION_HINTS: &[(&str, &str, &str)] = &[
+    (
+        "system",
+        "EWrongVersion",
+        "the Walrus contracts were upgraded; update the Walrus client to the latest version",
+    ),
+    (
+        "staking",
+        "EWrongVersion",
+        "the Walrus contracts were upgraded; update the Walrus client to the latest version",
+    ),
+    (
+        "subsidies",
+        "EWrongVersion",
+        "the Walrus contracts were upgraded; update the Walrus client to the latest version",
+    ),
+    (
+        "upgrade",
+        "EWrongVersion",
+        "the Walrus contracts were upgraded; update the Walrus client to the latest version",
+    ),
+    (
+        "system_state_inner",
+        "EStorageExceeded",
+        "the storage capacity of Walrus is exhausted; try again with a smaller blob or later",
+    ),
+    (
+        "system_state_inner",
+        "EInvalidEpochsAhead",
+        "the number of epochs exceeds the maximum; check the maximum number of epochs ahead \
+        with `walrus info` and use a smaller number of epochs",
+    ),
+    (
+        "system_state_inner",
+        "EInvalidIdEpoch",
+        "the certificate was created in a different epoch, likely due to an epoch change; \
+        retry the operation",
+    ),
+    (
+        "system_state_inner",
+        "EInvalidResourceSize",
+        "the requested storage size is invalid; use a positive size that does not exceed the \
+        available capacity",
+    ),
+    (
+        "blob",
+        "ENotCertified",
+        "the blob is not certified yet; store the blob again to certify it",
+    ),
+    (
+        "blob",
+        "EBlobNotDeletable",
+        "the blob is permanent and cannot be deleted; only blobs stored with `--deletable` can \
+        be deleted",
+    ),
+    (
+        "blob",
+        "EResourceBounds",
+        "the storage resource does not cover the required epochs; use a storage resource \
+        whose epochs cover the blob's lifetime",
+    ),
+    (
+        "blob",
+        "EResourceSize",
+        "the storage resource is too small for the encoded blob; use a larger storage \
+        resource",
+    ),
+    (
+        "blob",
+        "EAlreadyCertified",
+        "the blob is already certified; check its status with `walrus blob-status`",
+    ),
+    (
+        "blob",
+        "EInvalidBlobId",
+        "the blob ID does not match the registered blob; re-encode the blob and store it again",
+    ),
+    (
+        "blob",
+        "EDuplicateMetadata",
+        "the attribute already exists on the blob; remove it before setting it again",
+    ),
+    (
+        "blob",
+        "EMissingMetadata",
+        "the blob has no attributes; set an attribute with `walrus set-blob-attribute` first",
+    ),
+    (
+        "storage_resource",
+        "EInvalidEpoch",
+        "the split epoch must lie strictly between the start and end epoch of the storage \
+        resource",
+    ),
+    (
+        "storage_resource",
+        "EIncompatibleEpochs",
+        "storage resources can only be fused if they cover the same epochs, or adjacent \
+        epochs with the same size; check the epochs of the storage resources",
+    ),
+    (
+        "storage_resource",
+        "EIncompatibleAmount",
+        "storage resources with adjacent epochs can only be fused if they have the same size, \
+        and a split must be smaller than the storage resource; check the storage sizes",
+    ),
+    (
+        "storage_accounting",
+        "ETooFarInFuture",
+        "the end epoch is too far in the future; use a smaller number of epochs",
+    ),
+    (
+        "staked_wal",
+        "EStakeBelowThreshold",
+        "the stake is below the minimum staking threshold; stake a larger amount",
+    ),
+    (
+        "staked_wal",
+        "ENotWithdrawing",
+        "the stake must be marked for withdrawal before it can be withdrawn; request the \
+        withdrawal first",
+    ),
+    (
+        "staked_wal",
+        "EAlreadyWithdrawing",
+        "the withdrawal of the stake was already requested; withdraw it once the withdrawal \
+        epoch is reached",
+    ),
+    (
+        "staking_pool",
+        "EWithdrawEpochNotReached",
+        "the stake cannot be withdrawn yet; retry after the withdrawal epoch is reached",
+    ),
+    (
+        "staking_pool",
+        "EPoolIsNotActive",
+        "the staking pool of the storage node is not active; stake with a different node",
+    ),
+    (
+        "staking_pool",
+        "EAuthorizationFailure",
+        "the sender is not authorized to perform this operation on the storage node; use the \
+        wallet that holds the node's capability or is authorized for the operation",
+    ),
+    (
+        "staking_inner",
+        "EWrongEpochState",
+        "the operation is not possible during the current epoch change; retry once the epoch \
+        change has completed",
+    ),
+    (
+        "wal_exchange",
+        "EInsufficientFundsInExchange",
+        "the exchange does not hold enough WAL; exchange a smaller amount or use a different \
+        exchange",
+    ),
+    (
+        "wal_exchange",
+        "EInsufficientInputBalance",
+        "the wallet does not hold enough SUI for the exchange; exchange a smaller amount",
+    ),
+];
+
+/// Returns a hint on how to resolve the error `error_name` of the Move module `module`.
+fn remediation_hint(module: &str, error_name: &str) -> Option<&'static str> {
+    REMEDIATION_HINTS
+        .iter()
+        .find(|(hint_module, hint_error_name, _)| {
+            *hint_module == module && *hint_error_name == error_name
+        })
+        .map(|(_, _, hint)| *hint)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -191,4 +422,76 @@ mod tests {
             _ => panic!("wrong error"),
         }
     }
+
+    /// Returns the module name and the error constants of all Move modules in the contracts.
+    fn abort_codes_in_move_sources() -> Vec<(String, String, u64)> {
+        let contracts_dir =
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../../contracts");
+        let module_pattern = regex::Regex::new(r"module\s+\w+::(\w+)\s*;").unwrap();
+        let error_pattern = regex::Regex::new(r"const\s+(E\w*)\s*:\s*u64\s*=\s*([\d_]+)").unwrap();
+
+        let mut abort_codes = vec![];
+        for entry in walkdir::WalkDir::new(contracts_dir) {
+            let path = entry.unwrap().into_path();
+            let in_sources = path.components().any(|c| c.as_os_str() == "sources");
+            let in_build = path.components().any(|c| c.as_os_str() == "build");
+            if !in_sources || in_build || path.extension().is_none_or(|ext| ext != "move") {
+                continue;
+            }
+            let content = std::fs::read_to_string(&path).unwrap();
+            let Some(module) = module_pattern.captures(&content) else {
+                continue;
+            };
+            for error in error_pattern.captures_iter(&content) {
+                abort_codes.push((
+                    module[1].to_owned(),
+                    error[1].to_owned(),
+                    error[2].replace('_', "").parse().unwrap(),
+                ));
+            }
+        }
+        abort_codes
+    }
+
+    fn raw_error(module: &str, error_code: u64) -> RawMoveError {
+        RawMoveError {
+            function: "function".to_owned(),
+            module: module.to_owned(),
+            package: ObjectID::ZERO,
+            error_code,
+            unparsed: String::new(),
+            command_index: 0,
+            instruction: 0,
+        }
+    }
+
+    #[test]
+    fn all_abort_codes_in_move_sources_are_typed() {
+        let abort_codes = abort_codes_in_move_sources();
+        assert!(!abort_codes.is_empty());
+
+        for (module, error_name, error_code) in abort_codes {
+            let error = MoveExecutionError::from(raw_error(&module, error_code));
+            assert_eq!(
+                error.error_name(),
+                Some(error_name.as_str()),
+                "abort code {error_code} of module {module} is not mapped correctly"
+            );
+            assert!(error.description().is_some_and(|d| !d.is_empty()));
+            assert!(error.to_string().contains(&error_name));
+        }
+    }
+
+    #[test]
+    fn remediation_hints_refer_to_existing_errors() {
+        let abort_codes = abort_codes_in_move_sources();
+        for (module, error_name, hint) in REMEDIATION_HINTS {
+            let (_, _, error_code) = abort_codes
+                .iter()
+                .find(|(m, e, _)| m == module && e == error_name)
+                .unwrap_or_else(|| panic!("{module}::{error_name} is not in the Move sources"));
+            let error = MoveExecutionError::from(raw_error(module, *error_code));
+            assert_eq!(error.remediation_hint(), Some(*hint));
+        }
+    }
 }